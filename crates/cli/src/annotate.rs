@@ -0,0 +1,221 @@
+//! Implements `roc annotate`, which prints the inferred type annotation of each top-level
+//! definition in a module that doesn't have one, or with `--write`, adds them to the module.
+
+use std::io;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{check_load_config, handle_loading_problem, report_problems_typechecked};
+use roc_can::expr::DeclarationTag;
+use roc_error_macros::exit_code;
+use roc_fmt::def::fmt_defs;
+use roc_fmt::Buf;
+use roc_load::LoadedModule;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::Defs;
+use roc_parse::header::parse_module_defs;
+use roc_parse::state::State;
+use roc_problem::Severity;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+
+use crate::{threading_from_flags, ProjectConfig, FLAG_MAIN, FLAG_WRITE, ROC_FILE};
+
+pub fn annotate(matches: &ArgMatches) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = Bump::new();
+    let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let project_config = ProjectConfig::for_roc_file(roc_file_path);
+    let threading = threading_from_flags(matches, &project_config);
+
+    let load_result = roc_load::load_and_typecheck(
+        &arena,
+        roc_file_path.to_owned(),
+        matches.get_one::<PathBuf>(FLAG_MAIN).cloned(),
+        RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+        check_load_config(threading, None),
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(problem) => return handle_loading_problem(problem),
+    };
+
+    // Types inferred from code with errors in it could be wrong, so don't suggest them.
+    // Warnings are fine; `roc check` is the place to hear about those.
+    if has_errors(&loaded) {
+        let problems = report_problems_typechecked(&mut loaded, None);
+
+        problems.print_error_warning_count(start_time.elapsed());
+
+        return Ok(problems.exit_code());
+    }
+
+    let (path, source) = loaded.sources[&loaded.module_id].clone();
+    let annotations = inferred_annotations(&arena, &mut loaded, &source);
+
+    if matches.get_flag(FLAG_WRITE) {
+        if !annotations.is_empty() {
+            std::fs::write(&path, insert_annotations(&source, &annotations))?;
+        }
+
+        println!(
+            "Added {} to {}.",
+            match annotations.len() {
+                1 => "1 annotation".to_string(),
+                n => format!("{n} annotations"),
+            },
+            path.display()
+        );
+    } else {
+        for (_, annotation) in annotations {
+            println!("{annotation}");
+        }
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+fn has_errors(loaded: &LoadedModule) -> bool {
+    let can_errors = loaded
+        .can_problems
+        .values()
+        .flatten()
+        .any(|problem| problem.severity() != Severity::Warning);
+    let type_errors = loaded
+        .type_problems
+        .values()
+        .flatten()
+        .any(|problem| problem.severity() != Severity::Warning);
+
+    can_errors || type_errors
+}
+
+/// The inferred annotation of each top-level definition in the root module which doesn't have one,
+/// along with the offset in the source where the definition's name starts.
+fn inferred_annotations(
+    arena: &Bump,
+    loaded: &mut LoadedModule,
+    source: &str,
+) -> Vec<(usize, String)> {
+    let home = loaded.module_id;
+    let decls = &loaded.declarations_by_id[&home];
+    let subs = loaded.solved.inner_mut();
+    let mut annotations = Vec::new();
+
+    for index in 0..decls.len() {
+        match decls.declarations[index] {
+            DeclarationTag::Value
+            | DeclarationTag::Function(_)
+            | DeclarationTag::Recursive(_)
+            | DeclarationTag::TailRecursive(_) => {}
+            DeclarationTag::Expectation
+            | DeclarationTag::ExpectationFx
+            | DeclarationTag::Destructure(_)
+            | DeclarationTag::MutualRecursion { .. } => continue,
+        }
+
+        if decls.annotations[index].is_some() {
+            continue;
+        }
+
+        let loc_symbol = &decls.symbols[index];
+        let name = loc_symbol.value.as_str(&loaded.interns);
+        let start = loc_symbol.region.start().offset as usize;
+        let end = loc_symbol.region.end().offset as usize;
+
+        // Skip definitions the compiler made up while desugaring, which aren't in the source.
+        if source.get(start..end) != Some(name) {
+            continue;
+        }
+
+        let var = decls.variables[index];
+        let type_str = name_and_print_var(var, subs, home, &loaded.interns, DebugPrint::NOTHING);
+
+        annotations.push((start, format_annotation(arena, name, &type_str)));
+    }
+
+    annotations
+}
+
+/// Formats `name : type_str` the way `roc format` would.
+fn format_annotation(arena: &Bump, name: &str, type_str: &str) -> String {
+    let src: &str = arena.alloc_str(&format!("{name} : {type_str}\n"));
+
+    match parse_module_defs(arena, State::new(src.as_bytes()), Defs::default()) {
+        Ok(defs) => {
+            let mut buf = Buf::new_in(arena);
+
+            fmt_defs(&mut buf, &defs, 0);
+
+            buf.as_str().trim_end().to_string()
+        }
+        // The type printer and the parser should agree, but if they don't, the annotation is
+        // still useful to see.
+        Err(_) => src.trim_end().to_string(),
+    }
+}
+
+/// Puts each annotation on its own line, right before the line where its definition starts,
+/// and indented the same way.
+fn insert_annotations(source: &str, annotations: &[(usize, String)]) -> String {
+    let mut annotations = annotations.to_vec();
+    annotations.sort_by_key(|(offset, _)| *offset);
+
+    let mut output = String::with_capacity(source.len());
+    let mut copied_up_to = 0;
+
+    for (offset, annotation) in annotations {
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &source[line_start..offset];
+
+        output.push_str(&source[copied_up_to..line_start]);
+
+        for line in annotation.lines() {
+            output.push_str(indent);
+            output.push_str(line);
+            output.push('\n');
+        }
+
+        copied_up_to = line_start;
+    }
+
+    output.push_str(&source[copied_up_to..]);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_are_formatted() {
+        let arena = Bump::new();
+
+        assert_eq!(
+            format_annotation(&arena, "swap", "( a,b ) -> (b, a)"),
+            "swap : (a, b) -> (b, a)"
+        );
+        assert_eq!(
+            format_annotation(&arena, "count", "{  total:U64 }"),
+            "count : { total : U64 }"
+        );
+    }
+
+    #[test]
+    fn annotations_go_before_their_defs() {
+        let source = "module [a, b]\n\na = 1\n\nb = \\x ->\n    y = x\n    y\n";
+        let a = source.find("a = ").unwrap();
+        let y = source.find("y = ").unwrap();
+
+        assert_eq!(
+            insert_annotations(
+                source,
+                &[(y, "y : Str".to_string()), (a, "a : Num *".to_string())]
+            ),
+            "module [a, b]\n\na : Num *\na = 1\n\nb = \\x ->\n    y : Str\n    y = x\n    y\n"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Implements `roc bench`, which times top-level `expect`s that are marked with a `# bench`
+//! comment right before them:
+//!
+//! ```roc
+//! # bench sum a big list
+//! expect List.sum (List.range { start: At 0, end: Before 1_000_000 }) > 0
+//! ```
+//!
+//! Benchmarks are always compiled with optimizations. Each one is run once to check that it
+//! passes, then some untimed warmup runs, then the timed runs. Only pure `expect`s can be
+//! benchmarks. Since they're ordinary `expect`s, `roc test` runs them too (once each).
+
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{
+    handle_error_module, handle_loading_problem, report_problems_monomorphized,
+};
+use roc_error_macros::exit_code;
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_load::{
+    ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, NumericDefaults, Tabs,
+};
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::{RenderTarget, ANSI_STYLE_CODES, DEFAULT_PALETTE};
+use roc_target::Target;
+
+use crate::{
+    threading_from_flags, ProjectConfig, FLAG_ITERATIONS, FLAG_MAIN, FLAG_WARMUP, ROC_FILE,
+};
+
+pub fn bench(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = &Bump::new();
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+
+    if !path.exists() {
+        eprintln!(
+            "\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n",
+            path.display()
+        );
+
+        process::exit(exit_code::IO_OR_USAGE_ERROR);
+    }
+
+    let project_config = ProjectConfig::for_roc_file(path);
+    let threading = threading_from_flags(matches, &project_config);
+    let warmup = *matches.get_one::<u32>(FLAG_WARMUP).unwrap() as usize;
+    let iterations = *matches.get_one::<u32>(FLAG_ITERATIONS).unwrap() as usize;
+    let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
+
+    let load_config = LoadConfig {
+        target,
+        function_kind: FunctionKind::from_env(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Test,
+        progress: None,
+        tabs: Tabs::from_env(),
+        numeric_defaults: NumericDefaults::from_env(),
+    };
+    let load_result = roc_load::load_and_monomorphize(
+        arena,
+        path.to_path_buf(),
+        opt_main_path.cloned(),
+        RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+        load_config,
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+        }
+    };
+    let problems = report_problems_monomorphized(&mut loaded, None);
+
+    let mut expectations = std::mem::take(&mut loaded.expectations);
+
+    let interns = loaded.interns.clone();
+    let sources = loaded.sources.clone();
+
+    let (dyn_lib, expects_by_module, layout_interner) =
+        roc_repl_expect::run::expect_mono_module_to_dylib(
+            arena,
+            target,
+            loaded,
+            OptLevel::Optimize,
+            LlvmBackendMode::CliTest,
+        )
+        .unwrap();
+
+    if problems.warnings > 0 {
+        problems.print_error_warning_count(start_time.elapsed());
+        println!(".\n");
+    }
+
+    let arena = &Bump::new();
+    let interns = arena.alloc(interns);
+    let global_layout_interner = layout_interner.into_global();
+
+    let mut writer = io::stdout();
+    let mut benchmarks_run = 0;
+    let mut benchmarks_failed = 0;
+
+    for (module_id, expects) in expects_by_module.into_iter() {
+        let (_, source) = &sources[&module_id];
+
+        for expect in expects.pure {
+            let start = expect.region.start().offset as usize;
+            let end = expect.region.end().offset as usize;
+
+            let Some(name) = benchmark_name(&source[start..end]) else {
+                continue;
+            };
+
+            let timings = roc_repl_expect::run::bench_toplevel_expect(
+                &mut writer,
+                RenderTarget::ColorTerminal,
+                arena,
+                interns,
+                &global_layout_interner,
+                &dyn_lib,
+                &mut expectations,
+                expect,
+                warmup,
+                iterations,
+            )?;
+
+            benchmarks_run += 1;
+
+            match timings {
+                Some(timings) => println!("{name}\n    {}\n", Stats::from_timings(&timings)),
+                None => benchmarks_failed += 1,
+            }
+        }
+    }
+
+    if benchmarks_run == 0 {
+        println!("No benchmarks were found. Mark a top-level `expect` as a benchmark by putting a `# bench` comment right before it.");
+
+        Ok(exit_code::FAILURE)
+    } else if benchmarks_failed > 0 {
+        let red = ANSI_STYLE_CODES.red;
+        let reset = ANSI_STYLE_CODES.reset;
+
+        println!("{red}{benchmarks_failed}{reset} of {benchmarks_run} benchmarks failed.");
+
+        Ok(exit_code::FAILURE)
+    } else {
+        Ok(exit_code::SUCCESS)
+    }
+}
+
+/// Given the source of a top-level `expect`, including the comments before it, returns the
+/// benchmark's name if it's marked with a `# bench` comment. Benchmarks without a name after
+/// `# bench` are named after their `expect` line.
+fn benchmark_name(expect_src: &str) -> Option<String> {
+    let mut marker = None;
+
+    for line in expect_src.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            let name = comment.trim_start().strip_prefix("bench");
+
+            if let Some(name) = name.filter(|name| name.is_empty() || name.starts_with(' ')) {
+                marker = Some(name.trim());
+            }
+        } else if !line.is_empty() {
+            return marker.map(|name| match name {
+                "" => line.to_string(),
+                name => name.to_string(),
+            });
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, PartialEq)]
+struct Stats {
+    iterations: usize,
+    mean: Duration,
+    median: Duration,
+    std_dev: Duration,
+}
+
+impl Stats {
+    fn from_timings(timings: &[Duration]) -> Self {
+        let mut nanos: Vec<f64> = timings.iter().map(|t| t.as_nanos() as f64).collect();
+        nanos.sort_by(f64::total_cmp);
+
+        let n = nanos.len();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let median = match n % 2 {
+            0 => (nanos[n / 2 - 1] + nanos[n / 2]) / 2.0,
+            _ => nanos[n / 2],
+        };
+        let variance = match n {
+            1 => 0.0,
+            _ => nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64,
+        };
+
+        Self {
+            iterations: n,
+            mean: Duration::from_nanos(mean.round() as u64),
+            median: Duration::from_nanos(median.round() as u64),
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mean {}, median {}, std dev {} ({} runs)",
+            format_duration(self.mean),
+            format_duration(self.median),
+            format_duration(self.std_dev),
+            self.iterations
+        )
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+
+    if nanos < 1e3 {
+        format!("{nanos} ns")
+    } else if nanos < 1e6 {
+        format!("{:.2} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.2} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_benchmarks() {
+        assert_eq!(benchmark_name("expect 1 == 1"), None);
+        assert_eq!(benchmark_name("# some comment\nexpect 1 == 1"), None);
+        assert_eq!(benchmark_name("# benchmarks are fun\nexpect 1 == 1"), None);
+        assert_eq!(
+            benchmark_name("\n# bench\nexpect List.len [] == 0"),
+            Some("expect List.len [] == 0".to_string())
+        );
+        assert_eq!(
+            benchmark_name("# setup:\n#  bench   big sum \nexpect\n    List.sum [1, 2] == 3"),
+            Some("big sum".to_string())
+        );
+    }
+
+    #[test]
+    fn computes_stats() {
+        let timings = [4, 1, 3, 2].map(Duration::from_micros);
+
+        assert_eq!(
+            Stats::from_timings(&timings),
+            Stats {
+                iterations: 4,
+                mean: Duration::from_nanos(2500),
+                median: Duration::from_nanos(2500),
+                std_dev: Duration::from_nanos(1291),
+            }
+        );
+        assert_eq!(
+            Stats::from_timings(&timings).to_string(),
+            "mean 2.50 µs, median 2.50 µs, std dev 1.29 µs (4 runs)"
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Support for `roc.toml`, an optional project configuration file.
+//!
+//! The nearest `roc.toml` in the directory of the .roc file being built (or in any directory
+//! above it) provides defaults for some command line flags. Flags given on the command line
+//! always take precedence. Every setting is optional:
+//!
+//! ```toml
+//! [build]
+//! opt-level = "optimize" # one of "dev", "normal", "optimize", or "opt-size"
+//! target = "wasm32"      # only used by `roc build`, and overridden by $ROC_TARGET
+//! linker = "legacy"
+//! max-threads = 4
+//!
+//! [warnings]
+//! deny = true # don't run a program which has warnings, as if they were errors
+//!
+//! [glue]
+//! spec = "glue/RustGlue.roc" # relative to the directory containing roc.toml
+//! dir = "host/src/glue"
+//!
+//! [format]
+//! sort-headers = true # alphabetize the lists in module headers, like `roc format --sort-headers`
+//! trailing-commas = "never" # or "always", for the last item of multiline lists, records, etc.
+//! doc-comment-width = 80 # rewrap ## doc comment paragraphs to fit in this many columns
+//! max-blank-lines = 2 # collapse longer runs of blank lines to this many (at least 1)
+//! blank-line-between-defs = true # exactly one blank line between top-level defs
+//! align-record-fields = true # line up the fields of multiline records and record types
+//! group-digits-above = 4 # write 1_000_000 instead of 1000000, and 1000 instead of 1_000
+//! hex-digits = "upper" # or "lower", for the letters in literals like 0xFF
+//! normalize-exponents = true # write 1.5e5 instead of 1.5E05
+//! pipeline-width = 100 # put each step of |> pipelines wider than this on its own line
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use roc_error_macros::exit_code;
+use roc_fmt::{HexCase, TrailingCommas};
+use serde::Deserialize;
+
+pub const CONFIG_FILENAME: &str = "roc.toml";
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub build: BuildSection,
+    pub warnings: WarningsSection,
+    pub glue: GlueSection,
+    pub format: FormatSection,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BuildSection {
+    pub opt_level: Option<ConfigOptLevel>,
+    pub target: Option<String>,
+    pub linker: Option<ConfigLinker>,
+    pub max_threads: Option<usize>,
+}
+
+/// The equivalent of passing `--dev`, `--optimize`, `--opt-size`, or none of those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigOptLevel {
+    Dev,
+    Normal,
+    Optimize,
+    OptSize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigLinker {
+    Surgical,
+    Legacy,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WarningsSection {
+    pub deny: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GlueSection {
+    pub spec: Option<PathBuf>,
+    pub dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FormatSection {
+    pub sort_headers: bool,
+    pub trailing_commas: Option<ConfigTrailingCommas>,
+    pub doc_comment_width: Option<usize>,
+    pub max_blank_lines: Option<usize>,
+    pub blank_line_between_defs: bool,
+    pub align_record_fields: bool,
+    pub group_digits_above: Option<usize>,
+    pub hex_digits: Option<ConfigHexCase>,
+    pub normalize_exponents: bool,
+    pub pipeline_width: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigTrailingCommas {
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigHexCase {
+    Upper,
+    Lower,
+}
+
+impl ConfigLinker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigLinker::Surgical => "surgical",
+            ConfigLinker::Legacy => "legacy",
+        }
+    }
+}
+
+impl From<ConfigTrailingCommas> for TrailingCommas {
+    fn from(trailing_commas: ConfigTrailingCommas) -> Self {
+        match trailing_commas {
+            ConfigTrailingCommas::Always => TrailingCommas::Always,
+            ConfigTrailingCommas::Never => TrailingCommas::Never,
+        }
+    }
+}
+
+impl From<ConfigHexCase> for HexCase {
+    fn from(hex_case: ConfigHexCase) -> Self {
+        match hex_case {
+            ConfigHexCase::Upper => HexCase::Upper,
+            ConfigHexCase::Lower => HexCase::Lower,
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Finds and loads the `roc.toml` for the given .roc file, or returns the default
+    /// configuration if there isn't one. Exits with an error if the file is invalid.
+    pub fn for_roc_file(roc_file_path: &Path) -> Self {
+        let config_path = match find_config_file(roc_file_path) {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+
+        let result = std::fs::read_to_string(&config_path)
+            .map_err(|err| err.to_string())
+            .and_then(|src| Self::parse(&src).map_err(|err| err.to_string()));
+
+        match result {
+            Ok(mut config) => {
+                // Paths in roc.toml are relative to the directory it's in.
+                let config_dir = config_path.parent().unwrap_or(Path::new("."));
+
+                for path in [&mut config.glue.spec, &mut config.glue.dir]
+                    .into_iter()
+                    .flatten()
+                {
+                    *path = config_dir.join(&*path);
+                }
+
+                config
+            }
+            Err(err) => {
+                eprintln!("\nI could not read {}:\n\n{err}", config_path.display());
+
+                std::process::exit(exit_code::IO_OR_USAGE_ERROR);
+            }
+        }
+    }
+
+    pub fn parse(src: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(src)
+    }
+}
+
+fn find_config_file(roc_file_path: &Path) -> Option<PathBuf> {
+    let roc_file_dir = match roc_file_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let roc_file_dir = std::env::current_dir()
+        .map(|current_dir| current_dir.join(&roc_file_dir))
+        .unwrap_or(roc_file_dir);
+
+    roc_file_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILENAME))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_full_config() {
+        let src = r#"
+            [build]
+            opt-level = "opt-size"
+            target = "wasm32"
+            linker = "legacy"
+            max-threads = 2
+
+            [warnings]
+            deny = true
+
+            [glue]
+            spec = "glue/RustGlue.roc"
+            dir = "glue-out"
+
+            [format]
+            sort-headers = true
+            trailing-commas = "never"
+            doc-comment-width = 80
+            max-blank-lines = 2
+            blank-line-between-defs = true
+            align-record-fields = true
+            group-digits-above = 4
+            hex-digits = "lower"
+            normalize-exponents = true
+            pipeline-width = 100
+        "#;
+
+        assert_eq!(
+            ProjectConfig::parse(src).unwrap(),
+            ProjectConfig {
+                build: BuildSection {
+                    opt_level: Some(ConfigOptLevel::OptSize),
+                    target: Some("wasm32".to_string()),
+                    linker: Some(ConfigLinker::Legacy),
+                    max_threads: Some(2),
+                },
+                warnings: WarningsSection { deny: true },
+                glue: GlueSection {
+                    spec: Some(PathBuf::from("glue/RustGlue.roc")),
+                    dir: Some(PathBuf::from("glue-out")),
+                },
+                format: FormatSection {
+                    sort_headers: true,
+                    trailing_commas: Some(ConfigTrailingCommas::Never),
+                    doc_comment_width: Some(80),
+                    max_blank_lines: Some(2),
+                    blank_line_between_defs: true,
+                    align_record_fields: true,
+                    group_digits_above: Some(4),
+                    hex_digits: Some(ConfigHexCase::Lower),
+                    normalize_exponents: true,
+                    pipeline_width: Some(100),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_settings() {
+        assert_eq!(ProjectConfig::parse("").unwrap(), ProjectConfig::default());
+        assert!(ProjectConfig::parse("[build]\noptimise = true").is_err());
+        assert!(ProjectConfig::parse("[build]\nopt-level = \"fast\"").is_err());
+    }
+
+    #[test]
+    fn finds_config_in_parent_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let app_dir = dir.path().join("examples").join("hello");
+
+        std::fs::create_dir_all(&app_dir).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILENAME),
+            "[glue]\nspec = \"RustGlue.roc\"",
+        )
+        .unwrap();
+
+        let config = ProjectConfig::for_roc_file(&app_dir.join("main.roc"));
+
+        assert_eq!(config.glue.spec, Some(dir.path().join("RustGlue.roc")));
+    }
+}
@@ -0,0 +1,141 @@
+//! Implements `--open` for `roc docs`.
+//!
+//! The generated docs link to each other with absolute URLs (e.g. `<base href="/">`), so opening
+//! index.html as a file doesn't work. Instead, this serves the generated directory over HTTP on
+//! localhost, and opens a browser there.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub fn serve_docs(docs_dir: &Path) -> io::Result<i32> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let url = format!("http://{}/", listener.local_addr()?);
+
+    println!("\nServing the docs at {url} - press Ctrl+C to stop.");
+
+    if let Err(err) = open_browser(&url) {
+        eprintln!("I couldn't open a web browser ({err}), but you can visit {url} yourself.");
+    }
+
+    for stream in listener.incoming().flatten() {
+        // A browser giving up on a request shouldn't stop the server.
+        let _ = respond(stream, docs_dir);
+    }
+
+    Ok(0)
+}
+
+fn respond(mut stream: TcpStream, docs_dir: &Path) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+
+    reader.read_line(&mut request_line)?;
+
+    // Read the rest of the request, so closing the connection doesn't reset it.
+    let mut header = String::new();
+
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    // e.g. "GET /Str HTTP/1.1"
+    let url_path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let file = file_for_url_path(docs_dir, url_path)
+        .and_then(|path| fs::read(&path).ok().map(|bytes| (path, bytes)));
+
+    match file {
+        Some((path, bytes)) => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                content_type(&path),
+                bytes.len()
+            )?;
+            stream.write_all(&bytes)
+        }
+        None => {
+            let body = "Not found";
+
+            write!(
+                stream,
+                "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+    }
+}
+
+/// The file to serve for a request, or None if it's outside `docs_dir`.
+fn file_for_url_path(docs_dir: &Path, url_path: &str) -> Option<PathBuf> {
+    let url_path = url_path.split(['?', '#']).next().unwrap_or_default();
+    let mut path = docs_dir.to_path_buf();
+
+    for segment in url_path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            _ => path.push(segment),
+        }
+    }
+
+    if path.is_dir() {
+        path.push("index.html");
+    }
+
+    Some(path)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+fn open_browser(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+
+        command.args(["/C", "start", ""]);
+
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command.arg(url).spawn().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_files_for_url_paths() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(dir.path().join("Str")).unwrap();
+
+        assert_eq!(
+            file_for_url_path(dir.path(), "/"),
+            Some(dir.path().join("index.html"))
+        );
+        assert_eq!(
+            file_for_url_path(dir.path(), "/Str#join"),
+            Some(dir.path().join("Str").join("index.html"))
+        );
+        assert_eq!(
+            file_for_url_path(dir.path(), "/styles.css?v=1"),
+            Some(dir.path().join("styles.css"))
+        );
+        assert_eq!(file_for_url_path(dir.path(), "/../secrets"), None);
+    }
+}
@@ -1,16 +1,23 @@
 use std::ffi::OsStr;
-use std::io::Write;
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 
 use bumpalo::Bump;
-use roc_error_macros::{internal_error, user_error};
-use roc_fmt::def::fmt_defs;
-use roc_fmt::header::fmt_header;
-use roc_fmt::Buf;
-use roc_parse::ast::{FullAst, SpacesBefore};
-use roc_parse::header::parse_module_defs;
+use roc_error_macros::{exit_code, internal_error};
+use roc_fmt::def::fmt_module_defs_parts;
+use roc_fmt::header::{fmt_header, sort_header_lists};
+use roc_fmt::{BlankLines, Buf, HexCase, NumberLiterals, TrailingCommas};
+use roc_parse::ast::{Defs, Header, SpacesBefore};
+use roc_parse::header::{parse_module_defs_recovering, ModuleDefsPart};
 use roc_parse::normalize::Normalize;
-use roc_parse::{header, parser::SyntaxError, state::State};
+use roc_parse::{
+    header,
+    parser::SyntaxError,
+    state::{State, Tabs},
+};
+use roc_reporting::report::to_unformatted_def_report_string;
+
+use crate::ProjectConfig;
 
 #[derive(Copy, Clone, Debug)]
 pub enum FormatMode {
@@ -19,6 +26,55 @@ pub enum FormatMode {
     CheckOnly,
 }
 
+/// Formatting which has to be asked for, with flags or in roc.toml.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FormatOptions {
+    /// Alphabetize what headers expose and import, and their packages, merging duplicates.
+    pub sort_headers: bool,
+    pub trailing_commas: TrailingCommas,
+    /// Rewrap doc comment paragraphs to fit in this many columns.
+    pub doc_comment_width: Option<usize>,
+    pub blank_lines: BlankLines,
+    /// Line up the fields of multiline records and record types.
+    pub align_record_fields: bool,
+    pub number_literals: NumberLiterals,
+    /// Put each step of `|>` pipelines wider than this on its own line.
+    pub pipeline_width: Option<usize>,
+}
+
+impl FormatOptions {
+    /// These options, plus any the file's roc.toml asks for.
+    fn for_file(self, path: &Path) -> Self {
+        let config = ProjectConfig::for_roc_file(path).format;
+
+        Self {
+            sort_headers: self.sort_headers || config.sort_headers,
+            trailing_commas: config
+                .trailing_commas
+                .map_or(self.trailing_commas, TrailingCommas::from),
+            doc_comment_width: config.doc_comment_width.or(self.doc_comment_width),
+            blank_lines: BlankLines {
+                max: config.max_blank_lines.unwrap_or(self.blank_lines.max),
+                between_top_level_defs: self.blank_lines.between_top_level_defs
+                    || config.blank_line_between_defs,
+            },
+            align_record_fields: self.align_record_fields || config.align_record_fields,
+            number_literals: NumberLiterals {
+                group_digits_above: config
+                    .group_digits_above
+                    .or(self.number_literals.group_digits_above),
+                hex_case: config
+                    .hex_digits
+                    .map(HexCase::from)
+                    .or(self.number_literals.hex_case),
+                normalize_exponents: self.number_literals.normalize_exponents
+                    || config.normalize_exponents,
+            },
+            pipeline_width: config.pipeline_width.or(self.pipeline_width),
+        }
+    }
+}
+
 fn flatten_directories(files: std::vec::Vec<PathBuf>) -> std::vec::Vec<PathBuf> {
     let mut to_flatten = files;
     let mut files = vec![];
@@ -63,21 +119,34 @@ fn is_roc_file(path: &Path) -> bool {
     matches!(path.extension().and_then(OsStr::to_str), Some("roc"))
 }
 
-pub fn format_files(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(), String> {
+pub fn format_files(
+    files: std::vec::Vec<PathBuf>,
+    mode: FormatMode,
+    options: FormatOptions,
+) -> Result<(), String> {
     let arena = Bump::new();
     let mut files_to_reformat = Vec::new(); // to track which files failed `roc format --check`
 
     for file in flatten_directories(files) {
         let src = std::fs::read_to_string(&file).unwrap();
 
-        match format_src(&arena, &src) {
-            Ok(buf) => {
+        match format_module(&arena, &src, options.for_file(&file)) {
+            Ok(Formatted {
+                src: buf,
+                unformatted_defs,
+            }) => {
+                warn_unformatted_defs(&file, &src, unformatted_defs);
+
                 match mode {
                     FormatMode::CheckOnly => {
-                        // If a file fails `format --check`, add it to the file
-                        // list for reporting afterwards.
+                        // If a file fails `format --check`, print what would change, and
+                        // add it to the file list for reporting afterwards.
                         if buf.as_str() != src {
-                            files_to_reformat.push(file.display().to_string());
+                            let file_name = file.display().to_string();
+
+                            print!("{}", format_diff(&file_name, &src, buf.as_str()));
+
+                            files_to_reformat.push(file_name);
                         }
                     }
                     FormatMode::WriteToFile => {
@@ -90,6 +159,10 @@ pub fn format_files(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(
                 }
             }
             Err(err) => match err {
+                FormatProblem::InvalidSyntax { parse_err } => {
+                    eprintln!("Unexpected parse failure when parsing this formatting:\n\n{:?}\n\nParse error was:\n\n{}\n\n", src, parse_err);
+                    std::process::exit(exit_code::PARSE_OR_CANONICALIZE_ERRORS)
+                }
                 FormatProblem::ParsingFailed {
                     formatted_src,
                     parse_err,
@@ -166,8 +239,95 @@ pub fn format_files(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(
     Ok(())
 }
 
+/// Formats source code read from stdin, for `roc format -` and `roc format --stdin`.
+/// The `filename` is only used in messages, so editors formatting an unsaved buffer
+/// can say which buffer had a problem.
+pub fn format_stdin(mode: FormatMode, filename: &str, options: FormatOptions) -> io::Result<i32> {
+    let mut buf = Vec::new();
+
+    io::stdin().read_to_end(&mut buf)?;
+
+    let src = match std::str::from_utf8(&buf) {
+        Ok(src) => src,
+        Err(err) => {
+            eprintln!("{filename} contained invalid UTF-8 bytes: {err:?}");
+
+            return Ok(exit_code::IO_OR_USAGE_ERROR);
+        }
+    };
+
+    let arena = Bump::new();
+
+    match format_module(&arena, src, options.for_file(Path::new(filename))) {
+        Ok(Formatted {
+            src: formatted_src,
+            unformatted_defs,
+        }) => {
+            warn_unformatted_defs(Path::new(filename), src, unformatted_defs);
+
+            match mode {
+                FormatMode::CheckOnly => {
+                    if src == formatted_src {
+                        Ok(exit_code::SUCCESS)
+                    } else {
+                        print!("{}", format_diff(filename, src, &formatted_src));
+                        eprintln!("{filename} needs to be reformatted.");
+
+                        Ok(exit_code::FAILURE)
+                    }
+                }
+                FormatMode::WriteToStdout => {
+                    io::stdout().lock().write_all(formatted_src.as_bytes())?;
+
+                    Ok(exit_code::SUCCESS)
+                }
+                FormatMode::WriteToFile => {
+                    internal_error!("There's no file to write to when formatting stdin")
+                }
+            }
+        }
+        Err(FormatProblem::InvalidSyntax { parse_err }) => {
+            eprintln!(
+                "I could not format {filename}, because it has a syntax error:\n\n{parse_err}"
+            );
+
+            Ok(exit_code::PARSE_OR_CANONICALIZE_ERRORS)
+        }
+        Err(problem) => {
+            eprintln!("`roc format` failed on {filename}: {problem:?}");
+
+            Ok(exit_code::INTERNAL_COMPILER_ERROR)
+        }
+    }
+}
+
+/// Warns about the defs which were left as they were, because they have syntax errors.
+fn warn_unformatted_defs(path: &Path, src: &str, unformatted_defs: std::vec::Vec<SyntaxError>) {
+    for error in unformatted_defs {
+        eprintln!(
+            "{}",
+            to_unformatted_def_report_string(
+                path.to_path_buf(),
+                src,
+                error,
+                io::stderr().is_terminal()
+            )
+        );
+    }
+}
+
+/// A unified diff from the original source to its formatted version, for `roc format --check`.
+pub fn format_diff(file_name: &str, src: &str, formatted_src: &str) -> String {
+    similar::TextDiff::from_lines(src, formatted_src)
+        .unified_diff()
+        .header(file_name, file_name)
+        .to_string()
+}
+
 #[derive(Debug)]
 pub enum FormatProblem {
+    /// The source code didn't parse in the first place.
+    InvalidSyntax { parse_err: String },
     ParsingFailed {
         formatted_src: String,
         parse_err: String,
@@ -183,12 +343,46 @@ pub enum FormatProblem {
     },
 }
 
-pub fn format_src(arena: &Bump, src: &str) -> Result<String, FormatProblem> {
-    let ast = arena.alloc(parse_all(arena, src).unwrap_or_else(|e| {
-        user_error!("Unexpected parse failure when parsing this formatting:\n\n{:?}\n\nParse error was:\n\n{:?}\n\n", src, e)
-    }));
-    let mut buf = Buf::new_in(arena);
-    fmt_all(&mut buf, ast);
+/// A formatted module.
+#[derive(Debug)]
+pub struct Formatted<'a> {
+    pub src: String,
+    /// The syntax errors of the top-level defs which were left as they were, because they didn't
+    /// parse.
+    pub unformatted_defs: std::vec::Vec<SyntaxError<'a>>,
+}
+
+pub fn format_src(
+    arena: &Bump,
+    src: &str,
+    options: FormatOptions,
+) -> Result<String, FormatProblem> {
+    format_module(arena, src, options).map(|formatted| formatted.src)
+}
+
+/// Formats a module. A top-level def with a syntax error doesn't stop the rest from being
+/// formatted: it's left as it was, and its error is returned with the formatted source.
+pub fn format_module<'a>(
+    arena: &'a Bump,
+    src: &'a str,
+    options: FormatOptions,
+) -> Result<Formatted<'a>, FormatProblem> {
+    let mut ast = match parse_all(arena, src) {
+        Ok(ast) => ast,
+        Err(e) => {
+            return Err(FormatProblem::InvalidSyntax {
+                parse_err: format!("{:?}", e),
+            });
+        }
+    };
+
+    if options.sort_headers {
+        ast.header.item = sort_header_lists(arena, &ast.header.item);
+    }
+
+    let ast = arena.alloc(ast);
+    let mut buf = new_buf(arena, options);
+    fmt_all(&mut buf, ast, src);
 
     let reparsed_ast = match arena.alloc(parse_all(arena, buf.as_str())) {
         Ok(ast) => ast,
@@ -200,8 +394,8 @@ pub fn format_src(arena: &Bump, src: &str) -> Result<String, FormatProblem> {
         }
     };
 
-    let ast_normalized = ast.normalize(arena);
-    let reparsed_ast_normalized = reparsed_ast.normalize(arena);
+    let ast_normalized = ast.normalize(arena, src);
+    let reparsed_ast_normalized = reparsed_ast.normalize(arena, buf.as_str());
 
     // HACK!
     // We compare the debug format strings of the ASTs, because I'm finding in practice that _somewhere_ deep inside the ast,
@@ -217,9 +411,9 @@ pub fn format_src(arena: &Bump, src: &str) -> Result<String, FormatProblem> {
     }
 
     // Now verify that the resultant formatting is _stable_ - i.e. that it doesn't change again if re-formatted
-    let mut reformatted_buf = Buf::new_in(arena);
+    let mut reformatted_buf = new_buf(arena, options);
 
-    fmt_all(&mut reformatted_buf, reparsed_ast);
+    fmt_all(&mut reformatted_buf, reparsed_ast, buf.as_str());
 
     if buf.as_str() != reformatted_buf.as_str() {
         return Err(FormatProblem::ReformattingUnstable {
@@ -228,18 +422,77 @@ pub fn format_src(arena: &Bump, src: &str) -> Result<String, FormatProblem> {
         });
     }
 
-    Ok(buf.as_str().to_string())
+    Ok(Formatted {
+        src: buf.as_str().to_string(),
+        unformatted_defs: ast
+            .defs
+            .iter()
+            .filter_map(|part| match part {
+                ModuleDefsPart::Defs(_) => None,
+                ModuleDefsPart::Broken { error, .. } => Some(error.clone()),
+            })
+            .collect(),
+    })
+}
+
+/// A module whose top-level defs might not all have parsed.
+#[derive(Debug)]
+struct ModuleAst<'a> {
+    header: SpacesBefore<'a, Header<'a>>,
+    defs: std::vec::Vec<ModuleDefsPart<'a>>,
+}
+
+/// What formatting mustn't change: the parts of the module which parsed, without their spaces,
+/// and the source of the defs which didn't.
+#[derive(Debug)]
+struct NormalizedModule<'a> {
+    header: SpacesBefore<'a, Header<'a>>,
+    defs: std::vec::Vec<Result<Defs<'a>, &'a str>>,
+}
+
+impl<'a> ModuleAst<'a> {
+    fn normalize(&self, arena: &'a Bump, src: &'a str) -> NormalizedModule<'a> {
+        NormalizedModule {
+            header: self.header.normalize(arena),
+            defs: self
+                .defs
+                .iter()
+                .map(|part| match part {
+                    ModuleDefsPart::Defs(defs) => Ok(defs.normalize(arena)),
+                    ModuleDefsPart::Broken { region, .. } => {
+                        let start = region.start().offset as usize;
+                        let end = region.end().offset as usize;
+
+                        Err(src[start..end].trim())
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn new_buf(arena: &Bump, options: FormatOptions) -> Buf<'_> {
+    Buf::new_in(arena)
+        .with_trailing_commas(options.trailing_commas)
+        .with_doc_comment_width(options.doc_comment_width)
+        .with_blank_lines(options.blank_lines)
+        .with_aligned_record_fields(options.align_record_fields)
+        .with_number_literals(options.number_literals)
+        .with_pipeline_width(options.pipeline_width)
 }
 
-fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<FullAst<'a>, SyntaxError<'a>> {
-    let (header, state) = header::parse_header(arena, State::new(src.as_bytes()))
-        .map_err(|e| SyntaxError::Header(e.problem))?;
+fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<ModuleAst<'a>, SyntaxError<'a>> {
+    let (header, state) = header::parse_header(
+        arena,
+        State::new(src.as_bytes()).with_tabs(Tabs::from_env()),
+    )
+    .map_err(|e| SyntaxError::Header(e.problem))?;
 
     let (h, defs) = header.item.upgrade_header_imports(arena);
 
-    let defs = parse_module_defs(arena, state, defs)?;
+    let defs = parse_module_defs_recovering(arena, state, defs);
 
-    Ok(FullAst {
+    Ok(ModuleAst {
         header: SpacesBefore {
             before: header.before,
             item: h,
@@ -248,10 +501,10 @@ fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<FullAst<'a>, SyntaxErr
     })
 }
 
-fn fmt_all<'a>(buf: &mut Buf<'a>, ast: &'a FullAst) {
+fn fmt_all<'a>(buf: &mut Buf<'a>, ast: &'a ModuleAst, src: &str) {
     fmt_header(buf, &ast.header);
 
-    fmt_defs(buf, &ast.defs, 0);
+    fmt_module_defs_parts(buf, &ast.defs, src);
 
     buf.fmt_end_of_file();
 }
@@ -303,7 +556,11 @@ main =
         let dir = tempdir().unwrap();
         let file_path = setup_test_file(dir.path(), "test1.roc", UNFORMATTED_ROC);
 
-        let result = format_files(vec![file_path.clone()], FormatMode::CheckOnly);
+        let result = format_files(
+            vec![file_path.clone()],
+            FormatMode::CheckOnly,
+            FormatOptions::default(),
+        );
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
@@ -322,7 +579,11 @@ main =
         let file1 = setup_test_file(dir.path(), "test1.roc", UNFORMATTED_ROC);
         let file2 = setup_test_file(dir.path(), "test2.roc", UNFORMATTED_ROC);
 
-        let result = format_files(vec![file1, file2], FormatMode::CheckOnly);
+        let result = format_files(
+            vec![file1, file2],
+            FormatMode::CheckOnly,
+            FormatOptions::default(),
+        );
         assert!(result.is_err());
         let error_message = result.unwrap_err();
         assert!(error_message.contains("test1.roc") && error_message.contains("test2.roc"));
@@ -335,12 +596,31 @@ main =
         let dir = tempdir().unwrap();
         let file_path = setup_test_file(dir.path(), "formatted.roc", FORMATTED_ROC);
 
-        let result = format_files(vec![file_path], FormatMode::CheckOnly);
+        let result = format_files(
+            vec![file_path],
+            FormatMode::CheckOnly,
+            FormatOptions::default(),
+        );
         assert!(result.is_ok());
 
         cleanup_temp_dir(dir);
     }
 
+    #[test]
+    fn test_format_diff() {
+        let diff = format_diff("test1.roc", UNFORMATTED_ROC, FORMATTED_ROC);
+
+        assert!(diff.starts_with("--- test1.roc\n+++ test1.roc\n@@ "));
+        assert!(diff.contains("\n-        Stdout.line! \"What's your name?\"\n"));
+        assert!(diff.contains("\n+    Stdout.line! \"What's your name?\"\n"));
+        assert!(!diff.contains("\n-import pf.Stdout\n"));
+
+        assert_eq!(
+            format_diff("formatted.roc", FORMATTED_ROC, FORMATTED_ROC),
+            ""
+        );
+    }
+
     #[test]
     fn test_some_files_need_reformatting() {
         let dir = tempdir().unwrap();
@@ -351,6 +631,7 @@ main =
         let result = format_files(
             vec![file_formatted, file1_unformated, file2_unformated],
             FormatMode::CheckOnly,
+            FormatOptions::default(),
         );
         assert!(result.is_err());
         let error_message = result.unwrap_err();
@@ -359,4 +640,151 @@ main =
 
         cleanup_temp_dir(dir);
     }
+
+    #[test]
+    fn test_sort_headers() {
+        let arena = Bump::new();
+        let src = "module [b, a, b]
+
+import Foo
+";
+        let sorted = FormatOptions {
+            sort_headers: true,
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            format_src(&arena, src, FormatOptions::default()).unwrap(),
+            src
+        );
+        assert_eq!(
+            format_src(&arena, src, sorted).unwrap(),
+            "module [a, b]\n\nimport Foo\n"
+        );
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let arena = Bump::new();
+        let src = "module [a, b]\n\na = 1 # one\nb =\n    x = 2\n\n\n\n    x\n";
+        let options = FormatOptions {
+            blank_lines: BlankLines {
+                max: 2,
+                between_top_level_defs: true,
+            },
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            format_src(&arena, src, FormatOptions::default()).unwrap(),
+            "module [a, b]\n\na = 1 # one\nb =\n    x = 2\n\n    x\n"
+        );
+        assert_eq!(
+            format_src(&arena, src, options).unwrap(),
+            "module [a, b]\n\na = 1 # one\n\nb =\n    x = 2\n\n\n    x\n"
+        );
+    }
+
+    #[test]
+    fn test_align_record_fields() {
+        let arena = Bump::new();
+        let src = "module [a]\n\na : { x : U8, long : U8 }\na = {\n    x: 1,\n    long: 2,\n}\n";
+        let options = FormatOptions {
+            align_record_fields: true,
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            format_src(&arena, src, FormatOptions::default()).unwrap(),
+            src
+        );
+        assert_eq!(
+            format_src(&arena, src, options).unwrap(),
+            "module [a]\n\na : { x : U8, long : U8 }\na = {\n    x:    1,\n    long: 2,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_number_literals() {
+        let arena = Bump::new();
+        let src = "module [a]\n\na = [1000000, 1_000, 0xabCD, 0xABCDEC, 1.5E05, -12345.5e-005]\n";
+        let options = FormatOptions {
+            number_literals: NumberLiterals {
+                group_digits_above: Some(4),
+                hex_case: Some(HexCase::Upper),
+                normalize_exponents: true,
+            },
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            format_src(&arena, src, FormatOptions::default()).unwrap(),
+            src
+        );
+        assert_eq!(
+            format_src(&arena, src, options).unwrap(),
+            "module [a]\n\na = [1_000_000, 1000, 0xABCD, 0xABCDEC, 1.5e5, -12_345.5e-5]\n"
+        );
+    }
+
+    #[test]
+    fn test_pipeline_width() {
+        let arena = Bump::new();
+        let src = "module [a]\n\na = [1, 2, 3] |> List.map Num.toStr |> Str.joinWith \", \"\n";
+        let options = FormatOptions {
+            pipeline_width: Some(40),
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            format_src(&arena, src, FormatOptions::default()).unwrap(),
+            src
+        );
+        assert_eq!(
+            format_src(&arena, src, options).unwrap(),
+            "module [a]\n\na =\n    [1, 2, 3]\n    |> List.map Num.toStr\n    |> Str.joinWith \", \"\n"
+        );
+    }
+
+    #[test]
+    fn test_format_module_with_syntax_error() {
+        let arena = Bump::new();
+        let src = "module [a, b, c]\n\na =   1\n\nb = [1,\n    2,\n\n# About c\nc =   {x:1}\n";
+        let formatted = format_module(&arena, src, FormatOptions::default()).unwrap();
+
+        assert_eq!(
+            formatted.src,
+            "module [a, b, c]\n\na = 1\n\nb = [1,\n    2,\n\n# About c\nc = { x: 1 }\n"
+        );
+        assert_eq!(formatted.unformatted_defs.len(), 1);
+    }
+
+    #[test]
+    fn test_format_options_from_config() {
+        let dir = tempdir().unwrap();
+        let file_path = setup_test_file(
+            dir.path(),
+            "test1.roc",
+            "module [b, a]\n\nx = [\n    1,\n    2,\n]",
+        );
+
+        std::fs::write(
+            dir.path().join(crate::CONFIG_FILENAME),
+            "[format]\nsort-headers = true\ntrailing-commas = \"never\"",
+        )
+        .unwrap();
+
+        assert!(format_files(
+            vec![file_path.clone()],
+            FormatMode::WriteToFile,
+            FormatOptions::default()
+        )
+        .is_ok());
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "module [a, b]\n\nx = [\n    1,\n    2\n]\n"
+        );
+
+        cleanup_temp_dir(dir);
+    }
 }
@@ -4,29 +4,27 @@
 extern crate const_format;
 
 use bumpalo::Bump;
-use clap::{
-    builder::PossibleValuesParser, parser::ValueSource, value_parser, Arg, ArgAction, ArgMatches,
-    Command,
-};
-use roc_build::link::{LinkType, LinkingStrategy};
+use clap::{parser::ValueSource, value_parser, Arg, ArgAction, ArgMatches, Command};
+use roc_build::link::{LinkType, LinkingStrategy, Sanitizer};
 use roc_build::program::{
     handle_error_module, handle_loading_problem, standard_load_config, BuildFileError,
-    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, DEFAULT_ROC_FILENAME,
+    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, EmitArtifacts, DEFAULT_ROC_FILENAME,
 };
 #[cfg(not(windows))]
 use roc_collections::MutMap;
-use roc_error_macros::{internal_error, user_error};
+use roc_error_macros::{exit_code, internal_error, user_error};
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{ExpectMetadata, Threading};
+use roc_load::{ExpectMetadata, NumericDefaults, Tabs, Threading};
 #[cfg(not(windows))]
 use roc_module::symbol::ModuleId;
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
 #[cfg(not(windows))]
+use roc_reporting::error::r#type::{EXPAND_ALIASES_VAR, VERBOSE_TYPES_VAR};
 use roc_reporting::report::ANSI_STYLE_CODES;
-use roc_target::{Architecture, Target};
+use roc_target::{Architecture, OperatingSystem, Target};
 use std::env;
 use std::ffi::{CString, OsStr, OsString};
 use std::io;
@@ -34,6 +32,7 @@ use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_int};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::str::FromStr;
 #[cfg(not(windows))]
 use std::time::Duration;
 use std::time::Instant;
@@ -41,8 +40,28 @@ use strum::IntoEnumIterator;
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+mod annotate;
+#[cfg(not(windows))]
+mod bench;
+mod config;
+mod docs_server;
 mod format;
-pub use format::{format_files, format_src, FormatMode};
+mod messages;
+mod progress;
+mod valgrind;
+mod watch;
+pub use annotate::annotate;
+#[cfg(not(windows))]
+pub use bench::bench;
+pub use config::{ConfigOptLevel, ProjectConfig, CONFIG_FILENAME};
+pub use docs_server::serve_docs;
+pub use format::{
+    format_diff, format_files, format_module, format_src, format_stdin, FormatMode, FormatOptions,
+    Formatted,
+};
+pub use progress::{clear_progress_bar, progress_reporter};
+pub use valgrind::test_with_valgrind;
+pub use watch::watch;
 
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
@@ -50,25 +69,41 @@ pub const CMD_DEV: &str = "dev";
 pub const CMD_REPL: &str = "repl";
 pub const CMD_DOCS: &str = "docs";
 pub const CMD_CHECK: &str = "check";
+pub const CMD_LINT: &str = "lint";
+pub const CMD_ANNOTATE: &str = "annotate";
+pub const CMD_LSP: &str = "lsp";
+pub const CMD_CACHE: &str = "cache";
+pub const CMD_CACHE_CLEAN: &str = "clean";
+pub const CMD_CACHE_DIR: &str = "dir";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
+pub const CMD_BENCH: &str = "bench";
 pub const CMD_GLUE: &str = "glue";
 pub const CMD_GEN_STUB_LIB: &str = "gen-stub-lib";
 pub const CMD_PREPROCESS_HOST: &str = "preprocess-host";
+pub const CMD_COMPLETIONS: &str = "completions";
 
 pub const FLAG_EMIT_LLVM_IR: &str = "emit-llvm-ir";
+pub const FLAG_EMIT: &str = "emit";
 pub const FLAG_PROFILING: &str = "profiling";
+pub const FLAG_DEBUG: &str = "debug";
 pub const FLAG_BUNDLE: &str = "bundle";
+pub const FLAG_DETERMINISTIC: &str = "deterministic";
 pub const FLAG_DEV: &str = "dev";
+pub const FLAG_BACKEND: &str = "backend";
 pub const FLAG_OPTIMIZE: &str = "optimize";
 pub const FLAG_MAX_THREADS: &str = "max-threads";
+pub const FLAG_JOBS: &str = "jobs";
 pub const FLAG_OPT_SIZE: &str = "opt-size";
+pub const FLAG_OPT_LEVEL: &str = "opt-level";
 pub const FLAG_LIB: &str = "lib";
+pub const FLAG_GLUE: &str = "glue";
 pub const FLAG_NO_LINK: &str = "no-link";
 pub const FLAG_TARGET: &str = "target";
 pub const FLAG_TIME: &str = "time";
 pub const FLAG_VERBOSE: &str = "verbose";
+pub const FLAG_MESSAGE_FORMAT: &str = "message-format";
 pub const FLAG_NO_COLOR: &str = "no-color";
 pub const FLAG_NO_HEADER: &str = "no-header";
 pub const FLAG_LINKER: &str = "linker";
@@ -76,19 +111,42 @@ pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_STDIN: &str = "stdin";
 pub const FLAG_STDOUT: &str = "stdout";
+pub const FLAG_STDIN_FILENAME: &str = "stdin-filename";
+pub const FLAG_SORT_HEADERS: &str = "sort-headers";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
 pub const FLAG_OUTPUT: &str = "output";
 pub const FLAG_FUZZ: &str = "fuzz";
+pub const FLAG_SANITIZE: &str = "sanitize";
+pub const FLAG_ATOMIC_REFCOUNT: &str = "atomic-refcount";
+pub const FLAG_PRINT_EXPECTS: &str = "print-expects";
 pub const FLAG_MAIN: &str = "main";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_WARMUP: &str = "warmup";
+pub const FLAG_ITERATIONS: &str = "iterations";
+pub const FLAG_WRITE: &str = "write";
+pub const FLAG_NO_CACHE: &str = "no-cache";
+pub const FLAG_TAB_WIDTH: &str = "tab-width";
+pub const FLAG_VERBOSE_TYPES: &str = "verbose-types";
+pub const FLAG_EXPAND_ALIASES: &str = "expand-aliases";
+pub const FLAG_EXPLAIN_DEFAULTS: &str = "explain-defaults";
+pub const FLAG_DENY_DEFAULTS: &str = "deny-defaults";
+pub const FLAG_VALGRIND: &str = "valgrind";
+/// The environment variable which sets the default for `--target`.
+pub const ROC_TARGET_VAR: &str = "ROC_TARGET";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
+pub const GLUE_LEGACY_ROC_FILE: &str = "GLUE_LEGACY_ROC_FILE";
+pub const FLAG_LANG: &str = "lang";
+pub const FLAG_OPEN: &str = "open";
+pub const FLAG_THEME: &str = "theme";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 pub const FLAG_PP_HOST: &str = "host";
 pub const FLAG_PP_PLATFORM: &str = "platform";
 pub const FLAG_PP_DYLIB: &str = "lib";
+pub const SHELL: &str = "SHELL";
 
 pub const VERSION: &str = env!("ROC_VERSION");
 const DEFAULT_GENERATED_DOCS_DIR: &str = "generated-docs";
@@ -106,9 +164,25 @@ pub fn build_app() -> Command {
         .value_parser(value_parser!(usize))
         .required(false);
 
+    let flag_jobs = Arg::new(FLAG_JOBS)
+        .long(FLAG_JOBS)
+        .short('j')
+        .help("Use exactly this many threads during compilation, even if there are fewer cores")
+        .value_parser(value_parser!(usize))
+        .conflicts_with(FLAG_MAX_THREADS)
+        .required(false);
+
+    let flag_opt_level = Arg::new(FLAG_OPT_LEVEL)
+        .long(FLAG_OPT_LEVEL)
+        .short('O')
+        .help("Choose how much to optimize the compiled program, e.g. -O2\n(0 is the default; 3 is the same as --optimize, and size is the same as --opt-size. Higher levels take longer to compile.)")
+        .value_parser(["0", "1", "2", "3", "size"])
+        .conflicts_with_all([FLAG_OPTIMIZE, FLAG_OPT_SIZE])
+        .required(false);
+
     let flag_opt_size = Arg::new(FLAG_OPT_SIZE)
         .long(FLAG_OPT_SIZE)
-        .help("Optimize the compiled program to have a small binary size\n(Optimization takes time to complete.)")
+        .help("Optimize the compiled program to have a small binary size\n(Optimization takes time to complete. `roc build --emit size-report` lists which functions are biggest.)")
         .action(ArgAction::SetTrue)
         .required(false);
 
@@ -118,6 +192,13 @@ pub fn build_app() -> Command {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_backend = Arg::new(FLAG_BACKEND)
+        .long(FLAG_BACKEND)
+        .help("Which backend to generate code with\n(llvm makes faster programs; dev compiles faster; wasm builds for --target wasm32. By default, llvm is used unless --dev is given.)")
+        .value_parser(["llvm", "dev", "wasm"])
+        .conflicts_with(FLAG_DEV)
+        .required(false);
+
     let flag_emit_llvm_ir = Arg::new(FLAG_EMIT_LLVM_IR)
         .long(FLAG_EMIT_LLVM_IR)
         .help("Emit a `.ll` file containing the LLVM IR of the program")
@@ -126,7 +207,13 @@ pub fn build_app() -> Command {
 
     let flag_profiling = Arg::new(FLAG_PROFILING)
         .long(FLAG_PROFILING)
-        .help("Keep debug info in the final generated program even in optimized builds")
+        .help("Build a program that's easy to profile with tools like `perf`, even when optimized\n(Keeps debug info and frame pointers, gives Roc functions readable names, and doesn't inline them.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_debug = Arg::new(FLAG_DEBUG)
+        .long(FLAG_DEBUG)
+        .help("Build a program whose crashes can be debugged with tools like `gdb` and `lldb`, even when optimized\n(Keeps debug info, so backtraces show Roc function names, files, and line numbers.)")
         .action(ArgAction::SetTrue)
         .required(false);
 
@@ -136,6 +223,12 @@ pub fn build_app() -> Command {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_verbose = Arg::new(FLAG_VERBOSE)
+        .long(FLAG_VERBOSE)
+        .help("Print each module's progress through the compiler, and how long each step took")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_linker = Arg::new(FLAG_LINKER)
         .long(FLAG_LINKER)
         .help("Set which linker to use\n(The surgical linker is enabled by default only when building for wasm32 or x86_64 Linux, because those are the only targets it currently supports. Otherwise the legacy linker is used by default.)")
@@ -160,12 +253,36 @@ pub fn build_app() -> Command {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_sanitize = Arg::new(FLAG_SANITIZE)
+        .long(FLAG_SANITIZE)
+        .help("Check the program for memory errors while it runs, with AddressSanitizer or LeakSanitizer\n(Instruments the Roc code and links in the sanitizer's runtime, which also watches the memory the platform allocates. Only supported on Linux and macOS, and uses the legacy linker.)")
+        .value_parser(["address", "leak"])
+        .required(false);
+
+    let flag_atomic_refcount = Arg::new(FLAG_ATOMIC_REFCOUNT)
+        .long(FLAG_ATOMIC_REFCOUNT)
+        .help("Update refcounts atomically, so the platform can share Roc values across threads\n(The platform's host must do the same, e.g. with roc_std's atomic-refcount feature. Only supported by the LLVM backend.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
+    let flag_print_expects = Arg::new(FLAG_PRINT_EXPECTS)
+        .long(FLAG_PRINT_EXPECTS)
+        .help("Keep `expect`s in the built program, and print the code and looked-up values of any that fail\n(Prints through the platform's roc_dbg. Only supported by the LLVM backend.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_main = Arg::new(FLAG_MAIN)
         .long(FLAG_MAIN)
         .help("The .roc file of the main app/package module to resolve dependencies from")
         .value_parser(value_parser!(PathBuf))
         .required(false);
 
+    let flag_watch = Arg::new(FLAG_WATCH)
+        .long(FLAG_WATCH)
+        .help("Rebuild and rerun the app whenever a .roc file in its directory changes")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .value_parser(value_parser!(PathBuf))
@@ -178,13 +295,103 @@ pub fn build_app() -> Command {
         .num_args(0..)
         .allow_hyphen_values(true);
 
-    let build_target_values_parser =
-        PossibleValuesParser::new(Target::iter().map(Into::<&'static str>::into));
+    let target_names = Target::iter()
+        .map(Into::<&'static str>::into)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let target_help = format!("Choose a different target\n(One of: {target_names}; or a target triple such as x86_64-unknown-linux-gnu)\nDefaults to ${ROC_TARGET_VAR} if it's set");
+
+    // Accepts Roc's own target names, as well as target triples.
+    let build_target_values_parser = move |s: &str| match Target::from_str(s) {
+        Ok(_) => Ok(s.to_string()),
+        Err(_) => Err(format!(
+            "unsupported target; expected one of: {target_names}; or a supported target triple"
+        )),
+    };
+
+    let exit_codes_help = [
+        (exit_code::SUCCESS, "Success"),
+        (
+            exit_code::FAILURE,
+            "The command failed, e.g. `roc test` had failing tests",
+        ),
+        (exit_code::WARNINGS, "There were warnings, but no errors"),
+        (
+            exit_code::PARSE_OR_CANONICALIZE_ERRORS,
+            "There were syntax errors, or problems like unknown names",
+        ),
+        (exit_code::TYPE_ERRORS, "There were type errors"),
+        (exit_code::PROGRAM_CRASHED, "The program crashed"),
+        (
+            exit_code::IO_OR_USAGE_ERROR,
+            "A file could not be read or written, or the arguments were invalid",
+        ),
+        (
+            exit_code::NO_EXPECTATIONS,
+            "`roc test` found no expects to run",
+        ),
+        (exit_code::INTERNAL_COMPILER_ERROR, "The compiler hit a bug"),
+    ]
+    .iter()
+    .fold(String::from("Exit codes:"), |help, (code, meaning)| {
+        format!("{help}\n  {code:<5}{meaning}")
+    });
 
     Command::new("roc")
         .version(VERSION)
         .about("Run the given .roc file, if there are no compilation errors.\nYou can use one of the SUBCOMMANDS below to do something else!")
+        .after_help(exit_codes_help)
         .args_conflicts_with_subcommands(true)
+        .arg(
+            Arg::new(FLAG_NO_CACHE)
+                .long(FLAG_NO_CACHE)
+                .help("Don't use or add to the cache of downloaded packages\n(Packages are downloaded into a temporary directory instead, which is deleted afterwards.)")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .required(false)
+        )
+        .arg(
+            Arg::new(FLAG_TAB_WIDTH)
+                .long(FLAG_TAB_WIDTH)
+                .help(format!("Allow tabs in .roc files, with each one counting as up to this many columns of indentation\n(Tabs are rejected by default.)\nDefaults to ${} if it's set", Tabs::WIDTH_VAR))
+                .value_parser(value_parser!(u8).range(1..))
+                .global(true)
+                .required(false)
+        )
+        .arg(
+            Arg::new(FLAG_VERBOSE_TYPES)
+                .long(FLAG_VERBOSE_TYPES)
+                .help(format!("Print types in error messages in full, even when they're very large\n(Deeply nested parts of large types are shown as … by default.)\nDefaults to ${} if it's set", VERBOSE_TYPES_VAR))
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .required(false)
+        )
+        .arg(
+            Arg::new(FLAG_EXPAND_ALIASES)
+                .long(FLAG_EXPAND_ALIASES)
+                .help(format!("Print type aliases in error messages as the types they stand for\n(For example, a `Model` alias is shown as its record.)\nDefaults to ${} if it's set", EXPAND_ALIASES_VAR))
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .required(false)
+        )
+        .arg(
+            Arg::new(FLAG_EXPLAIN_DEFAULTS)
+                .long(FLAG_EXPLAIN_DEFAULTS)
+                .help(format!("Warn about each number whose type is ambiguous, saying which type it defaults to\n(Ambiguous integers become I64s and fractions become Decs.)\nSame as setting ${}=explain", NumericDefaults::VAR))
+                .action(ArgAction::SetTrue)
+                .conflicts_with(FLAG_DENY_DEFAULTS)
+                .global(true)
+                .required(false)
+        )
+        .arg(
+            Arg::new(FLAG_DENY_DEFAULTS)
+                .long(FLAG_DENY_DEFAULTS)
+                .help(format!("Report numbers whose type is ambiguous as errors, instead of defaulting them\nSame as setting ${}=deny", NumericDefaults::VAR))
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .required(false)
+        )
         .subcommand(Command::new(CMD_BUILD)
             .about("Build a binary from the given .roc file, but don't run it")
             .arg(Arg::new(FLAG_OUTPUT)
@@ -195,19 +402,36 @@ pub fn build_app() -> Command {
             )
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
             .arg(flag_opt_size.clone())
+            .arg(flag_opt_level.clone())
             .arg(flag_dev.clone())
+            .arg(flag_backend.clone())
             .arg(flag_emit_llvm_ir.clone())
             .arg(flag_profiling.clone())
+            .arg(flag_debug.clone())
             .arg(flag_time.clone())
+            .arg(flag_verbose.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_sanitize.clone())
+            .arg(flag_atomic_refcount.clone())
+            .arg(flag_print_expects.clone())
             .arg(flag_wasm_stack_size_kb)
+            .arg(
+                Arg::new(FLAG_EMIT)
+                    .long(FLAG_EMIT)
+                    .help("Write an intermediate artifact next to the .roc file\n(Can be given more than once, or as a comma-separated list, e.g. `--emit llvm-ir,asm`.)")
+                    .value_parser(EmitArtifacts::NAMES)
+                    .value_delimiter(',')
+                    .action(ArgAction::Append)
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
-                    .help("Choose a different target")
+                    .help(target_help.clone())
                     .default_value(Into::<&'static str>::into(Target::default()))
                     .value_parser(build_target_values_parser.clone())
                     .required(false),
@@ -215,8 +439,19 @@ pub fn build_app() -> Command {
             .arg(
                 Arg::new(FLAG_LIB)
                     .long(FLAG_LIB)
-                    .help("Build a C library instead of an executable")
-                    .action(ArgAction::SetTrue)
+                    .help("Build a C library instead of an executable\n(Either a shared library, which is the default, or a static library with `--lib=static`. The library does not include the platform's host; the program it's linked into provides that.)")
+                    .value_parser(["shared", "static"])
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("shared")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_GLUE)
+                    .long(FLAG_GLUE)
+                    .help("Also generate glue for calling the library from this language, in a directory next to it")
+                    .value_parser(["rust", "zig"])
+                    .requires(FLAG_LIB)
                     .required(false),
             )
             .arg(
@@ -227,6 +462,23 @@ pub fn build_app() -> Command {
                     .value_parser([".tar", ".tar.gz", ".tar.br"])
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_DETERMINISTIC)
+                    .long(FLAG_DETERMINISTIC)
+                    .help("Make the output byte-for-byte reproducible, so building the same source twice gives the same result\n(Modules are compiled on a single thread, and debug info, which contains file paths, is left out.)")
+                    .conflicts_with_all([FLAG_MAX_THREADS, FLAG_JOBS, FLAG_PROFILING, FLAG_DEBUG])
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_MESSAGE_FORMAT)
+                    .long(FLAG_MESSAGE_FORMAT)
+                    .help("How to print progress, problems, and results\n(`json` prints one JSON object per line, for build tools and editors.)")
+                    .value_parser(["human", "json"])
+                    .default_value("human")
+                    .conflicts_with_all([FLAG_BUNDLE, FLAG_GLUE, FLAG_TIME, FLAG_VERBOSE])
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_NO_LINK)
                     .long(FLAG_NO_LINK)
@@ -247,14 +499,19 @@ pub fn build_app() -> Command {
             .arg(flag_main.clone())
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
             .arg(flag_opt_size.clone())
+            .arg(flag_opt_level.clone())
             .arg(flag_dev.clone())
             .arg(flag_emit_llvm_ir.clone())
             .arg(flag_profiling.clone())
+            .arg(flag_debug.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_sanitize.clone())
+            .arg(flag_atomic_refcount.clone())
             .arg(
                 Arg::new(FLAG_VERBOSE)
                     .long(FLAG_VERBOSE)
@@ -262,6 +519,13 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false)
             )
+            .arg(
+                Arg::new(FLAG_VALGRIND)
+                    .long(FLAG_VALGRIND)
+                    .help("Run the tests under valgrind, and fail if Roc code leaks memory or has other memory errors")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to test")
@@ -270,6 +534,34 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME)
             )
             .arg(args_for_app.clone().last(true))
+            .after_help(concatcp!("Exits with code ", exit_code::FAILURE, " if any tests failed, or ", exit_code::NO_EXPECTATIONS, " if there were no expects to run."))
+        )
+        .subcommand(Command::new(CMD_BENCH)
+            .about("Time all top-level `expect`s marked with a `# bench` comment, in a main module and any modules it imports")
+            .arg(flag_main.clone())
+            .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
+            .arg(
+                Arg::new(FLAG_WARMUP)
+                    .long(FLAG_WARMUP)
+                    .help("How many times to run each benchmark before timing it")
+                    .value_parser(value_parser!(u32))
+                    .default_value("3")
+            )
+            .arg(
+                Arg::new(FLAG_ITERATIONS)
+                    .long(FLAG_ITERATIONS)
+                    .help("How many times to run each benchmark while timing it")
+                    .value_parser(value_parser!(u32).range(1..))
+                    .default_value("10")
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file to benchmark")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME)
+            )
         )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
@@ -287,19 +579,34 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false)
             )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("A module to load into the repl, as if by running `:load` on it")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
         )
         .subcommand(Command::new(CMD_RUN)
             .about("Run a .roc file even if it has build errors")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
             .arg(flag_opt_size.clone())
+            .arg(flag_opt_level.clone())
             .arg(flag_dev.clone())
+            .arg(flag_backend.clone())
             .arg(flag_emit_llvm_ir.clone())
             .arg(flag_profiling.clone())
+            .arg(flag_debug.clone())
             .arg(flag_time.clone())
+            .arg(flag_verbose.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_sanitize.clone())
+            .arg(flag_atomic_refcount.clone())
+            .arg(flag_print_expects)
+            .arg(flag_watch.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -307,14 +614,21 @@ pub fn build_app() -> Command {
             .about("`check` a .roc file, and then run it if there were no errors")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
             .arg(flag_opt_size.clone())
+            .arg(flag_opt_level.clone())
             .arg(flag_dev.clone())
             .arg(flag_emit_llvm_ir.clone())
             .arg(flag_profiling.clone())
+            .arg(flag_debug.clone())
             .arg(flag_time.clone())
+            .arg(flag_verbose.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_sanitize.clone())
+            .arg(flag_atomic_refcount.clone())
+            .arg(flag_watch.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -329,17 +643,24 @@ pub fn build_app() -> Command {
             .arg(
                 Arg::new(FLAG_CHECK)
                     .long(FLAG_CHECK)
-                    .help("Checks that specified files are formatted\n(If formatting is needed, return a non-zero exit code.)")
+                    .help("Checks that specified files are formatted, printing a diff for each file that is not\n(If formatting is needed, return a non-zero exit code.)")
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
             .arg(
                 Arg::new(FLAG_STDIN)
                     .long(FLAG_STDIN)
-                    .help("Read file to format from stdin")
+                    .help("Read file to format from stdin\n(`roc format -` is a shorthand for `roc format --stdin --stdout`.)")
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_STDIN_FILENAME)
+                    .long(FLAG_STDIN_FILENAME)
+                    .help("The name of the file being formatted from stdin, to use in error messages")
+                    .value_parser(value_parser!(String))
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_STDOUT)
                     .long(FLAG_STDOUT)
@@ -347,7 +668,14 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
-            .after_help("If DIRECTORY_OR_FILES is omitted, the .roc files in the current working\ndirectory are formatted.")
+            .arg(
+                Arg::new(FLAG_SORT_HEADERS)
+                    .long(FLAG_SORT_HEADERS)
+                    .help("Alphabetize what module headers expose and import, and their packages, merging duplicates\n(This can also be turned on with `sort-headers = true` in the [format] section of roc.toml.)")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .after_help("If DIRECTORY_OR_FILES is omitted, the .roc files in the current working\ndirectory are formatted. If it's -, stdin is formatted to stdout.")
         )
         .subcommand(Command::new(CMD_VERSION)
             .about(concatcp!("Print the Roc compiler’s version, which is currently ", VERSION)))
@@ -355,7 +683,9 @@ pub fn build_app() -> Command {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_main.clone())
             .arg(flag_time.clone())
+            .arg(flag_verbose.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to check")
@@ -364,6 +694,55 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME),
             )
             )
+        .subcommand(Command::new(CMD_CACHE)
+            .about("Manage the cache of downloaded packages")
+            .after_help("The cache is in $ROC_CACHE_DIR if that's set, otherwise in $XDG_CACHE_HOME/roc,\notherwise in ~/.cache/roc (or %APPDATA%\\Roc on Windows).")
+            .subcommand_required(true)
+            .subcommand(Command::new(CMD_CACHE_CLEAN)
+                .about("Delete all downloaded packages")
+            )
+            .subcommand(Command::new(CMD_CACHE_DIR)
+                .about("Print the path of the cache directory")
+            )
+        )
+        .subcommand(Command::new(CMD_LSP)
+            .about("Start a language server, for editors to talk to over stdin and stdout")
+        )
+        .subcommand(Command::new(CMD_ANNOTATE)
+            .about("Print the inferred type annotation of each top-level definition that doesn't have one")
+            .arg(flag_main.clone())
+            .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
+            .arg(
+                Arg::new(FLAG_WRITE)
+                    .long(FLAG_WRITE)
+                    .help("Add the annotations to the .roc file instead of printing them")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file to annotate")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_LINT)
+            .about("Check the code for problems, and also for code that works but could be clearer")
+            .arg(flag_main.clone())
+            .arg(flag_verbose.clone())
+            .arg(flag_max_threads.clone())
+            .arg(flag_jobs.clone())
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file to lint")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+            .after_help("Besides everything `roc check` reports, this warns about names which aren't camelCase,\nand about `if` and `when` expressions which could be simpler. It only looks at the\nmodules in the same package as ROC_FILE.")
+        )
         .subcommand(
             Command::new(CMD_DOCS)
                 .about("Generate documentation for a Roc package")
@@ -374,9 +753,23 @@ pub fn build_app() -> Command {
                     .required(false)
                     .default_value(DEFAULT_GENERATED_DOCS_DIR),
                 )
+                .arg(Arg::new(FLAG_OPEN)
+                    .long(FLAG_OPEN)
+                    .help("Serve the generated docs locally, and open them in a web browser")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+                )
+                .arg(Arg::new(FLAG_THEME)
+                    .long(FLAG_THEME)
+                    .help("The color scheme for the docs to use.\n(auto follows the reader's system setting)")
+                    .value_parser(["auto", "light", "dark"])
+                    .required(false)
+                    .default_value("auto"),
+                )
                 .arg(Arg::new(ROC_FILE)
-                    .help("The package's main .roc file")
+                    .help("The package's main .roc file.\nIf more than one is given, each one's docs go in their own subdirectory of the output directory.")
                     .value_parser(value_parser!(PathBuf))
+                    .num_args(1..)
                     .required(false)
                     .default_value(DEFAULT_ROC_FILENAME),
                 )
@@ -384,25 +777,46 @@ pub fn build_app() -> Command {
         .subcommand(Command::new(CMD_GLUE)
             .about("Generate glue code between a platform's Roc API and its host language")
             .arg(&flag_dev)
+            .arg(flag_watch.clone().help("Regenerate the glue whenever a .roc file in the platform's directory changes"))
+            // TODO add a --derive option. A glue spec's `makeGlue` only receives the platform's
+            // types, so the glue platform needs a way to pass options through to it first.
+            .arg(
+                Arg::new(FLAG_LANG)
+                    .long(FLAG_LANG)
+                    .help("Generate glue for this language, using the glue spec that comes with Roc\n(This is the default, with rust, if there's no --spec)")
+                    .value_parser(["rust", "zig"])
+                    .conflicts_with(GLUE_SPEC)
+                    .required(false)
+            )
             .arg(
                 Arg::new(GLUE_SPEC)
+                    .long("spec")
                     .help("The specification for how to translate Roc types into output files.")
                     .value_parser(value_parser!(PathBuf))
-                    .required(true)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The platform's .roc file, whose exposed types should be translated.")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME)
             )
             .arg(
                 Arg::new(GLUE_DIR)
                     .help("The directory for the generated glue code.\nNote: The implementation can write to any file in this directory.")
                     .value_parser(value_parser!(PathBuf))
-                    .required(true)
+                    .required(false)
             )
             .arg(
-                Arg::new(ROC_FILE)
-                    .help("The .roc file whose exposed types should be translated.")
+                // Supports the original form of this command: `roc glue GLUE_SPEC GLUE_DIR ROC_FILE`
+                Arg::new(GLUE_LEGACY_ROC_FILE)
                     .value_parser(value_parser!(PathBuf))
+                    .conflicts_with_all([GLUE_SPEC, FLAG_LANG])
+                    .hide(true)
                     .required(false)
-                    .default_value(DEFAULT_ROC_FILENAME)
             )
+            .after_help(concatcp!("GLUE_DIR and the glue spec can also be set in the [glue] section of ", CONFIG_FILENAME, "."))
         )
         .subcommand(Command::new(CMD_GEN_STUB_LIB)
             .about("Generate a stubbed shared library that can be used for linking a platform binary.\nThe stubbed library has prototypes, but no function bodies.\n\nNote: This command will be removed in favor of just using `roc build` once all platforms support the surgical linker")
@@ -415,12 +829,22 @@ pub fn build_app() -> Command {
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
-                    .help("Choose a different target")
+                    .help(target_help.clone())
                     .default_value(Into::<&'static str>::into(Target::default()))
                     .value_parser(build_target_values_parser.clone())
                     .required(false),
             )
         )
+        .subcommand(Command::new(CMD_COMPLETIONS)
+            .about("Print a shell completion script for the roc command")
+            .arg(
+                Arg::new(SHELL)
+                    .help("The shell to generate completions for")
+                    .value_parser(value_parser!(clap_complete::Shell))
+                    .required(true)
+            )
+            .after_help("For example, to enable completions in bash, add this to ~/.bashrc:\n\n    source <(roc completions bash)")
+        )
         .subcommand(Command::new(CMD_PREPROCESS_HOST)
             .about("Runs the surgical linker preprocessor to generate `.rh` and `.rm` files.")
             .arg(
@@ -451,7 +875,7 @@ pub fn build_app() -> Command {
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
-                    .help("Choose a different target")
+                    .help(target_help.clone())
                     .default_value(Into::<&'static str>::into(Target::default()))
                     .value_parser(build_target_values_parser.clone())
                     .required(false),
@@ -459,14 +883,22 @@ pub fn build_app() -> Command {
         )
         .arg(flag_optimize)
         .arg(flag_max_threads)
+        .arg(flag_jobs)
         .arg(flag_opt_size)
+        .arg(flag_opt_level)
         .arg(flag_dev)
+        .arg(flag_backend)
         .arg(flag_emit_llvm_ir)
         .arg(flag_profiling)
+        .arg(flag_debug)
         .arg(flag_time)
+        .arg(flag_verbose)
         .arg(flag_linker)
         .arg(flag_prebuilt)
         .arg(flag_fuzz)
+        .arg(flag_sanitize)
+        .arg(flag_atomic_refcount)
+        .arg(flag_watch)
         .arg(roc_file_to_run)
         .arg(args_for_app.trailing_var_arg(true))
 }
@@ -478,12 +910,92 @@ pub enum BuildConfig {
     BuildAndRunIfNoErrors,
 }
 
-fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
-    match (
+/// The `--optimize`, `--opt-size`, and `--dev` flags, or the `opt-level` from roc.toml if none
+/// of them (and no `-O`) were given.
+fn opt_flags(matches: &ArgMatches, project_config: &ProjectConfig) -> (bool, bool, bool) {
+    let flags = (
         matches.get_flag(FLAG_OPTIMIZE),
         matches.get_flag(FLAG_OPT_SIZE),
         matches.get_flag(FLAG_DEV),
-    ) {
+    );
+
+    if matches.contains_id(FLAG_OPT_LEVEL) {
+        return flags;
+    }
+
+    match (flags, project_config.build.opt_level) {
+        ((false, false, false), Some(ConfigOptLevel::Optimize)) => (true, false, false),
+        ((false, false, false), Some(ConfigOptLevel::OptSize)) => (false, true, false),
+        ((false, false, false), Some(ConfigOptLevel::Dev)) => (false, false, true),
+        _ => flags,
+    }
+}
+
+/// The `--jobs` and `--max-threads` flags, or `max-threads` from roc.toml if neither was given.
+pub fn threading_from_flags(matches: &ArgMatches, project_config: &ProjectConfig) -> Threading {
+    match matches.get_one::<usize>(FLAG_JOBS) {
+        Some(0) => user_error!("cannot build with 0 jobs"),
+        Some(1) => return Threading::Single,
+        Some(n) => return Threading::Exactly(*n),
+        None => {}
+    }
+
+    match matches
+        .get_one::<usize>(FLAG_MAX_THREADS)
+        .or(project_config.build.max_threads.as_ref())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    }
+}
+
+/// The target named by the ROC_TARGET environment variable, if it's set.
+pub fn target_from_env() -> Option<Target> {
+    let target_name = env::var(ROC_TARGET_VAR)
+        .ok()
+        .filter(|name| !name.is_empty())?;
+
+    match Target::from_str(&target_name) {
+        Ok(target) => Some(target),
+        Err(_) => unsupported_target(
+            &format!("The {ROC_TARGET_VAR} environment variable"),
+            &target_name,
+        ),
+    }
+}
+
+/// Explains that `target_name`, which came from `source`, isn't a target Roc supports, then exits.
+pub fn unsupported_target(source: &str, target_name: &str) -> ! {
+    let target_names = Target::iter()
+        .map(Into::<&'static str>::into)
+        .collect::<Vec<_>>()
+        .join("\n    ");
+
+    eprintln!(
+        "\n{source} is set to `{target_name}`, which isn't a target Roc supports.\n\nThese are the supported targets:\n\n    {target_names}\n\nTarget triples such as x86_64-unknown-linux-gnu work too.\n"
+    );
+
+    process::exit(exit_code::IO_OR_USAGE_ERROR);
+}
+
+fn opt_level_from_flags(matches: &ArgMatches, project_config: &ProjectConfig) -> OptLevel {
+    let flags = opt_flags(matches, project_config);
+
+    if let Some(level) = matches.get_one::<String>(FLAG_OPT_LEVEL) {
+        return match (level.as_str(), flags) {
+            (_, (_, _, true)) => user_error!("build can be only one of `--dev` or `-O`"),
+            ("0", _) => OptLevel::Normal,
+            ("1", _) => OptLevel::Less,
+            ("2", _) => OptLevel::Moderate,
+            ("3", _) => OptLevel::Optimize,
+            ("size", _) => OptLevel::Size,
+            (other, _) => internal_error!("unexpected optimization level: {other}"),
+        };
+    }
+
+    match flags {
         (true, false, false) => OptLevel::Optimize,
         (false, true, false) => OptLevel::Size,
         (false, false, true) => OptLevel::Development,
@@ -497,6 +1009,11 @@ pub fn test(_matches: &ArgMatches, _target: Target) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
 }
 
+#[cfg(windows)]
+pub fn bench(_matches: &ArgMatches, _target: Target) -> io::Result<i32> {
+    todo!("running benchmarks does not work on windows right now")
+}
+
 #[cfg(not(windows))]
 struct ModuleTestResults {
     module_id: ModuleId,
@@ -513,16 +1030,11 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
 
     let start_time = Instant::now();
     let arena = Bump::new();
-    let opt_level = opt_level_from_flags(matches);
-
-    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
-        None => Threading::AllAvailable,
-        Some(0) => user_error!("cannot build with at most 0 threads"),
-        Some(1) => Threading::Single,
-        Some(n) => Threading::AtMost(*n),
-    };
-
     let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let project_config = ProjectConfig::for_roc_file(path);
+    let opt_level = opt_level_from_flags(matches, &project_config);
+
+    let threading = threading_from_flags(matches, &project_config);
 
     // Spawn the root task
     if !path.exists() {
@@ -542,7 +1054,7 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             _ => eprintln!("\nThis file was not found: {expected_file_path_string}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n"),
         }
 
-        process::exit(1);
+        process::exit(exit_code::IO_OR_USAGE_ERROR);
     }
 
     let arena = &arena;
@@ -559,6 +1071,9 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Test,
+        progress: None,
+        tabs: Tabs::from_env(),
+        numeric_defaults: NumericDefaults::from_env(),
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
@@ -577,7 +1092,7 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
         }
     };
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, None);
 
     let mut expectations = std::mem::take(&mut loaded.expectations);
 
@@ -659,7 +1174,7 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         // you actually have zero tests, but it can save you from
         // having a change to your CI script accidentally stop
         // running tests altogether!
-        Ok(2)
+        Ok(exit_code::NO_EXPECTATIONS)
     } else {
         if matches.get_flag(FLAG_VERBOSE) {
             println!("Compiled in {} ms.", compilation_duration.as_millis());
@@ -672,7 +1187,11 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             println!("{test_summary_str}");
         }
 
-        Ok((total_failed_count > 0) as i32)
+        if total_failed_count > 0 {
+            Ok(exit_code::FAILURE)
+        } else {
+            Ok(exit_code::SUCCESS)
+        }
     }
 }
 
@@ -712,6 +1231,91 @@ fn test_summary(failed_count: usize, passed_count: usize, tests_duration: Durati
     )
 }
 
+pub fn glue(matches: &ArgMatches) -> io::Result<i32> {
+    let first_arg = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let opt_output_path = matches.get_one::<PathBuf>(GLUE_DIR).cloned();
+
+    let (input_path, opt_spec_path, opt_lang) =
+        match matches.get_one::<PathBuf>(GLUE_LEGACY_ROC_FILE) {
+            // `roc glue GLUE_SPEC GLUE_DIR ROC_FILE`
+            Some(input_path) => (input_path.to_owned(), Some(first_arg.to_owned()), None),
+            None => (
+                first_arg.to_owned(),
+                matches.get_one::<PathBuf>(GLUE_SPEC).cloned(),
+                matches.get_one::<String>(FLAG_LANG),
+            ),
+        };
+
+    if matches.get_flag(FLAG_WATCH) {
+        return watch(&input_path);
+    }
+
+    let glue_config = ProjectConfig::for_roc_file(&input_path).glue;
+
+    let output_path = match opt_output_path.or(glue_config.dir) {
+        Some(output_path) => output_path,
+        None => {
+            eprintln!("What directory should `roc glue` write to? Specify it after the .roc file, or in the [glue] section of {CONFIG_FILENAME}.");
+
+            return Ok(exit_code::IO_OR_USAGE_ERROR);
+        }
+    };
+
+    // --lang takes priority over the spec in roc.toml, and the Rust spec is the default
+    let builtin_spec;
+    let spec_path = match (opt_lang, opt_spec_path.or(glue_config.spec)) {
+        (None, Some(spec_path)) => spec_path,
+        (opt_lang, _) => {
+            let lang = opt_lang.map_or("rust", String::as_str);
+            builtin_spec = roc_glue::builtin_spec(lang)?.unwrap();
+
+            builtin_spec.path().to_path_buf()
+        }
+    };
+
+    if !spec_path.is_file() {
+        eprintln!("This glue spec was not found: {}", spec_path.display());
+
+        return Ok(exit_code::IO_OR_USAGE_ERROR);
+    }
+
+    // have the backend supply `roc_alloc` and friends
+    let backend = match matches.get_flag(FLAG_DEV) {
+        true => CodeGenBackend::Assembly(AssemblyBackendMode::Test),
+        false => CodeGenBackend::Llvm(LlvmBackendMode::BinaryGlue),
+    };
+
+    if !output_path.exists() || output_path.is_dir() {
+        roc_glue::generate(&input_path, &output_path, &spec_path, backend)
+    } else {
+        eprintln!("`roc glue` must be given a directory to output into, because the glue might generate multiple files.");
+
+        Ok(exit_code::IO_OR_USAGE_ERROR)
+    }
+}
+
+/// Generates glue for the platform a library was built with, into a directory next to the
+/// library named after it, e.g. `app-glue/` for `app.so`.
+fn library_glue(lang: &str, platform_main_roc: &Path, lib_path: &Path) -> io::Result<i32> {
+    let spec = roc_glue::builtin_spec(lang)?.unwrap();
+
+    let lib_stem = lib_path.file_stem().unwrap_or_default().to_string_lossy();
+    let glue_dir = lib_path.with_file_name(format!("{lib_stem}-glue"));
+
+    let exit_code = roc_glue::generate(
+        platform_main_roc,
+        &glue_dir,
+        spec.path(),
+        CodeGenBackend::Llvm(LlvmBackendMode::BinaryGlue),
+    )?;
+
+    if exit_code == 0 {
+        println!("\nGenerated {lang} glue in:\n\n    {}", glue_dir.display());
+    }
+
+    Ok(exit_code)
+}
+
 /// Find the element of `options` with the smallest edit distance to
 /// `reference`. Returns a tuple containing the element and the distance, or
 /// `None` if the `options` `Vec` is empty.
@@ -735,6 +1339,12 @@ pub fn build(
     use BuildConfig::*;
 
     let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    let deterministic = matches
+        .try_get_one::<bool>(FLAG_DETERMINISTIC)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false);
     {
         // Spawn the root task
         if !path.exists() {
@@ -777,7 +1387,7 @@ pub fn build(
                 }
             }
 
-            process::exit(1);
+            process::exit(exit_code::IO_OR_USAGE_ERROR);
         }
 
         if config == BuildConfig::BuildOnly && matches.contains_id(FLAG_BUNDLE) {
@@ -805,7 +1415,7 @@ pub fn build(
 
             // Rather than building an executable or library, we're building
             // a tarball so this code can be distributed via HTTPS
-            let filename = roc_packaging::tarball::build(path, compression)?;
+            let filename = roc_packaging::tarball::build(path, compression, deterministic)?;
             let total_time_ms = start_time.elapsed().as_millis();
             let total_time = if total_time_ms > 1000 {
                 format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
@@ -828,15 +1438,40 @@ pub fn build(
     // so we don't want to spend time freeing these values
     let arena = ManuallyDrop::new(Bump::new());
 
+    let project_config = ProjectConfig::for_roc_file(path);
+
     let opt_level = if let BuildConfig::BuildAndRunIfNoErrors = config {
         OptLevel::Development
     } else {
-        opt_level_from_flags(matches)
+        opt_level_from_flags(matches, &project_config)
     };
 
     // Note: This allows using `--dev` with `--optimize`.
     // This means frontend optimizations and dev backend.
-    let code_gen_backend = if matches.get_flag(FLAG_DEV) {
+    let (_, _, use_dev_backend) = opt_flags(matches, &project_config);
+    let use_dev_backend = match matches.try_get_one::<String>(FLAG_BACKEND).ok().flatten() {
+        Some(backend) => match backend.as_str() {
+            "llvm" => false,
+            "wasm" if !matches!(target.architecture(), Architecture::Wasm32) => {
+                user_error!("The wasm backend can only build for `--target wasm32`.")
+            }
+            _ => true,
+        },
+        None => use_dev_backend,
+    };
+
+    // Only `roc build` and `roc run` have this flag.
+    let print_expects = matches
+        .try_get_one::<bool>(FLAG_PRINT_EXPECTS)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false);
+    if print_expects && use_dev_backend {
+        user_error!("Cannot use --print-expects while using a dev backend.");
+    }
+
+    let code_gen_backend = if use_dev_backend {
         if matches!(target.architecture(), Architecture::Wasm32) {
             CodeGenBackend::Wasm
         } else {
@@ -844,35 +1479,70 @@ pub fn build(
         }
     } else {
         let backend_mode = match opt_level {
+            // `roc dev` already reports failed `expect`s
             OptLevel::Development => LlvmBackendMode::BinaryDev,
-            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => LlvmBackendMode::Binary,
+            _ if print_expects => LlvmBackendMode::BinaryPrintExpects,
+            OptLevel::Normal
+            | OptLevel::Less
+            | OptLevel::Moderate
+            | OptLevel::Size
+            | OptLevel::Optimize => LlvmBackendMode::Binary,
         };
 
         CodeGenBackend::Llvm(backend_mode)
     };
 
-    let emit_llvm_ir = matches.get_flag(FLAG_EMIT_LLVM_IR);
-    if emit_llvm_ir && !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
-        user_error!("Cannot emit llvm ir while using a dev backend.");
+    let mut emit = EmitArtifacts {
+        llvm_ir: matches.get_flag(FLAG_EMIT_LLVM_IR),
+        ..Default::default()
+    };
+
+    // Only `roc build` has the --emit flag.
+    if let Ok(Some(names)) = matches.try_get_many::<String>(FLAG_EMIT) {
+        for name in names {
+            emit.enable(name);
+        }
     }
 
-    let emit_debug_info = matches.get_flag(FLAG_PROFILING)
-        || matches!(opt_level, OptLevel::Development | OptLevel::Normal);
+    if !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
+        if let Some(name) = emit.llvm_only().next() {
+            user_error!("Cannot emit {name} while using a dev backend.");
+        }
+    }
+
+    // Debug info embeds the paths of the source files, so it's left out of deterministic builds.
+    let emit_debug_info = !deterministic
+        && (matches.get_flag(FLAG_PROFILING)
+            || matches.get_flag(FLAG_DEBUG)
+            || matches!(
+                opt_level,
+                OptLevel::Development | OptLevel::Normal | OptLevel::Less
+            ));
     let emit_timings = matches.get_flag(FLAG_TIME);
 
-    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
-        None => Threading::AllAvailable,
-        Some(0) => user_error!("cannot build with at most 0 threads"),
-        Some(1) => Threading::Single,
-        Some(n) => Threading::AtMost(*n),
+    // When modules are loaded in parallel, the order they finish in decides things like which
+    // ids they get and the order procedures are generated in, and those show up in the output.
+    let threading = if deterministic {
+        Threading::Single
+    } else {
+        threading_from_flags(matches, &project_config)
     };
 
     let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
 
+    // The surgical linker doesn't add the app's symbols or debug info to the executable, so
+    // profilers and debuggers wouldn't be able to name Roc functions.
     let linking_strategy = if wasm_dev_backend {
         LinkingStrategy::Additive
     } else if !roc_linker::supported(link_type, target)
-        || matches.get_one::<String>(FLAG_LINKER).map(|s| s.as_str()) == Some("legacy")
+        || matches.get_flag(FLAG_PROFILING)
+        || matches.get_flag(FLAG_DEBUG)
+        || matches.contains_id(FLAG_SANITIZE)
+        || matches
+            .get_one::<String>(FLAG_LINKER)
+            .map(|s| s.as_str())
+            .or(project_config.build.linker.map(|linker| linker.as_str()))
+            == Some("legacy")
     {
         LinkingStrategy::Legacy
     } else {
@@ -896,6 +1566,29 @@ pub fn build(
         user_error!("Cannot instrument binary for fuzzing while using a dev backend.");
     }
 
+    let sanitizer = matches
+        .get_one::<String>(FLAG_SANITIZE)
+        .and_then(|name| Sanitizer::from_name(name));
+    if let Some(sanitizer) = sanitizer {
+        let name = sanitizer.name();
+
+        if !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
+            user_error!("Cannot use --sanitize {name} while using a dev backend.");
+        }
+
+        if !matches!(
+            target.operating_system(),
+            OperatingSystem::Linux | OperatingSystem::Mac
+        ) {
+            user_error!("--sanitize {name} is only supported when building for Linux or macOS.");
+        }
+    }
+
+    let atomic_refcount = matches.get_flag(FLAG_ATOMIC_REFCOUNT);
+    if atomic_refcount && !matches!(code_gen_backend, CodeGenBackend::Llvm(_)) {
+        user_error!("Cannot use --atomic-refcount while using a dev backend.");
+    }
+
     let wasm_dev_stack_bytes: Option<u32> = matches
         .try_get_one::<u32>(FLAG_WASM_STACK_SIZE_KB)
         .ok()
@@ -911,11 +1604,29 @@ pub fn build(
         backend: code_gen_backend,
         opt_level,
         emit_debug_info,
-        emit_llvm_ir,
+        emit,
         fuzz,
+        profiling: matches.get_flag(FLAG_PROFILING),
+        sanitizer,
+        atomic_refcount,
     };
 
-    let load_config = standard_load_config(target, build_ordering, threading);
+    let json_messages = matches
+        .try_get_one::<String>(FLAG_MESSAGE_FORMAT)
+        .ok()
+        .flatten()
+        .map_or(false, |format| format == "json");
+    let start_time = Instant::now();
+
+    let mut load_config = standard_load_config(target, build_ordering, threading);
+
+    load_config.progress = if json_messages {
+        messages::build_started(path);
+
+        Some(messages::json_reporter())
+    } else {
+        progress_reporter(matches.get_flag(FLAG_VERBOSE))
+    };
 
     let res_binary_path = build_file(
         &arena,
@@ -932,12 +1643,24 @@ pub fn build(
         out_path,
     );
 
+    clear_progress_bar();
+
+    if json_messages {
+        return Ok(messages::build_finished(
+            res_binary_path,
+            path,
+            link_type,
+            start_time,
+        ));
+    }
+
     match res_binary_path {
         Ok(BuiltFile {
             binary_path,
             problems,
             total_time,
             expect_metadata,
+            platform_main_roc,
         }) => {
             match config {
                 BuildOnly => {
@@ -955,6 +1678,14 @@ pub fn build(
                     problems.print_error_warning_count(total_time);
                     println!(" while successfully building:\n\n    {generated_filename}");
 
+                    if let Some(lang) = matches.get_one::<String>(FLAG_GLUE) {
+                        let glue_exit_code = library_glue(lang, &platform_main_roc, &binary_path)?;
+
+                        if glue_exit_code != 0 {
+                            return Ok(glue_exit_code);
+                        }
+                    }
+
                     // Return a nonzero exit code if there were problems
                     Ok(problems.exit_code())
                 }
@@ -969,6 +1700,15 @@ pub fn build(
                         // Return a nonzero exit code due to fatal problem
                         return Ok(problems.exit_code());
                     }
+                    if project_config.warnings.deny && problems.warnings > 0 {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nCannot run program because {CONFIG_FILENAME} denies warnings…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+
+                        return Ok(problems.exit_code());
+                    }
                     if problems.errors > 0 || problems.warnings > 0 {
                         problems.print_error_warning_count(total_time);
                         println!(
@@ -999,6 +1739,15 @@ pub fn build(
                         // Return a nonzero exit code due to fatal problem
                         return Ok(problems.exit_code());
                     }
+                    if project_config.warnings.deny && problems.warnings > 0 {
+                        problems.print_error_warning_count(total_time);
+                        println!(
+                            ".\n\nCannot run program because {CONFIG_FILENAME} denies warnings…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+
+                        return Ok(problems.exit_code());
+                    }
                     debug_assert_eq!(
                         problems.errors, 0,
                         "if there are non-fatal errors, they should have been returned as an error variant"
@@ -1147,6 +1896,9 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
     let executable = roc_run_executable_file_path(binary_bytes)?;
     let (argv_cstrings, envp_cstrings) = make_argv_envp(arena, &executable, args);
 
+    // The app replaces this process, so this is the last chance to clean up after --no-cache.
+    roc_packaging::cache::remove_temp_cache_dir();
+
     let argv: bumpalo::collections::Vec<*const c_char> = argv_cstrings
         .iter()
         .map(|s| s.as_ptr())
@@ -1161,7 +1913,11 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
 
     match opt_level {
         OptLevel::Development => roc_dev_native(arena, executable, argv, envp, expect_metadata),
-        OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => unsafe {
+        OptLevel::Normal
+        | OptLevel::Less
+        | OptLevel::Moderate
+        | OptLevel::Size
+        | OptLevel::Optimize => unsafe {
             roc_run_native_fast(executable, &argv, &envp);
         },
     }
@@ -1295,8 +2051,8 @@ fn roc_dev_native(
                         break if libc::WIFEXITED(status) {
                             libc::WEXITSTATUS(status)
                         } else {
-                            // we don't have an exit code, but something went wrong if we're in this else
-                            1
+                            // The program didn't exit on its own, e.g. it segfaulted.
+                            exit_code::PROGRAM_CRASHED
                         };
                     }
                     ChildProcessMsg::Expect => {
@@ -1413,6 +2169,9 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
         // TODO forward the arguments
         let (argv_cstrings, envp_cstrings) = make_argv_envp(&arena, &executable, args);
 
+        // The app replaces this process, so this is the last chance to clean up after --no-cache.
+        roc_packaging::cache::remove_temp_cache_dir();
+
         let argv: bumpalo::collections::Vec<*const c_char> = argv_cstrings
             .iter()
             .map(|s| s.as_ptr())
@@ -1430,7 +2189,11 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
                 // roc_run_native_debug(executable, &argv, &envp, expectations, interns)
                 internal_error!("running `expect`s does not currently work on windows")
             }
-            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => {
+            OptLevel::Normal
+            | OptLevel::Less
+            | OptLevel::Moderate
+            | OptLevel::Size
+            | OptLevel::Optimize => {
                 roc_run_native_fast(executable, &argv, &envp);
             }
         }
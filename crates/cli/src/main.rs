@@ -1,24 +1,31 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
 use bumpalo::Bump;
+use clap::parser::ValueSource;
+use clap::ArgMatches;
 use roc_build::link::LinkType;
-use roc_build::program::{check_file, CodeGenBackend};
+use roc_build::program::{check_file, lint_file, DEFAULT_ROC_FILENAME};
 use roc_cli::{
-    build_app, format_files, format_src, test, BuildConfig, FormatMode, CMD_BUILD, CMD_CHECK,
-    CMD_DEV, CMD_DOCS, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_PREPROCESS_HOST, CMD_REPL,
-    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB, FLAG_MAIN,
-    FLAG_NO_COLOR, FLAG_NO_HEADER, FLAG_NO_LINK, FLAG_OUTPUT, FLAG_PP_DYLIB, FLAG_PP_HOST,
-    FLAG_PP_PLATFORM, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_TIME, GLUE_DIR, GLUE_SPEC,
-    ROC_FILE, VERSION,
+    annotate, bench, build_app, clear_progress_bar, format_files, format_stdin, glue,
+    progress_reporter, serve_docs, target_from_env, test, test_with_valgrind, threading_from_flags,
+    unsupported_target, watch, BuildConfig, FormatMode, FormatOptions, ProjectConfig, CMD_ANNOTATE,
+    CMD_BENCH, CMD_BUILD, CMD_CACHE, CMD_CACHE_CLEAN, CMD_CACHE_DIR, CMD_CHECK, CMD_COMPLETIONS,
+    CMD_DEV, CMD_DOCS, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_LINT, CMD_LSP,
+    CMD_PREPROCESS_HOST, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION, CONFIG_FILENAME,
+    DIRECTORY_OR_FILES, FLAG_BACKEND, FLAG_CHECK, FLAG_DENY_DEFAULTS, FLAG_EXPAND_ALIASES,
+    FLAG_EXPLAIN_DEFAULTS, FLAG_LIB, FLAG_MAIN, FLAG_NO_CACHE, FLAG_NO_COLOR, FLAG_NO_HEADER,
+    FLAG_NO_LINK, FLAG_OPEN, FLAG_OUTPUT, FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM,
+    FLAG_SORT_HEADERS, FLAG_STDIN, FLAG_STDIN_FILENAME, FLAG_STDOUT, FLAG_TAB_WIDTH, FLAG_TARGET,
+    FLAG_THEME, FLAG_TIME, FLAG_VALGRIND, FLAG_VERBOSE, FLAG_VERBOSE_TYPES, FLAG_WATCH, ROC_FILE,
+    SHELL, VERSION,
 };
-use roc_docs::generate_docs_html;
-use roc_error_macros::user_error;
-use roc_gen_dev::AssemblyBackendMode;
-use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{FunctionKind, LoadingProblem, Threading};
+use roc_docs::{generate_docs_html, DocsTheme};
+use roc_error_macros::{exit_code, user_error};
+use roc_load::{FunctionKind, LoadingProblem, NumericDefaults, Tabs};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::error::r#type::{EXPAND_ALIASES_VAR, VERBOSE_TYPES_VAR};
 use roc_target::Target;
 use std::fs::{self, FileType};
-use std::io::{self, Read, Write};
+use std::io;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use target_lexicon::Triple;
@@ -38,31 +45,74 @@ fn main() -> io::Result<()> {
         .get_subcommands()
         .map(|c| c.get_name().to_owned())
         .collect();
-    let matches = app.get_matches();
+    let matches = app.try_get_matches().unwrap_or_else(|err| {
+        // clap exits with 2 for usage errors, but for us 2 means there were warnings.
+        if err.use_stderr() {
+            let _ = err.print();
+
+            std::process::exit(exit_code::IO_OR_USAGE_ERROR);
+        }
+
+        err.exit()
+    });
+
+    // With --no-cache, packages get downloaded into a temporary cache dir instead.
+    if matches.get_flag(FLAG_NO_CACHE) {
+        cache::use_temp_cache_dir()?;
+    }
+
+    // Tabs are handled by whatever parses .roc files, which looks up ROC_TAB_WIDTH; setting it
+    // here means --tab-width applies to every command.
+    if let Some(width) = matches.get_one::<u8>(FLAG_TAB_WIDTH) {
+        std::env::set_var(Tabs::WIDTH_VAR, width.to_string());
+    }
+
+    // Likewise, reports look up ROC_VERBOSE_TYPES and ROC_EXPAND_ALIASES when printing types.
+    if matches.get_flag(FLAG_VERBOSE_TYPES) {
+        std::env::set_var(VERBOSE_TYPES_VAR, "1");
+    }
+
+    if matches.get_flag(FLAG_EXPAND_ALIASES) {
+        std::env::set_var(EXPAND_ALIASES_VAR, "1");
+    }
+
+    // And loading a program looks up ROC_NUMERIC_DEFAULTS.
+    if matches.get_flag(FLAG_EXPLAIN_DEFAULTS) {
+        std::env::set_var(NumericDefaults::VAR, "explain");
+    } else if matches.get_flag(FLAG_DENY_DEFAULTS) {
+        std::env::set_var(NumericDefaults::VAR, "deny");
+    }
+
+    // Check ROC_TARGET up front, so a typo in it gets reported no matter which command is run.
+    let env_target = target_from_env();
 
     let exit_code = match matches.subcommand() {
         None => {
-            if matches.contains_id(ROC_FILE) {
+            if matches.get_flag(FLAG_WATCH) {
+                watch(matches.get_one::<PathBuf>(ROC_FILE).unwrap())
+            } else if matches.contains_id(ROC_FILE) {
                 build(
                     &matches,
                     &subcommands,
                     BuildConfig::BuildAndRunIfNoErrors,
-                    Triple::host().into(),
+                    run_target(&matches),
                     None,
                     RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
                     LinkType::Executable,
                 )
             } else {
-                Ok(1)
+                Ok(exit_code::IO_OR_USAGE_ERROR)
             }
         }
         Some((CMD_RUN, matches)) => {
-            if matches.contains_id(ROC_FILE) {
+            if matches.get_flag(FLAG_WATCH) {
+                watch(matches.get_one::<PathBuf>(ROC_FILE).unwrap())
+            } else if matches.contains_id(ROC_FILE) {
                 build(
                     matches,
                     &subcommands,
                     BuildConfig::BuildAndRun,
-                    Triple::host().into(),
+                    run_target(matches),
                     None,
                     RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
                     LinkType::Executable,
@@ -70,20 +120,25 @@ fn main() -> io::Result<()> {
             } else {
                 eprintln!("What .roc file do you want to run? Specify it at the end of the `roc run` command.");
 
-                Ok(1)
+                Ok(exit_code::IO_OR_USAGE_ERROR)
             }
         }
         Some((CMD_TEST, matches)) => {
-            if matches.contains_id(ROC_FILE) {
+            if matches.get_flag(FLAG_VALGRIND) {
+                test_with_valgrind(matches)
+            } else if matches.contains_id(ROC_FILE) {
                 test(matches, Triple::host().into())
             } else {
                 eprintln!("What .roc file do you want to test? Specify it at the end of the `roc test` command.");
 
-                Ok(1)
+                Ok(exit_code::IO_OR_USAGE_ERROR)
             }
         }
+        Some((CMD_BENCH, matches)) => bench(matches, Triple::host().into()),
         Some((CMD_DEV, matches)) => {
-            if matches.contains_id(ROC_FILE) {
+            if matches.get_flag(FLAG_WATCH) {
+                watch(matches.get_one::<PathBuf>(ROC_FILE).unwrap())
+            } else if matches.contains_id(ROC_FILE) {
                 build(
                     matches,
                     &subcommands,
@@ -96,34 +151,13 @@ fn main() -> io::Result<()> {
             } else {
                 eprintln!("What .roc file do you want to build? Specify it at the end of the `roc run` command.");
 
-                Ok(1)
-            }
-        }
-        Some((CMD_GLUE, matches)) => {
-            let input_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
-            let output_path = matches.get_one::<PathBuf>(GLUE_DIR).unwrap();
-            let spec_path = matches.get_one::<PathBuf>(GLUE_SPEC).unwrap();
-
-            // have the backend supply `roc_alloc` and friends
-            let backend = match matches.get_flag(FLAG_DEV) {
-                true => CodeGenBackend::Assembly(AssemblyBackendMode::Test),
-                false => CodeGenBackend::Llvm(LlvmBackendMode::BinaryGlue),
-            };
-
-            if !output_path.exists() || output_path.is_dir() {
-                roc_glue::generate(input_path, output_path, spec_path, backend)
-            } else {
-                eprintln!("`roc glue` must be given a directory to output into, because the glue might generate multiple files.");
-
-                Ok(1)
+                Ok(exit_code::IO_OR_USAGE_ERROR)
             }
         }
+        Some((CMD_GLUE, matches)) => glue(matches),
         Some((CMD_GEN_STUB_LIB, matches)) => {
             let input_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
-            let target = matches
-                .get_one::<String>(FLAG_TARGET)
-                .and_then(|s| Target::from_str(s).ok())
-                .unwrap_or_default();
+            let target = target_flag_or(matches, || env_target.unwrap_or_default());
             let function_kind = FunctionKind::from_env();
             roc_linker::generate_stub_lib(
                 input_path,
@@ -160,10 +194,7 @@ fn main() -> io::Result<()> {
                     dylib_path.display()
                 ));
             }
-            let target = matches
-                .get_one::<String>(FLAG_TARGET)
-                .and_then(|s| Target::from_str(s).ok())
-                .unwrap_or_default();
+            let target = target_flag_or(matches, || env_target.unwrap_or_default());
 
             let verbose_and_time = matches.get_one::<bool>(roc_cli::FLAG_VERBOSE).unwrap();
 
@@ -179,15 +210,33 @@ fn main() -> io::Result<()> {
             Ok(0)
         }
         Some((CMD_BUILD, matches)) => {
-            let target = matches
-                .get_one::<String>(FLAG_TARGET)
-                .and_then(|s| Target::from_str(s).ok())
-                .unwrap_or_default();
-            let link_type = match (matches.get_flag(FLAG_LIB), matches.get_flag(FLAG_NO_LINK)) {
-                (true, false) => LinkType::Dylib,
-                (true, true) => user_error!("build can only be one of `--lib` or `--no-link`"),
-                (false, true) => LinkType::None,
-                (false, false) => LinkType::Executable,
+            let project_config =
+                ProjectConfig::for_roc_file(matches.get_one::<PathBuf>(ROC_FILE).unwrap());
+            let wasm_backend = matches
+                .get_one::<String>(FLAG_BACKEND)
+                .is_some_and(|backend| backend == "wasm");
+            let target = target_flag_or(matches, || {
+                match (env_target, &project_config.build.target) {
+                    (Some(target), _) => target,
+                    (None, Some(target_name)) => {
+                        Target::from_str(target_name).unwrap_or_else(|_| {
+                            unsupported_target(
+                                &format!("The build target in {CONFIG_FILENAME}"),
+                                target_name,
+                            )
+                        })
+                    }
+                    (None, None) if wasm_backend => Target::Wasm32,
+                    (None, None) => Target::default(),
+                }
+            });
+            let lib_kind = matches.get_one::<String>(FLAG_LIB).map(String::as_str);
+            let link_type = match (lib_kind, matches.get_flag(FLAG_NO_LINK)) {
+                (Some("static"), false) => LinkType::StaticLib,
+                (Some(_), false) => LinkType::Dylib,
+                (Some(_), true) => user_error!("build can only be one of `--lib` or `--no-link`"),
+                (None, true) => LinkType::None,
+                (None, false) => LinkType::Executable,
             };
             let out_path = matches
                 .get_one::<OsString>(FLAG_OUTPUT)
@@ -208,23 +257,24 @@ fn main() -> io::Result<()> {
 
             let emit_timings = matches.get_flag(FLAG_TIME);
             let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
-            let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
-                None => Threading::AllAvailable,
-                Some(0) => user_error!("cannot build with at most 0 threads"),
-                Some(1) => Threading::Single,
-                Some(n) => Threading::AtMost(*n),
-            };
+            let project_config = ProjectConfig::for_roc_file(roc_file_path);
+            let threading = threading_from_flags(matches, &project_config);
 
             let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
 
-            match check_file(
+            let result = check_file(
                 &arena,
                 roc_file_path.to_owned(),
                 opt_main_path.cloned(),
                 emit_timings,
                 RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
                 threading,
-            ) {
+                progress_reporter(matches.get_flag(FLAG_VERBOSE)),
+            );
+
+            clear_progress_bar();
+
+            match result {
                 Ok((problems, total_time)) => {
                     problems.print_error_warning_count(total_time);
                     Ok(problems.exit_code())
@@ -233,30 +283,118 @@ fn main() -> io::Result<()> {
                 Err(LoadingProblem::FormattedReport(report)) => {
                     print!("{report}");
 
-                    Ok(1)
+                    Ok(exit_code::PARSE_OR_CANONICALIZE_ERRORS)
                 }
                 Err(other) => {
                     panic!("build_file failed with error:\n{other:?}");
                 }
             }
         }
+        Some((CMD_ANNOTATE, matches)) => annotate(matches),
+        Some((CMD_CACHE, matches)) => match matches.subcommand() {
+            Some((CMD_CACHE_CLEAN, _)) => {
+                match cache::clean()? {
+                    Some(dir) => println!("Deleted the downloaded packages in {}", dir.display()),
+                    None => println!("There were no downloaded packages to delete."),
+                }
+
+                Ok(0)
+            }
+            Some((CMD_CACHE_DIR, _)) => {
+                println!("{}", cache::roc_cache_dir().display());
+
+                Ok(0)
+            }
+            _ => unreachable!(),
+        },
+        Some((CMD_LSP, _)) => {
+            roc_language_server::start();
+
+            Ok(0)
+        }
+        Some((CMD_LINT, matches)) => {
+            let arena = Bump::new();
+
+            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let project_config = ProjectConfig::for_roc_file(roc_file_path);
+            let threading = threading_from_flags(matches, &project_config);
+
+            let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
+
+            let result = lint_file(
+                &arena,
+                roc_file_path.to_owned(),
+                opt_main_path.cloned(),
+                RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+                threading,
+                progress_reporter(matches.get_flag(FLAG_VERBOSE)),
+            );
+
+            clear_progress_bar();
+
+            match result {
+                Ok((problems, total_time)) => {
+                    problems.print_error_warning_count(total_time);
+                    Ok(problems.exit_code())
+                }
+
+                Err(LoadingProblem::FormattedReport(report)) => {
+                    print!("{report}");
+
+                    Ok(exit_code::PARSE_OR_CANONICALIZE_ERRORS)
+                }
+                Err(other) => {
+                    panic!("lint_file failed with error:\n{other:?}");
+                }
+            }
+        }
         Some((CMD_REPL, matches)) => {
             let has_color = !matches.get_one::<bool>(FLAG_NO_COLOR).unwrap();
             let has_header = !matches.get_one::<bool>(FLAG_NO_HEADER).unwrap();
 
-            Ok(roc_repl_cli::main(has_color, has_header))
+            let opt_load_path = matches.get_one::<PathBuf>(ROC_FILE);
+
+            Ok(roc_repl_cli::main(
+                has_color,
+                has_header,
+                opt_load_path.map(PathBuf::as_path),
+            ))
         }
         Some((CMD_DOCS, matches)) => {
-            let root_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let root_paths: Vec<PathBuf> = matches
+                .get_many::<PathBuf>(ROC_FILE)
+                .unwrap()
+                .cloned()
+                .collect();
             let out_dir = matches.get_one::<OsString>(FLAG_OUTPUT).unwrap();
+            let theme = match matches.get_one::<String>(FLAG_THEME).map(String::as_str) {
+                Some("light") => DocsTheme::Light,
+                Some("dark") => DocsTheme::Dark,
+                _ => DocsTheme::Auto,
+            };
 
-            generate_docs_html(root_path.to_owned(), out_dir.as_ref());
+            generate_docs_html(&root_paths, out_dir.as_ref(), theme);
 
-            Ok(0)
+            if matches.get_flag(FLAG_OPEN) {
+                serve_docs(out_dir.as_ref())
+            } else {
+                Ok(0)
+            }
         }
         Some((CMD_FORMAT, matches)) => {
-            let from_stdin = matches.get_flag(FLAG_STDIN);
-            let to_stdout = matches.get_flag(FLAG_STDOUT);
+            // `roc format -` formats stdin to stdout
+            let dash = matches
+                .get_many::<OsString>(DIRECTORY_OR_FILES)
+                .map_or(false, |mut values| values.any(|value| value == "-"));
+
+            if dash && matches.get_many::<OsString>(DIRECTORY_OR_FILES).unwrap().len() > 1 {
+                eprintln!("`roc format -` formats stdin, so it can't be given any other files or directories.");
+                std::process::exit(exit_code::IO_OR_USAGE_ERROR);
+            }
+
+            let from_stdin = dash || matches.get_flag(FLAG_STDIN);
+            let to_stdout =
+                matches.get_flag(FLAG_STDOUT) || (dash && !matches.get_flag(FLAG_CHECK));
             let format_mode = if to_stdout {
                 FormatMode::WriteToStdout
             } else {
@@ -268,7 +406,7 @@ fn main() -> io::Result<()> {
 
             if from_stdin && matches!(format_mode, FormatMode::WriteToFile) {
                 eprintln!("When using the --stdin flag, either the --check or the --stdout flag must also be specified. (Otherwise, it's unclear what filename to write to!)");
-                std::process::exit(1);
+                std::process::exit(exit_code::IO_OR_USAGE_ERROR);
             }
 
             let roc_files = {
@@ -277,6 +415,7 @@ fn main() -> io::Result<()> {
                 let mut values: Vec<OsString> = Vec::new();
 
                 match matches.get_many::<OsString>(DIRECTORY_OR_FILES) {
+                    Some(_) if dash => {}
                     Some(os_values) => {
                         for os_string in os_values {
                             values.push(os_string.to_owned());
@@ -306,54 +445,23 @@ fn main() -> io::Result<()> {
                 roc_files
             };
 
+            let format_options = FormatOptions {
+                sort_headers: matches.get_flag(FLAG_SORT_HEADERS),
+                ..FormatOptions::default()
+            };
+
             let format_exit_code = if from_stdin {
-                let mut buf = Vec::new();
-                let arena = Bump::new();
-
-                io::stdin().read_to_end(&mut buf)?;
-
-                let src = std::str::from_utf8(&buf).unwrap_or_else(|err| {
-                    eprintln!("Stdin contained invalid UTF-8 bytes: {err:?}");
-                    std::process::exit(1);
-                });
-
-                match format_src(&arena, src) {
-                    Ok(formatted_src) => {
-                        match format_mode {
-                            FormatMode::CheckOnly => {
-                                if src == formatted_src {
-                                    eprintln!("One or more files need to be reformatted.");
-                                    1
-                                } else {
-                                    0
-                                }
-                            }
-                            FormatMode::WriteToStdout => {
-                                std::io::stdout()
-                                    .lock()
-                                    .write_all(formatted_src.as_bytes())
-                                    .unwrap();
-
-                                0
-                            }
-                            FormatMode::WriteToFile => {
-                                // We would have errored out already if you specified --stdin
-                                // without either --stdout or --check specified as well.
-                                unreachable!()
-                            }
-                        }
-                    }
-                    Err(problem) => {
-                        eprintln!("`roc format` failed: {problem:?}");
-                        1
-                    }
-                }
+                let stdin_filename = matches
+                    .get_one::<String>(FLAG_STDIN_FILENAME)
+                    .map_or("<stdin>", String::as_str);
+
+                format_stdin(format_mode, stdin_filename, format_options)?
             } else {
-                match format_files(roc_files, format_mode) {
+                match format_files(roc_files, format_mode, format_options) {
                     Ok(()) => 0,
                     Err(message) => {
                         eprintln!("{message}");
-                        1
+                        exit_code::FAILURE
                     }
                 }
             };
@@ -364,8 +472,23 @@ fn main() -> io::Result<()> {
             println!("roc {}", VERSION);
             Ok(0)
         }
+        Some((CMD_COMPLETIONS, matches)) => {
+            let shell = *matches.get_one::<clap_complete::Shell>(SHELL).unwrap();
+
+            clap_complete::generate(shell, &mut build_app(), "roc", &mut io::stdout());
+
+            Ok(0)
+        }
         _ => unreachable!(),
-    }?;
+    }
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+
+        exit_code::IO_OR_USAGE_ERROR
+    });
+
+    // process::exit doesn't run destructors, so delete the temporary cache dir first.
+    cache::remove_temp_cache_dir();
 
     std::process::exit(exit_code);
 }
@@ -390,6 +513,25 @@ fn read_all_roc_files(
     Ok(())
 }
 
+/// The target for `roc run`, and for running a .roc file without a subcommand: the current
+/// machine, unless `--backend wasm` asks for a wasm module (which is run in an interpreter).
+/// The target given with `--target`, or the fallback if `--target` wasn't given.
+fn target_flag_or(matches: &ArgMatches, fallback: impl FnOnce() -> Target) -> Target {
+    match matches.value_source(FLAG_TARGET) {
+        Some(ValueSource::CommandLine) => {
+            Target::from_str(matches.get_one::<String>(FLAG_TARGET).unwrap()).unwrap()
+        }
+        _ => fallback(),
+    }
+}
+
+fn run_target(matches: &ArgMatches) -> Target {
+    match matches.get_one::<String>(FLAG_BACKEND) {
+        Some(backend) if backend == "wasm" => Target::Wasm32,
+        _ => Triple::host().into(),
+    }
+}
+
 fn roc_files_recursive<P: AsRef<Path>>(
     path: P,
     file_type: FileType,
@@ -0,0 +1,218 @@
+//! Implements `--message-format json` for `roc build`, which prints what happens during the build
+//! as newline-delimited JSON on stdout (like cargo's `--message-format json`), so build tools and
+//! editors can follow along without parsing output meant for people.
+//!
+//! Each message is an object whose `"reason"` says what kind of message it is:
+//!
+//! - `build-started`: `file`
+//! - `module-phase-finished`: `module`, `phase`, `duration_ms`
+//! - `code-gen-finished` and `link-finished`: `duration_ms`
+//! - `diagnostic`: `severity` ("warning", "error", or "fatal"), `file`, `title`, `region`, and
+//!   `rendered`, which is the whole report as plain text. `region` is `{"start": {"line", "column"},
+//!   "end": {"line", "column"}}`, counting from 1 like editors do and ending just past the last
+//!   character, or null if the problem isn't about a particular part of the file.
+//! - `artifact`: `kind` ("executable", "shared-library", "static-library", or "object"), `path`
+//! - `build-finished`: `success`, `errors`, `warnings`, `duration_ms`
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use roc_build::link::LinkType;
+use roc_build::program::{report_problems_typechecked, BuildFileError, BuiltFile};
+use roc_error_macros::exit_code;
+use roc_load::progress::{ProgressEvent, ProgressReporter};
+use roc_load::LoadingProblem;
+use roc_problem::Severity;
+use roc_region::all::{LineColumn, LineColumnRegion};
+use roc_reporting::cli::{Diagnostic, Problems};
+use roc_reporting::report::strip_colors;
+use serde_json::{json, Value};
+
+pub fn json_reporter() -> ProgressReporter {
+    ProgressReporter::with_diagnostics(|event| {
+        if let Some(message) = progress_message(event) {
+            emit(message);
+        }
+    })
+}
+
+pub fn build_started(roc_file_path: &Path) {
+    emit(json!({
+        "reason": "build-started",
+        "file": roc_file_path.to_string_lossy(),
+    }));
+}
+
+/// Reports how the build went, and returns the exit code `roc build` should exit with.
+pub fn build_finished(
+    result: Result<BuiltFile<'_>, BuildFileError<'_>>,
+    roc_file_path: &Path,
+    link_type: LinkType,
+    start_time: Instant,
+) -> i32 {
+    let (problems, code) = match result {
+        Ok(BuiltFile {
+            binary_path,
+            problems,
+            ..
+        }) => {
+            emit(json!({
+                "reason": "artifact",
+                "kind": artifact_kind(link_type),
+                "path": binary_path.to_string_lossy(),
+            }));
+
+            (problems, problems.exit_code())
+        }
+        Err(BuildFileError::ErrorModule { mut module, .. }) => {
+            let problems = report_problems_typechecked(&mut module, Some(&json_reporter()));
+
+            (problems, problems.exit_code())
+        }
+        Err(BuildFileError::LoadingProblem(problem)) => {
+            let (rendered, code) = match problem {
+                LoadingProblem::FormattedReport(report) => (
+                    strip_colors(&report),
+                    exit_code::PARSE_OR_CANONICALIZE_ERRORS,
+                ),
+                _ => (
+                    format!("Failed with error: {problem:?}"),
+                    exit_code::INTERNAL_COMPILER_ERROR,
+                ),
+            };
+
+            emit(diagnostic_message(&Diagnostic {
+                severity: Severity::Fatal,
+                path: roc_file_path.to_path_buf(),
+                title: String::new(),
+                region: None,
+                rendered,
+            }));
+
+            let problems = Problems {
+                fatally_errored: true,
+                errors: 1,
+                ..Problems::default()
+            };
+
+            (problems, code)
+        }
+    };
+
+    emit(json!({
+        "reason": "build-finished",
+        "success": problems.errors == 0,
+        "errors": problems.errors,
+        "warnings": problems.warnings,
+        "duration_ms": millis(start_time.elapsed()),
+    }));
+
+    code
+}
+
+fn progress_message(event: &ProgressEvent) -> Option<Value> {
+    match event {
+        ProgressEvent::Module {
+            module_name,
+            phase,
+            duration,
+            ..
+        } => Some(json!({
+            "reason": "module-phase-finished",
+            "module": module_name,
+            "phase": phase.as_str(),
+            "duration_ms": millis(*duration),
+        })),
+        ProgressEvent::Loaded => None,
+        ProgressEvent::CodeGen { duration } => Some(json!({
+            "reason": "code-gen-finished",
+            "duration_ms": millis(*duration),
+        })),
+        ProgressEvent::Link { duration } => Some(json!({
+            "reason": "link-finished",
+            "duration_ms": millis(*duration),
+        })),
+        ProgressEvent::Diagnostic(diagnostic) => Some(diagnostic_message(diagnostic)),
+    }
+}
+
+fn diagnostic_message(diagnostic: &Diagnostic) -> Value {
+    let severity = match diagnostic.severity {
+        Severity::Warning => "warning",
+        Severity::RuntimeError => "error",
+        Severity::Fatal => "fatal",
+    };
+
+    json!({
+        "reason": "diagnostic",
+        "severity": severity,
+        "file": diagnostic.path.to_string_lossy(),
+        "title": diagnostic.title,
+        "region": diagnostic.region.map(region_message),
+        "rendered": diagnostic.rendered,
+    })
+}
+
+fn region_message(region: LineColumnRegion) -> Value {
+    let position = |pos: LineColumn| {
+        json!({
+            "line": pos.line + 1,
+            "column": pos.column + 1,
+        })
+    };
+
+    json!({
+        "start": position(region.start),
+        "end": position(region.end),
+    })
+}
+
+fn artifact_kind(link_type: LinkType) -> &'static str {
+    match link_type {
+        LinkType::Executable => "executable",
+        LinkType::Dylib => "shared-library",
+        LinkType::StaticLib => "static-library",
+        LinkType::None => "object",
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+fn emit(message: Value) {
+    println!("{message}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn diagnostics_are_one_line() {
+        let message = diagnostic_message(&Diagnostic {
+            severity: Severity::Warning,
+            path: PathBuf::from("main.roc"),
+            title: "UNUSED IMPORT".to_string(),
+            region: Some(LineColumnRegion::new(
+                LineColumn { line: 2, column: 4 },
+                LineColumn { line: 2, column: 7 },
+            )),
+            rendered: "── UNUSED IMPORT ── main.roc ─\n\nNothing from Str is used.".to_string(),
+        });
+
+        // Newline-delimited JSON can't have newlines inside messages.
+        assert!(!message.to_string().contains('\n'));
+        assert_eq!(message["reason"], "diagnostic");
+        assert_eq!(message["severity"], "warning");
+        assert_eq!(message["file"], "main.roc");
+        assert_eq!(
+            message["region"],
+            json!({
+                "start": { "line": 3, "column": 5 },
+                "end": { "line": 3, "column": 8 },
+            })
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Implements `--verbose` for `roc build`, `roc run`, `roc dev`, and `roc check`, which prints
+//! each module's progress through the compiler as it happens. Without `--verbose`, building a
+//! program with lots of modules shows a compact progress bar instead.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use roc_load::progress::{ProgressEvent, ProgressReporter};
+
+/// Smaller programs build quickly enough that a progress bar would just flicker.
+const PROGRESS_BAR_MIN_MODULES: usize = 20;
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+/// The length of the progress bar currently on screen, or 0 if there isn't one.
+static PROGRESS_BAR_LEN: AtomicUsize = AtomicUsize::new(0);
+
+pub fn progress_reporter(verbose: bool) -> Option<ProgressReporter> {
+    if verbose {
+        Some(ProgressReporter::new(print_event))
+    } else if io::stderr().is_terminal() {
+        Some(ProgressReporter::new(update_progress_bar))
+    } else {
+        None
+    }
+}
+
+/// Erases the progress bar, if one is showing, so other output can be printed.
+pub fn clear_progress_bar() {
+    let len = PROGRESS_BAR_LEN.swap(0, Ordering::Relaxed);
+
+    if len > 0 {
+        eprint!("\r{}\r", " ".repeat(len));
+    }
+}
+
+fn print_event(event: &ProgressEvent) {
+    match event {
+        ProgressEvent::Module {
+            module_name,
+            phase,
+            duration,
+            ..
+        } => {
+            eprintln!(
+                "{:>16} {module_name} ({} ms)",
+                phase.as_str(),
+                duration.as_millis()
+            );
+        }
+        ProgressEvent::Loaded => {}
+        ProgressEvent::CodeGen { duration } => {
+            eprintln!("{:>16} ({} ms)", "generating code", duration.as_millis());
+        }
+        ProgressEvent::Link { duration } => {
+            eprintln!("{:>16} ({} ms)", "linking", duration.as_millis());
+        }
+        ProgressEvent::Diagnostic(_) => {}
+    }
+}
+
+fn update_progress_bar(event: &ProgressEvent) {
+    match event {
+        ProgressEvent::Module {
+            modules_solved,
+            modules_known,
+            ..
+        } if *modules_known >= PROGRESS_BAR_MIN_MODULES => {
+            let line = progress_bar_line(*modules_solved, *modules_known);
+
+            PROGRESS_BAR_LEN.store(line.len(), Ordering::Relaxed);
+            eprint!("\r{line}");
+
+            let _ = io::stderr().flush();
+        }
+        ProgressEvent::Module { .. } | ProgressEvent::Diagnostic(_) => {}
+        ProgressEvent::Loaded | ProgressEvent::CodeGen { .. } | ProgressEvent::Link { .. } => {
+            clear_progress_bar();
+        }
+    }
+}
+
+fn progress_bar_line(done: usize, total: usize) -> String {
+    let filled = match total {
+        0 => 0,
+        _ => PROGRESS_BAR_WIDTH * done.min(total) / total,
+    };
+
+    format!(
+        "[{}{}] {done}/{total} modules",
+        "=".repeat(filled),
+        " ".repeat(PROGRESS_BAR_WIDTH - filled)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_bar_fills_up() {
+        assert_eq!(
+            progress_bar_line(0, 20),
+            format!("[{}] 0/20 modules", " ".repeat(30))
+        );
+        assert_eq!(
+            progress_bar_line(10, 20),
+            format!("[{}{}] 10/20 modules", "=".repeat(15), " ".repeat(15))
+        );
+        assert_eq!(
+            progress_bar_line(20, 20),
+            format!("[{}] 20/20 modules", "=".repeat(30))
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Implements `roc test --valgrind`, which runs `roc test` under valgrind's memcheck and turns
+//! leaks and other memory errors in the compiled Roc code into test failures. Refcounting bugs
+//! usually don't crash anything, so without this they only show up as memory that never gets
+//! freed.
+//!
+//! The expects run in the same process as the compiler, so valgrind sees all of it. Only errors
+//! with a stack frame in the library the expects were compiled to are reported; the rest belong
+//! to the compiler.
+
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::process::Command;
+
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use roc_error_macros::exit_code;
+use roc_reporting::report::ANSI_STYLE_CODES;
+
+use crate::{build_app, CMD_TEST, FLAG_VALGRIND};
+
+/// The file names of the library `roc test` compiles expects to, on each platform.
+const EXPECTS_LIB_NAMES: [&str; 2] = ["app.so", "app.dylib"];
+
+/// Leaks valgrind is confident about. "Possibly lost" and "still reachable" memory can be pointed
+/// to from somewhere that's still alive, so they aren't reported.
+const REPORTED_LEAK_KINDS: [&str; 2] = ["Leak_DefinitelyLost", "Leak_IndirectlyLost"];
+
+/// Runs the same `roc test` again under valgrind, minus `--valgrind`, and returns the exit code it
+/// should exit with: the one `roc test` exited with, or a failure if there were memory errors in
+/// Roc code.
+pub fn test_with_valgrind(matches: &ArgMatches) -> io::Result<i32> {
+    let xml_file = tempfile::NamedTempFile::new()?;
+    let mut cmd = Command::new("valgrind");
+
+    cmd.args(["--tool=memcheck", "--leak-check=full", "--xml=yes", "-q"])
+        .arg(format!("--xml-file={}", xml_file.path().display()));
+
+    if let Some(suppressions) = std::env::var_os("VALGRIND_SUPPRESSIONS") {
+        let mut arg = OsString::from("--suppressions=");
+        arg.push(suppressions);
+        cmd.arg(arg);
+    }
+
+    cmd.arg(std::env::current_exe()?)
+        .arg(CMD_TEST)
+        .args(test_args_without_valgrind(matches));
+
+    let status = match cmd.status() {
+        Ok(status) => status,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            eprintln!("\n`roc test --valgrind` needs valgrind, but I couldn't find it. Is it installed and on your PATH?\n");
+
+            return Ok(exit_code::IO_OR_USAGE_ERROR);
+        }
+        Err(err) => return Err(err),
+    };
+
+    let errors = memory_errors(&std::fs::read_to_string(xml_file.path())?);
+
+    for error in errors.iter() {
+        println!("\n{error}");
+    }
+
+    let test_exit_code = status.code().unwrap_or(exit_code::FAILURE);
+
+    if errors.is_empty() {
+        Ok(test_exit_code)
+    } else {
+        let red = ANSI_STYLE_CODES.red;
+        let reset = ANSI_STYLE_CODES.reset;
+
+        println!(
+            "\nvalgrind found {red}{}{reset} memory {} in Roc code.",
+            errors.len(),
+            if errors.len() == 1 { "error" } else { "errors" }
+        );
+
+        Ok(match test_exit_code {
+            exit_code::SUCCESS => exit_code::FAILURE,
+            other => other,
+        })
+    }
+}
+
+/// The arguments to `roc test` that gave these matches, minus `--valgrind`. They're rebuilt from
+/// the matches rather than copied from argv, because argv can have top-level flags before `test`.
+/// Defaults and values from environment variables are left out, since the child gets them anyway.
+fn test_args_without_valgrind(matches: &ArgMatches) -> Vec<OsString> {
+    let mut app = build_app();
+
+    // This propagates global flags (like --no-cache) to the subcommands.
+    app.build();
+
+    let test_cmd = app.find_subcommand(CMD_TEST).unwrap();
+    let mut options = Vec::new();
+    let mut positionals = Vec::new();
+    let mut args_for_app = Vec::new();
+
+    for arg in test_cmd.get_arguments() {
+        let id = arg.get_id().as_str();
+
+        if id == FLAG_VALGRIND || matches.value_source(id) != Some(ValueSource::CommandLine) {
+            continue;
+        }
+
+        let values = matches
+            .get_raw(id)
+            .into_iter()
+            .flatten()
+            .map(OsStr::to_os_string);
+
+        if arg.is_positional() {
+            if arg.is_last_set() {
+                args_for_app.extend(values);
+            } else {
+                positionals.extend(values);
+            }
+        } else {
+            let name = match arg.get_long() {
+                Some(long) => format!("--{long}"),
+                None => format!("-{}", arg.get_short().unwrap()),
+            };
+
+            if arg.get_action().takes_values() {
+                // With `=`, the value can't be mistaken for a positional argument.
+                for value in values {
+                    let mut option = OsString::from(format!("{name}="));
+                    option.push(value);
+                    options.push(option);
+                }
+            } else {
+                options.push(OsString::from(name));
+            }
+        }
+    }
+
+    options.extend(positionals);
+
+    if !args_for_app.is_empty() {
+        options.push(OsString::from("--"));
+        options.extend(args_for_app);
+    }
+
+    options
+}
+
+#[derive(Debug, PartialEq)]
+struct MemoryError {
+    kind: String,
+    what: String,
+    /// The names of the functions on the stack, innermost first.
+    functions: Vec<String>,
+}
+
+impl std::fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let red = ANSI_STYLE_CODES.red;
+        let reset = ANSI_STYLE_CODES.reset;
+
+        write!(f, "{red}{}{reset}: {}", self.kind, self.what)?;
+
+        for function in self.functions.iter() {
+            write!(f, "\n    at {function}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The memory errors in valgrind's XML output which happened in Roc code.
+fn memory_errors(xml: &str) -> Vec<MemoryError> {
+    let mut errors = Vec::new();
+
+    for error in xml.split("<error>").skip(1) {
+        let error = error.split("</error>").next().unwrap_or_default();
+        let kind = first_tag(error, "kind").unwrap_or_default();
+
+        if kind.starts_with("Leak_") && !REPORTED_LEAK_KINDS.contains(&kind) {
+            continue;
+        }
+
+        let in_roc_code = all_tags(error, "obj").into_iter().any(|obj| {
+            EXPECTS_LIB_NAMES
+                .iter()
+                .any(|name| obj.rsplit('/').next() == Some(name))
+        });
+
+        if !in_roc_code {
+            continue;
+        }
+
+        // Leaks describe themselves in <xwhat><text>, and everything else in <what>.
+        let what = first_tag(error, "what")
+            .or_else(|| first_tag(error, "text"))
+            .unwrap_or_default();
+
+        errors.push(MemoryError {
+            kind: kind.to_string(),
+            what: unescape(what),
+            functions: all_tags(error, "fn").into_iter().map(unescape).collect(),
+        });
+    }
+
+    errors
+}
+
+fn all_tags<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+
+    xml.split(open.as_str())
+        .skip(1)
+        .filter_map(|rest| rest.split_once(close.as_str()).map(|(content, _)| content))
+        .collect()
+}
+
+fn first_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    all_tags(xml, tag).first().copied()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0"?>
+<valgrindoutput>
+<error>
+  <unique>0x1</unique>
+  <kind>Leak_DefinitelyLost</kind>
+  <xwhat>
+    <text>24 bytes in 1 blocks are definitely lost in loss record 1 of 2</text>
+    <leakedbytes>24</leakedbytes>
+  </xwhat>
+  <stack>
+    <frame><obj>/usr/libexec/valgrind/vgpreload_memcheck-amd64-linux.so</obj><fn>malloc</fn></frame>
+    <frame><obj>/tmp/.tmpAbC123/app.so</obj><fn>List.map</fn></frame>
+  </stack>
+</error>
+<error>
+  <unique>0x2</unique>
+  <kind>Leak_DefinitelyLost</kind>
+  <xwhat><text>8 bytes in 1 blocks are definitely lost in loss record 2 of 2</text></xwhat>
+  <stack>
+    <frame><obj>/usr/bin/roc</obj><fn>roc_load::file::load</fn></frame>
+  </stack>
+</error>
+<error>
+  <unique>0x3</unique>
+  <kind>Leak_PossiblyLost</kind>
+  <xwhat><text>16 bytes in 1 blocks are possibly lost</text></xwhat>
+  <stack>
+    <frame><obj>/tmp/.tmpAbC123/app.so</obj><fn>Str.concat</fn></frame>
+  </stack>
+</error>
+<error>
+  <unique>0x4</unique>
+  <kind>InvalidRead</kind>
+  <what>Invalid read of size 8</what>
+  <stack>
+    <frame><obj>/tmp/.tmpAbC123/app.so</obj><fn>Dict.get&lt;U64&gt;</fn></frame>
+  </stack>
+</error>
+</valgrindoutput>
+"#;
+
+    #[test]
+    fn finds_memory_errors_in_roc_code() {
+        assert_eq!(
+            memory_errors(XML),
+            vec![
+                MemoryError {
+                    kind: "Leak_DefinitelyLost".to_string(),
+                    what: "24 bytes in 1 blocks are definitely lost in loss record 1 of 2"
+                        .to_string(),
+                    functions: vec!["malloc".to_string(), "List.map".to_string()],
+                },
+                MemoryError {
+                    kind: "InvalidRead".to_string(),
+                    what: "Invalid read of size 8".to_string(),
+                    functions: vec!["Dict.get<U64>".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rebuilds_test_args_without_valgrind() {
+        let matches = build_app().get_matches_from([
+            "roc",
+            "--no-cache",
+            "test",
+            "--valgrind",
+            "--main",
+            "main.roc",
+            "--optimize",
+            "Tests.roc",
+            "--",
+            "--valgrind",
+        ]);
+        let (_, test_matches) = matches.subcommand().unwrap();
+
+        assert_eq!(
+            test_args_without_valgrind(test_matches),
+            [
+                "--main=main.roc",
+                "--optimize",
+                "--no-cache",
+                "Tests.roc",
+                "--",
+                "--valgrind"
+            ]
+        );
+    }
+}
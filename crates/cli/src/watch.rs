@@ -0,0 +1,382 @@
+//! Implements `--watch` for `roc dev` and `roc run`.
+//!
+//! Running an app replaces the current process (via `execve`), so rather than rebuilding
+//! in-process, the watcher runs the same `roc` command (minus `--watch`) as a child process,
+//! and restarts it whenever one of the app's .roc files changes. Those are the source files of
+//! every module the app loads (so its imports, packages, and platform, wherever they live), plus
+//! any .roc file next to the app or in a directory below it, to notice new modules.
+//!
+//! Since every rebuild is a new `roc` process, nothing from the previous load is reused.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use bumpalo::Bump;
+use roc_build::program::check_load_config;
+use roc_load::Threading;
+use roc_packaging::cache::{self, RocCacheDir};
+
+use crate::FLAG_WATCH;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+pub fn watch(roc_file_path: &Path) -> io::Result<i32> {
+    let roc_exe = std::env::current_exe()?;
+
+    handle_interrupts();
+
+    let args = args_without_watch_flag(std::env::args_os().skip(1));
+
+    let watched_dir = match roc_file_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let mut module_files = load_module_files(roc_file_path).unwrap_or_default();
+    let mut snapshot = snapshot_roc_files(&watched_dir, &module_files);
+
+    loop {
+        let mut child = spawn(&roc_exe, &args)?;
+        let mut reported_exit = false;
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if interrupted() {
+                kill(&mut child)?;
+
+                return Ok(INTERRUPTED_EXIT_CODE);
+            }
+
+            let latest = snapshot_roc_files(&watched_dir, &module_files);
+
+            if latest != snapshot {
+                // Imports may have been added or removed. If the app doesn't load right now
+                // (e.g. it's in the middle of being edited), keep watching what it used to load.
+                if let Some(files) = load_module_files(roc_file_path) {
+                    module_files = files;
+                }
+
+                snapshot = snapshot_roc_files(&watched_dir, &module_files);
+                break;
+            }
+
+            if !reported_exit {
+                if let Some(status) = child.try_wait()? {
+                    reported_exit = true;
+
+                    take_back_terminal();
+
+                    // Ctrl-C goes to the program while it runs, so this is how we hear about it.
+                    if was_interrupted(status) {
+                        return Ok(INTERRUPTED_EXIT_CODE);
+                    }
+
+                    println!(
+                        "\n\x1B[36m{}\x1B[39m\n\nThe program exited ({status}). Waiting for changes to its .roc files…",
+                        "─".repeat(80),
+                    );
+                }
+            }
+        }
+
+        kill(&mut child)?;
+
+        println!(
+            "\n\x1B[36m{}\x1B[39m\n\nA .roc file changed; rebuilding…\n",
+            "─".repeat(80)
+        );
+    }
+}
+
+/// The arguments to pass through to the child `roc` process. Anything after `--` is
+/// an argument for the app itself, so a `--watch` there is left alone.
+fn args_without_watch_flag(args: impl Iterator<Item = OsString>) -> Vec<OsString> {
+    let watch_flag = OsString::from(format!("--{FLAG_WATCH}"));
+    let mut removed = false;
+    let mut passed_separator = false;
+
+    args.filter(|arg| {
+        if arg == "--" {
+            passed_separator = true;
+        } else if !removed && !passed_separator && *arg == watch_flag {
+            removed = true;
+
+            return false;
+        }
+
+        true
+    })
+    .collect()
+}
+
+/// The source files of every module the app loads, found by loading it the way `roc check`
+/// does. Returns None if the app can't be loaded, e.g. because a header doesn't parse.
+fn load_module_files(roc_file_path: &Path) -> Option<BTreeSet<PathBuf>> {
+    let arena = Bump::new();
+
+    let loaded = roc_load::load_and_typecheck(
+        &arena,
+        roc_file_path.to_path_buf(),
+        None,
+        RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+        check_load_config(Threading::AllAvailable, None),
+    )
+    .ok()?;
+
+    let files = loaded
+        .sources
+        .into_values()
+        .map(|(path, _)| path)
+        // builtins are part of the compiler, not files on disk
+        .filter(|path| path.is_file())
+        .collect();
+
+    Some(files)
+}
+
+/// When each of `module_files` and each .roc file in or below `dir` was last modified.
+fn snapshot_roc_files(dir: &Path, module_files: &BTreeSet<PathBuf>) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+
+    for path in module_files {
+        if let Ok(modified) = path.metadata().and_then(|meta| meta.modified()) {
+            snapshot.insert(path.clone(), modified);
+        }
+    }
+    let mut to_visit = vec![dir.to_path_buf()];
+
+    while let Some(dir) = to_visit.pop() {
+        // Files can come and go while we're looking; if we can't read something, skip it.
+        let entries = match dir.read_dir() {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_hidden = path
+                .file_name()
+                .and_then(OsStr::to_str)
+                .map_or(false, |name| name.starts_with('.'));
+
+            if is_hidden {
+                continue;
+            }
+
+            if path.is_dir() {
+                to_visit.push(path);
+            } else if path.extension() == Some(OsStr::new("roc")) {
+                if let Ok(modified) = entry.metadata().and_then(|meta| meta.modified()) {
+                    snapshot.insert(path, modified);
+                }
+            }
+        }
+    }
+
+    snapshot
+}
+
+/// Like a shell, exits with 128 + SIGINT when the user presses Ctrl-C.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+#[cfg(unix)]
+static INTERRUPTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// While the program runs, Ctrl-C goes to it rather than to us. A SIGINT can still be sent to
+/// the watcher directly though, and then we stop the program before exiting, so it isn't left
+/// running without anyone watching it.
+#[cfg(unix)]
+fn handle_interrupts() {
+    let handler: extern "C" fn(libc::c_int) = on_interrupt;
+
+    unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+}
+
+#[cfg(not(unix))]
+fn handle_interrupts() {}
+
+#[cfg(unix)]
+fn interrupted() -> bool {
+    INTERRUPTED.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+#[cfg(not(unix))]
+fn interrupted() -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn was_interrupted(status: std::process::ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal() == Some(libc::SIGINT)
+}
+
+#[cfg(not(unix))]
+fn was_interrupted(_status: std::process::ExitStatus) -> bool {
+    false
+}
+
+/// Makes `process_group` the terminal's foreground process group, which is the one that can
+/// read stdin and that Ctrl-C is sent to. Does nothing if stdin isn't a terminal.
+///
+/// This is async-signal-safe, so the child can call it between `fork` and `exec`.
+#[cfg(unix)]
+fn set_foreground(process_group: libc::pid_t) {
+    unsafe {
+        if libc::isatty(libc::STDIN_FILENO) == 0 {
+            return;
+        }
+
+        // A process that isn't in the foreground gets SIGTTOU (which stops it) for changing the
+        // foreground group, unless it blocks that signal.
+        let mut block: libc::sigset_t = std::mem::zeroed();
+        let mut previous: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut block);
+        libc::sigaddset(&mut block, libc::SIGTTOU);
+
+        libc::sigprocmask(libc::SIG_BLOCK, &block, &mut previous);
+        libc::tcsetpgrp(libc::STDIN_FILENO, process_group);
+        libc::sigprocmask(libc::SIG_SETMASK, &previous, std::ptr::null_mut());
+    }
+}
+
+#[cfg(unix)]
+fn take_back_terminal() {
+    set_foreground(unsafe { libc::getpgrp() });
+}
+
+#[cfg(not(unix))]
+fn take_back_terminal() {}
+
+#[cfg(unix)]
+fn spawn(roc_exe: &Path, args: &[OsString]) -> io::Result<Child> {
+    use std::os::unix::process::CommandExt;
+
+    let mut command = Command::new(roc_exe);
+
+    command.args(args);
+
+    // The child gets its own process group, so that on a restart, killing the group also kills
+    // any processes it forked (e.g. the app itself when running `roc dev`). That group is made
+    // the foreground one, so the program can read stdin and Ctrl-C stops it.
+    //
+    // Both the child and we do this, because either could get to run first.
+    unsafe {
+        command.pre_exec(|| {
+            libc::setpgid(0, 0);
+            set_foreground(libc::getpid());
+
+            Ok(())
+        });
+    }
+
+    let child = command.spawn()?;
+    let process_group = child.id() as libc::pid_t;
+
+    unsafe { libc::setpgid(process_group, process_group) };
+    set_foreground(process_group);
+
+    Ok(child)
+}
+
+#[cfg(not(unix))]
+fn spawn(roc_exe: &Path, args: &[OsString]) -> io::Result<Child> {
+    Command::new(roc_exe).args(args).spawn()
+}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) -> io::Result<()> {
+    if child.try_wait()?.is_none() {
+        // The process group has the same id as the child, since we made it the group leader.
+        unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+    }
+
+    child.wait()?;
+    take_back_terminal();
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) -> io::Result<()> {
+    if child.try_wait()?.is_none() {
+        child.kill()?;
+    }
+
+    child.wait().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os_strings(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn removes_watch_flag() {
+        assert_eq!(
+            args_without_watch_flag(os_strings(&["dev", "--watch", "main.roc"]).into_iter()),
+            os_strings(&["dev", "main.roc"])
+        );
+    }
+
+    #[test]
+    fn keeps_watch_flag_meant_for_app() {
+        assert_eq!(
+            args_without_watch_flag(
+                os_strings(&["run", "--watch", "main.roc", "--", "--watch"]).into_iter()
+            ),
+            os_strings(&["run", "main.roc", "--", "--watch"])
+        );
+    }
+
+    #[test]
+    fn snapshot_sees_new_and_changed_roc_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.roc");
+
+        std::fs::write(&main, "app [main] {}").unwrap();
+        std::fs::write(dir.path().join("main"), "not a roc file").unwrap();
+        std::fs::create_dir(dir.path().join("platform")).unwrap();
+
+        let before = snapshot_roc_files(dir.path(), &BTreeSet::new());
+        assert_eq!(before.keys().collect::<Vec<_>>(), vec![&main]);
+
+        let platform_main = dir.path().join("platform").join("main.roc");
+        std::fs::write(&platform_main, "platform \"test\"").unwrap();
+
+        let after = snapshot_roc_files(dir.path(), &BTreeSet::new());
+        assert_ne!(before, after);
+        assert!(after.contains_key(&platform_main));
+    }
+
+    #[test]
+    fn snapshot_sees_module_files_elsewhere() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let platform_dir = tempfile::tempdir().unwrap();
+        let platform_main = platform_dir.path().join("main.roc");
+
+        std::fs::write(&platform_main, "platform \"test\"").unwrap();
+
+        let module_files = BTreeSet::from([platform_main.clone()]);
+        let snapshot = snapshot_roc_files(app_dir.path(), &module_files);
+
+        assert_eq!(snapshot.keys().collect::<Vec<_>>(), vec![&platform_main]);
+    }
+}
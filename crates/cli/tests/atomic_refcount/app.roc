@@ -0,0 +1,9 @@
+app [main] { pf: platform "platform/main.roc" }
+
+# Putting `bytes` in a list twice increments its refcount, and
+# dropping that list afterwards decrements it again.
+main : List U8 -> U64
+main = \bytes ->
+    [bytes, bytes]
+    |> List.map List.len
+    |> List.sum
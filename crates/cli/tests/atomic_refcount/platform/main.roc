@@ -0,0 +1,9 @@
+platform "atomic-refcount"
+    requires {} { main : List U8 -> U64 }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : List U8 -> U64
+mainForHost = \bytes -> main bytes
@@ -0,0 +1,139 @@
+#![allow(non_snake_case)]
+
+use core::ffi::c_void;
+use roc_std::{RocList, RocStr};
+
+extern "C" {
+    #[link_name = "roc__mainForHost_1_exposed_generic"]
+    fn roc_main(output: *mut u64, bytes: &RocList<u8>);
+}
+
+const THREADS: usize = 8;
+const CALLS_PER_THREAD: usize = 100_000;
+
+/// Sharing a refcounted Roc value between threads is only sound with atomic refcounting.
+struct Shared<T>(T);
+
+unsafe impl<T> Send for Shared<T> {}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_alloc(size: usize, _alignment: u32) -> *mut c_void {
+    return libc::malloc(size);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_realloc(
+    c_ptr: *mut c_void,
+    new_size: usize,
+    _old_size: usize,
+    _alignment: u32,
+) -> *mut c_void {
+    return libc::realloc(c_ptr, new_size);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
+    return libc::free(c_ptr);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_panic(msg: *mut RocStr, tag_id: u32) {
+    match tag_id {
+        0 => {
+            eprintln!("Roc standard library hit a panic: {}", &*msg);
+        }
+        1 => {
+            eprintln!("Application hit a panic: {}", &*msg);
+        }
+        _ => unreachable!(),
+    }
+    std::process::exit(1);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dbg(loc: *mut RocStr, msg: *mut RocStr, src: *mut RocStr) {
+    eprintln!("[{}] {} = {}", &*loc, &*src, &*msg);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut c_void {
+    libc::memset(dst, c, n)
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_getppid() -> libc::pid_t {
+    libc::getppid()
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_mmap(
+    addr: *mut libc::c_void,
+    len: libc::size_t,
+    prot: libc::c_int,
+    flags: libc::c_int,
+    fd: libc::c_int,
+    offset: libc::off_t,
+) -> *mut libc::c_void {
+    libc::mmap(addr, len, prot, flags, fd, offset)
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_shm_open(
+    name: *const libc::c_char,
+    oflag: libc::c_int,
+    mode: libc::mode_t,
+) -> libc::c_int {
+    libc::shm_open(name, oflag, mode as libc::c_uint)
+}
+
+/// Hands the same list to Roc from several threads at once. Both this host (through roc_std)
+/// and the Roc builtins update the list's refcount, so unless both update it atomically,
+/// some updates get lost and the list is no longer unique at the end.
+#[no_mangle]
+pub extern "C" fn rust_main() -> i32 {
+    let bytes = RocList::from_slice(&[0u8; 64]);
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let shared = Shared(bytes.clone());
+
+            std::thread::spawn(move || {
+                let shared = shared;
+                let mut total = 0;
+
+                for _ in 0..CALLS_PER_THREAD {
+                    let arg = shared.0.clone();
+                    let mut output = 0;
+
+                    unsafe { roc_main(&mut output, &arg) };
+
+                    // arg has been passed to roc now, and it assumes ownership.
+                    // so we must not touch its refcount now
+                    std::mem::forget(arg);
+
+                    total += output;
+                }
+
+                total
+            })
+        })
+        .collect();
+
+    let total: u64 = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .sum();
+
+    if bytes.is_unique() {
+        println!("Refcounts agree after counting {total} bytes");
+
+        0
+    } else {
+        println!("Refcounts disagree after counting {total} bytes");
+
+        1
+    }
+}
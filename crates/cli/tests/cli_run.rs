@@ -17,10 +17,14 @@ mod cli_run {
     use const_format::concatcp;
     use indoc::indoc;
     use regex::Regex;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_RUN, CMD_TEST};
+    use roc_cli::{
+        CMD_ANNOTATE, CMD_BENCH, CMD_BUILD, CMD_CACHE, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_LINT,
+        CMD_RUN, CMD_TEST,
+    };
     use roc_reporting::report::strip_colors;
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
+    use std::ffi::OsStr;
     use std::iter;
     use std::path::Path;
     use std::process::ExitStatus;
@@ -55,6 +59,9 @@ mod cli_run {
     const OPTIMIZE_FLAG: &str = concatcp!("--", roc_cli::FLAG_OPTIMIZE);
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
+    const NO_CACHE_FLAG: &str = concatcp!("--", roc_cli::FLAG_NO_CACHE);
+    const PRINT_EXPECTS_FLAG: &str = concatcp!("--", roc_cli::FLAG_PRINT_EXPECTS);
+    const ATOMIC_REFCOUNT_FLAG: &str = concatcp!("--", roc_cli::FLAG_ATOMIC_REFCOUNT);
     #[allow(dead_code)]
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT);
     #[allow(dead_code)]
@@ -549,6 +556,7 @@ mod cli_run {
     }
 
     #[test]
+    #[serial(expects_zig_platform)]
     fn expects_dev_and_test() {
         // these are in the same test function so we don't have to worry about race conditions
         // on the building of the platform
@@ -728,6 +736,50 @@ mod cli_run {
         )
     }
 
+    #[test]
+    #[serial(expects_zig_platform)]
+    #[cfg_attr(windows, ignore)]
+    fn print_expects() {
+        let file = file_path_from_root("crates/cli/tests/expects", "print_expects.roc");
+        let self_path = file.display().to_string();
+
+        // builds only print failed expects when asked to
+        let out = run_roc_on_failure_is_panic(&file, [CMD_RUN], &[], &[], &[]);
+        assert!(
+            out.stdout.ends_with("Program finished!\n"),
+            "{}",
+            out.stdout
+        );
+        assert!(!out.stdout.contains("expect count == 3"), "{}", out.stdout);
+
+        let out = run_roc_on_failure_is_panic(&file, [CMD_RUN, PRINT_EXPECTS_FLAG], &[], &[], &[]);
+        let stdout = out.stdout.replace(&self_path, "<ignored for tests>");
+        let expected_ending = indoc!(
+            r#"
+            [<ignored for tests>:10] expect count == 3 = Bool.false
+            [<ignored for tests>:10] count = 2
+            Program finished!
+            "#
+        );
+        assert!(stdout.ends_with(expected_ending), "{stdout}");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    // the host shares a list across threads, using roc_std's atomic-refcount feature
+    fn atomic_refcount() {
+        check_output_with_stdin(
+            &file_path_from_root("crates/cli/tests/atomic_refcount", "app.roc"),
+            &[],
+            &[ATOMIC_REFCOUNT_FLAG],
+            &[],
+            &[],
+            "Refcounts agree after counting 102400000 bytes\n",
+            UseValgrind::No,
+            TestCliCommands::Run,
+        );
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     fn module_params() {
@@ -941,6 +993,33 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn bench_marked_expects() {
+        let file = fixture_file("bench", "Bench.roc");
+        let out = run_roc(
+            [
+                CMD_BENCH,
+                file.to_str().unwrap(),
+                "--warmup",
+                "1",
+                "--iterations",
+                "3",
+            ],
+            &[],
+            &[],
+        );
+        let stdout = strip_colors(&out.stdout);
+
+        assert!(out.status.success(), "{stdout}\n{}", out.stderr);
+        assert!(stdout.contains("sum to a million\n    mean "), "{stdout}");
+        assert!(
+            stdout.contains("expect List.len (List.repeat 'x' 1000) == 1000\n    mean "),
+            "{stdout}"
+        );
+        assert_eq!(stdout.matches("(3 runs)").count(), 2, "{stdout}");
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     fn transitive_expects() {
@@ -1587,6 +1666,123 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg_attr(windows, ignore)]
+    fn run_no_cache_deletes_temp_cache_dir() {
+        // The temporary cache dir goes in TMPDIR, so give roc one we can look inside.
+        let tmp = tempfile::tempdir().unwrap();
+        let env = [("TMPDIR", tmp.path().to_str().unwrap())];
+        let main = fixture_file("multi-dep-str", "Main.roc");
+
+        let out = run_roc([CMD_RUN, NO_CACHE_FLAG, main.to_str().unwrap()], &[], &env);
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(out.stdout.ends_with("I am Dep2.str2\n"), "{}", out.stdout);
+
+        // `roc run` replaces itself with the app, so it has to delete the dir before that.
+        let leftover_dirs: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .collect();
+        assert!(leftover_dirs.is_empty(), "{leftover_dirs:?}");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg_attr(windows, ignore)]
+    fn build_deterministic_is_reproducible() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = fixture_file("multi-dep-str", "Main.roc");
+        let build_bytes = |name: &str| {
+            let out_path = dir.path().join(name);
+            let out = run_roc(
+                [
+                    OsStr::new(CMD_BUILD),
+                    OsStr::new("--deterministic"),
+                    main.as_os_str(),
+                    OsStr::new("--output"),
+                    out_path.as_os_str(),
+                ],
+                &[],
+                &[],
+            );
+
+            assert!(out.status.success(), "build failed: {}", out.stderr);
+
+            std::fs::read(out_path).unwrap()
+        };
+
+        assert_eq!(build_bytes("first"), build_bytes("second"));
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg_attr(windows, ignore)]
+    fn build_message_format_json() {
+        let main = fixture_file("multi-dep-str", "Main.roc");
+        let out = run_roc(
+            [
+                CMD_BUILD,
+                "--message-format",
+                "json",
+                main.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "build failed: {}", out.stderr);
+
+        let messages: Vec<serde_json::Value> = out
+            .stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let reasons: Vec<&str> = messages
+            .iter()
+            .map(|message| message["reason"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(reasons.first(), Some(&"build-started"));
+        assert!(reasons.contains(&"module-phase-finished"));
+        assert!(reasons.contains(&"artifact"));
+        assert_eq!(reasons.last(), Some(&"build-finished"));
+        assert_eq!(messages.last().unwrap()["success"], true);
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn build_message_format_json_diagnostic_region() {
+        let file = known_bad_file("TypeError.roc");
+        let out = run_roc(
+            [
+                CMD_BUILD,
+                "--message-format",
+                "json",
+                file.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+
+        let diagnostic = out
+            .stdout
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .find(|message| message["reason"] == "diagnostic")
+            .expect("there should be a diagnostic for the type mismatch");
+
+        assert_eq!(diagnostic["title"], "TYPE MISMATCH");
+        assert_eq!(
+            diagnostic["region"],
+            serde_json::json!({
+                "start": { "line": 5, "column": 5 },
+                "end": { "line": 5, "column": 77 },
+            })
+        );
+    }
+
     #[test]
     #[serial(multi_dep_thunk)]
     #[cfg_attr(windows, ignore)]
@@ -1803,6 +1999,168 @@ mod cli_run {
         );
     }
 
+    #[test]
+    fn lint_reports_style_problems() {
+        let file = fixture_file("lint", "Lints.roc");
+        let out = run_roc([CMD_LINT, file.to_str().unwrap()], &[], &[]);
+        let stdout = strip_colors(&out.stdout);
+
+        assert_eq!(out.status.code(), Some(2), "{stdout}");
+        assert!(stdout.contains("NAMING CONVENTION"), "{stdout}");
+        assert!(stdout.contains("itemCount"), "{stdout}");
+        assert!(stdout.contains("REDUNDANT IF"), "{stdout}");
+        assert!(stdout.contains("UNNECESSARY WHEN"), "{stdout}");
+        assert!(stdout.contains("0 errors and 3 warnings"), "{stdout}");
+    }
+
+    #[test]
+    fn annotate_prints_inferred_types() {
+        let file = fixture_file("annotate", "Annotate.roc");
+        let out = run_roc([CMD_ANNOTATE, file.to_str().unwrap()], &[], &[]);
+
+        assert!(out.status.success(), "{}\n{}", out.stdout, out.stderr);
+
+        let mut lines: Vec<&str> = out.stdout.lines().collect();
+        lines.sort();
+
+        assert_eq!(lines, ["answer : Str", "double : Num a -> Num a"]);
+    }
+
+    #[test]
+    fn annotate_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Annotate.roc");
+        std::fs::copy(fixture_file("annotate", "Annotate.roc"), &file).unwrap();
+
+        let out = run_roc([CMD_ANNOTATE, file.to_str().unwrap(), "--write"], &[], &[]);
+        assert!(out.status.success(), "{}\n{}", out.stdout, out.stderr);
+
+        let annotated = std::fs::read_to_string(&file).unwrap();
+
+        assert!(
+            annotated.contains("\ndouble : Num a -> Num a\ndouble = "),
+            "{annotated}"
+        );
+        assert!(
+            annotated.contains("\nanswer : Str\nanswer = "),
+            "{annotated}"
+        );
+        assert_eq!(annotated.matches("greet : ").count(), 1, "{annotated}");
+    }
+
+    #[test]
+    fn check_ignores_style_problems() {
+        let file = fixture_file("lint", "Lints.roc");
+        let out = run_roc([CMD_CHECK, file.to_str().unwrap()], &[], &[]);
+
+        assert_eq!(out.status.code(), Some(0), "{}", out.stdout);
+    }
+
+    #[test]
+    fn wasm_backend_needs_wasm_target() {
+        let file = fixture_file("annotate", "Annotate.roc");
+        let out = run_roc(
+            [
+                CMD_BUILD,
+                "--backend",
+                "wasm",
+                "--target",
+                "linux-x64",
+                file.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+
+        assert!(!out.status.success());
+        assert!(
+            out.stderr
+                .contains("The wasm backend can only build for `--target wasm32`."),
+            "{}",
+            out.stderr
+        );
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg_attr(windows, ignore)]
+    fn test_without_expects() {
+        let file = fixture_file("multi-dep-str", "Main.roc");
+        let out = run_roc([CMD_TEST, file.to_str().unwrap()], &[], &[]);
+
+        assert_eq!(
+            out.status.code(),
+            Some(roc_error_macros::exit_code::NO_EXPECTATIONS),
+            "{}\n{}",
+            out.stdout,
+            out.stderr
+        );
+        assert!(
+            out.stdout.contains("No expectations were found."),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    fn test_under_valgrind() {
+        if !ALLOW_VALGRIND {
+            return;
+        }
+
+        let file = fixture_file("bench", "Bench.roc");
+        let out = run_roc([CMD_TEST, "--valgrind", file.to_str().unwrap()], &[], &[]);
+
+        assert!(out.status.success(), "{}\n{}", out.stdout, out.stderr);
+        assert!(out.stdout.contains("passed"), "{}", out.stdout);
+        assert!(!out.stdout.contains("memory error"), "{}", out.stdout);
+    }
+
+    #[test]
+    fn unsupported_roc_target_env_var() {
+        let file = fixture_file("annotate", "Annotate.roc");
+        let out = run_roc(
+            [CMD_CHECK, file.to_str().unwrap()],
+            &[],
+            &[("ROC_TARGET", "linux-x65")],
+        );
+
+        assert!(!out.status.success());
+        assert!(
+            out.stderr.contains(
+                "The ROC_TARGET environment variable is set to `linux-x65`, which isn't a target Roc supports."
+            ),
+            "{}",
+            out.stderr
+        );
+        assert!(out.stderr.contains("linux-x64"), "{}", out.stderr);
+    }
+
+    #[test]
+    fn cache_dir_and_clean() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().to_str().unwrap();
+        let env = [("ROC_CACHE_DIR", cache_dir)];
+
+        let out = run_roc([CMD_CACHE, "dir"], &[], &env);
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_eq!(out.stdout.trim(), cache_dir);
+
+        let package = dir
+            .path()
+            .join("packages")
+            .join("example.com")
+            .join("abc123");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(package.join("main.roc"), "package [] {}").unwrap();
+
+        let out = run_roc([CMD_CACHE, "clean"], &[], &env);
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(!dir.path().join("packages").exists());
+        // The cache dir itself is left alone, since ROC_CACHE_DIR could point anywhere.
+        assert!(dir.path().exists());
+    }
+
     #[test]
     fn format_check_good() {
         check_format_check_as_expected(&fixture_file("format", "Formatted.roc"), true);
@@ -1821,6 +2179,33 @@ mod cli_run {
         // This doesn't fail, since only "Formatted.roc" and non-roc files are present in this folder
         check_format_check_as_expected(&fixtures_dir("format/formatted_directory"), true);
     }
+
+    #[test]
+    fn format_stdin_to_stdout() {
+        let not_formatted =
+            std::fs::read_to_string(fixture_file("format", "NotFormatted.roc")).unwrap();
+        let formatted = std::fs::read_to_string(fixture_file("format", "Formatted.roc")).unwrap();
+
+        let out = run_roc([CMD_FORMAT, "-"], &[&not_formatted], &[]);
+
+        assert!(out.status.success());
+        assert_eq!(out.stdout, formatted);
+    }
+
+    #[test]
+    fn format_stdin_syntax_error_uses_stdin_filename() {
+        let out = run_roc(
+            [CMD_FORMAT, "--stdin-filename", "Unsaved.roc", "-"],
+            &["app [main] { pf: platform \"main.roc\" }\n\nmain = (\n"],
+            &[],
+        );
+
+        assert_eq!(
+            out.status.code(),
+            Some(roc_error_macros::exit_code::PARSE_OR_CANONICALIZE_ERRORS)
+        );
+        assert!(out.stderr.contains("I could not format Unsaved.roc"));
+    }
 }
 
 #[cfg(feature = "wasm32-cli-run")]
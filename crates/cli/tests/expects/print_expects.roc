@@ -0,0 +1,12 @@
+app "print-expects-test"
+    packages { pf: "zig-platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main =
+    words = Str.split "not empty" " "
+    count = List.len words
+
+    expect count == 3
+
+    "Program finished!\n"
@@ -0,0 +1,10 @@
+interface Annotate
+    exposes [double, greet, answer]
+    imports []
+
+double = \n -> n * 2
+
+greet : Str -> Str
+greet = \name -> "Hello, $(name)!"
+
+answer = Str.concat "4" "2"
@@ -0,0 +1,14 @@
+interface Bench
+    exposes [sumTo]
+    imports []
+
+sumTo = \n ->
+    List.range { start: At 0, end: At n } |> List.sum
+
+expect sumTo 3 == 6
+
+# bench sum to a million
+expect sumTo 1_000_000 > 0
+
+# bench
+expect List.len (List.repeat 'x' 1000) == 1000
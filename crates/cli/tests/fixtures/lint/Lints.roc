@@ -0,0 +1,12 @@
+interface Lints
+    exposes [isEmpty, describe]
+    imports []
+
+isEmpty = \list ->
+    item_count = List.len list
+
+    if item_count == 0 then Bool.true else Bool.false
+
+describe = \n ->
+    when n is
+        x -> Num.toStr x
@@ -357,8 +357,10 @@ where
     }
 
     match opt_level {
-        OptLevel::Development | OptLevel::Normal => morphic_lib::solve_trivial(program),
-        OptLevel::Optimize | OptLevel::Size => morphic_lib::solve(program),
+        OptLevel::Development | OptLevel::Normal | OptLevel::Less => {
+            morphic_lib::solve_trivial(program)
+        }
+        OptLevel::Moderate | OptLevel::Optimize | OptLevel::Size => morphic_lib::solve(program),
     }
 }
 
@@ -2,7 +2,7 @@ use crate::target::arch_str;
 use libloading::{Error, Library};
 use roc_command_utils::{cargo, clang, rustup, zig};
 use roc_debug_flags;
-use roc_error_macros::internal_error;
+use roc_error_macros::{exit_code, internal_error};
 use roc_mono::ir::OptLevel;
 use roc_target::{Architecture, OperatingSystem, Target};
 use std::collections::HashMap;
@@ -26,6 +26,43 @@ pub enum LinkingStrategy {
     Additive,
 }
 
+/// A sanitizer to check the program with while it runs (e.g. `roc build --sanitize address`).
+/// Only the app is instrumented, but the sanitizer's runtime watches every allocation, including
+/// the ones the platform makes and frees on the app's behalf.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sanitizer {
+    /// AddressSanitizer: use after free, double free, and out-of-bounds accesses, plus leaks
+    Address,
+    /// LeakSanitizer on its own: memory that is never freed, reported when the program exits
+    Leak,
+}
+
+impl Sanitizer {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "address" => Some(Sanitizer::Address),
+            "leak" => Some(Sanitizer::Leak),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sanitizer::Address => "address",
+            Sanitizer::Leak => "leak",
+        }
+    }
+
+    /// The LLVM pass which instruments the app's code, if this sanitizer needs one.
+    /// LeakSanitizer only needs its runtime, which replaces `malloc` and `free`.
+    pub fn llvm_pass(self) -> Option<&'static str> {
+        match self {
+            Sanitizer::Address => Some("asan"),
+            Sanitizer::Leak => None,
+        }
+    }
+}
+
 /// input_paths can include the host as well as the app. e.g. &["host.o", "roc_app.o"]
 pub fn link(
     target: Target,
@@ -33,6 +70,10 @@ pub fn link(
     input_paths: &[&str],
     link_type: LinkType,
 ) -> io::Result<(Child, PathBuf)> {
+    if link_type == LinkType::StaticLib {
+        return link_static_lib(target, output_path, input_paths);
+    }
+
     match target.arch_os() {
         (Architecture::Wasm32, _) => link_wasm32(target, output_path, input_paths, link_type),
         (_, OperatingSystem::Linux) => link_linux(target, output_path, input_paths, link_type),
@@ -42,6 +83,84 @@ pub fn link(
     }
 }
 
+/// Like [link], but lets clang do the linking, so that it adds the runtime of `sanitizer`.
+pub fn link_with_sanitizer(
+    target: Target,
+    output_path: PathBuf,
+    input_paths: &[&str],
+    link_type: LinkType,
+    sanitizer: Sanitizer,
+) -> io::Result<(Child, PathBuf)> {
+    let output_path = match link_type {
+        LinkType::Executable => output_path,
+        LinkType::Dylib => output_path.with_extension(target.shared_library_file_ext()),
+        LinkType::None | LinkType::StaticLib => internal_error!(
+            "link_with_sanitizer should not be called with link type of {:?}",
+            link_type
+        ),
+    };
+
+    let mut clang_command = clang();
+
+    clang_command
+        .arg(format!("-fsanitize={}", sanitizer.name()))
+        .args(input_paths)
+        .args(extra_link_flags())
+        .args(["-lm", "-lpthread", "-ldl"]);
+
+    match target.operating_system() {
+        OperatingSystem::Linux => {
+            clang_command.args(["-lrt", "-lutil"]);
+        }
+        OperatingSystem::Mac => {
+            if link_type == LinkType::Dylib {
+                clang_command.arg("-Wl,-undefined,dynamic_lookup");
+            }
+        }
+        _ => internal_error!(
+            "TODO gracefully handle sanitizers on unsupported target: {:?}",
+            target
+        ),
+    }
+
+    if link_type == LinkType::Dylib {
+        clang_command.arg("-shared");
+    }
+
+    clang_command.arg("-o").arg(&output_path);
+
+    debug_print_command(&clang_command);
+
+    Ok((clang_command.spawn()?, output_path))
+}
+
+/// Archives the input object files into a static library, rather than linking them.
+/// The host is not included; the program that links the library provides it.
+fn link_static_lib(
+    target: Target,
+    output_path: PathBuf,
+    input_paths: &[&str],
+) -> io::Result<(Child, PathBuf)> {
+    let output_path = output_path.with_extension(target.static_library_file_ext());
+
+    // `ar` adds to an existing archive, so start from scratch.
+    if output_path.exists() {
+        fs::remove_file(&output_path)?;
+    }
+
+    let mut ar_command = zig();
+
+    ar_command
+        .args(["ar", "rcs", output_path.to_str().unwrap()])
+        .args(input_paths);
+
+    debug_print_command(&ar_command);
+
+    let child = ar_command.spawn()?;
+
+    Ok((child, output_path))
+}
+
 /// Same format as the precompiled host filename, except with a file extension like ".o" or ".obj"
 pub fn legacy_host_file(target: Target, platform_main_roc: &Path) -> PathBuf {
     let lib_ext = target.static_library_file_ext();
@@ -198,7 +317,7 @@ pub fn build_zig_host_native(
         zig_cmd.args(["-mcpu", "x86_64"]);
     }
 
-    if matches!(opt_level, OptLevel::Optimize) {
+    if matches!(opt_level, OptLevel::Moderate | OptLevel::Optimize) {
         zig_cmd.args(["-O", "ReleaseSafe"]);
     } else if matches!(opt_level, OptLevel::Size) {
         zig_cmd.args(["-O", "ReleaseSmall", "-fno-strip"]);
@@ -257,7 +376,7 @@ pub fn build_zig_host_native(
         target,
     ]);
 
-    if matches!(opt_level, OptLevel::Optimize) {
+    if matches!(opt_level, OptLevel::Moderate | OptLevel::Optimize) {
         zig_cmd.args(&["-O", "ReleaseSafe"]);
     } else if matches!(opt_level, OptLevel::Size) {
         zig_cmd.args(&["-O", "ReleaseSmall"]);
@@ -306,7 +425,7 @@ pub fn build_zig_host_wasm32(
             "-fstrip",
         ]);
 
-    if matches!(opt_level, OptLevel::Optimize) {
+    if matches!(opt_level, OptLevel::Moderate | OptLevel::Optimize) {
         zig_cmd.args(["-O", "ReleaseSafe"]);
     } else if matches!(opt_level, OptLevel::Size) {
         zig_cmd.args(["-O", "ReleaseSmall"]);
@@ -376,7 +495,7 @@ pub fn build_c_host_native(
     } else {
         clang_cmd.args(["-fPIC", "-c"]);
     }
-    if matches!(opt_level, OptLevel::Optimize) {
+    if matches!(opt_level, OptLevel::Moderate | OptLevel::Optimize) {
         clang_cmd.arg("-O3");
     } else if matches!(opt_level, OptLevel::Size) {
         clang_cmd.arg("-Os");
@@ -427,7 +546,7 @@ pub fn build_swift_host_native(
         command.args(["-import-objc-header", objc_header]);
     }
 
-    if matches!(opt_level, OptLevel::Optimize) {
+    if matches!(opt_level, OptLevel::Moderate | OptLevel::Optimize) {
         command.arg("-O");
     } else if matches!(opt_level, OptLevel::Size) {
         command.arg("-Osize");
@@ -549,7 +668,10 @@ pub fn rebuild_host(
 
         cargo_cmd.arg("build").current_dir(cargo_dir);
         // Rust doesn't expose size without editing the cargo.toml. Instead just use release.
-        if matches!(opt_level, OptLevel::Optimize | OptLevel::Size) {
+        if matches!(
+            opt_level,
+            OptLevel::Moderate | OptLevel::Optimize | OptLevel::Size
+        ) {
             cargo_cmd.arg("--release");
         }
 
@@ -628,7 +750,7 @@ pub fn rebuild_host(
             "-o",
             rust_host_dest.to_str().unwrap(),
         ]);
-        if matches!(opt_level, OptLevel::Optimize) {
+        if matches!(opt_level, OptLevel::Moderate | OptLevel::Optimize) {
             rustc_cmd.arg("-O");
         } else if matches!(opt_level, OptLevel::Size) {
             rustc_cmd.args(["-C", "opt-level=s"]);
@@ -732,7 +854,10 @@ pub fn rebuild_host(
 // there can be multiple release folders, one in target and one in target/x86_64-unknown-linux-musl,
 // we want the one that was most recently used
 fn find_used_target_sub_folder(opt_level: OptLevel, target_folder: PathBuf) -> PathBuf {
-    let out_folder_name = if matches!(opt_level, OptLevel::Optimize | OptLevel::Size) {
+    let out_folder_name = if matches!(
+        opt_level,
+        OptLevel::Moderate | OptLevel::Optimize | OptLevel::Size
+    ) {
         "release"
     } else {
         "debug"
@@ -952,7 +1077,7 @@ fn link_linux(
                     .collect::<Vec<String>>()
                     .join("\n");
                 eprintln!("We looked in the following directories:\n{dirs}");
-                process::exit(1);
+                process::exit(exit_code::IO_OR_USAGE_ERROR);
             }
         };
 
@@ -997,7 +1122,10 @@ fn link_linux(
 
             (["-shared"], output_path)
         }
-        LinkType::None => internal_error!("link_linux should not be called with link type of none"),
+        LinkType::None | LinkType::StaticLib => internal_error!(
+            "link_linux should not be called with link type of {:?}",
+            link_type
+        ),
     };
 
     let env_path = env::var("PATH").unwrap_or_else(|_| "".to_string());
@@ -1069,7 +1197,10 @@ fn link_macos(
 
             (vec!["-dylib", "-undefined", "dynamic_lookup"], output_path)
         }
-        LinkType::None => internal_error!("link_macos should not be called with link type of none"),
+        LinkType::None | LinkType::StaticLib => internal_error!(
+            "link_macos should not be called with link type of {:?}",
+            link_type
+        ),
     };
 
     let arch = match target.architecture() {
@@ -1288,6 +1419,9 @@ fn link_windows(
             Ok((child, output_path))
         }
         LinkType::None => todo!(),
+        LinkType::StaticLib => {
+            internal_error!("link_windows should not be called with link type of StaticLib")
+        }
     }
 }
 
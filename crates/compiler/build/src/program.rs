@@ -1,21 +1,24 @@
 use crate::link::{
-    legacy_host_file, link, preprocess_host_wasm32, rebuild_host, LinkType, LinkingStrategy,
+    legacy_host_file, link, link_with_sanitizer, preprocess_host_wasm32, rebuild_host, LinkType,
+    LinkingStrategy, Sanitizer,
 };
 use bumpalo::collections::CollectIn;
 use bumpalo::Bump;
 use inkwell::memory_buffer::MemoryBuffer;
-use roc_error_macros::internal_error;
+use roc_error_macros::{exit_code, internal_error};
 use roc_gen_dev::AssemblyBackendMode;
-use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
+use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode, SourceLocations};
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
+use roc_load::progress::{ProgressEvent, ProgressReporter};
 use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, FunctionKind, LoadConfig, LoadMonomorphizedError,
-    LoadedModule, LoadingProblem, MonomorphizedModule, Threading,
+    LoadedModule, LoadingProblem, MonomorphizedModule, NumericDefaults, Tabs, Threading,
 };
+use roc_module::symbol::PackageQualified;
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::{
-    cli::{report_problems, Problems},
+    cli::{report_problems_to, Diagnostic, Problems},
     report::{RenderTarget, DEFAULT_PALETTE},
 };
 use roc_target::{Architecture, Target};
@@ -38,24 +41,52 @@ pub struct CodeGenTiming {
     pub total: Duration,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
-    report_problems(
+/// Prints the problems, unless there's a progress reporter which
+/// [reports diagnostics](ProgressReporter::reports_diagnostics), in which case they go to it.
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    progress: Option<&ProgressReporter>,
+) -> Problems {
+    let mut on_diagnostic = diagnostic_reporter(progress);
+
+    report_problems_to(
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        &loaded.platform_hints,
+        on_diagnostic
+            .as_mut()
+            .map(|f| f as &mut dyn FnMut(Diagnostic)),
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
-    report_problems(
+/// Prints the problems, unless there's a progress reporter which
+/// [reports diagnostics](ProgressReporter::reports_diagnostics), in which case they go to it.
+pub fn report_problems_typechecked(
+    loaded: &mut LoadedModule,
+    progress: Option<&ProgressReporter>,
+) -> Problems {
+    let mut on_diagnostic = diagnostic_reporter(progress);
+
+    report_problems_to(
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        &loaded.platform_hints,
+        on_diagnostic
+            .as_mut()
+            .map(|f| f as &mut dyn FnMut(Diagnostic)),
     )
 }
 
+fn diagnostic_reporter(progress: Option<&ProgressReporter>) -> Option<impl FnMut(Diagnostic) + '_> {
+    let progress = progress.filter(|progress| progress.reports_diagnostics())?;
+
+    Some(move |diagnostic: Diagnostic| progress.report(&ProgressEvent::Diagnostic(diagnostic)))
+}
+
 pub enum CodeObject {
     MemoryBuffer(MemoryBuffer),
     Vector(Vec<u8>),
@@ -84,8 +115,78 @@ pub struct CodeGenOptions {
     pub backend: CodeGenBackend,
     pub opt_level: OptLevel,
     pub emit_debug_info: bool,
-    pub emit_llvm_ir: bool,
+    pub emit: EmitArtifacts,
     pub fuzz: bool,
+    /// Keep Roc function names readable and their frames walkable, for profilers like `perf`.
+    pub profiling: bool,
+    /// Instrument the app for this sanitizer, and link in its runtime.
+    pub sanitizer: Option<Sanitizer>,
+    /// Use the builtins with atomic refcounting, so the host can share Roc values across threads.
+    /// The host must use roc_std's `atomic-refcount` feature too. Only supported by the LLVM backend.
+    pub atomic_refcount: bool,
+}
+
+/// Intermediate artifacts to write to disk during code gen (e.g. for `roc build --emit`).
+/// Each one is written next to the .roc file, with the extension given by [EmitArtifacts::path].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmitArtifacts {
+    pub llvm_ir: bool,
+    pub llvm_bc: bool,
+    pub asm: bool,
+    pub obj: bool,
+    pub mono_ir: bool,
+    pub size_report: bool,
+}
+
+impl EmitArtifacts {
+    /// The names accepted by `roc build --emit`.
+    pub const NAMES: [&'static str; 6] =
+        ["llvm-ir", "llvm-bc", "asm", "obj", "mono-ir", "size-report"];
+
+    /// Enable the artifact with the given name (one of [EmitArtifacts::NAMES]).
+    /// Returns false if the name was not recognized.
+    pub fn enable(&mut self, name: &str) -> bool {
+        let flag = match name {
+            "llvm-ir" => &mut self.llvm_ir,
+            "llvm-bc" => &mut self.llvm_bc,
+            "asm" => &mut self.asm,
+            "obj" => &mut self.obj,
+            "mono-ir" => &mut self.mono_ir,
+            "size-report" => &mut self.size_report,
+            _ => return false,
+        };
+
+        *flag = true;
+
+        true
+    }
+
+    /// The enabled artifacts which only the LLVM backend can produce.
+    pub fn llvm_only(&self) -> impl Iterator<Item = &'static str> {
+        [
+            (self.llvm_ir, "llvm-ir"),
+            (self.llvm_bc, "llvm-bc"),
+            (self.asm, "asm"),
+            (self.size_report, "size-report"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+    }
+
+    /// Where the artifact with the given name is written, for the given .roc file.
+    pub fn path(roc_file_path: &Path, name: &str) -> PathBuf {
+        let extension = match name {
+            "llvm-ir" => "ll",
+            "llvm-bc" => "bc",
+            "asm" => "s",
+            "obj" => "o",
+            "mono-ir" => "mono",
+            "size-report" => "sizes",
+            _ => internal_error!("Unknown artifact to emit: {name:?}"),
+        };
+
+        roc_file_path.with_extension(extension)
+    }
 }
 
 type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
@@ -102,11 +203,26 @@ pub fn gen_from_mono_module<'a>(
 ) -> GenFromMono<'a> {
     let path = roc_file_path;
     let debug = code_gen_options.emit_debug_info;
-    let emit_llvm_ir = code_gen_options.emit_llvm_ir;
+    let emit = code_gen_options.emit;
     let fuzz = code_gen_options.fuzz;
+    let profiling = code_gen_options.profiling;
+    let sanitizer = code_gen_options.sanitizer;
+    let atomic_refcount = code_gen_options.atomic_refcount;
     let opt = code_gen_options.opt_level;
 
-    match code_gen_options.backend {
+    if emit.mono_ir {
+        let mono_ir_file = EmitArtifacts::path(path, "mono-ir");
+        let procs = loaded
+            .procedures
+            .values()
+            .map(|proc| proc.to_pretty(&loaded.layout_interner, 200, true))
+            .collect::<Vec<_>>();
+
+        eprintln!("Emitting mono IR to {}", mono_ir_file.display());
+        std::fs::write(&mono_ir_file, procs.join("\n")).unwrap();
+    }
+
+    let gen_from_mono = match code_gen_options.backend {
         CodeGenBackend::Wasm => gen_from_mono_module_dev(
             arena,
             loaded,
@@ -131,10 +247,22 @@ pub fn gen_from_mono_module<'a>(
             opt,
             backend_mode,
             debug,
-            emit_llvm_ir,
+            emit,
             fuzz,
+            profiling,
+            sanitizer,
+            atomic_refcount,
         ),
+    };
+
+    if emit.obj {
+        let obj_file = EmitArtifacts::path(path, "obj");
+
+        eprintln!("Emitting object file to {}", obj_file.display());
+        std::fs::write(&obj_file, &*gen_from_mono.0).unwrap();
     }
+
+    gen_from_mono
 }
 
 // TODO how should imported modules factor into this? What if those use builtins too?
@@ -149,8 +277,11 @@ fn gen_from_mono_module_llvm<'a>(
     opt_level: OptLevel,
     backend_mode: LlvmBackendMode,
     emit_debug_info: bool,
-    emit_llvm_ir: bool,
+    emit: EmitArtifacts,
     fuzz: bool,
+    profiling: bool,
+    sanitizer: Option<Sanitizer>,
+    atomic_refcount: bool,
 ) -> GenFromMono<'a> {
     use crate::target::{self, convert_opt_level};
     use inkwell::attributes::{Attribute, AttributeLoc};
@@ -162,14 +293,14 @@ fn gen_from_mono_module_llvm<'a>(
 
     // Generate the binary
     let context = Context::create();
-    let module = arena.alloc(module_from_builtins(target, &context, "app"));
-
-    let app_ll_file = {
-        let mut roc_file_path_buf = PathBuf::from(roc_file_path);
-        roc_file_path_buf.set_extension("ll");
+    let module = arena.alloc(module_from_builtins(
+        target,
+        &context,
+        "app",
+        atomic_refcount,
+    ));
 
-        roc_file_path_buf
-    };
+    let app_ll_file = EmitArtifacts::path(roc_file_path, "llvm-ir");
 
     let kind_id = Attribute::get_named_enum_kind_id("alwaysinline");
     debug_assert!(kind_id > 0);
@@ -196,7 +327,8 @@ fn gen_from_mono_module_llvm<'a>(
     }
 
     let builder = context.create_builder();
-    let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let (dibuilder, compile_unit) =
+        roc_gen_llvm::llvm::build::Env::new_debug_info_for_file(module, roc_file_path);
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
     // Compile and add all the Procs before adding main
@@ -217,6 +349,20 @@ fn gen_from_mono_module_llvm<'a>(
             .keys()
             .copied()
             .collect(),
+
+        source_locations: SourceLocations {
+            files: loaded
+                .sources
+                .iter()
+                .map(|(module_id, (path, _))| (*module_id, path.clone()))
+                .collect(),
+            lines: loaded.definition_lines,
+            sources: loaded
+                .sources
+                .into_iter()
+                .map(|(module_id, (_, source))| (module_id, source))
+                .collect(),
+        },
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -242,6 +388,16 @@ fn gen_from_mono_module_llvm<'a>(
         EntryPoint::Test => roc_mono::ir::EntryPoint::Expects { symbols: &[] },
     };
 
+    let proc_symbols = if profiling {
+        loaded
+            .procedures
+            .keys()
+            .map(|(symbol, _)| *symbol)
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
     roc_gen_llvm::llvm::build::build_procedures(
         &env,
         &loaded.layout_interner,
@@ -253,6 +409,18 @@ fn gen_from_mono_module_llvm<'a>(
         &loaded.glue_layouts,
     );
 
+    if profiling {
+        roc_gen_llvm::llvm::build::prepare_for_profiling(&env, proc_symbols);
+    }
+
+    if matches!(opt_level, OptLevel::Size) {
+        roc_gen_llvm::llvm::build::prepare_for_size(&env);
+    }
+
+    if sanitizer == Some(Sanitizer::Address) {
+        roc_gen_llvm::llvm::build::prepare_for_address_sanitizer(&env);
+    }
+
     // We are now finished building the LLVM IR.
     let generate_final_ir = all_code_gen_start.elapsed();
     let code_gen_object_start = Instant::now();
@@ -283,8 +451,47 @@ fn gen_from_mono_module_llvm<'a>(
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
 
+    if emit.size_report {
+        let size_report_file = EmitArtifacts::path(roc_file_path, "size-report");
+
+        eprintln!("Emitting size report to {}", size_report_file.display());
+        std::fs::write(&size_report_file, size_report(module)).unwrap();
+    }
+
+    if emit.llvm_bc {
+        let app_bc_file = EmitArtifacts::path(roc_file_path, "llvm-bc");
+
+        eprintln!("Emitting LLVM bitcode to {}", app_bc_file.display());
+
+        if !module.write_bitcode_to_path(&app_bc_file) {
+            internal_error!("Writing LLVM bitcode to {:?} failed", app_bc_file);
+        }
+    }
+
+    if emit.asm {
+        let app_asm_file = EmitArtifacts::path(roc_file_path, "asm");
+
+        match target.architecture() {
+            Architecture::X86_64 | Architecture::X86_32 | Architecture::Aarch64 => {
+                let target_machine =
+                    target::target_machine(target, convert_opt_level(opt_level), RelocMode::PIC)
+                        .unwrap();
+
+                eprintln!("Emitting assembly to {}", app_asm_file.display());
+                target_machine
+                    .write_to_file(env.module, FileType::Assembly, &app_asm_file)
+                    .expect("Writing assembly file failed");
+            }
+            _ => eprintln!(
+                "Not emitting assembly, because it is not supported for {:?}",
+                target.architecture()
+            ),
+        }
+    }
+
     let gen_sanitizers = cfg!(feature = "sanitizers") && std::env::var("ROC_SANITIZERS").is_ok();
-    let memory_buffer = if fuzz || gen_sanitizers {
+    let sanitizer_pass = sanitizer.and_then(Sanitizer::llvm_pass);
+    let memory_buffer = if fuzz || gen_sanitizers || sanitizer_pass.is_some() {
         let dir = tempfile::tempdir().unwrap();
         let dir = dir.into_path();
 
@@ -311,6 +518,7 @@ fn gen_from_mono_module_llvm<'a>(
                 "-sanitizer-coverage-trace-compares",
             ]);
         }
+        passes.extend(sanitizer_pass);
         if gen_sanitizers {
             for sanitizer in std::env::var("ROC_SANITIZERS")
                 .unwrap()
@@ -355,7 +563,7 @@ fn gen_from_mono_module_llvm<'a>(
 
         assert!(opt_output.stderr.is_empty(), "{opt_output:#?}");
 
-        if emit_llvm_ir {
+        if emit.llvm_ir {
             eprintln!("Emitting LLVM IR to {}", &app_ll_file.display());
 
             std::fs::copy(temp_app_processed_file, app_ll_file).unwrap();
@@ -384,7 +592,7 @@ fn gen_from_mono_module_llvm<'a>(
 
         MemoryBuffer::create_from_file(&temp_app_o_file).expect("memory buffer creation works")
     } else {
-        if emit_llvm_ir {
+        if emit.llvm_ir {
             eprintln!("Emitting LLVM IR to {}", &app_ll_file.display());
             module.print_to_file(&app_ll_file).unwrap();
         }
@@ -430,6 +638,47 @@ fn gen_from_mono_module_llvm<'a>(
     )
 }
 
+/// Lists the functions in `module` from biggest to smallest, by how many LLVM instructions they
+/// have, so it's easy to see what takes up the most space in the compiled program.
+fn size_report(module: &inkwell::module::Module) -> String {
+    use std::fmt::Write;
+
+    let mut sizes: Vec<(String, usize)> = module
+        .get_functions()
+        .filter(|function| function.count_basic_blocks() > 0)
+        .map(|function| {
+            let mut instructions = 0;
+
+            for block in function.get_basic_blocks() {
+                let mut instruction = block.get_first_instruction();
+
+                while let Some(current) = instruction {
+                    instructions += 1;
+                    instruction = current.get_next_instruction();
+                }
+            }
+
+            let name = function.get_name().to_string_lossy().into_owned();
+
+            (name, instructions)
+        })
+        .collect();
+
+    sizes.sort_by(|(name1, size1), (name2, size2)| size2.cmp(size1).then(name1.cmp(name2)));
+
+    let total: usize = sizes.iter().map(|(_, size)| size).sum();
+    let mut report = String::new();
+
+    writeln!(report, "{:>12}  function", "instructions").unwrap();
+    writeln!(report, "{total:>12}  (all {} functions)", sizes.len()).unwrap();
+
+    for (name, size) in sizes {
+        writeln!(report, "{size:>12}  {name}").unwrap();
+    }
+
+    report
+}
+
 #[cfg(feature = "target-wasm32")]
 fn gen_from_mono_module_dev<'a>(
     arena: &'a bumpalo::Bump,
@@ -616,6 +865,8 @@ pub struct BuiltFile<'a> {
     pub problems: Problems,
     pub total_time: Duration,
     pub expect_metadata: ExpectMetadata<'a>,
+    /// The main.roc of the platform the app was built with
+    pub platform_main_roc: PathBuf,
 }
 
 pub enum BuildOrdering {
@@ -657,7 +908,7 @@ pub fn handle_error_module(
 ) -> std::io::Result<i32> {
     debug_assert!(module.total_problems() > 0);
 
-    let problems = report_problems_typechecked(&mut module);
+    let problems = report_problems_typechecked(&mut module, None);
 
     problems.print_error_warning_count(total_time);
 
@@ -680,13 +931,13 @@ pub fn handle_loading_problem(problem: LoadingProblem) -> std::io::Result<i32> {
     match problem {
         LoadingProblem::FormattedReport(report) => {
             print!("{report}");
-            Ok(1)
+            Ok(exit_code::PARSE_OR_CANONICALIZE_ERRORS)
         }
         _ => {
             // TODO: tighten up the types here, we should always end up with a
             // formatted report from load.
             println!("Failed with error: {problem:?}");
-            Ok(1)
+            Ok(exit_code::INTERNAL_COMPILER_ERROR)
         }
     }
 }
@@ -708,6 +959,9 @@ pub fn standard_load_config(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode,
+        progress: None,
+        tabs: Tabs::from_env(),
+        numeric_defaults: NumericDefaults::from_env(),
     }
 }
 
@@ -727,6 +981,7 @@ pub fn build_file<'a>(
     out_path: Option<&Path>,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
+    let progress = load_config.progress.clone();
 
     // Step 1: compile the app and generate the .o file
     let loaded = roc_load::load_and_monomorphize(
@@ -751,6 +1006,7 @@ pub fn build_file<'a>(
         loaded,
         compilation_start,
         out_path,
+        progress,
     )
 }
 
@@ -768,6 +1024,7 @@ fn build_loaded_file<'a>(
     loaded: roc_load::MonomorphizedModule<'a>,
     compilation_start: Instant,
     out_path: Option<&Path>,
+    progress: Option<ProgressReporter>,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let platform_main_roc = match &loaded.entry_point {
         EntryPoint::Executable { platform_path, .. } => platform_path.to_path_buf(),
@@ -838,13 +1095,16 @@ fn build_loaded_file<'a>(
     };
 
     // We don't need to spawn a rebuild thread when using a prebuilt host.
-    let rebuild_thread = if matches!(link_type, LinkType::Dylib | LinkType::None) {
+    let rebuild_thread = if matches!(
+        link_type,
+        LinkType::Dylib | LinkType::StaticLib | LinkType::None
+    ) {
         None
     } else if is_platform_prebuilt {
         if !preprocessed_host_path.exists() {
             invalid_prebuilt_platform(prebuilt_requested, preprocessed_host_path);
 
-            std::process::exit(1);
+            std::process::exit(exit_code::IO_OR_USAGE_ERROR);
         }
 
         if linking_strategy == LinkingStrategy::Surgical {
@@ -906,7 +1166,7 @@ fn build_loaded_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, progress.as_ref());
     let loaded = loaded;
 
     let opt_rebuild_timing = if let Some(rebuild_thread) = rebuild_thread {
@@ -951,6 +1211,12 @@ fn build_loaded_file<'a>(
     buf.push('\n');
     report_timing(buf, "Total", code_gen_timing.total);
 
+    if let Some(progress) = &progress {
+        progress.report(&ProgressEvent::CodeGen {
+            duration: code_gen_timing.total,
+        });
+    }
+
     let compilation_end = compilation_start.elapsed();
     let size = roc_app_bytes.len();
 
@@ -1013,7 +1279,10 @@ fn build_loaded_file<'a>(
 
             let mut inputs = vec![app_o_file.to_str().unwrap()];
 
-            if !matches!(link_type, LinkType::Dylib | LinkType::None) {
+            if !matches!(
+                link_type,
+                LinkType::Dylib | LinkType::StaticLib | LinkType::None
+            ) {
                 // the host has been compiled into a .o or .obj file
                 inputs.push(preprocessed_host_path.as_path().to_str().unwrap());
             }
@@ -1022,8 +1291,17 @@ fn build_loaded_file<'a>(
                 inputs.push(builtins_host_tempfile.path().to_str().unwrap());
             }
 
-            let (mut child, _) = link(target, output_exe_path.clone(), &inputs, link_type)
-                .map_err(|_| todo!("gracefully handle `ld` failing to spawn."))?;
+            let (mut child, _) = match code_gen_options.sanitizer {
+                Some(sanitizer) => link_with_sanitizer(
+                    target,
+                    output_exe_path.clone(),
+                    &inputs,
+                    link_type,
+                    sanitizer,
+                ),
+                None => link(target, output_exe_path.clone(), &inputs, link_type),
+            }
+            .map_err(|_| todo!("gracefully handle `ld` failing to spawn."))?;
 
             let exit_status = child
                 .wait()
@@ -1044,6 +1322,12 @@ fn build_loaded_file<'a>(
 
     let linking_time = link_start.elapsed();
 
+    if let Some(progress) = &progress {
+        progress.report(&ProgressEvent::Link {
+            duration: linking_time,
+        });
+    }
+
     if emit_timings {
         println!("Finished linking in {} ms\n", linking_time.as_millis());
     }
@@ -1055,6 +1339,7 @@ fn build_loaded_file<'a>(
         problems,
         total_time,
         expect_metadata,
+        platform_main_roc,
     })
 }
 
@@ -1186,24 +1471,13 @@ pub fn check_file<'a>(
     emit_timings: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
+    progress: Option<ProgressReporter>,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
-    // only used for generating errors. We don't do code generation, so hardcoding should be fine
-    // we need monomorphization for when exhaustiveness checking
-    let target = Target::LinuxX64;
-
     // Step 1: compile the app and generate the .o file
 
-    let load_config = LoadConfig {
-        target,
-        function_kind: FunctionKind::from_env(),
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
-        palette: DEFAULT_PALETTE,
-        threading,
-        exec_mode: ExecutionMode::Check,
-    };
+    let load_config = check_load_config(threading, progress.clone());
     let mut loaded = roc_load::load_and_typecheck(
         arena,
         roc_file_path,
@@ -1254,7 +1528,69 @@ pub fn check_file<'a>(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
-    Ok((report_problems_typechecked(&mut loaded), compilation_end))
+    Ok((
+        report_problems_typechecked(&mut loaded, progress.as_ref()),
+        compilation_end,
+    ))
+}
+
+/// Like [`check_file`], but also runs the lints from `roc_lint` over the modules in the root
+/// module's own package (not the platform's, or other packages'), and reports what they find.
+pub fn lint_file<'a>(
+    arena: &'a Bump,
+    roc_file_path: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    roc_cache_dir: RocCacheDir<'_>,
+    threading: Threading,
+    progress: Option<ProgressReporter>,
+) -> Result<(Problems, Duration), LoadingProblem<'a>> {
+    let compilation_start = Instant::now();
+
+    let load_config = check_load_config(threading, progress.clone());
+    let mut loaded = roc_load::load_and_typecheck(
+        arena,
+        roc_file_path,
+        opt_main_path,
+        roc_cache_dir,
+        load_config,
+    )?;
+
+    for (module_id, decls) in loaded.declarations_by_id.iter() {
+        let in_root_package = matches!(
+            loaded.interns.module_ids.get_name(*module_id),
+            Some(PackageQualified::Unqualified(_))
+        );
+
+        if in_root_package && !module_id.is_builtin() {
+            loaded
+                .can_problems
+                .entry(*module_id)
+                .or_default()
+                .extend(roc_lint::lint_decls(decls, &loaded.interns));
+        }
+    }
+
+    Ok((
+        report_problems_typechecked(&mut loaded, progress.as_ref()),
+        compilation_start.elapsed(),
+    ))
+}
+
+pub fn check_load_config(threading: Threading, progress: Option<ProgressReporter>) -> LoadConfig {
+    LoadConfig {
+        // only used for generating errors. We don't do code generation, so hardcoding should be fine
+        // we need monomorphization for when exhaustiveness checking
+        target: Target::LinuxX64,
+        function_kind: FunctionKind::from_env(),
+        // TODO: expose this from CLI?
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        progress,
+        tabs: Tabs::from_env(),
+        numeric_defaults: NumericDefaults::from_env(),
+    }
 }
 
 pub fn build_str_test<'a>(
@@ -1269,8 +1605,11 @@ pub fn build_str_test<'a>(
         backend: CodeGenBackend::Llvm(LlvmBackendMode::Binary),
         opt_level: OptLevel::Normal,
         emit_debug_info: false,
-        emit_llvm_ir: false,
+        emit: EmitArtifacts::default(),
         fuzz: false,
+        profiling: false,
+        sanitizer: None,
+        atomic_refcount: false,
     };
 
     let emit_timings = false;
@@ -1311,6 +1650,7 @@ pub fn build_str_test<'a>(
         loaded,
         compilation_start,
         None,
+        None,
     )
 }
 
@@ -1325,6 +1665,8 @@ fn with_output_extension(
             // Additive linking and no linking both output the object file type.
             path.with_extension(target.object_file_ext())
         }
+        (_, LinkType::Dylib) => path.with_extension(target.shared_library_file_ext()),
+        (_, LinkType::StaticLib) => path.with_extension(target.static_library_file_ext()),
         _ => path.with_extension(target.executable_file_ext().unwrap_or_default()),
     }
 }
@@ -27,21 +27,41 @@ fn main() {
 
     // LLVM .bc FILES
 
-    generate_bc_file(&bitcode_path, "ir", "builtins-host");
+    // Apps built with `--atomic-refcount` use the builtins with atomic refcounting,
+    // so we build both variants.
+    for atomic_refcount in [false, true] {
+        generate_bc_file(&bitcode_path, "ir", "builtins-host", atomic_refcount);
+
+        if !DEBUG {
+            generate_bc_file(
+                &bitcode_path,
+                "ir-wasm32",
+                "builtins-wasm32",
+                atomic_refcount,
+            );
+        }
 
-    if !DEBUG {
-        generate_bc_file(&bitcode_path, "ir-wasm32", "builtins-wasm32");
+        generate_bc_file(&bitcode_path, "ir-x86", "builtins-x86", atomic_refcount);
+        generate_bc_file(
+            &bitcode_path,
+            "ir-x86_64",
+            "builtins-x86_64",
+            atomic_refcount,
+        );
+        generate_bc_file(
+            &bitcode_path,
+            "ir-aarch64",
+            "builtins-aarch64",
+            atomic_refcount,
+        );
+        generate_bc_file(
+            &bitcode_path,
+            "ir-windows-x86_64",
+            "builtins-windows-x86_64",
+            atomic_refcount,
+        );
     }
 
-    generate_bc_file(&bitcode_path, "ir-x86", "builtins-x86");
-    generate_bc_file(&bitcode_path, "ir-x86_64", "builtins-x86_64");
-    generate_bc_file(&bitcode_path, "ir-aarch64", "builtins-aarch64");
-    generate_bc_file(
-        &bitcode_path,
-        "ir-windows-x86_64",
-        "builtins-windows-x86_64",
-    );
-
     get_zig_files(bitcode_path.as_path(), &|path| {
         let path: &Path = path;
         println!(
@@ -57,7 +77,10 @@ fn main() {
         .expect("Failed to delete temp dir zig_cache_dir.");
 }
 
-fn generate_bc_file(bitcode_path: &Path, zig_object: &str, file_name: &str) {
+fn generate_bc_file(bitcode_path: &Path, zig_object: &str, file_name: &str, atomic_refcount: bool) {
+    let file_name = variant_file_name(file_name, atomic_refcount);
+    let file_name = file_name.as_str();
+
     let mut ll_path = bitcode_path.join("zig-out").join(file_name);
     ll_path.set_extension("ll");
     let dest_ir_host = ll_path.to_str().expect("Invalid dest ir path");
@@ -75,13 +98,33 @@ fn generate_bc_file(bitcode_path: &Path, zig_object: &str, file_name: &str) {
 
     let mut zig_cmd = zig();
 
-    zig_cmd
-        .current_dir(bitcode_path)
-        .args(["build", zig_object, "-Drelease=true"]);
+    zig_cmd.current_dir(bitcode_path).args([
+        "build",
+        zig_object,
+        "-Drelease=true",
+        atomic_refcount_flag(atomic_refcount),
+    ]);
 
     run_command(zig_cmd, 0);
 }
 
+fn atomic_refcount_flag(atomic_refcount: bool) -> &'static str {
+    if atomic_refcount {
+        "-Datomic-refcount=true"
+    } else {
+        "-Datomic-refcount=false"
+    }
+}
+
+/// build.zig adds `-atomic` to the name of builtins that use atomic refcounting.
+fn variant_file_name(file_name: &str, atomic_refcount: bool) -> String {
+    match file_name.split_once('.') {
+        _ if !atomic_refcount => file_name.to_string(),
+        Some((stem, extension)) => format!("{stem}-atomic.{extension}"),
+        None => format!("{file_name}-atomic"),
+    }
+}
+
 pub fn get_lib_dir() -> PathBuf {
     // Currently we have the OUT_DIR variable which points to `/target/debug/build/roc_builtins-*/out/`.
     // So we just need to add "/bitcode" to that.
@@ -14,9 +14,17 @@ pub fn build(b: *Build) void {
     const fallback_main_path = "./src/main.zig";
     const main_path_desc = b.fmt("Override path to main.zig. Used by \"ir\" and \"test\". Defaults to \"{s}\". ", .{fallback_main_path});
     const main_path = .{ .path = b.option([]const u8, "main-path", main_path_desc) orelse fallback_main_path };
+    const atomic_refcount = b.option(bool, "atomic-refcount", "Use atomic instructions for refcount increments and decrements. Defaults to false.") orelse false;
+
+    const build_options = b.addOptions();
+    build_options.addOption(bool, "atomic_refcount", atomic_refcount);
+
+    // The compiler ships both variants, so the atomic one is installed under its own name.
+    const output_suffix = if (atomic_refcount) "-atomic" else "";
 
     // Tests
     const main_tests = b.addTest(.{ .root_source_file = main_path, .link_libc = true });
+    main_tests.addOptions("build_options", build_options);
     const test_step = b.step("test", "Run tests");
     test_step.dependOn(&b.addRunArtifact(main_tests).step);
 
@@ -34,22 +42,24 @@ pub fn build(b: *Build) void {
     const wasm32_target = makeWasm32Target();
 
     // LLVM IR
-    generateLlvmIrFile(b, mode, host_target, main_path, "ir", "builtins-host");
-    generateLlvmIrFile(b, mode, linux32_target, main_path, "ir-x86", "builtins-x86");
-    generateLlvmIrFile(b, mode, linux_x64_target, main_path, "ir-x86_64", "builtins-x86_64");
-    generateLlvmIrFile(b, mode, linux_aarch64_target, main_path, "ir-aarch64", "builtins-aarch64");
-    generateLlvmIrFile(b, mode, windows64_target, main_path, "ir-windows-x86_64", "builtins-windows-x86_64");
-    generateLlvmIrFile(b, mode, wasm32_target, main_path, "ir-wasm32", "builtins-wasm32");
+    generateLlvmIrFile(b, build_options, output_suffix, mode, host_target, main_path, "ir", "builtins-host");
+    generateLlvmIrFile(b, build_options, output_suffix, mode, linux32_target, main_path, "ir-x86", "builtins-x86");
+    generateLlvmIrFile(b, build_options, output_suffix, mode, linux_x64_target, main_path, "ir-x86_64", "builtins-x86_64");
+    generateLlvmIrFile(b, build_options, output_suffix, mode, linux_aarch64_target, main_path, "ir-aarch64", "builtins-aarch64");
+    generateLlvmIrFile(b, build_options, output_suffix, mode, windows64_target, main_path, "ir-windows-x86_64", "builtins-windows-x86_64");
+    generateLlvmIrFile(b, build_options, output_suffix, mode, wasm32_target, main_path, "ir-wasm32", "builtins-wasm32");
 
     // Generate Object Files
-    generateObjectFile(b, mode, host_target, main_path, "object", "builtins-host");
-    generateObjectFile(b, mode, windows64_target, main_path, "windows-x86_64-object", "builtins-windows-x86_64");
-    generateObjectFile(b, mode, wasm32_target, main_path, "wasm32-object", "builtins-wasm32");
+    generateObjectFile(b, build_options, output_suffix, mode, host_target, main_path, "object", "builtins-host");
+    generateObjectFile(b, build_options, output_suffix, mode, windows64_target, main_path, "windows-x86_64-object", "builtins-windows-x86_64");
+    generateObjectFile(b, build_options, output_suffix, mode, wasm32_target, main_path, "wasm32-object", "builtins-wasm32");
 }
 
 // TODO zig 0.9 can generate .bc directly, switch to that when it is released!
 fn generateLlvmIrFile(
     b: *Build,
+    build_options: *Build.Step.Options,
+    output_suffix: []const u8,
     mode: std.builtin.Mode,
     target: CrossTarget,
     main_path: LazyPath,
@@ -57,6 +67,7 @@ fn generateLlvmIrFile(
     object_name: []const u8,
 ) void {
     const obj = b.addObject(.{ .name = object_name, .root_source_file = main_path, .optimize = mode, .target = target, .use_llvm = true });
+    obj.addOptions("build_options", build_options);
     obj.strip = true;
     obj.disable_stack_probing = true;
     if (target.cpu_arch != .wasm32)
@@ -66,8 +77,8 @@ fn generateLlvmIrFile(
     _ = obj.getEmittedBin();
     const ir_file = obj.getEmittedLlvmIr();
     const bc_file = obj.getEmittedLlvmBc();
-    const install_ir = b.addInstallFile(ir_file, b.fmt("{s}.ll", .{object_name}));
-    const install_bc = b.addInstallFile(bc_file, b.fmt("{s}.bc", .{object_name}));
+    const install_ir = b.addInstallFile(ir_file, b.fmt("{s}{s}.ll", .{ object_name, output_suffix }));
+    const install_bc = b.addInstallFile(bc_file, b.fmt("{s}{s}.bc", .{ object_name, output_suffix }));
 
     const ir = b.step(step_name, "Build LLVM ir");
     ir.dependOn(&install_ir.step);
@@ -82,6 +93,8 @@ fn generateLlvmIrFile(
 // or something similar.
 fn generateObjectFile(
     b: *Build,
+    build_options: *Build.Step.Options,
+    output_suffix: []const u8,
     mode: std.builtin.Mode,
     target: CrossTarget,
     main_path: LazyPath,
@@ -89,6 +102,7 @@ fn generateObjectFile(
     object_name: []const u8,
 ) void {
     const obj = b.addObject(.{ .name = object_name, .root_source_file = main_path, .optimize = mode, .target = target, .use_llvm = true });
+    obj.addOptions("build_options", build_options);
     obj.strip = true;
     obj.link_function_sections = true;
     obj.force_pic = true;
@@ -103,7 +117,7 @@ fn generateObjectFile(
         "obj"
     else
         "o";
-    const install = b.addInstallFile(obj_file, b.fmt("{s}.{s}", .{ object_name, suffix }));
+    const install = b.addInstallFile(obj_file, b.fmt("{s}{s}.{s}", .{ object_name, output_suffix, suffix }));
 
     const obj_step = b.step(step_name, "Build object file for linking");
     obj_step.dependOn(&obj.step);
@@ -5,4 +5,9 @@ set -euxo pipefail
 
 # For non-native binaries, Zig test needs a "test command" it can use
 cargo build --locked --release -p roc_wasm_interp
-zig test -target wasm32-wasi-musl -O ReleaseFast src/main.zig --test-cmd ../../../../target/release/roc_wasm_interp --test-cmd-bin
+
+# build.zig normally provides the `build_options` module; mirror its defaults here
+mkdir -p zig-cache
+echo 'pub const atomic_refcount = false;' > zig-cache/build_options.zig
+
+zig test -target wasm32-wasi-musl -O ReleaseFast --mod build_options::zig-cache/build_options.zig --deps build_options src/main.zig --test-cmd ../../../../target/release/roc_wasm_interp --test-cmd-bin
@@ -1,5 +1,6 @@
 const std = @import("std");
 const builtin = @import("builtin");
+const build_options = @import("build_options");
 const Monotonic = std.builtin.AtomicOrder.Monotonic;
 
 const DEBUG_INCDEC = false;
@@ -181,7 +182,10 @@ const Refcount = enum {
     atomic,
 };
 
-const RC_TYPE = Refcount.normal;
+// Atomic refcounting lets hosts share Roc values across threads.
+// Enable it with `zig build -Datomic-refcount=true`; the compiler builds both variants,
+// and links the atomic one into apps built with `--atomic-refcount`.
+const RC_TYPE = if (build_options.atomic_refcount) Refcount.atomic else Refcount.normal;
 
 pub fn increfRcPtrC(ptr_to_refcount: *isize, amount: isize) callconv(.C) void {
     if (RC_TYPE == Refcount.none) return;
@@ -391,6 +391,7 @@ pub(crate) fn make_apply_symbol(
                 module_params: _,
             }) => {
                 references.insert_type_lookup(symbol, QualifiedReference::Unqualified);
+                env.check_deprecated(symbol, region);
                 Ok(symbol)
             }
             Err(problem) => {
@@ -406,6 +407,7 @@ pub(crate) fn make_apply_symbol(
                 module_params: _,
             }) => {
                 references.insert_type_lookup(symbol, QualifiedReference::Qualified);
+                env.check_deprecated(symbol, region);
                 Ok(symbol)
             }
             Err(problem) => {
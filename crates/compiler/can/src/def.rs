@@ -1044,10 +1044,16 @@ pub(crate) fn canonicalize_defs<'a>(
     // there are opaques that implement an ability using a value symbol). But, value symbols might
     // shadow symbols defined in a local ability def.
 
-    for either_index in loc_defs.tags.iter() {
+    for (index, either_index) in loc_defs.tags.iter().enumerate() {
         if let Ok(type_index) = either_index.split() {
             let type_def = &loc_defs.type_defs[type_index.index()];
             let pending_type_def = to_pending_type_def(env, type_def, scope, pattern_type);
+            if let PendingTypeDef::Alias { name, .. }
+            | PendingTypeDef::Opaque { name, .. }
+            | PendingTypeDef::Ability { name, .. } = &pending_type_def
+            {
+                record_doc_comment(env, loc_defs, index, *name, pattern_type);
+            }
             if let PendingTypeDef::Ability { name, members } = &pending_type_def {
                 pending_abilities_in_scope.insert(
                     name.value,
@@ -1074,6 +1080,15 @@ pub(crate) fn canonicalize_defs<'a>(
                 pattern_type,
             );
 
+            if let PendingValue::Def(pending_def) = &pending {
+                let loc_pattern = pending_def.loc_pattern();
+
+                if let Pattern::Identifier(symbol) = loc_pattern.value {
+                    let loc_symbol = Loc::at(loc_pattern.region, symbol);
+                    record_doc_comment(env, loc_defs, index, loc_symbol, pattern_type);
+                }
+            }
+
             pending_value_defs.push(Loc::at(region, pending));
         }
     }
@@ -1114,6 +1129,89 @@ pub(crate) fn canonicalize_defs<'a>(
     )
 }
 
+/// Looks at the doc comment directly above a def. Documented top-level defs are remembered so
+/// that the ones missing from `exposes` can be reported, and if the doc comment has a
+/// `Deprecated:` line, the message after the colon is remembered so that every use of the def
+/// can be reported. A `Tail-recursive` line marks the def as a function whose calls to itself
+/// must all be tail calls; that line alone doesn't count as documentation.
+fn record_doc_comment(
+    env: &mut Env,
+    defs: &Defs,
+    index: usize,
+    loc_symbol: Loc<Symbol>,
+    pattern_type: PatternType,
+) {
+    let mut has_docs = false;
+    let mut message = None;
+    let mut is_tail_recursive = false;
+
+    for comment_or_newline in &defs.spaces[defs.space_before[index].indices()] {
+        match comment_or_newline {
+            ast::CommentOrNewline::DocComment(doc) => {
+                let doc = doc.trim();
+
+                if is_tail_recursive_marker(doc) {
+                    is_tail_recursive = true;
+                } else {
+                    has_docs = true;
+                }
+
+                if let Some(rest) = doc.strip_prefix("Deprecated:") {
+                    message = Some(rest.trim());
+                }
+            }
+            ast::CommentOrNewline::Newline | ast::CommentOrNewline::LineComment(_) => {
+                // Only the doc comment block immediately above the def counts.
+                has_docs = false;
+                message = None;
+                is_tail_recursive = false;
+            }
+        }
+    }
+
+    if is_tail_recursive {
+        env.documented_tail_recursive.insert(loc_symbol.value);
+    }
+
+    if has_docs && matches!(pattern_type, PatternType::TopLevelDef) {
+        env.documented_top_level
+            .insert(loc_symbol.value, loc_symbol.region);
+    }
+
+    if let Some(message) = message {
+        env.deprecated.insert(loc_symbol.value, message.into());
+    }
+}
+
+/// Whether a doc comment line is `Tail-recursive`, optionally followed by punctuation or an
+/// explanation, like `Tail-recursive, so long lists can't overflow the stack.`
+fn is_tail_recursive_marker(doc: &str) -> bool {
+    match doc.strip_prefix("Tail-recursive") {
+        Some(rest) => !rest.starts_with(|c: char| c.is_alphanumeric()),
+        None => false,
+    }
+}
+
+/// Reports a function whose doc comment says it's tail-recursive, but which calls itself
+/// somewhere other than in tail position (or never calls itself at all).
+fn check_tail_recursive(env: &mut Env, loc_symbol: Loc<Symbol>, loc_body: &Loc<Expr>) {
+    let self_calls = crate::traverse::find_self_calls(loc_symbol.value, loc_body);
+
+    let non_tail_calls: Vec<Region> = self_calls
+        .iter()
+        .filter(|(_, is_tail)| !is_tail)
+        .map(|(region, _)| *region)
+        .collect();
+
+    if self_calls.is_empty() || !non_tail_calls.is_empty() {
+        env.problem(Problem::NotTailRecursive {
+            symbol: loc_symbol.value,
+            region: loc_symbol.region,
+            non_tail_calls,
+        });
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn canonicalize_value_defs<'a>(
     env: &mut Env<'a>,
@@ -1189,7 +1287,10 @@ fn canonicalize_value_defs<'a>(
     for (def_index, pending_def) in pending_value_defs.iter().enumerate() {
         let mut new_bindings = BindingsFromPattern::new(pending_def.loc_pattern()).peekable();
 
-        if new_bindings.peek().is_none() {
+        // A shadowed name was already reported; don't also call its def unnecessary.
+        let is_shadowed = matches!(pending_def.loc_pattern().value, Pattern::Shadowed(..));
+
+        if new_bindings.peek().is_none() && !is_shadowed {
             env.problem(Problem::NoIdentifiersIntroduced(
                 pending_def.loc_pattern().region,
             ));
@@ -2466,14 +2567,15 @@ fn canonicalize_pending_value_def<'a>(
             }
         }
         IngestedFile(loc_pattern, opt_loc_ann, path_literal) => {
-            let relative_path =
-                if let ast::StrLiteral::PlainLine(ingested_path) = path_literal.value {
-                    ingested_path
-                } else {
-                    todo!(
+            let relative_path = if let ast::StrLiteral::PlainLine(ingested_path)
+            | ast::StrLiteral::Raw(ingested_path) = path_literal.value
+            {
+                ingested_path
+            } else {
+                todo!(
                     "Only plain strings are supported. Other cases should be made impossible here"
                 );
-                };
+            };
 
             let mut file_path: PathBuf = env.module_path.into();
             // Remove the header file name and push the new path.
@@ -2610,6 +2712,14 @@ fn canonicalize_pending_body<'a>(
                 closure_data.recursive = is_recursive;
                 closure_data.name = *defined_symbol;
 
+                if env.documented_tail_recursive.contains(defined_symbol) {
+                    check_tail_recursive(
+                        env,
+                        Loc::at(loc_can_pattern.region, *defined_symbol),
+                        &closure_data.loc_body,
+                    );
+                }
+
                 let loc_can_expr = Loc::at(loc_expr.region, Expr::Closure(closure_data));
 
                 let def_references = DefReferences::Function(can_output.references.clone());
@@ -2975,7 +3085,7 @@ fn to_pending_type_def<'a>(
                         env.problem(roc_problem::can::Problem::Shadowing {
                             original_region: shadowed_symbol.region,
                             shadow,
-                            kind: ShadowKind::Variable,
+                            kind: ShadowKind::of_variable(shadowed_symbol.value),
                         });
                         // Pretend the member isn't a part of the ability
                         continue;
@@ -3033,7 +3143,7 @@ struct PendingModuleImportParams<'a> {
 pub struct IntroducedImport {
     module_id: ModuleId,
     region: Region,
-    exposed_symbols: Vec<(Symbol, Region)>,
+    pub(crate) exposed_symbols: Vec<(Symbol, Region)>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -3272,7 +3382,7 @@ fn to_pending_value_def<'a>(
                     env.problem(Problem::Shadowing {
                         original_region: original.region,
                         shadow,
-                        kind: ShadowKind::Variable
+                        kind: ShadowKind::of_variable(original.value)
                     });
 
                     return PendingValue::InvalidIngestedFile;
@@ -3342,6 +3452,17 @@ fn correct_mutual_recursive_type_alias(
             // Don't try to instantiate the alias itself in its own definition.
             to_instantiate.set(index, false);
 
+            // If this alias turns out to be illegally cyclic, this is the chain of other
+            // aliases we'll report it as going through on its way back to itself.
+            let others = if cycle.count_ones() > 1 {
+                alias_cycle_chain(&matrix, cycle, index)
+                    .into_iter()
+                    .map(|i| Loc::at(aliases[i].region, symbols_introduced[i]))
+                    .collect()
+            } else {
+                vec![]
+            };
+
             // Within a recursive group, we must instantiate all aliases like how they came to the
             // loop. e.g. given
             //
@@ -3448,7 +3569,7 @@ fn correct_mutual_recursive_type_alias(
                     env,
                     rec,
                     alias,
-                    vec![],
+                    others,
                     var_store,
                     &mut can_still_report_error,
                 );
@@ -3470,10 +3591,12 @@ fn correct_mutual_recursive_type_alias(
 
         if all_are_narrow {
             // This cycle is illegal!
-            let mut indices = cycle.iter_ones();
-            let first_index = indices.next().unwrap();
+            let first_index = cycle.first_one().unwrap();
 
-            let rest: Vec<Symbol> = indices.map(|i| symbols_introduced[i]).collect();
+            let rest: Vec<Loc<Symbol>> = alias_cycle_chain(&matrix, cycle, first_index)
+                .into_iter()
+                .map(|i| Loc::at(aliases[i].region, symbols_introduced[i]))
+                .collect();
 
             let alias_name = symbols_introduced[first_index];
             let alias = aliases.get_mut(first_index).unwrap();
@@ -3497,11 +3620,48 @@ fn correct_mutual_recursive_type_alias(
     unsafe { VecMap::zip(symbols_introduced, aliases) }
 }
 
+/// Find the shortest chain of aliases that `start` goes through to reference itself again,
+/// using only aliases in its `cycle`. The returned indices don't include `start` itself, so an
+/// empty chain means that `start` references itself directly.
+fn alias_cycle_chain(
+    matrix: &ReferenceMatrix,
+    cycle: &bitvec::slice::BitSlice,
+    start: usize,
+) -> Vec<usize> {
+    let mut came_from = vec![None; cycle.len()];
+    let mut queue = std::collections::VecDeque::from([start]);
+
+    while let Some(index) = queue.pop_front() {
+        for referenced in matrix.references_for(index) {
+            if referenced == start {
+                let mut chain = Vec::new();
+                let mut current = index;
+
+                while current != start {
+                    chain.push(current);
+                    current = came_from[current].unwrap();
+                }
+
+                chain.reverse();
+
+                return chain;
+            }
+
+            if cycle[referenced] && came_from[referenced].is_none() {
+                came_from[referenced] = Some(index);
+                queue.push_back(referenced);
+            }
+        }
+    }
+
+    Vec::new()
+}
+
 fn make_tag_union_of_alias_recursive(
     env: &mut Env,
     alias_name: Symbol,
     alias: &mut Alias,
-    others: Vec<Symbol>,
+    others: Vec<Loc<Symbol>>,
     var_store: &mut VarStore,
     can_report_cyclic_error: &mut bool,
 ) -> Result<(), ()> {
@@ -3585,7 +3745,7 @@ fn make_tag_union_recursive_help<'a, 'b>(
     infer_ext_in_output_variables: impl Iterator<Item = Type>,
     alias_kind: AliasKind,
     region: Region,
-    others: Vec<Symbol>,
+    others: Vec<Loc<Symbol>>,
     typ: &'b mut Type,
     var_store: &mut VarStore,
     can_report_cyclic_error: &mut bool,
@@ -3696,7 +3856,7 @@ fn mark_cyclic_alias(
     symbol: Symbol,
     alias_kind: AliasKind,
     region: Region,
-    others: Vec<Symbol>,
+    others: Vec<Loc<Symbol>>,
     report: bool,
 ) {
     *typ = Type::Error;
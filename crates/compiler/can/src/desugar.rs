@@ -354,7 +354,7 @@ pub fn desugar_expr<'a>(
         | Crash => loc_expr,
 
         Str(str_literal) => match str_literal {
-            StrLiteral::PlainLine(_) => loc_expr,
+            StrLiteral::PlainLine(_) | StrLiteral::Raw(_) => loc_expr,
             StrLiteral::Line(segments) => {
                 let region = loc_expr.region;
                 let value = Str(StrLiteral::Line(desugar_str_segments(env, scope, segments)));
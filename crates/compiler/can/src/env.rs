@@ -3,7 +3,7 @@ use std::path::Path;
 use crate::procedure::References;
 use crate::scope::{ModuleLookup, Scope, SymbolLookup};
 use bumpalo::Bump;
-use roc_collections::{MutMap, VecSet};
+use roc_collections::{MutMap, MutSet, VecMap, VecSet};
 use roc_module::ident::{Ident, ModuleName};
 use roc_module::symbol::{IdentIdsByModule, ModuleId, PQModuleName, PackageModuleIds, Symbol};
 use roc_problem::can::{Problem, RuntimeError};
@@ -39,6 +39,17 @@ pub struct Env<'a> {
 
     pub top_level_symbols: VecSet<Symbol>,
 
+    /// Definitions marked with a `## Deprecated:` doc comment, along with the message that
+    /// follows it. This includes definitions from imported modules.
+    pub deprecated: VecMap<Symbol, Box<str>>,
+
+    /// Top-level definitions that have a doc comment, and where their names are.
+    pub documented_top_level: VecMap<Symbol, Region>,
+
+    /// Definitions marked with a `## Tail-recursive` doc comment, which must only call themselves
+    /// in tail position.
+    pub documented_tail_recursive: VecSet<Symbol>,
+
     pub home_params_record: Option<(Symbol, Variable)>,
 
     pub arena: &'a Bump,
@@ -76,12 +87,26 @@ impl<'a> Env<'a> {
             qualified_type_lookups: VecSet::default(),
             tailcallable_symbol: None,
             top_level_symbols: VecSet::default(),
+            deprecated: VecMap::default(),
+            documented_top_level: VecMap::default(),
+            documented_tail_recursive: VecSet::default(),
             home_params_record: None,
             opt_shorthand,
             line_info: arena.alloc(None),
         }
     }
 
+    /// Warn about a use of a definition that was marked as deprecated.
+    pub fn check_deprecated(&mut self, symbol: Symbol, region: Region) {
+        if let Some(message) = self.deprecated.get(&symbol) {
+            self.problems.push(Problem::DeprecatedUse {
+                symbol,
+                region,
+                message: message.clone(),
+            });
+        }
+    }
+
     pub fn qualified_lookup(
         &mut self,
         scope: &Scope,
@@ -102,11 +127,13 @@ impl<'a> Env<'a> {
             }
             None => Err(RuntimeError::ModuleNotImported {
                 module_name: module_name.clone(),
+                ident: Ident::from(ident),
                 imported_modules: scope
                     .modules
                     .available_names()
                     .map(|string| string.as_ref().into())
                     .collect(),
+                modules_exposing_ident: self.modules_exposing(scope, ident),
                 region,
                 module_exists: self
                     .qualified_module_ids
@@ -126,7 +153,7 @@ impl<'a> Env<'a> {
         if let Some(module) = scope.modules.lookup_by_id(&module_id) {
             self.qualified_lookup_help(scope, module, ident, region)
         } else {
-            Err(self.module_exists_but_not_imported(scope, module_id, region))
+            Err(self.module_exists_but_not_imported(scope, module_id, ident, region))
         }
     }
 
@@ -198,7 +225,7 @@ impl<'a> Env<'a> {
                         exposed_values: exposed_ids.exposed_values(),
                     }),
                 },
-                _ => Err(self.module_exists_but_not_imported(scope, module.id, region)),
+                _ => Err(self.module_exists_but_not_imported(scope, module.id, ident, region)),
             }
         }
     }
@@ -207,6 +234,7 @@ impl<'a> Env<'a> {
         &self,
         scope: &Scope,
         module_id: ModuleId,
+        ident: &str,
         region: Region,
     ) -> RuntimeError {
         RuntimeError::ModuleNotImported {
@@ -216,16 +244,37 @@ impl<'a> Env<'a> {
                 .expect("Module ID known, but not in the module IDs somehow")
                 .as_inner()
                 .clone(),
+            ident: Ident::from(ident),
             imported_modules: scope
                 .modules
                 .available_names()
                 .map(|string| string.as_ref().into())
                 .collect(),
+            // The module exists, so we'll suggest importing it rather than another module.
+            modules_exposing_ident: MutSet::default(),
             region,
             module_exists: true,
         }
     }
 
+    /// The names of the imported modules which expose `ident`
+    fn modules_exposing(&self, scope: &Scope, ident: &str) -> MutSet<Box<str>> {
+        scope
+            .modules
+            .available_names()
+            .filter(|module_name| {
+                scope.modules.lookup(module_name).is_some_and(|module| {
+                    module.id != self.home
+                        && self
+                            .dep_idents
+                            .get(&module.id)
+                            .is_some_and(|exposed_ids| exposed_ids.get_id(ident).is_some())
+                })
+            })
+            .map(|module_name| module_name.as_ref().into())
+            .collect()
+    }
+
     pub fn problem(&mut self, problem: Problem) {
         self.problems.push(problem)
     }
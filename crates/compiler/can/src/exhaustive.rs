@@ -4,14 +4,15 @@ use roc_collections::all::HumanIndex;
 use roc_collections::VecMap;
 use roc_error_macros::internal_error;
 use roc_exhaustive::{
-    is_useful, Ctor, CtorName, Error, Guard, ListArity, Literal, Pattern, RenderAs, TagId, Union,
+    is_useful, CoveredBy, Ctor, CtorName, Error, Guard, ListArity, Literal, Pattern, RenderAs,
+    TagId, Union,
 };
 use roc_module::ident::{Lowercase, TagIdIntType, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{
     Content, FlatType, GetSubsSlice, RedundantMark, SortedTagsIterator, Subs, SubsFmtContent,
-    Variable,
+    TagExt, Variable,
 };
 use roc_types::types::{gather_tags_unsorted_iter, AliasKind};
 
@@ -49,9 +50,22 @@ pub fn check(
     } = sketched_rows.reify_to_non_redundant(subs, real_var)?;
     all_errors.extend(errors);
 
+    let unguarded_rows = without_guards(&non_redundant_rows);
+
     let exhaustive = match roc_exhaustive::check(overall_region, context, non_redundant_rows) {
         Ok(()) => true,
         Err(errors) => {
+            // With guards around, missing patterns are found by leaving the guarded branches out,
+            // so they all get reported as lacking an `if` clause. If some patterns are missing
+            // even when the guarded branches are counted, those need a new branch instead, so
+            // report them as they are.
+            let errors = match unguarded_rows
+                .map(|rows| roc_exhaustive::check(overall_region, context, rows))
+            {
+                Some(Err(unguarded_errors)) => unguarded_errors,
+                _ => errors,
+            };
+
             all_errors.extend(errors);
             false
         }
@@ -536,6 +550,19 @@ pub fn sketch_when_branches(region: Region, patterns: &[expr::WhenBranch]) -> Sk
     }
 }
 
+/// Undoes the `#Guard` wrapping from [sketch_when_branches], treating every guarded branch as
+/// if its guard always passed. Returns None if the rows weren't wrapped in the first place.
+fn without_guards(rows: &[Vec<Pattern>]) -> Option<Vec<Vec<Pattern>>> {
+    rows.iter()
+        .map(|row| match row.as_slice() {
+            [Pattern::Ctor(union, _, args)] if union.render_as == RenderAs::Guard => {
+                Some(vec![args[1].clone()])
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn sketch_pattern_to_rows(region: Region, pattern: &crate::pattern::Pattern) -> SketchedRows {
     let row = SketchedRow {
         patterns: vec![sketch_pattern(pattern)],
@@ -569,6 +596,8 @@ fn to_nonredundant_rows(
         overall_region,
     } = rows;
     let mut checked_rows = Vec::with_capacity(rows.len());
+    let mut checked_row_numbers = Vec::with_capacity(rows.len());
+    let mut checked_row_regions = Vec::with_capacity(rows.len());
 
     let mut redundancies = vec![];
     let mut errors = vec![];
@@ -597,10 +626,22 @@ fn to_nonredundant_rows(
         } else if !(matches!(guard, Guard::HasGuard)
             || is_useful(checked_rows.clone(), next_row.clone()))
         {
+            let covered_by = checked_rows
+                .iter()
+                .position(|checked_row| !is_useful(vec![checked_row.clone()], next_row.clone()))
+                .map(|i| CoveredBy {
+                    index: HumanIndex::zero_based(checked_row_numbers[i]),
+                    region: checked_row_regions[i],
+                    irrefutable: checked_rows[i]
+                        .iter()
+                        .all(|pattern| matches!(pattern, Pattern::Anything)),
+                });
+
             Some(Error::Redundant {
                 overall_region,
                 branch_region: region,
                 index: HumanIndex::zero_based(row_number),
+                covered_by,
             })
         } else {
             None
@@ -609,6 +650,8 @@ fn to_nonredundant_rows(
         match redundant_err {
             None => {
                 checked_rows.push(next_row);
+                checked_row_numbers.push(row_number);
+                checked_row_regions.push(region);
             }
             Some(err) => {
                 redundancies.push(redundant_mark);
@@ -686,7 +729,11 @@ fn convert_tag(subs: &Subs, whole_var: Variable, this_tag: &TagName) -> (Union,
 
     // DEVIATION: model openness by attaching a #Open constructor, that can never
     // be matched unless there's an `Anything` pattern.
+    //
+    // An extension that's only there because the union is open-in-output-position doesn't count:
+    // the annotation spelled out every tag a value of this type can actually have.
     let opt_openness_tag = match subs.get_content_without_compacting(ext.var()) {
+        _ if matches!(ext, TagExt::Openness(_)) => None,
         FlexVar(_) | RigidVar(_) => {
             let openness_tag = TagName(NONEXHAUSIVE_CTOR.into());
             num_tags += 1;
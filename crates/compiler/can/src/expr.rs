@@ -1933,6 +1933,7 @@ fn canonicalize_var_lookup(
                 output
                     .references
                     .insert_value_lookup(lookup, QualifiedReference::Unqualified);
+                env.check_deprecated(lookup.symbol, region);
 
                 if scope.abilities_store.is_ability_member_name(lookup.symbol) {
                     AbilityMember(
@@ -1958,6 +1959,7 @@ fn canonicalize_var_lookup(
                 output
                     .references
                     .insert_value_lookup(lookup, QualifiedReference::Qualified);
+                env.check_deprecated(lookup.symbol, region);
 
                 if scope.abilities_store.is_ability_member_name(lookup.symbol) {
                     AbilityMember(
@@ -2466,19 +2468,23 @@ fn flatten_str_literal<'a>(
     use ast::StrLiteral::*;
 
     match literal {
-        PlainLine(str_slice) => (Expr::Str((*str_slice).into()), Output::default()),
+        PlainLine(str_slice) | Raw(str_slice) => {
+            (Expr::Str((*str_slice).into()), Output::default())
+        }
         Line(segments) => flatten_str_lines(env, var_store, scope, &[segments]),
         Block(lines) => flatten_str_lines(env, var_store, scope, lines),
     }
 }
 
-/// Comments, newlines, and nested interpolation are disallowed inside interpolation
+/// Comments and newlines are disallowed inside interpolation. (Nested interpolation is a parse error.)
 pub fn is_valid_interpolation(expr: &ast::Expr<'_>) -> bool {
     match expr {
         // These definitely contain neither comments nor newlines, so they are valid
         ast::Expr::Var { .. }
         | ast::Expr::SingleQuote(_)
         | ast::Expr::Str(StrLiteral::PlainLine(_))
+        // The parser rejects interpolations inside of this one, so these are all plain segments
+        | ast::Expr::Str(StrLiteral::Line(_))
         | ast::Expr::Float(_)
         | ast::Expr::Num(_)
         | ast::Expr::NonBase10Int { .. }
@@ -2500,6 +2506,7 @@ pub fn is_valid_interpolation(expr: &ast::Expr<'_>) -> bool {
         | ast::Expr::SpaceBefore(_, _)
         | ast::Expr::Str(StrLiteral::Block(_))
         | ast::Expr::SpaceAfter(_, _) => false,
+        ast::Expr::Str(StrLiteral::Raw(string)) => !string.contains('\n'),
         // Desugared dbg expression
         ast::Expr::Defs(_, loc_ret) => match loc_ret.value {
             ast::Expr::LowLevelDbg(_, _, continuation) => {
@@ -2508,16 +2515,6 @@ pub fn is_valid_interpolation(expr: &ast::Expr<'_>) -> bool {
             _ => false,
         },
         // These can contain subexpressions, so we need to recursively check those
-        ast::Expr::Str(StrLiteral::Line(segments)) => {
-            segments.iter().all(|segment| match segment {
-                ast::StrSegment::EscapedChar(_)
-                | ast::StrSegment::Unicode(_)
-                | ast::StrSegment::Plaintext(_) => true,
-                // Disallow nested interpolation. Alternatively, we could allow it but require
-                // a comment above it apologizing to the next person who has to read the code.
-                ast::StrSegment::Interpolated(_) => false,
-            })
-        }
         ast::Expr::Record(fields) => fields.iter().all(|loc_field| match loc_field.value {
             ast::AssignedField::RequiredValue(_label, loc_comments, loc_val)
             | ast::AssignedField::OptionalValue(_label, loc_comments, loc_val)
@@ -14,13 +14,13 @@ use crate::pattern::{
 use crate::procedure::References;
 use crate::scope::Scope;
 use bumpalo::Bump;
-use roc_collections::{MutMap, SendMap, VecMap, VecSet};
+use roc_collections::{MutMap, MutSet, SendMap, VecMap, VecSet};
 use roc_error_macros::internal_error;
 use roc_module::ident::Ident;
 use roc_module::ident::Lowercase;
 use roc_module::symbol::{IdentId, IdentIds, IdentIdsByModule, ModuleId, PackageModuleIds, Symbol};
 use roc_parse::ast::{Defs, TypeAnnotation};
-use roc_parse::header::HeaderType;
+use roc_parse::header::{ExposedName, HeaderType};
 use roc_parse::pattern::PatternType;
 use roc_problem::can::{Problem, RuntimeError};
 use roc_region::all::{Loc, Region};
@@ -189,6 +189,8 @@ pub struct ModuleOutput {
     pub scope: Scope,
     pub loc_expects: VecMap<Region, Vec<ExpectLookup>>,
     pub loc_dbgs: VecMap<Symbol, DbgLookup>,
+    /// This module's definitions that are marked as deprecated, with their messages.
+    pub deprecated: VecMap<Symbol, Box<str>>,
 }
 
 fn has_no_implementation(expr: &Expr) -> bool {
@@ -226,6 +228,7 @@ pub fn canonicalize_module_defs<'a>(
     symbols_from_requires: &[(Loc<Symbol>, Loc<TypeAnnotation<'a>>)],
     var_store: &mut VarStore,
     opt_shorthand: Option<&'a str>,
+    imported_deprecations: VecMap<Symbol, Box<str>>,
 ) -> ModuleOutput {
     let mut can_exposed_imports = MutMap::default();
 
@@ -248,6 +251,7 @@ pub fn canonicalize_module_defs<'a>(
         qualified_module_ids,
         opt_shorthand,
     );
+    env.deprecated = imported_deprecations;
 
     for (name, alias) in aliases.into_iter() {
         scope.add_alias(
@@ -386,6 +390,18 @@ pub fn canonicalize_module_defs<'a>(
         }
     }
 
+    // A doc comment suggests the def was meant to be part of the module's API. Unused ones were
+    // already reported above, so only mention the ones the module uses itself.
+    if let HeaderType::Module { .. } = header_type {
+        for (symbol, region) in std::mem::take(&mut env.documented_top_level) {
+            if output.references.has_type_or_value_lookup(symbol)
+                && !exposed_symbols.contains(&symbol)
+            {
+                env.problem(Problem::DocumentedButNotExposed(symbol, region));
+            }
+        }
+    }
+
     for named in output.introduced_variables.named {
         rigid_variables.named.insert(named.variable, named.name);
     }
@@ -476,6 +492,22 @@ pub fn canonicalize_module_defs<'a>(
         })
         .collect();
 
+    // Values exposed by `import Foo exposing [bar]` are exposed imports too, just like the ones
+    // that came in through the initial scope.
+    for import in imports_introduced.iter() {
+        for (symbol, region) in import.exposed_symbols.iter() {
+            let is_value = env
+                .dep_idents
+                .get(&symbol.module_id())
+                .and_then(|ident_ids| ident_ids.get_name(symbol.ident_id()))
+                .map_or(false, |name| name.starts_with(char::is_lowercase));
+
+            if is_value {
+                can_exposed_imports.insert(*symbol, *region);
+            }
+        }
+    }
+
     report_unused_imports(imports_introduced, &output.references, &mut env, &mut scope);
 
     for index in 0..declarations.len() {
@@ -634,8 +666,44 @@ pub fn canonicalize_module_defs<'a>(
     // exposed_symbols and added to exposed_vars_by_symbol. If any were
     // not, that means they were declared as exposed but there was
     // no actual declaration with that name!
+    let top_level_names: MutSet<Box<str>> = if exposed_but_not_defined.is_empty() {
+        MutSet::default()
+    } else {
+        let abilities = scope
+            .abilities_store
+            .iter_abilities()
+            .map(|(ability, _)| ability);
+
+        env.top_level_symbols
+            .iter()
+            .copied()
+            .chain(aliases.keys().copied())
+            .chain(abilities)
+            .filter(|symbol| symbol.module_id() == home)
+            .filter_map(|symbol| scope.locals.ident_ids.get_name(symbol.ident_id()))
+            .filter(|name| name.starts_with(char::is_alphabetic))
+            // Names that are already exposed can't be the one that was meant.
+            .filter(|name| {
+                !exposes_entries(header_type)
+                    .iter()
+                    .any(|entry| entry.value.as_str() == *name)
+            })
+            .map(|name| name.into())
+            .collect()
+    };
+
     for symbol in exposed_but_not_defined {
-        env.problem(Problem::ExposedButNotDefined(symbol));
+        let name = scope
+            .locals
+            .ident_ids
+            .get_name(symbol.ident_id())
+            .unwrap_or("");
+
+        env.problem(Problem::ExposedButNotDefined {
+            symbol,
+            region: exposes_entry_region(header_type, name),
+            top_level_names: top_level_names.clone(),
+        });
 
         // In case this exposed value is referenced by other modules,
         // create a decl for it whose implementation is a runtime error.
@@ -760,6 +828,11 @@ pub fn canonicalize_module_defs<'a>(
         referenced_values,
         exposed_imports: can_exposed_imports,
         problems: env.problems,
+        deprecated: env
+            .deprecated
+            .into_iter()
+            .filter(|(symbol, _)| symbol.module_id() == home)
+            .collect(),
         symbols_from_requires,
         pending_derives,
         loc_expects: collected.expects,
@@ -768,6 +841,21 @@ pub fn canonicalize_module_defs<'a>(
     }
 }
 
+/// Where the header lists `name` in its `exposes` (or a platform's `provides`).
+fn exposes_entry_region(header_type: &HeaderType, name: &str) -> Region {
+    exposes_entries(header_type)
+        .iter()
+        .find(|entry| entry.value.as_str() == name)
+        .map_or(Region::zero(), |entry| entry.region)
+}
+
+fn exposes_entries<'h>(header_type: &'h HeaderType) -> &'h [Loc<ExposedName<'h>>] {
+    match header_type {
+        HeaderType::Platform { provides, .. } => provides,
+        _ => header_type.exposed_or_provided_values(),
+    }
+}
+
 fn fix_values_captured_in_closure_def(
     def: &mut crate::def::Def,
     no_capture_symbols: &mut VecSet<Symbol>,
@@ -289,15 +289,15 @@ pub fn canonicalize_def_header_pattern<'a>(
                     };
                     Loc::at(region, can_pattern)
                 }
-                Err((original_region, shadow, new_symbol)) => {
+                Err((original, shadow, new_symbol)) => {
                     env.problem(Problem::RuntimeError(RuntimeError::Shadowing {
-                        original_region,
+                        original_region: original.region,
                         shadow: shadow.clone(),
-                        kind: ShadowKind::Variable,
+                        kind: ShadowKind::of_variable(original.value),
                     }));
                     output.references.insert_bound(new_symbol);
 
-                    let can_pattern = Pattern::Shadowed(original_region, shadow, new_symbol);
+                    let can_pattern = Pattern::Shadowed(original.region, shadow, new_symbol);
                     Loc::at(region, can_pattern)
                 }
             }
@@ -344,7 +344,7 @@ fn canonicalize_pattern_symbol(
                 env.problem(Problem::RuntimeError(RuntimeError::Shadowing {
                     original_region: shadowed_symbol.region,
                     shadow: shadow.clone(),
-                    kind: ShadowKind::Variable,
+                    kind: ShadowKind::of_variable(shadowed_symbol.value),
                 }));
                 output.references.insert_bound(new_symbol);
 
@@ -828,7 +828,7 @@ pub fn canonicalize_record_destructs<'a>(
                         env.problem(Problem::RuntimeError(RuntimeError::Shadowing {
                             original_region: shadowed_symbol.region,
                             shadow: shadow.clone(),
-                            kind: ShadowKind::Variable,
+                            kind: ShadowKind::of_variable(shadowed_symbol.value),
                         }));
 
                         // No matter what the other patterns
@@ -899,7 +899,7 @@ pub fn canonicalize_record_destructs<'a>(
                         env.problem(Problem::RuntimeError(RuntimeError::Shadowing {
                             original_region: shadowed_symbol.region,
                             shadow: shadow.clone(),
-                            kind: ShadowKind::Variable,
+                            kind: ShadowKind::of_variable(shadowed_symbol.value),
                         }));
 
                         // No matter what the other patterns
@@ -1089,7 +1089,7 @@ fn flatten_str_literal(literal: &StrLiteral<'_>) -> Pattern {
     use ast::StrLiteral::*;
 
     match literal {
-        PlainLine(str_slice) => Pattern::StrLiteral((*str_slice).into()),
+        PlainLine(str_slice) | Raw(str_slice) => Pattern::StrLiteral((*str_slice).into()),
         Line(segments) => flatten_str_lines(&[segments]),
         Block(lines) => flatten_str_lines(lines),
     }
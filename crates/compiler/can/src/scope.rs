@@ -328,7 +328,7 @@ impl Scope {
         pending_abilities_in_scope: &PendingAbilitiesInScope,
         ident: Ident,
         region: Region,
-    ) -> Result<(Symbol, Option<Symbol>), (Region, Loc<Ident>, Symbol)> {
+    ) -> Result<(Symbol, Option<Symbol>), (Loc<Symbol>, Loc<Ident>, Symbol)> {
         let ident = &ident;
 
         match self.introduce_help(ident.as_str(), region) {
@@ -347,7 +347,11 @@ impl Scope {
                                 value: ident.clone(),
                                 region,
                             };
-                            Err((loc_original_shadow.region, shadow, shadow_symbol))
+                            Err((
+                                Loc::at(loc_original_shadow.region, original_symbol),
+                                shadow,
+                                shadow_symbol,
+                            ))
                         }
                         None => {
                             self.shadows
@@ -363,7 +367,11 @@ impl Scope {
                         region,
                     };
 
-                    Err((original_region, shadow, shadow_symbol))
+                    Err((
+                        Loc::at(original_region, original_symbol),
+                        shadow,
+                        shadow_symbol,
+                    ))
                 }
             }
             Ok(symbol) => Ok((symbol, None)),
@@ -966,3 +966,262 @@ pub fn find_declaration(symbol: Symbol, decls: &'_ Declarations) -> Option<Found
         }
     }
 }
+
+/// Finds where each value, function, and lambda in `decls` is defined, including ones defined
+/// inside other definitions.
+///
+/// A named function can show up twice, once for its name and once for its lambda; the name comes
+/// first.
+pub fn find_definitions(decls: &Declarations) -> Vec<Loc<Symbol>> {
+    let mut visitor = Finder { found: Vec::new() };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        found: Vec<Loc<Symbol>>,
+    }
+
+    impl Visitor for Finder {
+        fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+            match decl {
+                DeclarationInfo::Value { loc_symbol, .. }
+                | DeclarationInfo::Function { loc_symbol, .. } => self.found.push(loc_symbol),
+                DeclarationInfo::Destructure { .. } | DeclarationInfo::Expectation { .. } => {}
+            }
+
+            walk_decl(self, decl);
+        }
+
+        fn visit_def(&mut self, def: &Def) {
+            if let Pattern::Identifier(symbol) = def.loc_pattern.value {
+                self.found.push(Loc::at(def.loc_pattern.region, symbol));
+            }
+
+            walk_def(self, def);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if let Expr::Closure(ClosureData { name, .. }) = expr {
+                self.found.push(Loc::at(region, *name));
+            }
+
+            walk_expr(self, expr, var);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolOccurrence {
+    /// A pattern that binds the symbol, e.g. the `foo` in `foo = 1`.
+    Definition(Region),
+    /// The type of an annotation for the symbol, e.g. the `Str` in `foo : Str`. The name in front
+    /// of the colon doesn't survive canonicalization, so only the signature's region is known.
+    Signature(Region),
+    /// An expression that refers to the symbol. For a qualified lookup like `Dep.foo`, this is the
+    /// region of the whole lookup.
+    Reference(Region),
+}
+
+impl SymbolOccurrence {
+    pub fn region(&self) -> Region {
+        match self {
+            SymbolOccurrence::Definition(region)
+            | SymbolOccurrence::Signature(region)
+            | SymbolOccurrence::Reference(region) => *region,
+        }
+    }
+}
+
+/// Finds every call the function `symbol` makes to itself in `loc_body`, along with whether the
+/// call is in tail position, so that its result is returned as-is and the call can become a loop.
+///
+/// A call used as an argument of a tag in tail position counts as a tail call too, since tail
+/// recursion modulo cons turns those into loops as well.
+pub fn find_self_calls(symbol: Symbol, loc_body: &Loc<Expr>) -> Vec<(Region, bool)> {
+    let mut visitor = Finder {
+        symbol,
+        in_tail: true,
+        found: Vec::new(),
+    };
+
+    visitor.visit_expr(&loc_body.value, loc_body.region, Variable::NULL);
+    return visitor.found;
+
+    struct Finder {
+        symbol: Symbol,
+        /// Whether the expression being visited is in tail position.
+        in_tail: bool,
+        found: Vec<(Region, bool)>,
+    }
+
+    impl Finder {
+        fn is_self_call(&self, expr: &Expr) -> bool {
+            match expr {
+                Expr::Call(f, _, _) => matches!(f.1.value, Expr::Var(s, _) if s == self.symbol),
+                _ => false,
+            }
+        }
+
+        fn visit_in_tail(&mut self, in_tail: bool, loc_expr: &Loc<Expr>, var: Variable) {
+            self.in_tail = in_tail;
+            self.visit_expr(&loc_expr.value, loc_expr.region, var);
+        }
+    }
+
+    impl Visitor for Finder {
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            // Only the parts of an expression handled below can be in tail position; everything
+            // else is walked with `in_tail` off.
+            let in_tail = std::mem::replace(&mut self.in_tail, false);
+
+            match expr {
+                Expr::Call(..) if self.is_self_call(expr) => {
+                    self.found.push((region, in_tail));
+                    walk_expr(self, expr, var);
+                }
+                Expr::If {
+                    cond_var,
+                    branches,
+                    branch_var,
+                    final_else,
+                } => {
+                    for (cond, body) in branches {
+                        self.visit_in_tail(false, cond, *cond_var);
+                        self.visit_in_tail(in_tail, body, *branch_var);
+                    }
+
+                    self.visit_in_tail(in_tail, final_else, *branch_var);
+                }
+                Expr::When {
+                    cond_var,
+                    expr_var,
+                    loc_cond,
+                    branches,
+                    ..
+                } => {
+                    self.visit_in_tail(false, loc_cond, *cond_var);
+
+                    for branch in branches {
+                        if let Some(guard) = &branch.guard {
+                            self.visit_in_tail(false, guard, Variable::BOOL);
+                        }
+
+                        self.visit_in_tail(in_tail, &branch.value, *expr_var);
+                    }
+                }
+                Expr::LetRec(defs, body, _) => {
+                    defs.iter().for_each(|def| self.visit_def(def));
+                    self.visit_in_tail(in_tail, body, var);
+                }
+                Expr::LetNonRec(def, body) => {
+                    self.visit_def(def);
+                    self.visit_in_tail(in_tail, body, var);
+                }
+                Expr::Expect {
+                    loc_condition,
+                    loc_continuation,
+                    ..
+                }
+                | Expr::ExpectFx {
+                    loc_condition,
+                    loc_continuation,
+                    ..
+                } => {
+                    self.visit_in_tail(false, loc_condition, Variable::BOOL);
+                    self.visit_in_tail(in_tail, loc_continuation, var);
+                }
+                Expr::Dbg {
+                    variable,
+                    loc_message,
+                    loc_continuation,
+                    ..
+                } => {
+                    self.visit_in_tail(false, loc_message, *variable);
+                    self.visit_in_tail(in_tail, loc_continuation, var);
+                }
+                Expr::Tag { arguments, .. } => {
+                    for (arg_var, arg) in arguments {
+                        let is_tail = in_tail && self.is_self_call(&arg.value);
+                        self.visit_in_tail(is_tail, arg, *arg_var);
+                    }
+                }
+                _ => walk_expr(self, expr, var),
+            }
+        }
+    }
+}
+
+/// Finds every place in `decls` that defines, annotates, or refers to `symbol`.
+pub fn find_symbol_occurrences(symbol: Symbol, decls: &Declarations) -> Vec<SymbolOccurrence> {
+    let mut visitor = Finder {
+        symbol,
+        found: Vec::new(),
+    };
+
+    // Top-level annotations aren't passed along with function declarations, so pick them up here.
+    for (loc_symbol, annotation) in decls.symbols.iter().zip(decls.annotations.iter()) {
+        if let (true, Some(annotation)) = (loc_symbol.value == symbol, annotation) {
+            visitor
+                .found
+                .push(SymbolOccurrence::Signature(annotation.region));
+        }
+    }
+
+    visitor.visit_decls(decls);
+    visitor.found.sort_by_key(|occurrence| occurrence.region());
+    return visitor.found;
+
+    struct Finder {
+        symbol: Symbol,
+        found: Vec<SymbolOccurrence>,
+    }
+
+    impl Visitor for Finder {
+        fn visit_def(&mut self, def: &Def) {
+            if let (Pattern::Identifier(s), Some(annotation)) =
+                (&def.loc_pattern.value, &def.annotation)
+            {
+                if *s == self.symbol {
+                    self.found
+                        .push(SymbolOccurrence::Signature(annotation.region));
+                }
+            }
+
+            walk_def(self, def)
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(s)
+                | Pattern::Shadowed(_, _, s)
+                | Pattern::AbilityMemberSpecialization { ident: s, .. }
+                    if *s == self.symbol =>
+                {
+                    self.found.push(SymbolOccurrence::Definition(region));
+                }
+                _ => walk_pattern(self, pattern),
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if destruct.symbol == self.symbol && !matches!(destruct.typ, DestructType::Guard(..)) {
+                self.found.push(SymbolOccurrence::Definition(region));
+            }
+
+            walk_record_destruct(self, destruct);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Var(s, _)
+                | Expr::ParamsVar { symbol: s, .. }
+                | Expr::AbilityMember(s, _, _)
+                    if *s == self.symbol =>
+                {
+                    self.found.push(SymbolOccurrence::Reference(region));
+                }
+                _ => walk_expr(self, expr, var),
+            }
+        }
+    }
+}
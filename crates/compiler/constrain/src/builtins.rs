@@ -257,6 +257,13 @@ pub(crate) fn builtin_type(symbol: Symbol, args: Vec<Type>) -> Type {
     )
 }
 
+/// A fresh `Str` type, for string literals. Unlike [`Types::STR`], which is shared by every use of
+/// `Str`, a type error involving this one only affects the literal it belongs to.
+#[cfg_attr(not(debug_assertions), inline(always))]
+pub(crate) fn str_type() -> Type {
+    builtin_type(Symbol::STR_STR, vec![])
+}
+
 #[cfg_attr(not(debug_assertions), inline(always))]
 pub(crate) fn empty_list_type(var: Variable) -> Type {
     list_type(Type::Variable(var))
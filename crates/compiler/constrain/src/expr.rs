@@ -4,6 +4,7 @@ use std::ops::Range;
 
 use crate::builtins::{
     empty_list_type, float_literal, int_literal, list_type, num_literal, single_quote_literal,
+    str_type,
 };
 use crate::pattern::{constrain_pattern, PatternState};
 use roc_can::annotation::IntroducedVariables;
@@ -371,7 +372,8 @@ pub fn constrain_expr(
             constraints.exists(vars, and_constraint)
         }
         Str(_) => {
-            let str_index = constraints.push_type(types, Types::STR);
+            let str_type_index = types.from_old_type(&str_type());
+            let str_index = constraints.push_type(types, str_type_index);
             let expected_index = expected;
             constraints.equal_types(str_index, expected_index, Category::Str, region)
         }
@@ -545,7 +547,8 @@ pub fn constrain_expr(
             constraints.exists(vars, and_constraint)
         }
         Expr::Crash { msg, ret_var } => {
-            let str_index = constraints.push_type(types, Types::STR);
+            let str_type_index = types.from_old_type(&str_type());
+            let str_index = constraints.push_type(types, str_type_index);
             let expected_msg = constraints.push_expected_type(Expected::ForReason(
                 Reason::CrashArg,
                 str_index,
@@ -432,7 +432,8 @@ pub fn constrain_pattern(
         }
 
         StrLiteral(_) => {
-            let str_type = constraints.push_type(types, Types::STR);
+            let str_type_index = types.from_old_type(&builtins::str_type());
+            let str_type = constraints.push_type(types, str_type_index);
             state.constraints.push(constraints.equal_pattern_types(
                 str_type,
                 expected,
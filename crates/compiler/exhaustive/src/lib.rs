@@ -134,6 +134,15 @@ pub enum Literal {
     Str(Box<str>),
 }
 
+/// An earlier pattern that makes a later one redundant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoveredBy {
+    pub index: HumanIndex,
+    pub region: Region,
+    /// Whether the pattern matches every possible value, like `_` or a bare identifier does.
+    pub irrefutable: bool,
+}
+
 /// Error
 
 #[derive(Clone, Debug, PartialEq)]
@@ -143,6 +152,9 @@ pub enum Error {
         overall_region: Region,
         branch_region: Region,
         index: HumanIndex,
+        /// The earlier pattern that matches everything this one does all by itself, if any.
+        /// When this is None, it takes several earlier patterns together to cover this one.
+        covered_by: Option<CoveredBy>,
     },
     Unmatchable {
         overall_region: Region,
@@ -4,8 +4,8 @@ use crate::{
     Buf,
 };
 use roc_parse::ast::{
-    AbilityImpls, AssignedField, Collection, Expr, ExtractSpaces, ImplementsAbilities,
-    ImplementsAbility, ImplementsClause, Tag, TypeAnnotation, TypeHeader,
+    AbilityImpls, AssignedField, Collection, CommentOrNewline, Expr, ExtractSpaces,
+    ImplementsAbilities, ImplementsAbility, ImplementsClause, Tag, TypeAnnotation, TypeHeader,
 };
 use roc_parse::ident::UppercaseIdent;
 use roc_region::all::Loc;
@@ -329,18 +329,20 @@ impl<'a> Formattable for TypeAnnotation<'a> {
             }
 
             Record { fields, ext } => {
+                buf.begin_record_fields();
                 fmt_collection(buf, indent, Braces::Curly, *fields, newlines);
+                buf.end_record_fields();
 
                 if let Some(loc_ext_ann) = *ext {
                     loc_ext_ann.value.format(buf, indent);
                 }
             }
 
-            As(lhs, _spaces, TypeHeader { name, vars }) => {
-                // TODO use _spaces?
+            As(lhs, spaces, TypeHeader { name, vars }) => {
                 lhs.value
                     .format_with_options(buf, Parens::InFunctionType, Newlines::No, indent);
-                buf.spaces(1);
+                fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent + INDENT);
+                buf.space_or_indent(indent + INDENT);
                 buf.push_str("as");
                 buf.spaces(1);
                 buf.push_str(name.value);
@@ -357,10 +359,10 @@ impl<'a> Formattable for TypeAnnotation<'a> {
                     .iter()
                     .any(|implements| implements.is_multiline())
                 {
-                    buf.newline();
+                    buf.ensure_ends_with_newline();
                     buf.indent(indent);
                 } else {
-                    buf.spaces(1);
+                    buf.space_or_indent(indent);
                 }
                 for (i, has) in implements_clauses.iter().enumerate() {
                     buf.push_str(if i == 0 {
@@ -458,13 +460,7 @@ fn format_assigned_field_help<T>(
             buf.indent(indent);
             buf.push_str(name.value);
 
-            if !spaces.is_empty() {
-                fmt_spaces(buf, spaces.iter(), indent);
-            }
-
-            buf.spaces(separator_spaces);
-            buf.push(':');
-            buf.spaces(1);
+            fmt_separator(buf, spaces, indent, separator_spaces, ':');
             ann.value.format(buf, indent);
         }
         OptionalValue(name, spaces, ann) => {
@@ -475,13 +471,7 @@ fn format_assigned_field_help<T>(
 
             buf.push_str(name.value);
 
-            if !spaces.is_empty() {
-                fmt_spaces(buf, spaces.iter(), indent);
-            }
-
-            buf.spaces(separator_spaces);
-            buf.push('?');
-            buf.spaces(1);
+            fmt_separator(buf, spaces, indent, separator_spaces, '?');
             ann.value.format(buf, indent);
         }
         IgnoredValue(name, spaces, ann) => {
@@ -493,13 +483,7 @@ fn format_assigned_field_help<T>(
             buf.push('_');
             buf.push_str(name.value);
 
-            if !spaces.is_empty() {
-                fmt_spaces(buf, spaces.iter(), indent);
-            }
-
-            buf.spaces(separator_spaces);
-            buf.push(':');
-            buf.spaces(1);
+            fmt_separator(buf, spaces, indent, separator_spaces, ':');
             ann.value.format(buf, indent);
         }
         LabelOnly(name) => {
@@ -524,6 +508,39 @@ fn format_assigned_field_help<T>(
     }
 }
 
+/// A field's `:` or `?`, and the spaces around it. After a comment, the separator starts the next
+/// line.
+///
+/// When record fields are aligned, the padding goes before the separator in types (which have a
+/// space there), so the colons line up, and after it in values, so the values do.
+fn fmt_separator(
+    buf: &mut Buf,
+    spaces: &[CommentOrNewline],
+    indent: u16,
+    separator_spaces: usize,
+    separator: char,
+) {
+    if spaces.is_empty() && separator_spaces > 0 {
+        buf.record_field_padding();
+    }
+
+    fmt_spaces(buf, spaces.iter(), indent);
+
+    if buf.ends_with_newline() {
+        buf.indent(indent);
+    } else {
+        buf.spaces(separator_spaces);
+    }
+
+    buf.push(separator);
+
+    if spaces.is_empty() && separator_spaces == 0 {
+        buf.record_field_padding();
+    }
+
+    buf.spaces(1);
+}
+
 impl<'a> Formattable for Tag<'a> {
     fn is_multiline(&self) -> bool {
         use self::Tag::*;
@@ -578,13 +595,24 @@ impl<'a> Formattable for Tag<'a> {
 
 impl<'a> Formattable for ImplementsClause<'a> {
     fn is_multiline(&self) -> bool {
-        // No, always put abilities in an "implements" clause on one line
-        false
+        // Always put abilities in an "implements" clause on one line, unless there are comments
+        // around the type variable.
+        let var = self.var.value.extract_spaces();
+
+        var.before
+            .iter()
+            .chain(var.after.iter())
+            .any(|space| space.is_comment())
     }
 
     fn format_with_options(&self, buf: &mut Buf, parens: Parens, newlines: Newlines, indent: u16) {
-        buf.push_str(self.var.value.extract_spaces().item);
-        buf.spaces(1);
+        let var = self.var.value.extract_spaces();
+
+        fmt_comments_only(buf, var.before.iter(), NewlineAt::Bottom, indent + INDENT);
+        buf.indent(indent + INDENT);
+        buf.push_str(var.item);
+        fmt_comments_only(buf, var.after.iter(), NewlineAt::Bottom, indent + INDENT);
+        buf.space_or_indent(indent + INDENT);
         buf.push_str(roc_parse::keyword::IMPLEMENTS);
         buf.spaces(1);
 
@@ -617,9 +645,10 @@ impl<'a> Formattable for AbilityImpls<'a> {
                 fmt_collection(buf, indent, Braces::Curly, *impls, Newlines::No);
             }
             AbilityImpls::SpaceBefore(impls, spaces) => {
-                buf.newline();
+                buf.ensure_ends_with_newline();
                 buf.indent(indent);
                 fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent);
+                buf.indent(indent);
                 impls.format_with_options(buf, parens, Newlines::No, indent);
             }
             AbilityImpls::SpaceAfter(impls, spaces) => {
@@ -654,9 +683,10 @@ impl<'a> Formattable for ImplementsAbility<'a> {
                 }
             }
             ImplementsAbility::SpaceBefore(ab, spaces) => {
-                buf.newline();
+                buf.ensure_ends_with_newline();
                 buf.indent(indent);
                 fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent);
+                buf.indent(indent);
                 ab.format_with_options(buf, parens, Newlines::No, indent)
             }
             ImplementsAbility::SpaceAfter(ab, spaces) => {
@@ -689,9 +719,10 @@ impl<'a> Formattable for ImplementsAbilities<'a> {
                 fmt_collection(buf, indent, Braces::Square, *has_abilities, Newlines::No);
             }
             ImplementsAbilities::SpaceBefore(has_abilities, spaces) => {
-                buf.newline();
+                buf.ensure_ends_with_newline();
                 buf.indent(indent);
                 fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent);
+                buf.indent(indent);
                 has_abilities.format_with_options(buf, parens, Newlines::No, indent)
             }
             ImplementsAbilities::SpaceAfter(has_abilities, spaces) => {
@@ -43,6 +43,9 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
         buf.indent(braces_indent);
         buf.push(start);
 
+        // A comment can't go on the same line as a comment before it.
+        let mut prev_item_ended_with_comment = false;
+
         for (index, item) in items.iter().enumerate() {
             let is_first_item = index == 0;
             let item = item.extract_spaces();
@@ -57,7 +60,9 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
                     // and that newline appears before the first comment (if there is one).
                     buf.ensure_ends_with_newline();
                 } else {
-                    if item.before.starts_with(&[CommentOrNewline::Newline]) {
+                    if item.before.starts_with(&[CommentOrNewline::Newline])
+                        || prev_item_ended_with_comment
+                    {
                         buf.ensure_ends_with_newline();
                     }
 
@@ -88,7 +93,7 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
             buf.indent(item_indent);
             item.item.format(buf, item_indent);
 
-            buf.push(',');
+            buf.push_item_comma(index == items.len() - 1);
 
             if !item.after.is_empty() {
                 if item.after.iter().any(|s| s.is_newline()) {
@@ -97,9 +102,11 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
 
                 fmt_comments_only(buf, item.after.iter(), NewlineAt::None, item_indent);
             }
+
+            prev_item_ended_with_comment = item.after.iter().any(|s| s.is_comment());
         }
 
-        if items.final_comments().iter().any(|s| s.is_newline()) {
+        if items.final_comments().iter().any(|s| s.is_newline()) || prev_item_ended_with_comment {
             buf.newline();
         }
 
@@ -1,15 +1,19 @@
 use crate::annotation::{is_collection_multiline, Formattable, Newlines, Parens};
 use crate::collection::{fmt_collection, Braces};
-use crate::expr::fmt_str_literal;
+use crate::expr::{fmt_str_literal, is_pipeline_too_wide};
 use crate::pattern::fmt_pattern;
-use crate::spaces::{fmt_default_newline, fmt_default_spaces, fmt_spaces, INDENT};
+use crate::spaces::{
+    fmt_comments_only, fmt_default_newline, fmt_default_spaces, fmt_spaces,
+    fmt_spaces_max_consecutive_newlines, NewlineAt, INDENT,
+};
 use crate::Buf;
 use roc_parse::ast::{
-    AbilityMember, Defs, Expr, ExtractSpaces, ImportAlias, ImportAsKeyword, ImportExposingKeyword,
-    ImportedModuleName, IngestedFileAnnotation, IngestedFileImport, ModuleImport,
-    ModuleImportParams, Pattern, Spaces, StrLiteral, TypeAnnotation, TypeDef, TypeHeader, ValueDef,
+    AbilityMember, CommentOrNewline, Defs, Expr, ExtractSpaces, Implements, ImportAlias,
+    ImportAsKeyword, ImportExposingKeyword, ImportedModuleName, IngestedFileAnnotation,
+    IngestedFileImport, ModuleImport, ModuleImportParams, Pattern, Spaces, StrLiteral,
+    TypeAnnotation, TypeDef, TypeHeader, ValueDef,
 };
-use roc_parse::header::Keyword;
+use roc_parse::header::{Keyword, ModuleDefsPart};
 use roc_region::all::Loc;
 
 /// A Located formattable value is also formattable
@@ -27,12 +31,30 @@ impl<'a> Formattable for Defs<'a> {
         indent: u16,
     ) {
         let mut prev_spaces = true;
+        // Defs at indent 0 are a module's, rather than a body's. Imports stay together, though.
+        let one_blank_line_before: Vec<bool> =
+            if indent == 0 && buf.blank_lines().between_top_level_defs {
+                let is_import: Vec<bool> = self.defs().map(|def| is_import(&def)).collect();
+
+                (0..is_import.len())
+                    .map(|index| index > 0 && !(is_import[index - 1] && is_import[index]))
+                    .collect()
+            } else {
+                vec![false; self.tags.len()]
+            };
 
         for (index, def) in self.defs().enumerate() {
             let spaces_before = &self.spaces[self.space_before[index].indices()];
             let spaces_after = &self.spaces[self.space_after[index].indices()];
 
-            if prev_spaces {
+            if one_blank_line_before[index] {
+                let prev_spaces_after = &self.spaces[self.space_after[index - 1].indices()];
+
+                fmt_spaces_between_top_level_defs(
+                    buf,
+                    prev_spaces_after.iter().chain(spaces_before),
+                );
+            } else if prev_spaces {
                 fmt_spaces(buf, spaces_before.iter(), indent);
             } else {
                 fmt_default_newline(buf, spaces_before, indent);
@@ -43,7 +65,14 @@ impl<'a> Formattable for Defs<'a> {
                 Err(value_def) => value_def.format(buf, indent),
             }
 
-            fmt_spaces(buf, spaces_after.iter(), indent);
+            // If there's a next def, these are part of the spaces before it.
+            if !one_blank_line_before
+                .get(index + 1)
+                .copied()
+                .unwrap_or(false)
+            {
+                fmt_spaces(buf, spaces_after.iter(), indent);
+            }
 
             prev_spaces = !spaces_after.is_empty();
         }
@@ -57,7 +86,14 @@ impl<'a> Formattable for TypeDef<'a> {
         match self {
             Alias { ann, .. } => ann.is_multiline(),
             Opaque { typ, .. } => typ.is_multiline(),
-            Ability { members, .. } => members.iter().any(|d| d.is_multiline()),
+            Ability {
+                loc_implements,
+                members,
+                ..
+            } => {
+                implements_has_comments(&loc_implements.value)
+                    || members.iter().any(|d| d.is_multiline())
+            }
         }
     }
 
@@ -127,7 +163,7 @@ impl<'a> Formattable for TypeDef<'a> {
             }
             Ability {
                 header: TypeHeader { name, vars },
-                loc_implements: _,
+                loc_implements,
                 members,
             } => {
                 buf.indent(indent);
@@ -137,8 +173,7 @@ impl<'a> Formattable for TypeDef<'a> {
                     fmt_pattern(buf, &var.value, indent, Parens::NotNeeded);
                     buf.indent(indent);
                 }
-                buf.spaces(1);
-                buf.push_str(roc_parse::keyword::IMPLEMENTS);
+                fmt_implements(buf, &loc_implements.value, indent + INDENT);
 
                 if !self.is_multiline() {
                     debug_assert_eq!(members.len(), 1);
@@ -151,6 +186,12 @@ impl<'a> Formattable for TypeDef<'a> {
                     );
                 } else {
                     for member in members.iter() {
+                        // A comment around `implements` can make an ability multiline, even
+                        // when its only member was on the same line.
+                        if member.name.value.extract_spaces().before.is_empty() {
+                            buf.ensure_ends_with_newline();
+                        }
+
                         member.format_with_options(
                             buf,
                             Parens::NotNeeded,
@@ -164,6 +205,33 @@ impl<'a> Formattable for TypeDef<'a> {
     }
 }
 
+fn implements_has_comments(implements: &Implements) -> bool {
+    match implements {
+        Implements::Implements => false,
+        Implements::SpaceBefore(inner, spaces) | Implements::SpaceAfter(inner, spaces) => {
+            spaces.iter().any(|space| space.is_comment()) || implements_has_comments(inner)
+        }
+    }
+}
+
+/// The `implements` keyword of an ability definition, along with any comments around it.
+fn fmt_implements(buf: &mut Buf, implements: &Implements, indent: u16) {
+    match implements {
+        Implements::Implements => {
+            buf.space_or_indent(indent);
+            buf.push_str(roc_parse::keyword::IMPLEMENTS);
+        }
+        Implements::SpaceBefore(inner, spaces) => {
+            fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent);
+            fmt_implements(buf, inner, indent);
+        }
+        Implements::SpaceAfter(inner, spaces) => {
+            fmt_implements(buf, inner, indent);
+            fmt_comments_only(buf, spaces.iter(), NewlineAt::Bottom, indent);
+        }
+    }
+}
+
 impl<'a> Formattable for TypeHeader<'a> {
     fn is_multiline(&self) -> bool {
         self.vars.iter().any(|v| v.is_multiline())
@@ -582,6 +650,83 @@ pub fn fmt_defs(buf: &mut Buf, defs: &Defs, indent: u16) {
     defs.format(buf, indent);
 }
 
+/// Formats a module's defs from `parse_module_defs_recovering`. The ones which didn't parse stay
+/// exactly as they were in `src`, on lines of their own.
+pub fn fmt_module_defs_parts(buf: &mut Buf, parts: &[ModuleDefsPart], src: &str) {
+    for (index, part) in parts.iter().enumerate() {
+        match part {
+            ModuleDefsPart::Defs(defs) => fmt_defs(buf, defs, 0),
+            ModuleDefsPart::Broken { region, .. } => {
+                let start = region.start().offset as usize;
+                let end = region.end().offset as usize;
+                let is_last = index == parts.len() - 1;
+
+                fmt_verbatim(buf, &src[start..end], is_last);
+            }
+        }
+    }
+}
+
+fn fmt_verbatim(buf: &mut Buf, text: &str, is_last: bool) {
+    let code = text.trim();
+
+    if code.is_empty() {
+        return;
+    }
+
+    let before = &text[..text.len() - text.trim_start().len()];
+    let after = &text[text.trim_end().len()..];
+    let blank_line_between_defs = buf.blank_lines().between_top_level_defs;
+
+    buf.ensure_ends_with_newline();
+
+    if before.matches('\n').count() > 1 || blank_line_between_defs {
+        buf.ensure_ends_with_blank_line();
+    }
+
+    // The first line keeps its indentation, unless it's on the same line as what came before.
+    let code = match before.rfind('\n') {
+        Some(index) => text[index + 1..].trim_end(),
+        None => code,
+    };
+
+    buf.indent(0);
+    buf.push_str_allow_spaces(code);
+    buf.newline();
+
+    if !is_last && (after.matches('\n').count() > 1 || blank_line_between_defs) {
+        buf.ensure_ends_with_blank_line();
+    }
+}
+
+/// Exactly one blank line right after the previous def, and at most one between the comments
+/// before the next one.
+fn fmt_spaces_between_top_level_defs<'a, 'buf, I>(buf: &mut Buf<'buf>, spaces: I)
+where
+    I: Iterator<Item = &'a CommentOrNewline<'a>>,
+{
+    let mut spaces = spaces.peekable();
+
+    // A comment at the end of the previous def's last line stays there.
+    if let Some(comment @ CommentOrNewline::LineComment(_)) = spaces.peek() {
+        fmt_spaces(buf, std::iter::once(*comment), 0);
+        spaces.next();
+    }
+
+    buf.ensure_ends_with_blank_line();
+
+    let comments = spaces.skip_while(|space| matches!(space, CommentOrNewline::Newline));
+
+    fmt_spaces_max_consecutive_newlines(buf, comments, 2, 0);
+}
+
+fn is_import(def: &Result<&TypeDef, &ValueDef>) -> bool {
+    matches!(
+        def,
+        Err(ValueDef::ModuleImport(_) | ValueDef::IngestedFileImport(_))
+    )
+}
+
 pub fn fmt_annotated_body_comment<'a>(
     buf: &mut Buf,
     indent: u16,
@@ -640,6 +785,11 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
         buf.push_str(" =");
     }
 
+    fmt_assigned_body(buf, body, indent);
+}
+
+/// What comes after a def's `=` or a backpassing step's `<-`.
+pub fn fmt_assigned_body<'a>(buf: &mut Buf, body: &'a Expr<'a>, indent: u16) {
     if body.is_multiline() {
         match body {
             Expr::SpaceBefore(sub_def, spaces) => {
@@ -688,6 +838,10 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
                 body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
             }
         }
+    } else if is_pipeline_too_wide(buf, body, indent + INDENT) {
+        // Like a multiline pipeline, it starts on the next line.
+        buf.newline();
+        body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
     } else {
         buf.spaces(1);
         body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
@@ -0,0 +1,119 @@
+//! Formatting as a list of edits to the source, rather than a whole new source, so editors can
+//! format a file without moving the cursor or scroll position of the parts that didn't change.
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use bumpalo::Bump;
+use roc_parse::ast::SpacesBefore;
+use roc_parse::header::{self, parse_module_defs_recovering};
+use roc_parse::parser::SyntaxError;
+use roc_parse::state::State;
+use roc_region::all::{Position, Region};
+use similar::{DiffOp, TextDiff};
+
+use crate::def::fmt_module_defs_parts;
+use crate::header::fmt_header;
+use crate::Buf;
+
+/// Formatting usually changes little enough that diffing takes milliseconds, but when it
+/// changes a lot, the diff stops looking for the smallest edits after this long.
+const DIFF_TIME_LIMIT: Duration = Duration::from_millis(500);
+
+/// Replaces the part of the source in `region` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub region: Region,
+    pub replacement: String,
+}
+
+/// Formats a module, returning the edits which turn `src` into the formatted source. They're
+/// empty if it was already formatted. The formatter's settings come from `buf`, which should be
+/// empty.
+///
+/// Top-level defs with syntax errors are left as they are, so only an error in the header means
+/// there's nothing to format.
+pub fn format_edits<'a>(
+    arena: &'a Bump,
+    src: &'a str,
+    mut buf: Buf<'a>,
+) -> Result<Vec<TextEdit>, SyntaxError<'a>> {
+    let (module, state) = header::parse_header(arena, State::new(src.as_bytes()))
+        .map_err(|e| SyntaxError::Header(e.problem))?;
+    let (header, defs) = module.item.upgrade_header_imports(arena);
+    let parts = parse_module_defs_recovering(arena, state, defs);
+
+    fmt_header(
+        &mut buf,
+        &SpacesBefore {
+            before: module.before,
+            item: header,
+        },
+    );
+    fmt_module_defs_parts(&mut buf, &parts, src);
+    buf.fmt_end_of_file();
+
+    Ok(text_edits(src, buf.as_str()))
+}
+
+/// The edits which turn `src` into `formatted`, in order and not overlapping. Each one only
+/// covers the characters which changed, so everything else stays where it was.
+pub fn text_edits(src: &str, formatted: &str) -> Vec<TextEdit> {
+    let diff = TextDiff::configure()
+        .deadline(Instant::now() + DIFF_TIME_LIMIT)
+        .diff_chars(src, formatted);
+
+    changes(&diff)
+        .into_iter()
+        .map(|(src_range, formatted_range)| TextEdit {
+            region: Region::new(
+                Position::new(src_range.start as u32),
+                Position::new(src_range.end as u32),
+            ),
+            replacement: formatted[formatted_range].to_string(),
+        })
+        .collect()
+}
+
+/// Where the old and new text differ, as byte ranges. Changes next to each other are combined.
+fn changes(diff: &TextDiff<str>) -> Vec<(Range<usize>, Range<usize>)> {
+    let old_offsets = offsets(diff.old_slices());
+    let new_offsets = offsets(diff.new_slices());
+    let mut changes: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let mut prev_changed = false;
+
+    for op in diff.ops() {
+        let changed = !matches!(op, DiffOp::Equal { .. });
+
+        if changed {
+            let old = old_offsets[op.old_range().start]..old_offsets[op.old_range().end];
+            let new = new_offsets[op.new_range().start]..new_offsets[op.new_range().end];
+
+            match changes.last_mut() {
+                Some((prev_old, prev_new)) if prev_changed => {
+                    prev_old.end = old.end;
+                    prev_new.end = new.end;
+                }
+                _ => changes.push((old, new)),
+            }
+        }
+
+        prev_changed = changed;
+    }
+
+    changes
+}
+
+/// The offset each slice starts at, plus the offset after the last one.
+fn offsets(slices: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(slices.len() + 1);
+    let mut offset = 0;
+
+    offsets.push(offset);
+
+    for slice in slices {
+        offset += slice.len();
+        offsets.push(offset);
+    }
+
+    offsets
+}
@@ -1,6 +1,7 @@
 use crate::annotation::{except_last, is_collection_multiline, Formattable, Newlines, Parens};
 use crate::collection::{fmt_collection, Braces};
-use crate::def::fmt_defs;
+use crate::def::{fmt_assigned_body, fmt_defs};
+use crate::number::{fmt_decimal_literal, fmt_hex_digits};
 use crate::pattern::fmt_pattern;
 use crate::spaces::{
     count_leading_newlines, fmt_comments_only, fmt_spaces, fmt_spaces_no_blank_lines, NewlineAt,
@@ -323,11 +324,11 @@ impl<'a> Formattable for Expr<'a> {
             }
             &Num(string) => {
                 buf.indent(indent);
-                buf.push_str(string);
+                fmt_decimal_literal(buf, string, false);
             }
             &Float(string) => {
                 buf.indent(indent);
-                buf.push_str(string);
+                fmt_decimal_literal(buf, string, true);
             }
             Tag(string) | OpaqueRef(string) => {
                 buf.indent(indent);
@@ -354,7 +355,11 @@ impl<'a> Formattable for Expr<'a> {
                     Base::Decimal => { /* nothing */ }
                 }
 
-                buf.push_str(string);
+                if base == Base::Hex {
+                    fmt_hex_digits(buf, string);
+                } else {
+                    buf.push_str(string);
+                }
             }
             Record(fields) => {
                 fmt_record_like(
@@ -469,7 +474,14 @@ impl<'a> Formattable for Expr<'a> {
             When(loc_condition, branches) => fmt_when(buf, loc_condition, branches, indent),
             Tuple(items) => fmt_collection(buf, indent, Braces::Round, *items, Newlines::No),
             List(items) => fmt_collection(buf, indent, Braces::Square, *items, Newlines::No),
-            BinOps(lefts, right) => fmt_binops(buf, lefts, right, false, indent),
+            BinOps(lefts, right) => {
+                // Only pipelines at the start of a line get broken up, since breaking one after
+                // something else (like a record field's name) would make that multiline too.
+                let breaks_for_width = (buf.is_empty() || buf.ends_with_newline())
+                    && is_pipeline_too_wide(buf, self, indent);
+
+                fmt_binops(buf, lefts, right, breaks_for_width, indent)
+            }
             UnaryOp(sub_expr, unary_op) => {
                 buf.indent(indent);
                 match &unary_op.value {
@@ -571,6 +583,7 @@ pub fn is_str_multiline(literal: &StrLiteral) -> bool {
             // even if the string is only a single line.
             true
         }
+        Raw(string) => string.contains('\n'),
     }
 }
 
@@ -638,7 +651,7 @@ fn fmt_str_body(body: &str, buf: &mut Buf) {
     }
 }
 
-fn format_str_segment(seg: &StrSegment, buf: &mut Buf, indent: u16) {
+fn format_str_segment(seg: &StrSegment, buf: &mut Buf) {
     use StrSegment::*;
 
     match seg {
@@ -663,6 +676,10 @@ fn format_str_segment(seg: &StrSegment, buf: &mut Buf, indent: u16) {
             buf.push(escaped.to_parsed_char());
         }
         Interpolated(loc_expr) => {
+            // The parser measures the indentation of an interpolation's lines from the line it
+            // starts on, not from the string.
+            let indent = buf.cur_line_indent();
+
             buf.push_str("$(");
             // e.g. (name) in "Hi, $(name)!"
             loc_expr.value.format_with_options(
@@ -671,30 +688,14 @@ fn format_str_segment(seg: &StrSegment, buf: &mut Buf, indent: u16) {
                 Newlines::No,      // Interpolations can never have newlines
                 indent,
             );
+            buf.indent(indent);
             buf.push(')');
         }
     }
 }
 
 fn push_op(buf: &mut Buf, op: BinOp) {
-    match op {
-        called_via::BinOp::Caret => buf.push('^'),
-        called_via::BinOp::Star => buf.push('*'),
-        called_via::BinOp::Slash => buf.push('/'),
-        called_via::BinOp::DoubleSlash => buf.push_str("//"),
-        called_via::BinOp::Percent => buf.push('%'),
-        called_via::BinOp::Plus => buf.push('+'),
-        called_via::BinOp::Minus => buf.push('-'),
-        called_via::BinOp::Equals => buf.push_str("=="),
-        called_via::BinOp::NotEquals => buf.push_str("!="),
-        called_via::BinOp::LessThan => buf.push('<'),
-        called_via::BinOp::GreaterThan => buf.push('>'),
-        called_via::BinOp::LessThanOrEq => buf.push_str("<="),
-        called_via::BinOp::GreaterThanOrEq => buf.push_str(">="),
-        called_via::BinOp::And => buf.push_str("&&"),
-        called_via::BinOp::Or => buf.push_str("||"),
-        called_via::BinOp::Pizza => buf.push_str("|>"),
-    }
+    buf.push_str(op.as_str());
 }
 
 pub fn fmt_str_literal(buf: &mut Buf, literal: StrLiteral, indent: u16) {
@@ -704,7 +705,8 @@ pub fn fmt_str_literal(buf: &mut Buf, literal: StrLiteral, indent: u16) {
         PlainLine(string) => {
             // When a PlainLine contains '\n' or '"', format as a block string
             if string.contains('"') || string.contains('\n') {
-                buf.ensure_ends_with_newline();
+                let indent = block_string_indent(buf, indent);
+
                 buf.indent(indent);
                 buf.push_str("\"\"\"");
                 buf.push_newline_literal();
@@ -726,13 +728,14 @@ pub fn fmt_str_literal(buf: &mut Buf, literal: StrLiteral, indent: u16) {
             buf.indent(indent);
             buf.push('"');
             for seg in segments.iter() {
-                format_str_segment(seg, buf, 0)
+                format_str_segment(seg, buf)
             }
             buf.push('"');
         }
         Block(lines) => {
             // Block strings will always be formatted with """ on new lines
-            buf.ensure_ends_with_newline();
+            let indent = block_string_indent(buf, indent);
+
             buf.indent(indent);
             buf.push_str("\"\"\"");
             buf.push_newline_literal();
@@ -742,7 +745,7 @@ pub fn fmt_str_literal(buf: &mut Buf, literal: StrLiteral, indent: u16) {
                     // only add indent if the line isn't empty
                     if *seg != StrSegment::Plaintext("\n") {
                         buf.indent(indent);
-                        format_str_segment(seg, buf, indent);
+                        format_str_segment(seg, buf);
                     } else {
                         buf.push_newline_literal();
                     }
@@ -753,6 +756,65 @@ pub fn fmt_str_literal(buf: &mut Buf, literal: StrLiteral, indent: u16) {
             buf.indent(indent);
             buf.push_str("\"\"\"");
         }
+        Raw(string) => {
+            let hashes = "#".repeat(raw_str_hashes(string));
+
+            // The string is written exactly as it was, even the indentation of its lines.
+            buf.indent(indent);
+            buf.push('r');
+            buf.push_str(&hashes);
+            buf.push('"');
+            buf.push_str_allow_spaces(string);
+            buf.push('"');
+            buf.push_str(&hashes);
+        }
+    }
+}
+
+/// The fewest `#`s a raw string can be written with, which is one more than the most the string
+/// has in a row after a `"`.
+fn raw_str_hashes(string: &str) -> usize {
+    string
+        .split('"')
+        .skip(1)
+        .map(|after_quote| after_quote.len() - after_quote.trim_start_matches('#').len())
+        .max()
+        .map_or(1, |most| most + 1)
+}
+
+/// Block strings start on a line of their own. When something's already on the line (like a
+/// record field's name), they go on the next one, indented under it. Their contents are indented
+/// as much as the opening quotes, because that's the part of each line the parser leaves out.
+fn block_string_indent(buf: &mut Buf, indent: u16) -> u16 {
+    if buf.is_empty() || buf.ends_with_newline() {
+        indent
+    } else {
+        let line_indent = buf.cur_line_indent();
+
+        buf.newline();
+        indent.max(line_indent + INDENT)
+    }
+}
+
+/// Whether `expr` is a `|>` pipeline which would go past the pipeline width (if there is one)
+/// written on one line, starting at `column`. Those get written one step per line instead.
+pub fn is_pipeline_too_wide(buf: &Buf, expr: &Expr, column: u16) -> bool {
+    let Some(width) = buf.pipeline_width() else {
+        return false;
+    };
+
+    match expr {
+        Expr::BinOps(lefts, _)
+            if !expr.is_multiline() && lefts.iter().any(|(_, op)| op.value == BinOp::Pizza) =>
+        {
+            // It's measured on one line, so it mustn't get broken up for being too wide.
+            let mut scratch = buf.scratch().with_pipeline_width(None);
+
+            expr.format(&mut scratch, 0);
+
+            column as usize + scratch.as_str().chars().count() > width
+        }
+        _ => false,
     }
 }
 
@@ -846,49 +908,27 @@ fn fmt_when<'a>(
     buf.push_str("when");
     if is_multiline_condition {
         let condition_indent = indent + INDENT;
+        let condition = loc_condition.value.extract_spaces();
+
+        fmt_comments_only(
+            buf,
+            condition.before.iter(),
+            NewlineAt::Top,
+            condition_indent,
+        );
+        buf.newline();
+        condition.item.format(buf, condition_indent);
 
-        match &loc_condition.value {
-            Expr::SpaceBefore(expr_below, spaces_above_expr) => {
-                fmt_comments_only(
-                    buf,
-                    spaces_above_expr.iter(),
-                    NewlineAt::Top,
-                    condition_indent,
-                );
-                buf.newline();
-                match &expr_below {
-                    Expr::SpaceAfter(expr_above, spaces_below_expr) => {
-                        // If any of the spaces is a newline, add a newline at the top.
-                        // Otherwise leave it as just a comment.
-                        let newline_at = if spaces_below_expr
-                            .iter()
-                            .any(|spaces| matches!(spaces, CommentOrNewline::Newline))
-                        {
-                            NewlineAt::Top
-                        } else {
-                            NewlineAt::None
-                        };
+        // If any of the spaces after the condition is a newline, put its comments on their own
+        // lines. Otherwise leave them at the end of the condition's last line.
+        let newline_at = if condition.after.iter().any(|space| space.is_newline()) {
+            NewlineAt::Top
+        } else {
+            NewlineAt::None
+        };
 
-                        expr_above.format(buf, condition_indent);
-                        fmt_comments_only(
-                            buf,
-                            spaces_below_expr.iter(),
-                            newline_at,
-                            condition_indent,
-                        );
-                        buf.newline();
-                    }
-                    _ => {
-                        expr_below.format(buf, condition_indent);
-                    }
-                }
-            }
-            _ => {
-                buf.newline();
-                loc_condition.format(buf, condition_indent);
-                buf.newline();
-            }
-        }
+        fmt_comments_only(buf, condition.after.iter(), newline_at, condition_indent);
+        buf.newline();
         buf.indent(indent);
     } else {
         buf.spaces(1);
@@ -962,7 +1002,8 @@ fn fmt_when<'a>(
                     buf.indent(indent + INDENT);
                     buf.push('|');
                 } else {
-                    buf.push_str(" |");
+                    buf.space_or_indent(indent + INDENT);
+                    buf.push('|');
                 }
 
                 buf.spaces(1);
@@ -972,12 +1013,14 @@ fn fmt_when<'a>(
         }
 
         if let Some(guard_expr) = &branch.guard {
-            buf.push_str(" if");
+            buf.space_or_indent(indent + INDENT);
+            buf.push_str("if");
             buf.spaces(1);
             guard_expr.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
         }
 
-        buf.push_str(" ->");
+        buf.space_or_indent(indent + INDENT);
+        buf.push_str("->");
 
         match expr.value {
             Expr::SpaceBefore(nested, spaces) => {
@@ -1096,59 +1139,22 @@ fn fmt_if<'a>(
         buf.push_str("if");
 
         if is_multiline_condition {
-            match &loc_condition.value {
-                Expr::SpaceBefore(expr_below, spaces_before_expr) => {
-                    fmt_comments_only(
-                        buf,
-                        spaces_before_expr.iter(),
-                        NewlineAt::Top,
-                        return_indent,
-                    );
-                    buf.newline();
-
-                    match &expr_below {
-                        Expr::SpaceAfter(expr_above, spaces_after_expr) => {
-                            expr_above.format(buf, return_indent);
-
-                            // If any of the spaces is a newline, add a newline at the top.
-                            // Otherwise leave it as just a comment.
-                            let newline_at = if spaces_after_expr
-                                .iter()
-                                .any(|spaces| matches!(spaces, CommentOrNewline::Newline))
-                            {
-                                NewlineAt::Top
-                            } else {
-                                NewlineAt::None
-                            };
+            let condition = loc_condition.value.extract_spaces();
 
-                            fmt_comments_only(
-                                buf,
-                                spaces_after_expr.iter(),
-                                newline_at,
-                                return_indent,
-                            );
-                            buf.newline();
-                        }
-
-                        _ => {
-                            expr_below.format(buf, return_indent);
-                        }
-                    }
-                }
+            fmt_comments_only(buf, condition.before.iter(), NewlineAt::Top, return_indent);
+            buf.newline();
+            condition.item.format(buf, return_indent);
 
-                Expr::SpaceAfter(expr_above, spaces_below_expr) => {
-                    buf.newline();
-                    expr_above.format(buf, return_indent);
-                    fmt_comments_only(buf, spaces_below_expr.iter(), NewlineAt::Top, return_indent);
-                    buf.newline();
-                }
+            // If any of the spaces after the condition is a newline, put its comments on their
+            // own lines. Otherwise leave them at the end of the condition's last line.
+            let newline_at = if condition.after.iter().any(|space| space.is_newline()) {
+                NewlineAt::Top
+            } else {
+                NewlineAt::None
+            };
 
-                _ => {
-                    buf.newline();
-                    loc_condition.format(buf, return_indent);
-                    buf.newline();
-                }
-            }
+            fmt_comments_only(buf, condition.after.iter(), newline_at, return_indent);
+            buf.newline();
             buf.indent(indent);
         } else {
             buf.spaces(1);
@@ -1328,8 +1334,6 @@ fn fmt_backpassing<'a>(
     loc_ret: &'a Loc<Expr<'a>>,
     indent: u16,
 ) {
-    use self::Expr::*;
-
     let arguments_are_multiline = loc_patterns
         .iter()
         .any(|loc_pattern| loc_pattern.is_multiline());
@@ -1372,30 +1376,8 @@ fn fmt_backpassing<'a>(
 
     buf.push_str("<-");
 
-    let is_multiline = loc_ret.value.is_multiline();
-
-    // If the body is multiline, go down a line and indent.
-    let body_indent = if is_multiline {
-        indent + INDENT
-    } else {
-        indent
-    };
-
-    // the body of the Backpass can be on the same line, or
-    // on a new line. If it's on the same line, insert a space.
-
-    match &loc_body.value {
-        SpaceBefore(_, _) => {
-            // the body starts with (first comment and then) a newline
-            // do nothing
-        }
-        _ => {
-            // add a space after the `<-`
-            buf.spaces(1);
-        }
-    };
-
-    loc_body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, body_indent);
+    // Laid out like a def's body, so the steps of a backpassing sequence read like defs.
+    fmt_assigned_body(buf, &loc_body.value, indent);
     loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
 }
 
@@ -1423,12 +1405,13 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
     to_space_before: ToSpaceBefore,
 ) where
     Field: Formattable,
-    Format: Fn(&mut Buf, &Field, u16, &str),
+    Format: Fn(&mut Buf, &Field, u16, &str, bool),
     ToSpaceBefore: Fn(&'a Field) -> Option<(&'a Field, &'a [CommentOrNewline<'a>])>,
 {
     let loc_fields = fields.items;
     let final_comments = fields.final_comments();
     buf.indent(indent);
+    buf.begin_record_fields();
     if loc_fields.is_empty() && final_comments.iter().all(|c| c.is_newline()) && prefix.is_none() {
         buf.push_str("{}");
     } else {
@@ -1443,12 +1426,14 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
             Some(RecordPrefix::Update(record_var)) => {
                 buf.spaces(1);
                 record_var.format(buf, indent);
-                buf.push_str(" &");
+                buf.space_or_indent(indent + INDENT);
+                buf.push('&');
             }
             Some(RecordPrefix::Mapper(mapper_var)) => {
                 buf.spaces(1);
                 mapper_var.format(buf, indent);
-                buf.push_str(" <-");
+                buf.space_or_indent(indent + INDENT);
+                buf.push_str("<-");
             }
         }
 
@@ -1482,7 +1467,9 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
                     }
                 }
 
-                format_field_multiline(buf, &field.value, field_indent, "");
+                let is_last_field = index == loc_fields.len() - 1;
+
+                format_field_multiline(buf, &field.value, field_indent, "", is_last_field);
             }
 
             if count_leading_newlines(final_comments.iter()) > 1 {
@@ -1515,6 +1502,8 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
         buf.indent(indent);
         buf.push('}');
     }
+
+    buf.end_record_fields();
 }
 
 fn format_assigned_field_multiline<T>(
@@ -1522,6 +1511,7 @@ fn format_assigned_field_multiline<T>(
     field: &AssignedField<T>,
     indent: u16,
     separator_prefix: &str,
+    is_last_field: bool,
 ) where
     T: Formattable,
 {
@@ -1539,9 +1529,14 @@ fn format_assigned_field_multiline<T>(
 
             buf.push_str(separator_prefix);
             buf.push_str(":");
+
+            if spaces.is_empty() {
+                buf.record_field_padding();
+            }
+
             buf.spaces(1);
             ann.value.format(buf, indent);
-            buf.push(',');
+            buf.push_item_comma(is_last_field);
         }
         OptionalValue(name, spaces, ann) => {
             buf.newline();
@@ -1555,9 +1550,14 @@ fn format_assigned_field_multiline<T>(
 
             buf.push_str(separator_prefix);
             buf.push_str("?");
+
+            if spaces.is_empty() {
+                buf.record_field_padding();
+            }
+
             buf.spaces(1);
             ann.value.format(buf, indent);
-            buf.push(',');
+            buf.push_item_comma(is_last_field);
         }
         IgnoredValue(name, spaces, ann) => {
             buf.newline();
@@ -1572,15 +1572,20 @@ fn format_assigned_field_multiline<T>(
 
             buf.push_str(separator_prefix);
             buf.push_str(":");
+
+            if spaces.is_empty() {
+                buf.record_field_padding();
+            }
+
             buf.spaces(1);
             ann.value.format(buf, indent);
-            buf.push(',');
+            buf.push_item_comma(is_last_field);
         }
         LabelOnly(name) => {
             buf.newline();
             buf.indent(indent);
             buf.push_str(name.value);
-            buf.push(',');
+            buf.push_item_comma(is_last_field);
         }
         AssignedField::SpaceBefore(sub_field, _spaces) => {
             // We have something like that:
@@ -1590,7 +1595,13 @@ fn format_assigned_field_multiline<T>(
             // ```
             // we'd like to preserve this
 
-            format_assigned_field_multiline(buf, sub_field, indent, separator_prefix);
+            format_assigned_field_multiline(
+                buf,
+                sub_field,
+                indent,
+                separator_prefix,
+                is_last_field,
+            );
         }
         AssignedField::SpaceAfter(sub_field, spaces) => {
             // We have something like that:
@@ -1604,7 +1615,13 @@ fn format_assigned_field_multiline<T>(
             // # comment
             // otherfield
             // ```
-            format_assigned_field_multiline(buf, sub_field, indent, separator_prefix);
+            format_assigned_field_multiline(
+                buf,
+                sub_field,
+                indent,
+                separator_prefix,
+                is_last_field,
+            );
             fmt_comments_only(buf, spaces.iter(), NewlineAt::Top, indent);
         }
         Malformed(raw) => {
@@ -5,12 +5,13 @@ use crate::collection::{fmt_collection, Braces};
 use crate::expr::fmt_str_literal;
 use crate::spaces::{fmt_comments_only, fmt_default_spaces, fmt_spaces, NewlineAt, INDENT};
 use crate::Buf;
+use bumpalo::Bump;
 use roc_parse::ast::{Collection, CommentOrNewline, Header, Spaced, Spaces, SpacesBefore};
 use roc_parse::header::{
-    AppHeader, ExposedName, ExposesKeyword, HostedHeader, ImportsEntry, ImportsKeyword, Keyword,
-    KeywordItem, ModuleHeader, ModuleName, PackageEntry, PackageHeader, PackageKeyword,
-    PackageName, PackagesKeyword, PlatformHeader, PlatformKeyword, PlatformRequires,
-    ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent,
+    AppHeader, ExposedName, ExposesKeyword, HintEntry, HintsKeyword, HostedHeader, ImportsEntry,
+    ImportsKeyword, Keyword, KeywordItem, ModuleHeader, ModuleName, PackageEntry, PackageHeader,
+    PackageKeyword, PackageName, PackagesKeyword, PlatformHeader, PlatformKeyword,
+    PlatformRequires, ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent,
 };
 use roc_parse::ident::UppercaseIdent;
 use roc_region::all::Loc;
@@ -68,6 +69,7 @@ keywords! {
     ProvidesKeyword,
     ToKeyword,
     PlatformKeyword,
+    HintsKeyword,
 }
 
 impl<V: Formattable> Formattable for Option<V> {
@@ -197,6 +199,7 @@ pub fn fmt_hosted_header<'a>(buf: &mut Buf, header: &'a HostedHeader<'a>) {
     let indent = INDENT;
     fmt_default_spaces(buf, header.before_name, indent);
 
+    buf.indent(indent);
     buf.push_str(header.name.value.as_str());
 
     header.exposes.keyword.format(buf, indent);
@@ -223,6 +226,7 @@ pub fn fmt_spaces_with_outdent(buf: &mut Buf, spaces: &[CommentOrNewline], inden
     } else {
         let indent = max(INDENT, indent + INDENT);
         fmt_default_spaces(buf, spaces, indent);
+        buf.indent(indent);
         indent
     }
 }
@@ -255,6 +259,11 @@ pub fn fmt_platform_header<'a>(buf: &mut Buf, header: &'a PlatformHeader<'a>) {
     fmt_imports(buf, header.imports.item, indent);
     header.provides.keyword.format(buf, indent);
     fmt_provides(buf, header.provides.item, None, indent);
+
+    if let Some(hints) = &header.hints {
+        hints.keyword.format(buf, indent);
+        fmt_collection(buf, indent, Braces::Curly, hints.item, Newlines::No);
+    }
 }
 
 fn fmt_requires(buf: &mut Buf, requires: &PlatformRequires, indent: u16) {
@@ -451,6 +460,27 @@ impl<'a> Formattable for PackageEntry<'a> {
     }
 }
 
+impl<'a> Formattable for HintEntry<'a> {
+    fn is_multiline(&self) -> bool {
+        false
+    }
+
+    fn format_with_options(
+        &self,
+        buf: &mut Buf,
+        _parens: Parens,
+        _newlines: Newlines,
+        indent: u16,
+    ) {
+        buf.indent(indent);
+        buf.push_str(self.name.value);
+        buf.push(':');
+        fmt_default_spaces(buf, self.spaces_after_name, indent);
+        buf.indent(indent + INDENT);
+        fmt_str_literal(buf, self.hint.value, indent + INDENT);
+    }
+}
+
 impl<'a> Formattable for ImportsEntry<'a> {
     fn is_multiline(&self) -> bool {
         false
@@ -523,3 +553,267 @@ fn fmt_imports_entry(buf: &mut Buf, entry: &ImportsEntry, indent: u16) {
         }
     }
 }
+
+/// Alphabetizes the lists in a header: what it exposes, provides, and imports, and its packages.
+/// Entries which appear more than once are merged, unless that would lose a comment.
+pub fn sort_header_lists<'a>(arena: &'a Bump, header: &Header<'a>) -> Header<'a> {
+    match header {
+        Header::Module(header) => Header::Module(ModuleHeader {
+            exposes: sort_exposed_names(arena, header.exposes),
+            interface_imports: header
+                .interface_imports
+                .map(|imports| sort_keyword_item(imports, |list| sort_imports(arena, list))),
+            ..header.clone()
+        }),
+        Header::App(header) => Header::App(AppHeader {
+            provides: sort_exposed_names(arena, header.provides),
+            packages: header
+                .packages
+                .map(|packages| sort_packages(arena, *packages)),
+            old_imports: header
+                .old_imports
+                .map(|imports| sort_keyword_item(imports, |list| sort_imports(arena, list))),
+            ..header.clone()
+        }),
+        Header::Package(header) => Header::Package(PackageHeader {
+            exposes: sort_module_names(arena, header.exposes),
+            packages: header
+                .packages
+                .map(|packages| sort_packages(arena, *packages)),
+            ..header.clone()
+        }),
+        Header::Platform(header) => Header::Platform(PlatformHeader {
+            exposes: sort_keyword_item(header.exposes, |list| sort_module_names(arena, list)),
+            packages: sort_keyword_item(header.packages, |list| sort_packages(arena, list)),
+            imports: sort_keyword_item(header.imports, |list| sort_imports(arena, list)),
+            provides: sort_keyword_item(header.provides, |list| sort_exposed_names(arena, list)),
+            ..header.clone()
+        }),
+        Header::Hosted(header) => Header::Hosted(HostedHeader {
+            exposes: sort_keyword_item(header.exposes, |list| sort_exposed_names(arena, list)),
+            imports: sort_keyword_item(header.imports, |list| sort_imports(arena, list)),
+            ..header.clone()
+        }),
+    }
+}
+
+fn sort_keyword_item<'a, K, V>(
+    keyword_item: KeywordItem<'a, K, V>,
+    sort: impl FnOnce(V) -> V,
+) -> KeywordItem<'a, K, V> {
+    KeywordItem {
+        keyword: keyword_item.keyword,
+        item: sort(keyword_item.item),
+    }
+}
+
+fn sort_exposed_names<'a>(
+    arena: &'a Bump,
+    names: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+) -> Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>> {
+    sort_list(
+        arena,
+        names,
+        |name| <&str>::from(*name).to_string(),
+        |a, b| (a == b).then_some(*a),
+    )
+}
+
+fn sort_module_names<'a>(
+    arena: &'a Bump,
+    names: Collection<'a, Loc<Spaced<'a, ModuleName<'a>>>>,
+) -> Collection<'a, Loc<Spaced<'a, ModuleName<'a>>>> {
+    sort_list(
+        arena,
+        names,
+        |name| name.as_str().to_string(),
+        |a, b| (a == b).then_some(*a),
+    )
+}
+
+fn sort_packages<'a>(
+    arena: &'a Bump,
+    packages: Collection<'a, Loc<Spaced<'a, PackageEntry<'a>>>>,
+) -> Collection<'a, Loc<Spaced<'a, PackageEntry<'a>>>> {
+    sort_list(
+        arena,
+        packages,
+        |entry| entry.shorthand.to_string(),
+        // Two different packages with the same shorthand are an error, which shouldn't go away
+        // just because the header got sorted.
+        |a, b| {
+            let same_package = a.shorthand == b.shorthand
+                && a.package_name.value == b.package_name.value
+                && a.platform_marker.is_some() == b.platform_marker.is_some();
+            let b_has_comments = has_comments(b.spaces_after_shorthand)
+                || has_comments(b.platform_marker.unwrap_or_default());
+
+            (same_package && !b_has_comments).then_some(*a)
+        },
+    )
+}
+
+fn sort_imports<'a>(
+    arena: &'a Bump,
+    imports: Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>,
+) -> Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>> {
+    // What each import exposes gets sorted too.
+    let imports = imports.map_items(arena, |loc_entry| {
+        loc_entry.map(|spaced| {
+            spaced.map(arena, |entry| match entry {
+                ImportsEntry::Module(name, exposes) => {
+                    ImportsEntry::Module(*name, sort_exposed_names(arena, *exposes))
+                }
+                ImportsEntry::Package(shorthand, name, exposes) => {
+                    ImportsEntry::Package(shorthand, *name, sort_exposed_names(arena, *exposes))
+                }
+                ImportsEntry::IngestedFile(..) => *entry,
+            })
+        })
+    });
+
+    sort_list(
+        arena,
+        imports,
+        |entry| match entry {
+            ImportsEntry::Module(name, _) => name.as_str().to_string(),
+            ImportsEntry::Package(shorthand, name, _) => {
+                format!("{shorthand}.{}", name.as_str())
+            }
+            ImportsEntry::IngestedFile(_, typed_ident) => {
+                typed_ident.item().ident.value.to_string()
+            }
+        },
+        |a, b| match (a, b) {
+            (ImportsEntry::Module(a_name, a_exposes), ImportsEntry::Module(b_name, b_exposes))
+                if a_name == b_name =>
+            {
+                merge_exposes(arena, *a_exposes, *b_exposes)
+                    .map(|exposes| ImportsEntry::Module(*a_name, exposes))
+            }
+            (
+                ImportsEntry::Package(a_shorthand, a_name, a_exposes),
+                ImportsEntry::Package(b_shorthand, b_name, b_exposes),
+            ) if a_shorthand == b_shorthand && a_name == b_name => {
+                merge_exposes(arena, *a_exposes, *b_exposes)
+                    .map(|exposes| ImportsEntry::Package(a_shorthand, *a_name, exposes))
+            }
+            (ImportsEntry::IngestedFile(..), ImportsEntry::IngestedFile(..)) => {
+                (a == b).then_some(*a)
+            }
+            _ => None,
+        },
+    )
+}
+
+/// What two imports of the same module expose, together.
+fn merge_exposes<'a>(
+    arena: &'a Bump,
+    a: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+    b: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+) -> Option<Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>> {
+    if has_comments(b.final_comments()) {
+        return None;
+    }
+
+    let items = arena.alloc_slice_copy(&[a.items, b.items].concat());
+
+    Some(sort_exposed_names(arena, a.replace_items(items)))
+}
+
+/// Sorts a list by each entry's key, ignoring case, then merges each run of entries with the same
+/// key for which `merge` returns something. An entry with comments of its own is never merged
+/// into the one before it, because the comments would be lost.
+fn sort_list<'a, T: Copy>(
+    arena: &'a Bump,
+    list: Collection<'a, Loc<Spaced<'a, T>>>,
+    key: impl Fn(&T) -> String,
+    merge: impl Fn(&T, &T) -> Option<T>,
+) -> Collection<'a, Loc<Spaced<'a, T>>> {
+    let (mut entries, final_comments) = attach_trailing_comments(arena, list);
+
+    entries.sort_by_cached_key(|entry| {
+        let key = key(entry.value.item());
+
+        (key.to_lowercase(), key)
+    });
+
+    let mut sorted: Vec<Loc<Spaced<'a, T>>> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        if let Some(prev) = sorted.last_mut() {
+            if !spaced_has_comments(&entry.value) {
+                if let Some(merged) = merge(prev.value.item(), entry.value.item()) {
+                    prev.value = prev.value.map(arena, |_| merged);
+
+                    continue;
+                }
+            }
+        }
+
+        sorted.push(entry);
+    }
+
+    Collection::with_items_and_comments(arena, arena.alloc_slice_copy(&sorted), final_comments)
+}
+
+/// The parser puts a comment at the end of an entry's line before the next entry (or with the
+/// list's final comments), where it would get separated from its entry by sorting. This moves
+/// those comments after the entries they're next to.
+fn attach_trailing_comments<'a, T: Copy>(
+    arena: &'a Bump,
+    list: Collection<'a, Loc<Spaced<'a, T>>>,
+) -> (Vec<Loc<Spaced<'a, T>>>, &'a [CommentOrNewline<'a>]) {
+    let mut entries = list.items.to_vec();
+    let mut final_comments = list.final_comments();
+
+    for index in 0..entries.len() {
+        let spaces_after = match entries.get(index + 1) {
+            Some(next) => match next.value {
+                Spaced::SpaceBefore(_, spaces) => spaces,
+                _ => &[],
+            },
+            None => final_comments,
+        };
+
+        let (comment, rest) = match spaces_after.split_first() {
+            Some((comment @ CommentOrNewline::LineComment(_), rest))
+                if !matches!(entries[index].value, Spaced::SpaceAfter(..)) =>
+            {
+                (*comment, rest)
+            }
+            _ => continue,
+        };
+
+        entries[index].value =
+            Spaced::SpaceAfter(arena.alloc(entries[index].value), arena.alloc([comment]));
+
+        match entries.get_mut(index + 1) {
+            Some(next) => {
+                if let Spaced::SpaceBefore(inner, _) = next.value {
+                    next.value = if rest.is_empty() {
+                        *inner
+                    } else {
+                        Spaced::SpaceBefore(inner, rest)
+                    };
+                }
+            }
+            None => final_comments = rest,
+        }
+    }
+
+    (entries, final_comments)
+}
+
+fn spaced_has_comments<T>(spaced: &Spaced<'_, T>) -> bool {
+    match spaced {
+        Spaced::Item(_) => false,
+        Spaced::SpaceBefore(inner, spaces) | Spaced::SpaceAfter(inner, spaces) => {
+            has_comments(spaces) || spaced_has_comments(inner)
+        }
+    }
+}
+
+fn has_comments(spaces: &[CommentOrNewline<'_>]) -> bool {
+    spaces.iter().any(CommentOrNewline::is_comment)
+}
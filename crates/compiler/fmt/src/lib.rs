@@ -5,19 +5,82 @@
 pub mod annotation;
 pub mod collection;
 pub mod def;
+pub mod edits;
 pub mod expr;
 pub mod header;
+mod number;
 pub mod pattern;
+mod reflow;
 pub mod spaces;
 
 use bumpalo::{collections::String, Bump};
 
+/// Whether the last item in a multiline list, tuple, record, or tag union gets a comma after it,
+/// like the others do. Single-line ones never end with a comma.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingCommas {
+    #[default]
+    Always,
+    Never,
+}
+
+/// Which blank lines the formatter keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlankLines {
+    /// Runs of more blank lines than this get collapsed to this many. It should be at least 1, so
+    /// single blank lines (like the ones between the steps of a def's body) stay.
+    pub max: usize,
+    /// Whether there's always exactly one blank line between top-level defs, instead of however
+    /// many (up to the max) there were.
+    pub between_top_level_defs: bool,
+}
+
+impl Default for BlankLines {
+    fn default() -> Self {
+        Self {
+            max: 1,
+            between_top_level_defs: false,
+        }
+    }
+}
+
+/// How number literals get written. By default, they're written as they are.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLiterals {
+    /// Decimal numbers with more digits than this before the `.` get an underscore between each
+    /// group of three (like `1_000_000`), and others don't get any.
+    pub group_digits_above: Option<usize>,
+    /// The case of the letters in hex literals, like the `ff` in `0xff`.
+    pub hex_case: Option<HexCase>,
+    /// Whether float exponents are written with a lowercase `e` and without leading zeros, like
+    /// `1.5e5` instead of `1.5E05`.
+    pub normalize_exponents: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexCase {
+    Upper,
+    Lower,
+}
+
 #[derive(Debug)]
 pub struct Buf<'a> {
     text: String<'a>,
     spaces_to_flush: usize,
     newlines_to_flush: usize,
     beginning_of_line: bool,
+    trailing_commas: TrailingCommas,
+    doc_comment_width: Option<usize>,
+    blank_lines: BlankLines,
+    align_record_fields: bool,
+    number_literals: NumberLiterals,
+    pipeline_width: Option<usize>,
+    /// For aligning record fields: the offsets in `text` where each unfinished record's fields
+    /// can be padded, innermost record last.
+    open_field_groups: Vec<Vec<usize>>,
+    /// The same, for finished records inside the unfinished ones. They get padded once the
+    /// outermost record is finished, since padding moves the text after it.
+    field_groups: Vec<Vec<usize>>,
 }
 
 impl<'a> Buf<'a> {
@@ -27,9 +90,185 @@ impl<'a> Buf<'a> {
             spaces_to_flush: 0,
             newlines_to_flush: 0,
             beginning_of_line: true,
+            trailing_commas: TrailingCommas::default(),
+            doc_comment_width: None,
+            blank_lines: BlankLines::default(),
+            align_record_fields: false,
+            number_literals: NumberLiterals::default(),
+            pipeline_width: None,
+            open_field_groups: Vec::new(),
+            field_groups: Vec::new(),
+        }
+    }
+
+    pub fn with_trailing_commas(self, trailing_commas: TrailingCommas) -> Buf<'a> {
+        Buf {
+            trailing_commas,
+            ..self
+        }
+    }
+
+    /// Rewraps doc comment paragraphs to fit in this many columns. Without a width, doc comments
+    /// keep their line breaks.
+    pub fn with_doc_comment_width(self, doc_comment_width: Option<usize>) -> Buf<'a> {
+        Buf {
+            doc_comment_width,
+            ..self
+        }
+    }
+
+    pub fn doc_comment_width(&self) -> Option<usize> {
+        self.doc_comment_width
+    }
+
+    pub fn with_blank_lines(self, blank_lines: BlankLines) -> Buf<'a> {
+        Buf {
+            blank_lines,
+            ..self
+        }
+    }
+
+    pub fn blank_lines(&self) -> BlankLines {
+        self.blank_lines
+    }
+
+    /// Lines up the fields of multiline records, with spaces before the `:` in record types and
+    /// after it in record values:
+    ///
+    /// ```roc
+    /// { name   : Str, ... }
+    /// { name:    "Sam", ... }
+    /// ```
+    pub fn with_aligned_record_fields(self, align_record_fields: bool) -> Buf<'a> {
+        Buf {
+            align_record_fields,
+            ..self
+        }
+    }
+
+    pub fn with_number_literals(self, number_literals: NumberLiterals) -> Buf<'a> {
+        Buf {
+            number_literals,
+            ..self
+        }
+    }
+
+    pub fn number_literals(&self) -> NumberLiterals {
+        self.number_literals
+    }
+
+    /// Breaks `|>` pipelines which would go past this many columns on one line into one step per
+    /// line, like pipelines which were already written on several lines:
+    ///
+    /// ```roc
+    /// names =
+    ///     users
+    ///     |> List.keepIf isActive
+    ///     |> List.map .name
+    /// ```
+    ///
+    /// Without a width, pipelines keep their line breaks.
+    pub fn with_pipeline_width(self, pipeline_width: Option<usize>) -> Buf<'a> {
+        Buf {
+            pipeline_width,
+            ..self
+        }
+    }
+
+    pub fn pipeline_width(&self) -> Option<usize> {
+        self.pipeline_width
+    }
+
+    /// An empty buf with the same settings, for finding out how wide something would be before
+    /// deciding how to write it.
+    pub fn scratch(&self) -> Buf<'a> {
+        Buf {
+            text: String::new_in(self.text.bump()),
+            spaces_to_flush: 0,
+            newlines_to_flush: 0,
+            beginning_of_line: true,
+            open_field_groups: Vec::new(),
+            field_groups: Vec::new(),
+            ..*self
+        }
+    }
+
+    /// Starts a record whose fields get lined up, if that's on. Every call needs a matching
+    /// `end_record_fields` once the record has been written.
+    pub fn begin_record_fields(&mut self) {
+        if self.align_record_fields {
+            self.open_field_groups.push(Vec::new());
+        }
+    }
+
+    /// Where the innermost record's current field can be padded to line up with the others.
+    pub fn record_field_padding(&mut self) {
+        if let Some(group) = self.open_field_groups.last_mut() {
+            group.push(self.text.len());
+        }
+    }
+
+    pub fn end_record_fields(&mut self) {
+        if let Some(group) = self.open_field_groups.pop() {
+            self.field_groups.push(group);
+
+            if self.open_field_groups.is_empty() {
+                self.align_fields();
+            }
+        }
+    }
+
+    /// Pads the finished records' fields so they line up. This runs after their layout is done,
+    /// so it only sees fields which ended up on lines of their own. A field whose value starts on
+    /// the next line doesn't get padded, and neither do records with several fields on a line
+    /// (single-line ones).
+    fn align_fields(&mut self) {
+        let mut padding: Vec<(usize, usize)> = Vec::new();
+
+        for group in std::mem::take(&mut self.field_groups) {
+            let mut columns = Vec::with_capacity(group.len());
+            let mut prev_line_start = None;
+
+            for offset in group {
+                let line_start = self.text[..offset].rfind('\n').map_or(0, |index| index + 1);
+                let rest_of_line = self.text[offset..].split('\n').next().unwrap_or("");
+
+                if prev_line_start == Some(line_start) {
+                    columns.clear();
+                    break;
+                }
+
+                prev_line_start = Some(line_start);
+
+                if !rest_of_line.trim().is_empty() {
+                    columns.push((offset, offset - line_start));
+                }
+            }
+
+            if let Some(max_column) = columns.iter().map(|(_, column)| *column).max() {
+                padding.extend(
+                    columns
+                        .into_iter()
+                        .map(|(offset, column)| (offset, max_column - column)),
+                );
+            }
+        }
+
+        // From the end, so the offsets still to pad don't move.
+        padding.sort_unstable();
+
+        for (offset, spaces) in padding.into_iter().rev() {
+            if spaces > 0 {
+                self.text.insert_str(offset, &" ".repeat(spaces));
+            }
         }
     }
 
+    /// The most newlines in a row, which is one more than the most blank lines in a row.
+    pub fn max_consecutive_newlines(&self) -> usize {
+        self.blank_lines.max.max(1) + 1
+    }
+
     pub fn as_str(&'a self) -> &'a str {
         self.text.as_str()
     }
@@ -99,8 +338,27 @@ impl<'a> Buf<'a> {
         self.spaces_to_flush += count;
     }
 
+    /// For things which usually go on the same line as what's before them, like the `->` after a
+    /// `when` branch's pattern: adds a space, unless a comment ended the line, in which case it
+    /// indents the next one instead.
+    pub fn space_or_indent(&mut self, indent: u16) {
+        if self.beginning_of_line {
+            self.indent(indent);
+        } else {
+            self.spaces(1);
+        }
+    }
+
+    /// The comma after an item in a multiline collection, which the last item only gets if
+    /// trailing commas are on.
+    pub fn push_item_comma(&mut self, is_last_item: bool) {
+        if !is_last_item || self.trailing_commas == TrailingCommas::Always {
+            self.push(',');
+        }
+    }
+
     /// Only for use in emitting newlines in block strings, which don't follow the rule of
-    /// having at most two newlines in a row.
+    /// having at most `max_consecutive_newlines` in a row.
     pub fn push_newline_literal(&mut self) {
         self.spaces_to_flush = 0;
         self.newlines_to_flush += 1;
@@ -109,7 +367,19 @@ impl<'a> Buf<'a> {
 
     pub fn newline(&mut self) {
         self.spaces_to_flush = 0;
-        self.newlines_to_flush = std::cmp::min(self.newlines_to_flush + 1, 2);
+        self.newlines_to_flush = std::cmp::max(
+            self.newlines_to_flush,
+            std::cmp::min(self.newlines_to_flush + 1, 2),
+        );
+        self.beginning_of_line = true;
+    }
+
+    /// Like `newline`, but for keeping the blank lines from the source, so it can add more than
+    /// one blank line in a row (up to the max).
+    pub fn newline_keeping_blank_lines(&mut self) {
+        self.spaces_to_flush = 0;
+        self.newlines_to_flush =
+            std::cmp::min(self.newlines_to_flush + 1, self.max_consecutive_newlines());
         self.beginning_of_line = true;
     }
 
@@ -147,6 +417,20 @@ impl<'a> Buf<'a> {
         self.flush_spaces();
     }
 
+    /// How far the line being written is indented.
+    pub fn cur_line_indent(&self) -> u16 {
+        if self.newlines_to_flush > 0 || self.beginning_of_line {
+            return self.spaces_to_flush as u16;
+        }
+
+        let line = match self.text.rfind('\n') {
+            Some(index) => &self.text[index + 1..],
+            None => &self.text,
+        };
+
+        (line.len() - line.trim_start_matches(' ').len()) as u16
+    }
+
     pub fn ends_with_space(&self) -> bool {
         self.spaces_to_flush > 0 || self.text.ends_with(' ')
     }
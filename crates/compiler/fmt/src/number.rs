@@ -0,0 +1,107 @@
+//! Writing number literals in a consistent style, for formatters configured with one (see
+//! `Buf::with_number_literals`).
+use roc_parse::number_literal::split_number_suffix;
+
+use crate::{Buf, HexCase};
+
+/// A decimal integer or float literal, like `-1000000u64` or `1.5E05`.
+pub fn fmt_decimal_literal(buf: &mut Buf, literal: &str, is_float: bool) {
+    let rules = buf.number_literals();
+
+    if rules.group_digits_above.is_none() && !rules.normalize_exponents {
+        buf.push_str(literal);
+        return;
+    }
+
+    let (sign, unsigned) = match literal.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", literal),
+    };
+    let (number, suffix) = split_number_suffix(unsigned);
+    let integer_len = number.find(['.', 'e', 'E']).unwrap_or(number.len());
+    let (integer, rest) = number.split_at(integer_len);
+
+    // Integers with letters in them don't parse, and get reported later.
+    let is_valid = integer.chars().all(|c| c.is_ascii_digit() || c == '_');
+
+    let integer = match rules.group_digits_above {
+        Some(max_ungrouped) if is_valid => group_digits(integer, max_ungrouped),
+        _ => integer.to_string(),
+    };
+
+    // Without a `.`, an uppercase `E` is a letter rather than an exponent, so a literal like
+    // `15E05` isn't a float.
+    let rest = if rules.normalize_exponents && is_float {
+        normalize_exponent(rest)
+    } else {
+        rest.to_string()
+    };
+
+    buf.push_str(&format!("{sign}{integer}{rest}{suffix}"));
+}
+
+/// The digits of a hex literal, after the `0x`.
+pub fn fmt_hex_digits(buf: &mut Buf, literal: &str) {
+    let (digits, suffix) = split_number_suffix(literal);
+    let digits = match buf.number_literals().hex_case {
+        Some(HexCase::Upper) => digits.to_ascii_uppercase(),
+        Some(HexCase::Lower) => digits.to_ascii_lowercase(),
+        None => digits.to_string(),
+    };
+    let cased = format!("{digits}{suffix}");
+
+    // Lowercase digits can end up looking like a suffix, like the `dec` in `0xABCDEC`, which
+    // would change the literal's type. Those stay as they were.
+    if split_number_suffix(&cased).1 == suffix {
+        buf.push_str(&cased);
+    } else {
+        buf.push_str(literal);
+    }
+}
+
+/// Integers with more than `max_ungrouped` digits get an underscore between each group of three,
+/// and others don't get any.
+fn group_digits(integer: &str, max_ungrouped: usize) -> String {
+    let digits: Vec<char> = integer.chars().filter(|c| *c != '_').collect();
+
+    if digits.len() <= max_ungrouped {
+        return digits.into_iter().collect();
+    }
+
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (index, digit) in digits.iter().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push('_');
+        }
+
+        grouped.push(*digit);
+    }
+
+    grouped
+}
+
+/// Writes the exponent in what comes after a float's integer part (like the `.5E05` in `1.5E05`)
+/// with a lowercase `e`, and without leading zeros.
+fn normalize_exponent(fraction_and_exponent: &str) -> String {
+    let Some(e_index) = fraction_and_exponent.find(['e', 'E']) else {
+        return fraction_and_exponent.to_string();
+    };
+
+    let (fraction, exponent) = fraction_and_exponent.split_at(e_index);
+    let exponent = &exponent[1..];
+    let (sign, exponent) = match exponent.strip_prefix('-') {
+        Some(exponent) => ("-", exponent),
+        None => ("", exponent),
+    };
+    let without_zeros = exponent.trim_start_matches('0');
+
+    // An exponent of zero keeps one
+    let exponent = if without_zeros.starts_with(|c: char| c.is_ascii_digit()) {
+        without_zeros
+    } else {
+        &exponent[exponent.len() - without_zeros.len() - 1..]
+    };
+
+    format!("{fraction}e{sign}{exponent}")
+}
@@ -136,6 +136,8 @@ impl<'a> Formattable for Pattern<'a> {
                         loc_pattern.format(buf, indent);
 
                         if it.peek().is_some() {
+                            // A field can end with a comment, so the comma may start a line.
+                            buf.indent(indent);
                             buf.push_str(",");
                             buf.spaces(1);
                         }
@@ -143,6 +145,7 @@ impl<'a> Formattable for Pattern<'a> {
                     buf.spaces(1);
                 }
 
+                buf.indent(indent);
                 buf.push_str("}");
             }
 
@@ -0,0 +1,152 @@
+//! Rewrapping the paragraphs of `##` doc comments to fit in a width, for formatters configured
+//! with one (see `Buf::with_doc_comment_width`).
+//!
+//! Docs are Markdown, so only text that Markdown would wrap anyway gets rewrapped: paragraphs,
+//! and list items on their own. Code blocks, headings, quotes, tables, and HTML stay exactly as
+//! they are.
+
+/// Doc comments narrower than this would be mostly line breaks, so deeply indented ones are
+/// wrapped to this many columns instead, and go past the width.
+const MIN_TEXT_WIDTH: usize = 40;
+
+/// Each line is what comes after the `## `, and so are the returned lines.
+pub fn reflow_docs(lines: &[&str], indent: u16, width: usize) -> Vec<String> {
+    let text_width = width
+        .saturating_sub(indent as usize + "## ".len())
+        .max(MIN_TEXT_WIDTH);
+    let mut output = Vec::with_capacity(lines.len());
+    let mut paragraph = Paragraph::default();
+    let mut in_fence = false;
+
+    for line in lines {
+        let trimmed = line.trim_start();
+        let leading_spaces = line.len() - trimmed.len();
+
+        if in_fence || is_fence(trimmed) {
+            paragraph.wrap_into(&mut output, text_width);
+            output.push(line.to_string());
+
+            if is_fence(trimmed) {
+                in_fence = !in_fence;
+            }
+        } else if trimmed.is_empty() {
+            paragraph.wrap_into(&mut output, text_width);
+            output.push(String::new());
+        } else if leading_spaces >= 4 && !paragraph.is_open() {
+            // Indented code
+            output.push(line.to_string());
+        } else if let Some(marker_len) = list_marker_len(trimmed) {
+            paragraph.wrap_into(&mut output, text_width);
+            paragraph = Paragraph {
+                first_prefix: line[..leading_spaces + marker_len].to_string(),
+                rest_prefix: " ".repeat(leading_spaces + marker_len),
+                words: trimmed[marker_len..].split_whitespace().collect(),
+            };
+        } else if is_unwrappable(trimmed) {
+            paragraph.wrap_into(&mut output, text_width);
+            output.push(line.to_string());
+        } else if paragraph.is_open() && leading_spaces >= paragraph.rest_prefix.len() {
+            paragraph.words.extend(trimmed.split_whitespace());
+        } else {
+            // A new paragraph, which keeps its indentation (it could be inside a list item).
+            paragraph.wrap_into(&mut output, text_width);
+            paragraph = Paragraph {
+                first_prefix: line[..leading_spaces].to_string(),
+                rest_prefix: line[..leading_spaces].to_string(),
+                words: trimmed.split_whitespace().collect(),
+            };
+        }
+    }
+
+    paragraph.wrap_into(&mut output, text_width);
+
+    output
+}
+
+#[derive(Default)]
+struct Paragraph<'a> {
+    /// What goes before the first line's words, like `- ` for a list item.
+    first_prefix: String,
+    /// What goes before the other lines' words, to line them up with the first line's.
+    rest_prefix: String,
+    words: Vec<&'a str>,
+}
+
+impl<'a> Paragraph<'a> {
+    fn is_open(&self) -> bool {
+        !self.first_prefix.is_empty() || !self.words.is_empty()
+    }
+
+    /// Adds the paragraph's lines to the output, and empties it.
+    fn wrap_into(&mut self, output: &mut Vec<String>, text_width: usize) {
+        let Paragraph {
+            first_prefix,
+            rest_prefix,
+            words,
+        } = std::mem::take(self);
+
+        if words.is_empty() {
+            if !first_prefix.is_empty() {
+                output.push(first_prefix.trim_end().to_string());
+            }
+
+            return;
+        }
+
+        let mut line = first_prefix;
+        let mut line_has_words = false;
+
+        for word in words {
+            // A line starting with something like `-` or `#` would become a list item or a
+            // heading, so those stay on the line before, even if it gets too long.
+            let can_start_line = list_marker_len(word).is_none() && !is_unwrappable(word);
+
+            if line_has_words && can_start_line && line.len() + 1 + word.len() > text_width {
+                output.push(line);
+                line = rest_prefix.clone();
+                line_has_words = false;
+            }
+
+            if line_has_words {
+                line.push(' ');
+            }
+
+            line.push_str(word);
+            line_has_words = true;
+        }
+
+        output.push(line);
+    }
+}
+
+fn is_fence(trimmed: &str) -> bool {
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Lines which are a block of their own in Markdown, so they can't be joined with others.
+fn is_unwrappable(trimmed: &str) -> bool {
+    is_fence(trimmed)
+        || trimmed.starts_with('#')
+        || trimmed.starts_with('>')
+        || trimmed.starts_with('|')
+        || trimmed.starts_with('<')
+        || trimmed
+            .chars()
+            .all(|c| matches!(c, '-' | '*' | '_' | '=' | ' '))
+}
+
+/// The length of the bullet or number at the start of a list item, including the space after it.
+fn list_marker_len(trimmed: &str) -> Option<usize> {
+    let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+    let marker_len = match trimmed.as_bytes().get(digits) {
+        Some(b'-' | b'*' | b'+') if digits == 0 => 1,
+        Some(b'.' | b')') if digits > 0 => digits + 1,
+        _ => return None,
+    };
+
+    match trimmed.as_bytes().get(marker_len) {
+        Some(b' ') => Some(marker_len + 1),
+        None => Some(marker_len),
+        _ => None,
+    }
+}
@@ -1,5 +1,8 @@
+use std::iter::Peekable;
+
 use roc_parse::ast::CommentOrNewline;
 
+use crate::reflow::reflow_docs;
 use crate::Buf;
 
 /// The number of spaces to indent.
@@ -32,10 +35,12 @@ pub fn fmt_spaces<'a, 'buf, I>(buf: &mut Buf<'buf>, spaces: I, indent: u16)
 where
     I: Iterator<Item = &'a CommentOrNewline<'a>>,
 {
-    fmt_spaces_max_consecutive_newlines(buf, spaces, 2, indent)
+    let max_consecutive_newlines = buf.max_consecutive_newlines();
+
+    fmt_spaces_max_consecutive_newlines(buf, spaces, max_consecutive_newlines, indent)
 }
 
-fn fmt_spaces_max_consecutive_newlines<'a, 'buf, I>(
+pub fn fmt_spaces_max_consecutive_newlines<'a, 'buf, I>(
     buf: &mut Buf<'buf>,
     spaces: I,
     max_consecutive_newlines: usize,
@@ -45,15 +50,16 @@ fn fmt_spaces_max_consecutive_newlines<'a, 'buf, I>(
 {
     use self::CommentOrNewline::*;
 
-    // Only ever print two newlines back to back.
+    // Only ever print max_consecutive_newlines back to back.
     // (Two newlines renders as one blank line.)
     let mut consecutive_newlines = 0;
+    let mut spaces = spaces.peekable();
 
-    for space in spaces {
+    while let Some(space) = spaces.next() {
         match space {
             Newline => {
                 if consecutive_newlines < max_consecutive_newlines {
-                    buf.newline();
+                    buf.newline_keeping_blank_lines();
 
                     // Don't bother incrementing it if we're already over the limit.
                     // There's no upside, and it might eventually overflow.
@@ -68,9 +74,11 @@ fn fmt_spaces_max_consecutive_newlines<'a, 'buf, I>(
                 consecutive_newlines = 1;
             }
             DocComment(docs) => {
-                buf.indent(indent);
-                fmt_docs(buf, docs);
-                buf.newline();
+                for docs in doc_comment_lines(buf, docs, &mut spaces, indent) {
+                    buf.indent(indent);
+                    fmt_docs(buf, &docs);
+                    buf.newline();
+                }
 
                 consecutive_newlines = 1;
             }
@@ -102,8 +110,9 @@ pub fn fmt_comments_only<'a, 'buf, I>(
     use NewlineAt::*;
 
     let mut comment_seen = false;
+    let mut spaces = spaces.peekable();
 
-    for space in spaces {
+    while let Some(space) = spaces.next() {
         match space {
             Newline => {}
             LineComment(comment) => {
@@ -115,12 +124,14 @@ pub fn fmt_comments_only<'a, 'buf, I>(
                 comment_seen = true;
             }
             DocComment(docs) => {
-                if comment_seen || new_line_at == Top || new_line_at == Both {
-                    buf.newline();
+                for docs in doc_comment_lines(buf, docs, &mut spaces, indent) {
+                    if comment_seen || new_line_at == Top || new_line_at == Both {
+                        buf.newline();
+                    }
+                    buf.indent(indent);
+                    fmt_docs(buf, &docs);
+                    comment_seen = true;
                 }
-                buf.indent(indent);
-                fmt_docs(buf, docs);
-                comment_seen = true;
             }
         }
     }
@@ -178,6 +189,32 @@ where
     count
 }
 
+/// The lines of the doc comment which starts with `first`, rewrapped if the buffer has a doc
+/// comment width. The doc comment's other lines get taken from `spaces`.
+fn doc_comment_lines<'a, I>(
+    buf: &Buf,
+    first: &'a str,
+    spaces: &mut Peekable<I>,
+    indent: u16,
+) -> Vec<String>
+where
+    I: Iterator<Item = &'a CommentOrNewline<'a>>,
+{
+    let width = match buf.doc_comment_width() {
+        Some(width) => width,
+        None => return vec![first.to_string()],
+    };
+
+    let mut lines = vec![first];
+
+    while let Some(CommentOrNewline::DocComment(line)) = spaces.peek() {
+        lines.push(line);
+        spaces.next();
+    }
+
+    reflow_docs(&lines, indent, width)
+}
+
 fn fmt_docs(buf: &mut Buf, docs: &str) {
     // The "##" in a doc comment should always be preceded by a newline or a space,
     // unless it's the very beginning of the buffer.
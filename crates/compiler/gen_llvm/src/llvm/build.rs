@@ -5,7 +5,7 @@ use crate::llvm::build_list::{
 use crate::llvm::convert::{
     argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
 };
-use crate::llvm::expect::{clone_to_shared_memory, SharedMemoryPointer};
+use crate::llvm::expect::{clone_to_shared_memory, print_failed_expect, SharedMemoryPointer};
 use crate::llvm::memcpy::build_memcpy;
 use crate::llvm::refcounting::{
     build_reset, decrement_refcount_layout, increment_refcount_layout, PointerToRefcount,
@@ -19,7 +19,7 @@ use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::{
-    AsDIScope, DICompileUnit, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
+    AsDIScope, DICompileUnit, DIFile, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
 };
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
@@ -53,10 +53,11 @@ use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
     RawFunctionLayout, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_region::all::{LineInfo, Region};
 use roc_std::RocDec;
 use roc_target::{PtrWidth, Target};
 use std::convert::TryInto;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::convert::{struct_type_from_union_layout, RocUnion};
 use super::intrinsics::{
@@ -660,19 +661,23 @@ fn print_fn_verification_output() -> bool {
 #[macro_export]
 macro_rules! debug_info_init {
     ($env:expr, $function_value:expr) => {{
+        $crate::debug_info_init!($env, $function_value, $env.compile_unit.get_file(), 0)
+    }};
+    ($env:expr, $function_value:expr, $file:expr, $line:expr) => {{
         use inkwell::debug_info::AsDIScope;
 
+        let line: u32 = $line;
         let func_scope = $function_value.get_subprogram().expect("subprogram");
         let lexical_block = $env.dibuilder.create_lexical_block(
             /* scope */ func_scope.as_debug_info_scope(),
-            /* file */ $env.compile_unit.get_file(),
-            /* line_no */ 0,
+            /* file */ $file,
+            /* line_no */ line,
             /* column_no */ 0,
         );
 
         let loc = $env.dibuilder.create_debug_location(
             $env.context,
-            /* line */ 0,
+            /* line */ line,
             /* column */ 0,
             /* current_scope */ lexical_block.as_debug_info_scope(),
             /* inlined_at */ None,
@@ -686,6 +691,9 @@ pub enum LlvmBackendMode {
     /// Assumes primitives (roc_alloc, roc_panic, etc) are provided by the host
     Binary,
     BinaryDev,
+    /// Like `Binary`, but a failed `expect` reports where it is and the values it looked up
+    /// through the host's `roc_dbg`, rather than being compiled away
+    BinaryPrintExpects,
     /// Creates a test wrapper around the main roc function to catch and report panics.
     /// Provides a testing implementation of primitives (roc_alloc, roc_panic, etc)
     BinaryGlue,
@@ -699,6 +707,7 @@ impl LlvmBackendMode {
         match self {
             LlvmBackendMode::Binary => true,
             LlvmBackendMode::BinaryDev => true,
+            LlvmBackendMode::BinaryPrintExpects => true,
             LlvmBackendMode::BinaryGlue => false,
             LlvmBackendMode::GenTest => false,
             LlvmBackendMode::WasmGenTest => true,
@@ -711,6 +720,7 @@ impl LlvmBackendMode {
         match self {
             LlvmBackendMode::Binary => false,
             LlvmBackendMode::BinaryDev => false,
+            LlvmBackendMode::BinaryPrintExpects => false,
             LlvmBackendMode::BinaryGlue => true,
             LlvmBackendMode::GenTest => true,
             LlvmBackendMode::WasmGenTest => true,
@@ -722,12 +732,51 @@ impl LlvmBackendMode {
         match self {
             LlvmBackendMode::Binary => false,
             LlvmBackendMode::BinaryDev => true,
+            LlvmBackendMode::BinaryPrintExpects => false,
             LlvmBackendMode::BinaryGlue => false,
             LlvmBackendMode::GenTest => false,
             LlvmBackendMode::WasmGenTest => false,
             LlvmBackendMode::CliTest => true,
         }
     }
+
+    /// Whether a failed `expect` prints itself, rather than being sent to `roc dev` or `roc test`
+    pub(crate) fn prints_expects(self) -> bool {
+        matches!(self, LlvmBackendMode::BinaryPrintExpects)
+    }
+}
+
+/// Where the definitions procedures are generated from are in the Roc source, so that debuggers
+/// can show the file and line a Roc function comes from.
+#[derive(Debug, Default)]
+pub struct SourceLocations {
+    /// The source file of each module.
+    pub files: MutMap<ModuleId, PathBuf>,
+    /// The line (counting from 1) each definition starts on.
+    pub lines: MutMap<Symbol, u32>,
+    /// The source code of each module, to show the code of a failed `expect`.
+    pub sources: MutMap<ModuleId, Box<str>>,
+}
+
+impl SourceLocations {
+    /// The line `symbol` is defined on, or 0 if that isn't known, e.g. for generated functions.
+    pub fn line(&self, symbol: Symbol) -> u32 {
+        self.lines.get(&symbol).copied().unwrap_or(0)
+    }
+
+    /// Where `region` of `module_id` is, as `path:line`, along with its source code.
+    pub fn region(&self, module_id: ModuleId, region: Region) -> Option<(String, &str)> {
+        let path = self.files.get(&module_id)?;
+        let source = self.sources.get(&module_id)?;
+
+        let code = source.get(region.start().offset as usize..region.end().offset as usize)?;
+        let line_col = LineInfo::new(source).convert_pos(region.start());
+
+        Some((
+            format!("{}:{}", path.to_string_lossy(), line_col.line + 1),
+            code,
+        ))
+    }
 }
 
 pub struct Env<'a, 'ctx, 'env> {
@@ -741,6 +790,7 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target: Target,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    pub source_locations: SourceLocations,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -948,6 +998,16 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn new_debug_info(module: &Module<'ctx>) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
+        Self::new_debug_info_for_file(module, Path::new("roc_app"))
+    }
+
+    /// Like `new_debug_info`, but the compile unit is named after the Roc file being built.
+    pub fn new_debug_info_for_file(
+        module: &Module<'ctx>,
+        roc_file_path: &Path,
+    ) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
+        let (filename, directory) = split_debug_path(roc_file_path);
+
         let debug_metadata_version = module.get_context().i32_type().const_int(3, false);
         module.add_basic_value_flag(
             "Debug Info Version",
@@ -957,8 +1017,8 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         module.create_debug_info_builder(
             true,
             /* language */ inkwell::debug_info::DWARFSourceLanguage::C,
-            /* filename */ "roc_app",
-            /* directory */ ".",
+            /* filename */ &filename,
+            /* directory */ &directory,
             /* producer */ "my llvm compiler frontend",
             /* is_optimized */ false,
             /* compiler command line flags */ "",
@@ -1007,36 +1067,142 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             /* is_optimized */ false,
         )
     }
+
+    /// The debug info file for the source of `module_id`, falling back to the compile unit's file
+    /// when its source isn't known.
+    pub fn debug_file(&self, module_id: ModuleId) -> DIFile<'ctx> {
+        match self.source_locations.files.get(&module_id) {
+            Some(path) => {
+                let (filename, directory) = split_debug_path(path);
+
+                self.dibuilder.create_file(&filename, &directory)
+            }
+            None => self.compile_unit.get_file(),
+        }
+    }
+
+    /// Like `new_subprogram`, but for the procedure of `symbol`: it's named `Module.name` like in
+    /// Roc source, keeps `function_name` as its linkage name, and points at the file and line
+    /// `symbol` is defined on.
+    pub fn new_proc_subprogram(&self, symbol: Symbol, function_name: &str) -> DISubprogram<'ctx> {
+        let dibuilder = self.dibuilder;
+        let file = self.debug_file(symbol.module_id());
+        let line = self.source_locations.line(symbol);
+
+        let module_name = self
+            .interns
+            .module_ids
+            .get_name(symbol.module_id())
+            .unwrap();
+        let name = format!("{}.{}", module_name, symbol.as_str(&self.interns));
+
+        let ditype = dibuilder
+            .create_basic_type(
+                "type_name",
+                0_u64,
+                0x00,
+                inkwell::debug_info::DIFlags::PUBLIC,
+            )
+            .unwrap();
+
+        let subroutine_type = dibuilder.create_subroutine_type(
+            file,
+            /* return type */ Some(ditype.as_type()),
+            /* parameter types */ &[],
+            inkwell::debug_info::DIFlags::PUBLIC,
+        );
+
+        dibuilder.create_function(
+            /* scope */ file.as_debug_info_scope(),
+            /* func name */ &name,
+            /* linkage_name */ Some(function_name),
+            /* file */ file,
+            /* line_no */ line,
+            /* DIType */ subroutine_type,
+            /* is_local_to_unit */ true,
+            /* is_definition */ true,
+            /* scope_line */ line,
+            /* flags */ inkwell::debug_info::DIFlags::PUBLIC,
+            /* is_optimized */ false,
+        )
+    }
+}
+
+/// Splits a path into the file name and directory that debug info refers to files by.
+fn split_debug_path(path: &Path) -> (String, String) {
+    let filename = match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.to_string_lossy().into_owned(),
+    };
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+        _ => ".".to_string(),
+    };
+
+    (filename, directory)
+}
+
+/// The bitcode of the builtins for this target. The `-atomic` variant of each file has the
+/// builtins that use atomic refcounting.
+macro_rules! builtins_bitcode {
+    ($target:expr, $variant:literal) => {
+        if $target == target_lexicon::Triple::host().into() {
+            include_bytes!(concat!(
+                "../../../builtins/bitcode/zig-out/builtins-host",
+                $variant,
+                ".bc"
+            ))
+        } else {
+            match $target {
+                Target::Wasm32 => include_bytes!(concat!(
+                    "../../../builtins/bitcode/zig-out/builtins-wasm32",
+                    $variant,
+                    ".bc"
+                )),
+                Target::LinuxX32 => include_bytes!(concat!(
+                    "../../../builtins/bitcode/zig-out/builtins-x86",
+                    $variant,
+                    ".bc"
+                )),
+                Target::LinuxX64 => include_bytes!(concat!(
+                    "../../../builtins/bitcode/zig-out/builtins-x86_64",
+                    $variant,
+                    ".bc"
+                )),
+                Target::LinuxArm64 => include_bytes!(concat!(
+                    "../../../builtins/bitcode/zig-out/builtins-aarch64",
+                    $variant,
+                    ".bc"
+                )),
+                Target::WinX64 => include_bytes!(concat!(
+                    "../../../builtins/bitcode/zig-out/builtins-windows-x86_64",
+                    $variant,
+                    ".bc"
+                )),
+                _ => panic!(
+                    "The zig builtins are not currently built for this target: {:?}",
+                    $target
+                ),
+            }
+        }
+    };
 }
 
+/// `atomic_refcount` selects the builtins that use atomic refcounting, for hosts that share Roc
+/// values across threads (see roc_std's `atomic-refcount` feature).
 pub fn module_from_builtins<'ctx>(
     target: Target,
     ctx: &'ctx Context,
     module_name: &str,
+    atomic_refcount: bool,
 ) -> Module<'ctx> {
     // In the build script for the builtins module, we compile the builtins into LLVM bitcode
 
-    let bitcode_bytes: &[u8] = if target == target_lexicon::Triple::host().into() {
-        include_bytes!("../../../builtins/bitcode/zig-out/builtins-host.bc")
+    let bitcode_bytes: &[u8] = if atomic_refcount {
+        builtins_bitcode!(target, "-atomic")
     } else {
-        match target {
-            Target::Wasm32 => {
-                include_bytes!("../../../builtins/bitcode/zig-out/builtins-wasm32.bc")
-            }
-            Target::LinuxX32 => {
-                include_bytes!("../../../builtins/bitcode/zig-out/builtins-x86.bc")
-            }
-            Target::LinuxX64 => {
-                include_bytes!("../../../builtins/bitcode/zig-out/builtins-x86_64.bc")
-            }
-            Target::LinuxArm64 => {
-                include_bytes!("../../../builtins/bitcode/zig-out/builtins-aarch64.bc")
-            }
-            Target::WinX64 => {
-                include_bytes!("../../../builtins/bitcode/zig-out/builtins-windows-x86_64.bc")
-            }
-            _ => panic!("The zig builtins are not currently built for this target: {target:?}"),
-        }
+        builtins_bitcode!(target, "")
     };
 
     let memory_buffer = MemoryBuffer::create_from_memory_range(bitcode_bytes, module_name);
@@ -1101,10 +1267,97 @@ pub fn module_from_builtins<'ctx>(
     module
 }
 
+/// How the LLVM pass pipeline is tuned for an optimization level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassTuning {
+    pub optimization_level: OptimizationLevel,
+    /// 0 optimizes for speed, 1 is like `-Os`, and 2 is like `-Oz`.
+    pub size_level: u32,
+    /// Functions whose inlining cost is below this get inlined. With `None`, only functions
+    /// marked `alwaysinline` are.
+    pub inline_threshold: Option<u32>,
+    pub unroll_loops: bool,
+    /// Run loop rotation, LICM, induction variable simplification, and loop deletion on each
+    /// function before the rest of the pipeline, so later passes see simpler loops.
+    pub loop_opts: bool,
+    /// Turn loops into vector instructions.
+    pub vectorize_loops: bool,
+    /// Combine independent scalar instructions into vector instructions.
+    pub vectorize_slp: bool,
+    /// Merge functions that compile to the same code, like specializations whose layouts match,
+    /// along with identical constants.
+    pub merge_functions: bool,
+}
+
+impl PassTuning {
+    pub fn for_opt_level(opt_level: OptLevel) -> Self {
+        match opt_level {
+            OptLevel::Development | OptLevel::Normal => PassTuning {
+                optimization_level: OptimizationLevel::None,
+                size_level: 0,
+                inline_threshold: None,
+                unroll_loops: false,
+                loop_opts: false,
+                vectorize_loops: false,
+                vectorize_slp: false,
+                merge_functions: false,
+            },
+            OptLevel::Less => PassTuning {
+                optimization_level: OptimizationLevel::Less,
+                size_level: 0,
+                // Only inline functions that are (nearly) free to inline.
+                inline_threshold: Some(0),
+                unroll_loops: false,
+                loop_opts: false,
+                vectorize_loops: false,
+                vectorize_slp: false,
+                merge_functions: false,
+            },
+            OptLevel::Moderate => PassTuning {
+                optimization_level: OptimizationLevel::Default,
+                size_level: 0,
+                // This is clang's threshold for `-O2`.
+                inline_threshold: Some(225),
+                unroll_loops: true,
+                loop_opts: true,
+                vectorize_loops: true,
+                vectorize_slp: false,
+                merge_functions: false,
+            },
+            OptLevel::Size => PassTuning {
+                optimization_level: OptimizationLevel::Default,
+                // 2 is equivalent to `-Oz`.
+                size_level: 2,
+                // TODO: For some usecase, like embedded, it is useful to expose this and tune it.
+                // This really depends on if inlining causes enough simplifications to reduce code size.
+                inline_threshold: Some(50),
+                unroll_loops: false,
+                loop_opts: false,
+                vectorize_loops: false,
+                vectorize_slp: false,
+                merge_functions: true,
+            },
+            OptLevel::Optimize => PassTuning {
+                optimization_level: OptimizationLevel::Aggressive,
+                size_level: 0,
+                // this threshold seems to do what we want
+                inline_threshold: Some(750),
+                unroll_loops: true,
+                loop_opts: true,
+                vectorize_loops: true,
+                vectorize_slp: true,
+                merge_functions: false,
+            },
+        }
+    }
+}
+
 pub fn construct_optimization_passes<'a>(
     module: &'a Module,
     opt_level: OptLevel,
 ) -> (PassManager<Module<'a>>, PassManager<FunctionValue<'a>>) {
+    let tuning = PassTuning::for_opt_level(opt_level);
+
     let mpm = PassManager::create(());
     let fpm = PassManager::create(module);
 
@@ -1118,24 +1371,20 @@ pub fn construct_optimization_passes<'a>(
     fpm.add_tail_call_elimination_pass();
 
     let pmb = PassManagerBuilder::create();
-    match opt_level {
-        OptLevel::Development | OptLevel::Normal => {
-            pmb.set_optimization_level(OptimizationLevel::None);
-        }
-        OptLevel::Size => {
-            pmb.set_optimization_level(OptimizationLevel::Default);
-            // 2 is equivalent to `-Oz`.
-            pmb.set_size_level(2);
-
-            // TODO: For some usecase, like embedded, it is useful to expose this and tune it.
-            // This really depends on if inlining causes enough simplifications to reduce code size.
-            pmb.set_inliner_with_threshold(50);
-        }
-        OptLevel::Optimize => {
-            pmb.set_optimization_level(OptimizationLevel::Aggressive);
-            // this threshold seems to do what we want
-            pmb.set_inliner_with_threshold(750);
-        }
+    pmb.set_optimization_level(tuning.optimization_level);
+    pmb.set_size_level(tuning.size_level);
+    pmb.set_disable_unroll_loops(!tuning.unroll_loops);
+
+    if let Some(threshold) = tuning.inline_threshold {
+        pmb.set_inliner_with_threshold(threshold);
+    }
+
+    if tuning.loop_opts {
+        // rotating puts loops in the shape the other loop passes expect
+        fpm.add_loop_rotate_pass();
+        fpm.add_licm_pass();
+        fpm.add_ind_var_simplify_pass();
+        fpm.add_loop_deletion_pass();
     }
 
     // Add extra optimization passes for Optimize.
@@ -1153,8 +1402,6 @@ pub fn construct_optimization_passes<'a>(
 
         fpm.add_memcpy_optimize_pass(); // this one is very important
 
-        fpm.add_licm_pass();
-
         // turn invoke into call
         // TODO: is this pass needed. It theoretically prunes unused exception handling info.
         // This seems unrelated to the comment above. It also seems to be missing in llvm-16.
@@ -1169,6 +1416,30 @@ pub fn construct_optimization_passes<'a>(
     pmb.populate_module_pass_manager(&mpm);
     pmb.populate_function_pass_manager(&fpm);
 
+    // The builder leaves vectorization off, so it's added after the rest of the pipeline, once
+    // inlining has exposed the loops worth vectorizing.
+    if tuning.vectorize_loops || tuning.vectorize_slp {
+        if tuning.vectorize_loops {
+            mpm.add_loop_vectorize_pass();
+        }
+
+        if tuning.vectorize_slp {
+            mpm.add_slp_vectorize_pass();
+        }
+
+        // clean up after the vectorizers
+        mpm.add_instruction_combining_pass();
+        mpm.add_cfg_simplification_pass();
+    }
+
+    if tuning.merge_functions {
+        mpm.add_merge_functions_pass();
+        mpm.add_constant_merge_pass();
+
+        // the functions and constants that were merged away are now unused
+        mpm.add_global_dce_pass();
+    }
+
     fpm.initialize();
 
     // For now, we have just one of each
@@ -1383,7 +1654,10 @@ pub fn build_exp_literal<'a, 'ctx>(
     }
 }
 
-fn build_string_literal<'ctx>(env: &Env<'_, 'ctx, '_>, str_literal: &str) -> BasicValueEnum<'ctx> {
+pub(crate) fn build_string_literal<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    str_literal: &str,
+) -> BasicValueEnum<'ctx> {
     if str_literal.len() < env.small_str_bytes() as usize {
         match env.small_str_bytes() {
             24 => small_str_ptr_width_8(env, str_literal).into(),
@@ -3632,6 +3906,20 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                         throw_internal_exception(env, "An expectation failed!");
                     }
                 }
+            } else if env.mode.prints_expects() {
+                bd.position_at_end(throw_block);
+
+                print_failed_expect(
+                    env,
+                    layout_interner,
+                    layout_ids,
+                    scope,
+                    parent,
+                    *region,
+                    lookups,
+                );
+
+                bd.new_build_unconditional_branch(then_block);
             } else {
                 bd.position_at_end(throw_block);
                 bd.new_build_unconditional_branch(then_block);
@@ -3700,6 +3988,20 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                         throw_internal_exception(env, "An expectation failed!");
                     }
                 }
+            } else if env.mode.prints_expects() {
+                bd.position_at_end(throw_block);
+
+                print_failed_expect(
+                    env,
+                    layout_interner,
+                    layout_ids,
+                    scope,
+                    parent,
+                    *region,
+                    lookups,
+                );
+
+                bd.new_build_unconditional_branch(then_block);
             } else {
                 bd.position_at_end(throw_block);
                 bd.new_build_unconditional_branch(then_block);
@@ -4972,7 +5274,10 @@ fn expose_function_to_host_help_c_abi<'a, 'ctx>(
             )
         }
 
-        LlvmBackendMode::Binary | LlvmBackendMode::BinaryDev | LlvmBackendMode::BinaryGlue => {}
+        LlvmBackendMode::Binary
+        | LlvmBackendMode::BinaryDev
+        | LlvmBackendMode::BinaryPrintExpects
+        | LlvmBackendMode::BinaryGlue => {}
     }
 
     // a generic version that writes the result into a passed *u8 pointer
@@ -5025,13 +5330,14 @@ fn expose_function_to_host_help_c_abi<'a, 'ctx>(
             roc_call_result_type(env, roc_function.get_type().get_return_type().unwrap()).into()
         }
 
-        LlvmBackendMode::Binary | LlvmBackendMode::BinaryDev | LlvmBackendMode::BinaryGlue => {
-            basic_type_from_layout(
-                env,
-                layout_interner,
-                layout_interner.get_repr(return_layout),
-            )
-        }
+        LlvmBackendMode::Binary
+        | LlvmBackendMode::BinaryDev
+        | LlvmBackendMode::BinaryPrintExpects
+        | LlvmBackendMode::BinaryGlue => basic_type_from_layout(
+            env,
+            layout_interner,
+            layout_interner.get_repr(return_layout),
+        ),
     };
 
     let size: BasicValueEnum = return_type.size_of().unwrap().into();
@@ -5828,7 +6134,7 @@ fn build_procedures_help<'a>(
     use LlvmBackendMode::*;
     match env.mode {
         GenTest | WasmGenTest | CliTest => { /* no host, or exposing types is not supported */ }
-        Binary | BinaryDev | BinaryGlue => {
+        Binary | BinaryDev | BinaryPrintExpects | BinaryGlue => {
             for (proc_name, alias_name, hels) in host_exposed_lambda_sets.iter() {
                 let ident_string = proc_name.name().as_str(&env.interns);
                 let fn_name: String = format!("{}_{}", ident_string, hels.id.0);
@@ -5882,6 +6188,95 @@ fn func_spec_name<'a>(
     buf
 }
 
+/// Makes the module easier to profile: the procs get their plain `Module.name` as their function
+/// name instead of the name [func_spec_name] gives them, they aren't inlined, and every function
+/// keeps its frame pointer. Functions can't be looked up by name afterwards, so this has to run
+/// after all the procedures have been built.
+pub fn prepare_for_profiling(
+    env: &Env<'_, '_, '_>,
+    proc_symbols: impl IntoIterator<Item = Symbol>,
+) {
+    let mut readable_names = MutMap::default();
+
+    for symbol in proc_symbols {
+        let module_string = env.interns.module_ids.get_name(symbol.module_id()).unwrap();
+        let ident_string = symbol.as_str(&env.interns);
+
+        readable_names.insert(
+            format!("{module_string}_{ident_string}"),
+            format!("{module_string}.{ident_string}"),
+        );
+    }
+
+    let noinline = env
+        .context
+        .create_enum_attribute(Attribute::get_named_enum_kind_id("noinline"), 0);
+    let frame_pointer = env.context.create_string_attribute("frame-pointer", "all");
+
+    for fn_val in env.module.get_functions() {
+        fn_val.add_attribute(AttributeLoc::Function, frame_pointer);
+
+        if fn_val.get_linkage() != Linkage::Internal {
+            continue;
+        }
+
+        // The specialization at the end never has an underscore in it.
+        let name = fn_val.get_name().to_str().unwrap_or_default();
+        let Some((base, spec)) = name.rsplit_once('_') else {
+            continue;
+        };
+        let is_spec = spec == "erased" || spec.chars().all(|c| c.is_ascii_hexdigit());
+
+        if let Some(readable_name) = readable_names.get(base).filter(|_| is_spec) {
+            // LLVM adds a numeric suffix when there's more than one specialization.
+            fn_val
+                .as_global_value()
+                .as_pointer_value()
+                .set_name(readable_name);
+            fn_val.add_attribute(AttributeLoc::Function, noinline);
+        }
+    }
+}
+
+/// Marks every function defined in the module as `minsize` and `optsize`, so code generation
+/// picks smaller instructions over faster ones, and targets that outline repeated code from such
+/// functions by default (like AArch64) do.
+pub fn prepare_for_size(env: &Env<'_, '_, '_>) {
+    let minsize = env
+        .context
+        .create_enum_attribute(Attribute::get_named_enum_kind_id("minsize"), 0);
+    let optsize = env
+        .context
+        .create_enum_attribute(Attribute::get_named_enum_kind_id("optsize"), 0);
+    let optnone_kind_id = Attribute::get_named_enum_kind_id("optnone");
+
+    for fn_val in env.module.get_functions() {
+        // LLVM rejects `minsize` on functions that are never optimized
+        let is_optnone = fn_val
+            .get_enum_attribute(AttributeLoc::Function, optnone_kind_id)
+            .is_some();
+
+        if fn_val.count_basic_blocks() > 0 && !is_optnone {
+            fn_val.add_attribute(AttributeLoc::Function, minsize);
+            fn_val.add_attribute(AttributeLoc::Function, optsize);
+        }
+    }
+}
+
+/// Marks every function defined in the module as `sanitize_address`, because AddressSanitizer
+/// only instruments the functions which ask for it.
+pub fn prepare_for_address_sanitizer(env: &Env<'_, '_, '_>) {
+    let sanitize_address = env
+        .context
+        .create_enum_attribute(Attribute::get_named_enum_kind_id("sanitize_address"), 0);
+
+    for fn_val in env.module.get_functions() {
+        if fn_val.count_basic_blocks() > 0 {
+            fn_val.add_attribute(AttributeLoc::Function, sanitize_address);
+        }
+    }
+}
+
 fn build_proc_header<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
     layout_interner: &STLayoutInterner<'a>,
@@ -5921,10 +6316,15 @@ fn build_proc_header<'a, 'ctx>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = env.new_proc_subprogram(symbol, &fn_name);
     fn_val.set_subprogram(subprogram);
 
-    debug_info_init!(env, fn_val);
+    debug_info_init!(
+        env,
+        fn_val,
+        env.debug_file(symbol.module_id()),
+        env.source_locations.line(symbol)
+    );
 
     if env.exposed_to_host.contains(&symbol) {
         let arguments = Vec::from_iter_in(proc.args.iter().map(|(layout, _)| *layout), env.arena);
@@ -6228,7 +6628,15 @@ fn build_proc<'a, 'ctx>(
 
     builder.position_at_end(entry);
 
-    debug_info_init!(env, fn_val);
+    let symbol = proc.name.name();
+    scope.set_home(symbol.module_id());
+
+    debug_info_init!(
+        env,
+        fn_val,
+        env.debug_file(symbol.module_id()),
+        env.source_locations.line(symbol)
+    );
 
     // Add args to scope
     for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
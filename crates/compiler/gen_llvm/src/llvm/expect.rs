@@ -1,7 +1,8 @@
 use crate::debug_info_init;
-use crate::llvm::bitcode::call_str_bitcode_fn;
+use crate::llvm::bitcode::{call_str_bitcode_fn, BitcodeReturns};
 use crate::llvm::build::{
-    create_entry_block_alloca, get_tag_id, store_roc_value, tag_pointer_clear_tag_id, Env,
+    build_string_literal, create_entry_block_alloca, get_tag_id, store_roc_value,
+    tag_pointer_clear_tag_id, Env,
 };
 use crate::llvm::build_list::{self, incrementing_elem_loop};
 use crate::llvm::convert::{basic_type_from_layout, RocUnion};
@@ -9,19 +10,21 @@ use inkwell::builder::Builder;
 use inkwell::module::Linkage;
 use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
-use inkwell::AddressSpace;
-use roc_builtins::bitcode;
+use inkwell::{AddressSpace, IntPredicate};
+use roc_builtins::bitcode::{self, IntWidth};
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::symbol::Symbol;
 use roc_mono::ir::LookupType;
 use roc_mono::layout::{
-    Builtin, InLayout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
+    Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
 };
 use roc_region::all::Region;
 
 use super::build::BuilderExt;
 use super::build::{add_func, FunctionSpec, LlvmBackendMode};
 use super::convert::struct_type_from_union_layout;
+use super::lowlevel::dec_to_str;
+use super::refcounting::decrement_refcount_layout;
 use super::scope::Scope;
 use super::struct_::RocStruct;
 
@@ -1100,3 +1103,224 @@ fn build_clone_builtin<'a, 'ctx>(
         }
     }
 }
+
+/// Reports a failed `expect` through the host's `roc_dbg`: first the condition that was false,
+/// then the value of each variable the condition looked up.
+pub(crate) fn print_failed_expect<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    scope: &Scope<'a, 'ctx>,
+    parent: FunctionValue<'ctx>,
+    region: Region,
+    lookups: &[Symbol],
+) {
+    let (location, condition) = match scope
+        .home()
+        .and_then(|home| env.source_locations.region(home, region))
+    {
+        Some((location, condition)) => (location, format!("expect {condition}")),
+        None => (format!("{region:?}"), String::from("expect")),
+    };
+
+    let location = build_string_literal(env, &location);
+    let source = build_string_literal(env, &condition);
+    let message = build_string_literal(env, "Bool.false");
+
+    env.call_dbg(env, location, source, message);
+
+    for lookup in lookups {
+        let (value, layout) = scope.load_symbol_and_layout(lookup);
+
+        let source = build_string_literal(env, lookup.as_str(&env.interns));
+        let message = build_to_str(env, layout_interner, layout_ids, parent, value, layout);
+
+        env.call_dbg(env, location, source, message);
+
+        decrement_refcount_layout(env, layout_interner, layout_ids, message, Layout::STR);
+    }
+}
+
+/// Builds a `Str` showing `value`, based on its layout. The returned string is owned by the
+/// caller; `value` is only borrowed.
+fn build_to_str<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    parent: FunctionValue<'ctx>,
+    value: BasicValueEnum<'ctx>,
+    layout: InLayout<'a>,
+) -> BasicValueEnum<'ctx> {
+    match layout_interner.get_repr(layout) {
+        LayoutRepr::Builtin(builtin) => {
+            build_builtin_to_str(env, layout_interner, layout_ids, parent, value, builtin)
+        }
+        struct_layout @ LayoutRepr::Struct(field_layouts) => {
+            let mut string = build_string_literal(env, "(");
+
+            for (index, field_layout) in field_layouts.iter().enumerate() {
+                if index > 0 {
+                    string = str_concat(env, string, build_string_literal(env, ", "));
+                }
+
+                let field = RocStruct::from(value).load_at_index(
+                    env,
+                    layout_interner,
+                    struct_layout,
+                    index as _,
+                );
+                let piece = build_to_str(
+                    env,
+                    layout_interner,
+                    layout_ids,
+                    parent,
+                    field,
+                    *field_layout,
+                );
+
+                string = str_concat(env, string, piece);
+                decrement_refcount_layout(env, layout_interner, layout_ids, piece, Layout::STR);
+            }
+
+            str_concat(env, string, build_string_literal(env, ")"))
+        }
+        LayoutRepr::Union(union_layout) => {
+            // tag names are gone by now, so the best we can show is which tag this is
+            let tag_id = get_tag_id(env, layout_interner, parent, &union_layout, value);
+            let tag_id =
+                env.builder
+                    .new_build_int_z_extend(tag_id, env.context.i64_type(), "tag_id_u64");
+
+            let piece = call_str_bitcode_fn(
+                env,
+                &[],
+                &[tag_id.into()],
+                BitcodeReturns::Str,
+                &bitcode::STR_FROM_INT[IntWidth::U64],
+            );
+
+            let string = str_concat(env, build_string_literal(env, "<tag #"), piece);
+            decrement_refcount_layout(env, layout_interner, layout_ids, piece, Layout::STR);
+
+            str_concat(env, string, build_string_literal(env, ">"))
+        }
+        LayoutRepr::LambdaSet(_) | LayoutRepr::FunctionPointer(_) | LayoutRepr::Erased(_) => {
+            build_string_literal(env, "<function>")
+        }
+        LayoutRepr::Ptr(_) | LayoutRepr::RecursivePointer(_) => {
+            build_string_literal(env, "<pointer>")
+        }
+    }
+}
+
+fn build_builtin_to_str<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    parent: FunctionValue<'ctx>,
+    value: BasicValueEnum<'ctx>,
+    builtin: Builtin<'a>,
+) -> BasicValueEnum<'ctx> {
+    let bd = env.builder;
+
+    match builtin {
+        Builtin::Int(int_width) => call_str_bitcode_fn(
+            env,
+            &[],
+            &[value],
+            BitcodeReturns::Str,
+            &bitcode::STR_FROM_INT[int_width],
+        ),
+        Builtin::Float(float_width) => call_str_bitcode_fn(
+            env,
+            &[],
+            &[value],
+            BitcodeReturns::Str,
+            &bitcode::STR_FROM_FLOAT[float_width],
+        ),
+        Builtin::Decimal => dec_to_str(env, value),
+        Builtin::Bool => bd.new_build_select(
+            value.into_int_value(),
+            build_string_literal(env, "Bool.true"),
+            build_string_literal(env, "Bool.false"),
+            "bool_to_str",
+        ),
+        Builtin::Str => {
+            let string = str_concat(env, build_string_literal(env, "\""), value);
+
+            str_concat(env, string, build_string_literal(env, "\""))
+        }
+        Builtin::List(elem) => {
+            let list = value.into_struct_value();
+            let (elements, len, _cap) = build_list::destructure(env.builder, list);
+
+            let element_type =
+                basic_type_from_layout(env, layout_interner, layout_interner.get_repr(elem));
+            let elements = bd.new_build_pointer_cast(
+                elements,
+                element_type.ptr_type(AddressSpace::default()),
+                "elements",
+            );
+
+            let start = build_string_literal(env, "[");
+            let str_type = start.get_type();
+
+            let string_ptr = create_entry_block_alloca(env, str_type, "list_to_str");
+            bd.new_build_store(string_ptr, start);
+
+            let body = |layout_interner: &STLayoutInterner<'a>, index: IntValue<'ctx>, element| {
+                let is_first = bd.new_build_int_compare(
+                    IntPredicate::EQ,
+                    index,
+                    index.get_type().const_zero(),
+                    "is_first",
+                );
+                let separator = bd.new_build_select(
+                    is_first,
+                    build_string_literal(env, ""),
+                    build_string_literal(env, ", "),
+                    "separator",
+                );
+
+                let string = bd.new_build_load(str_type, string_ptr, "string");
+                let string = str_concat(env, string, separator);
+
+                let piece = build_to_str(env, layout_interner, layout_ids, parent, element, elem);
+                let string = str_concat(env, string, piece);
+                decrement_refcount_layout(env, layout_interner, layout_ids, piece, Layout::STR);
+
+                bd.new_build_store(string_ptr, string);
+            };
+
+            incrementing_elem_loop(
+                env,
+                layout_interner,
+                parent,
+                elem,
+                elements,
+                len,
+                "index",
+                body,
+            );
+
+            let string = bd.new_build_load(str_type, string_ptr, "string");
+
+            str_concat(env, string, build_string_literal(env, "]"))
+        }
+    }
+}
+
+/// Appends `second` to `first`, which this takes ownership of. `second` is only borrowed.
+fn str_concat<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    first: BasicValueEnum<'ctx>,
+    second: BasicValueEnum<'ctx>,
+) -> BasicValueEnum<'ctx> {
+    call_str_bitcode_fn(
+        env,
+        &[first, second],
+        &[],
+        BitcodeReturns::Str,
+        bitcode::STR_CONCAT,
+    )
+}
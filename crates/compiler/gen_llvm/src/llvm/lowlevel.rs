@@ -2011,7 +2011,10 @@ fn dec_alloca<'ctx>(env: &Env<'_, 'ctx, '_>, value: IntValue<'ctx>) -> BasicValu
     }
 }
 
-fn dec_to_str<'ctx>(env: &Env<'_, 'ctx, '_>, dec: BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx> {
+pub(crate) fn dec_to_str<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    dec: BasicValueEnum<'ctx>,
+) -> BasicValueEnum<'ctx> {
     let dec = dec.into_int_value();
 
     match env.target {
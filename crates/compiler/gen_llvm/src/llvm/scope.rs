@@ -14,6 +14,8 @@ pub(crate) struct Scope<'a, 'ctx> {
     symbols: ImMap<Symbol, (InLayout<'a>, BasicValueEnum<'ctx>)>,
     top_level_thunks: ImMap<Symbol, (ProcLayout<'a>, FunctionValue<'ctx>)>,
     join_points: ImMap<JoinPointId, (BasicBlock<'ctx>, Vec<JoinPointArg<'ctx>>)>,
+    /// The module the procedure being built was defined in
+    home: Option<ModuleId>,
 }
 
 #[derive(Debug)]
@@ -45,6 +47,14 @@ impl<'a, 'ctx> Scope<'a, 'ctx> {
         }
     }
 
+    pub fn set_home(&mut self, module_id: ModuleId) {
+        self.home = Some(module_id);
+    }
+
+    pub fn home(&self) -> Option<ModuleId> {
+        self.home
+    }
+
     pub fn insert_top_level_thunk(
         &mut self,
         symbol: Symbol,
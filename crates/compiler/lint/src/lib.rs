@@ -0,0 +1,164 @@
+//! Finds code which works, but could be clearer, for `roc lint`: names which aren't camelCase,
+//! and `if`s and `when`s which could be simplified.
+//!
+//! These run over the canonical IR, and come out as (warning) canonicalization problems so they
+//! get reported like any others. Unused definitions and imports are already reported during
+//! canonicalization, and shadowing is an error, so those aren't repeated here.
+#![warn(clippy::dbg_macro)]
+
+use roc_can::expr::{Declarations, Expr};
+use roc_can::pattern::Pattern;
+use roc_can::traverse::{walk_expr, walk_pattern, Visitor};
+use roc_module::symbol::{Interns, Symbol};
+use roc_problem::can::Problem;
+use roc_region::all::Region;
+use roc_types::subs::Variable;
+
+pub fn lint_decls(decls: &Declarations, interns: &Interns) -> Vec<Problem> {
+    let mut linter = Linter {
+        interns,
+        problems: Vec::new(),
+    };
+
+    linter.visit_decls(decls);
+
+    linter.problems
+}
+
+struct Linter<'a> {
+    interns: &'a Interns,
+    problems: Vec<Problem>,
+}
+
+impl Linter<'_> {
+    fn check_name(&mut self, symbol: Symbol, region: Region) {
+        let name = self
+            .interns
+            .all_ident_ids
+            .get(&symbol.module_id())
+            .and_then(|ident_ids| ident_ids.get_name(symbol.ident_id()));
+
+        if let Some(suggestion) = name.and_then(camel_case_suggestion) {
+            self.problems.push(Problem::NonCamelCaseName {
+                symbol,
+                region,
+                suggestion,
+            });
+        }
+    }
+}
+
+impl Visitor for Linter<'_> {
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+        if let Pattern::Identifier(symbol) | Pattern::As(_, symbol) = pattern {
+            self.check_name(*symbol, region);
+        }
+
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                if let [(_, then)] = branches.as_slice() {
+                    let negated = match (bool_value(&then.value), bool_value(&final_else.value)) {
+                        (Some(true), Some(false)) => Some(false),
+                        (Some(false), Some(true)) => Some(true),
+                        _ => None,
+                    };
+
+                    if let Some(negated) = negated {
+                        self.problems.push(Problem::RedundantIf { region, negated });
+                    }
+                }
+            }
+            Expr::When { branches, .. } => {
+                if let [branch] = branches.as_slice() {
+                    let matches_everything = match branch.patterns.as_slice() {
+                        [pattern] => matches!(
+                            pattern.pattern.value,
+                            Pattern::Underscore | Pattern::Identifier(_)
+                        ),
+                        _ => false,
+                    };
+
+                    if matches_everything && branch.guard.is_none() {
+                        self.problems.push(Problem::SingleBranchWhen { region });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+/// `Bool.true` or `Bool.false`
+fn bool_value(expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Var(Symbol::BOOL_TRUE, _) => Some(true),
+        Expr::Var(Symbol::BOOL_FALSE, _) => Some(false),
+        _ => None,
+    }
+}
+
+/// For a name like `my_value`, returns the camelCase version (`myValue`), or None if the name is
+/// fine as it is. A trailing `!` (for effectful functions) is kept.
+fn camel_case_suggestion(name: &str) -> Option<String> {
+    // Names the compiler makes up while desugaring don't start with a lowercase letter.
+    if !name.starts_with(|c: char| c.is_ascii_lowercase()) || !name.contains('_') {
+        return None;
+    }
+
+    let (base, suffix) = match name.strip_suffix('!') {
+        Some(base) => (base, "!"),
+        None => (name, ""),
+    };
+
+    let mut suggestion = String::with_capacity(name.len());
+    let mut capitalize_next = false;
+
+    for ch in base.chars() {
+        if ch == '_' {
+            capitalize_next = true;
+        } else if capitalize_next {
+            suggestion.extend(ch.to_uppercase());
+            capitalize_next = false;
+        } else {
+            suggestion.push(ch);
+        }
+    }
+
+    suggestion.push_str(suffix);
+
+    Some(suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::camel_case_suggestion;
+
+    #[test]
+    fn suggests_camel_case() {
+        assert_eq!(camel_case_suggestion("myValue"), None);
+        assert_eq!(camel_case_suggestion("x"), None);
+        assert_eq!(camel_case_suggestion("#arg_1"), None);
+        assert_eq!(
+            camel_case_suggestion("my_value"),
+            Some("myValue".to_string())
+        );
+        assert_eq!(
+            camel_case_suggestion("read_file!"),
+            Some("readFile!".to_string())
+        );
+        assert_eq!(
+            camel_case_suggestion("trailing__"),
+            Some("trailing".to_string())
+        );
+    }
+}
@@ -29,12 +29,17 @@ const MODULES: &[(ModuleId, &str)] = &[
 ];
 
 fn main() {
+    // Every module gets loaded into the same arena, which is reset in between;
+    // that way its chunks are allocated once rather than once per module.
+    let mut arena = Bump::new();
+
     for (module_id, filename) in MODULES {
-        write_subs_for_module(*module_id, filename);
+        write_subs_for_module(&arena, *module_id, filename);
+        arena.reset();
     }
 }
 
-fn write_subs_for_module(module_id: ModuleId, filename: &str) {
+fn write_subs_for_module(arena: &Bump, module_id: ModuleId, filename: &str) {
     // Tell Cargo that if the given file changes, to rerun this build script.
     let filepath = PathBuf::from("..")
         .join("builtins")
@@ -49,7 +54,7 @@ fn write_subs_for_module(module_id: ModuleId, filename: &str) {
     if SKIP_SUBS_CACHE {
         write_types_for_module_dummy(&output_path)
     } else {
-        write_types_for_module_real(module_id, filename, &output_path)
+        write_types_for_module_real(arena, module_id, filename, &output_path)
     }
 }
 
@@ -58,13 +63,17 @@ fn write_types_for_module_dummy(output_path: &Path) {
     std::fs::write(output_path, []).unwrap();
 }
 
-fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path: &Path) {
+fn write_types_for_module_real(
+    arena: &Bump,
+    module_id: ModuleId,
+    filename: &str,
+    output_path: &Path,
+) {
     use roc_can::module::TypeState;
     use roc_load_internal::file::{LoadingProblem, Threading};
     use roc_packaging::cache::RocCacheDir;
     use roc_reporting::cli::report_problems;
 
-    let arena = Bump::new();
     let cwd = std::env::current_dir().unwrap();
     let source = roc_builtins::roc::module_source(module_id);
     // This is just for typechecking. Target shouldn't matter.
@@ -72,7 +81,7 @@ fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path:
     let function_kind = roc_solve::FunctionKind::LambdaSet;
 
     let res_module = roc_load_internal::file::load_and_typecheck_str(
-        &arena,
+        arena,
         PathBuf::from(filename),
         source,
         cwd,
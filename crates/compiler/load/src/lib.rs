@@ -24,7 +24,11 @@ pub use roc_load_internal::file::{
 pub use roc_load_internal::module::{
     CheckedModule, EntryPoint, Expectations, ExposedToHost, LoadedModule, MonomorphizedModule,
 };
-pub use roc_solve::FunctionKind;
+pub use roc_load_internal::progress;
+pub use roc_load_internal::references;
+pub use roc_load_internal::rename;
+pub use roc_parse::state::Tabs;
+pub use roc_solve::{FunctionKind, NumericDefaults};
 
 #[allow(clippy::too_many_arguments)]
 fn load<'a>(
@@ -67,11 +71,13 @@ pub fn load_single_threaded<'a>(
         exposed_types,
         target,
         function_kind,
+        NumericDefaults::Silent,
         cached_subs,
         render,
         palette,
         exec_mode,
         roc_cache_dir,
+        None,
     )
 }
 
@@ -110,8 +116,15 @@ pub fn load_and_monomorphize_from_str<'a>(
 ) -> Result<MonomorphizedModule<'a>, LoadMonomorphizedError<'a>> {
     use LoadResult::*;
 
-    let load_start =
-        LoadStart::from_str(arena, filename, opt_main_path, src, roc_cache_dir, src_dir)?;
+    let load_start = LoadStart::from_str(
+        arena,
+        filename,
+        opt_main_path,
+        src,
+        roc_cache_dir,
+        src_dir,
+        load_config.tabs,
+    )?;
     let exposed_types = ExposedByModule::default();
 
     match load(arena, load_start, exposed_types, roc_cache_dir, load_config)? {
@@ -136,6 +149,7 @@ pub fn load_and_monomorphize<'a>(
         load_config.render,
         roc_cache_dir,
         load_config.palette,
+        load_config.tabs,
     )?;
 
     let exposed_types = ExposedByModule::default();
@@ -162,6 +176,7 @@ pub fn load_and_typecheck<'a>(
         load_config.render,
         roc_cache_dir,
         load_config.palette,
+        load_config.tabs,
     )?;
 
     let exposed_types = ExposedByModule::default();
@@ -194,6 +209,7 @@ pub fn load_and_typecheck_str<'a>(
         source,
         roc_cache_dir,
         src_dir,
+        Tabs::Reject,
     )?;
 
     // NOTE: this function is meant for tests, and so we use single-threaded
@@ -13,7 +13,10 @@ mod test_reporting {
     use indoc::indoc;
     use roc_can::abilities::AbilitiesStore;
     use roc_can::expr::PendingDerives;
-    use roc_load::{self, ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+    use roc_load::{
+        self, ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, NumericDefaults, Tabs,
+        Threading,
+    };
     use roc_module::symbol::{Interns, ModuleId};
     use roc_packaging::cache::RocCacheDir;
     use roc_parse::header::parse_header;
@@ -21,6 +24,7 @@ mod test_reporting {
     use roc_parse::test_helpers::parse_expr_with;
     use roc_problem::Severity;
     use roc_region::all::LineInfo;
+    use roc_reporting::error::r#type::TypePrintOptions;
     use roc_reporting::report::{
         can_problem, parse_problem, type_problem, RenderTarget, Report, ANSI_STYLE_CODES,
         DEFAULT_PALETTE,
@@ -101,15 +105,16 @@ mod test_reporting {
         use std::fs::File;
         use std::io::Write;
 
-        let module_src = if src.starts_with("app") {
-            maybe_save_parse_test_case(subdir, src, false);
-            // this is already a module
-            src.to_string()
-        } else {
-            maybe_save_parse_test_case(subdir, src, true);
-            // this is an expression, promote it to a module
-            promote_expr_to_module(src)
-        };
+        let module_src =
+            if src.starts_with("app") || src.starts_with("package") || src.starts_with("module") {
+                maybe_save_parse_test_case(subdir, src, false);
+                // this is already a module
+                src.to_string()
+            } else {
+                maybe_save_parse_test_case(subdir, src, true);
+                // this is an expression, promote it to a module
+                promote_expr_to_module(src)
+            };
 
         let loaded = {
             // Use a deterministic temporary directory.
@@ -130,6 +135,9 @@ mod test_reporting {
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
                 function_kind: FunctionKind::LambdaSet,
+                progress: None,
+                tabs: Tabs::Reject,
+                numeric_defaults: NumericDefaults::Silent,
             };
             let result = roc_load::load_and_typecheck(
                 arena,
@@ -176,7 +184,13 @@ mod test_reporting {
         Ok((module_src, type_problems, can_problems, home, interns))
     }
 
-    fn list_reports_new<F>(subdir: &str, arena: &Bump, src: &str, finalize_render: F) -> String
+    fn list_reports_new<F>(
+        subdir: &str,
+        arena: &Bump,
+        src: &str,
+        type_options: TypePrintOptions,
+        finalize_render: F,
+    ) -> String
     where
         F: FnOnce(RocDocBuilder<'_>, &mut String),
     {
@@ -193,7 +207,8 @@ mod test_reporting {
                 let src_lines: Vec<&str> = module_src.split('\n').collect();
                 let mut reports = Vec::new();
 
-                let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+                let alloc = RocDocAllocator::new(&src_lines, home, &interns)
+                    .with_type_options(type_options);
 
                 for problem in can_problems {
                     let report = can_problem(&alloc, &lines, filename.clone(), problem.clone());
@@ -425,6 +440,21 @@ mod test_reporting {
 
     /// Do not call this directly! Use the test_report macro below!
     fn __new_report_problem_as(test_name: &str, src: &str, check_render: impl FnOnce(&str)) {
+        report_problem_with_type_options_as(
+            test_name,
+            src,
+            TypePrintOptions::default(),
+            check_render,
+        )
+    }
+
+    /// Like `__new_report_problem_as`, but prints types with the given options.
+    fn report_problem_with_type_options_as(
+        test_name: &str,
+        src: &str,
+        type_options: TypePrintOptions,
+        check_render: impl FnOnce(&str),
+    ) {
         let arena = Bump::new();
 
         let finalize_render = |doc: RocDocBuilder<'_>, buf: &mut String| {
@@ -433,7 +463,7 @@ mod test_reporting {
                 .expect("list_reports")
         };
 
-        let buf = list_reports_new(test_name, &arena, src, finalize_render);
+        let buf = list_reports_new(test_name, &arena, src, type_options, finalize_render);
 
         check_render(buf.as_str());
     }
@@ -534,15 +564,13 @@ mod test_reporting {
         @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    The `i` name is first defined here:
+    The `i` name is defined twice:
 
     4│      i = 1
-            ^
-
-    But then it's defined a second time here:
-
+     │      - first defined here
+    5│
     6│      s = \i ->
-                 ^
+                 ^ defined again here
 
     Since these variables have the same name, it's easy to use the wrong
     one by accident. Give one of them a new name.
@@ -566,15 +594,13 @@ mod test_reporting {
         @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    The `Booly` name is first defined here:
+    The `Booly` name is defined twice:
 
     4│      Booly : [Yes, No]
-            ^^^^^^^^^^^^^^^^^
-
-    But then it's defined a second time here:
-
+     │      ----------------- first defined here
+    5│
     6│      Booly : [Yes, No, Maybe]
-            ^^^^^^^^^^^^^^^^^^^^^^^^
+            ^^^^^^^^^^^^^^^^^^^^^^^^ defined again here
 
     Since these aliases have the same name, it's easy to use the wrong one
     by accident. Give one of them a new name.
@@ -603,6 +629,32 @@ mod test_reporting {
 
     6│          if selectedId != thisId == adminsId then
                    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+
+    Note: == and != have the same precedence, and they don't group to the
+    left or the right, so I can't tell which should happen first. Add
+    parentheses to say, like (a != b) == c.
+    "
+    );
+
+    test_report!(
+        report_precedence_problem_comparisons,
+        indoc!(
+            r"
+            1 < 2 <= 3
+            "
+        ),
+        @r"
+    ── SYNTAX PROBLEM in /code/proj/Main.roc ───────────────────────────────────────
+
+    Using < and <= together requires parentheses, to clarify how they
+    should be grouped.
+
+    4│      1 < 2 <= 3
+            ^^^^^^^^^^
+
+    Note: <, >, <=, and >= all have the same precedence, and they don't
+    group to the left or the right, so I can't tell which should happen
+    first. Add parentheses to say, like (a < b) <= c.
     "
     );
 
@@ -689,6 +741,10 @@ mod test_reporting {
     5│>          1
     6│>              == 2
     7│>              == 3
+
+    Note: == and != have the same precedence, and they don't group to the
+    left or the right, so I can't tell which should happen first. Add
+    parentheses to say, like (a == b) == c.
     "
     );
 
@@ -716,9 +772,9 @@ mod test_reporting {
 
     If you don't need `htmlChildren`, then you can just remove it. However,
     if you really do need `htmlChildren` as an argument of `box`, prefix it
-    with an underscore, like this: "_`htmlChildren`". Adding an underscore
-    at the start of a variable name is a way of saying that the variable
-    is not used.
+    with an underscore, like this: `_htmlChildren`. Adding an underscore at
+    the start of a variable name is a way of saying that the variable is
+    not used.
 
     ── UNUSED DEFINITION in /code/proj/Main.roc ────────────────────────────────────
 
@@ -822,6 +878,22 @@ mod test_reporting {
         );
     }
 
+    #[test]
+    fn report_region_past_u16_lines() {
+        // Lines used to be counted in u16s, which wrapped around after 65535.
+        let src = format!(
+            "app \"test\" provides [main] to \"./platform\"\n{}main = notDefined\n",
+            "\n".repeat(70_000)
+        );
+
+        __new_report_problem_as("report_region_past_u16_lines", &src, |golden| {
+            assert!(
+                golden.contains("70002│  main = notDefined\n               ^^^^^^^^^^"),
+                "{golden}"
+            )
+        });
+    }
+
     test_report!(
         if_condition_not_bool,
         indoc!(
@@ -1122,6 +1194,17 @@ mod test_reporting {
     infinitely.
 
         (∞ -> a) -> a
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    4│      f = \g -> g g
+                        ^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1147,6 +1230,17 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> *
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    4│      f = \x -> f [x]
+                        ^^^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1174,6 +1268,17 @@ mod test_reporting {
 
         List ∞ -> *
 
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    4│      f = \x -> g x
+                        ^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
+
     ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
@@ -1186,6 +1291,17 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> *
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    5│      g = \x -> f [x]
+                        ^^^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1283,6 +1399,17 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> *
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    5│      f = \x -> f [x]
+                        ^^^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1309,6 +1436,17 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> List *
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    5│      f = \x -> f [x]
+                        ^^^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1348,6 +1486,17 @@ mod test_reporting {
 
         List ∞ -> List *
 
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    5│      f = \x -> g x
+                        ^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
+
     ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
@@ -1360,6 +1509,17 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> List *
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    6│      g = \x -> f [x]
+                        ^^^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1388,6 +1548,17 @@ mod test_reporting {
 
         List ∞ -> List *
 
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    4│      f = \x -> g x
+                        ^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
+
     ── CIRCULAR TYPE in /code/proj/Main.roc ────────────────────────────────────────
 
     I'm inferring a weird self-referential type for `g`:
@@ -1400,6 +1571,17 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> List *
+
+    The type of this argument would have to contain itself, which is where
+    the self-reference comes from:
+
+    6│      g = \x -> f [x]
+                        ^^^
+
+    Tip: This usually happens when a value is applied to itself, like `g g`;
+    when a function calls itself with its argument wrapped in something,
+    like `f [x]`; or when a call is missing an argument, so a function ends
+    up being used where its result was expected.
     "
     );
 
@@ -1439,6 +1621,44 @@ mod test_reporting {
     "
     );
 
+    test_report!(
+        tuple_elem_mismatch_elides_same_elems,
+        indoc!(
+            r#"
+            f : (Str, Str, { name : Str, age : U8 }, Bool) -> Str
+            f = \_ -> ""
+
+            f ("x", "y", { name: "z", age: "w" }, Bool.true)
+            "#
+        ),
+        @r#"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    This 1st argument to `f` has an unexpected type:
+
+    7│      f ("x", "y", { name: "z", age: "w" }, Bool.true)
+              ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+
+    The argument is a tuple of type:
+
+        (
+            …,
+            …,
+            { age : Str, … },
+            …,
+        )a
+
+    But `f` needs its 1st argument to be:
+
+        (
+            …,
+            …,
+            { age : U8, … },
+            …,
+        )a
+    "#
+    );
+
     test_report!(
         tag_mismatch,
         indoc!(
@@ -2870,6 +3090,180 @@ mod test_reporting {
     "
     );
 
+    test_report!(
+        patterns_nested_tag_payload_not_exhaustive,
+        indoc!(
+            r"
+            x : Result (Result U8 U8) U8
+            x = Ok (Ok 1)
+
+            when x is
+                Ok (Ok _) -> 1
+                Err _ -> 2
+            "
+        ),
+        @r"
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when x is
+    8│>          Ok (Ok _) -> 1
+    9│>          Err _ -> 2
+
+    Other possibilities include:
+
+        Ok (Err _)
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "
+    );
+
+    test_report!(
+        patterns_record_tag_field_not_exhaustive,
+        indoc!(
+            r"
+            r : { a : Result U8 U8, b : U8 }
+            r = { a: Ok 1, b: 2 }
+
+            when r is
+                { a: Ok _, b } -> b
+            "
+        ),
+        @r"
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when r is
+    8│>          { a: Ok _, b } -> b
+
+    Other possibilities include:
+
+        { a: Err _, b }
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "
+    );
+
+    test_report!(
+        patterns_nested_guard_not_exhaustive,
+        indoc!(
+            r"
+            x : Result (Result U8 U8) U8
+            x = Ok (Ok 1)
+
+            when x is
+                Ok (Ok n) if n > 0 -> 1
+                Ok (Err _) -> 1
+                Err _ -> 2
+            "
+        ),
+        @r"
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+     7│>      when x is
+     8│>          Ok (Ok n) if n > 0 -> 1
+     9│>          Ok (Err _) -> 1
+    10│>          Err _ -> 2
+
+    Other possibilities include:
+
+        Ok (Ok _)    (note the lack of an if clause)
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "
+    );
+
+    test_report!(
+        patterns_missing_despite_guard_not_exhaustive,
+        indoc!(
+            r"
+            x : Result (Result U8 U8) U8
+            x = Ok (Ok 1)
+
+            when x is
+                Ok (Ok n) if n > 0 -> 1
+                Err _ -> 2
+            "
+        ),
+        @r"
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when x is
+    8│>          Ok (Ok n) if n > 0 -> 1
+    9│>          Err _ -> 2
+
+    Other possibilities include:
+
+        Ok (Err _)
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "
+    );
+
+    test_report!(
+        patterns_tuple_annotated_union_not_exhaustive,
+        indoc!(
+            r"
+            x : (Result U8 U8, [A, B])
+            x = (Ok 1, A)
+
+            when x is
+                (Ok _, A) -> 1
+                (Err _, _) -> 1
+            "
+        ),
+        @r"
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when x is
+    8│>          (Ok _, A) -> 1
+    9│>          (Err _, _) -> 1
+
+    Other possibilities include:
+
+        ( Ok _, B )
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "
+    );
+
+    test_report!(
+        patterns_tag_payloads_annotated_union_not_exhaustive,
+        indoc!(
+            r"
+            x : [T (Result U8 U8) [A, B]]
+            x = T (Ok 1) A
+
+            when x is
+                T (Ok _) A -> 1
+                T (Err _) _ -> 1
+            "
+        ),
+        @r"
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when x is
+    8│>          T (Ok _) A -> 1
+    9│>          T (Err _) _ -> 1
+
+    Other possibilities include:
+
+        T (Ok _) B
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "
+    );
+
     test_report!(
         patterns_int_redundant,
         indoc!(
@@ -2890,11 +3284,36 @@ mod test_reporting {
     6│>          2 -> 4
     7│           _ -> 5
 
-    Any value of this shape will be handled by a previous pattern, so this
+    Any value of this shape will be handled by the 1st pattern, so this
     one should be removed.
     "
     );
 
+    test_report!(
+        branch_after_catch_all_is_redundant,
+        indoc!(
+            r"
+            when 0x1 is
+                2 -> 3
+                other -> other
+                4 -> 5
+            "
+        ),
+        @r"
+    ── REDUNDANT PATTERN in /code/proj/Main.roc ────────────────────────────────────
+
+    The 3rd pattern is redundant:
+
+    6│          other -> other
+     │          ----- matches everything
+    7│          4 -> 5
+                ^ never reached
+
+    The 2nd pattern matches any value, so no branch after it can ever be
+    reached. Either move that pattern to the end, or remove this one.
+    "
+    );
+
     test_report!(
         unify_alias_other,
         indoc!(
@@ -2933,7 +3352,6 @@ mod test_reporting {
     );
 
     test_report!(
-        #[ignore]
         cyclic_alias,
         indoc!(
             r"
@@ -2949,13 +3367,82 @@ mod test_reporting {
         @r"
     ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
 
-    The `Foo` alias is self-recursive in an invalid way:
+    The `Foo` alias is recursive in an invalid way:
 
     4│      Foo : { x : Bar }
             ^^^
 
-    Recursion in aliases is only allowed if recursion happens behind a
+    The `Foo` alias depends on itself through the following chain of
+    definitions:
+
+        ┌─────┐
+        │     Foo
+        │     ↓
+        │     Bar
+        └─────┘
+
+    `Bar` is defined here:
+
+    5│      Bar : { y : Foo }
+            ^^^
+
+    Recursion in aliases is only allowed if recursion happens behind a
+    tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `Bar` in a tag union with
+    a non-recursive variant, like [Done, More Bar], or add a non-recursive
+    variant to a tag union that is already in the cycle.
+    "
+    );
+
+    test_report!(
+        cyclic_alias_through_chain,
+        indoc!(
+            r"
+            Foo : { x : Bar }
+            Bar : List Baz
+            Baz : [Baz Str Foo]
+
+            f : Foo
+
+            f
+            "
+        ),
+        @r"
+    ── CYCLIC ALIAS in /code/proj/Main.roc ─────────────────────────────────────────
+
+    The `Foo` alias is recursive in an invalid way:
+
+    4│      Foo : { x : Bar }
+            ^^^
+
+    The `Foo` alias depends on itself through the following chain of
+    definitions:
+
+        ┌─────┐
+        │     Foo
+        │     ↓
+        │     Bar
+        │     ↓
+        │     Baz
+        └─────┘
+
+    `Bar` is defined here:
+
+    5│      Bar : List Baz
+            ^^^
+
+    `Baz` is defined here:
+
+    6│      Baz : [Baz Str Foo]
+            ^^^
+
+    Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `Bar` in a tag union with
+    a non-recursive variant, like [Done, More Bar], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -2982,6 +3469,10 @@ mod test_reporting {
 
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `Foo` in a tag union with
+    a non-recursive variant, like [Done, More Foo], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -3390,6 +3881,30 @@ mod test_reporting {
     "###
     );
 
+    test_report!(
+        keyword_near_miss,
+        indoc!(
+            r"
+            whn x is
+                A -> 1
+            "
+        ),
+        @r###"
+    ── MISSPELLED KEYWORD in tmp/keyword_near_miss/Test.roc ────────────────────────
+
+    I am partway through parsing a definition, but I got stuck here:
+
+    1│  app "test" provides [main] to "./platform"
+    2│
+    3│  main =
+    4│      whn x is
+            ^^^
+
+    Did you mean to use the `when` keyword here? This looks like a
+    misspelling of it.
+    "###
+    );
+
     test_report!(
         two_different_cons,
         indoc!(
@@ -3833,6 +4348,31 @@ mod test_reporting {
     "
     );
 
+    test_report!(
+        module_typo_suggests_modules_exposing_value,
+        indoc!(
+            r"
+            Lst.map
+            "
+        ),
+        @r"
+    ── MODULE NOT IMPORTED in /code/proj/Main.roc ──────────────────────────────────
+
+    The `Lst` module is not imported:
+
+    4│      Lst.map
+            ^^^^^^^
+
+    Is there an import missing? Perhaps there is a typo. Did you mean one
+    of these?
+
+        List.map
+        Set.map
+        Dict.map
+        Task.map
+    "
+    );
+
     test_report!(
         optional_record_default_type_error,
         indoc!(
@@ -4163,14 +4703,13 @@ mod test_reporting {
 
     The 3rd pattern is redundant:
 
-    4│      when Foo 1 2 3 is
-    5│          Foo _ 1 _ -> 1
     6│          _ -> 2
+     │          - matches everything
     7│          _ -> 3
-                ^
+                ^ never reached
 
-    Any value of this shape will be handled by a previous pattern, so this
-    one should be removed.
+    The 2nd pattern matches any value, so no branch after it can ever be
+    reached. Either move that pattern to the end, or remove this one.
     "
     );
 
@@ -4213,8 +4752,8 @@ mod test_reporting {
 
     If you don't need `foo`, then you can just remove it. However, if you
     really do need `foo` as an argument of `f`, prefix it with an underscore,
-    like this: "_`foo`". Adding an underscore at the start of a variable
-    name is a way of saying that the variable is not used.
+    like this: `_foo`. Adding an underscore at the start of a variable name
+    is a way of saying that the variable is not used.
     "#
     );
 
@@ -4560,6 +5099,10 @@ mod test_reporting {
                       ^
 
     Tab characters are not allowed in Roc code. Please use spaces instead!
+
+    If you'd rather keep the tabs, run roc with `--tab-width 4` (or set
+    `ROC_TAB_WIDTH=4`) and each one will count as up to that many columns of
+    indentation.
     "###
     );
 
@@ -4575,6 +5118,10 @@ mod test_reporting {
                              ^
 
     Tab characters are not allowed in Roc code. Please use spaces instead!
+
+    If you'd rather keep the tabs, run roc with `--tab-width 4` (or set
+    `ROC_TAB_WIDTH=4`) and each one will count as up to that many columns of
+    indentation.
     "###
     );
 
@@ -4970,14 +5517,14 @@ mod test_reporting {
             import Menu { x = 4 }
             "
         ),@r###"
-    ── RECORD PARSE PROBLEM in tmp/weird_import_params_record/Test.roc ─────────────
+    ── UNFINISHED RECORD in tmp/weird_import_params_record/Test.roc ────────────────
 
     I am partway through parsing a record, but I got stuck here:
 
     4│      import Menu { x = 4 }
-                        ^
+                            ^
 
-    TODO provide more context.
+    I was expecting a `:`, a `,`, or a `}` next.
     "###
     );
 
@@ -5590,8 +6137,32 @@ mod test_reporting {
     6│
         ^
 
-    I was expecting to see a closing square bracket before this, so try
-    adding a ] and see if that helps?
+    I was expecting an expression or a `]` next.
+
+    Note: When I get stuck like this, it usually means that there is a
+    missing parenthesis or bracket somewhere earlier. It could also be a
+    stray keyword or operator.
+    "
+    );
+
+    test_report!(
+        list_missing_comma,
+        indoc!(
+            r"
+            [1, 2 # 3, 4
+            )
+            "
+        ),
+        @r"
+    ── UNFINISHED LIST in tmp/list_missing_comma/Test.roc ──────────────────────────
+
+    I am partway through started parsing a list, but I got stuck here:
+
+    4│      [1, 2 # 3, 4
+    5│      )
+            ^
+
+    I was expecting a `,` or a `]` next.
 
     Note: When I get stuck like this, it usually means that there is a
     missing parenthesis or bracket somewhere earlier. It could also be a
@@ -5677,6 +6248,37 @@ mod test_reporting {
     "#
     );
 
+    test_report!(
+        unterminated_interpolation,
+        r#""Hello, $(name""#,
+        @r#"
+    ── ENDLESS INTERPOLATION in tmp/unterminated_interpolation/Test.roc ────────────
+
+    I cannot find the end of this string interpolation:
+
+    4│      "Hello, $(name"
+                    ^^^^^^^
+
+    I was expecting a ) to end it, like in "The count is $(count)".
+    "#
+    );
+
+    test_report!(
+        nested_interpolation,
+        r#""a $(Str.trim "$(x) ") b""#,
+        @r#"
+    ── NESTED INTERPOLATION in tmp/nested_interpolation/Test.roc ───────────────────
+
+    This string interpolation is inside of another one:
+
+    4│      "a $(Str.trim "$(x) ") b"
+                           ^^^^
+
+    Interpolations can't be nested. Try giving the inner string a name
+    with a `=` definition, and interpolating that instead.
+    "#
+    );
+
     test_report!(
         single_quote_too_long,
         r"'abcdef'",
@@ -5726,6 +6328,21 @@ mod test_reporting {
     "#
     );
 
+    test_report!(
+        endless_raw_string,
+        r###"r##"C:\Users"#"###,
+        @r###"
+    ── ENDLESS STRING in tmp/endless_raw_string/Test.roc ───────────────────────────
+
+    I cannot find the end of this raw string:
+
+    4│      r##"C:\Users"#
+            ^
+
+    It starts with r##", so I was looking for a "## to end it.
+    "###
+    );
+
     test_report!(
         multi_insufficient_indent,
         "    \"\"\"\n  testing\n    \"\"\"", // 4 space indent on the start, 2 space on the `testing` line
@@ -6139,6 +6756,28 @@ All branches in an `if` must have the same type!
     "###
     );
 
+    test_report!(
+        old_package_header,
+        indoc!(
+            r#"
+            package "pkg"
+                exposes []
+                packages {}
+            "#
+        ),
+        @r#"
+    ── OLD PACKAGE HEADER in /code/proj/Main.roc ───────────────────────────────────
+
+    This package header has a name, which is the old style:
+
+    1│  package "pkg"
+                ^^^^^
+
+    Package headers don't have names anymore, so they look like
+    package [Foo] {} now. roc format can update this one for you.
+    "#
+    );
+
     #[test]
     fn provides_to_identifier() {
         report_header_problem_as(
@@ -6714,7 +7353,7 @@ All branches in an `if` must have the same type!
         @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    This alias has the same name as a builtin:
+    This alias has the same name as a builtin from the Result module:
 
     4│      Result a b : [Ok a, Err b]
             ^^^^^^^^^^^^^^^^^^^^^^^^^^
@@ -6752,7 +7391,7 @@ All branches in an `if` must have the same type!
         @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    This alias has the same name as a builtin:
+    This alias has the same name as a builtin from the Result module:
 
     4│      Result a b : [Ok a, Err b]
             ^^^^^^^^^^^^^^^^^^^^^^^^^^
@@ -7059,6 +7698,10 @@ All branches in an `if` must have the same type!
 
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `F` in a tag union with a
+    non-recursive variant, like [Done, More F], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -7082,6 +7725,10 @@ All branches in an `if` must have the same type!
 
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `F` in a tag union with a
+    non-recursive variant, like [Done, More F], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -7104,6 +7751,10 @@ All branches in an `if` must have the same type!
 
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `F` in a tag union with a
+    non-recursive variant, like [Done, More F], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -7762,6 +8413,10 @@ All branches in an `if` must have the same type!
     But `get` needs its 2nd argument to be:
 
         U64
+
+    Tip: This number is negative, so it doesn't fit in `U64`, whose values
+    range from 0 to 18446744073709551615. The smallest type it fits in is
+    `I8`.
     "
     );
 
@@ -7788,6 +8443,10 @@ All branches in an `if` must have the same type!
     But `get` needs its 2nd argument to be:
 
         U64
+
+    Tip: This number is negative, so it doesn't fit in `U64`, whose values
+    range from 0 to 18446744073709551615. The smallest type it fits in is
+    `I64`.
     "
     );
 
@@ -7815,6 +8474,10 @@ All branches in an `if` must have the same type!
     But `get` needs its 2nd argument to be:
 
         U64
+
+    Tip: This number is negative, so it doesn't fit in `U64`, whose values
+    range from 0 to 18446744073709551615. The smallest type it fits in is
+    `I64`.
     "
     );
 
@@ -7845,6 +8508,9 @@ All branches in an `if` must have the same type!
         U8
 
     The branches must be cases of the `when` condition's type!
+
+    Tip: This number is negative, so it doesn't fit in `U8`, whose values
+    range from 0 to 255. The smallest type it fits in is `I8`.
     "
     );
 
@@ -7868,6 +8534,10 @@ All branches in an `if` must have the same type!
 
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `R` in a tag union with a
+    non-recursive variant, like [Done, More R], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -7891,6 +8561,10 @@ All branches in an `if` must have the same type!
 
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `R` in a tag union with a
+    non-recursive variant, like [Done, More R], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -7922,8 +8596,17 @@ All branches in an `if` must have the same type!
         │     Bar
         └─────┘
 
+    `Bar` is defined here:
+
+    5│      Bar a : [Stuff (Foo a)]
+            ^^^
+
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `Bar` in a tag union with
+    a non-recursive variant, like [Done, More Bar], or add a non-recursive
+    variant to a tag union that is already in the cycle.
     "
     );
 
@@ -7943,7 +8626,7 @@ All branches in an `if` must have the same type!
         @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    This alias has the same name as a builtin:
+    This alias has the same name as a builtin from the Result module:
 
     4│      Result a b : [Ok a, Err b]
             ^^^^^^^^^^^^^^^^^^^^^^^^^^
@@ -8805,15 +9488,13 @@ All branches in an `if` must have the same type!
         @r"
         ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-        The `Ability` name is first defined here:
+        The `Ability` name is defined twice:
 
         3│  Ability implements ab : a -> U64 where a implements Ability
-            ^^^^^^^
-
-        But then it's defined a second time here:
-
+         │  ------- first defined here
+        4│
         5│  Ability implements ab1 : a -> U64 where a implements Ability
-            ^^^^^^^
+            ^^^^^^^ defined again here
 
         Since these abilities have the same name, it's easy to use the wrong
         one by accident. Give one of them a new name.
@@ -10095,30 +10776,16 @@ All branches in an `if` must have the same type!
         @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    The `main` name is first defined here:
+    The `main` name is defined twice:
 
     3│  main = 1
-        ^^^^
-
-    But then it's defined a second time here:
-
+     │  ---- first defined here
+    4│
     5│  main = \n -> n + 2
-        ^^^^
+        ^^^^ defined again here
 
     Since these variables have the same name, it's easy to use the wrong
     one by accident. Give one of them a new name.
-
-    ── UNNECESSARY DEFINITION in /code/proj/Main.roc ───────────────────────────────
-
-    This destructure assignment doesn't introduce any new variables:
-
-    5│  main = \n -> n + 2
-        ^^^^
-
-    If you don't need to use the value on the right-hand-side of this
-    assignment, consider removing the assignment. Since Roc is purely
-    functional, assignments that don't introduce variables cannot affect a
-    program's behavior!
     "
     );
 
@@ -10192,6 +10859,23 @@ All branches in an `if` must have the same type!
     But you are trying to use it as:
 
         * -> Str
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    This `if` has an `else` branch with a different type from its `then` branch:
+
+    5│      f = \_ -> if Bool.true then {} else f {}
+                                                ^^^^
+
+    This `f` call produces:
+
+        * -> Str
+
+    but the `then` branch has the type:
+
+        {}
+
+    All branches in an `if` must have the same type!
     "
     );
 
@@ -10982,6 +11666,11 @@ All branches in an `if` must have the same type!
 
     Recursion in opaquees is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, wrap the reference to `Recursive` in a tag
+    union with a non-recursive variant, like [Done, More Recursive], or
+    add a non-recursive variant to a tag union that is already in the
+    cycle.
     "
     );
 
@@ -11149,7 +11838,7 @@ All branches in an `if` must have the same type!
 
     If you don't need `x`, then you can just remove it. However, if you
     really do need `x` as an argument of this function, prefix it with an
-    underscore, like this: "_`x`". Adding an underscore at the start of a
+    underscore, like this: `_x`. Adding an underscore at the start of a
     variable name is a way of saying that the variable is not used.
     "#
     );
@@ -11560,7 +12249,7 @@ All branches in an `if` must have the same type!
     @r"
     ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
 
-    This opaque type has the same name as a builtin:
+    This opaque type has the same name as a builtin from the Num module:
 
     4│      Dec := [ S Dec, Z ]
             ^^^^^^^^^^^^^^^^^^^
@@ -12042,6 +12731,9 @@ All branches in an `if` must have the same type!
 
         U8
 
+    Tip: This number is negative, so it doesn't fit in `U8`, whose values
+    range from 0 to 255. The smallest type it fits in is `I8`.
+
     ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
 
     This 2nd argument to `shiftRightBy` has an unexpected type:
@@ -12057,6 +12749,9 @@ All branches in an `if` must have the same type!
 
         U8
 
+    Tip: This number is negative, so it doesn't fit in `U8`, whose values
+    range from 0 to 255. The smallest type it fits in is `I8`.
+
     ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
 
     This 2nd argument to `shiftLeftBy` has an unexpected type:
@@ -12071,6 +12766,9 @@ All branches in an `if` must have the same type!
     But `shiftLeftBy` needs its 2nd argument to be:
 
         U8
+
+    Tip: This number is negative, so it doesn't fit in `U8`, whose values
+    range from 0 to 255. The smallest type it fits in is `I8`.
     "
     );
 
@@ -12154,6 +12852,9 @@ All branches in an `if` must have the same type!
         U16, I32, U32, I64, U64, I128, or U128
 
     The branches must be cases of the `when` condition's type!
+
+    Tip: This number doesn't fit in `U8`, whose values range from 0 to 255.
+    The smallest type it fits in is `U16`.
     "#
     );
 
@@ -13027,7 +13728,7 @@ All branches in an `if` must have the same type!
     8│>          [.., A] -> ""
     9│           [..] -> ""
 
-    Any value of this shape will be handled by a previous pattern, so this
+    Any value of this shape will be handled by the 1st pattern, so this
     one should be removed.
     "#
     );
@@ -13386,7 +14087,7 @@ All branches in an `if` must have the same type!
      9│>          [_] -> ""
     10│           [..] -> ""
 
-    Any value of this shape will be handled by a previous pattern, so this
+    Any value of this shape will be handled by the 2nd pattern, so this
     one should be removed.
     "#
     );
@@ -13414,8 +14115,8 @@ All branches in an `if` must have the same type!
     9│          [..] -> ""
                 ^^^^
 
-    Any value of this shape will be handled by a previous pattern, so this
-    one should be removed.
+    Any value of this shape will be handled by the patterns before it, so
+    this one should be removed.
     "#
     );
 
@@ -13442,7 +14143,7 @@ All branches in an `if` must have the same type!
     9│          [.., _] -> ""
                 ^^^^^^^
 
-    Any value of this shape will be handled by a previous pattern, so this
+    Any value of this shape will be handled by the 2nd pattern, so this
     one should be removed.
     "#
     );
@@ -13469,7 +14170,7 @@ All branches in an `if` must have the same type!
     8│>          [_, .., {}] -> ""
     9│           [..] -> ""
 
-    Any value of this shape will be handled by a previous pattern, so this
+    Any value of this shape will be handled by the 1st pattern, so this
     one should be removed.
     "#
     );
@@ -13621,7 +14322,7 @@ All branches in an `if` must have the same type!
 
     I can't generate an implementation of the `Eq` ability for
 
-        FloatingPoint ?
+        FloatingPoint Binary64
 
     Note: I can't derive `Bool.isEq` for floating-point types. That's
     because Roc's floating-point numbers cannot be compared for total
@@ -14426,4 +15127,696 @@ All branches in an `if` must have the same type!
     //     ),
     //     @r""
     // );
+
+    test_report!(
+        independent_type_errors_are_all_reported,
+        indoc!(
+            r#"
+            x : U8
+            x = "a"
+
+            y : Bool
+            y = "b"
+
+            z : U8
+            z = {}
+
+            w : Bool
+            w = {}
+
+            a = if Bool.true then "" else 1
+
+            b = a + 1
+
+            { x, y, z, w, b }
+            "#
+        ),
+        @r#"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `x` definition:
+
+    4│      x : U8
+    5│      x = "a"
+                ^^^
+
+    The body is a string of type:
+
+        Str
+
+    But the type annotation on `x` says it should be:
+
+        U8
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `y` definition:
+
+    7│      y : Bool
+    8│      y = "b"
+                ^^^
+
+    The body is a string of type:
+
+        Str
+
+    But the type annotation on `y` says it should be:
+
+        Bool
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `z` definition:
+
+    10│      z : U8
+    11│      z = {}
+                 ^^
+
+    The body is a record of type:
+
+        {}
+
+    But the type annotation on `z` says it should be:
+
+        U8
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `w` definition:
+
+    13│      w : Bool
+    14│      w = {}
+                 ^^
+
+    The body is a record of type:
+
+        {}
+
+    But the type annotation on `w` says it should be:
+
+        Bool
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    This `if` has an `else` branch with a different type from its `then` branch:
+
+    16│      a = if Bool.true then "" else 1
+                                           ^
+
+    The `else` branch is a number of type:
+
+        Num *
+
+    but the `then` branch has the type:
+
+        Str
+
+    All branches in an `if` must have the same type!
+    "#
+    );
+
+    test_report!(
+        large_nested_types_are_elided,
+        indoc!(
+            r#"
+            greet : Str -> Str
+            greet = \name -> name
+
+            model = {
+                user: {
+                    name: "Ada",
+                    email: "ada@example.com",
+                    address: {
+                        street: "Main St",
+                        city: "London",
+                        geo: { lat: 51.5, lon: -0.1, accuracy: { meters: 10.0, source: "gps" } },
+                    },
+                },
+                settings: {
+                    theme: {
+                        colors: { fg: "black", bg: "white", accent: "blue" },
+                        font: { family: "serif", size: 12u8 },
+                    },
+                    locale: "en-GB",
+                },
+                cart: {
+                    items: [{ id: 1u64, name: "tea", price: { amount: 2.5dec, currency: "GBP" } }],
+                    total: 2.5dec,
+                },
+            }
+
+            greet model
+            "#
+        ),
+        @r"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    This 1st argument to `greet` has an unexpected type:
+
+    30│      greet model
+                   ^^^^^
+
+    This `model` value is a:
+
+        {
+            cart : {
+                items : List {
+                    id : U64,
+                    name : Str,
+                    price : { … },
+                },
+                total : Dec,
+            },
+            settings : {
+                locale : Str,
+                theme : {
+                    colors : { … },
+                    font : { … },
+                },
+            },
+            user : {
+                address : {
+                    city : Str,
+                    geo : { … },
+                    street : Str,
+                },
+                email : Str,
+                name : Str,
+            },
+        }
+
+    But `greet` needs its 1st argument to be:
+
+        Str
+
+    Note: These types are large, so some of their deeply nested parts are
+    shown as … to keep them readable. Run roc with `--verbose-types` (or
+    set `ROC_VERBOSE_TYPES=1`) to see them in full.
+    "
+    );
+
+    test_report!(
+        num_literal_out_of_range_suggests_type,
+        indoc!(
+            r#"
+            x : U8
+            x = 300
+
+            y : I8
+            y = -200
+
+            f : U32 -> U32
+            f = \n -> n
+
+            { x, y, z: f -1 }
+            "#
+        ),
+        @r"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `x` definition:
+
+    4│      x : U8
+    5│      x = 300
+                ^^^
+
+    The body is a number of type:
+
+        I16, U16, F32, I32, U32, F64, I64, U64, I128, Dec, or U128
+
+    But the type annotation on `x` says it should be:
+
+        U8
+
+    Tip: This number doesn't fit in `U8`, whose values range from 0 to 255.
+    The smallest type it fits in is `U16`.
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `y` definition:
+
+    7│      y : I8
+    8│      y = -200
+                ^^^^
+
+    The body is a number of type:
+
+        I16, F32, I32, F64, I64, I128, or Dec
+
+    But the type annotation on `y` says it should be:
+
+        I8
+
+    Tip: This number doesn't fit in `I8`, whose values range from -128 to
+    127. The smallest type it fits in is `I16`.
+
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    This 1st argument to `f` has an unexpected type:
+
+    13│      { x, y, z: f -1 }
+                          ^^
+
+    The argument is a number of type:
+
+        I8, I16, F32, I32, F64, I64, I128, or Dec
+
+    But `f` needs its 1st argument to be:
+
+        U32
+
+    Tip: This number is negative, so it doesn't fit in `U32`, whose values
+    range from 0 to 4294967295. The smallest type it fits in is `I8`.
+    "
+    );
+
+    test_report!(
+        shadowing_exposed_builtin,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            import List exposing [map, len]
+            import Bool exposing [true]
+
+            map = \x, _ -> x
+
+            true = 0
+
+            main =
+                len = 3
+                (map [1] Num.toStr, true, len)
+            "#
+        ),
+        @r"
+    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+
+    This definition has the same name as `List.map`, a builtin from the List
+    module:
+
+    6│  map = \x, _ -> x
+        ^^^
+
+    The builtin is in scope because this import exposes it:
+
+    3│  import List exposing [map, len]
+                              ^^^
+
+    Give this definition a different name, or remove `map` from the import's
+    `exposing` list and refer to the builtin as `List.map`.
+
+    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+
+    This definition has the same name as `Bool.true`, a builtin from the
+    Bool module:
+
+    8│  true = 0
+        ^^^^
+
+    The builtin is in scope because this import exposes it:
+
+    4│  import Bool exposing [true]
+                              ^^^^
+
+    Give this definition a different name, or remove `true` from the
+    import's `exposing` list and refer to the builtin as `Bool.true`.
+
+    ── DUPLICATE NAME in /code/proj/Main.roc ───────────────────────────────────────
+
+    This definition has the same name as `List.len`, a builtin from the List
+    module:
+
+    11│      len = 3
+             ^^^
+
+    The builtin is in scope because this import exposes it:
+
+    3│  import List exposing [map, len]
+                                   ^^^
+
+    Give this definition a different name, or remove `len` from the import's
+    `exposing` list and refer to the builtin as `List.len`.
+    "
+    );
+
+    test_report!(
+        deprecated_def_and_alias,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ## Deprecated: Use `Point` instead.
+            Coord : { x : I64, y : I64 }
+
+            Point : { x : I64, y : I64 }
+
+            ## Returns the origin.
+            ## Deprecated: Use `zero` instead.
+            origin : Coord
+            origin = { x: 0, y: 0 }
+
+            zero : Point
+            zero = { x: 0, y: 0 }
+
+            main = (origin, zero)
+            "#
+        ),
+        @r"
+    ── DEPRECATED in /code/proj/Main.roc ───────────────────────────────────────────
+
+    `Coord` is deprecated:
+
+    10│  origin : Coord
+                  ^^^^^
+
+    Use `Point` instead.
+
+    ── DEPRECATED in /code/proj/Main.roc ───────────────────────────────────────────
+
+    `origin` is deprecated:
+
+    16│  main = (origin, zero)
+                 ^^^^^^
+
+    Use `zero` instead.
+    "
+    );
+
+    test_report!(
+        task_used_as_value,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            greeting : Task Str []
+            greeting = Task.ok "Hello"
+
+            main : Str
+            main = Str.concat greeting "!"
+            "#
+        ),
+        @r#"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    This 1st argument to `concat` has an unexpected type:
+
+    7│  main = Str.concat greeting "!"
+                          ^^^^^^^^
+
+    This `greeting` value is a:
+
+        Task Str []
+
+    But `concat` needs its 1st argument to be:
+
+        Str
+
+    Tip: This is a `Task` that succeeds with `Str`, but it's used as if it
+    were the `Task`'s result. Did you forget a `Task.await`, or a `!` after it?
+    "#
+    );
+
+    test_report!(
+        value_used_as_task,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main : Task Str []
+            main = "Hello"
+            "#
+        ),
+        @r#"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `main` definition:
+
+    3│  main : Task Str []
+    4│  main = "Hello"
+               ^^^^^^^
+
+    The body is a string of type:
+
+        Str
+
+    But the type annotation on `main` says it should be:
+
+        Task Str []
+
+    Tip: A `Task` is expected here, but this is the `Str` it would succeed
+    with. Did you mean to wrap it with `Task.ok`?
+    "#
+    );
+
+    test_report!(
+        task_success_mismatch,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main : Task {} []
+            main = Task.ok "Hello"
+            "#
+        ),
+        @r#"
+    ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+    Something is off with the body of the `main` definition:
+
+    3│  main : Task {} []
+    4│  main = Task.ok "Hello"
+               ^^^^^^^^^^^^^^^
+
+    This `ok` call produces:
+
+        Task Str []
+
+    But the type annotation on `main` says it should be:
+
+        Task {} []
+
+    Tip: This `Task` succeeds with `Str`, but you are treating it as if it
+    succeeds with {}. Did you forget a `Task.await` or `Task.map`?
+    "#
+    );
+
+    #[test]
+    fn expand_aliases_in_types() {
+        report_problem_with_type_options_as(
+            "expand_aliases_in_types",
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Pair a : { first : a, second : a }
+
+                swap : Pair Str -> Pair Str
+                swap = \{ first, second } -> { first: second, second: first }
+
+                numbers : Pair I64
+                numbers = { first: 1, second: 2 }
+
+                main = swap numbers
+                "#
+            ),
+            TypePrintOptions {
+                expand_aliases: true,
+                ..TypePrintOptions::default()
+            },
+            |golden| {
+                insta::assert_snapshot!(golden, @r#"
+                ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+                This 1st argument to `swap` has an unexpected type:
+
+                11│  main = swap numbers
+                                 ^^^^^^^
+
+                This `numbers` value is a:
+
+                    {
+                        first : I64,
+                        second : I64,
+                    }
+
+                But `swap` needs its 1st argument to be:
+
+                    {
+                        first : Str,
+                        second : Str,
+                    }
+                "#)
+            },
+        )
+    }
+
+    #[test]
+    fn one_line_types_within_max_width() {
+        report_problem_with_type_options_as(
+            "one_line_types_within_max_width",
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Point : { x : I64, y : I64 }
+
+                origin : Point
+                origin = { x: 0, y: 0 }
+
+                main : [Red, Green, Blue]
+                main = { from: origin, to: origin }
+                "#
+            ),
+            TypePrintOptions {
+                max_width: Some(40),
+                ..TypePrintOptions::default()
+            },
+            |golden| {
+                insta::assert_snapshot!(golden, @r#"
+                ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+                Something is off with the body of the `main` definition:
+
+                8│  main : [Red, Green, Blue]
+                9│  main = { from: origin, to: origin }
+                           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+
+                The body is a record of type:
+
+                    { from : Point, to : Point }
+
+                But the type annotation on `main` says it should be:
+
+                    [Blue, Green, Red]
+                "#)
+            },
+        )
+    }
+
+    #[test]
+    fn qualified_type_names() {
+        report_problem_with_type_options_as(
+            "qualified_type_names",
+            indoc!(
+                r#"
+                module [main]
+
+                Pair a : { first : a, second : a }
+
+                swap : Pair Str -> Pair Str
+                swap = \{ first, second } -> { first: second, second: first }
+
+                numbers : Pair I64
+                numbers = { first: 1, second: 2 }
+
+                main = swap numbers
+                "#
+            ),
+            TypePrintOptions {
+                qualify_names: true,
+                ..TypePrintOptions::default()
+            },
+            |golden| {
+                insta::assert_snapshot!(golden, @r#"
+                ── TYPE MISMATCH in /code/proj/Main.roc ────────────────────────────────────────
+
+                This 1st argument to `swap` has an unexpected type:
+
+                11│  main = swap numbers
+                                 ^^^^^^^
+
+                This `numbers` value is a:
+
+                    Test.Pair I64
+
+                But `swap` needs its 1st argument to be:
+
+                    Test.Pair Str
+                "#)
+            },
+        )
+    }
+
+    test_report!(
+        not_tail_recursive,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ## Tail-recursive
+            sum : List I64 -> I64
+            sum = \list ->
+                when list is
+                    [] -> 0
+                    [x, .. as rest] -> x + sum rest
+
+            main = sum [1, 2, 3]
+            "#
+        ),
+        @r#"
+    ── NOT TAIL RECURSIVE in /code/proj/Main.roc ───────────────────────────────────
+
+    `sum`'s doc comment says it's tail-recursive, but it calls itself where
+    there's still work to do after the call returns:
+
+    8│          [x, .. as rest] -> x + sum rest
+                                       ^^^^^^^^
+
+    Calls whose result is returned as-is get compiled into loops, but each
+    of these uses more stack space, so `sum` can overflow the stack when it
+    recurses deeply.
+
+    Tip: Pass the work that's left along in an extra argument, like an
+    accumulator, so the call's result can be returned directly.
+    "#
+    );
+
+    test_report!(
+        tail_recursive_without_calling_itself,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ## Tail-recursive, so big counts don't overflow the stack.
+            countdown : U64 -> U64
+            countdown = \n -> n
+
+            main = countdown 10
+            "#
+        ),
+        @r#"
+    ── NOT TAIL RECURSIVE in /code/proj/Main.roc ───────────────────────────────────
+
+    `countdown`'s doc comment says it's tail-recursive, but it never calls
+    itself:
+
+    5│  countdown = \n -> n
+        ^^^^^^^^^
+
+    Only a function that calls itself can be tail-recursive. If it isn't
+    meant to, remove the Tail-recursive line from its doc comment.
+    "#
+    );
+
+    test_no_problem!(
+        tail_recursive_with_accumulator,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ## Tail-recursive
+            sumHelp : List I64, I64 -> I64
+            sumHelp = \list, total ->
+                when list is
+                    [] -> total
+                    [x, .. as rest] ->
+                        if x == 0 then
+                            sumHelp rest total
+                        else
+                            sumHelp rest (total + x)
+
+            main = sumHelp [1, 2, 3] 0
+            "#
+        )
+    );
 }
@@ -7,6 +7,7 @@ use crate::module::{
     ModuleTiming, MonomorphizedModule, ParsedModule, ToplevelExpects, TypeCheckedModule,
 };
 use crate::module_cache::ModuleCache;
+use crate::progress::{ModulePhase, ProgressReporter, ProgressTracker};
 use bumpalo::{collections::CollectIn, Bump};
 use crossbeam::channel::{bounded, Sender};
 use crossbeam::deque::{Injector, Worker};
@@ -47,21 +48,21 @@ use roc_mono::layout::{
 use roc_mono::reset_reuse;
 use roc_mono::{drop_specialization, inc_dec};
 use roc_packaging::cache::RocCacheDir;
-use roc_parse::ast::{self, CommentOrNewline, ExtractSpaces, Spaced, ValueDef};
-use roc_parse::header::parse_module_defs;
+use roc_parse::ast::{self, CommentOrNewline, ExtractSpaces, Spaced, StrLiteral, ValueDef};
 use roc_parse::header::{
-    self, AppHeader, ExposedName, HeaderType, ImportsKeywordItem, PackageEntry, PackageHeader,
-    PlatformHeader, To,
+    self, parse_module_defs_with_warnings, AppHeader, ExposedName, HeaderType, ImportsKeywordItem,
+    PackageEntry, PackageHeader, PlatformHeader, To,
 };
 use roc_parse::parser::{FileError, SourceError, SyntaxError};
+use roc_parse::state::Tabs;
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
 use roc_reporting::error::r#type::suggest;
 #[cfg(not(target_family = "wasm"))]
 use roc_reporting::report::to_https_problem_report_string;
-use roc_reporting::report::{to_file_problem_report_string, Palette, RenderTarget};
+use roc_reporting::report::{to_file_problem_report_string, Palette, PlatformHints, RenderTarget};
 use roc_solve::module::{extract_module_owned_implementations, SolveConfig, Solved, SolvedModule};
-use roc_solve::FunctionKind;
+use roc_solve::{find_defaulted_numbers, FunctionKind, NumericDefaults};
 use roc_solve_problem::TypeError;
 use roc_target::Target;
 use roc_types::subs::{CopiedImport, ExposedTypesStorageSubs, Subs, VarStore, Variable};
@@ -112,6 +113,12 @@ pub struct LoadConfig {
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
     pub function_kind: FunctionKind,
+    /// Where to send per-module progress events, if anywhere.
+    pub progress: Option<ProgressReporter>,
+    /// Whether tabs in .roc files are rejected, or count as some number of columns.
+    pub tabs: Tabs,
+    /// Whether numbers whose type is ambiguous are defaulted silently, with a warning, or not at all.
+    pub numeric_defaults: NumericDefaults,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -187,6 +194,7 @@ fn start_phase<'a>(
                             module_ids: Arc::clone(&state.arc_modules),
                             shorthands: Arc::clone(&state.arc_shorthands),
                             ident_ids_by_module: Arc::clone(&state.ident_ids_by_module),
+                            tabs: state.tabs,
                         }
                     }
                 }
@@ -252,6 +260,7 @@ fn start_phase<'a>(
                 let mut aliases = MutMap::default();
                 let mut abilities_store = PendingAbilitiesStore::default();
                 let mut imported_module_params = VecMap::default();
+                let mut imported_deprecations = VecMap::default();
 
                 for imported in parsed.available_modules.keys() {
                     match state.module_cache.aliases.get(imported) {
@@ -298,6 +307,14 @@ fn start_phase<'a>(
                     if let Some(params) = state.module_cache.module_params.get(imported) {
                         imported_module_params.insert(*imported, params.clone());
                     }
+
+                    if let Some(deprecated) = state.module_cache.deprecated.get(imported) {
+                        imported_deprecations.extend(
+                            deprecated
+                                .iter()
+                                .map(|(symbol, message)| (*symbol, message.clone())),
+                        );
+                    }
                 }
 
                 let skip_constraint_gen = {
@@ -317,6 +334,7 @@ fn start_phase<'a>(
                     exposed_module_ids: state.exposed_modules,
                     exec_mode: state.exec_mode,
                     imported_module_params,
+                    imported_deprecations,
                 }
             }
 
@@ -355,6 +373,7 @@ fn start_phase<'a>(
                     constraints,
                     constraint,
                     state.function_kind,
+                    state.numeric_defaults,
                     pending_derives,
                     var_store,
                     available_modules,
@@ -705,10 +724,14 @@ struct State<'a> {
     /// If the root is an app module, the shorthand specified in its header's `to` field
     pub opt_platform_shorthand: Option<&'a str>,
     pub platform_data: Option<PlatformData<'a>>,
+    /// Hints from the platform's header, for type error reports
+    pub platform_hints: PlatformHints,
     pub exposed_types: ExposedByModule,
     pub platform_path: PlatformPath<'a>,
     pub target: Target,
     pub(self) function_kind: FunctionKind,
+    pub(self) tabs: Tabs,
+    pub(self) numeric_defaults: NumericDefaults,
 
     /// Note: only packages and platforms actually expose any modules;
     /// for all others, this will be empty.
@@ -758,6 +781,8 @@ struct State<'a> {
     cached_types: CachedTypeState,
 
     layout_interner: GlobalLayoutInterner<'a>,
+
+    progress: Option<ProgressTracker>,
 }
 
 type CachedTypeState = Arc<Mutex<MutMap<ModuleId, TypeState>>>;
@@ -767,6 +792,14 @@ impl<'a> State<'a> {
         self.exec_mode.goal_phase()
     }
 
+    fn report_progress(&mut self, module_id: ModuleId, phase: ModulePhase, duration: Duration) {
+        if let Some(progress) = self.progress.as_mut() {
+            let module_ids = self.arc_modules.lock();
+
+            progress.finished(module_id, module_ids.get_name(module_id), phase, duration);
+        }
+    }
+
     fn new(
         root_id: ModuleId,
         root_path: PathBuf,
@@ -774,6 +807,8 @@ impl<'a> State<'a> {
         opt_platform_shorthand: Option<&'a str>,
         target: Target,
         function_kind: FunctionKind,
+        tabs: Tabs,
+        numeric_defaults: NumericDefaults,
         exposed_types: ExposedByModule,
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
@@ -783,6 +818,7 @@ impl<'a> State<'a> {
         palette: Palette,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
+        progress: Option<ProgressReporter>,
     ) -> Self {
         let cache_dir = roc_packaging::cache::roc_cache_packages_dir();
         let dependencies = Dependencies::new(exec_mode.goal_phase());
@@ -796,7 +832,10 @@ impl<'a> State<'a> {
             cache_dir,
             target,
             function_kind,
+            tabs,
+            numeric_defaults,
             platform_data: None,
+            platform_hints: PlatformHints::default(),
             platform_path: PlatformPath::NotSpecified,
             module_cache: ModuleCache::default(),
             dependencies,
@@ -822,6 +861,7 @@ impl<'a> State<'a> {
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
             world_abilities: Default::default(),
             layout_interner: GlobalLayoutInterner::with_capacity(128, target),
+            progress: progress.map(ProgressTracker::new),
         }
     }
 }
@@ -880,6 +920,7 @@ enum BuildTask<'a> {
         module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
         shorthands: Arc<Mutex<MutMap<&'a str, ShorthandPath>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
+        tabs: Tabs,
     },
     Parse {
         header: ModuleHeader<'a>,
@@ -899,6 +940,7 @@ enum BuildTask<'a> {
         skip_constraint_gen: bool,
         exec_mode: ExecutionMode,
         imported_module_params: VecMap<ModuleId, ModuleParams>,
+        imported_deprecations: VecMap<Symbol, Box<str>>,
     },
     Solve {
         module: Module,
@@ -909,6 +951,7 @@ enum BuildTask<'a> {
         constraints: Constraints,
         constraint: ConstraintSoa,
         function_kind: FunctionKind,
+        numeric_defaults: NumericDefaults,
         pending_derives: PendingDerives,
         var_store: VarStore,
         declarations: Declarations,
@@ -1066,6 +1109,7 @@ pub fn load_and_typecheck_str<'a>(
         source,
         roc_cache_dir,
         src_dir,
+        Tabs::Reject,
     )?;
 
     // this function is used specifically in the case
@@ -1079,6 +1123,9 @@ pub fn load_and_typecheck_str<'a>(
         threading,
         exec_mode: ExecutionMode::Check,
         function_kind,
+        progress: None,
+        tabs: Tabs::Reject,
+        numeric_defaults: NumericDefaults::Silent,
     };
 
     match load(
@@ -1110,6 +1157,7 @@ pub struct LoadStart<'a> {
     root_type: RootType,
     opt_platform_shorthand: Option<&'a str>,
     src_dir: PathBuf,
+    tabs: Tabs,
 }
 
 #[derive(Debug, Clone)]
@@ -1126,6 +1174,7 @@ impl<'a> LoadStart<'a> {
         render: RenderTarget,
         roc_cache_dir: RocCacheDir<'_>,
         palette: Palette,
+        tabs: Tabs,
     ) -> Result<Self, LoadingProblem<'a>> {
         let arc_modules = Arc::new(Mutex::new(PackageModuleIds::default()));
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
@@ -1146,6 +1195,7 @@ impl<'a> LoadStart<'a> {
             Arc::clone(&ident_ids_by_module),
             roc_cache_dir,
             root_start_time,
+            tabs,
         );
 
         let load_result = match load_result {
@@ -1158,6 +1208,7 @@ impl<'a> LoadStart<'a> {
                 header_output,
                 opt_main_path,
                 &mut src_dir,
+                tabs,
             ),
             Err(problem) => Err(problem),
         };
@@ -1191,6 +1242,7 @@ impl<'a> LoadStart<'a> {
             root_msg: header_output.msg,
             root_type,
             opt_platform_shorthand: header_output.opt_platform_shorthand,
+            tabs,
         })
     }
 
@@ -1201,6 +1253,7 @@ impl<'a> LoadStart<'a> {
         src: &'a str,
         roc_cache_dir: RocCacheDir<'_>,
         mut src_dir: PathBuf,
+        tabs: Tabs,
     ) -> Result<Self, LoadingProblem<'a>> {
         let arc_modules = Arc::new(Mutex::new(PackageModuleIds::default()));
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
@@ -1218,6 +1271,7 @@ impl<'a> LoadStart<'a> {
             Arc::clone(&ident_ids_by_module),
             roc_cache_dir,
             root_start_time,
+            tabs,
         )?;
 
         let (header_output, root_type) = handle_root_type(
@@ -1229,6 +1283,7 @@ impl<'a> LoadStart<'a> {
             header_output,
             opt_main_path,
             &mut src_dir,
+            tabs,
         )?;
 
         let HeaderOutput {
@@ -1247,6 +1302,7 @@ impl<'a> LoadStart<'a> {
             root_msg,
             root_type,
             opt_platform_shorthand: opt_platform_id,
+            tabs,
         })
     }
 }
@@ -1260,6 +1316,7 @@ fn handle_root_type<'a>(
     mut header_output: HeaderOutput<'a>,
     opt_main_path: Option<PathBuf>,
     src_dir: &mut PathBuf,
+    tabs: Tabs,
 ) -> Result<(HeaderOutput<'a>, RootType), LoadingProblem<'a>> {
     if let Msg::Header(ModuleHeader {
         module_id: header_id,
@@ -1290,6 +1347,7 @@ fn handle_root_type<'a>(
                         Arc::clone(&ident_ids_by_module),
                         Arc::clone(&arc_shorthands),
                         cache_dir,
+                        tabs,
                     )?;
 
                     header_output.msg = Msg::Many(messages);
@@ -1330,13 +1388,14 @@ fn load_packages_from_main<'a>(
     ident_ids_by_module: SharedIdentIdsByModule,
     arc_shorthands: Arc<Mutex<MutMap<&'a str, ShorthandPath>>>,
     cache_dir: &Path,
+    tabs: Tabs,
 ) -> Result<(), LoadingProblem<'a>> {
     let src_bytes = fs::read(&filename).map_err(|err| LoadingProblem::FileProblem {
         filename: filename.clone(),
         error: err.kind(),
     })?;
 
-    let parse_state = roc_parse::state::State::new(arena.alloc(src_bytes));
+    let parse_state = roc_parse::state::State::new(arena.alloc(src_bytes)).with_tabs(tabs);
 
     let (parsed_header, _) =
         roc_parse::header::parse_header(arena, parse_state.clone()).map_err(|fail| {
@@ -1365,6 +1424,7 @@ fn load_packages_from_main<'a>(
         None,
         module_ids,
         ident_ids_by_module,
+        tabs,
         filename.clone(),
     );
 
@@ -1394,6 +1454,8 @@ pub enum Threading {
     Single,
     AllAvailable,
     AtMost(usize),
+    /// Use this many threads, even if there are fewer cores.
+    Exactly(usize),
 }
 
 /// The loading process works like this, starting from the given filename (e.g. "main.roc"):
@@ -1465,15 +1527,19 @@ pub fn load<'a>(
             // so we have a single-threaded implementation
             Threads::Single
         } else {
-            match std::thread::available_parallelism().map(|v| v.get()) {
-                Err(_) => Threads::Single,
-                Ok(0) => unreachable!("NonZeroUsize"),
-                Ok(1) => Threads::Single,
-                Ok(reported) => match load_config.threading {
-                    Threading::Single => Threads::Single,
-                    Threading::AllAvailable => Threads::Many(reported),
-                    Threading::AtMost(at_most) => Threads::Many(Ord::min(reported, at_most)),
-                },
+            let reported = std::thread::available_parallelism().map_or(1, |v| v.get());
+
+            let count = match load_config.threading {
+                Threading::Single => 1,
+                Threading::AllAvailable => reported,
+                Threading::AtMost(at_most) => Ord::min(reported, at_most),
+                Threading::Exactly(count) => count,
+            };
+
+            // The multi-threaded implementation needs a main thread plus at least one worker.
+            match count {
+                0 | 1 => Threads::Single,
+                count => Threads::Many(count),
             }
         }
     };
@@ -1485,11 +1551,13 @@ pub fn load<'a>(
             exposed_types,
             load_config.target,
             load_config.function_kind,
+            load_config.numeric_defaults,
             cached_types,
             load_config.render,
             load_config.palette,
             load_config.exec_mode,
             roc_cache_dir,
+            load_config.progress,
         ),
         Threads::Many(threads) => load_multi_threaded(
             arena,
@@ -1497,12 +1565,14 @@ pub fn load<'a>(
             exposed_types,
             load_config.target,
             load_config.function_kind,
+            load_config.numeric_defaults,
             cached_types,
             load_config.render,
             load_config.palette,
             threads,
             load_config.exec_mode,
             roc_cache_dir,
+            load_config.progress,
         ),
     }
 }
@@ -1514,11 +1584,13 @@ pub fn load_single_threaded<'a>(
     exposed_types: ExposedByModule,
     target: Target,
     function_kind: FunctionKind,
+    numeric_defaults: NumericDefaults,
     cached_types: MutMap<ModuleId, TypeState>,
     render: RenderTarget,
     palette: Palette,
     exec_mode: ExecutionMode,
     roc_cache_dir: RocCacheDir<'_>,
+    progress: Option<ProgressReporter>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
         arc_modules,
@@ -1530,6 +1602,7 @@ pub fn load_single_threaded<'a>(
         root_type,
         src_dir,
         opt_platform_shorthand,
+        tabs,
         ..
     } = load_start;
 
@@ -1547,6 +1620,8 @@ pub fn load_single_threaded<'a>(
         opt_platform_shorthand,
         target,
         function_kind,
+        tabs,
+        numeric_defaults,
         exposed_types,
         arc_modules,
         ident_ids_by_module,
@@ -1556,6 +1631,7 @@ pub fn load_single_threaded<'a>(
         palette,
         number_of_workers,
         exec_mode,
+        progress,
     );
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
@@ -1569,6 +1645,9 @@ pub fn load_single_threaded<'a>(
     let stealer = worker.stealer();
     let stealers = &[stealer];
 
+    // reset after every module's canonicalization, so each module reuses the previous one's chunks
+    let mut scratch_arena = Bump::new();
+
     // now we just manually interleave stepping the state "thread" and the worker "thread"
     loop {
         match state_thread_step(arena, state, worker_listeners, &injector, &msg_tx, &msg_rx) {
@@ -1582,7 +1661,15 @@ pub fn load_single_threaded<'a>(
         // then check if the worker can step
         let control_flow =
             roc_worker::worker_task_step(&worker, &injector, stealers, &worker_msg_rx, |task| {
-                run_task(task, arena, &src_dir, msg_tx.clone(), roc_cache_dir, target)
+                run_task(
+                    task,
+                    arena,
+                    &mut scratch_arena,
+                    &src_dir,
+                    msg_tx.clone(),
+                    roc_cache_dir,
+                    target,
+                )
             });
 
         match control_flow {
@@ -1622,6 +1709,10 @@ fn state_thread_step<'a>(
                     // We're done! There should be no more messages pending.
                     debug_assert!(msg_rx.is_empty());
 
+                    if let Some(progress) = &state.progress {
+                        progress.loaded();
+                    }
+
                     let exposed_aliases_by_symbol = exposed_aliases_by_symbol
                         .into_iter()
                         .map(|(k, (_, v))| (k, v))
@@ -1653,6 +1744,10 @@ fn state_thread_step<'a>(
                     // We're done! There should be no more messages pending.
                     debug_assert!(msg_rx.is_empty());
 
+                    if let Some(progress) = &state.progress {
+                        progress.loaded();
+                    }
+
                     let monomorphized = finish_specialization(
                         arena,
                         state,
@@ -1897,12 +1992,14 @@ fn load_multi_threaded<'a>(
     exposed_types: ExposedByModule,
     target: Target,
     function_kind: FunctionKind,
+    numeric_defaults: NumericDefaults,
     cached_types: MutMap<ModuleId, TypeState>,
     render: RenderTarget,
     palette: Palette,
     available_threads: usize,
     exec_mode: ExecutionMode,
     roc_cache_dir: RocCacheDir<'_>,
+    progress: Option<ProgressReporter>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
         arc_modules,
@@ -1914,6 +2011,7 @@ fn load_multi_threaded<'a>(
         root_type,
         src_dir,
         opt_platform_shorthand,
+        tabs,
         ..
     } = load_start;
 
@@ -1946,6 +2044,8 @@ fn load_multi_threaded<'a>(
         opt_platform_shorthand,
         target,
         function_kind,
+        tabs,
+        numeric_defaults,
         exposed_types,
         arc_modules,
         ident_ids_by_module,
@@ -1955,6 +2055,7 @@ fn load_multi_threaded<'a>(
         palette,
         num_workers,
         exec_mode,
+        progress,
     );
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
@@ -2006,11 +2107,15 @@ fn load_multi_threaded<'a>(
                     .builder()
                     .stack_size(EXPANDED_STACK_SIZE)
                     .spawn(move |_| {
+                        // reset after every module this worker canonicalizes
+                        let mut scratch_arena = Bump::new();
+
                         // will process messages until we run out
                         roc_worker::worker_task(worker, injector, stealers, worker_msg_rx, |task| {
                             run_task(
                                 task,
                                 worker_arena,
+                                &mut scratch_arena,
                                 src_dir,
                                 msg_tx.clone(),
                                 roc_cache_dir,
@@ -2229,6 +2334,7 @@ fn update<'a>(
                     config_shorthand,
                     provides,
                     exposes_ids,
+                    hints,
                     ..
                 } => {
                     work.extend(state.dependencies.notify_package(config_shorthand));
@@ -2260,6 +2366,18 @@ fn update<'a>(
                         });
                     }
 
+                    if header.is_root_module
+                        || state.opt_platform_shorthand == Some(config_shorthand)
+                    {
+                        for hint in hints {
+                            if let StrLiteral::PlainLine(text) | StrLiteral::Raw(text) =
+                                hint.value.hint.value
+                            {
+                                state.platform_hints.insert(hint.value.name.value, text);
+                            }
+                        }
+                    }
+
                     if header.is_root_module {
                         state.exposed_modules = exposes_ids;
                     }
@@ -2295,14 +2413,29 @@ fn update<'a>(
                 state.module_cache.module_names.insert(*id, name.clone());
             }
 
+            if let Some(progress) = state.progress.as_mut() {
+                progress.discovered(parsed.deps_by_name.values().copied());
+            }
+
+            let timing = &parsed.module_timing;
+            let mut duration = timing.read_roc_file;
+            duration += timing.parse_header;
+            duration += timing.parse_body;
+            state.report_progress(module_id, ModulePhase::Load, duration);
+
             // This was a dependency. Write it down and keep processing messages.
             let mut exposed_symbols: VecSet<Symbol> = VecSet::with_capacity(parsed.exposes.len());
 
             // TODO can we avoid this loop by storing them as a Set in Header to begin with?
             for symbol in parsed.exposes.iter() {
-                exposed_symbols.insert(*symbol);
+                exposed_symbols.insert(symbol.value);
             }
 
+            state
+                .module_cache
+                .header_exposes
+                .insert(module_id, parsed.exposes.clone());
+
             // NOTE we currently re-parse the headers when a module is imported twice.
             // We need a proper solution that marks a phase as in-progress so it's not repeated
             // debug_assert!(!state.exposed_symbols_by_module.contains_key(&home));
@@ -2383,6 +2516,11 @@ fn update<'a>(
         }) => {
             let module_id = constrained_module.module.module_id;
             log!("generated constraints for {:?}", module_id);
+
+            let timing = &constrained_module.module_timing;
+            let mut duration = timing.canonicalize;
+            duration += timing.constrain;
+            state.report_progress(module_id, ModulePhase::Canonicalize, duration);
             state
                 .module_cache
                 .can_problems
@@ -2414,6 +2552,13 @@ fn update<'a>(
                     .insert(module_id, module_params);
             }
 
+            if !constrained_module.deprecated.is_empty() {
+                state
+                    .module_cache
+                    .deprecated
+                    .insert(module_id, constrained_module.deprecated.clone());
+            }
+
             state
                 .module_cache
                 .constrained
@@ -2445,6 +2590,8 @@ fn update<'a>(
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
 
+            state.report_progress(module_id, ModulePhase::Solve, module_timing.solve);
+
             state
                 .module_cache
                 .type_problems
@@ -2585,6 +2732,20 @@ fn update<'a>(
                         LayoutCache::new(state.layout_interner.fork(), state.target)
                     });
 
+                    if let Some((_, src)) = state.module_cache.sources.get(&module_id) {
+                        let line_info = LineInfo::new(src);
+
+                        for loc_symbol in roc_can::traverse::find_definitions(&decls) {
+                            let line = line_info.convert_pos(loc_symbol.region.start()).line + 1;
+
+                            state
+                                .module_cache
+                                .definition_lines
+                                .entry(loc_symbol.value)
+                                .or_insert(line);
+                        }
+                    }
+
                     let typechecked = TypeCheckedModule {
                         module_id,
                         layout_cache,
@@ -2703,6 +2864,10 @@ fn update<'a>(
 
             log!("made specializations for {:?}", module_id);
 
+            if let Some(duration) = module_timing.make_specializations.last() {
+                state.report_progress(module_id, ModulePhase::Specialize, *duration);
+            }
+
             // in the future, layouts will be in SoA form and we'll want to hold on to this data
             let _ = layout_cache;
 
@@ -3214,6 +3379,7 @@ fn finish_specialization<'a>(
         host_exposed_lambda_sets,
         module_cache,
         platform_data,
+        platform_hints,
         ..
     } = state;
 
@@ -3221,6 +3387,7 @@ fn finish_specialization<'a>(
         type_problems,
         can_problems,
         sources,
+        definition_lines,
         ..
     } = module_cache;
 
@@ -3250,10 +3417,12 @@ fn finish_specialization<'a>(
         host_exposed_lambda_sets,
         entry_point,
         sources,
+        definition_lines,
         timings: state.timings,
         toplevel_expects,
         glue_layouts: GlueLayouts { getters: vec![] },
         uses_prebuilt_platform,
+        platform_hints,
     })
 }
 
@@ -3346,6 +3515,8 @@ fn finish(
         exposed_imports: state.module_cache.exposed_imports,
         imports: state.module_cache.imports,
         exposes: state.module_cache.exposes,
+        header_exposes: state.module_cache.header_exposes,
+        platform_hints: state.platform_hints,
     }
 }
 
@@ -3358,6 +3529,7 @@ fn load_package_from_disk<'a>(
     app_module_id: Option<ModuleId>,
     module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
     ident_ids_by_module: SharedIdentIdsByModule,
+    tabs: Tabs,
 ) -> Result<Msg<'a>, LoadingProblem<'a>> {
     let module_start_time = Instant::now();
     let file_io_start = module_start_time;
@@ -3368,7 +3540,7 @@ fn load_package_from_disk<'a>(
         Ok(bytes_vec) => {
             let parse_start = Instant::now();
             let bytes = arena.alloc(bytes_vec);
-            let parse_state = roc_parse::state::State::new(bytes);
+            let parse_state = roc_parse::state::State::new(bytes).with_tabs(tabs);
             let parsed = roc_parse::header::parse_header(arena, parse_state.clone());
             let parse_header_duration = parse_start.elapsed();
 
@@ -3444,6 +3616,7 @@ fn load_package_from_disk<'a>(
                         None,
                         module_ids,
                         ident_ids_by_module,
+                        tabs,
                         filename,
                     );
 
@@ -3496,6 +3669,7 @@ fn load_package_from_disk<'a>(
                         None,
                         module_ids,
                         ident_ids_by_module,
+                        tabs,
                         filename,
                     );
 
@@ -3607,6 +3781,7 @@ fn load_module<'a>(
     arc_shorthands: Arc<Mutex<MutMap<&'a str, ShorthandPath>>>,
     roc_cache_dir: RocCacheDir<'_>,
     ident_ids_by_module: SharedIdentIdsByModule,
+    tabs: Tabs,
 ) -> Result<HeaderOutput<'a>, LoadingProblem<'a>> {
     let module_start_time = Instant::now();
 
@@ -3667,6 +3842,7 @@ fn load_module<'a>(
         ident_ids_by_module,
         roc_cache_dir,
         module_start_time,
+        tabs,
     )
 }
 
@@ -3804,9 +3980,10 @@ fn parse_header<'a>(
     src_bytes: &'a [u8],
     roc_cache_dir: RocCacheDir<'_>,
     start_time: Instant,
+    tabs: Tabs,
 ) -> Result<HeaderOutput<'a>, LoadingProblem<'a>> {
     let parse_start = Instant::now();
-    let parse_state = roc_parse::state::State::new(src_bytes);
+    let parse_state = roc_parse::state::State::new(src_bytes).with_tabs(tabs);
     let parsed = roc_parse::header::parse_header(arena, parse_state.clone());
     let parse_header_duration = parse_start.elapsed();
 
@@ -3998,6 +4175,7 @@ fn parse_header<'a>(
                 Some(module_id),
                 module_ids,
                 ident_ids_by_module,
+                tabs,
                 filename,
             );
 
@@ -4084,6 +4262,7 @@ fn load_packages<'a>(
     app_module_id: Option<ModuleId>,
     module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
     ident_ids_by_module: SharedIdentIdsByModule,
+    tabs: Tabs,
     #[allow(unused_variables)] // for wasm
     filename: PathBuf,
 ) {
@@ -4147,6 +4326,7 @@ fn load_packages<'a>(
             app_module_id,
             module_ids.clone(),
             ident_ids_by_module.clone(),
+            tabs,
         ) {
             Ok(msg) => {
                 load_messages.push(msg);
@@ -4169,6 +4349,7 @@ fn load_filename<'a>(
     ident_ids_by_module: SharedIdentIdsByModule,
     roc_cache_dir: RocCacheDir<'_>,
     module_start_time: Instant,
+    tabs: Tabs,
 ) -> Result<HeaderOutput<'a>, LoadingProblem<'a>> {
     let file_io_start = Instant::now();
     let file = fs::read(&filename);
@@ -4187,6 +4368,7 @@ fn load_filename<'a>(
             arena.alloc(bytes),
             roc_cache_dir,
             module_start_time,
+            tabs,
         ),
         Err(err) => Err(LoadingProblem::FileProblem {
             filename,
@@ -4205,6 +4387,7 @@ fn load_from_str<'a>(
     ident_ids_by_module: SharedIdentIdsByModule,
     roc_cache_dir: RocCacheDir<'_>,
     module_start_time: Instant,
+    tabs: Tabs,
 ) -> Result<HeaderOutput<'a>, LoadingProblem<'a>> {
     let file_io_start = Instant::now();
     let file_io_duration = file_io_start.elapsed();
@@ -4221,6 +4404,7 @@ fn load_from_str<'a>(
         src.as_bytes(),
         roc_cache_dir,
         module_start_time,
+        tabs,
     )
 }
 
@@ -4314,6 +4498,7 @@ impl<'a> BuildTask<'a> {
         constraints: Constraints,
         constraint: ConstraintSoa,
         function_kind: FunctionKind,
+        numeric_defaults: NumericDefaults,
         pending_derives: PendingDerives,
         var_store: VarStore,
         imported_modules: MutMap<ModuleId, Region>,
@@ -4339,6 +4524,7 @@ impl<'a> BuildTask<'a> {
             constraints,
             constraint,
             function_kind,
+            numeric_defaults,
             pending_derives,
             var_store,
             declarations,
@@ -4780,6 +4966,7 @@ fn run_solve<'a>(
     constraints: Constraints,
     constraint: ConstraintSoa,
     function_kind: FunctionKind,
+    numeric_defaults: NumericDefaults,
     pending_derives: PendingDerives,
     var_store: VarStore,
     decls: Declarations,
@@ -4869,6 +5056,14 @@ fn run_solve<'a>(
         checkmate,
     } = solve_result;
 
+    if !module_id.is_builtin() {
+        problems.extend(find_defaulted_numbers(
+            solved_subs.inner(),
+            &decls,
+            numeric_defaults,
+        ));
+    }
+
     let exposed_types = roc_solve::module::exposed_types_storage_subs(
         module_id,
         &mut solved_subs,
@@ -5006,6 +5201,10 @@ fn build_platform_header<'a>(
         arena,
     );
     let requires_types = unspace(arena, header.requires.item.rigids.items);
+    let hints = match &header.hints {
+        Some(hints) => unspace(arena, hints.item.items),
+        None => &[],
+    };
 
     let header_type = HeaderType::Platform {
         // A config_shorthand of "" should be fine
@@ -5016,6 +5215,7 @@ fn build_platform_header<'a>(
         exposes: exposes.into_bump_slice(),
         requires,
         requires_types,
+        hints,
     };
 
     let info = HeaderInfo {
@@ -5044,6 +5244,7 @@ fn canonicalize_and_constrain<'a>(
     exposed_module_ids: &[ModuleId],
     exec_mode: ExecutionMode,
     imported_module_params: VecMap<ModuleId, ModuleParams>,
+    imported_deprecations: VecMap<Symbol, Box<str>>,
 ) -> CanAndCon {
     let canonicalize_start = Instant::now();
 
@@ -5059,6 +5260,7 @@ fn canonicalize_and_constrain<'a>(
         mut module_timing,
         symbols_from_requires,
         opt_shorthand,
+        parse_warnings,
         ..
     } = parsed;
 
@@ -5087,6 +5289,7 @@ fn canonicalize_and_constrain<'a>(
         &symbols_from_requires,
         &mut var_store,
         opt_shorthand,
+        imported_deprecations,
     );
 
     let mut types = Types::new();
@@ -5233,11 +5436,19 @@ fn canonicalize_and_constrain<'a>(
         module_timing,
         types,
         pending_derives: module_output.pending_derives,
+        deprecated: module_output.deprecated,
     };
 
+    // The parser's warnings get reported along with canonicalization's
+    let mut canonicalization_problems: Vec<_> = parse_warnings
+        .into_iter()
+        .map(roc_problem::can::Problem::ParseWarning)
+        .collect();
+    canonicalization_problems.extend(module_output.problems);
+
     CanAndCon {
         constrained_module,
-        canonicalization_problems: module_output.problems,
+        canonicalization_problems,
         module_docs,
     }
 }
@@ -5258,14 +5469,15 @@ fn parse<'a>(
     let header_import_defs =
         roc_parse::ast::Header::header_imports_to_defs(arena, header.header_imports);
 
-    let parsed_defs = match parse_module_defs(arena, parse_state.clone(), header_import_defs) {
-        Ok(success) => success,
-        Err(fail) => {
-            return Err(LoadingProblem::ParsingFailed(
-                fail.into_file_error(header.module_path, &parse_state),
-            ));
-        }
-    };
+    let (parsed_defs, parse_warnings) =
+        match parse_module_defs_with_warnings(arena, parse_state.clone(), header_import_defs) {
+            Ok(success) => success,
+            Err(fail) => {
+                return Err(LoadingProblem::ParsingFailed(
+                    fail.into_file_error(header.module_path, &parse_state),
+                ));
+            }
+        };
 
     // SAFETY: By this point we've already incrementally verified that there
     // are no UTF-8 errors in these bytes. If there had been any UTF-8 errors,
@@ -5341,7 +5553,7 @@ fn parse<'a>(
         }
     }
 
-    let mut exposed: Vec<Symbol> = Vec::with_capacity(num_exposes);
+    let mut exposed: Vec<Loc<Symbol>> = Vec::with_capacity(num_exposes);
 
     // Make sure the module_ids has ModuleIds for all our deps,
     // then record those ModuleIds in can_module_ids for later.
@@ -5436,7 +5648,7 @@ fn parse<'a>(
             let ident_id = ident_ids.get_or_insert(loc_exposed.value.as_str());
             let symbol = Symbol::new(header.module_id, ident_id);
 
-            exposed.push(symbol);
+            exposed.push(Loc::at(loc_exposed.region, symbol));
         }
 
         // Generate IdentIds entries for all values this module provides,
@@ -5460,7 +5672,7 @@ fn parse<'a>(
                 let ident_id = ident_ids.get_or_insert(loc_name.value.as_str());
                 let symbol = Symbol::new(header.module_id, ident_id);
 
-                exposed.push(symbol);
+                exposed.push(Loc::at(loc_name.region, symbol));
             }
         }
 
@@ -5540,6 +5752,7 @@ fn parse<'a>(
         header_type,
         header_comments: header_docs,
         opt_shorthand: header.opt_shorthand,
+        parse_warnings,
     };
 
     Ok(Msg::Parsed(parsed))
@@ -6222,6 +6435,7 @@ fn load_derived_partial_procs<'a>(
 fn run_task<'a>(
     task: BuildTask<'a>,
     arena: &'a Bump,
+    scratch_arena: &mut Bump,
     src_dir: &Path,
     msg_tx: MsgSender<'a>,
     roc_cache_dir: RocCacheDir<'_>,
@@ -6235,6 +6449,7 @@ fn run_task<'a>(
             module_ids,
             shorthands,
             ident_ids_by_module,
+            tabs,
         } => load_module(
             arena,
             src_dir,
@@ -6243,6 +6458,7 @@ fn run_task<'a>(
             shorthands,
             roc_cache_dir,
             ident_ids_by_module,
+            tabs,
         )
         .map(|HeaderOutput { msg, .. }| msg),
         Parse {
@@ -6270,9 +6486,13 @@ fn run_task<'a>(
             exposed_module_ids,
             exec_mode,
             imported_module_params,
+            imported_deprecations,
         } => {
+            // Nothing canonicalization allocates outlives this task (CanAndCon
+            // owns all of its data), so it can use the scratch arena, which is
+            // then reset for the next module instead of growing `arena`.
             let can_and_con = canonicalize_and_constrain(
-                arena,
+                scratch_arena,
                 &qualified_module_ids,
                 dep_idents,
                 exposed_symbols,
@@ -6283,7 +6503,9 @@ fn run_task<'a>(
                 exposed_module_ids,
                 exec_mode,
                 imported_module_params,
+                imported_deprecations,
             );
+            scratch_arena.reset();
 
             Ok(Msg::CanonicalizedAndConstrained(can_and_con))
         }
@@ -6295,6 +6517,7 @@ fn run_task<'a>(
             constraints,
             constraint,
             function_kind,
+            numeric_defaults,
             pending_derives,
             var_store,
             ident_ids,
@@ -6315,6 +6538,7 @@ fn run_task<'a>(
             constraints,
             constraint,
             function_kind,
+            numeric_defaults,
             pending_derives,
             var_store,
             declarations,
@@ -8,6 +8,9 @@ pub mod docs;
 pub mod file;
 pub mod module;
 mod module_cache;
+pub mod progress;
+pub mod references;
+pub mod rename;
 
 #[cfg(target_family = "wasm")]
 mod wasm_instant;
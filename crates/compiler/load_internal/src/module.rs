@@ -15,7 +15,9 @@ use roc_mono::ir::{GlueLayouts, HostExposedLambdaSets, LambdaSetId, Proc, ProcLa
 use roc_mono::layout::{LayoutCache, STLayoutInterner};
 use roc_parse::ast::{CommentOrNewline, Defs, TypeAnnotation};
 use roc_parse::header::{HeaderType, PackageName};
+use roc_parse::parser::ParseWarning;
 use roc_region::all::{Loc, Region};
+use roc_reporting::report::PlatformHints;
 use roc_solve::module::Solved;
 use roc_solve_problem::TypeError;
 use roc_types::subs::{ExposedTypesStorageSubs, Subs, VarStore, Variable};
@@ -52,6 +54,9 @@ pub struct LoadedModule {
     pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub exposed_imports: MutMap<ModuleId, MutMap<Symbol, Region>>,
     pub exposes: MutMap<ModuleId, Vec<(Symbol, Variable)>>,
+    /// The names in each module header's `exposes` (or a platform's `provides`) list.
+    pub header_exposes: MutMap<ModuleId, Vec<Loc<Symbol>>>,
+    pub platform_hints: PlatformHints,
 }
 
 impl LoadedModule {
@@ -89,6 +94,20 @@ impl LoadedModule {
             .map(|symbol| symbol.as_str(&self.interns))
             .collect()
     }
+
+    /// The canonical declarations of every loaded module, including the root one.
+    pub fn all_declarations(&self) -> impl Iterator<Item = (ModuleId, &Declarations)> {
+        let root = self
+            .declarations_by_id
+            .iter()
+            .map(|(module_id, decls)| (*module_id, decls));
+        let others = self
+            .typechecked
+            .iter()
+            .map(|(module_id, checked)| (*module_id, &checked.decls));
+
+        root.chain(others)
+    }
 }
 
 #[derive(Debug)]
@@ -120,6 +139,8 @@ pub(crate) struct ConstrainedModule {
     // Rather than adding pending derives as constraints, hand them directly to solve because they
     // must be solved at the end of a module.
     pub(crate) pending_derives: PendingDerives,
+    /// Definitions in this module that are marked as deprecated, with their messages.
+    pub(crate) deprecated: VecMap<Symbol, Box<str>>,
 }
 
 #[derive(Debug)]
@@ -184,10 +205,14 @@ pub struct MonomorphizedModule<'a> {
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The line (counting from 1) each value, function, and lambda is defined on in its module's
+    /// source, for debug info.
+    pub definition_lines: MutMap<Symbol, u32>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub expectations: VecMap<ModuleId, Expectations>,
     pub uses_prebuilt_platform: bool,
     pub glue_layouts: GlueLayouts<'a>,
+    pub platform_hints: PlatformHints,
 }
 
 #[derive(Debug)]
@@ -206,8 +231,9 @@ pub struct ParsedModule<'a> {
     pub package_qualified_available_modules: MutSet<PackageQualified<'a, ModuleId>>,
     pub packages: MutMap<&'a str, PackageName<'a>>,
     pub initial_scope: MutMap<Ident, (Symbol, Region)>,
-    pub exposes: Vec<Symbol>,
+    pub exposes: Vec<Loc<Symbol>>,
     pub opt_shorthand: Option<&'a str>,
+    pub parse_warnings: Vec<ParseWarning>,
 }
 
 #[derive(Debug)]
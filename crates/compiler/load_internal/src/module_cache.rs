@@ -10,7 +10,7 @@ use roc_module::ident::ModuleName;
 use roc_module::symbol::{ModuleId, PQModuleName, Symbol};
 use roc_mono::ir::ExternalSpecializations;
 use roc_problem::Severity;
-use roc_region::all::Region;
+use roc_region::all::{Loc, Region};
 use roc_solve_problem::TypeError;
 use roc_types::subs::Variable;
 use roc_types::types::Alias;
@@ -28,6 +28,7 @@ pub(crate) struct ModuleCache<'a> {
     pub(crate) pending_abilities: MutMap<ModuleId, PendingAbilitiesStore>,
     pub(crate) constrained: MutMap<ModuleId, ConstrainedModule>,
     pub(crate) module_params: MutMap<ModuleId, ModuleParams>,
+    pub(crate) deprecated: MutMap<ModuleId, VecMap<Symbol, Box<str>>>,
     pub(crate) typechecked: MutMap<ModuleId, TypeCheckedModule<'a>>,
     pub(crate) checked: MutMap<ModuleId, CheckedModule>,
     pub(crate) found_specializations: MutMap<ModuleId, FoundSpecializationsModule<'a>>,
@@ -39,10 +40,13 @@ pub(crate) struct ModuleCache<'a> {
     pub(crate) imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub(crate) exposes: MutMap<ModuleId, Vec<(Symbol, Variable)>>,
     pub(crate) exposed_imports: MutMap<ModuleId, MutMap<Symbol, Region>>,
+    pub(crate) header_exposes: MutMap<ModuleId, Vec<Loc<Symbol>>>,
     pub(crate) top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
     pub(crate) documentation: VecMap<ModuleId, ModuleDocumentation>,
     pub(crate) can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub(crate) type_problems: MutMap<ModuleId, Vec<TypeError>>,
+    /// The line (counting from 1) each definition in a module being built starts on.
+    pub(crate) definition_lines: MutMap<Symbol, u32>,
 
     pub(crate) sources: MutMap<ModuleId, (PathBuf, &'a str)>,
 }
@@ -104,6 +108,7 @@ impl Default for ModuleCache<'_> {
             pending_abilities: Default::default(),
             constrained: Default::default(),
             module_params: Default::default(),
+            deprecated: Default::default(),
             typechecked: Default::default(),
             checked: Default::default(),
             found_specializations: Default::default(),
@@ -112,10 +117,12 @@ impl Default for ModuleCache<'_> {
             imports: Default::default(),
             exposed_imports: Default::default(),
             exposes: Default::default(),
+            header_exposes: Default::default(),
             top_level_thunks: Default::default(),
             documentation: Default::default(),
             can_problems: Default::default(),
             type_problems: Default::default(),
+            definition_lines: Default::default(),
             sources: Default::default(),
         }
     }
@@ -0,0 +1,163 @@
+//! Progress events for reporting what the compiler is doing while it works, e.g. for
+//! `roc build --verbose` and the progress bar shown when building large programs.
+
+use std::fmt;
+use std::sync::Arc;
+
+use roc_collections::MutSet;
+use roc_module::symbol::{ModuleId, PQModuleName, PackageQualified};
+use roc_reporting::cli::Diagnostic;
+
+#[cfg(target_family = "wasm")]
+use crate::wasm_instant::Duration;
+#[cfg(not(target_family = "wasm"))]
+use std::time::Duration;
+
+/// The per-module phases that get reported. (Builtin modules are never reported.)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModulePhase {
+    /// Reading and parsing the module's file.
+    Load,
+    /// Canonicalization and constraint generation.
+    Canonicalize,
+    Solve,
+    /// Finding and making specializations.
+    Specialize,
+}
+
+impl ModulePhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModulePhase::Load => "loading",
+            ModulePhase::Canonicalize => "canonicalizing",
+            ModulePhase::Solve => "solving",
+            ModulePhase::Specialize => "specializing",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    /// A module finished a phase.
+    Module {
+        module_name: String,
+        phase: ModulePhase,
+        duration: Duration,
+        /// How many modules have finished type checking so far.
+        modules_solved: usize,
+        /// How many modules we know about so far. This grows as imports are discovered.
+        modules_known: usize,
+    },
+    /// All modules are done, and code gen is starting.
+    Loaded,
+    CodeGen {
+        duration: Duration,
+    },
+    Link {
+        duration: Duration,
+    },
+    /// A problem was found. This is only reported by a [`ProgressReporter::with_diagnostics`];
+    /// otherwise, problems are printed.
+    Diagnostic(Diagnostic),
+}
+
+/// Receives [`ProgressEvent`]s. The callback may be called from the loading thread while
+/// other threads are working, so it should be quick.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    callback: Arc<dyn Fn(&ProgressEvent) + Send + Sync>,
+    diagnostics: bool,
+}
+
+impl ProgressReporter {
+    pub fn new(callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Arc::new(callback),
+            diagnostics: false,
+        }
+    }
+
+    /// Like [`ProgressReporter::new`], but problems are passed to the callback as
+    /// [`ProgressEvent::Diagnostic`]s instead of being printed.
+    pub fn with_diagnostics(callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Arc::new(callback),
+            diagnostics: true,
+        }
+    }
+
+    pub fn reports_diagnostics(&self) -> bool {
+        self.diagnostics
+    }
+
+    pub fn report(&self, event: &ProgressEvent) {
+        (self.callback)(event)
+    }
+}
+
+impl fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressReporter")
+    }
+}
+
+/// Keeps the counts that go along with each [`ProgressEvent::Module`].
+#[derive(Debug)]
+pub(crate) struct ProgressTracker {
+    reporter: ProgressReporter,
+    known: MutSet<ModuleId>,
+    solved: usize,
+}
+
+impl ProgressTracker {
+    pub fn new(reporter: ProgressReporter) -> Self {
+        Self {
+            reporter,
+            known: MutSet::default(),
+            solved: 0,
+        }
+    }
+
+    pub fn discovered(&mut self, module_ids: impl IntoIterator<Item = ModuleId>) {
+        self.known
+            .extend(module_ids.into_iter().filter(|id| !id.is_builtin()));
+    }
+
+    pub fn finished(
+        &mut self,
+        module_id: ModuleId,
+        module_name: Option<&PQModuleName>,
+        phase: ModulePhase,
+        duration: Duration,
+    ) {
+        if module_id.is_builtin() {
+            return;
+        }
+
+        self.known.insert(module_id);
+
+        if phase == ModulePhase::Solve {
+            self.solved += 1;
+        }
+
+        let module_name = match module_name {
+            Some(PackageQualified::Unqualified(name)) => name.as_str().to_string(),
+            Some(PackageQualified::Qualified(shorthand, name)) => {
+                format!("{shorthand}.{}", name.as_str())
+            }
+            None => format!("{module_id:?}"),
+        };
+
+        self.reporter.report(&ProgressEvent::Module {
+            module_name,
+            phase,
+            duration,
+            modules_solved: self.solved,
+            modules_known: self.known.len(),
+        });
+    }
+
+    pub fn loaded(&self) {
+        self.reporter.report(&ProgressEvent::Loaded);
+    }
+}
@@ -0,0 +1,57 @@
+//! Finding everywhere a symbol is used across the loaded modules, for editor tooling.
+
+use crate::module::LoadedModule;
+use roc_can::traverse::{find_symbol_occurrences, SymbolOccurrence};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::Region;
+
+/// A place in a module's source that mentions a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolReference {
+    pub module_id: ModuleId,
+    pub region: Region,
+}
+
+/// Returns the region of every use of `symbol` in the loaded modules, qualified (`Dep.foo`) or
+/// not, ordered by module and then by position. When `include_definition` is set, the patterns
+/// that define the symbol are included too.
+pub fn find_references(
+    loaded: &LoadedModule,
+    symbol: Symbol,
+    include_definition: bool,
+) -> Vec<SymbolReference> {
+    let mut references = Vec::new();
+
+    for (module_id, decls) in loaded.all_declarations() {
+        // Only the module that defines a symbol can refer to it without importing it.
+        let can_refer = module_id == symbol.module_id()
+            || loaded
+                .imports
+                .get(&module_id)
+                .map_or(false, |imports| imports.contains(&symbol.module_id()));
+
+        if !can_refer {
+            continue;
+        }
+
+        references.extend(
+            find_symbol_occurrences(symbol, decls)
+                .into_iter()
+                .filter_map(|occurrence| match occurrence {
+                    SymbolOccurrence::Reference(region) => Some(region),
+                    SymbolOccurrence::Definition(region) if include_definition => Some(region),
+                    SymbolOccurrence::Definition(_) | SymbolOccurrence::Signature(_) => None,
+                })
+                .map(|region| SymbolReference { module_id, region }),
+        );
+    }
+
+    references.sort_by_key(|reference| {
+        (
+            loaded.interns.module_name(reference.module_id).to_string(),
+            reference.region,
+        )
+    });
+
+    references
+}
@@ -0,0 +1,158 @@
+//! Working out which parts of the source have to change to rename a symbol, for editor tooling.
+
+use crate::module::LoadedModule;
+use bumpalo::Bump;
+use roc_can::traverse::{find_symbol_occurrences, SymbolOccurrence};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_parse::ident::lowercase_ident;
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_region::all::{Position, Region};
+
+/// A region of a module's source that holds the old name and must be replaced by the new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenameEdit {
+    pub module_id: ModuleId,
+    pub region: Region,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameProblem {
+    /// Builtins are not defined in any loaded source, so they can't be renamed.
+    Builtin,
+    /// Types, abilities and tags can't be renamed yet; only values can.
+    NotAValue,
+    /// The new name isn't a valid name for a value, e.g. because it's uppercase or a keyword.
+    InvalidName,
+    /// The new name is already used in this module, so renaming would shadow or be shadowed.
+    NameTaken(ModuleId),
+}
+
+/// Returns every region that must be replaced with `new_name` to rename `symbol`: its
+/// definition, the name in front of its annotation, every use (qualified or not), and its entries
+/// in `exposes` lists and `import ... exposing [...]` lists, across all loaded modules.
+pub fn rename_edits(
+    loaded: &LoadedModule,
+    symbol: Symbol,
+    new_name: &str,
+) -> Result<Vec<RenameEdit>, RenameProblem> {
+    if symbol.is_builtin() {
+        return Err(RenameProblem::Builtin);
+    }
+
+    let old_name = symbol.as_str(&loaded.interns);
+
+    if !old_name.starts_with(|c: char| c.is_lowercase() || c == '_') {
+        return Err(RenameProblem::NotAValue);
+    }
+
+    if !is_value_name(new_name) {
+        return Err(RenameProblem::InvalidName);
+    }
+
+    if new_name == old_name {
+        return Ok(Vec::new());
+    }
+
+    let mut edits = Vec::new();
+
+    for (module_id, decls) in loaded.all_declarations() {
+        if module_id.is_builtin() {
+            continue;
+        }
+
+        let Some((_, src)) = loaded.sources.get(&module_id) else {
+            continue;
+        };
+
+        let mut regions: Vec<Region> = find_symbol_occurrences(symbol, decls)
+            .into_iter()
+            .filter_map(|occurrence| occurrence_name_region(src, occurrence, old_name))
+            .collect();
+
+        if let Some(region) = loaded
+            .exposed_imports
+            .get(&module_id)
+            .and_then(|exposed| exposed.get(&symbol))
+        {
+            regions.push(*region);
+        }
+
+        if let Some(header_exposes) = loaded.header_exposes.get(&module_id) {
+            regions.extend(
+                header_exposes
+                    .iter()
+                    .filter(|loc_symbol| loc_symbol.value == symbol)
+                    .map(|loc_symbol| loc_symbol.region),
+            );
+        }
+
+        if regions.is_empty() {
+            continue;
+        }
+
+        let name_taken = loaded
+            .interns
+            .all_ident_ids
+            .get(&module_id)
+            .map_or(false, |ident_ids| ident_ids.get_id(new_name).is_some())
+            || loaded
+                .exposed_imports
+                .get(&module_id)
+                .map_or(false, |exposed| {
+                    exposed
+                        .keys()
+                        .any(|imported| imported.as_str(&loaded.interns) == new_name)
+                });
+
+        if name_taken {
+            return Err(RenameProblem::NameTaken(module_id));
+        }
+
+        regions.sort();
+        regions.dedup();
+
+        edits.extend(
+            regions
+                .into_iter()
+                .map(|region| RenameEdit { module_id, region }),
+        );
+    }
+
+    Ok(edits)
+}
+
+fn is_value_name(name: &str) -> bool {
+    let arena = Bump::new();
+
+    let parsed = lowercase_ident().parse(&arena, State::new(name.as_bytes()), 0);
+
+    matches!(parsed, Ok((_, ident, _)) if ident.len() == name.len())
+}
+
+/// Narrows an occurrence down to just the characters of `name`, e.g. the `foo` in `Dep.foo`.
+fn occurrence_name_region(src: &str, occurrence: SymbolOccurrence, name: &str) -> Option<Region> {
+    let region = occurrence.region();
+    let start = region.start().offset as usize;
+    let end = region.end().offset as usize;
+
+    let name_start = match occurrence {
+        SymbolOccurrence::Definition(_) => start + src.get(start..end)?.find(name)?,
+        SymbolOccurrence::Reference(_) => start + src.get(start..end)?.rfind(name)?,
+        SymbolOccurrence::Signature(_) => {
+            // The name is whatever comes right before the colon, as in `foo : Str`.
+            let before = src.get(..start)?.trim_end().strip_suffix(':')?.trim_end();
+
+            if !before.ends_with(name) {
+                return None;
+            }
+
+            before.len() - name.len()
+        }
+    };
+
+    Some(Region::new(
+        Position::new(name_start as u32),
+        Position::new((name_start + name.len()) as u32),
+    ))
+}
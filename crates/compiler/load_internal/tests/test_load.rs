@@ -22,15 +22,19 @@ use roc_load_internal::file::{
     ExecutionMode, LoadConfig, LoadResult, LoadStart, LoadingProblem, Threading,
 };
 use roc_load_internal::module::LoadedModule;
+use roc_load_internal::references::{find_references, SymbolReference};
+use roc_load_internal::rename::{rename_edits, RenameProblem};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_packaging::cache::RocCacheDir;
+use roc_parse::state::Tabs;
 use roc_problem::can::Problem;
 use roc_region::all::LineInfo;
 use roc_reporting::report::{can_problem, DEFAULT_PALETTE};
 use roc_reporting::report::{strip_colors, RenderTarget};
-use roc_reporting::report::{type_problem, RocDocAllocator};
+use roc_reporting::report::{type_problem, PlatformHints, RocDocAllocator};
 use roc_solve::FunctionKind;
+use roc_solve::NumericDefaults;
 use roc_solve_problem::TypeError;
 use roc_target::Target;
 use roc_test_utils_dir::TmpDir;
@@ -45,6 +49,7 @@ fn load_and_typecheck(
     exposed_types: ExposedByModule,
     target: Target,
     function_kind: FunctionKind,
+    numeric_defaults: NumericDefaults,
 ) -> Result<LoadedModule, LoadingProblem> {
     use LoadResult::*;
 
@@ -55,6 +60,7 @@ fn load_and_typecheck(
         RenderTarget::Generic,
         RocCacheDir::Disallowed,
         DEFAULT_PALETTE,
+        Tabs::Reject,
     )?;
     let load_config = LoadConfig {
         target,
@@ -63,6 +69,9 @@ fn load_and_typecheck(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        progress: None,
+        tabs: Tabs::Reject,
+        numeric_defaults,
     };
 
     match roc_load_internal::file::load(
@@ -112,6 +121,7 @@ fn format_type_problems(
     problems: Vec<TypeError>,
     home: ModuleId,
     interns: &Interns,
+    platform_hints: &PlatformHints,
     filename: PathBuf,
     src: &str,
 ) -> String {
@@ -119,7 +129,7 @@ fn format_type_problems(
 
     let src_lines: Vec<&str> = src.split('\n').collect();
     let lines = LineInfo::new(src);
-    let alloc = RocDocAllocator::new(&src_lines, home, interns);
+    let alloc = RocDocAllocator::new(&src_lines, home, interns).with_platform_hints(platform_hints);
     let reports = problems
         .into_iter()
         .flat_map(|problem| type_problem(&alloc, &lines, filename.clone(), problem))
@@ -136,10 +146,18 @@ fn format_type_problems(
 }
 
 fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModule, String> {
+    multiple_modules_with_defaults(subdir, NumericDefaults::Silent, files)
+}
+
+fn multiple_modules_with_defaults(
+    subdir: &str,
+    numeric_defaults: NumericDefaults,
+    files: Vec<(&str, &str)>,
+) -> Result<LoadedModule, String> {
     let arena = Bump::new();
     let arena = &arena;
 
-    match multiple_modules_help(subdir, arena, files) {
+    match multiple_modules_help(subdir, arena, files, numeric_defaults) {
         Err(io_error) => panic!("IO trouble: {io_error:?}"),
         Ok(Err(LoadingProblem::FormattedReport(buf))) => Err(buf),
         Ok(Err(loading_problem)) => Err(format!("{loading_problem:?}")),
@@ -167,6 +185,7 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
                     type_problems,
                     home,
                     &loaded_module.interns,
+                    &loaded_module.platform_hints,
                     filepath.clone(),
                     src,
                 ));
@@ -181,6 +200,7 @@ fn multiple_modules_help<'a>(
     subdir: &str,
     arena: &'a Bump,
     mut files: Vec<(&str, &str)>,
+    numeric_defaults: NumericDefaults,
 ) -> Result<Result<LoadedModule, roc_load_internal::file::LoadingProblem<'a>>, std::io::Error> {
     use std::fs::{self, File};
     use std::io::Write;
@@ -224,6 +244,7 @@ fn multiple_modules_help<'a>(
             Default::default(),
             TARGET,
             FunctionKind::LambdaSet,
+            numeric_defaults,
         )
     };
 
@@ -244,6 +265,7 @@ fn load_fixture(
         subs_by_module,
         TARGET,
         FunctionKind::LambdaSet,
+        NumericDefaults::Silent,
     );
     let mut loaded_module = match loaded {
         Ok(x) => x,
@@ -428,6 +450,7 @@ fn module_with_deps() {
         subs_by_module,
         TARGET,
         FunctionKind::LambdaSet,
+        NumericDefaults::Silent,
     );
 
     let mut loaded_module = loaded.expect("Test module failed to load");
@@ -1353,15 +1376,13 @@ fn ingested_file_import_shadows_symbol() {
             r#"
             ── DUPLICATE NAME in tmp/ingested_import_shadows_symbol/Main.roc ───────────────
 
-            The `name` name is first defined here:
+            The `name` name is defined twice:
 
             3│  name = "Joe"
-                ^^^^
-
-            But then it's defined a second time here:
-
+             │  ---- first defined here
+            4│
             5│  import "name.txt" as name : Str
-                                     ^^^^
+                                     ^^^^ defined again here
 
             Since these variables have the same name, it's easy to use the wrong
             one by accident. Give one of them a new name.
@@ -2031,6 +2052,84 @@ fn import_builtin_in_platform_and_check_app() {
     assert!(result.is_ok(), "should check");
 }
 
+#[test]
+fn platform_hint_for_exposed_function() {
+    let modules = vec![
+        (
+            "platform/main.roc",
+            indoc!(
+                r#"
+                    platform "testplatform"
+                        requires {} { main : Str }
+                        exposes [Chat]
+                        packages {}
+                        imports []
+                        provides [mainForHost]
+                        hints {
+                            Chat.send: "Messages are always text, so use Num.toStr to send a number.",
+                        }
+
+                    mainForHost : Str
+                    mainForHost = main
+                    "#
+            ),
+        ),
+        (
+            "platform/Chat.roc",
+            indoc!(
+                r"
+                    module [send]
+
+                    send : Str -> Str
+                    send = \message -> message
+                    "
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                    app "test"
+                        packages { pf: "platform/main.roc" }
+                        provides [main] to pf
+
+                    import pf.Chat
+
+                    main = Chat.send 42
+                    "#
+            ),
+        ),
+    ];
+
+    let err = multiple_modules("platform_hint_for_exposed_function", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r"
+            ── TYPE MISMATCH in tmp/platform_hint_for_exposed_function/main.roc ────────────
+
+            This 1st argument to `send` has an unexpected type:
+
+            7│  main = Chat.send 42
+                                 ^^
+
+            The argument is a number of type:
+
+                Num *
+
+            But `send` needs its 1st argument to be:
+
+                Str
+
+            Hint: The platform says this about `Chat.send`: Messages are always
+            text, so use Num.toStr to send a number.
+            "
+        ),
+        "\n{}",
+        err
+    );
+}
+
 #[test]
 fn module_cyclic_import_itself() {
     let modules = vec![(
@@ -2178,3 +2277,414 @@ fn roc_file_no_extension() {
 
     assert_eq!(err, expected, "\n{}", err);
 }
+
+#[test]
+fn deprecated_import() {
+    let modules = vec![
+        (
+            "Dep.roc",
+            indoc!(
+                r#"
+            interface Dep exposes [oldName, newName] imports []
+
+            ## Deprecated: Use `newName` instead.
+            oldName = 1
+
+            newName = 1
+            "#
+            ),
+        ),
+        (
+            "Main.roc",
+            indoc!(
+                r#"
+            interface Main exposes [main] imports []
+
+            import Dep exposing [oldName]
+
+            main = Dep.newName + oldName
+            "#
+            ),
+        ),
+    ];
+
+    let err = multiple_modules("deprecated_import", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r"
+            ── DEPRECATED in tmp/deprecated_import/Main.roc ────────────────────────────────
+
+            `Dep.oldName` is deprecated:
+
+            5│  main = Dep.newName + oldName
+                                     ^^^^^^^
+
+            Use `newName` instead.
+            "
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn exposed_but_not_defined_suggestion() {
+    let modules = vec![(
+        "Main.roc",
+        indoc!(
+            r#"
+            module [mian, helper]
+
+            main = 1
+
+            helper = main + 1
+            "#
+        ),
+    )];
+
+    let err = multiple_modules("exposed_but_not_defined_suggestion", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r"
+            ── MISSING DEFINITION in tmp/exposed_but_not_defined_suggestion/Main.roc ───────
+
+            `mian` is listed as exposed, but it isn't defined in this module:
+
+            1│  module [mian, helper]
+                        ^^^^
+
+            These top-level definitions have similar names:
+
+                main
+
+            If you meant one of them, put its name in `exposes` instead. Otherwise,
+            add a definition for `mian`, or remove it from `exposes`.
+            "
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn documented_but_not_exposed() {
+    let modules = vec![(
+        "Main.roc",
+        indoc!(
+            r#"
+            module [main]
+
+            main = helper 1
+
+            ## Adds one.
+            helper = \n -> n + 1
+            "#
+        ),
+    )];
+
+    let err = multiple_modules("documented_but_not_exposed", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r"
+            ── DOCUMENTED BUT NOT EXPOSED in tmp/documented_but_not_exposed/Main.roc ───────
+
+            `helper` has a doc comment, but this module doesn't expose it:
+
+            6│  helper = \n -> n + 1
+                ^^^^^^
+
+            Doc comments describe a module's API, but other modules can't use
+            `helper`. If it's meant to be public, add it to `exposes`. Otherwise, use
+            a regular # comment instead of ##.
+            "
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn explain_numeric_defaults() {
+    let modules = vec![(
+        "Main.roc",
+        indoc!(
+            r#"
+            module [main, increment, limit]
+
+            increment = \n -> n + 1
+
+            limit = 100
+
+            main =
+                total = increment 1 + 2
+                fraction = 0.5 * 3
+
+                "$(Num.toStr total) $(Num.toStr fraction) $(Num.toStr limit)"
+            "#
+        ),
+    )];
+
+    let err = multiple_modules_with_defaults(
+        "explain_numeric_defaults",
+        NumericDefaults::Explain,
+        modules,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r#"
+            ── DEFAULTED NUMBER in tmp/explain_numeric_defaults/Main.roc ───────────────────
+
+            Nothing decides this number's type, so it defaults to `I64`:
+
+            8│      total = increment 1 + 2
+                                      ^
+
+            Integers whose type isn't decided become an `I64` if they fit in one,
+            and fractions become a `Dec`.
+
+            Tip: To pick the type yourself, add a type annotation to a definition
+            that uses this number, or give it a suffix like 5u8.
+
+            ── DEFAULTED NUMBER in tmp/explain_numeric_defaults/Main.roc ───────────────────
+
+            Nothing decides this number's type, so it defaults to `Dec`:
+
+            9│      fraction = 0.5 * 3
+                               ^^^
+
+            Integers whose type isn't decided become an `I64` if they fit in one,
+            and fractions become a `Dec`.
+
+            Tip: To pick the type yourself, add a type annotation to a definition
+            that uses this number, or give it a suffix like 5u8.
+
+            ── DEFAULTED NUMBER in tmp/explain_numeric_defaults/Main.roc ───────────────────
+
+            Nothing decides this number's type, so it defaults to `I64`:
+
+            11│      "$(Num.toStr total) $(Num.toStr fraction) $(Num.toStr limit)"
+                                                                           ^^^^^
+
+            Integers whose type isn't decided become an `I64` if they fit in one,
+            and fractions become a `Dec`.
+
+            Tip: To pick the type yourself, add a type annotation to a definition
+            that uses this number, or give it a suffix like 5u8.
+            "#
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn deny_numeric_defaults() {
+    let modules = vec![(
+        "Main.roc",
+        indoc!(
+            r#"
+            module [main]
+
+            main : Str
+            main =
+                size : U8
+                size = 3
+
+                Num.toStr (size + 1) |> Str.concat (Num.toStr 40)
+            "#
+        ),
+    )];
+
+    let err =
+        multiple_modules_with_defaults("deny_numeric_defaults", NumericDefaults::Deny, modules)
+            .unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r#"
+            ── AMBIGUOUS NUMBER TYPE in tmp/deny_numeric_defaults/Main.roc ─────────────────
+
+            Nothing decides this number's type:
+
+            8│      Num.toStr (size + 1) |> Str.concat (Num.toStr 40)
+                                                                  ^^
+
+            It would have defaulted to `I64`, but defaulting number types is turned
+            off.
+
+            Tip: To pick the type yourself, add a type annotation to a definition
+            that uses this number, or give it a suffix like 5u8.
+            "#
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn rename_edits_across_modules() {
+    let modules = vec![
+        (
+            "Dep.roc",
+            indoc!(
+                r#"
+            interface Dep exposes [oldName, twice] imports []
+
+            oldName : I64
+            oldName = 1
+
+            twice = oldName + oldName
+            "#
+            ),
+        ),
+        (
+            "Main.roc",
+            indoc!(
+                r#"
+            interface Main exposes [main] imports []
+
+            import Dep exposing [oldName]
+
+            main = Dep.oldName + oldName + Dep.twice
+            "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("rename_edits_across_modules", modules).unwrap();
+    let interns = &loaded_module.interns;
+    let dep_id = interns.module_ids.get_id(&"Dep".into()).unwrap();
+    let ident_id = interns
+        .all_ident_ids
+        .get(&dep_id)
+        .unwrap()
+        .get_id("oldName")
+        .unwrap();
+    let symbol = roc_module::symbol::Symbol::new(dep_id, ident_id);
+
+    let mut edits = rename_edits(&loaded_module, symbol, "newName").unwrap();
+    edits.sort_by_key(|edit| (interns.module_name(edit.module_id).to_string(), edit.region));
+
+    let edits: Vec<String> = edits
+        .into_iter()
+        .map(|edit| {
+            let src = &loaded_module.sources[&edit.module_id].1;
+            let start = edit.region.start().offset as usize;
+            let end = edit.region.end().offset as usize;
+            assert_eq!(&src[start..end], "oldName");
+
+            let position = LineInfo::new(src).convert_pos(edit.region.start());
+            format!(
+                "{} {}:{}",
+                interns.module_name(edit.module_id),
+                position.line + 1,
+                position.column + 1
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        edits,
+        vec![
+            "Dep 1:24",
+            "Dep 3:1",
+            "Dep 4:1",
+            "Dep 6:9",
+            "Dep 6:19",
+            "Main 3:22",
+            "Main 5:12",
+            "Main 5:22",
+        ]
+    );
+
+    assert_eq!(
+        rename_edits(&loaded_module, symbol, "twice"),
+        Err(RenameProblem::NameTaken(dep_id))
+    );
+    assert_eq!(
+        rename_edits(&loaded_module, symbol, "when"),
+        Err(RenameProblem::InvalidName)
+    );
+}
+
+#[test]
+fn find_references_across_modules() {
+    let modules = vec![
+        (
+            "Dep.roc",
+            indoc!(
+                r#"
+            interface Dep exposes [helper, twice] imports []
+
+            helper = \n -> n + 1
+
+            twice = \n -> helper (helper n)
+            "#
+            ),
+        ),
+        (
+            "Main.roc",
+            indoc!(
+                r#"
+            interface Main exposes [main] imports []
+
+            import Dep
+
+            main =
+                incr = Dep.helper
+                incr (Dep.twice 1)
+            "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("find_references_across_modules", modules).unwrap();
+    let interns = &loaded_module.interns;
+    let dep_id = interns.module_ids.get_id(&"Dep".into()).unwrap();
+    let ident_id = interns
+        .all_ident_ids
+        .get(&dep_id)
+        .unwrap()
+        .get_id("helper")
+        .unwrap();
+    let symbol = roc_module::symbol::Symbol::new(dep_id, ident_id);
+
+    let show = |references: Vec<SymbolReference>| -> Vec<String> {
+        references
+            .into_iter()
+            .map(|reference| {
+                let src = &loaded_module.sources[&reference.module_id].1;
+                let start = reference.region.start().offset as usize;
+                let end = reference.region.end().offset as usize;
+                let position = LineInfo::new(src).convert_pos(reference.region.start());
+
+                format!(
+                    "{} {}:{} {}",
+                    interns.module_name(reference.module_id),
+                    position.line + 1,
+                    position.column + 1,
+                    &src[start..end]
+                )
+            })
+            .collect()
+    };
+
+    assert_eq!(
+        show(find_references(&loaded_module, symbol, false)),
+        vec!["Dep 5:15 helper", "Dep 5:23 helper", "Main 6:12 Dep.helper"]
+    );
+    assert_eq!(
+        show(find_references(&loaded_module, symbol, true)),
+        vec![
+            "Dep 3:1 helper",
+            "Dep 5:15 helper",
+            "Dep 5:23 helper",
+            "Main 6:12 Dep.helper"
+        ]
+    );
+}
@@ -76,7 +76,7 @@ pub fn remove_module_param_arguments(
 
             // Irrelevant
             TypeError::BadPattern(_, _, _, _)
-            | TypeError::CircularType(_, _, _)
+            | TypeError::CircularType(_, _, _, _)
             | TypeError::CircularDef(_)
             | TypeError::UnexposedLookup(_, _)
             | TypeError::UnfulfilledAbility(_)
@@ -99,7 +99,12 @@ pub fn remove_module_param_arguments(
             | TypeError::IngestedFileUnsupportedType(_, _)
             | TypeError::UnexpectedModuleParams(_, _)
             | TypeError::MissingModuleParams(_, _, _)
-            | TypeError::ModuleParamsMismatch(_, _, _, _) => {}
+            | TypeError::ModuleParamsMismatch(_, _, _, _)
+            | TypeError::DefaultedNumber {
+                region: _,
+                default: _,
+                is_error: _,
+            } => {}
         }
     }
 }
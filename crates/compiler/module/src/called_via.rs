@@ -159,11 +159,19 @@ pub enum BinOp {
 impl BinOp {
     /// how wide this operator is when typed out
     pub fn width(self) -> u16 {
-        match self {
-            Caret | Star | Slash | Percent | Plus | Minus | LessThan | GreaterThan => 1,
-            DoubleSlash | Equals | NotEquals | LessThanOrEq | GreaterThanOrEq | And | Or
-            | Pizza => 2,
-        }
+        self.as_str().len() as u16
+    }
+
+    /// The operator as it's typed out, e.g. `"|>"`
+    pub fn as_str(self) -> &'static str {
+        const DISPLAY_TABLE: [&str; 16] = generate_display_table();
+
+        DISPLAY_TABLE[self as usize]
+    }
+
+    /// Every binary operator, from the one with the highest precedence to the one with the lowest
+    pub fn iter() -> impl Iterator<Item = BinOp> {
+        PRECEDENCES.iter().map(|(op, _)| *op)
     }
 }
 
@@ -201,7 +209,10 @@ impl BinOp {
         ASSOCIATIVITY_TABLE[self as usize]
     }
 
-    fn precedence(self) -> u8 {
+    /// How tightly this operator binds. Operators with higher precedence get applied first,
+    /// so `a + b * c` means `a + (b * c)`. Operators with the same precedence are grouped
+    /// according to their associativity.
+    pub fn precedence(self) -> u8 {
         const PRECEDENCE_TABLE: [u8; 16] = generate_precedence_table();
 
         PRECEDENCE_TABLE[self as usize]
@@ -222,9 +233,19 @@ impl Ord for BinOp {
 
 impl std::fmt::Display for BinOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        const DISPLAY_TABLE: [&str; 16] = generate_display_table();
+        write!(f, "{}", self.as_str())
+    }
+}
 
-        write!(f, "{}", DISPLAY_TABLE[*self as usize])
+impl std::str::FromStr for BinOp {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DISPLAY_STRINGS
+            .iter()
+            .find(|(_, string)| *string == s)
+            .map(|(op, _)| *op)
+            .ok_or(())
     }
 }
 
@@ -288,4 +309,21 @@ mod tests {
     fn indices_are_correct_in_display_string() {
         index_is_binop_u8(DISPLAY_STRINGS.iter().map(|(op, _)| *op), "DISPLAY_STRINGS")
     }
+
+    #[test]
+    fn iter_goes_from_highest_to_lowest_precedence() {
+        let ops: Vec<BinOp> = BinOp::iter().collect();
+
+        assert_eq!(ops.len(), PRECEDENCES.len());
+        assert!(ops.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn display_strings_parse_back() {
+        for op in BinOp::iter() {
+            assert_eq!(op.as_str().parse(), Ok(op));
+        }
+
+        assert_eq!("<|".parse::<BinOp>(), Err(()));
+    }
 }
@@ -126,6 +126,10 @@ macro_rules! return_on_layout_error_help {
 pub enum OptLevel {
     Development,
     Normal,
+    /// Only cheap optimizations (`-O1`).
+    Less,
+    /// Most optimizations, but less inlining than `Optimize` (`-O2`).
+    Moderate,
     Size,
     Optimize,
 }
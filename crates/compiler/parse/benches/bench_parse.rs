@@ -5,6 +5,7 @@ use roc_parse::{
     header::{self, parse_module_defs},
     state::State,
 };
+use std::fmt::Write;
 use std::path::PathBuf;
 
 pub fn parse_benchmark(c: &mut Criterion) {
@@ -48,6 +49,77 @@ pub fn parse_benchmark(c: &mut Criterion) {
             black_box(res.len());
         })
     });
+
+    for num_defs in [1_000, 10_000] {
+        let src = generate_module(num_defs);
+
+        c.bench_function(&format!("parse generated module ({num_defs} defs)"), |b| {
+            b.iter(|| {
+                let arena = Bump::new();
+
+                black_box(parse_module(&arena, &src));
+            })
+        });
+
+        // The same work, but resetting one arena instead of making a new one
+        // each time, the way the builtins are loaded by roc_load's build script.
+        c.bench_function(
+            &format!("parse generated module ({num_defs} defs, reused arena)"),
+            |b| {
+                let mut arena = Bump::new();
+
+                b.iter(|| {
+                    black_box(parse_module(&arena, &src));
+
+                    arena.reset();
+                })
+            },
+        );
+    }
+}
+
+fn parse_module(arena: &Bump, src: &str) -> usize {
+    let (_actual, state) = header::parse_header(arena, State::new(src.as_bytes())).unwrap();
+
+    parse_module_defs(arena, state, Defs::default())
+        .unwrap()
+        .len()
+}
+
+/// A module with `num_defs` top-level defs, cycling through the kinds of
+/// expressions that show up in real code.
+fn generate_module(num_defs: usize) -> String {
+    let mut src = String::from("module [main]\n\nmain = def0\n");
+
+    for i in 0..num_defs {
+        let next = i + 1;
+
+        match i % 5 {
+            0 => write!(
+                src,
+                "\ndef{i} : {{ name : Str, count : U64 }}\ndef{i} = {{ name: \"def {i}\", count: {i} }}\n"
+            ),
+            1 => write!(
+                src,
+                "\ndef{i} = \\x ->\n    when x is\n        Ok n if n > {i} -> n + {next}\n        Ok n -> n * 2\n        Err _ -> 0\n"
+            ),
+            2 => write!(
+                src,
+                "\ndef{i} = [{i}, {next}, {i} + {next}] |> List.map \\n -> n * 2\n"
+            ),
+            3 => write!(
+                src,
+                "\ndef{i} =\n    a = \"$(Num.toStr {i})\"\n    b = Str.concat a \"!\"\n\n    (a, b)\n"
+            ),
+            _ => write!(
+                src,
+                "\ndef{i} = if def{next}.count == 0 then Red else Custom {i} \"color\"\n"
+            ),
+        }
+        .unwrap();
+    }
+
+    src
 }
 
 criterion_group!(benches, parse_benchmark);
@@ -388,6 +388,9 @@ pub enum StrLiteral<'a> {
     PlainLine(&'a str),
     Line(&'a [StrSegment<'a>]),
     Block(&'a [&'a [StrSegment<'a>]]),
+    /// A raw string, like `r#"C:\Users"#`, which has no escapes or interpolations. This is the
+    /// text between the quotes, which can have newlines in it.
+    Raw(&'a str),
 }
 
 /// Values that can be tried, extracting success values or "returning early" on failure
@@ -2501,7 +2504,7 @@ impl<'a, T: Malformed> Malformed for Collection<'a, T> {
 impl<'a> Malformed for StrLiteral<'a> {
     fn is_malformed(&self) -> bool {
         match self {
-            StrLiteral::PlainLine(_) => false,
+            StrLiteral::PlainLine(_) | StrLiteral::Raw(_) => false,
             StrLiteral::Line(segs) => segs.iter().any(|seg| seg.is_malformed()),
             StrLiteral::Block(lines) => lines
                 .iter()
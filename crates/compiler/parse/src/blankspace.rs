@@ -4,7 +4,7 @@ use crate::parser::succeed;
 use crate::parser::Progress;
 use crate::parser::SpaceProblem;
 use crate::parser::{self, and, backtrackable, BadInputError, Parser, Progress::*};
-use crate::state::State;
+use crate::state::{State, Tabs};
 use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
 use roc_region::all::Loc;
@@ -344,6 +344,25 @@ where
     }
 }
 
+/// A `,` between arguments, but not a trailing one right before the `->`, like the last one in
+/// `\a, b, -> a` or `A, B, -> C`. Those are left for the caller to skip.
+pub fn argument_comma<'a, E>(to_error: fn(Position) -> E) -> impl Parser<'a, (), E>
+where
+    E: 'a + SpaceProblem,
+{
+    move |arena: &'a Bump, state: State<'a>, min_indent| {
+        let start = state.pos();
+        let (_, (), after_comma) = parser::byte(b',', to_error).parse(arena, state, min_indent)?;
+
+        match spaces::<E>().parse(arena, after_comma.clone(), min_indent) {
+            Ok((_, _, after_spaces)) if after_spaces.bytes().starts_with(b"->") => {
+                Err((NoProgress, to_error(start)))
+            }
+            _ => Ok((MadeProgress, (), after_comma)),
+        }
+    }
+}
+
 pub fn require_newline_or_eof<'a, E>(newline_problem: fn(Position) -> E) -> impl Parser<'a, (), E>
 where
     E: 'a + SpaceProblem,
@@ -465,7 +484,15 @@ where
                     }
                 }
 
-                let len = fast_eat_until_control_character(state.bytes());
+                let mut len = fast_eat_until_control_character(state.bytes());
+
+                // Tabs in a comment can't change the column of anything we parse, since the
+                // comment runs to the end of the line.
+                if state.tabs() != Tabs::Reject {
+                    while state.bytes().get(len) == Some(&b'\t') {
+                        len += 1 + fast_eat_until_control_character(&state.bytes()[len + 1..]);
+                    }
+                }
 
                 // We already checked that the string is valid UTF-8
                 debug_assert!(std::str::from_utf8(&state.bytes()[..len]).is_ok());
@@ -509,12 +536,18 @@ where
                 found_newline = true;
                 progress = MadeProgress;
             }
-            Some(b'\t') => {
-                return Err((
-                    progress,
-                    E::space_problem(BadInputError::HasTab, state.pos()),
-                ));
-            }
+            Some(b'\t') => match state.tabs() {
+                Tabs::Reject => {
+                    return Err((
+                        progress,
+                        E::space_problem(BadInputError::HasTab, state.pos()),
+                    ));
+                }
+                Tabs::Expand { width } => {
+                    state.advance_tab_mut(width);
+                    progress = MadeProgress;
+                }
+            },
             Some(x) if *x < b' ' => {
                 return Err((
                     progress,
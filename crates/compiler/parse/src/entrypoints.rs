@@ -0,0 +1,53 @@
+//! Parsing a whole expression, list of defs, or type annotation from source code. These are the
+//! parser's stable entry points, for things like the REPL, editors, and other tools; the parsers
+//! they're built from change shape whenever the syntax does.
+use bumpalo::Bump;
+use roc_region::all::{Loc, Position};
+
+use crate::ast::{Defs, Expr, TypeAnnotation};
+use crate::blankspace::space0_around_ee;
+use crate::header::parse_module_defs;
+use crate::parser::{EType, Parser, SourceError, SyntaxError};
+use crate::state::State;
+use crate::{expr, type_annotation};
+
+/// The problem with a parse that failed, and the source it was in, so it can be reported.
+pub type ParseError<'a> = SourceError<'a, SyntaxError<'a>>;
+
+/// Parses `src` as a single expression, like a REPL entry or the body of a def. It can start
+/// with defs (`x = 1` and so on), as long as it ends with an expression.
+pub fn parse_expr<'a>(arena: &'a Bump, src: &'a str) -> Result<Loc<Expr<'a>>, ParseError<'a>> {
+    let state = State::new(src.as_bytes());
+
+    expr::test_parse_expr(0, arena, state.clone())
+        .map_err(|fail| SyntaxError::Expr(fail, Position::default()).into_source_error(&state))
+}
+
+/// Parses `src` as the defs of a module, without a header.
+pub fn parse_defs<'a>(arena: &'a Bump, src: &'a str) -> Result<Defs<'a>, ParseError<'a>> {
+    let state = State::new(src.as_bytes());
+
+    parse_module_defs(arena, state.clone(), Defs::default())
+        .map_err(|fail| fail.into_source_error(&state))
+}
+
+/// Parses `src` as a type annotation, like `List Str -> Task {} []`.
+pub fn parse_type_annotation<'a>(
+    arena: &'a Bump,
+    src: &'a str,
+) -> Result<Loc<TypeAnnotation<'a>>, ParseError<'a>> {
+    let state = State::new(src.as_bytes());
+    let parser = space0_around_ee(
+        type_annotation::located(false),
+        EType::TIndentStart,
+        EType::TIndentEnd,
+    );
+
+    let result = match parser.parse(arena, state.clone(), 0) {
+        Ok((_, annotation, rest)) if rest.has_reached_end() => Ok(annotation),
+        Ok((_, _, rest)) => Err(EType::TEnd(rest.pos())),
+        Err((_, fail)) => Err(fail),
+    };
+
+    result.map_err(|fail| SyntaxError::Type(fail).into_source_error(&state))
+}
@@ -6,20 +6,21 @@ use crate::ast::{
     TypeAnnotation, TypeDef, TypeHeader, ValueDef,
 };
 use crate::blankspace::{
-    loc_space0_e, require_newline_or_eof, space0_after_e, space0_around_ee, space0_before_e,
-    space0_before_optional_after, space0_e, spaces, spaces_around, spaces_before,
+    argument_comma, loc_space0_e, require_newline_or_eof, space0_after_e, space0_around_ee,
+    space0_before_e, space0_before_optional_after, space0_e, spaces, spaces_around, spaces_before,
 };
 use crate::header::module_name_help;
 use crate::ident::{
     integer_ident, lowercase_ident, parse_ident, unqualified_ident, Accessor, Ident, Suffix,
 };
 use crate::parser::{
-    self, and, backtrackable, between, byte, byte_indent, collection_inner,
-    collection_trailing_sep_e, either, increment_min_indent, indented_seq_skip_first, loc, map,
-    map_with_arena, optional, reset_min_indent, sep_by1, sep_by1_e, set_min_indent, skip_first,
-    skip_second, specialize_err, specialize_err_ref, then, two_bytes, zero_or_more, EClosure,
-    EExpect, EExpr, EIf, EImport, EImportParams, EInParens, EList, ENumber, EPattern, ERecord,
-    EString, EType, EWhen, Either, ParseResult, Parser, SpaceProblem,
+    self, and, backtrackable, byte, byte_indent, collection_inner,
+    collection_trailing_comma_expected, collection_trailing_sep_e, either, increment_min_indent,
+    indented_seq_skip_first, loc, map, map_with_arena, optional, reset_min_indent, sep_by1,
+    sep_by1_e, set_min_indent, skip_first, skip_second, specialize_err, specialize_err_ref, then,
+    two_bytes, zero_or_more, EClosure, EExpect, EExpr, EIf, EImport, EImportParams, EInParens,
+    EList, ENumber, EPattern, ERecord, EString, EType, EWhen, Either, Expected, ParseResult,
+    Parser, SpaceProblem,
 };
 use crate::pattern::closure_param;
 use crate::state::State;
@@ -86,15 +87,16 @@ pub fn expr_help<'a>() -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
 
 fn loc_expr_in_parens_help<'a>() -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
     then(
-        loc(collection_trailing_sep_e(
+        loc(collection_trailing_comma_expected(
             byte(b'(', EInParens::Open),
             specialize_err_ref(
                 EInParens::Expr,
                 // space0_before_e(
                 loc_expr_block(false),
             ),
-            byte(b',', EInParens::End),
-            byte(b')', EInParens::End),
+            Expected::EXPR,
+            b')',
+            EInParens::End,
             Expr::SpaceBefore,
         )),
         move |arena, state, _, loc_elements| {
@@ -2279,10 +2281,10 @@ pub fn parse_top_level_defs<'a>(
         stmts_to_defs(&stmts, output, false, arena).map_err(|e| (MadeProgress, e))?;
 
     if let Some(expr) = last_expr {
-        return Err((
-            MadeProgress,
-            EExpr::UnexpectedTopLevelExpr(expr.region.start()),
-        ));
+        let problem =
+            keyword_near_miss(&expr).unwrap_or(EExpr::UnexpectedTopLevelExpr(expr.region.start()));
+
+        return Err((MadeProgress, problem));
     }
 
     if output.tags.len() > existing_len {
@@ -2308,9 +2310,9 @@ fn closure_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EClo
             // It may turn out to be malformed, but it is definitely a closure.
             and(
                 // Parse the params
-                // Params are comma-separated
+                // Params are comma-separated, and the last one can have a comma after it too
                 sep_by1_e(
-                    byte(b',', EClosure::Comma),
+                    argument_comma(EClosure::Comma),
                     space0_around_ee(
                         specialize_err(EClosure::Pattern, closure_param()),
                         EClosure::IndentArg,
@@ -2318,16 +2320,30 @@ fn closure_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EClo
                     ),
                     EClosure::Arg,
                 ),
-                skip_first(
-                    // Parse the -> which separates params from body
-                    two_bytes(b'-', b'>', EClosure::Arrow),
-                    // Parse the body
-                    block(options, true, EClosure::IndentBody, EClosure::Body),
+                and(
+                    skip_first(
+                        optional(byte(b',', EClosure::Comma)),
+                        space0_e(EClosure::IndentArrow),
+                    ),
+                    skip_first(
+                        // Parse the -> which separates params from body
+                        two_bytes(b'-', b'>', EClosure::Arrow),
+                        // Parse the body
+                        block(options, true, EClosure::IndentBody, EClosure::Body),
+                    ),
                 ),
             ),
         ),
-        |arena: &'a Bump, (params, body)| {
-            let params: Vec<'a, Loc<Pattern<'a>>> = params;
+        |arena: &'a Bump, (params, (spaces_before_arrow, body))| {
+            let mut params: Vec<'a, Loc<Pattern<'a>>> = params;
+
+            // Keep any comments after a trailing comma
+            if !spaces_before_arrow.is_empty() {
+                if let Some(last) = params.last_mut() {
+                    last.value = arena.alloc(last.value).after(spaces_before_arrow);
+                }
+            }
+
             let params: &'a [Loc<Pattern<'a>>] = params.into_bump_slice();
             Expr::Closure(params, arena.alloc(body))
         },
@@ -2952,10 +2968,16 @@ fn parse_stmt_seq<'a, E: SpaceProblem + 'a>(
                         break;
                     }
 
-                    return Err((
-                        MadeProgress,
-                        wrap_error(arena.alloc(EExpr::BadExprEnd(state.pos())), state.pos()),
-                    ));
+                    let problem = match stmts.last().map(|stmt| stmt.item) {
+                        Some(Loc {
+                            region,
+                            value: Stmt::Expr(expr),
+                        }) => keyword_near_miss(&Loc::at(region, expr)),
+                        _ => None,
+                    }
+                    .unwrap_or(EExpr::BadExprEnd(state.pos()));
+
+                    return Err((MadeProgress, wrap_error(arena.alloc(problem), state.pos())));
                 }
                 last_space = s_loc;
                 state = new_state;
@@ -2968,6 +2990,25 @@ fn parse_stmt_seq<'a, E: SpaceProblem + 'a>(
     Ok((MadeProgress, stmts, state))
 }
 
+/// When an expression statement starts with what looks like a misspelled keyword, like the `whn`
+/// in `whn x is`, its syntax error is probably because of that.
+fn keyword_near_miss<'a>(expr: &Loc<Expr<'a>>) -> Option<EExpr<'a>> {
+    match expr.value {
+        Expr::Var {
+            module_name: "",
+            ident,
+        } => keyword::near_miss(ident).map(|keyword| EExpr::KeywordNearMiss(keyword, expr.region)),
+        Expr::SpaceBefore(inner, _) => keyword_near_miss(&Loc::at(expr.region, *inner)),
+        Expr::Apply(func, _, _) => keyword_near_miss(func),
+        Expr::BinOps(lefts, _) => keyword_near_miss(&lefts.first()?.0),
+        _ => None,
+    }
+}
+
+fn stmt_after_expr<'a>(last_expr: &Loc<Expr<'a>>, stmt_start: Position) -> EExpr<'a> {
+    keyword_near_miss(last_expr).unwrap_or(EExpr::StmtAfterExpr(stmt_start))
+}
+
 /// Check if the current byte is a terminator for a sequence of statements
 fn at_terminator(state: &State<'_>) -> bool {
     matches!(
@@ -3069,8 +3110,8 @@ fn stmts_to_defs<'a>(
                         &[],
                     );
                 } else {
-                    if last_expr.is_some() {
-                        return Err(EExpr::StmtAfterExpr(sp_stmt.item.region.start()));
+                    if let Some(last_expr) = &last_expr {
+                        return Err(stmt_after_expr(last_expr, sp_stmt.item.region.start()));
                     }
 
                     let e = if sp_stmt.before.is_empty() {
@@ -3083,8 +3124,8 @@ fn stmts_to_defs<'a>(
                 }
             }
             Stmt::Backpassing(pats, call) => {
-                if last_expr.is_some() {
-                    return Err(EExpr::StmtAfterExpr(sp_stmt.item.region.start()));
+                if let Some(last_expr) = &last_expr {
+                    return Err(stmt_after_expr(last_expr, sp_stmt.item.region.start()));
                 }
 
                 if i + 1 >= stmts.len() {
@@ -3110,8 +3151,8 @@ fn stmts_to_defs<'a>(
             }
 
             Stmt::TypeDef(td) => {
-                if last_expr.is_some() {
-                    return Err(EExpr::StmtAfterExpr(sp_stmt.item.region.start()));
+                if let Some(last_expr) = &last_expr {
+                    return Err(stmt_after_expr(last_expr, sp_stmt.item.region.start()));
                 }
 
                 if let (
@@ -3165,8 +3206,8 @@ fn stmts_to_defs<'a>(
                 }
             }
             Stmt::ValueDef(vd) => {
-                if last_expr.is_some() {
-                    return Err(EExpr::StmtAfterExpr(sp_stmt.item.region.start()));
+                if let Some(last_expr) = &last_expr {
+                    return Err(stmt_after_expr(last_expr, sp_stmt.item.region.start()));
                 }
 
                 // NOTE: it shouldn't be necessary to convert ValueDef::Dbg into an expr, but
@@ -3360,11 +3401,12 @@ fn ident_to_expr<'a>(arena: &'a Bump, src: Ident<'a>) -> Expr<'a> {
 
 fn list_literal_help<'a>() -> impl Parser<'a, Expr<'a>, EList<'a>> {
     map_with_arena(
-        collection_trailing_sep_e(
+        collection_trailing_comma_expected(
             byte(b'[', EList::Open),
             specialize_err_ref(EList::Expr, loc_expr(false)),
-            byte(b',', EList::End),
-            byte(b']', EList::End),
+            Expected::EXPR,
+            b']',
+            EList::End,
             Expr::SpaceBefore,
         ),
         |arena, elements: Collection<'a, _>| {
@@ -3389,6 +3431,20 @@ pub enum RecordField<'a> {
 pub struct FoundApplyValue;
 
 impl<'a> RecordField<'a> {
+    fn is_label_only(&self) -> bool {
+        let mut current = self;
+
+        loop {
+            match current {
+                RecordField::LabelOnly(_) => break true,
+                RecordField::SpaceBefore(field, _) | RecordField::SpaceAfter(field, _) => {
+                    current = *field;
+                }
+                _ => break false,
+            }
+        }
+    }
+
     fn is_ignored_value(&self) -> bool {
         let mut current = self;
 
@@ -3547,35 +3603,66 @@ struct RecordHelp<'a> {
 }
 
 fn record_help<'a>() -> impl Parser<'a, RecordHelp<'a>, ERecord<'a>> {
-    between(
+    let record = reset_min_indent(record!(RecordHelp {
+        // You can optionally have an identifier followed by an '&' to
+        // make this a record update, e.g. { Foo.user & username: "blah" }.
+        prefix: optional(backtrackable(and(
+            // We wrap the ident in an Expr here,
+            // so that we have a Spaceable value to work with,
+            // and then in canonicalization verify that it's an Expr::Var
+            // (and not e.g. an `Expr::Access`) and extract its string.
+            spaces_around(loc(record_prefix_identifier())),
+            map_with_arena(
+                either(
+                    byte(b'&', ERecord::Ampersand),
+                    two_bytes(b'<', b'-', ERecord::Arrow),
+                ),
+                |_arena, output| match output {
+                    Either::First(()) => RecordHelpPrefix::Update,
+                    Either::Second(()) => RecordHelpPrefix::Mapper,
+                }
+            )
+        ))),
+        fields: collection_inner(
+            loc(record_field()),
+            byte(b',', |pos| ERecord::End(
+                Expected::COMMA.or(Expected::CLOSE_CURLY),
+                pos
+            )),
+            RecordField::SpaceBefore
+        ),
+    }));
+
+    skip_first(
         byte(b'{', ERecord::Open),
-        reset_min_indent(record!(RecordHelp {
-            // You can optionally have an identifier followed by an '&' to
-            // make this a record update, e.g. { Foo.user & username: "blah" }.
-            prefix: optional(backtrackable(and(
-                // We wrap the ident in an Expr here,
-                // so that we have a Spaceable value to work with,
-                // and then in canonicalization verify that it's an Expr::Var
-                // (and not e.g. an `Expr::Access`) and extract its string.
-                spaces_around(loc(record_prefix_identifier())),
-                map_with_arena(
-                    either(
-                        byte(b'&', ERecord::Ampersand),
-                        two_bytes(b'<', b'-', ERecord::Arrow),
-                    ),
-                    |_arena, output| match output {
-                        Either::First(()) => RecordHelpPrefix::Update,
-                        Either::Second(()) => RecordHelpPrefix::Mapper,
-                    }
-                )
-            ))),
-            fields: collection_inner(
-                loc(record_field()),
-                byte(b',', ERecord::End),
-                RecordField::SpaceBefore
-            ),
-        })),
-        byte(b'}', ERecord::End),
+        move |arena, state: State<'a>, min_indent: u32| {
+            let (progress, record, state) = record.parse(arena, state, min_indent)?;
+
+            if state.bytes().first() == Some(&b'}') {
+                Ok((MadeProgress, record, state.advance(1)))
+            } else {
+                let mut expected = Expected::in_collection(
+                    record.fields.items,
+                    &state,
+                    Expected::RECORD_FIELD,
+                    b'}',
+                );
+
+                // A field with just a label could have had a value after it
+                if expected.contains(Expected::COMMA)
+                    && record
+                        .fields
+                        .items
+                        .last()
+                        .map(|field| field.value.is_label_only())
+                        == Some(true)
+                {
+                    expected = expected.or(Expected::COLON);
+                }
+
+                Err((progress, ERecord::End(expected, state.pos())))
+            }
+        },
     )
 }
 
@@ -3826,14 +3913,6 @@ where
 
     match chomped {
         "" => Err((NoProgress, to_expectation(state.pos()))),
-        "+" => good!(OperatorOrDef::BinOp(BinOp::Plus), 1),
-        "-" => good!(OperatorOrDef::BinOp(BinOp::Minus), 1),
-        "*" => good!(OperatorOrDef::BinOp(BinOp::Star), 1),
-        "/" => good!(OperatorOrDef::BinOp(BinOp::Slash), 1),
-        "%" => good!(OperatorOrDef::BinOp(BinOp::Percent), 1),
-        "^" => good!(OperatorOrDef::BinOp(BinOp::Caret), 1),
-        ">" => good!(OperatorOrDef::BinOp(BinOp::GreaterThan), 1),
-        "<" => good!(OperatorOrDef::BinOp(BinOp::LessThan), 1),
         "." => {
             // a `.` makes no progress, so it does not interfere with `.foo` access(or)
             Err((NoProgress, to_error(".", state.pos())))
@@ -3841,21 +3920,17 @@ where
         "=" => good!(OperatorOrDef::Assignment, 1),
         ":=" => good!(OperatorOrDef::AliasOrOpaque(AliasOrOpaque::Opaque), 2),
         ":" => good!(OperatorOrDef::AliasOrOpaque(AliasOrOpaque::Alias), 1),
-        "|>" => good!(OperatorOrDef::BinOp(BinOp::Pizza), 2),
-        "==" => good!(OperatorOrDef::BinOp(BinOp::Equals), 2),
-        "!=" => good!(OperatorOrDef::BinOp(BinOp::NotEquals), 2),
-        ">=" => good!(OperatorOrDef::BinOp(BinOp::GreaterThanOrEq), 2),
-        "<=" => good!(OperatorOrDef::BinOp(BinOp::LessThanOrEq), 2),
-        "&&" => good!(OperatorOrDef::BinOp(BinOp::And), 2),
-        "||" => good!(OperatorOrDef::BinOp(BinOp::Or), 2),
-        "//" => good!(OperatorOrDef::BinOp(BinOp::DoubleSlash), 2),
         "->" => {
             // makes no progress, so it does not interfere with `_ if isGood -> ...`
             Err((NoProgress, to_error("->", state.pos())))
         }
         "<-" => good!(OperatorOrDef::Backpassing, 2),
         "!" => Err((NoProgress, to_error("!", state.pos()))),
-        _ => bad_made_progress!(chomped),
+        // Binary operators are looked up in the same table the formatter prints them from
+        _ => match chomped.parse() {
+            Ok(op) => good!(OperatorOrDef::BinOp(op), chomped.len()),
+            Err(()) => bad_made_progress!(chomped),
+        },
     }
 }
 
@@ -11,9 +11,9 @@ use crate::parser::Progress::{self, *};
 use crate::parser::{
     and, backtrackable, byte, collection_trailing_sep_e, increment_min_indent, loc, map,
     map_with_arena, optional, reset_min_indent, skip_first, skip_second, specialize_err, succeed,
-    then, two_bytes, zero_or_more, EExposes, EHeader, EImports, EPackageEntry, EPackageName,
-    EPackages, EParams, EProvides, ERequires, ETypedIdent, Parser, SourceError, SpaceProblem,
-    SyntaxError,
+    then, two_bytes, warn, zero_or_more, EExposes, EHeader, EHintEntry, EHints, EImports,
+    EPackageEntry, EPackageName, EPackages, EParams, EProvides, ERequires, ETypedIdent,
+    ParseWarning, Parser, SourceError, SpaceProblem, SyntaxError,
 };
 use crate::pattern::record_pattern_fields;
 use crate::state::State;
@@ -37,16 +37,153 @@ pub fn parse_module_defs<'a>(
     state: State<'a>,
     defs: Defs<'a>,
 ) -> Result<Defs<'a>, SyntaxError<'a>> {
+    parse_module_defs_with_warnings(arena, state, defs).map(|(defs, _)| defs)
+}
+
+/// Like `parse_module_defs`, but also returns the warnings about the module. When `state` comes
+/// from `parse_header`, they include the ones about the header.
+pub fn parse_module_defs_with_warnings<'a>(
+    arena: &'a bumpalo::Bump,
+    state: State<'a>,
+    defs: Defs<'a>,
+) -> Result<(Defs<'a>, std::vec::Vec<ParseWarning>), SyntaxError<'a>> {
     let min_indent = 0;
     match crate::expr::parse_top_level_defs(arena, state.clone(), defs) {
         Ok((_, defs, state)) => match end_of_file().parse(arena, state, min_indent) {
-            Ok(_) => Ok(defs),
+            Ok((_, (), state)) => Ok((defs, state.warnings())),
             Err((_, fail)) => Err(fail),
         },
         Err((_, fail)) => Err(SyntaxError::Expr(fail, state.pos())),
     }
 }
 
+/// Part of a module's defs, from `parse_module_defs_recovering`.
+#[derive(Debug, Clone)]
+pub enum ModuleDefsPart<'a> {
+    Defs(Defs<'a>),
+    /// Source which didn't parse: a top-level def, and anything after it up to where the next one
+    /// could start.
+    Broken {
+        region: Region,
+        error: SyntaxError<'a>,
+    },
+}
+
+/// Like `parse_module_defs`, but when a top-level def doesn't parse, it's skipped, and the defs
+/// after it still get parsed. Tools like the formatter use this to work on everything else in a
+/// file with a syntax error.
+///
+/// A top-level def can only start at the beginning of a line (anything inside one is indented),
+/// so those are the points parsing recovers at: each stretch of source between them is parsed on
+/// its own, and the ones which parse get parsed again together, so annotations stay with their
+/// bodies.
+pub fn parse_module_defs_recovering<'a>(
+    arena: &'a bumpalo::Bump,
+    state: State<'a>,
+    defs: Defs<'a>,
+) -> std::vec::Vec<ModuleDefsPart<'a>> {
+    if let Ok(defs) = parse_module_defs(arena, state.clone(), defs.clone()) {
+        return vec![ModuleDefsPart::Defs(defs)];
+    }
+
+    let bytes = state.original_bytes();
+    let mut starts = vec![state.pos().offset as usize];
+
+    starts.extend(top_level_def_starts(bytes, state.pos().offset as usize));
+    starts.push(bytes.len());
+
+    let parse_range = |defs: Defs<'a>, start: usize, end: usize| {
+        let state = if start == starts[0] {
+            // Not always at the beginning of a line, so this keeps the line's position.
+            let mut state = state.clone();
+            state.truncate(end);
+            state
+        } else {
+            State::new(&bytes[..end]).advance_to_line_start(start)
+        };
+
+        parse_module_defs(arena, state, defs)
+    };
+
+    let errors: std::vec::Vec<_> = starts
+        .windows(2)
+        .map(|range| parse_range(Defs::default(), range[0], range[1]).err())
+        .collect();
+    let mut parts = vec![];
+    // The defs from the header go in the first part.
+    let mut header_defs = Some(defs);
+    let mut index = 0;
+
+    while index < errors.len() {
+        match &errors[index] {
+            Some(error) => {
+                if let Some(defs) = header_defs.take().filter(|defs| !defs.is_empty()) {
+                    parts.push(ModuleDefsPart::Defs(defs));
+                }
+
+                parts.push(ModuleDefsPart::Broken {
+                    region: Region::new(
+                        Position::new(starts[index] as u32),
+                        Position::new(starts[index + 1] as u32),
+                    ),
+                    error: error.clone(),
+                });
+
+                index += 1;
+            }
+            None => {
+                let end = (index..errors.len())
+                    .find(|end| errors[*end].is_some())
+                    .unwrap_or(errors.len());
+                let defs = header_defs.take().unwrap_or_default();
+
+                parts.extend(parse_stretches(&parse_range, defs, &starts[index..=end]));
+
+                index = end;
+            }
+        }
+    }
+
+    parts
+}
+
+/// Parses stretches which each parsed on their own, together if possible.
+fn parse_stretches<'a>(
+    parse_range: &impl Fn(Defs<'a>, usize, usize) -> Result<Defs<'a>, SyntaxError<'a>>,
+    defs: Defs<'a>,
+    starts: &[usize],
+) -> std::vec::Vec<ModuleDefsPart<'a>> {
+    let (start, end) = (starts[0], starts[starts.len() - 1]);
+
+    match parse_range(defs.clone(), start, end) {
+        Ok(defs) => vec![ModuleDefsPart::Defs(defs)],
+        Err(_) => {
+            let mut defs = Some(defs);
+
+            starts
+                .windows(2)
+                .filter_map(|range| {
+                    parse_range(defs.take().unwrap_or_default(), range[0], range[1]).ok()
+                })
+                .map(ModuleDefsPart::Defs)
+                .collect()
+        }
+    }
+}
+
+/// The offsets after `start` of lines which could start a top-level def: ones beginning with a
+/// letter or `_`, without any indentation.
+fn top_level_def_starts(bytes: &[u8], start: usize) -> impl Iterator<Item = usize> + '_ {
+    bytes[start..]
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'\n')
+        .map(move |(index, _)| start + index + 1)
+        .filter(|line_start| {
+            matches!(bytes.get(*line_start), Some(byte) if byte.is_ascii_alphabetic() || *byte == b'_')
+        })
+}
+
 pub fn parse_header<'a>(
     arena: &'a bumpalo::Bump,
     state: State<'a>,
@@ -413,7 +550,10 @@ fn old_package_header<'a>() -> impl Parser<'a, PackageHeader<'a>, EHeader<'a>> {
         record!(OldPackageHeader {
             before_name: skip_second(
                 space0_e(EHeader::IndentStart),
-                specialize_err(EHeader::PackageName, package_name())
+                warn(
+                    specialize_err(EHeader::PackageName, package_name()),
+                    ParseWarning::OldPackageHeader
+                )
             ),
             exposes: specialize_err(EHeader::Exposes, exposes_modules()),
             packages: specialize_err(EHeader::Packages, loc(packages())),
@@ -452,6 +592,7 @@ fn platform_header<'a>() -> impl Parser<'a, PlatformHeader<'a>, EHeader<'a>> {
         packages: specialize_err(EHeader::Packages, packages()),
         imports: specialize_err(EHeader::Imports, imports()),
         provides: specialize_err(EHeader::Provides, provides_exposed()),
+        hints: optional(specialize_err(EHeader::Hints, hints())),
     })
     .trace("platform_header")
 }
@@ -756,6 +897,30 @@ fn packages_collection<'a>(
     )
 }
 
+#[inline(always)]
+fn hints<'a>() -> impl Parser<
+    'a,
+    KeywordItem<'a, HintsKeyword, Collection<'a, Loc<Spaced<'a, HintEntry<'a>>>>>,
+    EHints<'a>,
+> {
+    record!(KeywordItem {
+        keyword: spaces_around_keyword(
+            HintsKeyword,
+            EHints::Hints,
+            EHints::IndentHints,
+            EHints::IndentListStart
+        ),
+        item: collection_trailing_sep_e(
+            byte(b'{', EHints::ListStart),
+            specialize_err(EHints::HintEntry, loc(hint_entry())),
+            byte(b',', EHints::ListEnd),
+            byte(b'}', EHints::ListEnd),
+            Spaced::SpaceBefore
+        )
+    })
+    .trace("hints")
+}
+
 #[inline(always)]
 fn imports<'a>() -> impl Parser<
     'a,
@@ -957,6 +1122,8 @@ pub enum HeaderType<'a> {
         requires_types: &'a [Loc<UppercaseIdent<'a>>],
         exposes: &'a [Loc<ModuleName<'a>>],
         exposes_ids: &'a [ModuleId],
+        /// hints to show in type error reports involving these symbols
+        hints: &'a [Loc<HintEntry<'a>>],
 
         /// usually `pf`
         config_shorthand: &'a str,
@@ -1021,6 +1188,7 @@ impl<'a> HeaderType<'a> {
                 requires_types: _,
                 exposes: _,
                 exposes_ids: _,
+                hints: _,
                 config_shorthand: _,
             } => &None,
         }
@@ -1159,6 +1327,7 @@ keywords! {
     ProvidesKeyword => "provides",
     ToKeyword => "to",
     PlatformKeyword => "platform",
+    HintsKeyword => "hints",
     // Deprecated
     ImportsKeyword => "imports",
 }
@@ -1251,6 +1420,8 @@ pub struct PlatformHeader<'a> {
     pub imports: KeywordItem<'a, ImportsKeyword, Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>>,
     pub provides:
         KeywordItem<'a, ProvidesKeyword, Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>>,
+    pub hints:
+        Option<KeywordItem<'a, HintsKeyword, Collection<'a, Loc<Spaced<'a, HintEntry<'a>>>>>>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -1340,6 +1511,79 @@ pub fn package_entry<'a>() -> impl Parser<'a, Spaced<'a, PackageEntry<'a>>, EPac
     )
 }
 
+/// A hint the platform attaches to one of its exposed types or functions,
+/// which type error reports show when a mismatch involves that symbol.
+///
+/// e.g.
+///
+/// Task.await : "Use `!` after the task to await it."
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HintEntry<'a> {
+    /// Either a type like `Task`, or a qualified function like `Task.await`
+    pub name: Loc<&'a str>,
+    pub spaces_after_name: &'a [CommentOrNewline<'a>],
+    /// A plain or raw string; hints can't have escapes or interpolation
+    pub hint: Loc<StrLiteral<'a>>,
+}
+
+pub fn hint_entry<'a>() -> impl Parser<'a, Spaced<'a, HintEntry<'a>>, EHintEntry<'a>> {
+    map_with_arena(
+        and(
+            and(
+                loc(hint_name()),
+                and(
+                    space0_e(EHintEntry::IndentHint),
+                    skip_first(
+                        byte(b':', EHintEntry::Colon),
+                        space0_e(EHintEntry::IndentHint),
+                    ),
+                ),
+            ),
+            loc(hint_text()),
+        ),
+        move |arena, ((name, (spaces_before_colon, spaces_after_colon)), hint)| {
+            Spaced::Item(HintEntry {
+                name,
+                spaces_after_name: merge_spaces(arena, spaces_before_colon, spaces_after_colon),
+                hint,
+            })
+        },
+    )
+}
+
+fn hint_name<'a>() -> impl Parser<'a, &'a str, EHintEntry<'a>> {
+    map_with_arena(
+        and(
+            specialize_err(|_, pos| EHintEntry::Name(pos), ident::uppercase_ident()),
+            optional(skip_first(
+                byte(b'.', EHintEntry::Name),
+                specialize_err(|_, pos| EHintEntry::Name(pos), lowercase_ident()),
+            )),
+        ),
+        |arena, (module_or_type, opt_function)| match opt_function {
+            Some(function) => {
+                bumpalo::format!(in arena, "{}.{}", module_or_type, function).into_bump_str()
+            }
+            None => module_or_type,
+        },
+    )
+}
+
+fn hint_text<'a>() -> impl Parser<'a, StrLiteral<'a>, EHintEntry<'a>> {
+    then(
+        loc(specialize_err(
+            EHintEntry::Hint,
+            string_literal::parse_str_literal(),
+        )),
+        move |_arena, state, progress, text| match text.value {
+            StrLiteral::PlainLine(_) | StrLiteral::Raw(_) => Ok((progress, text.value, state)),
+            StrLiteral::Line(_) | StrLiteral::Block(_) => {
+                Err((progress, EHintEntry::Escapes(text.region.start())))
+            }
+        },
+    )
+}
+
 pub fn package_name<'a>() -> impl Parser<'a, PackageName<'a>, EPackageName<'a>> {
     then(
         loc(specialize_err(
@@ -1348,8 +1592,13 @@ pub fn package_name<'a>() -> impl Parser<'a, PackageName<'a>, EPackageName<'a>>
         )),
         move |_arena, state, progress, text| match text.value {
             StrLiteral::PlainLine(text) => Ok((progress, PackageName(text), state)),
+            StrLiteral::Raw(text) if !text.contains('\n') => {
+                Ok((progress, PackageName(text), state))
+            }
             StrLiteral::Line(_) => Err((progress, EPackageName::Escapes(text.region.start()))),
-            StrLiteral::Block(_) => Err((progress, EPackageName::Multiline(text.region.start()))),
+            StrLiteral::Block(_) | StrLiteral::Raw(_) => {
+                Err((progress, EPackageName::Multiline(text.region.start())))
+            }
         },
     )
 }
@@ -11,7 +11,7 @@ use crate::{
     number_literal::positive_number_literal,
     parser::{EExpr, ParseResult, Parser},
     state::State,
-    string_literal::{parse_str_like_literal, StrLikeLiteral},
+    string_literal::{is_raw_str_start, parse_str_like_literal, StrLikeLiteral},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -115,7 +115,7 @@ fn highlight_inner<'a>(
                         fast_forward_to(&mut state, tokens, start, |c| c == b'\n');
                     }
                 }
-                '"' | '\'' => {
+                '"' | '\'' | 'r' if b != 'r' || is_raw_str_start(state.bytes()) => {
                     if let Ok((_, item, new_state)) =
                         parse_str_like_literal().parse(arena, state.clone(), 0)
                     {
@@ -449,6 +449,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_highlight_raw_strings() {
+        let text = r###"r##"a"#b"##"###;
+        let tokens = highlight(text);
+        assert_eq!(
+            tokens,
+            vec![Loc::at(
+                Region::between(Position::new(0), Position::new(11)),
+                Token::String
+            )]
+        );
+    }
+
     #[test]
     fn test_highlight_single_quotes() {
         let text = r#"'a'"#;
@@ -24,3 +24,28 @@ pub const PLATFORM: &str = "platform";
 pub const KEYWORDS: [&str; 11] = [
     IF, THEN, ELSE, WHEN, AS, IS, DBG, IMPORT, EXPECT, EXPECT_FX, CRASH,
 ];
+
+/// The keyword `ident` is probably a misspelling of, like `when` for `whn`. Short keywords like
+/// `if` and `is` are left out, since lots of names are a letter away from them.
+pub fn near_miss(ident: &str) -> Option<&'static str> {
+    [WHEN, THEN, ELSE, IMPORT, EXPECT, CRASH]
+        .into_iter()
+        .find(|keyword| is_one_edit_away(ident, keyword))
+}
+
+/// Whether adding, removing, or changing one letter, or swapping two next to each other, turns
+/// `a` into `b`.
+fn is_one_edit_away(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let common_prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let (a, b) = (&a[common_prefix..], &b[common_prefix..]);
+
+    match (a, b) {
+        ([], []) => false,
+        ([_, a_rest @ ..], [_, b_rest @ ..]) if a_rest == b_rest => true,
+        ([_, a_rest @ ..], _) if a_rest == b => true,
+        (_, [_, b_rest @ ..]) if a == b_rest => true,
+        ([a1, a2, a_rest @ ..], [b1, b2, b_rest @ ..]) => a1 == b2 && a2 == b1 && a_rest == b_rest,
+        _ => false,
+    }
+}
@@ -8,6 +8,7 @@
 pub mod parser;
 pub mod ast;
 pub mod blankspace;
+mod entrypoints;
 pub mod expr;
 pub mod header;
 pub mod highlight;
@@ -22,3 +23,5 @@ pub mod state;
 pub mod string_literal;
 pub mod test_helpers;
 pub mod type_annotation;
+
+pub use entrypoints::{parse_defs, parse_expr, parse_type_annotation, ParseError};
@@ -5,7 +5,7 @@ use roc_region::all::{Loc, Position, Region};
 
 use crate::{
     ast::{
-        AbilityImpls, AbilityMember, AssignedField, Collection, Defs, Expr, FullAst, Header,
+        AbilityImpls, AbilityMember, AssignedField, Base, Collection, Defs, Expr, FullAst, Header,
         Implements, ImplementsAbilities, ImplementsAbility, ImplementsClause, ImportAlias,
         ImportAsKeyword, ImportExposingKeyword, ImportedModuleName, IngestedFileAnnotation,
         IngestedFileImport, ModuleImport, ModuleImportParams, Pattern, PatternAs, Spaced, Spaces,
@@ -13,18 +13,20 @@ use crate::{
         WhenBranch,
     },
     header::{
-        AppHeader, ExposedName, ExposesKeyword, HostedHeader, ImportsEntry, ImportsKeyword,
-        KeywordItem, ModuleHeader, ModuleName, ModuleParams, PackageEntry, PackageHeader,
-        PackageKeyword, PackageName, PackagesKeyword, PlatformHeader, PlatformKeyword,
-        PlatformRequires, ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent,
+        AppHeader, ExposedName, ExposesKeyword, HintEntry, HintsKeyword, HostedHeader,
+        ImportsEntry, ImportsKeyword, KeywordItem, ModuleHeader, ModuleName, ModuleParams,
+        PackageEntry, PackageHeader, PackageKeyword, PackageName, PackagesKeyword, PlatformHeader,
+        PlatformKeyword, PlatformRequires, ProvidesKeyword, ProvidesTo, RequiresKeyword, To,
+        ToKeyword, TypedIdent,
     },
     ident::{BadIdent, UppercaseIdent},
+    number_literal::split_number_suffix,
     parser::{
-        EAbility, EClosure, EExpect, EExposes, EExpr, EHeader, EIf, EImport, EImportParams,
-        EImports, EInParens, EList, EPackageEntry, EPackageName, EPackages, EParams, EPattern,
-        EProvides, ERecord, ERequires, EString, EType, ETypeAbilityImpl, ETypeApply, ETypeInParens,
-        ETypeInlineAlias, ETypeRecord, ETypeTagUnion, ETypedIdent, EWhen, PInParens, PList,
-        PRecord, SyntaxError,
+        EAbility, EClosure, EExpect, EExposes, EExpr, EHeader, EHintEntry, EHints, EIf, EImport,
+        EImportParams, EImports, EInParens, EList, EPackageEntry, EPackageName, EPackages, EParams,
+        EPattern, EProvides, ERecord, ERequires, EString, EType, ETypeAbilityImpl, ETypeApply,
+        ETypeInParens, ETypeInlineAlias, ETypeRecord, ETypeTagUnion, ETypedIdent, EWhen, PInParens,
+        PList, PRecord, SyntaxError,
     },
 };
 
@@ -64,6 +66,7 @@ keywords! {
     ProvidesKeyword,
     ToKeyword,
     PlatformKeyword,
+    HintsKeyword,
 }
 
 impl<'a> Normalize<'a> for Defs<'a> {
@@ -170,6 +173,7 @@ impl<'a> Normalize<'a> for Header<'a> {
                 packages: header.packages.normalize(arena),
                 imports: header.imports.normalize(arena),
                 provides: header.provides.normalize(arena),
+                hints: header.hints.normalize(arena),
             }),
             Header::Hosted(header) => Header::Hosted(HostedHeader {
                 before_name: &[],
@@ -279,6 +283,16 @@ impl<'a> Normalize<'a> for PackageEntry<'a> {
     }
 }
 
+impl<'a> Normalize<'a> for HintEntry<'a> {
+    fn normalize(&self, arena: &'a Bump) -> Self {
+        HintEntry {
+            name: self.name.normalize(arena),
+            spaces_after_name: &[],
+            hint: self.hint.normalize(arena),
+        }
+    }
+}
+
 impl<'a> Normalize<'a> for ImportsEntry<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match *self {
@@ -566,6 +580,7 @@ impl<'a> Normalize<'a> for StrLiteral<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match *self {
             StrLiteral::PlainLine(t) => StrLiteral::PlainLine(t),
+            StrLiteral::Raw(t) => StrLiteral::Raw(t),
             StrLiteral::Line(t) => {
                 let mut needs_merge = false;
                 let mut last_was_mergable = false;
@@ -677,17 +692,44 @@ impl<'a> Normalize<'a> for StrSegment<'a> {
     }
 }
 
+/// Number literals which only differ in ways the formatter can change (their underscores, the
+/// case of their hex digits, and how their exponents are written) normalize to the same text.
+fn normalize_number<'a>(arena: &'a Bump, literal: &'a str, base: Base) -> &'a str {
+    let (digits, suffix) = split_number_suffix(literal);
+    let mut normalized = String::with_capacity_in(literal.len(), arena);
+    let mut chars = digits.chars().filter(|c| *c != '_').peekable();
+
+    while let Some(c) = chars.next() {
+        normalized.push(c.to_ascii_lowercase());
+
+        if base == Base::Decimal && c.eq_ignore_ascii_case(&'e') {
+            if chars.peek() == Some(&'-') {
+                normalized.push('-');
+                chars.next();
+            }
+
+            // The exponent's leading zeros
+            while chars.peek() == Some(&'0') {
+                chars.next();
+            }
+        }
+    }
+
+    normalized.push_str(suffix);
+    normalized.into_bump_str()
+}
+
 impl<'a> Normalize<'a> for Expr<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match *self {
-            Expr::Float(a) => Expr::Float(a),
-            Expr::Num(a) => Expr::Num(a),
+            Expr::Float(a) => Expr::Float(normalize_number(arena, a, Base::Decimal)),
+            Expr::Num(a) => Expr::Num(normalize_number(arena, a, Base::Decimal)),
             Expr::NonBase10Int {
                 string,
                 base,
                 is_negative,
             } => Expr::NonBase10Int {
-                string,
+                string: normalize_number(arena, string, base),
                 base,
                 is_negative,
             },
@@ -1063,6 +1105,9 @@ impl<'a> Normalize<'a> for EExpr<'a> {
             EExpr::UnexpectedComma(_pos) => EExpr::UnexpectedComma(Position::zero()),
             EExpr::UnexpectedTopLevelExpr(_pos) => EExpr::UnexpectedTopLevelExpr(Position::zero()),
             EExpr::StmtAfterExpr(_pos) => EExpr::StmtAfterExpr(Position::zero()),
+            EExpr::KeywordNearMiss(keyword, _region) => {
+                EExpr::KeywordNearMiss(keyword, Region::zero())
+            }
             EExpr::RecordUpdateOldBuilderField(_pos) => {
                 EExpr::RecordUpdateOldBuilderField(Region::zero())
             }
@@ -1080,7 +1125,7 @@ impl<'a> Normalize<'a> for EList<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match self {
             EList::Open(_pos) => EList::Open(Position::zero()),
-            EList::End(_pos) => EList::End(Position::zero()),
+            EList::End(expected, _pos) => EList::End(*expected, Position::zero()),
             EList::Space(inner_err, _pos) => EList::Space(*inner_err, Position::zero()),
             EList::Expr(inner_err, _pos) => {
                 EList::Expr(arena.alloc(inner_err.normalize(arena)), Position::zero())
@@ -1102,11 +1147,15 @@ impl<'a> Normalize<'a> for EString<'a> {
             EString::EndlessSingleLine(_) => EString::EndlessSingleLine(Position::zero()),
             EString::EndlessMultiLine(_) => EString::EndlessMultiLine(Position::zero()),
             EString::EndlessSingleQuote(_) => EString::EndlessSingleQuote(Position::zero()),
-            EString::UnknownEscape(_) => EString::UnknownEscape(Position::zero()),
+            EString::EndlessRaw(hashes, _) => EString::EndlessRaw(*hashes, Position::zero()),
+            EString::UnknownEscape(_) => EString::UnknownEscape(Region::zero()),
             EString::Format(inner, _) => {
                 EString::Format(arena.alloc(inner.normalize(arena)), Position::zero())
             }
-            EString::FormatEnd(_) => EString::FormatEnd(Position::zero()),
+            EString::UnterminatedInterpolation(_) => {
+                EString::UnterminatedInterpolation(Region::zero())
+            }
+            EString::NestedInterpolation(_) => EString::NestedInterpolation(Region::zero()),
             EString::MultilineInsufficientIndent(_) => {
                 EString::MultilineInsufficientIndent(Position::zero())
             }
@@ -1141,7 +1190,7 @@ impl<'a> Normalize<'a> for EClosure<'a> {
 impl<'a> Normalize<'a> for EInParens<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match self {
-            EInParens::End(_) => EInParens::End(Position::zero()),
+            EInParens::End(expected, _) => EInParens::End(*expected, Position::zero()),
             EInParens::Open(_) => EInParens::Open(Position::zero()),
             EInParens::Empty(_) => EInParens::Empty(Position::zero()),
             EInParens::Expr(inner_err, _) => {
@@ -1155,7 +1204,7 @@ impl<'a> Normalize<'a> for EInParens<'a> {
 impl<'a> Normalize<'a> for ERecord<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match self {
-            ERecord::End(_) => ERecord::End(Position::zero()),
+            ERecord::End(expected, _) => ERecord::End(*expected, Position::zero()),
             ERecord::Open(_) => ERecord::Open(Position::zero()),
             ERecord::Field(_pos) => ERecord::Field(Position::zero()),
             ERecord::UnderscoreField(_pos) => ERecord::Field(Position::zero()),
@@ -1563,6 +1612,37 @@ impl<'a> Normalize<'a> for EPackages<'a> {
     }
 }
 
+impl<'a> Normalize<'a> for EHints<'a> {
+    fn normalize(&self, arena: &'a Bump) -> Self {
+        match self {
+            EHints::Space(inner_err, _) => EHints::Space(*inner_err, Position::zero()),
+            EHints::Hints(_) => EHints::Hints(Position::zero()),
+            EHints::IndentHints(_) => EHints::IndentHints(Position::zero()),
+            EHints::ListStart(_) => EHints::ListStart(Position::zero()),
+            EHints::ListEnd(_) => EHints::ListEnd(Position::zero()),
+            EHints::IndentListStart(_) => EHints::IndentListStart(Position::zero()),
+            EHints::HintEntry(inner_err, _) => {
+                EHints::HintEntry(inner_err.normalize(arena), Position::zero())
+            }
+        }
+    }
+}
+
+impl<'a> Normalize<'a> for EHintEntry<'a> {
+    fn normalize(&self, arena: &'a Bump) -> Self {
+        match self {
+            EHintEntry::Name(_) => EHintEntry::Name(Position::zero()),
+            EHintEntry::Colon(_) => EHintEntry::Colon(Position::zero()),
+            EHintEntry::IndentHint(_) => EHintEntry::IndentHint(Position::zero()),
+            EHintEntry::Hint(inner_err, _) => {
+                EHintEntry::Hint(inner_err.normalize(arena), Position::zero())
+            }
+            EHintEntry::Escapes(_) => EHintEntry::Escapes(Position::zero()),
+            EHintEntry::Space(inner_err, _) => EHintEntry::Space(*inner_err, Position::zero()),
+        }
+    }
+}
+
 impl<'a> Normalize<'a> for EHeader<'a> {
     fn normalize(&self, arena: &'a Bump) -> Self {
         match self {
@@ -1584,6 +1664,9 @@ impl<'a> Normalize<'a> for EHeader<'a> {
             EHeader::Packages(inner_err, _) => {
                 EHeader::Packages(inner_err.normalize(arena), Position::zero())
             }
+            EHeader::Hints(inner_err, _) => {
+                EHeader::Hints(inner_err.normalize(arena), Position::zero())
+            }
             EHeader::Space(inner_err, _) => EHeader::Space(*inner_err, Position::zero()),
             EHeader::Start(_) => EHeader::Start(Position::zero()),
             EHeader::ModuleName(_) => EHeader::ModuleName(Position::zero()),
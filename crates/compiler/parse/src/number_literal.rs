@@ -159,3 +159,21 @@ fn chomp_number(mut bytes: &[u8]) -> (bool, usize) {
     // therefore we parsed all of the bytes in the input
     (is_float, start_bytes_len)
 }
+
+/// The suffixes which give a number literal its type, like the `u8` in `1u8`.
+pub const NUMBER_SUFFIXES: [&str; 13] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "dec", "f32", "f64",
+];
+
+/// Splits a number literal into its digits and its suffix, which is empty if it doesn't have one.
+/// Like canonicalization, this only looks at how the literal ends, so the last digits of a hex
+/// literal like `0xff32` are taken to be a suffix.
+pub fn split_number_suffix(literal: &str) -> (&str, &str) {
+    match NUMBER_SUFFIXES
+        .iter()
+        .find(|suffix| literal.ends_with(*suffix))
+    {
+        Some(suffix) => literal.split_at(literal.len() - suffix.len()),
+        None => (literal, ""),
+    }
+}
@@ -1,6 +1,7 @@
 use crate::state::State;
 use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
+use roc_error_macros::internal_error;
 use roc_region::all::{Loc, Position, Region};
 use Progress::*;
 
@@ -63,6 +64,125 @@ pub enum SyntaxError<'a> {
     Space(BadInputError),
     NotEndOfFile(Position),
 }
+
+/// Syntax which still parses, but is going away. The parser keeps going when it finds some, and
+/// the compiler reports it as a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// A package header in the old style, like `package "name" exposes [Foo] packages {}`. The
+    /// region is the name, which package headers don't have anymore.
+    OldPackageHeader(Region),
+}
+
+impl ParseWarning {
+    pub fn region(&self) -> Region {
+        match self {
+            ParseWarning::OldPackageHeader(region) => *region,
+        }
+    }
+}
+
+/// The things the parser would have accepted where it got stuck, so error reports can say what
+/// could have come next.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Expected(u8);
+
+impl Expected {
+    pub const EXPR: Expected = Expected(1);
+    pub const RECORD_FIELD: Expected = Expected(1 << 1);
+    pub const COLON: Expected = Expected(1 << 2);
+    pub const COMMA: Expected = Expected(1 << 3);
+    pub const CLOSE_SQUARE: Expected = Expected(1 << 4);
+    pub const CLOSE_CURLY: Expected = Expected(1 << 5);
+    pub const CLOSE_PAREN: Expected = Expected(1 << 6);
+
+    const ALL: [Expected; 7] = [
+        Self::EXPR,
+        Self::RECORD_FIELD,
+        Self::COLON,
+        Self::COMMA,
+        Self::CLOSE_SQUARE,
+        Self::CLOSE_CURLY,
+        Self::CLOSE_PAREN,
+    ];
+
+    pub const fn or(self, other: Expected) -> Expected {
+        Expected(self.0 | other.0)
+    }
+
+    pub const fn contains(self, other: Expected) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Each thing in the set on its own, in the order they're declared in.
+    pub fn iter(self) -> impl Iterator<Item = Expected> {
+        Self::ALL.into_iter().filter(move |one| self.contains(*one))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::EXPR => "EXPR",
+            Self::RECORD_FIELD => "RECORD_FIELD",
+            Self::COLON => "COLON",
+            Self::COMMA => "COMMA",
+            Self::CLOSE_SQUARE => "CLOSE_SQUARE",
+            Self::CLOSE_CURLY => "CLOSE_CURLY",
+            Self::CLOSE_PAREN => "CLOSE_PAREN",
+            _ => internal_error!("{:?} is more than one thing", self.0),
+        }
+    }
+
+    fn closing_brace(byte: u8) -> Expected {
+        match byte {
+            b']' => Self::CLOSE_SQUARE,
+            b'}' => Self::CLOSE_CURLY,
+            b')' => Self::CLOSE_PAREN,
+            _ => internal_error!("{:?} isn't a closing brace", byte as char),
+        }
+    }
+
+    /// What could have come next in a comma-separated collection that got stuck at `state`,
+    /// instead of its closing brace: after an element, a comma, and otherwise another element.
+    pub(crate) fn in_collection<T>(
+        items: &[Loc<T>],
+        state: &State,
+        elem: Expected,
+        closing_brace: u8,
+    ) -> Expected {
+        let after_comma = match items.last() {
+            Some(last) => {
+                // Only spaces, comments, and maybe a comma come between the last element and
+                // where it got stuck.
+                let between = &state.original_bytes()
+                    [last.region.end().offset as usize..state.pos().offset as usize];
+                let mut in_comment = false;
+
+                between.iter().any(|byte| {
+                    match byte {
+                        b'#' => in_comment = true,
+                        b'\n' => in_comment = false,
+                        _ => {}
+                    }
+
+                    *byte == b',' && !in_comment
+                })
+            }
+            None => true,
+        };
+        let next = if after_comma { elem } else { Self::COMMA };
+
+        next.or(Self::closing_brace(closing_brace))
+    }
+}
+
+impl std::fmt::Debug for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: std::vec::Vec<_> = self.iter().map(Expected::name).collect();
+
+        write!(f, "Expected({})", names.join(" | "))
+    }
+}
+
 pub trait SpaceProblem: std::fmt::Debug {
     fn space_problem(e: BadInputError, pos: Position) -> Self;
 }
@@ -94,6 +214,8 @@ impl_space_problem! {
     EList<'a>,
     EPackageEntry<'a>,
     EPackages<'a>,
+    EHints<'a>,
+    EHintEntry<'a>,
     EPattern<'a>,
     EProvides<'a>,
     ERecord<'a>,
@@ -120,6 +242,7 @@ pub enum EHeader<'a> {
     Imports(EImports, Position),
     Requires(ERequires<'a>, Position),
     Packages(EPackages<'a>, Position),
+    Hints(EHints<'a>, Position),
 
     Space(BadInputError, Position),
     Start(Position),
@@ -224,6 +347,27 @@ pub enum EPackageEntry<'a> {
     Space(BadInputError, Position),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EHints<'a> {
+    Space(BadInputError, Position),
+    Hints(Position),
+    IndentHints(Position),
+    ListStart(Position),
+    ListEnd(Position),
+    IndentListStart(Position),
+    HintEntry(EHintEntry<'a>, Position),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EHintEntry<'a> {
+    Name(Position),
+    Colon(Position),
+    IndentHint(Position),
+    Hint(EString<'a>, Position),
+    Escapes(Position),
+    Space(BadInputError, Position),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EImports {
     Open(Position),
@@ -324,6 +468,8 @@ pub enum EExpr<'a> {
     DoubleColon(Position),
     Ident(Position),
     ElmStyleFunction(Region, Position),
+    /// A statement starting with what looks like a misspelled keyword, like `whn x is`.
+    KeywordNearMiss(&'a str, Region),
     MalformedPattern(Position),
     QualifiedTag(Position),
     BackpassComma(Position),
@@ -379,9 +525,16 @@ pub enum EString<'a> {
     EndlessSingleLine(Position),
     EndlessMultiLine(Position),
     EndlessSingleQuote(Position),
-    UnknownEscape(Position),
+    /// A raw string starting with this many `#`s, which doesn't have a `"` followed by as many
+    /// `#`s to end it.
+    EndlessRaw(u32, Position),
+    /// A backslash followed by something which isn't an escape; the region covers both.
+    UnknownEscape(Region),
     Format(&'a EExpr<'a>, Position),
-    FormatEnd(Position),
+    /// The `$(` at the start of the region was never closed with a `)`.
+    UnterminatedInterpolation(Region),
+    /// An interpolation inside a string which is itself inside an interpolation.
+    NestedInterpolation(Region),
     MultilineInsufficientIndent(Position),
     ExpectedDoubleQuoteGotSingleQuote(Position),
 }
@@ -395,7 +548,8 @@ pub enum ESingleQuote {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ERecord<'a> {
-    End(Position),
+    /// There's no `}` where it got stuck. The record could have had what's expected instead.
+    End(Expected, Position),
     Open(Position),
 
     Prefix(Position),
@@ -414,7 +568,8 @@ pub enum ERecord<'a> {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EInParens<'a> {
-    End(Position),
+    /// There's no `)` where it got stuck. The parens could have had what's expected instead.
+    End(Expected, Position),
     Open(Position),
 
     /// Empty parens, e.g. () is not allowed
@@ -445,7 +600,8 @@ pub enum EClosure<'a> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EList<'a> {
     Open(Position),
-    End(Position),
+    /// There's no `]` where it got stuck. The list could have had what's expected instead.
+    End(Expected, Position),
     Space(BadInputError, Position),
 
     Expr(&'a EExpr<'a>, Position),
@@ -728,7 +884,7 @@ pub enum ETypeAbilityImpl<'a> {
 impl<'a> From<ERecord<'a>> for ETypeAbilityImpl<'a> {
     fn from(e: ERecord<'a>) -> Self {
         match e {
-            ERecord::End(p) => ETypeAbilityImpl::End(p),
+            ERecord::End(_, p) => ETypeAbilityImpl::End(p),
             ERecord::Open(p) => ETypeAbilityImpl::Open(p),
             ERecord::Field(p) => ETypeAbilityImpl::Field(p),
             ERecord::UnderscoreField(p) => ETypeAbilityImpl::UnderscoreField(p),
@@ -1409,6 +1565,46 @@ pub fn loc<'a, Output, E: 'a>(
     }
 }
 
+/// If the given parser succeeds, adds a warning about the input it parsed to the state.
+///
+/// # Examples
+/// ```
+/// # #![forbid(unused_imports)]
+/// # use roc_parse::state::State;
+/// # use crate::roc_parse::parser::{Parser, ParseWarning, word, warn};
+/// # use roc_region::all::{Position, Region};
+/// # use bumpalo::Bump;
+/// # #[derive(Debug, PartialEq)]
+/// # enum Problem {
+/// #     NotFound(Position),
+/// # }
+/// # let arena = Bump::new();
+/// # fn foo<'a>(arena: &'a Bump) {
+/// let parser = warn(word("hello", Problem::NotFound), ParseWarning::OldPackageHeader);
+///
+/// let (_, _, state) = parser.parse(&arena, State::new("hello, world".as_bytes()), 0).unwrap();
+/// assert_eq!(
+///     state.warnings(),
+///     vec![ParseWarning::OldPackageHeader(Region::new(Position::new(0), Position::new(5)))]
+/// );
+/// # }
+/// # foo(&arena);
+/// ```
+pub fn warn<'a, Output, E: 'a>(
+    parser: impl Parser<'a, Output, E>,
+    to_warning: fn(Region) -> ParseWarning,
+) -> impl Parser<'a, Output, E> {
+    move |arena, state: crate::state::State<'a>, min_indent: u32| {
+        let start = state.pos();
+        let (progress, value, mut state) = parser.parse(arena, state, min_indent)?;
+        let region = Region::new(start, state.pos());
+
+        state.add_warning(arena, to_warning(region));
+
+        Ok((progress, value, state))
+    }
+}
+
 /// If the first one parses, ignore its output and move on to parse with the second one.
 ///
 /// # Examples
@@ -1535,6 +1731,45 @@ pub fn collection_inner<'a, Elem: 'a + crate::ast::Spaceable<'a> + Clone, E: 'a
     )
 }
 
+/// Like `collection_trailing_sep_e` for a comma-separated collection, but when there's no closing
+/// brace where it gets stuck, the error says what could have come next instead.
+pub fn collection_trailing_comma_expected<
+    'a,
+    Elem: 'a + crate::ast::Spaceable<'a> + Clone,
+    E: 'a + SpaceProblem,
+>(
+    opening_brace: impl Parser<'a, (), E>,
+    elem: impl Parser<'a, Loc<Elem>, E> + 'a,
+    elem_expected: Expected,
+    closing_brace: u8,
+    to_end: fn(Expected, Position) -> E,
+    space_before: impl Fn(&'a Elem, &'a [crate::ast::CommentOrNewline<'a>]) -> Elem,
+) -> impl Parser<'a, crate::ast::Collection<'a, Loc<Elem>>, E> {
+    let delimiter = byte(b',', move |pos| {
+        to_end(
+            Expected::COMMA.or(Expected::closing_brace(closing_brace)),
+            pos,
+        )
+    });
+    let inner = reset_min_indent(collection_inner(elem, delimiter, space_before));
+
+    skip_first(
+        opening_brace,
+        move |arena, state: crate::state::State<'a>, min_indent: u32| {
+            let (progress, collection, state) = inner.parse(arena, state, min_indent)?;
+
+            if state.bytes().first() == Some(&closing_brace) {
+                Ok((MadeProgress, collection, state.advance(1)))
+            } else {
+                let expected =
+                    Expected::in_collection(collection.items, &state, elem_expected, closing_brace);
+
+                Err((progress, to_end(expected, state.pos())))
+            }
+        },
+    )
+}
+
 pub fn collection_trailing_sep_e<
     'a,
     Elem: 'a + crate::ast::Spaceable<'a> + Clone,
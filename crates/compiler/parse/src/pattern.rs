@@ -83,9 +83,11 @@ pub fn loc_pattern_help<'a>() -> impl Parser<'a, Loc<Pattern<'a>>, EPattern<'a>>
 fn loc_pattern_help_help<'a>(
     can_have_arguments: bool,
 ) -> impl Parser<'a, Loc<Pattern<'a>>, EPattern<'a>> {
-    one_of!(
+    let pattern = one_of!(
         specialize_err(EPattern::PInParens, loc_pattern_in_parens_help()),
         loc(underscore_pattern_help()),
+        // Before identifiers, so the `r` in `r#"raw"#` isn't one
+        loc(string_like_pattern_help()),
         loc_ident_pattern_help(can_have_arguments),
         loc(specialize_err(
             EPattern::Record,
@@ -93,8 +95,16 @@ fn loc_pattern_help_help<'a>(
         )),
         loc(specialize_err(EPattern::List, list_pattern_help())),
         loc(number_pattern_help()),
-        loc(string_like_pattern_help()),
-    )
+    );
+
+    move |arena, state: State<'a>, min_indent| {
+        let start = state.pos();
+
+        match pattern.parse(arena, state, min_indent) {
+            Err((NoProgress, _)) => Err((NoProgress, EPattern::Start(start))),
+            result => result,
+        }
+    }
 }
 
 fn pattern_as<'a>() -> impl Parser<'a, PatternAs<'a>, EPattern<'a>> {
@@ -17,9 +17,9 @@ use core::{
 };
 
 #[cfg(not(test))]
-/// We store both line and column numbers as u16s, so the largest possible file you could open
-/// would be every line having the longest possible column length, or u16::MAX * u16::MAX.
-const MAX_ROC_SOURCE_FILE_SIZE: usize = u16::MAX as usize * u16::MAX as usize; // 4GB
+/// Positions are u32 byte offsets into the file (line and column numbers get computed from those),
+/// so the largest possible file you could open is u32::MAX bytes.
+const MAX_ROC_SOURCE_FILE_SIZE: usize = u32::MAX as usize; // 4GB
 
 #[cfg(test)]
 const MAX_ROC_SOURCE_FILE_SIZE: usize = 1024; // small enough that we can create a tempfile to exercise this scenario
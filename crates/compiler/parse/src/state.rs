@@ -1,7 +1,8 @@
+use bumpalo::Bump;
 use roc_region::all::{Position, Region};
 use std::fmt;
 
-use crate::parser::Progress;
+use crate::parser::{ParseWarning, Progress};
 
 /// A position in a source file.
 // NB: [Copy] is explicitly NOT derived to reduce the chance of bugs due to accidentally re-using
@@ -21,6 +22,54 @@ pub struct State<'a> {
 
     /// Position of the first non-whitespace character on the current line
     pub(crate) line_start_after_whitespace: Position,
+
+    /// Whether the parser is inside a string interpolation, which can't contain another one.
+    pub(crate) in_interpolation: bool,
+
+    /// What to do when there's a tab in whitespace or a comment
+    tabs: Tabs,
+
+    /// How many more columns than bytes the tabs so far on the current line take up
+    line_tab_columns: u32,
+
+    /// How many more columns than bytes the tabs in the current line's indentation take up
+    indent_tab_columns: u32,
+
+    /// Warnings about the input parsed so far. They're in the arena, so cloning the state doesn't
+    /// copy them, and a state which gets backtracked over takes its warnings with it.
+    warnings: Option<&'a Warnings<'a>>,
+}
+
+/// How the parser treats tab characters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tabs {
+    /// Tabs are a syntax error, since Roc code is indented with spaces.
+    #[default]
+    Reject,
+
+    /// A tab is allowed anywhere a space is, and moves the column to the next multiple of
+    /// `width`, so it counts for as much indentation as it would in an editor.
+    Expand { width: u8 },
+}
+
+impl Tabs {
+    /// The environment variable which makes tabs count as this many columns, instead of being
+    /// rejected.
+    pub const WIDTH_VAR: &'static str = "ROC_TAB_WIDTH";
+
+    /// Expand tabs to the width in `ROC_TAB_WIDTH`, if it's set to a valid width.
+    pub fn from_env() -> Self {
+        match std::env::var(Self::WIDTH_VAR).map(|width| width.trim().parse::<u8>()) {
+            Ok(Ok(width)) if width > 0 => Tabs::Expand { width },
+            _ => Tabs::Reject,
+        }
+    }
+}
+
+/// A list of warnings, newest first.
+struct Warnings<'a> {
+    warning: ParseWarning,
+    earlier: Option<&'a Warnings<'a>>,
 }
 
 impl<'a> State<'a> {
@@ -33,9 +82,26 @@ impl<'a> State<'a> {
             // Technically not correct.
             // We don't know the position of the first non-whitespace character yet.
             line_start_after_whitespace: Position::zero(),
+
+            in_interpolation: false,
+            tabs: Tabs::Reject,
+            line_tab_columns: 0,
+            indent_tab_columns: 0,
+            warnings: None,
         }
     }
 
+    /// Parse tabs according to `tabs`, rather than rejecting them.
+    #[must_use]
+    pub fn with_tabs(mut self, tabs: Tabs) -> State<'a> {
+        self.tabs = tabs;
+        self
+    }
+
+    pub fn tabs(&self) -> Tabs {
+        self.tabs
+    }
+
     pub fn original_bytes(&self) -> &'a [u8] {
         self.original_bytes
     }
@@ -45,11 +111,11 @@ impl<'a> State<'a> {
     }
 
     pub fn column(&self) -> u32 {
-        self.pos().offset - self.line_start.offset
+        self.pos().offset - self.line_start.offset + self.line_tab_columns
     }
 
     pub fn line_indent(&self) -> u32 {
-        self.line_start_after_whitespace.offset - self.line_start.offset
+        self.line_start_after_whitespace.offset - self.line_start.offset + self.indent_tab_columns
     }
 
     /// Check that the indent is at least `indent` spaces.
@@ -66,12 +132,36 @@ impl<'a> State<'a> {
         }
     }
 
+    /// Ends the input at `end`, so only the bytes before it get parsed.
+    pub(crate) fn truncate(&mut self, end: usize) {
+        self.original_bytes = &self.original_bytes[..end];
+    }
+
+    /// Advances the state to `offset`, which has to be the beginning of a line.
+    #[must_use]
+    pub(crate) fn advance_to_line_start(mut self, offset: usize) -> State<'a> {
+        self.offset = offset;
+        self.line_start = self.pos();
+        self.line_start_after_whitespace = self.pos();
+        self.line_tab_columns = 0;
+        self.indent_tab_columns = 0;
+        self
+    }
+
     /// Mutably advance the state by a given offset
     #[inline(always)]
     pub(crate) fn advance_mut(&mut self, offset: usize) {
         self.offset += offset;
     }
 
+    /// Mutably advance the state past a tab which is `width` columns wide.
+    pub(crate) fn advance_tab_mut(&mut self, width: u8) {
+        let width = width as u32;
+
+        self.line_tab_columns += width - 1 - self.column() % width;
+        self.offset += 1;
+    }
+
     /// If the next `text.len()` bytes of the input match the provided `text`,
     /// mutably advance the state by that much.
     #[inline(always)]
@@ -97,6 +187,8 @@ impl<'a> State<'a> {
     pub(crate) const fn advance_newline(mut self) -> State<'a> {
         self.offset += 1;
         self.line_start = self.pos();
+        self.line_tab_columns = 0;
+        self.indent_tab_columns = 0;
 
         // WARNING! COULD CAUSE BUGS IF WE FORGET TO CALL mark_current_indent LATER!
         // We really need to be stricter about this.
@@ -109,6 +201,7 @@ impl<'a> State<'a> {
     #[inline(always)]
     pub(crate) const fn mark_current_indent(mut self) -> State<'a> {
         self.line_start_after_whitespace = self.pos();
+        self.indent_tab_columns = self.line_tab_columns;
         self
     }
 
@@ -133,6 +226,27 @@ impl<'a> State<'a> {
     pub fn is_at_start_of_file(&self) -> bool {
         self.offset == 0
     }
+
+    pub(crate) fn add_warning(&mut self, arena: &'a Bump, warning: ParseWarning) {
+        self.warnings = Some(arena.alloc(Warnings {
+            warning,
+            earlier: self.warnings,
+        }));
+    }
+
+    /// The warnings about the input parsed so far, in the order they were found.
+    pub fn warnings(&self) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+        let mut next = self.warnings;
+
+        while let Some(list) = next {
+            warnings.push(list.warning);
+            next = list.earlier;
+        }
+
+        warnings.reverse();
+        warnings
+    }
 }
 
 impl<'a> fmt::Debug for State<'a> {
@@ -2,12 +2,13 @@ use crate::ast::{EscapedChar, SingleQuoteLiteral, StrLiteral, StrSegment};
 use crate::expr;
 use crate::parser::Progress::{self, *};
 use crate::parser::{
-    allocated, between, byte, loc, reset_min_indent, skip_second, specialize_err_ref, then,
-    BadInputError, ESingleQuote, EString, Parser,
+    allocated, between, byte, loc, reset_min_indent, specialize_err_ref, then, BadInputError,
+    EExpr, ESingleQuote, EString, ParseResult, Parser,
 };
 use crate::state::State;
 use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
+use roc_region::all::Region;
 
 /// One or more ASCII hex digits. (Useful when parsing unicode escape codes,
 /// which must consist entirely of ASCII hex digits.)
@@ -84,6 +85,60 @@ pub fn parse_str_literal<'a>() -> impl Parser<'a, StrLiteral<'a>, EString<'a>> {
     )
 }
 
+/// Whether `bytes` start with an `r`, one or more `#`s, and a `"`, which is how raw strings start.
+/// (Otherwise the `r` is an identifier, and the `#` starts a comment.)
+pub fn is_raw_str_start(bytes: &[u8]) -> bool {
+    let hashes = bytes.iter().skip(1).take_while(|&&b| b == b'#').count();
+
+    bytes.first() == Some(&b'r') && hashes > 0 && bytes.get(1 + hashes) == Some(&b'"')
+}
+
+/// A raw string, like `r#"C:\Users"#`. Everything up to the `"` followed by as many `#`s as it
+/// started with is part of the string, including backslashes, quotes, and newlines.
+fn raw_str_literal(state: State<'_>) -> ParseResult<'_, StrLikeLiteral<'_>, EString<'_>> {
+    let hashes = state.bytes()[1..]
+        .iter()
+        .take_while(|&&b| b == b'#')
+        .count();
+    let content_start = 1 + hashes + 1;
+
+    let content = &state.bytes()[content_start..];
+    let is_end = |end: &[u8]| end[0] == b'"' && end[1..].iter().all(|&b| b == b'#');
+
+    let Some(content_len) = content.windows(1 + hashes).position(is_end) else {
+        return Err((
+            MadeProgress,
+            EString::EndlessRaw(hashes as u32, state.pos()),
+        ));
+    };
+
+    let string = utf8(state.clone(), &content[..content_len])?;
+    let start = state.pos().offset as usize;
+    let len = content_start + content_len + 1 + hashes;
+
+    // Keep track of where the last line starts, if the string has more than one
+    let state = match string.rfind('\n') {
+        Some(newline) => {
+            let line_start = start + content_start + newline + 1;
+            let line = &string[newline + 1..];
+            let indent = line.len() - line.trim_start_matches(' ').len();
+
+            state
+                .advance_to_line_start(line_start)
+                .advance(indent)
+                .mark_current_indent()
+                .advance(start + len - line_start - indent)
+        }
+        None => state.advance(len),
+    };
+
+    Ok((
+        MadeProgress,
+        StrLikeLiteral::Str(StrLiteral::Raw(string)),
+        state,
+    ))
+}
+
 pub fn parse_str_like_literal<'a>() -> impl Parser<'a, StrLikeLiteral<'a>, EString<'a>> {
     move |arena: &'a Bump, mut state: State<'a>, min_indent: u32| {
         let is_multiline;
@@ -114,6 +169,8 @@ pub fn parse_str_like_literal<'a>() -> impl Parser<'a, StrLikeLiteral<'a>, EStri
 
             is_multiline = false;
             is_single_quote = true;
+        } else if is_raw_str_start(state.bytes()) {
+            return raw_str_literal(state);
         } else {
             return Err((NoProgress, EString::Open(state.pos())));
         }
@@ -416,7 +473,21 @@ pub fn parse_str_like_literal<'a>() -> impl Parser<'a, StrLikeLiteral<'a>, EStri
                             // Invalid escape! A backslash must be followed
                             // by one of these escapable characters:
                             // (\n, \t, \", \\, etc)
-                            return Err((MadeProgress, EString::UnknownEscape(state.pos())));
+                            let escape_len = match state.bytes().get(1) {
+                                // Include all of the character after the backslash
+                                Some(_) => {
+                                    2 + state.bytes()[2..]
+                                        .iter()
+                                        .take_while(|&&b| b & 0b1100_0000 == 0b1000_0000)
+                                        .count()
+                                }
+                                None => 1,
+                            };
+
+                            return Err((
+                                MadeProgress,
+                                EString::UnknownEscape(state.len_region(escape_len as u32)),
+                            ));
                         }
                     }
                 }
@@ -447,20 +518,64 @@ pub fn parse_str_like_literal<'a>() -> impl Parser<'a, StrLikeLiteral<'a>, EStri
                         }
                     }
 
+                    let interpolation_start = state.pos();
+                    let line_end = state.bytes().iter().position(|&b| b == b'\n');
+                    let line_end = interpolation_start
+                        .bump_column(line_end.unwrap_or(state.bytes().len()) as u32);
+                    let was_in_interpolation = state.in_interpolation;
+
                     // Advance past the `$(`
                     state.advance_mut(2);
+                    state.in_interpolation = true;
 
                     let original_byte_count = state.bytes().len();
 
                     // Parse an arbitrary expression, followed by ')'
-                    let (_progress, loc_expr, new_state) = skip_second(
-                        specialize_err_ref(
-                            EString::Format,
-                            loc(allocated(reset_min_indent(expr::expr_help()))),
-                        ),
-                        byte(b')', EString::FormatEnd),
+                    let (_progress, loc_expr, mut new_state) = match specialize_err_ref(
+                        EString::Format,
+                        loc(allocated(reset_min_indent(expr::expr_help()))),
                     )
-                    .parse(arena, state, min_indent)?;
+                    .parse(arena, state, min_indent)
+                    {
+                        Ok(answer) => answer,
+                        // Without a `)`, the closing quote starts a string instead, which
+                        // never ends
+                        Err((
+                            _,
+                            EString::Format(EExpr::Str(EString::EndlessSingleLine(_), _), _),
+                        )) if !is_multiline => {
+                            return Err((
+                                MadeProgress,
+                                EString::UnterminatedInterpolation(Region::new(
+                                    interpolation_start,
+                                    line_end,
+                                )),
+                            ));
+                        }
+                        Err(fail) => return Err(fail),
+                    };
+
+                    if !new_state.consume_mut(")") {
+                        return Err((
+                            MadeProgress,
+                            EString::UnterminatedInterpolation(Region::new(
+                                interpolation_start,
+                                new_state.pos(),
+                            )),
+                        ));
+                    }
+
+                    if was_in_interpolation {
+                        return Err((
+                            MadeProgress,
+                            EString::NestedInterpolation(Region::new(
+                                interpolation_start,
+                                new_state.pos(),
+                            )),
+                        ));
+                    }
+
+                    new_state.in_interpolation = false;
 
                     // Advance the iterator past the expr we just parsed.
                     for _ in 0..(original_byte_count - new_state.bytes().len()) {
@@ -8,7 +8,6 @@ use crate::parser::SyntaxError;
 use crate::state::State;
 use bumpalo::Bump;
 use roc_region::all::Loc;
-use roc_region::all::Position;
 
 pub fn parse_expr_with<'a>(
     arena: &'a Bump,
@@ -24,18 +23,11 @@ pub fn parse_loc_with<'a>(
     arena: &'a Bump,
     input: &'a str,
 ) -> Result<Loc<ast::Expr<'a>>, SourceError<'a, SyntaxError<'a>>> {
-    let state = State::new(input.as_bytes());
-
-    match crate::expr::test_parse_expr(0, arena, state.clone()) {
-        Ok(loc_expr) => Ok(loc_expr),
-        Err(fail) => Err(SyntaxError::Expr(fail, Position::default()).into_source_error(&state)),
-    }
+    crate::parse_expr(arena, input)
 }
 
 pub fn parse_defs_with<'a>(arena: &'a Bump, input: &'a str) -> Result<Defs<'a>, SyntaxError<'a>> {
-    let state = State::new(input.as_bytes());
-
-    parse_module_defs(arena, state, Defs::default())
+    crate::parse_defs(arena, input).map_err(|e| e.problem)
 }
 
 pub fn parse_header_with<'a>(
@@ -49,3 +41,117 @@ pub fn parse_header_with<'a>(
         Err(fail) => Err(SyntaxError::Header(fail.problem)),
     }
 }
+
+/// Parses `src` as an expression, as defs, and as a whole module, panicking if any region or
+/// position in what comes back (the AST or the error) is outside of `src`. The parser should
+/// never panic or loop forever either, whatever `src` is, so this is what fuzzing and tests of
+/// broken source code check.
+pub fn check_parser_invariants(src: &str) {
+    let arena = Bump::new();
+
+    assert_in_bounds(src, "expression", &crate::parse_expr(&arena, src));
+    assert_in_bounds(src, "defs", &crate::parse_defs(&arena, src));
+
+    match crate::header::parse_header(&arena, State::new(src.as_bytes())) {
+        Ok((header, state)) => {
+            assert_in_bounds(src, "module header", &header);
+            assert_in_bounds(
+                src,
+                "module defs",
+                &parse_module_defs(&arena, state, Defs::default()),
+            );
+        }
+        Err(fail) => assert_in_bounds(src, "module header", &fail.problem),
+    }
+}
+
+/// Regions and positions are in hundreds of AST and error types, so they're found by their debug
+/// output, which is `@start-end` for a region and `@offset` for a position.
+fn assert_in_bounds(src: &str, parsed_as: &str, parsed: &impl std::fmt::Debug) {
+    let debug = format!("{parsed:?}");
+    let mut chars = debug.char_indices().peekable();
+    let mut in_str = false;
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            // An `@` in a string literal isn't a position.
+            '\\' if in_str => {
+                chars.next();
+            }
+            '"' => in_str = !in_str,
+            '@' if !in_str => {
+                let offsets: Vec<usize> = debug[index + 1..]
+                    .split(|c: char| !c.is_ascii_digit() && c != '-')
+                    .next()
+                    .unwrap_or_default()
+                    .split('-')
+                    .map_while(|offset| offset.parse().ok())
+                    .collect();
+
+                let in_bounds = match offsets.as_slice() {
+                    [offset] => *offset <= src.len(),
+                    [start, end] => start <= end && *end <= src.len(),
+                    _ => true,
+                };
+
+                assert!(
+                    in_bounds,
+                    "Parsing this {}-byte source as {parsed_as} gave a region or position outside of it, at {}:\n\n{src}\n\n{debug}",
+                    src.len(),
+                    debug[index..].chars().take(20).collect::<String>(),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{TypeAnnotation, ValueDef};
+    use roc_region::all::Region;
+
+    const ALIASED_TAG_UNION: &str = "x : [A, B] as Foo";
+
+    fn parse_aliased_tag_union(arena: &Bump) -> Loc<TypeAnnotation<'_>> {
+        let defs = parse_defs_with(arena, ALIASED_TAG_UNION).unwrap();
+
+        match defs.value_defs.as_slice() {
+            [ValueDef::Annotation(_, loc_ann)] => *loc_ann,
+            other => panic!("Expected one annotation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn as_annotation_in_bounds() {
+        let arena = Bump::new();
+        let loc_ann = parse_aliased_tag_union(&arena);
+
+        assert!(matches!(loc_ann.value, TypeAnnotation::As(..)));
+        assert_in_bounds(ALIASED_TAG_UNION, "defs", &loc_ann);
+    }
+
+    #[test]
+    #[should_panic(expected = "gave a region or position outside of it")]
+    fn as_annotation_ending_before_it_starts() {
+        let arena = Bump::new();
+        let loc_ann = parse_aliased_tag_union(&arena);
+
+        // The region `term` used to give an alias without type variables: it spanned to the
+        // end of `Region::across_all` of no regions, which is `Region::zero()`.
+        let region = Region::span_across(&loc_ann.region, &Region::zero());
+
+        assert_in_bounds(ALIASED_TAG_UNION, "defs", &Loc::at(region, loc_ann.value));
+    }
+
+    #[test]
+    #[should_panic(expected = "gave a region or position outside of it")]
+    fn region_past_the_end() {
+        let arena = Bump::new();
+        let loc_ann = parse_aliased_tag_union(&arena);
+        let end = ALIASED_TAG_UNION.len() as u32 + 1;
+
+        assert_in_bounds(ALIASED_TAG_UNION, "defs", &Loc::new(0, end, loc_ann.value));
+    }
+}
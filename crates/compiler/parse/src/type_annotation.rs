@@ -3,7 +3,7 @@ use crate::ast::{
     ImplementsClause, Pattern, Spaceable, Spaced, Tag, TypeAnnotation, TypeHeader,
 };
 use crate::blankspace::{
-    space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
+    argument_comma, space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
 };
 use crate::expr::record_field;
 use crate::ident::{lowercase_ident, lowercase_ident_keyword_e};
@@ -148,9 +148,10 @@ fn term<'a>(stop_at_surface_has: bool) -> impl Parser<'a, Loc<TypeAnnotation<'a>
          (loc_ann, opt_as): (Loc<TypeAnnotation<'a>>, Option<(&'a [_], TypeHeader<'a>)>)| {
             match opt_as {
                 Some((spaces, alias)) => {
-                    let alias_vars_region =
-                        Region::across_all(alias.vars.iter().map(|v| &v.region));
-                    let region = Region::span_across(&loc_ann.region, &alias_vars_region);
+                    // The alias can be just a name, like the `Foo` in `[A, B] as Foo`.
+                    let alias_end_region =
+                        alias.vars.last().map_or(alias.name.region, |v| v.region);
+                    let region = Region::span_across(&loc_ann.region, &alias_end_region);
                     let value = TypeAnnotation::As(arena.alloc(loc_ann), spaces, alias);
 
                     Loc { region, value }
@@ -583,7 +584,7 @@ fn expression<'a>(
 
         let result = and(
             zero_or_more(skip_first(
-                byte(b',', EType::TFunctionArgument),
+                argument_comma(EType::TFunctionArgument),
                 one_of![
                     space0_around_ee(
                         term(stop_at_surface_has),
@@ -595,7 +596,11 @@ fn expression<'a>(
             ))
             .trace("type_annotation:expression:rest_args"),
             skip_second(
-                space0_e(EType::TIndentStart),
+                // The last argument can have a comma after it too
+                skip_first(
+                    optional(byte(b',', EType::TStart)),
+                    space0_e(EType::TIndentStart),
+                ),
                 two_bytes(b'-', b'>', EType::TStart),
             )
             .trace("type_annotation:expression:arrow"),
@@ -21,12 +21,12 @@ mod test_parse {
     use roc_parse::ast::Expr::{self, *};
     use roc_parse::ast::StrSegment::*;
     use roc_parse::ast::{self, EscapedChar};
-    use roc_parse::ast::{CommentOrNewline, StrLiteral::*};
-    use roc_parse::header::parse_module_defs;
-    use roc_parse::parser::SyntaxError;
-    use roc_parse::state::State;
+    use roc_parse::ast::{CommentOrNewline, ExtractSpaces, StrLiteral::*};
+    use roc_parse::header::{parse_header, parse_module_defs, parse_module_defs_with_warnings};
+    use roc_parse::parser::{EType, ParseWarning, SyntaxError};
+    use roc_parse::state::{State, Tabs};
     use roc_parse::test_helpers::parse_expr_with;
-    use roc_region::all::{Loc, Region};
+    use roc_region::all::{Loc, Position, Region};
     use std::{f64, i64};
 
     fn assert_parses_to<'a>(input: &'a str, expected_expr: Expr<'a>) {
@@ -402,6 +402,99 @@ mod test_parse {
         );
     }
 
+    // ENTRY POINTS
+
+    #[test]
+    fn parse_expr_entry_point() {
+        let arena = Bump::new();
+        let parsed = roc_parse::parse_expr(&arena, "x = 1\nx").unwrap();
+
+        assert!(matches!(parsed.value, Expr::Defs(..)));
+        assert_eq!(
+            parsed.region,
+            Region::new(Position::new(0), Position::new(7))
+        );
+
+        let err = roc_parse::parse_expr(&arena, "x +").unwrap_err();
+
+        assert!(matches!(err.problem, SyntaxError::Expr(..)));
+        assert_eq!(err.bytes, b"x +");
+    }
+
+    #[test]
+    fn parse_defs_entry_point() {
+        let arena = Bump::new();
+        let defs = roc_parse::parse_defs(&arena, "x = 1\n\ny : Str\ny = \"hi\"\n").unwrap();
+
+        assert_eq!(defs.len(), 2);
+        assert!(roc_parse::parse_defs(&arena, "x = ]").is_err());
+    }
+
+    #[test]
+    fn parse_type_annotation_entry_point() {
+        let arena = Bump::new();
+        let annotation =
+            roc_parse::parse_type_annotation(&arena, " List Str -> Task {} [] ").unwrap();
+
+        assert!(matches!(
+            annotation.value.extract_spaces().item,
+            ast::TypeAnnotation::Function(..)
+        ));
+
+        let err = roc_parse::parse_type_annotation(&arena, "List Str )").unwrap_err();
+
+        assert!(matches!(
+            err.problem,
+            SyntaxError::Type(EType::TEnd(pos)) if pos == Position::new(9)
+        ));
+    }
+
+    // TABS
+
+    #[test]
+    fn expanded_tabs_count_as_columns() {
+        let arena = Bump::new();
+        // The second branch only lines up with the first if the tab is four columns wide
+        let src = "x =\n  when y is\n    A -> 1 # a comment\twith a tab\n  \tB -> 2\n";
+        let parse = |tabs| {
+            parse_module_defs(
+                &arena,
+                State::new(src.as_bytes()).with_tabs(tabs),
+                ast::Defs::default(),
+            )
+        };
+
+        assert!(parse(Tabs::Expand { width: 4 }).is_ok());
+        assert!(parse(Tabs::Expand { width: 8 }).is_err());
+        assert!(parse(Tabs::Reject).is_err());
+    }
+
+    // WARNINGS
+
+    #[test]
+    fn old_package_header_warning() {
+        let arena = Bump::new();
+        let src = "package \"pkg\" exposes [Foo] packages {}\n\nx = 1\n";
+        let (_, state) = parse_header(&arena, State::new(src.as_bytes())).unwrap();
+        let (_, warnings) =
+            parse_module_defs_with_warnings(&arena, state, ast::Defs::default()).unwrap();
+
+        assert_eq!(
+            warnings,
+            [ParseWarning::OldPackageHeader(Region::new(
+                Position::new(8),
+                Position::new(13)
+            ))]
+        );
+
+        let src = "package [Foo] {}\n\nx = 1\n";
+        let (_, state) = parse_header(&arena, State::new(src.as_bytes())).unwrap();
+        let (_, warnings) =
+            parse_module_defs_with_warnings(&arena, state, ast::Defs::default()).unwrap();
+
+        assert_eq!(warnings, []);
+    }
+
     // PARSE ERROR
 
     // TODO this should be parse error, but isn't!
@@ -0,0 +1,154 @@
+//! Checks that the parser doesn't panic, loop forever, or give regions outside of the source,
+//! however broken the source is. The fuzz targets in `fuzz/` check the same thing, for longer.
+
+use proptest::prelude::*;
+use roc_parse::test_helpers::check_parser_invariants;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+/// Parsing anything this short takes milliseconds, so it must be stuck in a loop.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Bits of Roc syntax, for putting together source that's nearly valid.
+const TOKENS: &[&str] = &[
+    "x",
+    "foo",
+    "Foo",
+    "Foo.bar",
+    "1",
+    "0x1F",
+    "1.5e3",
+    "\"a\"",
+    "\"$(x)\"",
+    "\"\"\"",
+    "'a'",
+    " ",
+    "\n",
+    "\n    ",
+    "=",
+    ":",
+    ":=",
+    "->",
+    "<-",
+    "\\",
+    "|>",
+    "+",
+    "-",
+    "*",
+    "==",
+    "&&",
+    "!",
+    "?",
+    ",",
+    ".",
+    "..",
+    "&",
+    "_",
+    "@Foo",
+    "(",
+    ")",
+    "[",
+    "]",
+    "{",
+    "}",
+    "#",
+    "##",
+    "if",
+    "then",
+    "else",
+    "when",
+    "is",
+    "as",
+    "import",
+    "exposing",
+    "expect",
+    "dbg",
+    "implements",
+    "where",
+    "module",
+    "app",
+    "platform",
+    "package",
+];
+
+fn snapshots() -> Vec<String> {
+    let snapshots_dir =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../test_syntax/tests/snapshots");
+    let mut snapshots = Vec::new();
+
+    for kind in ["pass", "fail", "malformed"] {
+        for entry in std::fs::read_dir(snapshots_dir.join(kind)).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.extension().map_or(false, |ext| ext == "roc") {
+                snapshots.push(std::fs::read_to_string(path).unwrap());
+            }
+        }
+    }
+
+    snapshots
+}
+
+fn token_soup() -> impl Strategy<Value = String> {
+    proptest::collection::vec(proptest::sample::select(TOKENS), 0..40)
+        .prop_map(|tokens| tokens.concat())
+}
+
+/// One of the syntax snapshots, with a range of it replaced by a token.
+fn broken_snapshot() -> impl Strategy<Value = String> {
+    (
+        proptest::sample::select(snapshots()),
+        any::<proptest::sample::Index>(),
+        0..8usize,
+        proptest::sample::select(TOKENS),
+    )
+        .prop_map(|(mut src, start, len, token)| {
+            let mut start = start.index(src.len() + 1);
+            let mut end = (start + len).min(src.len());
+
+            while !src.is_char_boundary(start) {
+                start -= 1;
+            }
+
+            while !src.is_char_boundary(end) {
+                end += 1;
+            }
+
+            src.replace_range(start..end, token);
+            src
+        })
+}
+
+fn check_with_timeout(src: String) {
+    let (sender, receiver) = mpsc::channel();
+    let thread_src = src.clone();
+    let thread = std::thread::spawn(move || {
+        check_parser_invariants(&thread_src);
+        let _ = sender.send(());
+    });
+
+    match receiver.recv_timeout(TIMEOUT) {
+        Ok(()) => {}
+        Err(RecvTimeoutError::Disconnected) => {
+            if let Err(panic) = thread.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+        Err(RecvTimeoutError::Timeout) => {
+            panic!("Parsing this didn't finish after {TIMEOUT:?}:\n\n{src}")
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn parse_token_soup(src in token_soup()) {
+        check_with_timeout(src);
+    }
+
+    #[test]
+    fn parse_broken_snapshots(src in broken_snapshot()) {
+        check_with_timeout(src);
+    }
+}
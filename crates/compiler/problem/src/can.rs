@@ -6,6 +6,7 @@ use roc_module::called_via::BinOp;
 use roc_module::ident::{Ident, Lowercase, ModuleName, TagName};
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_parse::ast::Base;
+use roc_parse::parser::ParseWarning;
 use roc_parse::pattern::PatternType;
 use roc_region::all::{Loc, Region};
 use roc_types::types::AliasKind;
@@ -30,6 +31,19 @@ pub enum ShadowKind {
     Alias(Symbol),
     Opaque(Symbol),
     Ability(Symbol),
+    /// A builtin value brought into scope by an import's `exposing` list, like `List.map`
+    Builtin(Symbol),
+}
+
+impl ShadowKind {
+    /// The kind of shadow for a variable with the same name as `original`.
+    pub fn of_variable(original: Symbol) -> Self {
+        if original.is_builtin() {
+            ShadowKind::Builtin(original)
+        } else {
+            ShadowKind::Variable
+        }
+    }
 }
 
 /// Problems that can occur in the course of canonicalization.
@@ -38,7 +52,16 @@ pub enum Problem {
     UnusedDef(Symbol, Region),
     UnusedImport(Symbol, Region),
     UnusedModuleImport(ModuleId, Region),
-    ExposedButNotDefined(Symbol),
+    ExposedButNotDefined {
+        symbol: Symbol,
+        /// The entry in the header's `exposes` list
+        region: Region,
+        /// The names of the module's top-level definitions, to suggest from
+        top_level_names: MutSet<Box<str>>,
+    },
+    /// A top-level definition has a doc comment, as if it were part of the module's API, but it
+    /// isn't listed in `exposes`.
+    DocumentedButNotExposed(Symbol, Region),
     ImportNameConflict {
         name: ModuleName,
         is_alias: bool,
@@ -54,6 +77,23 @@ pub enum Problem {
         existing_symbol_region: Region,
     },
     DeprecatedBackpassing(Region),
+    /// A function whose doc comment says it's tail-recursive calls itself somewhere other than in
+    /// tail position, or never calls itself at all.
+    NotTailRecursive {
+        symbol: Symbol,
+        /// The function's name in its definition
+        region: Region,
+        /// The calls the function makes to itself which aren't in tail position
+        non_tail_calls: Vec<Region>,
+    },
+    /// A use of a definition whose doc comment marks it as deprecated.
+    DeprecatedUse {
+        symbol: Symbol,
+        region: Region,
+        message: Box<str>,
+    },
+    /// Syntax the parser still accepts, but which is going away.
+    ParseWarning(ParseWarning),
     /// First symbol is the name of the closure with that argument
     /// Bool is whether the closure is anonymous
     /// Second symbol is the name of the argument that is unused
@@ -68,7 +108,7 @@ pub enum Problem {
         shadow: Loc<Ident>,
         kind: ShadowKind,
     },
-    CyclicAlias(Symbol, Region, Vec<Symbol>, AliasKind),
+    CyclicAlias(Symbol, Region, Vec<Loc<Symbol>>, AliasKind),
     BadRecursion(Vec<CycleEntry>),
     PhantomTypeArgument {
         typ: Symbol,
@@ -242,6 +282,22 @@ pub enum Problem {
         one_occurrence: Region,
         kind: AliasKind,
     },
+    /// A value's name isn't camelCase, e.g. `my_value`. Only reported by `roc lint`.
+    NonCamelCaseName {
+        symbol: Symbol,
+        region: Region,
+        suggestion: String,
+    },
+    /// `if x then Bool.true else Bool.false` (or the other way around, if `negated`),
+    /// which could just be `x`. Only reported by `roc lint`.
+    RedundantIf {
+        region: Region,
+        negated: bool,
+    },
+    /// A `when` whose only branch matches everything. Only reported by `roc lint`.
+    SingleBranchWhen {
+        region: Region,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -264,7 +320,11 @@ impl Problem {
             Problem::ExplicitBuiltinTypeImport(_, _) => Warning,
             Problem::ImportShadowsSymbol { .. } => RuntimeError,
             Problem::DeprecatedBackpassing(_) => Warning,
-            Problem::ExposedButNotDefined(_) => RuntimeError,
+            Problem::DeprecatedUse { .. } => Warning,
+            Problem::NotTailRecursive { .. } => Warning,
+            Problem::ParseWarning(_) => Warning,
+            Problem::ExposedButNotDefined { .. } => RuntimeError,
+            Problem::DocumentedButNotExposed(_, _) => Warning,
             Problem::UnusedArgument(_, _, _, _) => Warning,
             Problem::UnusedBranchDef(_, _) => Warning,
             Problem::PrecedenceProblem(_) => RuntimeError,
@@ -323,6 +383,9 @@ impl Problem {
             Problem::OverAppliedDbg { .. } => RuntimeError,
             Problem::DefsOnlyUsedInRecursion(_, _) => Warning,
             Problem::FileProblem { .. } => Fatal,
+            Problem::NonCamelCaseName { .. } => Warning,
+            Problem::RedundantIf { .. } => Warning,
+            Problem::SingleBranchWhen { .. } => Warning,
         }
     }
 
@@ -349,6 +412,10 @@ impl Problem {
             | Problem::ExplicitBuiltinTypeImport(_, region)
             | Problem::ImportShadowsSymbol { region, .. }
             | Problem::DeprecatedBackpassing(region)
+            | Problem::DeprecatedUse { region, .. }
+            | Problem::NotTailRecursive { region, .. }
+            | Problem::ExposedButNotDefined { region, .. }
+            | Problem::DocumentedButNotExposed(_, region)
             | Problem::UnusedArgument(_, _, _, region)
             | Problem::UnusedBranchDef(_, region)
             | Problem::PrecedenceProblem(PrecedenceProblem::BothNonAssociative(region, _, _))
@@ -485,7 +552,11 @@ impl Problem {
             | Problem::UnappliedCrash { region }
             | Problem::OverAppliedDbg { region }
             | Problem::UnappliedDbg { region }
+            | Problem::NonCamelCaseName { region, .. }
+            | Problem::RedundantIf { region, .. }
+            | Problem::SingleBranchWhen { region }
             | Problem::DefsOnlyUsedInRecursion(_, region) => Some(*region),
+            Problem::ParseWarning(warning) => Some(warning.region()),
             Problem::RuntimeError(RuntimeError::CircularDef(cycle_entries))
             | Problem::BadRecursion(cycle_entries) => {
                 cycle_entries.first().map(|entry| entry.expr_region)
@@ -497,8 +568,7 @@ impl Problem {
             | Problem::RuntimeError(RuntimeError::VoidValue)
             | Problem::RuntimeError(RuntimeError::ExposedButNotDefined(_))
             | Problem::RuntimeError(RuntimeError::NoImplementationNamed { .. })
-            | Problem::FileProblem { .. }
-            | Problem::ExposedButNotDefined(_) => None,
+            | Problem::FileProblem { .. } => None,
         }
     }
 }
@@ -627,8 +697,13 @@ pub enum RuntimeError {
     ModuleNotImported {
         /// The name of the module that was referenced
         module_name: ModuleName,
+        /// The name that was looked up in the module, e.g. `map` in `Lst.map`
+        ident: Ident,
         /// A list of modules which *have* been imported
         imported_modules: MutSet<Box<str>>,
+        /// The imported modules which expose `ident`, so that e.g. `Lst.map` can
+        /// suggest `List.map` rather than just `List`
+        modules_exposing_ident: MutSet<Box<str>>,
         /// Where the problem occurred
         region: Region,
         /// Whether or not the module exists at all
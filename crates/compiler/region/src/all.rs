@@ -476,3 +476,32 @@ fn test_line_info() {
 
     check_correctness(&["", ""]);
 }
+
+#[test]
+fn test_line_info_past_u16() {
+    // Lines and columns used to be u16s, so make sure neither wraps around.
+    let long_line = "x".repeat(70_000);
+    let input = format!("{}{long_line}\nend", "\n".repeat(70_000));
+    let info = LineInfo::new(&input);
+
+    assert_eq!(info.num_lines(), 70_002);
+
+    let end_of_long_line = (input.len() - "\nend".len()) as u32;
+    let lc = LineColumn {
+        line: 70_000,
+        column: 70_000,
+    };
+
+    assert_eq!(info.convert_offset(end_of_long_line), lc);
+    assert_eq!(
+        info.convert_line_column(lc),
+        Position::new(end_of_long_line)
+    );
+    assert_eq!(
+        info.convert_offset(input.len() as u32),
+        LineColumn {
+            line: 70_001,
+            column: 3,
+        }
+    );
+}
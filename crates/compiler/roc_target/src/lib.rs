@@ -140,6 +140,16 @@ impl Target {
         }
     }
 
+    pub const fn shared_library_file_ext(&self) -> &str {
+        use Target::*;
+        match self {
+            LinuxX32 | LinuxX64 | LinuxArm64 => "so",
+            MacX64 | MacArm64 => "dylib",
+            WinX32 | WinX64 | WinArm64 => "dll",
+            Wasm32 => "wasm",
+        }
+    }
+
     pub const fn executable_file_ext(&self) -> Option<&str> {
         use Target::*;
         match self {
@@ -172,7 +182,11 @@ impl FromStr for Target {
             "windows-x64" => Ok(WinX64),
             "windows-arm64" => Ok(WinArm64),
             "wasm32" => Ok(Wasm32),
-            _ => Err(ParseError::InvalidTargetString),
+            // Also accept target triples, e.g. `x86_64-unknown-linux-gnu` or `aarch64-apple-darwin`
+            _ => Triple::from_str(s)
+                .ok()
+                .and_then(|triple| Target::from_triple(&triple).ok())
+                .ok_or(ParseError::InvalidTargetString),
         }
     }
 }
@@ -208,55 +222,66 @@ impl Default for Target {
     }
 }
 
-impl From<&Triple> for Target {
-    fn from(triple: &Triple) -> Self {
+impl Target {
+    /// Like `Target::from(triple)`, except it returns an error instead of exiting
+    /// if the triple is not supported.
+    pub fn from_triple(triple: &Triple) -> Result<Self, TargetFromTripleError> {
         use target_lexicon::*;
         match triple {
             Triple {
                 architecture: Architecture::X86_32(_),
                 operating_system: OperatingSystem::Linux,
                 ..
-            } => Target::LinuxX32,
+            } => Ok(Target::LinuxX32),
             Triple {
                 architecture: Architecture::X86_64,
                 operating_system: OperatingSystem::Linux,
                 ..
-            } => Target::LinuxX64,
+            } => Ok(Target::LinuxX64),
             Triple {
                 architecture: Architecture::Aarch64(_),
                 operating_system: OperatingSystem::Linux,
                 ..
-            } => Target::LinuxArm64,
+            } => Ok(Target::LinuxArm64),
             Triple {
                 architecture: Architecture::X86_32(_),
                 operating_system: OperatingSystem::Windows,
                 ..
-            } => Target::WinX32,
+            } => Ok(Target::WinX32),
             Triple {
                 architecture: Architecture::X86_64,
                 operating_system: OperatingSystem::Windows,
                 ..
-            } => Target::WinX64,
+            } => Ok(Target::WinX64),
             Triple {
                 architecture: Architecture::Aarch64(_),
                 operating_system: OperatingSystem::Windows,
                 ..
-            } => Target::WinArm64,
+            } => Ok(Target::WinArm64),
             Triple {
                 architecture: Architecture::X86_64,
                 operating_system: OperatingSystem::MacOSX { .. } | OperatingSystem::Darwin,
                 ..
-            } => Target::MacX64,
+            } => Ok(Target::MacX64),
             Triple {
                 architecture: Architecture::Aarch64(_),
                 operating_system: OperatingSystem::MacOSX { .. } | OperatingSystem::Darwin,
                 ..
-            } => Target::MacArm64,
+            } => Ok(Target::MacArm64),
             Triple {
                 architecture: Architecture::Wasm32,
                 ..
-            } => Target::Wasm32,
-            _ => {
+            } => Ok(Target::Wasm32),
+            _ => Err(TargetFromTripleError::TripleUnsupported),
+        }
+    }
+}
+
+impl From<&Triple> for Target {
+    fn from(triple: &Triple) -> Self {
+        match Target::from_triple(triple) {
+            Ok(target) => target,
+            Err(TargetFromTripleError::TripleUnsupported) => {
                 user_error!("Target triple ({}) is not currently supported by the roc compiler. Feel free to file an issue to request support", triple);
             }
         }
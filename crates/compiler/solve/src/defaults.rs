@@ -0,0 +1,233 @@
+//! Finding numbers whose type is never decided, and so gets defaulted when they're compiled.
+
+use roc_can::expr::{Declarations, Expr};
+use roc_can::traverse::{walk_decl, walk_expr, DeclarationInfo, Visitor};
+use roc_collections::MutSet;
+use roc_module::symbol::Symbol;
+use roc_region::all::Region;
+use roc_solve_problem::TypeError;
+use roc_types::num::{IntLitWidth, NumericRange};
+use roc_types::subs::{Content, FlatType, GetSubsSlice, LambdaSet, Subs, Variable};
+use roc_types::types::Uls;
+
+/// What to do about numbers whose type is ambiguous, which get compiled as an `I64` (or a `Dec`,
+/// if they have to be fractions).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NumericDefaults {
+    /// Pick the default type without saying anything.
+    #[default]
+    Silent,
+    /// Pick the default type, and warn about each number it was picked for.
+    Explain,
+    /// Report each number whose type would have been defaulted as an error.
+    Deny,
+}
+
+impl NumericDefaults {
+    /// The environment variable which picks how ambiguous numbers are handled: `explain` or
+    /// `deny`.
+    pub const VAR: &'static str = "ROC_NUMERIC_DEFAULTS";
+
+    /// Reads the mode from `ROC_NUMERIC_DEFAULTS`; anything other than `explain` or `deny` means
+    /// defaults are picked silently.
+    pub fn from_env() -> Self {
+        match std::env::var(Self::VAR).as_deref().map(str::trim) {
+            Ok("explain") => NumericDefaults::Explain,
+            Ok("deny") => NumericDefaults::Deny,
+            _ => NumericDefaults::Silent,
+        }
+    }
+}
+
+/// Reports each number in `decls` whose type nothing decides, and so would be defaulted.
+///
+/// A number is only reported once per type: in `Num.toStr (1 + 2)`, only the `1` is. Numbers
+/// whose type is part of the type of their top-level definition aren't reported, since that
+/// definition is polymorphic and each use of it decides the type.
+pub fn find_defaulted_numbers(
+    subs: &Subs,
+    decls: &Declarations,
+    mode: NumericDefaults,
+) -> Vec<TypeError> {
+    if mode == NumericDefaults::Silent {
+        return Vec::new();
+    }
+
+    let mut finder = DefaultFinder {
+        subs,
+        is_error: mode == NumericDefaults::Deny,
+        polymorphic: MutSet::default(),
+        reported: MutSet::default(),
+        problems: Vec::new(),
+    };
+
+    finder.visit_decls(decls);
+
+    finder.problems
+}
+
+struct DefaultFinder<'a> {
+    subs: &'a Subs,
+    is_error: bool,
+    /// Every variable in the type of the top-level definition being visited.
+    polymorphic: MutSet<Variable>,
+    /// The variables which have already been reported as defaulted.
+    reported: MutSet<Variable>,
+    problems: Vec<TypeError>,
+}
+
+impl Visitor for DefaultFinder<'_> {
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        self.polymorphic.clear();
+
+        match &decl {
+            DeclarationInfo::Value { expr_var, .. }
+            | DeclarationInfo::Function { expr_var, .. }
+            | DeclarationInfo::Destructure { expr_var, .. } => {
+                collect_variables(self.subs, *expr_var, &mut self.polymorphic)
+            }
+            DeclarationInfo::Expectation { .. } => {}
+        }
+
+        walk_decl(self, decl);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if let Expr::Num(..)
+        | Expr::Int(..)
+        | Expr::Float(..)
+        | Expr::SingleQuote(..)
+        | Expr::Var(..) = expr
+        {
+            if let Some((precision_var, default)) = defaulted_precision(self.subs, var) {
+                let root = self.subs.get_root_key_without_compacting(precision_var);
+
+                if !self.polymorphic.contains(&root) && self.reported.insert(root) {
+                    self.problems.push(TypeError::DefaultedNumber {
+                        region,
+                        default,
+                        is_error: self.is_error,
+                    });
+                }
+            }
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+/// If `var` is a number whose precision hasn't been decided, returns the variable for that
+/// precision, along with the type the number will be compiled as.
+fn defaulted_precision(subs: &Subs, var: Variable) -> Option<(Variable, IntLitWidth)> {
+    match *subs.get_content_without_compacting(var) {
+        Content::Alias(Symbol::NUM_NUM, args, _, _) => {
+            let precision_var = *subs.get_subs_slice(args.type_variables()).first()?;
+
+            match *subs.get_content_without_compacting(precision_var) {
+                Content::FlexVar(_) => Some((precision_var, IntLitWidth::I64)),
+                Content::RangedNumber(range) => {
+                    Some((precision_var, range.default_compilation_width()))
+                }
+                Content::Alias(Symbol::NUM_INTEGER, args, _, _) => {
+                    let int_var = *subs.get_subs_slice(args.type_variables()).first()?;
+
+                    match *subs.get_content_without_compacting(int_var) {
+                        Content::FlexVar(_) => Some((int_var, IntLitWidth::I64)),
+                        Content::RangedNumber(range) => {
+                            Some((int_var, range.default_compilation_width()))
+                        }
+                        _ => None,
+                    }
+                }
+                Content::Alias(Symbol::NUM_FLOATINGPOINT, args, _, _) => {
+                    let frac_var = *subs.get_subs_slice(args.type_variables()).first()?;
+
+                    match *subs.get_content_without_compacting(frac_var) {
+                        Content::FlexVar(_)
+                        | Content::RangedNumber(
+                            NumericRange::NumAtLeastSigned(_)
+                            | NumericRange::NumAtLeastEitherSign(_),
+                        ) => Some((frac_var, IntLitWidth::Dec)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        Content::Alias(Symbol::NUM_INT | Symbol::NUM_FRAC, _, real_var, _) => {
+            defaulted_precision(subs, real_var)
+        }
+        _ => None,
+    }
+}
+
+/// Adds the root of every variable reachable from `var` to `vars`.
+fn collect_variables(subs: &Subs, var: Variable, vars: &mut MutSet<Variable>) {
+    let mut stack = vec![var];
+
+    while let Some(var) = stack.pop() {
+        if !vars.insert(subs.get_root_key_without_compacting(var)) {
+            continue;
+        }
+
+        match subs.get_content_without_compacting(var) {
+            Content::FlexVar(_)
+            | Content::RigidVar(_)
+            | Content::FlexAbleVar(..)
+            | Content::RigidAbleVar(..)
+            | Content::ErasedLambda
+            | Content::RangedNumber(_)
+            | Content::Error => {}
+            Content::RecursionVar { structure, .. } => stack.push(*structure),
+            Content::Alias(_, args, real_var, _) => {
+                stack.extend(subs.get_subs_slice(args.all_variables()));
+                stack.push(*real_var);
+            }
+            Content::LambdaSet(LambdaSet {
+                solved,
+                recursion_var,
+                unspecialized,
+                ambient_function: _,
+            }) => {
+                for slice_index in solved.variables() {
+                    stack.extend(subs.get_subs_slice(subs[slice_index]));
+                }
+
+                stack.extend(recursion_var.into_variable());
+
+                for Uls(var, _, _) in subs.get_subs_slice(*unspecialized) {
+                    stack.push(*var);
+                }
+            }
+            Content::Structure(flat_type) => match flat_type {
+                FlatType::Apply(_, args) => stack.extend(subs.get_subs_slice(*args)),
+                FlatType::Func(args, closure_var, ret_var) => {
+                    stack.extend(subs.get_subs_slice(*args));
+                    stack.push(*closure_var);
+                    stack.push(*ret_var);
+                }
+                FlatType::Record(fields, ext) => {
+                    stack.extend(subs.get_subs_slice(fields.variables()));
+                    stack.push(*ext);
+                }
+                FlatType::Tuple(elems, ext) => {
+                    stack.extend(subs.get_subs_slice(elems.variables()));
+                    stack.push(*ext);
+                }
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    for slice_index in tags.variables() {
+                        stack.extend(subs.get_subs_slice(subs[slice_index]));
+                    }
+
+                    stack.push(ext.var());
+
+                    if let FlatType::RecursiveTagUnion(rec_var, _, _) = flat_type {
+                        stack.push(*rec_var);
+                    }
+                }
+                FlatType::FunctionOrTagUnion(_, _, ext) => stack.push(ext.var()),
+                FlatType::EmptyRecord | FlatType::EmptyTuple | FlatType::EmptyTagUnion => {}
+            },
+        }
+    }
+}
@@ -13,12 +13,14 @@ pub mod specialize;
 
 mod aliases;
 mod deep_copy;
+mod defaults;
 mod env;
 mod kinds;
 mod pools;
 mod to_var;
 
 pub use aliases::Aliases;
+pub use defaults::{find_defaulted_numbers, NumericDefaults};
 pub use env::{DerivedEnv, InferenceEnv, SolveEnv};
 pub use kinds::FunctionKind;
 pub use pools::Pools;
@@ -24,14 +24,16 @@ use roc_debug_flags::ROC_VERIFY_RIGID_LET_GENERALIZED;
 use roc_error_macros::internal_error;
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_problem::can::CycleEntry;
-use roc_region::all::Loc;
+use roc_region::all::{Loc, Region};
 use roc_solve_problem::TypeError;
 use roc_solve_schema::UnificationMode;
 use roc_types::subs::{
     self, Content, FlatType, GetSubsSlice, Mark, OptVariable, Rank, Subs, TagExt, UlsOfVar,
     Variable,
 };
-use roc_types::types::{Category, Polarity, Reason, RecordField, Type, TypeExtension, Types, Uls};
+use roc_types::types::{
+    Category, Polarity, Reason, RecordField, Type, TypeExtension, TypeTag, Types, Uls,
+};
 use roc_unify::unify::{
     unify, unify_introduced_ability_specialization, Obligated, SpecializationLsetCollector,
     Unified::*,
@@ -204,7 +206,8 @@ enum Work<'a> {
         rank: Rank,
         constraint: &'a Constraint,
     },
-    CheckForInfiniteTypes(LocalDefVarsVec<(Symbol, Loc<Variable>)>),
+    /// Holds the variables a let-binding introduced, and the constraint of its defs.
+    CheckForInfiniteTypes(LocalDefVarsVec<(Symbol, Loc<Variable>)>, &'a Constraint),
     /// The ret_con part of a let constraint that does NOT introduces rigid and/or flex variables
     LetConNoVariables {
         scope: &'a Scope,
@@ -272,11 +275,18 @@ fn solve(
                 // the default case; actually solve this constraint
                 (scope, rank, constraint)
             }
-            Work::CheckForInfiniteTypes(def_vars) => {
+            Work::CheckForInfiniteTypes(def_vars, defs_constraint) => {
                 // after a LetCon, we must check if any of the variables that we introduced
                 // loop back to themselves after solving the ret_constraint
                 for (symbol, loc_var) in def_vars.iter() {
-                    check_for_infinite_type(env, problems, *symbol, *loc_var);
+                    check_for_infinite_type(
+                        env,
+                        &can_types,
+                        problems,
+                        *symbol,
+                        *loc_var,
+                        defs_constraint,
+                    );
                 }
 
                 continue;
@@ -289,6 +299,7 @@ fn solve(
             } => {
                 // NOTE be extremely careful with shadowing here
                 let offset = let_con.defs_and_ret_constraint.index();
+                let defs_constraint = &env.constraints.constraints[offset];
                 let ret_constraint = &env.constraints.constraints[offset + 1];
 
                 // Add a variable for each def to new_vars_by_env.
@@ -327,7 +338,7 @@ fn solve(
                     constraint: ret_constraint,
                 });
                 // Check for infinite types first
-                stack.push(Work::CheckForInfiniteTypes(local_def_vars));
+                stack.push(Work::CheckForInfiniteTypes(local_def_vars, defs_constraint));
 
                 continue;
             }
@@ -339,6 +350,7 @@ fn solve(
             } => {
                 // NOTE be extremely careful with shadowing here
                 let offset = let_con.defs_and_ret_constraint.index();
+                let defs_constraint = &env.constraints.constraints[offset];
                 let ret_constraint = &env.constraints.constraints[offset + 1];
 
                 let mark = state.mark;
@@ -465,7 +477,7 @@ fn solve(
                     constraint: ret_constraint,
                 });
                 // Check for infinite types first
-                stack.push(Work::CheckForInfiniteTypes(local_def_vars));
+                stack.push(Work::CheckForInfiniteTypes(local_def_vars, defs_constraint));
 
                 state = state_for_ret_con;
 
@@ -1630,6 +1642,11 @@ fn close_pattern_matched_tag_unions(subs: &mut Subs, var: Variable) {
                 stack.extend(subs.get_subs_slice(fields.variables()));
             }
 
+            Structure(Tuple(elems, _)) => {
+                // Close up all nested tag unions.
+                stack.extend(subs.get_subs_slice(elems.variables()));
+            }
+
             Structure(Apply(Symbol::LIST_LIST, args)) => {
                 // Close up nested tag unions.
                 stack.extend(subs.get_subs_slice(args));
@@ -1929,9 +1946,11 @@ impl LocalDefVarsVec<(Symbol, Loc<Variable>)> {
 
 fn check_for_infinite_type(
     env: &mut InferenceEnv,
+    can_types: &Types,
     problems: &mut Vec<TypeError>,
     symbol: Symbol,
     loc_var: Loc<Variable>,
+    defs_constraint: &Constraint,
 ) {
     let var = loc_var.value;
 
@@ -1966,19 +1985,82 @@ fn check_for_infinite_type(
             }
         }
 
-        circular_error(env.subs, problems, symbol, &loc_var);
+        let self_referential_arg =
+            find_self_referential_arg(env, can_types, defs_constraint, loc_var.region);
+
+        circular_error(env.subs, problems, symbol, &loc_var, self_referential_arg);
     }
 }
 
+/// Finds the function argument most likely to have introduced an infinite type in these defs:
+/// the first one after the def's name whose type now contains itself. For example the second `g`
+/// in `f = \g -> g g`.
+fn find_self_referential_arg(
+    env: &mut InferenceEnv,
+    can_types: &Types,
+    defs_constraint: &Constraint,
+    def_region: Region,
+) -> Option<Region> {
+    let constraints = env.constraints;
+    let mut stack = vec![defs_constraint];
+    let mut first: Option<Region> = None;
+
+    while let Some(constraint) = stack.pop() {
+        match constraint {
+            Eq(roc_can::constraint::Eq(_, expectation_index, _, region))
+            | Lookup(_, expectation_index, region) => {
+                let expectation = &constraints.expectations[expectation_index.index()];
+
+                let arg_type_index = match expectation {
+                    Expected::ForReason(Reason::FnArg { .. }, type_index, _) => type_index,
+                    _ => continue,
+                };
+
+                let var = match arg_type_index.split() {
+                    Ok(type_index) => match can_types[type_index] {
+                        TypeTag::Variable(var) => var,
+                        _ => continue,
+                    },
+                    // the variable is stored directly in the index
+                    Err(var_index) => unsafe { Variable::from_index(var_index.index() as _) },
+                };
+
+                let is_earlier =
+                    |other: Region| (region.start(), region.len()) < (other.start(), other.len());
+
+                if region.start() >= def_region.end()
+                    && first.map_or(true, is_earlier)
+                    && env.subs.occurs(var).is_err()
+                {
+                    first = Some(*region);
+                }
+            }
+            And(slice) => {
+                stack.extend(constraints.constraints[slice.indices()].iter());
+            }
+            Let(index, _) => {
+                let let_con = &constraints.let_constraints[index.index()];
+                let offset = let_con.defs_and_ret_constraint.index();
+
+                stack.extend(constraints.constraints[offset..offset + 2].iter());
+            }
+            _ => {}
+        }
+    }
+
+    first
+}
+
 fn circular_error(
     subs: &mut Subs,
     problems: &mut Vec<TypeError>,
     symbol: Symbol,
     loc_var: &Loc<Variable>,
+    self_referential_arg: Option<Region>,
 ) {
     let var = loc_var.value;
     let error_type = subs.var_to_error_type(var, Polarity::OF_VALUE);
-    let problem = TypeError::CircularType(loc_var.region, symbol, error_type);
+    let problem = TypeError::CircularType(loc_var.region, symbol, error_type, self_referential_arg);
 
     subs.set_content(var, Content::Error);
 
@@ -9,13 +9,16 @@ use roc_module::{
 use roc_problem::{can::CycleEntry, Severity};
 use roc_region::all::Region;
 
+use roc_types::num::IntLitWidth;
 use roc_types::types::{Category, ErrorType, PatternCategory};
 
 #[derive(Debug, Clone)]
 pub enum TypeError {
     BadExpr(Region, Category, ErrorType, Expected<ErrorType>),
     BadPattern(Region, PatternCategory, ErrorType, PExpected<ErrorType>),
-    CircularType(Region, Symbol, ErrorType),
+    /// The last region is the function argument that most likely made the type refer to
+    /// itself, if there is one.
+    CircularType(Region, Symbol, ErrorType, Option<Region>),
     CircularDef(Vec<CycleEntry>),
     UnexposedLookup(Region, Symbol),
     UnfulfilledAbility(Unfulfilled),
@@ -39,6 +42,13 @@ pub enum TypeError {
     UnexpectedModuleParams(Region, ModuleId),
     MissingModuleParams(Region, ModuleId, ErrorType),
     ModuleParamsMismatch(Region, ModuleId, ErrorType, ErrorType),
+    /// A number whose type nothing decides, so it will be compiled as `default`. This is only
+    /// reported when asked for, as an error if `is_error` is set.
+    DefaultedNumber {
+        region: Region,
+        default: IntLitWidth,
+        is_error: bool,
+    },
 }
 
 impl TypeError {
@@ -63,6 +73,10 @@ impl TypeError {
             TypeError::ModuleParamsMismatch(..) => RuntimeError,
             TypeError::IngestedFileBadUtf8(..) => Fatal,
             TypeError::IngestedFileUnsupportedType(..) => Fatal,
+            TypeError::DefaultedNumber { is_error: true, .. } => RuntimeError,
+            TypeError::DefaultedNumber {
+                is_error: false, ..
+            } => Warning,
         }
     }
 
@@ -78,7 +92,8 @@ impl TypeError {
             | TypeError::BadPatternMissingAbility(region, ..)
             | TypeError::UnexpectedModuleParams(region, ..)
             | TypeError::MissingModuleParams(region, ..)
-            | TypeError::ModuleParamsMismatch(region, ..) => Some(*region),
+            | TypeError::ModuleParamsMismatch(region, ..)
+            | TypeError::DefaultedNumber { region, .. } => Some(*region),
             TypeError::UnfulfilledAbility(ab, ..) => ab.region(),
             TypeError::Exhaustive(e) => Some(e.region()),
             TypeError::CircularDef(c) => c.first().map(|ce| ce.symbol_region),
@@ -0,0 +1,119 @@
+#[path = "../src/helpers/mod.rs"]
+mod helpers;
+
+// defines roc_alloc and friends
+pub use helpers::platform_functions::*;
+
+use bumpalo::Bump;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult, run_roc_dylib};
+use roc_mono::ir::OptLevel;
+use roc_std::RocList;
+
+// Compiles the same programs at `-O1`, `-O2`, and `-O3`, to check that each optimization level
+// is worth its extra compile time.
+
+type Input = RocList<i64>;
+type Output = i64;
+
+type Main<I, O> = unsafe extern "C" fn(I, *mut RocCallResult<O>);
+
+const OPT_LEVELS: [(&str, OptLevel); 3] = [
+    ("-O1", OptLevel::Less),
+    ("-O2", OptLevel::Moderate),
+    ("-O3", OptLevel::Optimize),
+];
+
+// a tight loop, which benefits from unrolling and vectorization
+const ROC_SUM_OF_SQUARES: &str = indoc::indoc!(
+    r#"
+    app "bench" provides [main] to "./platform"
+
+    main : List I64 -> I64
+    main = \list ->
+        List.walk list 0 \sum, x -> sum + x * x
+    "#
+);
+
+// small functions calling each other, which benefit from inlining
+const ROC_PIPELINE: &str = indoc::indoc!(
+    r#"
+    app "bench" provides [main] to "./platform"
+
+    main : List I64 -> I64
+    main = \list ->
+        list
+        |> List.map (\x -> x + 1)
+        |> List.keepIf isEven
+        |> List.map (\x -> x * 3)
+        |> List.sum
+
+    isEven : I64 -> Bool
+    isEven = \x -> x % 2 == 0
+    "#
+);
+
+fn roc_function<'a, 'b>(
+    arena: &'a Bump,
+    source: &str,
+    opt_level: OptLevel,
+) -> libloading::Symbol<'a, Main<&'b Input, Output>> {
+    let config = helpers::llvm::HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        ignore_problems: false,
+        emit_debug_info: true,
+        opt_level,
+    };
+
+    let context = inkwell::context::Context::create();
+    let (main_fn_name, errors, lib) = helpers::llvm::helper(
+        arena,
+        config,
+        source,
+        arena.alloc(context),
+        roc_load::FunctionKind::LambdaSet,
+    );
+
+    assert!(errors.is_empty(), "Encountered errors:\n{errors}");
+
+    run_roc_dylib!(arena.alloc(lib), main_fn_name, &Input, Output)
+}
+
+fn create_input_list() -> RocList<i64> {
+    let numbers = Vec::from_iter(0..10_000);
+
+    RocList::from_slice(&numbers)
+}
+
+fn bench_opt_levels(c: &mut Criterion, arena: &Bump, name: &str, source: &str, input: &Input) {
+    let mut group = c.benchmark_group(name);
+
+    for (level_name, opt_level) in OPT_LEVELS {
+        let main = roc_function(arena, source, opt_level);
+
+        group.bench_function(level_name, |b| {
+            b.iter(|| unsafe {
+                let mut main_result = RocCallResult::default();
+
+                // the roc code will dec this list, so inc it first so it is not free'd
+                std::mem::forget(input.clone());
+
+                main(black_box(input), &mut main_result);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let arena = Bump::new();
+
+    let input = &*arena.alloc(create_input_list());
+
+    bench_opt_levels(c, &arena, "roc sum of squares", ROC_SUM_OF_SQUARES, input);
+    bench_opt_levels(c, &arena, "roc map keepIf sum", ROC_PIPELINE, input);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
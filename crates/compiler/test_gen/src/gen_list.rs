@@ -539,6 +539,26 @@ fn list_drop_first() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_walk_tail_recursive() {
+    // Self tail calls become loops in mono, so this doesn't overflow the stack in any backend.
+    assert_evals_to!(
+        indoc!(
+            r"
+                sumList = \list, total ->
+                    when List.first list is
+                        Ok x -> sumList (List.dropFirst list 1) (total + x)
+                        Err ListWasEmpty -> total
+
+                sumList (List.repeat 2 100_000) 0
+            "
+        ),
+        200_000,
+        i64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_drop_at() {
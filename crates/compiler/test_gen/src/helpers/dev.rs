@@ -1,6 +1,6 @@
 use libloading::Library;
 use roc_build::link::{link, LinkType};
-use roc_load::{EntryPoint, ExecutionMode, LoadConfig, Threading};
+use roc_load::{EntryPoint, ExecutionMode, LoadConfig, NumericDefaults, Tabs, Threading};
 use roc_mono::ir::CrashTag;
 use roc_mono::ir::SingleEntryPoint;
 use roc_packaging::cache::RocCacheDir;
@@ -62,6 +62,9 @@ pub fn helper(
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
         function_kind: FunctionKind::LambdaSet,
+        progress: None,
+        tabs: Tabs::Reject,
+        numeric_defaults: NumericDefaults::Silent,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
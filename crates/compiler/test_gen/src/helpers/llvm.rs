@@ -10,7 +10,8 @@ use roc_command_utils::zig;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult};
 use roc_load::{
-    EntryPoint, ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, Threading,
+    EntryPoint, ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, NumericDefaults,
+    Tabs, Threading,
 };
 use roc_mono::ir::{CrashTag, OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
@@ -76,6 +77,9 @@ fn create_llvm_module<'a>(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        progress: None,
+        tabs: Tabs::Reject,
+        numeric_defaults: NumericDefaults::Silent,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -139,7 +143,7 @@ fn create_llvm_module<'a>(
                 | UnusedModuleImport(_, _)
                 | RuntimeError(_)
                 | UnsupportedPattern(_, _)
-                | ExposedButNotDefined(_) => {
+                | ExposedButNotDefined { .. } => {
                     let report = can_problem(&alloc, &line_info, module_path.clone(), problem);
                     let mut buf = String::new();
 
@@ -182,7 +186,7 @@ fn create_llvm_module<'a>(
     }
 
     let builder = context.create_builder();
-    let module = roc_gen_llvm::llvm::build::module_from_builtins(target, context, "app");
+    let module = roc_gen_llvm::llvm::build::module_from_builtins(target, context, "app", false);
 
     let module = arena.alloc(module);
     let (module_pass, function_pass) =
@@ -230,6 +234,7 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        source_locations: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -258,6 +263,7 @@ fn create_llvm_module<'a>(
     let (main_fn_name, main_fn) = match config.mode {
         LlvmBackendMode::Binary => unreachable!(),
         LlvmBackendMode::BinaryDev => unreachable!(),
+        LlvmBackendMode::BinaryPrintExpects => unreachable!(),
         LlvmBackendMode::BinaryGlue => unreachable!(),
         LlvmBackendMode::CliTest => unreachable!(),
         LlvmBackendMode::WasmGenTest => roc_gen_llvm::llvm::build::build_wasm_test_wrapper(
@@ -18,6 +18,7 @@ use roc_load::FunctionKind;
 use roc_load::LoadConfig;
 use roc_load::LoadMonomorphizedError;
 use roc_load::Threading;
+use roc_load::{NumericDefaults, Tabs};
 use roc_module::symbol::Interns;
 use roc_module::symbol::Symbol;
 use roc_mono::ir::Proc;
@@ -158,6 +159,9 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        progress: None,
+        tabs: Tabs::Reject,
+        numeric_defaults: NumericDefaults::Silent,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
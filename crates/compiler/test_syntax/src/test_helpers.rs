@@ -3,6 +3,7 @@ use roc_fmt::{annotation::Formattable, header::fmt_header};
 use roc_parse::{
     ast::{Defs, Expr, FullAst, Header, Malformed, SpacesBefore},
     header::parse_module_defs,
+    highlight::{highlight, Token},
     normalize::Normalize,
     parser::{Parser, SyntaxError},
     state::State,
@@ -115,6 +116,14 @@ impl<'a> Output<'a> {
             Output::Full { .. } => format!("{self:#?}\n"),
         }
     }
+
+    /// How many comments are in the AST. The parser drops comments in a few places (like between
+    /// a type alias's name and its `:`), so this can be fewer than there were in the source.
+    pub fn comment_count(&self) -> usize {
+        let debug = format!("{self:?}");
+
+        debug.matches("LineComment(").count() + debug.matches("DocComment(").count()
+    }
 }
 
 impl<'a> Malformed for Output<'a> {
@@ -207,6 +216,24 @@ impl<'a> Input<'a> {
 
         handle_formatted_output(output.as_ref());
 
+        let comments_before = comments(self.as_str());
+        let comments_after = comments(output.as_ref().as_str());
+
+        // The formatter can't keep comments the parser didn't.
+        if actual.comment_count() == comments_before.len() && comments_before != comments_after {
+            panic!(
+                "Formatting bug; formatting didn't keep every comment\n\n\
+                * * * Source code before formatting:\n{}\n\n\
+                * * * Source code after formatting:\n{}\n\n\
+                * * * Comments before formatting:\n{:#?}\n\n\
+                * * * Comments after formatting:\n{:#?}\n\n",
+                self.as_str(),
+                output.as_ref().as_str(),
+                comments_before,
+                comments_after
+            );
+        }
+
         let reparsed_ast = output.as_ref().parse_in(&arena).unwrap_or_else(|err| {
             panic!(
                 "After formatting, the source code no longer parsed!\n\n\
@@ -260,3 +287,22 @@ impl<'a> Input<'a> {
         }
     }
 }
+
+/// The text of every comment in the source, sorted. The formatter is free to move comments, and
+/// to change the spaces after the `#`, but never to drop one.
+pub fn comments(src: &str) -> Vec<&str> {
+    let mut comments: Vec<&str> = highlight(src)
+        .into_iter()
+        .filter(|token| matches!(token.value, Token::LineComment | Token::DocComment))
+        .map(|token| {
+            let start = token.region.start().offset as usize;
+            let end = token.region.end().offset as usize;
+
+            src[start..end].trim_start_matches('#').trim()
+        })
+        .collect();
+
+    comments.sort_unstable();
+
+    comments
+}
@@ -0,0 +1,2 @@
+whn x is
+    A -> 1
@@ -0,0 +1 @@
+experct 1 == 1
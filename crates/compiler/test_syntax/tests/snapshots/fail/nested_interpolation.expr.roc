@@ -0,0 +1 @@
+"a $(Str.trim "$(x) ") b"
\ No newline at end of file
@@ -0,0 +1 @@
+r##"a "# b
\ No newline at end of file
@@ -0,0 +1,2 @@
+{ a: 1, # comment, more
+    5 }
\ No newline at end of file
@@ -0,0 +1 @@
+"Hello, $(name"
\ No newline at end of file
@@ -0,0 +1,2 @@
+f : Str, U8, -> U8
+f
@@ -1,3 +1,3 @@
 !
-"""
-"""
\ No newline at end of file
+    """
+    """
\ No newline at end of file
@@ -0,0 +1,10 @@
+platform "foo/barbaz"
+    requires {Model} { main : Task {} [] }
+    exposes [Task]
+    packages {}
+    imports []
+    provides [mainForHost]
+    hints {
+        Task: "Tasks only run when returned from main or awaited with `!`.",
+        Task.await:"Try `!` after the task instead of calling Task.await.",
+    }
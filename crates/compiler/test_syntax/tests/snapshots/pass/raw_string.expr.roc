@@ -0,0 +1,3 @@
+x = r##"C:\Users\"me"
+  indented "# ok"##
+x
\ No newline at end of file
@@ -4,15 +4,18 @@ extern crate indoc;
 #[cfg(test)]
 mod test_fmt {
     use bumpalo::Bump;
+    use roc_fmt::annotation::Formattable;
     use roc_fmt::def::fmt_defs;
-    use roc_fmt::header::fmt_header;
-    use roc_fmt::Buf;
-    use roc_parse::ast::{Defs, Header, SpacesBefore};
+    use roc_fmt::edits::{format_edits, TextEdit};
+    use roc_fmt::header::{fmt_header, sort_header_lists};
+    use roc_fmt::{BlankLines, Buf, HexCase, NumberLiterals, TrailingCommas};
+    use roc_parse::ast::{Defs, Header, Malformed, SpacesBefore};
     use roc_parse::header::{self, parse_module_defs};
     use roc_parse::state::State;
+    use roc_region::all::{Position, Region};
     use roc_test_utils::assert_multiline_str_eq;
     use roc_test_utils_dir::workspace_root;
-    use test_syntax::test_helpers::Input;
+    use test_syntax::test_helpers::{comments, Input, InputKind};
 
     fn check_formatting(expected: &'_ str) -> impl Fn(Input) + '_ {
         let expected = expected.trim();
@@ -1215,6 +1218,123 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn block_string_contents_indented_like_opening_quotes() {
+        expr_formats_to(
+            indoc!(
+                r#"
+                x = """
+                        griffin
+                          harpy
+                    phoenix
+                    """
+                x
+                "#
+            ),
+            indoc!(
+                r#"
+                x =
+                    """
+                        griffin
+                          harpy
+                    phoenix
+                    """
+                x
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn block_string_in_record_field() {
+        expr_formats_to(
+            indoc!(
+                r#"
+                { name:
+                    """
+                    griffin
+                    harpy
+                    """, age: 3 }
+                "#
+            ),
+            indoc!(
+                r#"
+                {
+                    name:
+                        """
+                        griffin
+                        harpy
+                        """,
+                    age: 3,
+                }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn block_string_interpolation() {
+        expr_formats_same(indoc!(
+            r#"
+            x =
+                """
+                Hello, $(name)!
+                  You have $(Num.toStr count) new $(if count == 1 then "message" else "messages").
+                """
+            x
+            "#
+        ));
+    }
+
+    #[test]
+    fn multiline_interpolation_in_single_line_string() {
+        expr_formats_same(indoc!(
+            r#"
+            x =
+                "Some long text before the interpolation: $(Str.joinWith
+                    [
+                        "a",
+                    ]
+                    ", ")"
+            x
+            "#
+        ));
+    }
+
+    #[test]
+    fn raw_string_written_with_fewest_hashes() {
+        expr_formats_to(
+            indoc!(
+                r####"
+                x = r###"a "# b"###
+                y = r##"C:\Users"##
+                x
+                "####
+            ),
+            indoc!(
+                r###"
+                x = r##"a "# b"##
+                y = r#"C:\Users"#
+                x
+                "###
+            ),
+        );
+    }
+
+    #[test]
+    fn raw_string_lines_stay_as_they_are() {
+        expr_formats_same(indoc!(
+            r##"
+            x =
+                r#"first line
+              second "line"
+                        third \line
+            "#
+            x
+            "##
+        ));
+    }
+
     #[test]
     fn zero() {
         expr_formats_same(indoc!(
@@ -5408,6 +5528,31 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn backpassing_body_laid_out_like_def_body() {
+        expr_formats_to(
+            indoc!(
+                r"
+                x <- Task.await
+                        (getChar scope)
+                y <- List.range { start: At 0, end: Length 10 }
+                    |> Task.forEach
+                x + y
+                "
+            ),
+            indoc!(
+                r"
+                x <- Task.await
+                    (getChar scope)
+                y <-
+                    List.range { start: At 0, end: Length 10 }
+                    |> Task.forEach
+                x + y
+                "
+            ),
+        );
+    }
+
     #[test]
     fn multiline_higher_order_function() {
         expr_formats_same(indoc!(
@@ -6295,6 +6440,981 @@ mod test_fmt {
         ));
     }
 
+    // SORTED HEADERS
+
+    fn header_sorts_to(input: &str, expected: &str) {
+        let arena = Bump::new();
+        let input = input.trim();
+        let expected = expected.trim();
+        let sort_and_format = |src: &str| {
+            let (header, _) = header::parse_header(&arena, State::new(src.as_bytes()))
+                .unwrap_or_else(|err| panic!("Couldn't parse this header:\n\n{src}\n\n{err:?}"));
+            let sorted = SpacesBefore {
+                before: header.before,
+                item: sort_header_lists(&arena, &header.item),
+            };
+            let mut buf = Buf::new_in(&arena);
+
+            fmt_header(&mut buf, &sorted);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, sort_and_format(input).as_str());
+
+        // Sorting a sorted header shouldn't change it.
+        assert_multiline_str_eq!(expected, sort_and_format(expected).as_str());
+    }
+
+    #[test]
+    fn sort_module_exposes() {
+        header_sorts_to("module [b, Bar, a, b, Baz]", "module [a, b, Bar, Baz]");
+    }
+
+    #[test]
+    fn sort_multiline_exposes_keeps_comments() {
+        header_sorts_to(
+            indoc!(
+                r"
+                module [
+                    toStr,
+                    # the main type
+                    Parser,
+                    fromStr, # for tests
+                    toStr,
+                    Parser, # exposed twice on purpose
+                ]"
+            ),
+            indoc!(
+                r"
+                module [
+                    fromStr, # for tests
+                    # the main type
+                    Parser,
+                    Parser, # exposed twice on purpose
+                    toStr,
+                ]"
+            ),
+        );
+    }
+
+    #[test]
+    fn sort_app_packages() {
+        header_sorts_to(
+            indoc!(
+                r#"
+                app [main, Model, init] {
+                    pf: platform "platform/main.roc",
+                    json: "json/main.roc",
+                    pf: platform "platform/main.roc",
+                    html: "html/main.roc",
+                }"#
+            ),
+            indoc!(
+                r#"
+                app [init, main, Model] {
+                    html: "html/main.roc",
+                    json: "json/main.roc",
+                    pf: platform "platform/main.roc",
+                }"#
+            ),
+        );
+    }
+
+    #[test]
+    fn sort_platform_imports_and_merge_what_they_expose() {
+        header_sorts_to(
+            indoc!(
+                r#"
+                platform "cli"
+                    requires {} { main : Task {} [] }
+                    exposes [Task, Stdout, File]
+                    packages { json: "json/main.roc", base: "base/main.roc" }
+                    imports [Task.{ await }, json.Json, Task, Effect.{ putLine, after }, Task.{ Task }]
+                    provides [mainForHost]"#
+            ),
+            indoc!(
+                r#"
+                platform "cli"
+                    requires {} { main : Task {} [] }
+                    exposes [File, Stdout, Task]
+                    packages { base: "base/main.roc", json: "json/main.roc" }
+                    imports [Effect.{ after, putLine }, json.Json, Task.{ await, Task }]
+                    provides [mainForHost]"#
+            ),
+        );
+    }
+
+    // TRAILING COMMAS
+
+    fn expr_formats_to_without_trailing_commas(input: &str, expected: &str) {
+        let arena = Bump::new();
+        let input = input.trim();
+        let expected = expected.trim();
+        let format = |src: &str| {
+            let src = arena.alloc_str(src);
+            let expr = roc_parse::test_helpers::parse_expr_with(&arena, src)
+                .unwrap_or_else(|err| panic!("Couldn't parse this:\n\n{src}\n\n{err:?}"));
+            let mut buf = Buf::new_in(&arena).with_trailing_commas(TrailingCommas::Never);
+
+            expr.format(&mut buf, 0);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, format(input).as_str());
+
+        // The output shouldn't change when it's formatted again.
+        assert_multiline_str_eq!(expected, format(expected).as_str());
+    }
+
+    #[test]
+    fn no_trailing_commas_in_multiline_collections() {
+        expr_formats_to_without_trailing_commas(
+            indoc!(
+                r"
+                x : [
+                    A,
+                    B Str,
+                ]
+                x = A
+
+                y = (
+                    [
+                        1,
+                        2,
+                    ],
+                    { a: x,
+                      b: 3 }
+                )
+
+                [1, 2, 3,]
+                "
+            ),
+            indoc!(
+                r"
+                x : [
+                    A,
+                    B Str
+                ]
+                x = A
+
+                y = (
+                    [
+                        1,
+                        2
+                    ],
+                    {
+                        a: x,
+                        b: 3
+                    }
+                )
+
+                [1, 2, 3]
+                "
+            ),
+        );
+    }
+
+    #[test]
+    fn no_trailing_comma_before_comment() {
+        expr_formats_to_without_trailing_commas(
+            indoc!(
+                r"
+                [
+                    1,
+                    2, # the last one
+                ]
+                "
+            ),
+            indoc!(
+                r"
+                [
+                    1,
+                    2 # the last one
+                ]
+                "
+            ),
+        );
+    }
+
+    // DOC COMMENT WIDTH
+
+    fn module_docs_reflow_to(width: usize, input: &str, expected: &str) {
+        let arena = Bump::new();
+        let expected = expected.trim();
+        let format = |src: &str| {
+            let src = arena.alloc_str(src.trim());
+            let (header, state) = header::parse_header(&arena, State::new(src.as_bytes()))
+                .unwrap_or_else(|err| panic!("Couldn't parse this:\n\n{src}\n\n{err:?}"));
+            let mut buf = Buf::new_in(&arena).with_doc_comment_width(Some(width));
+
+            fmt_module_and_defs(&arena, src, &header, state, &mut buf);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, format(input).as_str());
+
+        // The output shouldn't change when it's formatted again.
+        assert_multiline_str_eq!(expected, format(expected).as_str());
+    }
+
+    #[test]
+    fn doc_comment_paragraphs_are_rewrapped() {
+        module_docs_reflow_to(
+            50,
+            indoc!(
+                r#"
+                ## Parsing and printing
+                ## dates. Dates are always in the
+                ## UTC time zone.
+                ##
+                ## Use [parse] to read one from a string, and [toStr] to write one back.
+                module [parse, toStr]
+
+                ## Parses a date like `2024-01-31`.
+                ##
+                ##     parse "2024-01-31" == Ok { year: 2024, month: 1, day: 31 }
+                ##
+                ## - Years before 1970 can't be parsed, and neither can dates after 9999.
+                ## - Times
+                ##   are ignored.
+                ##
+                ## ```
+                ## parse "2024-01-31T12:00:00Z" == Ok { year: 2024, month: 1, day: 31 }
+                ## ```
+                parse = \_ ->
+                    ## A helper whose doc comment is long enough to be wrapped.
+                    helper = 1
+
+                    helper
+
+                toStr = \_ -> 1
+                "#
+            ),
+            indoc!(
+                r#"
+                ## Parsing and printing dates. Dates are always in
+                ## the UTC time zone.
+                ##
+                ## Use [parse] to read one from a string, and
+                ## [toStr] to write one back.
+                module [parse, toStr]
+
+                ## Parses a date like `2024-01-31`.
+                ##
+                ##     parse "2024-01-31" == Ok { year: 2024, month: 1, day: 31 }
+                ##
+                ## - Years before 1970 can't be parsed, and
+                ##   neither can dates after 9999.
+                ## - Times are ignored.
+                ##
+                ## ```
+                ## parse "2024-01-31T12:00:00Z" == Ok { year: 2024, month: 1, day: 31 }
+                ## ```
+                parse = \_ ->
+                    ## A helper whose doc comment is long enough
+                    ## to be wrapped.
+                    helper = 1
+
+                    helper
+
+                toStr = \_ -> 1
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn doc_comment_lines_never_start_with_a_list_marker() {
+        module_docs_reflow_to(
+            43,
+            indoc!(
+                r"
+                module [x]
+
+                ## Returns the length of the given list - 1, or # of items minus one.
+                x = 1
+                "
+            ),
+            indoc!(
+                r"
+                module [x]
+
+                ## Returns the length of the given list -
+                ## 1, or # of items minus one.
+                x = 1
+                "
+            ),
+        );
+    }
+
+    // BLANK LINES
+
+    fn module_formats_to_with_blank_lines(blank_lines: BlankLines, input: &str, expected: &str) {
+        let arena = Bump::new();
+        let expected = expected.trim();
+        let format = |src: &str| {
+            let src = arena.alloc_str(src.trim());
+            let (header, state) = header::parse_header(&arena, State::new(src.as_bytes()))
+                .unwrap_or_else(|err| panic!("Couldn't parse this:\n\n{src}\n\n{err:?}"));
+            let mut buf = Buf::new_in(&arena).with_blank_lines(blank_lines);
+
+            fmt_module_and_defs(&arena, src, &header, state, &mut buf);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, format(input).as_str());
+
+        // The output shouldn't change when it's formatted again.
+        assert_multiline_str_eq!(expected, format(expected).as_str());
+    }
+
+    #[test]
+    fn runs_of_blank_lines_collapse_to_the_max() {
+        module_formats_to_with_blank_lines(
+            BlankLines {
+                max: 2,
+                between_top_level_defs: false,
+            },
+            indoc!(
+                r"
+                module [x, y]
+
+
+
+                x = 1
+
+
+                y =
+                    a = 1
+
+
+
+
+                    a
+                "
+            ),
+            indoc!(
+                r"
+                module [x, y]
+
+
+                x = 1
+
+
+                y =
+                    a = 1
+
+
+                    a
+                "
+            ),
+        );
+    }
+
+    #[test]
+    fn one_blank_line_between_top_level_defs() {
+        module_formats_to_with_blank_lines(
+            BlankLines {
+                max: 1,
+                between_top_level_defs: true,
+            },
+            indoc!(
+                r"
+                module [x, y, z]
+
+                x = 1 # one
+                # about y
+
+
+
+                y : U64
+                y =
+                    a = 1
+
+                    a
+                ## Docs for z
+
+                # Not for z
+                z = 3
+                "
+            ),
+            indoc!(
+                r"
+                module [x, y, z]
+
+                x = 1 # one
+
+                # about y
+
+                y : U64
+                y =
+                    a = 1
+
+                    a
+
+                ## Docs for z
+
+                # Not for z
+                z = 3
+                "
+            ),
+        );
+    }
+
+    // RECORD FIELD ALIGNMENT
+
+    fn module_formats_to_with_aligned_fields(input: &str, expected: &str) {
+        let arena = Bump::new();
+        let expected = expected.trim();
+        let format = |src: &str| {
+            let src = arena.alloc_str(src.trim());
+            let (header, state) = header::parse_header(&arena, State::new(src.as_bytes()))
+                .unwrap_or_else(|err| panic!("Couldn't parse this:\n\n{src}\n\n{err:?}"));
+            let mut buf = Buf::new_in(&arena).with_aligned_record_fields(true);
+
+            fmt_module_and_defs(&arena, src, &header, state, &mut buf);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, format(input).as_str());
+
+        // The output shouldn't change when it's formatted again.
+        assert_multiline_str_eq!(expected, format(expected).as_str());
+    }
+
+    #[test]
+    fn aligned_record_fields() {
+        module_formats_to_with_aligned_fields(
+            indoc!(
+                r#"
+                module [user]
+
+                user : { name : Str, emailAddress : Str,
+                    age : U8 }
+                user = {
+                    name: "Sam",
+                    emailAddress: "sam@example.com",
+                    age: 30,
+                }
+                "#
+            ),
+            indoc!(
+                r#"
+                module [user]
+
+                user : {
+                    name         : Str,
+                    emailAddress : Str,
+                    age          : U8,
+                }
+                user = {
+                    name:         "Sam",
+                    emailAddress: "sam@example.com",
+                    age:          30,
+                }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn aligned_record_fields_nested() {
+        module_formats_to_with_aligned_fields(
+            indoc!(
+                r#"
+                module [config]
+
+                config = { rec & name: "app",
+                    # Where it runs
+                    server: {
+                        host: "localhost",
+                        port: 80,
+                    },
+                    point: { x: 1, y: 2 },
+                    onError: \err ->
+                        Stderr.line err,
+                    description:
+                        """
+                        An app,
+                        for config
+                        """,
+                }
+                "#
+            ),
+            indoc!(
+                r#"
+                module [config]
+
+                config = { rec &
+                    name:    "app",
+                    # Where it runs
+                    server:  {
+                        host: "localhost",
+                        port: 80,
+                    },
+                    point:   { x: 1, y: 2 },
+                    onError: \err ->
+                        Stderr.line err,
+                    description:
+                        """
+                        An app,
+                        for config
+                        """,
+                }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn single_line_records_are_not_aligned() {
+        module_formats_to_with_aligned_fields(
+            indoc!(
+                r#"
+                module [point]
+
+                point : { x : I64, longName : I64 }
+                point = { x: 1, longName: 2 }
+                "#
+            ),
+            indoc!(
+                r#"
+                module [point]
+
+                point : { x : I64, longName : I64 }
+                point = { x: 1, longName: 2 }
+                "#
+            ),
+        );
+    }
+
+    // PIPELINE WIDTH
+
+    fn module_formats_to_with_pipeline_width(width: usize, input: &str, expected: &str) {
+        let arena = Bump::new();
+        let expected = expected.trim();
+        let format = |src: &str| {
+            let src = arena.alloc_str(src.trim());
+            let (header, state) = header::parse_header(&arena, State::new(src.as_bytes()))
+                .unwrap_or_else(|err| panic!("Couldn't parse this:\n\n{src}\n\n{err:?}"));
+            let mut buf = Buf::new_in(&arena).with_pipeline_width(Some(width));
+
+            fmt_module_and_defs(&arena, src, &header, state, &mut buf);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, format(input).as_str());
+
+        // The output shouldn't change when it's formatted again.
+        assert_multiline_str_eq!(expected, format(expected).as_str());
+    }
+
+    #[test]
+    fn wide_pipelines_get_one_step_per_line() {
+        module_formats_to_with_pipeline_width(
+            40,
+            indoc!(
+                r#"
+                module [names, total]
+
+                names = users |> List.keepIf isActive |> List.map .name
+
+                total = List.sum [1, 2] |> Num.toStr
+
+                main =
+                    line = names |> Str.joinWith ", " |> Str.concat "!"
+                    List.len names + 1 |> Num.toStr |> Stdout.line
+                "#
+            ),
+            indoc!(
+                r#"
+                module [names, total]
+
+                names =
+                    users
+                    |> List.keepIf isActive
+                    |> List.map .name
+
+                total = List.sum [1, 2] |> Num.toStr
+
+                main =
+                    line =
+                        names
+                        |> Str.joinWith ", "
+                        |> Str.concat "!"
+                    List.len names
+                    + 1
+                    |> Num.toStr
+                    |> Stdout.line
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_pipelines_after_other_code_stay_on_one_line() {
+        module_formats_to_with_pipeline_width(
+            30,
+            indoc!(
+                r#"
+                module [config]
+
+                config = { names: users |> List.map .name |> List.sortAsc }
+                "#
+            ),
+            indoc!(
+                r#"
+                module [config]
+
+                config = { names: users |> List.map .name |> List.sortAsc }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_pipelines_in_backpassing() {
+        module_formats_to_with_pipeline_width(
+            40,
+            indoc!(
+                r#"
+                module [main]
+
+                main =
+                    contents <- File.readUtf8 path |> Task.onErr handleErr |> Task.await
+                    lines <- Task.await (Str.split contents "\n" |> Task.ok)
+                    Stdout.line lines
+                "#
+            ),
+            indoc!(
+                r#"
+                module [main]
+
+                main =
+                    contents <-
+                        File.readUtf8 path
+                        |> Task.onErr handleErr
+                        |> Task.await
+                    lines <- Task.await (Str.split contents "\n" |> Task.ok)
+                    Stdout.line lines
+                "#
+            ),
+        );
+    }
+
+    // NUMBER LITERALS
+
+    fn expr_formats_to_with_number_literals(
+        number_literals: NumberLiterals,
+        input: &str,
+        expected: &str,
+    ) {
+        let arena = Bump::new();
+        let input = input.trim();
+        let expected = expected.trim();
+        let format = |src: &str| {
+            let src = arena.alloc_str(src);
+            let expr = roc_parse::test_helpers::parse_expr_with(&arena, src)
+                .unwrap_or_else(|err| panic!("Couldn't parse this:\n\n{src}\n\n{err:?}"));
+            let mut buf = Buf::new_in(&arena).with_number_literals(number_literals);
+
+            expr.format(&mut buf, 0);
+
+            buf.as_str().trim().to_string()
+        };
+
+        assert_multiline_str_eq!(expected, format(input).as_str());
+
+        // The output shouldn't change when it's formatted again.
+        assert_multiline_str_eq!(expected, format(expected).as_str());
+    }
+
+    #[test]
+    fn number_literal_digit_groups() {
+        expr_formats_to_with_number_literals(
+            NumberLiterals {
+                group_digits_above: Some(4),
+                ..NumberLiterals::default()
+            },
+            "[1000000, 1_0_00, 12345, -1234567u64, 1234567.123456, 12345e10, 0xFFFFFF]",
+            "[1_000_000, 1000, 12_345, -1_234_567u64, 1_234_567.123456, 12_345e10, 0xFFFFFF]",
+        );
+    }
+
+    #[test]
+    fn number_literal_hex_case() {
+        expr_formats_to_with_number_literals(
+            NumberLiterals {
+                hex_case: Some(HexCase::Upper),
+                ..NumberLiterals::default()
+            },
+            "[0xff, -0xabc_def, 0xffu8, 0o777, 100]",
+            "[0xFF, -0xABC_DEF, 0xFFu8, 0o777, 100]",
+        );
+        expr_formats_to_with_number_literals(
+            NumberLiterals {
+                hex_case: Some(HexCase::Lower),
+                ..NumberLiterals::default()
+            },
+            "[0xFF, 0xFFu8, 0xABCDEC, 0xABCDECdec]",
+            // Lowercase, the first `0xABCDEC` would be a Dec with the digits `abc`.
+            "[0xff, 0xffu8, 0xABCDEC, 0xabcdecdec]",
+        );
+    }
+
+    #[test]
+    fn number_literal_exponents() {
+        expr_formats_to_with_number_literals(
+            NumberLiterals {
+                normalize_exponents: true,
+                ..NumberLiterals::default()
+            },
+            "[1.5E05, 2.0e-007, 3e00, 4.5e10f32, 1.5]",
+            "[1.5e5, 2.0e-7, 3e0, 4.5e10f32, 1.5]",
+        );
+    }
+
+    #[test]
+    fn number_literals_are_left_alone_by_default() {
+        expr_formats_same("[1000000, 1_000, 0xabCD, 1.5E05, 2.0e-007]");
+    }
+
+    // EDITS
+
+    fn apply_edits(src: &str, edits: &[TextEdit]) -> String {
+        let mut output = src.to_string();
+
+        // Later edits first, so the earlier ones' regions stay the same.
+        for edit in edits.iter().rev() {
+            let range = edit.region.start().offset as usize..edit.region.end().offset as usize;
+
+            output.replace_range(range, &edit.replacement);
+        }
+
+        output
+    }
+
+    #[test]
+    fn edits_only_cover_what_changed() {
+        let arena = Bump::new();
+        let src = "module [a, b]\n\na = [1,2]\n\nb =\n    x = 1\n    x\n";
+        let edits = format_edits(&arena, src, Buf::new_in(&arena)).unwrap();
+
+        assert_eq!(
+            edits,
+            vec![TextEdit {
+                region: Region::new(Position::new(22), Position::new(22)),
+                replacement: " ".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn edits_format_the_source() {
+        let arena = Bump::new();
+        let src = indoc!(
+            r#"
+            app [main]   { pf: platform "platform/main.roc" }
+            import pf.Stdout
+
+            # The entry point
+            main =
+              name= "World"
+
+
+
+              Stdout.line! "Hello, $(name)!"
+
+            greeting : Str->Str
+            greeting = \name -> Str.concat "Hi " name
+            "#
+        );
+        let edits = format_edits(&arena, src, Buf::new_in(&arena)).unwrap();
+        let formatted = apply_edits(src, &edits);
+
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                app [main] { pf: platform "platform/main.roc" }
+                import pf.Stdout
+
+                # The entry point
+                main =
+                    name = "World"
+
+                    Stdout.line! "Hello, $(name)!"
+
+                greeting : Str -> Str
+                greeting = \name -> Str.concat "Hi " name
+                "#
+            ),
+            formatted.as_str()
+        );
+        assert!(edits.iter().all(|edit| edit.replacement.len() < 10));
+        assert_eq!(
+            format_edits(&arena, arena.alloc_str(&formatted), Buf::new_in(&arena)).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn edits_leave_defs_with_syntax_errors_alone() {
+        let arena = Bump::new();
+        let src = indoc!(
+            r#"
+            module [a, b, c]
+
+            a =   1
+
+            b = when x is
+                A ->
+
+            c : {a:Str}
+            c = {a:"c"}
+            "#
+        );
+        let edits = format_edits(&arena, src, Buf::new_in(&arena)).unwrap();
+
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                module [a, b, c]
+
+                a = 1
+
+                b = when x is
+                    A ->
+
+                c : { a : Str }
+                c = { a: "c" }
+                "#
+            ),
+            apply_edits(src, &edits).as_str()
+        );
+    }
+
+    // COMMENTS
+
+    /// Every way to add one comment to the source: at the end of each line, on a line of its own
+    /// before each line, and in place of each space (continuing the line on the next one).
+    fn with_one_comment(src: &str) -> Vec<String> {
+        let lines: Vec<&str> = src.trim_end().lines().collect();
+        let mut variants = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let with_line = |replacement: String| {
+                let mut lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+                lines[index] = replacement;
+                lines.join("\n")
+            };
+
+            variants.push(with_line(format!("{line} # comment")));
+            variants.push(with_line(format!("{indent}# comment\n{line}")));
+
+            for (col, ch) in line.char_indices().skip(indent.len()) {
+                if ch == ' ' {
+                    variants.push(with_line(format!(
+                        "{} # comment\n{indent}    {}",
+                        &line[..col],
+                        &line[col + 1..]
+                    )));
+                }
+            }
+        }
+
+        variants
+    }
+
+    #[test]
+    fn comments_anywhere_in_snapshots_are_kept() {
+        // Put a comment everywhere it could go in each of the parser's snapshot tests, and check
+        // that formatting keeps it.
+        let snapshots_dir =
+            workspace_root().join("crates/compiler/test_syntax/tests/snapshots/pass");
+        let mut paths: Vec<_> = std::fs::read_dir(snapshots_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+
+        paths.sort();
+
+        for path in paths {
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+
+            if file_name.contains(".formatted.") {
+                continue;
+            }
+
+            let kind = if file_name.ends_with(".expr.roc") {
+                InputKind::Expr
+            } else if file_name.ends_with(".moduledefs.roc") {
+                InputKind::ModuleDefs
+            } else if file_name.ends_with(".header.roc") {
+                InputKind::Header
+            } else if file_name.ends_with(".full.roc") {
+                InputKind::Full
+            } else {
+                continue;
+            };
+
+            let src = std::fs::read_to_string(&path).unwrap();
+
+            // A `#` in a string or a character literal isn't a comment.
+            if src.contains('"') || src.contains('\'') {
+                continue;
+            }
+
+            for variant in with_one_comment(&src) {
+                let arena = Bump::new();
+
+                // Plenty of the variants aren't valid Roc. Those aren't the formatter's problem,
+                // and neither are comments the parser didn't keep.
+                let ast = match kind.with_text(&variant).parse_in(&arena) {
+                    Ok(ast) if !ast.is_malformed() => ast,
+                    _ => continue,
+                };
+                let expected = comments(&variant);
+
+                if ast.comment_count() != expected.len() {
+                    continue;
+                }
+
+                let output = ast.format();
+
+                assert_eq!(
+                    comments(output.as_ref().as_str()),
+                    expected,
+                    "Formatting {file_name} with a comment added dropped it:\n\n{variant}\n\nbecame:\n\n{}",
+                    output.as_ref().as_str()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn comments_in_type_annotations() {
+        expr_formats_same(indoc!(
+            r#"
+            f :
+                # before the arguments
+                Str,
+                # before the second argument
+                U64
+                -> { a : Str } # after the return type
+            f = \_, _ -> { a: "" }
+
+            f
+            "#
+        ));
+    }
+
     // this is a parse error atm
     //    #[test]
     //    fn multiline_apply() {
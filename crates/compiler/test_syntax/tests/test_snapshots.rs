@@ -209,17 +209,21 @@ mod test_snapshots {
         fail/imports_missing_comma.header,
         fail/inline_hastype.expr,
         fail/invalid_operator.expr,
+        fail/keyword_near_miss.expr,
+        fail/keyword_near_miss_at_top_level.moduledefs,
         fail/lambda_double_comma.expr,
         fail/lambda_extra_comma.expr,
         fail/lambda_leading_comma.expr,
         fail/lambda_missing_indent.expr,
         fail/list_double_comma.expr,
+        fail/list_missing_comma.expr,
         fail/list_pattern_not_terminated.expr,
         fail/list_pattern_weird_rest_pattern.expr,
         fail/list_without_end.expr,
         fail/module_params_with_missing_arrow.header,
         fail/module_with_unfinished_params.header,
         fail/multi_no_end.expr,
+        fail/nested_interpolation.expr,
         fail/newline_before_operator_with_defs.expr,
         fail/opaque_type_def_with_newline.expr,
         fail/pattern_binds_keyword.expr,
@@ -227,6 +231,10 @@ mod test_snapshots {
         fail/pattern_in_parens_end_comma.expr,
         fail/pattern_in_parens_indent_open.expr,
         fail/pattern_in_parens_open.expr,
+        fail/raw_string_endless.expr,
+        fail/record_field_after_comma.expr,
+        fail/record_label_only_unfinished.expr,
+        fail/record_missing_comma.expr,
         fail/record_type_end.expr,
         fail/record_type_keyword_field_name.expr,
         fail/record_type_missing_comma.expr,
@@ -240,6 +248,7 @@ mod test_snapshots {
         fail/tag_union_open.expr,
         fail/tag_union_second_lowercase_tag_name.expr,
         fail/trailing_operator.expr,
+        fail/tuple_missing_comma.expr,
         fail/type_annotation_double_colon.expr,
         fail/type_apply_stray_dot.expr,
         fail/type_argument_arrow_then_nothing.expr,
@@ -252,6 +261,7 @@ mod test_snapshots {
         fail/unfinished_closure_pattern_in_parens.expr,
         fail/unfinished_import_as_or_exposing.moduledefs,
         fail/unicode_not_hex.expr,
+        fail/unterminated_interpolation.expr,
         fail/weird_escape.expr,
         fail/when_missing_arrow.expr,
         fail/when_outdented_branch.expr,
@@ -291,6 +301,7 @@ mod test_snapshots {
         pass/basic_tuple.expr,
         pass/basic_var.expr,
         pass/closure_in_binop_with_spaces.expr,
+        pass/closure_with_trailing_comma.expr,
         pass/closure_with_underscores.expr,
         pass/comma_prefixed_indented_record.expr,
         pass/comment_after_annotation.expr,
@@ -333,6 +344,7 @@ mod test_snapshots {
         pass/full_app_header_trailing_commas.header,
         pass/function_effect_types.header,
         pass/function_with_tuple_ext_type.expr,
+        pass/function_type_with_trailing_comma.expr,
         pass/function_with_tuple_type.expr,
         pass/highest_float.expr,
         pass/highest_int.expr,
@@ -444,6 +456,7 @@ mod test_snapshots {
         pass/pattern_with_space_in_parens.expr, // https://github.com/roc-lang/roc/issues/929
         pass/pizza_bang.moduledefs,
         pass/pizza_dbg.expr,
+        pass/platform_header_with_hints.header,
         pass/plus_if.expr,
         pass/plus_when.expr,
         pass/pos_inf_float.expr,
@@ -452,6 +465,7 @@ mod test_snapshots {
         pass/provides_type.header,
         pass/qualified_field.expr,
         pass/qualified_var.expr,
+        pass/raw_string.expr,
         pass/record_access_after_tuple.expr,
         pass/record_builder.expr,
         pass/record_builder_ignored_fields.expr,
@@ -312,6 +312,20 @@ impl IntLitWidth {
         }
     }
 
+    /// The width for the builtin number type with this symbol, e.g. `U8` for `Num.U8`.
+    pub fn from_symbol(symbol: Symbol) -> Option<Self> {
+        use IntLitWidth::*;
+        [U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64, Dec]
+            .into_iter()
+            .find(|width| width.symbol() == symbol)
+    }
+
+    /// Whether this is the width of an integer type, as opposed to a fraction type which can
+    /// also hold some integer literals.
+    pub fn is_int(&self) -> bool {
+        !matches!(self, IntLitWidth::F32 | IntLitWidth::F64 | IntLitWidth::Dec)
+    }
+
     pub const fn symbol(&self) -> Symbol {
         match self {
             IntLitWidth::U8 => Symbol::NUM_U8,
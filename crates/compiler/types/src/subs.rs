@@ -1856,6 +1856,15 @@ impl Subs {
         self.utable.unioned_without_compacting(left, right)
     }
 
+    /// Whether `var` has been unified with one of the reserved variables. Those are shared by
+    /// everything that has their type, e.g. every string literal in a module is [`Variable::STR`].
+    pub fn is_unified_with_reserved(&self, var: Variable) -> bool {
+        let root = self.get_root_key_without_compacting(var);
+
+        (0..Variable::NUM_RESERVED_VARS as u32)
+            .any(|index| self.get_root_key_without_compacting(Variable(index)) == root)
+    }
+
     pub fn redundant(&self, var: Variable) -> bool {
         self.utable.is_redirect(var)
     }
@@ -2,7 +2,9 @@ use std::io;
 
 use bumpalo::Bump;
 use roc_collections::MutMap;
-use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_load::{
+    ExecutionMode, LoadConfig, LoadMonomorphizedError, NumericDefaults, Tabs, Threading,
+};
 use roc_module::symbol::{Interns, Symbol};
 use roc_mono::{
     ir::{Proc, ProcLayout},
@@ -49,6 +51,9 @@ pub(crate) fn write_compiled_ir<'a>(
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        progress: None,
+        tabs: Tabs::Reject,
+        numeric_defaults: NumericDefaults::Silent,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -363,7 +363,19 @@ fn unify_help<M: MetaCollector>(
         let type1 = env.var_to_error_type_contextual(var1, error_context, observed_pol);
         let type2 = env.var_to_error_type_contextual(var2, error_context, observed_pol);
 
-        env.union(var1, var2, Content::Error.into());
+        // Turn the mismatched types into errors, so that everything that depends on them doesn't
+        // report the same mismatch again. That must not happen to a reserved variable though,
+        // since it's shared by every other use of its type, and all mismatches involving that
+        // type would then go unreported for the rest of the module.
+        match (
+            env.is_unified_with_reserved(var1),
+            env.is_unified_with_reserved(var2),
+        ) {
+            (false, false) => env.union(var1, var2, Content::Error.into()),
+            (true, false) => env.set_content(var2, Content::Error),
+            (false, true) => env.set_content(var1, Content::Error),
+            (true, true) => {}
+        }
 
         let do_not_implement_ability = mismatches
             .into_iter()
@@ -30,7 +30,7 @@ pub fn worker_task_step<Task>(
     injector: &Injector<Task>,
     stealers: &[Stealer<Task>],
     worker_msg_rx: &Receiver<WorkerMsg>,
-    run_task: impl Fn(Task) -> Result<(), ChannelProblem>,
+    mut run_task: impl FnMut(Task) -> Result<(), ChannelProblem>,
 ) -> Result<ControlFlow<(), ()>, ChannelProblem> {
     match worker_msg_rx.try_recv() {
         Ok(msg) => {
@@ -74,7 +74,7 @@ pub fn worker_task<Task>(
     injector: &Injector<Task>,
     stealers: &[Stealer<Task>],
     worker_msg_rx: crossbeam::channel::Receiver<WorkerMsg>,
-    run_task: impl Fn(Task) -> Result<(), ChannelProblem>,
+    mut run_task: impl FnMut(Task) -> Result<(), ChannelProblem>,
 ) -> Result<(), ChannelProblem> {
     // Keep listening until we receive a Shutdown msg
     for msg in worker_msg_rx.iter() {
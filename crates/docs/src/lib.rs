@@ -7,7 +7,9 @@ use roc_can::scope::Scope;
 use roc_collections::VecSet;
 use roc_load::docs::{DocEntry, TypeAnnotation};
 use roc_load::docs::{ModuleDocumentation, RecordField};
-use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_load::{
+    ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, NumericDefaults, Tabs, Threading,
+};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_parse::ident::{parse_ident, Accessor, Ident};
@@ -20,14 +22,41 @@ use std::path::{Path, PathBuf};
 
 const LINK_SVG: &str = include_str!("./static/link.svg");
 
-pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
-    let mut loaded_module = load_module_for_docs(root_file);
-    let exposed_module_docs = get_exposed_module_docs(&mut loaded_module);
+/// Which color scheme the generated docs use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocsTheme {
+    /// Follow the reader's system setting (via `prefers-color-scheme`).
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
 
-    // TODO get these from the platform's source file rather than hardcoding them!
-    // github.com/roc-lang/roc/issues/5712
-    let package_name = "Documentation".to_string();
+impl DocsTheme {
+    /// styles.css puts all of its dark colors inside this media query.
+    const DARK_MEDIA_QUERY: &'static str = "@media (prefers-color-scheme: dark)";
 
+    fn apply(self, styles_css: &str) -> String {
+        match self {
+            DocsTheme::Auto => styles_css.to_string(),
+            // `not all` never matches, and `all` always does.
+            DocsTheme::Light => styles_css.replace(Self::DARK_MEDIA_QUERY, "@media not all"),
+            DocsTheme::Dark => styles_css.replace(Self::DARK_MEDIA_QUERY, "@media all"),
+        }
+    }
+}
+
+struct Assets<S: AsRef<str>> {
+    search_js: S,
+    styles_css: S,
+    raw_template_html: S,
+}
+
+/// Generates docs for each of the given packages or platforms into `build_dir`.
+///
+/// With one root file, its docs go directly into `build_dir`. With several, each one's docs go
+/// into a subdirectory named after it, and `build_dir/index.html` links to all of them.
+pub fn generate_docs_html(root_files: &[PathBuf], build_dir: &Path, theme: DocsTheme) {
     // Clear out the generated-docs dir (we'll create a fresh one at the end)
     if build_dir.exists() {
         fs::remove_dir_all(build_dir)
@@ -39,12 +68,6 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
     // For debug builds, read assets from fs to speed up build
     // Otherwise, include as string literal
 
-    struct Assets<S: AsRef<str>> {
-        search_js: S,
-        styles_css: S,
-        raw_template_html: S,
-    }
-
     #[cfg(not(debug_assertions))]
     let assets = {
         let search_js = include_str!("./static/search.js");
@@ -76,13 +99,98 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
         }
     };
 
+    let styles_css = theme.apply(assets.styles_css.as_ref());
+    let assets: Assets<&str> = Assets {
+        search_js: assets.search_js.as_ref(),
+        styles_css: styles_css.as_str(),
+        raw_template_html: assets.raw_template_html.as_ref(),
+    };
+
+    match root_files {
+        [root_file] => {
+            generate_package_docs(root_file.to_owned(), build_dir, &base_url(), &assets);
+        }
+        _ => {
+            let mut dir_names: Vec<String> = Vec::with_capacity(root_files.len());
+
+            for root_file in root_files {
+                let dir_name = docs_dir_name(root_file);
+
+                if dir_names.contains(&dir_name) {
+                    eprintln!(
+                        "More than one of the given .roc files would have its docs generated in {}. Try giving them different names, or putting them in differently-named directories.",
+                        build_dir.join(&dir_name).display()
+                    );
+
+                    std::process::exit(1);
+                }
+
+                let package_dir = build_dir.join(&dir_name);
+                let package_base_url = format!("{}{dir_name}/", base_url());
+
+                fs::create_dir_all(&package_dir)
+                    .expect("TODO gracefully handle not being able to create the package dir");
+
+                generate_package_docs(
+                    root_file.to_owned(),
+                    &package_dir,
+                    &package_base_url,
+                    &assets,
+                );
+
+                dir_names.push(dir_name);
+            }
+
+            write_assets(build_dir, &assets);
+
+            let rendered_index = assets
+                .raw_template_html
+                .replace("<!-- Prefetch links -->", "")
+                .replace("<!-- base -->", &base_url())
+                .replace(
+                    "<!-- Module links -->",
+                    &render_packages_sidebar(&dir_names),
+                )
+                .replace("<!-- Page title -->", "<title>Documentation</title>")
+                .replace(
+                    "<!-- Package Name -->",
+                    render_name_link("Documentation", &base_url()).as_str(),
+                )
+                .replace("<!-- Module Docs -->", &render_packages_index(&dir_names));
+
+            fs::write(build_dir.join("index.html"), rendered_index).unwrap_or_else(|error| {
+                panic!("Attempted to write index.html but failed with this error: {error}")
+            });
+        }
+    }
+
+    println!("🎉 Docs generated in {}", build_dir.display());
+}
+
+/// The name of the subdirectory a root file's docs go in, when generating docs for several at once:
+/// the file's name without its extension, or the name of its directory if it's a `main.roc`.
+fn docs_dir_name(root_file: &Path) -> String {
+    let stem = root_file.file_stem().and_then(|stem| stem.to_str());
+
+    let name = match stem {
+        Some("main") | None => root_file
+            .canonicalize()
+            .ok()
+            .and_then(|path| Some(path.parent()?.file_name()?.to_str()?.to_string())),
+        Some(stem) => Some(stem.to_string()),
+    };
+
+    name.unwrap_or_else(|| "main".to_string())
+}
+
+fn write_assets(dir: &Path, assets: &Assets<&str>) {
     // Write CSS, JS, and favicon
     // (The HTML requires more work!)
     for (file, contents) in [
         ("search.js", assets.search_js),
         ("styles.css", assets.styles_css),
     ] {
-        let dir = build_dir.join(file);
+        let dir = dir.join(file);
         fs::write(&dir, contents).unwrap_or_else(|error| {
             panic!(
                 "Attempted to write {} but failed with this error: {}",
@@ -91,6 +199,22 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
             )
         })
     }
+}
+
+fn generate_package_docs(
+    root_file: PathBuf,
+    build_dir: &Path,
+    base_url: &str,
+    assets: &Assets<&str>,
+) {
+    let mut loaded_module = load_module_for_docs(root_file);
+    let exposed_module_docs = get_exposed_module_docs(&mut loaded_module);
+
+    // TODO get these from the platform's source file rather than hardcoding them!
+    // github.com/roc-lang/roc/issues/5712
+    let package_name = "Documentation".to_string();
+
+    write_assets(build_dir, assets);
 
     // Insert asset urls & sidebar links
     let template_html = assets
@@ -108,7 +232,7 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
                 .join("\n    ")
                 .as_str(),
         )
-        .replace("<!-- base -->", &base_url())
+        .replace("<!-- base -->", base_url)
         .replace(
             "<!-- Module links -->",
             render_sidebar(exposed_module_docs.iter().map(|(_, docs)| docs)).as_str(),
@@ -134,7 +258,7 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
             )
             .replace(
                 "<!-- Package Name -->",
-                render_name_link(package_name.as_str()).as_str(),
+                render_name_link(package_name.as_str(), base_url).as_str(),
             )
             .replace(
                 "<!-- Module Docs -->",
@@ -161,7 +285,7 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
             )
             .replace(
                 "<!-- Package Name -->",
-                render_name_link(package_name.as_str()).as_str(),
+                render_name_link(package_name.as_str(), base_url).as_str(),
             )
             .replace(
                 "<!-- Module Docs -->",
@@ -170,6 +294,7 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
                     module_docs,
                     &loaded_module,
                     &all_exposed_symbols,
+                    base_url,
                 )
                 .as_str(),
             );
@@ -177,8 +302,6 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
         fs::write(module_dir.join("index.html"), rendered_module)
             .expect("TODO gracefully handle failing to write index.html inside module's dir");
     }
-
-    println!("🎉 Docs generated in {}", build_dir.display());
 }
 
 /// Gives only the module docs for modules that are exposed by the platform or package.
@@ -240,11 +363,67 @@ fn render_package_index(docs_by_module: &[(ModuleId, ModuleDocumentation)]) -> S
     index_buf
 }
 
+/// The index page linking to each package, when generating docs for several at once.
+fn render_packages_index(dir_names: &[String]) -> String {
+    let mut package_list_buf = String::new();
+
+    for dir_name in dir_names {
+        let mut link_buf = String::new();
+        let href = format!("{dir_name}/");
+
+        push_html(&mut link_buf, "a", vec![("href", href.as_str())], dir_name);
+        push_html(&mut package_list_buf, "li", vec![], link_buf.as_str());
+    }
+
+    let mut index_buf = String::new();
+
+    push_html(
+        &mut index_buf,
+        "h2",
+        vec![("class", "module-name")],
+        "Packages",
+    );
+    push_html(
+        &mut index_buf,
+        "ul",
+        vec![("class", "index-module-links")],
+        package_list_buf.as_str(),
+    );
+
+    index_buf
+}
+
+fn render_packages_sidebar(dir_names: &[String]) -> String {
+    let mut buf = String::new();
+
+    for dir_name in dir_names {
+        let mut sidebar_entry_content = String::new();
+        let href = format!("{dir_name}/");
+
+        push_html(
+            &mut sidebar_entry_content,
+            "a",
+            vec![("class", "sidebar-module-link"), ("href", href.as_str())],
+            dir_name,
+        );
+
+        push_html(
+            &mut buf,
+            "div",
+            vec![("class", "sidebar-entry")],
+            sidebar_entry_content.as_str(),
+        );
+    }
+
+    buf
+}
+
 fn render_module_documentation(
     module_id: ModuleId,
     module: &ModuleDocumentation,
     root_module: &LoadedModule,
     all_exposed_symbols: &VecSet<Symbol>,
+    base_url: &str,
 ) -> String {
     let mut buf = String::new();
     let module_name = module.name.as_str();
@@ -252,7 +431,7 @@ fn render_module_documentation(
     push_html(&mut buf, "h2", vec![("class", "module-name")], {
         let mut link_buf = String::new();
 
-        push_html(&mut link_buf, "a", vec![("href", "/")], module_name);
+        push_html(&mut link_buf, "a", vec![("href", base_url)], module_name);
 
         link_buf
     });
@@ -304,6 +483,7 @@ fn render_module_documentation(
                             &module.scope,
                             docs,
                             root_module,
+                            base_url,
                         );
                     }
 
@@ -318,6 +498,7 @@ fn render_module_documentation(
                     &module.scope,
                     docs,
                     root_module,
+                    base_url,
                 );
             }
             DocEntry::DetachedDoc(docs) => {
@@ -328,6 +509,7 @@ fn render_module_documentation(
                     &module.scope,
                     docs,
                     root_module,
+                    base_url,
                 );
             }
         };
@@ -392,19 +574,14 @@ fn base_url() -> String {
 }
 
 // TODO render version as well
-fn render_name_link(name: &str) -> String {
+fn render_name_link(name: &str, base_url: &str) -> String {
     let mut buf = String::new();
 
     push_html(&mut buf, "h1", vec![("class", "pkg-full-name")], {
         let mut link_buf = String::new();
 
         // link to root (= docs overview page)
-        push_html(
-            &mut link_buf,
-            "a",
-            vec![("href", base_url().as_str())],
-            name,
-        );
+        push_html(&mut link_buf, "a", vec![("href", base_url)], name);
 
         link_buf
     });
@@ -478,6 +655,9 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
         exec_mode: ExecutionMode::Check,
+        progress: None,
+        tabs: Tabs::from_env(),
+        numeric_defaults: NumericDefaults::from_env(),
     };
     match roc_load::load_and_typecheck(
         &arena,
@@ -923,6 +1103,7 @@ fn doc_url<'a>(
     interns: &'a Interns,
     mut module_name: &'a str,
     ident: &str,
+    base_url: &str,
 ) -> Result<DocUrl, (String, LinkProblem)> {
     if module_name.is_empty() {
         // This is an unqualified lookup, so look for the ident
@@ -976,7 +1157,7 @@ fn doc_url<'a>(
         }
     }
 
-    let mut url = base_url();
+    let mut url = base_url.to_string();
 
     // Example:
     //
@@ -998,6 +1179,7 @@ fn markdown_to_html(
     scope: &Scope,
     markdown: &str,
     loaded_module: &LoadedModule,
+    base_url: &str,
 ) {
     use pulldown_cmark::{BrokenLink, CodeBlockKind, CowStr, Event, LinkType, Tag::*};
 
@@ -1035,6 +1217,7 @@ fn markdown_to_html(
                                     &loaded_module.interns,
                                     module_name,
                                     symbol_name,
+                                    base_url,
                                 ) {
                                     Ok(DocUrl { url, title }) => Some((url.into(), title.into())),
                                     Err((link_markdown, problem)) => {
@@ -1069,6 +1252,7 @@ fn markdown_to_html(
                             &loaded_module.interns,
                             "",
                             type_name,
+                            base_url,
                         ) {
                             Ok(DocUrl { url, title }) => Some((url.into(), title.into())),
                             Err((link_markdown, problem)) => {
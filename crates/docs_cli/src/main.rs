@@ -1,6 +1,6 @@
 //! Provides a binary that is only used for static build servers.
 use clap::{value_parser, Arg, Command};
-use roc_docs::generate_docs_html;
+use roc_docs::{generate_docs_html, DocsTheme};
 use std::io;
 use std::path::PathBuf;
 
@@ -20,9 +20,16 @@ fn main() -> io::Result<()> {
         .get_matches();
 
     // Populate roc_files
+    let root_files: Vec<PathBuf> = matches
+        .get_many::<PathBuf>(ROC_FILE)
+        .unwrap()
+        .cloned()
+        .collect();
+
     generate_docs_html(
-        matches.get_one::<PathBuf>(ROC_FILE).unwrap().to_owned(),
+        &root_files,
         &PathBuf::from("./generated-docs"),
+        DocsTheme::Auto,
     );
 
     Ok(())
@@ -29,13 +29,46 @@ extern "C" {
 #[cfg(windows)]
 const STD_ERROR_HANDLE: i32 = -12;
 
+/// The exit codes of the `roc` CLI, so that scripts and CI can tell kinds of failure apart
+/// without inspecting stderr.
+pub mod exit_code {
+    /// Everything went fine.
+    pub const SUCCESS: i32 = 0;
+
+    /// The command did not succeed, e.g. `roc test` had failing tests or
+    /// `roc format --check` found files that need formatting.
+    pub const FAILURE: i32 = 1;
+
+    /// The code compiled, but there were warnings.
+    pub const WARNINGS: i32 = 2;
+
+    /// The code could not be parsed, or had problems like unknown names or shadowing.
+    pub const PARSE_OR_CANONICALIZE_ERRORS: i32 = 3;
+
+    /// The code parsed, but had type errors.
+    pub const TYPE_ERRORS: i32 = 4;
+
+    /// The compiled program crashed while `roc` was running it.
+    pub const PROGRAM_CRASHED: i32 = 5;
+
+    /// A file could not be read or written, or the command line arguments were invalid.
+    pub const IO_OR_USAGE_ERROR: i32 = 6;
+
+    /// `roc test` found no `expect`s to run. This is its own code so scripts can tell it apart
+    /// from failing tests; before exit codes were documented, it was 2, which now means warnings.
+    pub const NO_EXPECTATIONS: i32 = 7;
+
+    /// The compiler hit a bug. This is also the code Rust uses when a program panics.
+    pub const INTERNAL_COMPILER_ERROR: i32 = 101;
+}
+
 /// Print each of the given strings to stderr (if it's available; on wasm, nothing will
-/// be printed) and then immediately exit the program with an error.
-/// On wasm, this will trap, and on UNIX or Windows it will exit with a code of 1.
+/// be printed) and then immediately exit the program with the given exit code.
+/// On wasm, this will trap instead.
 #[inline(never)]
 #[cold]
 #[cfg(any(unix, windows, target_arch = "wasm32"))]
-pub fn error_and_exit(args: fmt::Arguments) -> ! {
+pub fn error_and_exit(exit_code: i32, args: fmt::Arguments) -> ! {
     use fmt::Write;
 
     struct StderrWriter;
@@ -78,12 +111,12 @@ pub fn error_and_exit(args: fmt::Arguments) -> ! {
 
     #[cfg(unix)]
     unsafe {
-        exit(1)
+        exit(exit_code)
     }
 
     #[cfg(windows)]
     unsafe {
-        ExitProcess(1)
+        ExitProcess(exit_code as u32)
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -109,10 +142,10 @@ pub const INTERNAL_ERROR_MESSAGE: &str = concat!(
 #[macro_export]
 macro_rules! internal_error {
     () => ({
-        $crate::error_and_exit(format_args!("{}\nLocation: {}:{}:{}", $crate::INTERNAL_ERROR_MESSAGE, file!(), line!(), column!()))
+        $crate::error_and_exit($crate::exit_code::INTERNAL_COMPILER_ERROR, format_args!("{}\nLocation: {}:{}:{}", $crate::INTERNAL_ERROR_MESSAGE, file!(), line!(), column!()))
     });
     ($($arg:tt)*) => ({
-        $crate::error_and_exit(format_args!(
+        $crate::error_and_exit($crate::exit_code::INTERNAL_COMPILER_ERROR, format_args!(
             "{}{}\nLocation: {}:{}:{}",
             $crate::INTERNAL_ERROR_MESSAGE,
             format_args!($($arg)*),
@@ -135,10 +168,10 @@ pub const USER_ERROR_MESSAGE: &str = concat!(
 #[macro_export]
 macro_rules! user_error {
     () => ({
-        $crate::error_and_exit(format_args!("{}\nLocation: {}:{}:{}", $crate::USER_ERROR_MESSAGE, file!(), line!(), column!()))
+        $crate::error_and_exit($crate::exit_code::IO_OR_USAGE_ERROR, format_args!("{}\nLocation: {}:{}:{}", $crate::USER_ERROR_MESSAGE, file!(), line!(), column!()))
     });
     ($($arg:tt)*) => ({
-        $crate::error_and_exit(format_args!(
+        $crate::error_and_exit($crate::exit_code::IO_OR_USAGE_ERROR, format_args!(
             "{}{}\nLocation: {}:{}:{}",
             $crate::USER_ERROR_MESSAGE,
             format_args!($($arg)*),
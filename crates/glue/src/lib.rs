@@ -10,6 +10,76 @@ pub mod types;
 
 pub use load::generate;
 
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A glue spec that comes with Roc, for `roc glue --lang`. The spec is embedded in the roc
+/// binary, and written to a temporary directory (which is deleted when this is dropped)
+/// along with the glue platform and the files it imports.
+pub struct BuiltinSpec {
+    path: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+impl BuiltinSpec {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+macro_rules! embed_files {
+    ($($path:literal),* $(,)?) => {
+        &[$(($path, include_str!(concat!("../../", $path)))),*]
+    };
+}
+
+/// The files the builtin specs need, by their paths relative to the `crates/` directory,
+/// so that the specs' relative imports resolve the same way they do in Roc's source tree.
+const BUILTIN_SPEC_FILES: &[(&str, &str)] = embed_files![
+    "glue/src/RustGlue.roc",
+    "glue/src/ZigGlue.roc",
+    "glue/platform/main.roc",
+    "glue/platform/File.roc",
+    "glue/platform/Shape.roc",
+    "glue/platform/Target.roc",
+    "glue/platform/TypeId.roc",
+    "glue/platform/Types.roc",
+    "glue/static/Cargo.toml",
+    "roc_std/Cargo.toml",
+    "roc_std/src/lib.rs",
+    "roc_std/src/roc_box.rs",
+    "roc_std/src/roc_list.rs",
+    "roc_std/src/roc_str.rs",
+    "roc_std/src/storage.rs",
+    "compiler/builtins/bitcode/src/list.zig",
+    "compiler/builtins/bitcode/src/str.zig",
+    "compiler/builtins/bitcode/src/utils.zig",
+];
+
+/// Writes out the glue spec that comes with Roc for the given language (e.g. "rust").
+/// Returns `None` if Roc doesn't come with a spec for that language.
+pub fn builtin_spec(lang: &str) -> io::Result<Option<BuiltinSpec>> {
+    let spec_file = match lang {
+        "rust" => "glue/src/RustGlue.roc",
+        "zig" => "glue/src/ZigGlue.roc",
+        _ => return Ok(None),
+    };
+
+    let dir = tempfile::tempdir()?;
+
+    for (relative_path, contents) in BUILTIN_SPEC_FILES {
+        let path = dir.path().join(relative_path);
+
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, contents)?;
+    }
+
+    Ok(Some(BuiltinSpec {
+        path: dir.path().join(spec_file),
+        _dir: dir,
+    }))
+}
+
 // required because we use roc_std here
 mod roc_externs {
     use core::ffi::c_void;
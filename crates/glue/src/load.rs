@@ -6,13 +6,16 @@ use roc_build::{
     link::{LinkType, LinkingStrategy},
     program::{
         build_file, handle_error_module, handle_loading_problem, standard_load_config,
-        BuildFileError, BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions,
+        BuildFileError, BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, EmitArtifacts,
     },
 };
 use roc_collections::MutMap;
 use roc_error_macros::todo_lambda_erasure;
 use roc_gen_llvm::run_roc::RocCallResult;
-use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_load::{
+    ExecutionMode, FunctionKind, LoadConfig, LoadedModule, LoadingProblem, NumericDefaults, Tabs,
+    Threading,
+};
 use roc_mono::ir::{generate_glue_procs, CrashTag, GlueProc, OptLevel};
 use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner};
 use roc_packaging::cache::{self, RocCacheDir};
@@ -57,8 +60,11 @@ pub fn generate(
                 backend,
                 opt_level: OptLevel::Development,
                 emit_debug_info: false,
-                emit_llvm_ir: false,
+                emit: EmitArtifacts::default(),
                 fuzz: false,
+                profiling: false,
+                sanitizer: None,
+                atomic_refcount: false,
             };
 
             let load_config = standard_load_config(
@@ -104,6 +110,7 @@ pub fn generate(
                     problems,
                     total_time,
                     expect_metadata: _,
+                    platform_main_roc: _,
                 }) => {
                     // TODO: Should binary_path be update to deal with extensions?
                     use roc_target::OperatingSystem;
@@ -422,6 +429,9 @@ pub fn load_types(
             palette: DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Check,
+            progress: None,
+            tabs: Tabs::from_env(),
+            numeric_defaults: NumericDefaults::from_env(),
         },
     )
     .unwrap_or_else(|problem| match problem {
@@ -223,22 +223,13 @@ mod glue_cli_run {
                 .expect("Unable to remove test_glue dir in order to regenerate it in the test");
         }
 
-        let rust_glue_spec = fixture_templates_dir
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .join("src")
-            .join("RustGlue.roc");
-
         // Generate a fresh test_glue for this platform
         let parts : Vec<_> =
             // converting these all to String avoids lifetime issues
-            std::iter::once("glue".to_string()).chain(
+            ["glue".to_string(), "--lang".to_string(), "rust".to_string()].into_iter().chain(
                 args.into_iter().map(|arg| arg.to_string()).chain([
-                    rust_glue_spec.to_str().unwrap().to_string(),
-                    glue_dir.to_str().unwrap().to_string(),
                     platform_module_path.to_str().unwrap().to_string(),
+                    glue_dir.to_str().unwrap().to_string(),
                 ]),
             ).collect();
         let glue_out = run_glue(parts.iter());
@@ -14,7 +14,6 @@ pub fn highlight_roc_code_inline(code: &str) -> String {
 
 pub fn highlight(code: &str) -> Vec<String> {
     let mut buf: Vec<String> = Vec::new();
-    let mut offset = 0;
 
     // Sometimes code snippets start with "»" in order to show that they're in the repl.
     // Special-case that even though it's normally not a valid highlight.
@@ -28,83 +27,118 @@ pub fn highlight(code: &str) -> Vec<String> {
         code
     };
 
-    for location in roc_parse::highlight::highlight(code) {
-        let current_text = &code[offset..location.byte_range().end];
-
-        match location.value {
-            // Comments `#` and Documentation comments `##`
-            Token::LineComment | Token::DocComment => {
-                buf = push_html_span(buf, current_text, "comment");
-            }
-            // Number, String, Tag, Type literals
-            Token::SingleQuote
-            | Token::String
-            | Token::UnicodeEscape
-            | Token::EscapedChar
-            | Token::Interpolated
-            | Token::Number => {
-                buf = push_html_span(buf, current_text, "literal");
-            }
-            // Keywords and punctuation
-            Token::Keyword
-            | Token::Equals
-            | Token::Backslash
-            | Token::Pizza
-            | Token::Arrow
-            | Token::Backpass
-            | Token::ColonEquals
-            | Token::Colon
-            | Token::And
-            | Token::QuestionMark => {
-                buf = push_html_span(buf, current_text, "kw");
-            }
-            // Operators
-            Token::Percent
-            | Token::Caret
-            | Token::Bang
-            | Token::BangEquals
-            | Token::Slash
-            | Token::DoubleSlash
-            | Token::Pipe
-            | Token::GreaterThan
-            | Token::GreaterThanEquals
-            | Token::Minus
-            | Token::LessThan
-            | Token::LessThanEquals
-            | Token::DoubleEquals
-            | Token::DoubleBar
-            | Token::Multiply
-            | Token::Plus
-            | Token::DoubleAnd => {
-                buf = push_html_span(buf, current_text, "op");
-            }
-            // Delimieters
-            Token::Paren
-            | Token::Bracket
-            | Token::Brace
-            | Token::Comma
-            | Token::Bar
-            | Token::Decimal => {
-                buf = push_html_span(buf, current_text, "delimeter");
-            }
-            // Types, Tags, and Modules
-            Token::UpperIdent | Token::AtSign => {
-                buf = push_html_span(buf, current_text, "upperident");
-            }
-            // Variables modules and field names
-            Token::LowerIdent | Token::Underscore => {
-                buf = push_html_span(buf, current_text, "lowerident");
-            }
-            // Anyting else that wasn't tokenised
-            Token::Error | Token::Other => {
-                buf = push_html(buf, current_text);
-            }
+    for (kind, text) in highlight_kinds(code) {
+        buf = match kind.css_class() {
+            Some(class) => push_html_span(buf, text, class),
+            None => push_html(buf, text),
+        };
+    }
+
+    buf
+}
+
+/// What a piece of code is, for deciding how to color it. Both the docs and the REPL color code
+/// this way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightKind {
+    /// Comments `#` and documentation comments `##`
+    Comment,
+    /// Numbers, strings, and characters
+    Literal,
+    /// Keywords, and punctuation like `=` and `->`
+    Keyword,
+    Operator,
+    Delimiter,
+    /// Types, tags, and modules
+    UpperIdent,
+    /// Variables and field names
+    LowerIdent,
+    /// Anything that wasn't tokenized
+    Plain,
+}
+
+impl HighlightKind {
+    fn css_class(self) -> Option<&'static str> {
+        match self {
+            HighlightKind::Comment => Some("comment"),
+            HighlightKind::Literal => Some("literal"),
+            HighlightKind::Keyword => Some("kw"),
+            HighlightKind::Operator => Some("op"),
+            HighlightKind::Delimiter => Some("delimeter"),
+            HighlightKind::UpperIdent => Some("upperident"),
+            HighlightKind::LowerIdent => Some("lowerident"),
+            HighlightKind::Plain => None,
         }
+    }
+}
 
-        offset = location.byte_range().end;
+/// Splits the code into pieces to color, covering all of it. Each piece starts right after the
+/// previous one, so whitespace ends up at the start of the piece after it.
+pub fn highlight_kinds(code: &str) -> Vec<(HighlightKind, &str)> {
+    let mut pieces = Vec::new();
+    let mut offset = 0;
+
+    for location in roc_parse::highlight::highlight(code) {
+        let end = location.byte_range().end;
+
+        pieces.push((token_kind(location.value), &code[offset..end]));
+
+        offset = end;
     }
 
-    buf
+    if offset < code.len() {
+        pieces.push((HighlightKind::Plain, &code[offset..]));
+    }
+
+    pieces
+}
+
+fn token_kind(token: Token) -> HighlightKind {
+    match token {
+        Token::LineComment | Token::DocComment => HighlightKind::Comment,
+        Token::SingleQuote
+        | Token::String
+        | Token::UnicodeEscape
+        | Token::EscapedChar
+        | Token::Interpolated
+        | Token::Number => HighlightKind::Literal,
+        Token::Keyword
+        | Token::Equals
+        | Token::Backslash
+        | Token::Pizza
+        | Token::Arrow
+        | Token::Backpass
+        | Token::ColonEquals
+        | Token::Colon
+        | Token::And
+        | Token::QuestionMark => HighlightKind::Keyword,
+        Token::Percent
+        | Token::Caret
+        | Token::Bang
+        | Token::BangEquals
+        | Token::Slash
+        | Token::DoubleSlash
+        | Token::Pipe
+        | Token::GreaterThan
+        | Token::GreaterThanEquals
+        | Token::Minus
+        | Token::LessThan
+        | Token::LessThanEquals
+        | Token::DoubleEquals
+        | Token::DoubleBar
+        | Token::Multiply
+        | Token::Plus
+        | Token::DoubleAnd => HighlightKind::Operator,
+        Token::Paren
+        | Token::Bracket
+        | Token::Brace
+        | Token::Comma
+        | Token::Bar
+        | Token::Decimal => HighlightKind::Delimiter,
+        Token::UpperIdent | Token::AtSign => HighlightKind::UpperIdent,
+        Token::LowerIdent | Token::Underscore => HighlightKind::LowerIdent,
+        Token::Error | Token::Other => HighlightKind::Plain,
+    }
 }
 
 fn push_html_span(mut buf: Vec<String>, curr: &str, class: &str) -> Vec<String> {
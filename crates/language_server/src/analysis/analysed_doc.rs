@@ -3,6 +3,7 @@ use std::collections::HashMap;
 
 use bumpalo::Bump;
 
+use roc_fmt::{edits::format_edits, Buf};
 use roc_module::symbol::{ModuleId, Symbol};
 
 use roc_region::all::LineInfo;
@@ -64,12 +65,6 @@ impl DocInfo {
         );
     }
 
-    fn whole_document_range(&self) -> Range {
-        let start = Position::new(0, 0);
-        let end = Position::new(self.line_info.num_lines(), 0);
-        Range::new(start, end)
-    }
-
     pub fn get_prefix_at_position(&self, position: Position) -> String {
         let position = position.to_roc_position(&self.line_info);
         let offset = position.offset as usize;
@@ -88,15 +83,17 @@ impl DocInfo {
         let source = &self.source;
         let arena = &Bump::new();
 
-        let ast = Ast::parse(arena, source).ok()?;
-        let fmt = ast.fmt();
+        let edits = format_edits(arena, source, Buf::new_in(arena)).ok()?;
 
-        if source == fmt.as_str() {
+        if edits.is_empty() {
             None
         } else {
-            let range = self.whole_document_range();
-            let text_edit = TextEdit::new(range, fmt.to_string().to_string());
-            Some(vec![text_edit])
+            let text_edits = edits
+                .into_iter()
+                .map(|edit| TextEdit::new(edit.region.to_range(&self.line_info), edit.replacement))
+                .collect();
+
+            Some(text_edits)
         }
     }
 
@@ -0,0 +1,4 @@
+//! The standalone `roc_language_server` binary. This is the same server as `roc lsp`.
+fn main() {
+    roc_language_server::start();
+}
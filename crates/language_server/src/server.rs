@@ -1,3 +1,5 @@
+//! A Language Server Protocol server for Roc, which is started by `roc lsp` (or by the
+//! standalone `roc_language_server` binary).
 use analysis::HIGHLIGHT_TOKENS_LEGEND;
 
 use log::{debug, trace};
@@ -351,8 +353,9 @@ where
     }
 }
 
+/// Serves the language server protocol over stdin and stdout until the client disconnects.
 #[tokio::main]
-async fn main() {
+pub async fn start() {
     env_logger::Builder::from_env("ROCLS_LOG").init();
 
     let stdin = tokio::io::stdin();
@@ -6,7 +6,9 @@
 use memmap2::{Mmap, MmapMut};
 use object::Object;
 use roc_error_macros::internal_error;
-use roc_load::{EntryPoint, ExecutionMode, ExposedToHost, LoadConfig, Threading};
+use roc_load::{
+    EntryPoint, ExecutionMode, ExposedToHost, LoadConfig, NumericDefaults, Tabs, Threading,
+};
 use roc_module::symbol::Interns;
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
@@ -29,6 +31,7 @@ pub enum LinkType {
     Executable = 0,
     Dylib = 1,
     None = 2,
+    StaticLib = 3,
 }
 
 pub fn supported(link_type: LinkType, target: Target) -> bool {
@@ -89,6 +92,9 @@ pub fn generate_stub_lib(
             palette: DEFAULT_PALETTE,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Executable,
+            progress: None,
+            tabs: Tabs::from_env(),
+            numeric_defaults: NumericDefaults::from_env(),
         },
     )
     .unwrap_or_else(|problem| todo!("{:?}", problem));
@@ -205,10 +205,40 @@ const ROC_CACHE_DIR_NAME: &str = "Roc";
 // e.g. the "roc" in ~/.cache/roc
 const ROC_CACHE_DIR_NAME: &str = "roc";
 
+/// The environment variable which overrides where roc's cache dir is.
+pub const ROC_CACHE_DIR_VAR: &str = "ROC_CACHE_DIR";
+
+/// Set by [use_temp_cache_dir]. This isn't passed on through ROC_CACHE_DIR, because then the
+/// apps that `roc run` starts would see it too.
+#[cfg(not(target_family = "wasm"))]
+static TEMP_CACHE_DIR: std::sync::Mutex<Option<tempfile::TempDir>> = std::sync::Mutex::new(None);
+
+/// Makes [roc_cache_dir] return a new temporary directory (e.g. for `roc --no-cache`), until
+/// [remove_temp_cache_dir] deletes it.
+#[cfg(not(target_family = "wasm"))]
+pub fn use_temp_cache_dir() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    *TEMP_CACHE_DIR.lock().unwrap() = Some(dir);
+
+    Ok(())
+}
+
+/// Deletes the directory made by [use_temp_cache_dir], if there is one. This has to happen before
+/// roc exits or replaces itself with the app it built, since neither runs destructors.
+#[cfg(not(target_family = "wasm"))]
+pub fn remove_temp_cache_dir() {
+    if let Some(dir) = TEMP_CACHE_DIR.lock().unwrap().take() {
+        // Failing to clean up a temp dir shouldn't fail the build.
+        let _ = dir.close();
+    }
+}
+
 /// This looks up environment variables, so it should ideally be called once and then cached!
 ///
-/// Returns a path of the form cache_dir_path.join(ROC_CACHE_DIR_NAME)
-/// where cache_dir_path is:
+/// Returns the directory made by [use_temp_cache_dir], if there is one.
+/// Otherwise, returns the ROC_CACHE_DIR environment variable, if it's set. Otherwise, returns a path of
+/// the form cache_dir_path.join(ROC_CACHE_DIR_NAME) where cache_dir_path is:
 /// - The XDG_CACHE_HOME environment varaible, if it's set.
 /// - Otherwise, ~/.cache on UNIX and %APPDATA% on Windows.
 ///
@@ -216,12 +246,20 @@ const ROC_CACHE_DIR_NAME: &str = "roc";
 ///
 /// So ~/.cache/roc will be typical on UNIX, and %APPDATA%\\Roc will be typical on Windows.
 ///
-/// Returns None if XDG_CACHE_HOME is not set, and also we can't determine the home directory
+/// Exits if none of those are set, and also we can't determine the home directory
 /// (or if %APPDATA% is missing on Windows) on this system.
 #[cfg(not(target_family = "wasm"))]
 pub fn roc_cache_dir() -> PathBuf {
     use std::{env, process};
 
+    if let Some(dir) = TEMP_CACHE_DIR.lock().unwrap().as_ref() {
+        return dir.path().to_path_buf();
+    }
+
+    if let Some(dir) = env::var_os(ROC_CACHE_DIR_VAR).filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+
     // Respect XDG, if the system appears to be using it.
     // https://specifications.freedesktop.org/basedir-spec/basedir-spec-latest.html
     match env::var_os("XDG_CACHE_HOME") {
@@ -268,3 +306,21 @@ pub fn roc_cache_packages_dir() -> PathBuf {
     const PACKAGES_DIR_NAME: &str = "packages";
     roc_cache_dir().join(PACKAGES_DIR_NAME)
 }
+
+/// Deletes all the downloaded packages (including any prebuilt platforms they came with), and
+/// returns the dir they were in, or None if there weren't any.
+///
+/// Only the directories roc creates inside the cache dir are deleted, not the cache dir itself,
+/// since ROC_CACHE_DIR could point anywhere.
+#[cfg(not(target_family = "wasm"))]
+pub fn clean() -> std::io::Result<Option<PathBuf>> {
+    let packages_dir = roc_cache_packages_dir();
+
+    if packages_dir.exists() {
+        fs::remove_dir_all(&packages_dir)?;
+
+        Ok(Some(packages_dir))
+    } else {
+        Ok(None)
+    }
+}
@@ -63,10 +63,17 @@ impl<'a> TryFrom<&'a str> for Compression {
 /// the name of that filename (including the .tar extension),
 /// so the caller can obtain the path to the file by calling
 /// Path::with_file_name(returned_string) on the Path argument it provided.
-pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String> {
+///
+/// If `deterministic` is set, the archive leaves out file metadata (like modification times)
+/// that can differ between two checkouts of the same source, so they bundle to the same bytes.
+pub fn build(
+    path_to_main: &Path,
+    compression: Compression,
+    deterministic: bool,
+) -> io::Result<String> {
     let mut archive_bytes = Vec::new();
 
-    write_archive(path_to_main, &mut archive_bytes)?;
+    write_archive(path_to_main, &mut archive_bytes, deterministic)?;
 
     // Now that we have our compressed archive, get its BLAKE3 hash
     // and base64url encode it. Use base64url encoding because:
@@ -113,7 +120,7 @@ pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String
 }
 
 /// Write an uncompressed tar archive to the given writer.
-fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
+fn write_archive<W: Write>(path: &Path, writer: W, deterministic: bool) -> io::Result<()> {
     let root_dir = if let Some(parent) = path.parent() {
         parent
     } else {
@@ -124,6 +131,11 @@ fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
         std::process::exit(1);
     };
     let mut builder = tar::Builder::new(writer);
+
+    if deterministic {
+        builder.mode(tar::HeaderMode::Deterministic);
+    }
+
     let arena = Bump::new();
     let mut buf = Vec::new();
 
@@ -148,9 +160,14 @@ fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
         Header::Platform(PlatformHeader { imports: _, .. }) => {
             // Add all the prebuilt host files to the archive.
             // These should all be in the same directory as the platform module.
-            for entry in std::fs::read_dir(root_dir)? {
-                let path = entry?.path();
+            // Sort the entries, since the order read_dir returns them in varies by filesystem.
+            let mut paths = std::fs::read_dir(root_dir)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<_>>>()?;
 
+            paths.sort();
+
+            for path in paths {
                 if [
                     // surgical linker format
                     Some("rh"),
@@ -228,12 +245,16 @@ fn add_source_files<W: Write>(
     root_dir: &Path,
     builder: &mut tar::Builder<W>,
 ) -> Result<(), io::Error> {
-    for entry in WalkDir::new(root_dir).into_iter().filter_entry(|entry| {
-        let path = entry.path();
-
-        // Ignore everything except directories and .roc files
-        path.is_dir() || path.extension().and_then(OsStr::to_str) == Some("roc")
-    }) {
+    for entry in WalkDir::new(root_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let path = entry.path();
+
+            // Ignore everything except directories and .roc files
+            path.is_dir() || path.extension().and_then(OsStr::to_str) == Some("roc")
+        })
+    {
         let entry = entry?;
         let path = entry.path();
 
@@ -296,7 +317,9 @@ fn add_ingested_files<W: Write>(
 
     RecursiveValueDefIter::new(&defs).try_for_each(|(def, _)| {
         if let ValueDef::IngestedFileImport(IngestedFileImport { path, .. }) = def {
-            if let StrLiteral::PlainLine(relative_path_str) = path.value {
+            if let StrLiteral::PlainLine(relative_path_str) | StrLiteral::Raw(relative_path_str) =
+                path.value
+            {
                 let relative_path: PathBuf = relative_path_str.into();
 
                 if relative_path_str.contains("..") {
@@ -12,7 +12,7 @@ use roc_mono::ir::OptLevel;
 use roc_mono::layout::STLayoutInterner;
 use roc_parse::ast::Expr;
 use roc_repl_eval::eval::jit_to_ast;
-use roc_repl_eval::gen::{format_answer, ReplOutput};
+use roc_repl_eval::gen::{format_answer, AnswerFormat, ReplOutput};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_std::RocStr;
 use roc_target::Target;
@@ -23,6 +23,7 @@ pub fn eval_llvm(
     mut loaded: MonomorphizedModule<'_>,
     target: Target,
     opt_level: OptLevel,
+    answer_format: AnswerFormat,
 ) -> Option<ReplOutput> {
     let arena = Bump::new();
 
@@ -82,7 +83,7 @@ pub fn eval_llvm(
         target,
     );
 
-    let expr_str = format_answer(&arena, expr).to_string();
+    let expr_str = format_answer(&arena, expr, answer_format).to_string();
 
     Some(ReplOutput {
         expr: expr_str,
@@ -128,8 +129,10 @@ impl<'a> ReplApp<'a> for CliApp {
 macro_rules! deref_number {
     ($name: ident, $t: ty) => {
         fn $name(&self, addr: usize) -> $t {
-            let ptr = addr as *const _;
-            unsafe { *ptr }
+            // Fields of records and tags aren't always aligned the way Rust expects, which matters
+            // for 128-bit numbers.
+            let ptr = addr as *const $t;
+            unsafe { ptr.read_unaligned() }
         }
     };
 }
@@ -195,7 +198,7 @@ fn mono_module_to_dylib_llvm<'a>(
     let context = Context::create();
     let builder = context.create_builder();
     let module = arena.alloc(roc_gen_llvm::llvm::build::module_from_builtins(
-        target, &context, "",
+        target, &context, "", false,
     ));
 
     let module = arena.alloc(module);
@@ -217,6 +220,7 @@ fn mono_module_to_dylib_llvm<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        source_locations: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -1,23 +1,22 @@
 //! Command Line Interface (CLI) functionality for the Read-Evaluate-Print-Loop (REPL).
 mod cli_gen;
 
-use bumpalo::Bump;
 use const_format::concatcp;
+use roc_highlight::{highlight_kinds, HighlightKind};
 use roc_load::MonomorphizedModule;
 use roc_mono::ir::OptLevel;
-use roc_repl_eval::gen::Problems;
+use roc_repl_eval::gen::{AnswerFormat, Problems, ReplOutput};
 use roc_repl_ui::colors::{CYAN, END_COL};
-use roc_repl_ui::repl_state::{ReplAction, ReplState};
-use roc_repl_ui::{format_output, is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS, TIPS};
-use roc_reporting::report::{
-    strip_colors, to_file_problem_report_string, ANSI_STYLE_CODES, DEFAULT_PALETTE,
-};
+use roc_repl_ui::session::{ReplEvaluator, ReplResponse, ReplSession};
+use roc_repl_ui::{format_output, is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS};
+use roc_reporting::report::{strip_colors, ANSI_STYLE_CODES};
 use roc_target::Target;
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
 use rustyline::Config;
 use rustyline_derive::{Completer, Helper, Hinter};
 use std::borrow::Cow;
+use std::path::Path;
 use target_lexicon::Triple;
 
 use crate::cli_gen::eval_llvm;
@@ -34,10 +33,10 @@ pub const WELCOME_MESSAGE: &str = concatcp!(
 #[derive(Completer, Helper, Hinter, Default)]
 pub struct ReplHelper {
     validator: InputValidator,
-    state: ReplState,
 }
 
-pub fn main(has_color: bool, has_header: bool) -> i32 {
+/// Runs the REPL. If `opt_load_path` is given, that module is loaded as if by `:load` first.
+pub fn main(has_color: bool, has_header: bool, opt_load_path: Option<&Path>) -> i32 {
     use rustyline::error::ReadlineError;
     use rustyline::Editor;
 
@@ -69,44 +68,32 @@ pub fn main(has_color: bool, has_header: bool) -> i32 {
         Editor::<ReplHelper>::with_config(Config::builder().color_mode(editor_color_mode).build());
     let repl_helper = ReplHelper::default();
     editor.set_helper(Some(repl_helper));
-    let target = Triple::host().into();
-    let mut arena = Bump::new();
+    let mut session = ReplSession::new(LlvmEvaluator, Triple::host().into());
+    let mut opt_pending_line = opt_load_path.map(|path| format!(":load {}", path.display()));
 
     loop {
-        match editor.readline(&strip_colors_if_necessary(PROMPT)) {
+        let readline = match opt_pending_line.take() {
+            Some(line) => Ok(line),
+            None => editor.readline(&strip_colors_if_necessary(PROMPT)),
+        };
+
+        match readline {
             Ok(line) => {
                 let line = line.trim();
 
                 editor.add_history_entry(line);
 
-                let repl_state = &mut editor
-                    .helper_mut()
-                    .expect("Editor helper was not set")
-                    .state;
-
-                arena.reset();
-                match repl_state.step(&arena, line, target, DEFAULT_PALETTE) {
-                    ReplAction::Eval { opt_mono, problems } => {
-                        let output = evaluate(opt_mono, problems, target);
-                        // If there was no output, don't print a blank line!
-                        // (This happens for something like a type annotation.)
-                        if !output.is_empty() {
-                            println!("{}", strip_colors_if_necessary(&output));
-                        }
-                    }
-                    ReplAction::Exit => {
-                        return 0;
+                match session.eval(line) {
+                    ReplResponse::Output(output) => {
+                        print!("{}", strip_colors_if_necessary(&output));
                     }
-                    ReplAction::FileProblem { filename, error } => {
-                        println!(
-                            "{}",
-                            to_file_problem_report_string(filename, error, has_color)
-                        );
+                    ReplResponse::Clear => {
+                        // Clear the screen, and move the cursor to the top left.
+                        print!("\x1B[2J\x1B[H");
                     }
-                    ReplAction::Help => {
-                        println!("{TIPS}");
+                    ReplResponse::Exit => {
+                        return 0;
                     }
-                    ReplAction::Nothing => {}
                 }
             }
             #[cfg(windows)]
@@ -129,12 +116,29 @@ pub fn main(has_color: bool, has_header: bool) -> i32 {
     }
 }
 
+/// Runs REPL inputs with the LLVM JIT.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LlvmEvaluator;
+
+impl ReplEvaluator for LlvmEvaluator {
+    fn eval(
+        &mut self,
+        mono: MonomorphizedModule<'_>,
+        target: Target,
+        answer_format: AnswerFormat,
+    ) -> Option<ReplOutput> {
+        eval_llvm(mono, target, OptLevel::Normal, answer_format)
+    }
+}
+
 pub fn evaluate(
     opt_mono: Option<MonomorphizedModule<'_>>,
     problems: Problems,
     target: Target,
+    answer_format: AnswerFormat,
 ) -> String {
-    let opt_output = opt_mono.and_then(|mono| eval_llvm(mono, target, OptLevel::Normal));
+    let opt_output =
+        opt_mono.and_then(|mono| eval_llvm(mono, target, OptLevel::Normal, answer_format));
     format_output(ANSI_STYLE_CODES, opt_output, problems)
 }
 
@@ -152,6 +156,38 @@ impl Validator for InputValidator {
 }
 
 impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let mut buf = String::with_capacity(line.len());
+
+        for (kind, text) in highlight_kinds(line) {
+            let color = match kind {
+                HighlightKind::Keyword => ANSI_STYLE_CODES.cyan,
+                HighlightKind::Literal => ANSI_STYLE_CODES.green,
+                HighlightKind::UpperIdent => ANSI_STYLE_CODES.yellow,
+                HighlightKind::Comment => ANSI_STYLE_CODES.white,
+                HighlightKind::Operator
+                | HighlightKind::Delimiter
+                | HighlightKind::LowerIdent
+                | HighlightKind::Plain => {
+                    buf.push_str(text);
+
+                    continue;
+                }
+            };
+
+            buf.push_str(color);
+            buf.push_str(text);
+            buf.push_str(ANSI_STYLE_CODES.reset);
+        }
+
+        buf.into()
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        // Any character can change how the rest of the input is highlighted, like a `"` or a `#`.
+        true
+    }
+
     fn has_continuation_prompt(&self) -> bool {
         true
     }
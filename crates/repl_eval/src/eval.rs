@@ -408,12 +408,14 @@ fn jit_to_ast_help<'a, A: ReplApp<'a>>(
                 U16 => num_helper!(u16),
                 U32 => num_helper!(u32),
                 U64 => num_helper!(u64),
-                U128 => num_helper!(u128),
+                // Rust and LLVM disagree on how to return a 128-bit integer, so use the same
+                // 16-byte-aligned struct as Dec.
+                U128 => num_helper!(roc_std::U128),
                 I8 => num_helper!(i8),
                 I16 => num_helper!(i16),
                 I32 => num_helper!(i32),
                 I64 => num_helper!(i64),
-                I128 => num_helper!(i128),
+                I128 => num_helper!(roc_std::I128),
             }
         }
         LayoutRepr::Builtin(Builtin::Float(float_width)) => {
@@ -1,15 +1,18 @@
 use bumpalo::Bump;
-use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_load::{
+    ExecutionMode, LoadConfig, LoadMonomorphizedError, NumericDefaults, Tabs, Threading,
+};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_problem::Severity;
 use roc_reporting::report::Palette;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use roc_fmt::annotation::Formattable;
 use roc_fmt::annotation::{Newlines, Parens};
+use roc_fmt::spaces::INDENT;
 use roc_load::{LoadingProblem, MonomorphizedModule};
-use roc_parse::ast::Expr;
-use roc_region::all::LineInfo;
+use roc_parse::ast::{AssignedField, Collection, Expr};
+use roc_region::all::{LineInfo, Loc};
 use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 use roc_solve::FunctionKind;
 use roc_target::Target;
@@ -20,19 +23,201 @@ pub struct ReplOutput {
     pub expr_type: String,
 }
 
-pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
+/// What functions are shown as, since there's no way to show what they do.
+pub const FUNCTION_ANSWER: &str = "<function>";
+
+/// How the REPL lays out answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnswerFormat {
+    /// Answers wider than this many columns are split over multiple lines, one field or element
+    /// per line.
+    pub width: usize,
+    /// Lists, records, tuples, and tags with payloads nested deeper than this are elided with `…`.
+    pub max_depth: Option<usize>,
+}
+
+pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'a>, format: AnswerFormat) -> &'a str {
     match answer {
-        Expr::Closure(_, _) | Expr::MalformedClosure => "<function>",
+        Expr::Closure(_, _) | Expr::MalformedClosure => FUNCTION_ANSWER,
         _ => {
-            let mut expr = roc_fmt::Buf::new_in(arena);
+            let answer = match format.max_depth {
+                Some(max_depth) => elide(arena, &answer, max_depth),
+                None => answer,
+            };
+            let mut buf = String::new();
 
-            answer.format_with_options(&mut expr, Parens::NotNeeded, Newlines::Yes, 0);
+            write_answer(arena, &answer, format.width, 0, 0, &mut buf);
 
-            expr.into_bump_str()
+            arena.alloc_str(&buf)
         }
     }
 }
 
+/// Replaces everything nested more than `depth` levels down with `…`.
+fn elide<'a>(arena: &'a Bump, expr: &Expr<'a>, depth: usize) -> Expr<'a> {
+    let is_nested = match expr {
+        Expr::List(items) | Expr::Tuple(items) => !items.is_empty(),
+        Expr::Record(fields) => !fields.is_empty(),
+        Expr::Apply(_, args, _) => !args.is_empty(),
+        _ => false,
+    };
+
+    if !is_nested {
+        return *expr;
+    } else if depth == 0 {
+        return Expr::Var {
+            module_name: "",
+            ident: "…",
+        };
+    }
+
+    let elide_loc = |loc_expr: &&'a Loc<Expr<'a>>| -> &'a Loc<Expr<'a>> {
+        arena.alloc(Loc::at(
+            loc_expr.region,
+            elide(arena, &loc_expr.value, depth - 1),
+        ))
+    };
+
+    match expr {
+        Expr::List(items) => Expr::List(Collection::with_items(
+            arena.alloc_slice_fill_iter(items.items.iter().map(elide_loc)),
+        )),
+        Expr::Tuple(items) => Expr::Tuple(Collection::with_items(
+            arena.alloc_slice_fill_iter(items.items.iter().map(elide_loc)),
+        )),
+        Expr::Record(fields) => Expr::Record(Collection::with_items(arena.alloc_slice_fill_iter(
+            fields.items.iter().map(|field| match field.value {
+                AssignedField::RequiredValue(name, spaces, value) => Loc::at(
+                    field.region,
+                    AssignedField::RequiredValue(name, spaces, elide_loc(&value)),
+                ),
+                _ => *field,
+            }),
+        ))),
+        Expr::Apply(tag, args, called_via) => Expr::Apply(
+            tag,
+            arena.alloc_slice_fill_iter(args.iter().map(elide_loc)),
+            *called_via,
+        ),
+        _ => *expr,
+    }
+}
+
+/// Writes the expr on one line if it fits in `width` when starting at `column`, and otherwise
+/// splits its fields or elements over multiple lines, indented one level past `indent`.
+fn write_answer<'a>(
+    arena: &'a Bump,
+    expr: &Expr<'a>,
+    width: usize,
+    indent: usize,
+    column: usize,
+    buf: &mut String,
+) {
+    let one_line = format_one_line(arena, expr);
+
+    if column + one_line.chars().count() <= width {
+        buf.push_str(one_line);
+
+        return;
+    }
+
+    let item_indent = indent + INDENT as usize;
+
+    match expr {
+        Expr::List(items) | Expr::Tuple(items) if !items.is_empty() => {
+            let (start, end) = match expr {
+                Expr::List(_) => ('[', ']'),
+                _ => ('(', ')'),
+            };
+
+            buf.push(start);
+
+            for item in items.iter() {
+                push_newline(buf, item_indent);
+                write_answer(arena, &item.value, width, item_indent, item_indent, buf);
+                buf.push(',');
+            }
+
+            push_newline(buf, indent);
+            buf.push(end);
+        }
+        Expr::Record(fields)
+            if !fields.is_empty()
+                && fields
+                    .iter()
+                    .all(|field| matches!(field.value, AssignedField::RequiredValue(..))) =>
+        {
+            buf.push('{');
+
+            for field in fields.iter() {
+                if let AssignedField::RequiredValue(name, _, value) = field.value {
+                    push_newline(buf, item_indent);
+                    buf.push_str(name.value);
+                    buf.push_str(": ");
+
+                    let column = item_indent + name.value.chars().count() + 2;
+
+                    write_answer(arena, &value.value, width, item_indent, column, buf);
+                    buf.push(',');
+                }
+            }
+
+            push_newline(buf, indent);
+            buf.push('}');
+        }
+        Expr::Apply(tag, args, _) if !args.is_empty() => {
+            let tag = format_one_line(arena, &tag.value);
+
+            buf.push_str(tag);
+
+            match args {
+                // Keep the opening bracket of a lone collection on the tag's line, like `Ok {`.
+                [arg] if matches!(arg.value, Expr::List(_) | Expr::Record(_) | Expr::Tuple(_)) => {
+                    buf.push(' ');
+
+                    let column = column + tag.chars().count() + 1;
+
+                    write_answer(arena, &arg.value, width, indent, column, buf);
+                }
+                _ => {
+                    for arg in args.iter() {
+                        push_newline(buf, item_indent);
+
+                        if matches!(arg.value, Expr::Apply(_, args, _) if !args.is_empty()) {
+                            buf.push('(');
+                            write_answer(
+                                arena,
+                                &arg.value,
+                                width,
+                                item_indent,
+                                item_indent + 1,
+                                buf,
+                            );
+                            buf.push(')');
+                        } else {
+                            write_answer(arena, &arg.value, width, item_indent, item_indent, buf);
+                        }
+                    }
+                }
+            }
+        }
+        _ => buf.push_str(one_line),
+    }
+}
+
+fn format_one_line<'a>(arena: &'a Bump, expr: &Expr<'_>) -> &'a str {
+    let mut buf = roc_fmt::Buf::new_in(arena);
+
+    expr.format_with_options(&mut buf, Parens::NotNeeded, Newlines::Yes, 0);
+
+    buf.into_bump_str()
+}
+
+fn push_newline(buf: &mut String, indent: usize) {
+    buf.push('\n');
+    buf.push_str(&" ".repeat(indent));
+}
+
 #[derive(Default, Debug)]
 pub struct Problems {
     pub errors: Vec<String>,
@@ -49,11 +234,13 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
     defs: I,
     expr: &str,
+    src_dir: &Path,
     target: Target,
     palette: Palette,
+    report_width: usize,
 ) -> (Option<MonomorphizedModule<'a>>, Problems) {
     let filename = PathBuf::from("replfile.roc");
-    let src_dir = PathBuf::from(".");
+    let src_dir = src_dir.to_path_buf();
     let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -69,6 +256,9 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             palette,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            progress: None,
+            tabs: Tabs::Reject,
+            numeric_defaults: NumericDefaults::Silent,
         },
     );
 
@@ -94,6 +284,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         }
     };
 
+    let repl_module_id = loaded.module_id;
     let MonomorphizedModule {
         interns,
         sources,
@@ -117,7 +308,10 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             continue;
         }
 
-        let line_info = LineInfo::new(module_src);
+        // Modules the REPL imports (e.g. with `:load`) get recompiled on every evaluation, so only
+        // report their errors; repeating their warnings every time would be noise.
+        let report_warnings = *home == repl_module_id;
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
 
         // Report parsing and canonicalization problems
@@ -128,14 +322,17 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             // because they must be part of the defs (excluding the most renently added def,
             // if that's the one being evaluated) and therefore not things we should show.
             // This filters out things like shadowing warnings and unused def warnings.
-            if problem.region().unwrap_or_default().end().offset as usize >= bytes_before_expr {
+            if *home != repl_module_id
+                || problem.region().unwrap_or_default().end().offset as usize >= bytes_before_expr
+            {
                 let report = can_problem(&alloc, &line_info, module_path.clone(), problem);
                 let severity = report.severity;
                 let mut buf = String::new();
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                report.render_color_terminal_with_width(report_width, &mut buf, &alloc, &palette);
 
                 match severity {
+                    Severity::Warning if !report_warnings => {}
                     Severity::Warning => {
                         warnings.push(buf);
                     }
@@ -151,9 +348,10 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
                 let severity = report.severity;
                 let mut buf = String::new();
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                report.render_color_terminal_with_width(report_width, &mut buf, &alloc, &palette);
 
                 match severity {
+                    Severity::Warning if !report_warnings => {}
                     Severity::Warning => {
                         warnings.push(buf);
                     }
@@ -95,7 +95,10 @@ mod test {
     use pretty_assertions::assert_eq;
     use roc_error_macros::internal_error;
     use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult, run_roc_dylib};
-    use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, Threading};
+    use roc_load::{
+        ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, NumericDefaults, Tabs,
+        Threading,
+    };
     use roc_packaging::cache::RocCacheDir;
     use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
     use target_lexicon::Triple;
@@ -124,6 +127,9 @@ mod test {
             palette: DEFAULT_PALETTE,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            progress: None,
+            tabs: Tabs::from_env(),
+            numeric_defaults: NumericDefaults::from_env(),
         };
         let loaded = match roc_load::load_and_monomorphize_from_str(
             arena,
@@ -4,6 +4,7 @@ use std::{
         atomic::{AtomicBool, AtomicU32},
         Arc,
     },
+    time::{Duration, Instant},
 };
 
 use bumpalo::collections::Vec as BumpVec;
@@ -236,6 +237,66 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     Ok((failed, passed))
 }
 
+/// Runs a top-level `expect` over and over for `roc bench`, and returns how long each of the
+/// timed runs took. The first run checks that the `expect` passes; if it doesn't, the failure is
+/// rendered like `roc test` would, and `None` is returned. That run and the `warmup` runs after it
+/// aren't timed.
+#[allow(clippy::too_many_arguments)]
+pub fn bench_toplevel_expect<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    lib: &libloading::Library,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expect: ToplevelExpect<'_>,
+    warmup: usize,
+    iterations: usize,
+) -> std::io::Result<Option<Vec<Duration>>> {
+    use roc_gen_llvm::try_run_jit_function;
+
+    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
+    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+
+    memory.set_shared_buffer(lib);
+
+    let passed = run_expect_pure(
+        writer,
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        lib,
+        expectations,
+        &mut memory,
+        expect,
+    )?;
+
+    if !passed {
+        return Ok(None);
+    }
+
+    let run = || {
+        // The expect passed once, and it's pure, so it will pass every time.
+        let _: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+    };
+
+    for _ in 0..warmup {
+        run();
+    }
+
+    let mut timings = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run();
+        timings.push(start.elapsed());
+    }
+
+    Ok(Some(timings))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_expect_pure<'a, W: std::io::Write>(
     writer: &mut W,
@@ -635,7 +696,7 @@ pub fn expect_mono_module_to_dylib<'a>(
     let context = Context::create();
     let builder = context.create_builder();
     let module = arena.alloc(roc_gen_llvm::llvm::build::module_from_builtins(
-        target, &context, "",
+        target, &context, "", false,
     ));
 
     let module = arena.alloc(module);
@@ -657,6 +718,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        source_locations: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -1,8 +1,9 @@
 use bumpalo::Bump;
 use indoc::indoc;
-use roc_repl_cli::{evaluate, ReplHelper};
+use roc_repl_cli::{evaluate, LlvmEvaluator, ReplHelper};
 use roc_repl_ui::is_incomplete;
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
+use roc_repl_ui::session::{ReplResponse, ReplSession};
 use roc_reporting::report::DEFAULT_PALETTE;
 use rustyline::Editor;
 use target_lexicon::Triple;
@@ -25,6 +26,21 @@ fn persisted_defs() {
     complete("y = 6", &mut state, "6 : Num *");
 }
 
+#[test]
+fn redefined_defs() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete(
+        "double = \\n -> n * 2",
+        &mut state,
+        "<function : Num a -> Num a>",
+    );
+    complete("x = \"five\"", &mut state, "\"five\" : Str");
+    complete("Str.concat x \"!\"", &mut state, "\"five!\" : Str");
+    complete("double 21", &mut state, "42 : Num *");
+}
+
 #[test]
 fn annotated_body() {
     let mut input = "t : [A, B, C]".to_string();
@@ -123,6 +139,41 @@ fn partial_record_definition() {
     }
 }
 
+#[test]
+fn unclosed_brackets() {
+    let mut input = "nums = [".to_string();
+    incomplete(&mut input);
+
+    input.push_str("1, 2,");
+    incomplete(&mut input);
+
+    input.push_str("3]");
+    complete(&input, &mut ReplState::new(), "[1, 2, 3] : List (Num *)");
+
+    // Brackets in strings don't count.
+    assert!(!is_incomplete(r#"Str.concat "(" "[""#));
+}
+
+#[test]
+fn trailing_operator() {
+    let mut input = "1 +".to_string();
+    incomplete(&mut input);
+
+    input.push_str("2 # a comment +");
+    complete(&input, &mut ReplState::new(), "3 : Num *");
+}
+
+#[test]
+fn indented_continuation() {
+    let mut input = r"addOne = \n ->".to_string();
+    incomplete(&mut input);
+
+    input.push_str("    n + 1");
+    incomplete(&mut input);
+
+    complete(&input, &mut ReplState::new(), "<function : Num a -> Num a>");
+}
+
 #[test]
 fn tips() {
     assert!(!is_incomplete(""));
@@ -132,6 +183,102 @@ fn tips() {
     assert!(matches!(action, ReplAction::Help));
 }
 
+#[test]
+fn commands() {
+    let mut state = ReplState::new();
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let mut step = |input: &str| state.step(&arena, input, target, DEFAULT_PALETTE);
+
+    assert!(matches!(step(":help set"), ReplAction::Message(_)));
+    assert!(matches!(step(":clear"), ReplAction::Clear));
+    assert!(matches!(step(":Q"), ReplAction::Exit));
+
+    // Commands with problems get a message instead of being evaluated.
+    assert!(matches!(step(":frobnicate"), ReplAction::Message(_)));
+    assert!(matches!(step(":clear everything"), ReplAction::Message(_)));
+    assert!(matches!(step(":set width lots"), ReplAction::Message(_)));
+    assert!(matches!(step(":load"), ReplAction::Message(_)));
+    assert!(!is_incomplete(":set width +"));
+
+    assert!(matches!(step(":set width 100"), ReplAction::Message(_)));
+    assert!(matches!(step(":set width 5"), ReplAction::Message(_)));
+    assert!(matches!(step(":set timing on"), ReplAction::Message(_)));
+    assert!(matches!(
+        step(":set timing sometimes"),
+        ReplAction::Message(_)
+    ));
+    assert_eq!(state.settings().width, 100);
+    assert!(state.settings().timing);
+}
+
+#[test]
+fn answer_layout() {
+    let mut state = ReplState::new();
+    let arena = Bump::new();
+    let target = Triple::host().into();
+
+    state.step(&arena, ":set width 30", target, DEFAULT_PALETTE);
+
+    complete(
+        r#"{ name: "Roc", tags: ["fast", "friendly", "functional"] }"#,
+        &mut state,
+        indoc!(
+            r#"
+            {
+                name: "Roc",
+                tags: [
+                    "fast",
+                    "friendly",
+                    "functional",
+                ],
+            } : { name : Str, tags : List Str }"#
+        ),
+    );
+
+    state.step(&arena, ":set depth 1", target, DEFAULT_PALETTE);
+
+    complete("[[1], [2, 3]]", &mut state, "[…, …] : List (List (Num *))");
+}
+
+#[test]
+fn reset() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete("x + 1", &mut state, "6 : Num *");
+
+    let arena = Bump::new();
+    let target = Triple::host().into();
+
+    assert!(matches!(
+        state.step(&arena, ":reset", target, DEFAULT_PALETTE),
+        ReplAction::Message(_)
+    ));
+
+    match state.step(&arena, "x + 1", target, DEFAULT_PALETTE) {
+        ReplAction::Eval { problems, .. } => assert!(!problems.errors.is_empty()),
+        action => panic!("Unexpected action: {:?}", action),
+    }
+}
+
+#[test]
+fn session() {
+    let mut session = ReplSession::new(LlvmEvaluator, Triple::host().into());
+    let mut eval = |input: &str| match session.eval(input) {
+        ReplResponse::Output(output) => {
+            String::from_utf8(strip_ansi_escapes::strip(output.trim()).unwrap()).unwrap()
+        }
+        response => format!("{response:?}"),
+    };
+
+    assert_eq!(eval("x = 41"), "41 : Num *");
+    assert_eq!(eval("x + 1"), "42 : Num *");
+    assert_eq!(eval("x : Str"), "");
+    assert_eq!(eval(":clear"), "Clear");
+    assert_eq!(eval(":q"), "Exit");
+}
+
 #[test]
 fn standalone_annotation() {
     let mut state = ReplState::new();
@@ -145,6 +292,61 @@ fn standalone_annotation() {
     assert!(matches!(action, ReplAction::Nothing));
 }
 
+#[test]
+fn load_module() {
+    let dir = tempfile::tempdir().unwrap();
+    let module_path = dir.path().join("Double.roc");
+    let app_path = dir.path().join("main.roc");
+
+    std::fs::write(&module_path, "module [double]\n\ndouble = \\n -> n * 2\n").unwrap();
+    std::fs::write(
+        &app_path,
+        "app [main] { pf: platform \"platform/main.roc\" }\n\nmain = 1\n",
+    )
+    .unwrap();
+
+    let mut state = ReplState::new();
+    let arena = Bump::new();
+    let target = Triple::host().into();
+
+    let load = format!(":load {}", module_path.display());
+
+    match state.step(&arena, &load, target, DEFAULT_PALETTE) {
+        ReplAction::Loaded {
+            exposed, problems, ..
+        } => {
+            assert_eq!(exposed, ["double"]);
+            assert!(problems.is_empty(), "{problems:?}");
+        }
+        action => panic!("Unexpected action: {:?}", action),
+    }
+
+    complete("double 21", &mut state, "42 : Num *");
+
+    // `:import` is another name for `:load`.
+    let import = format!(":import {}", module_path.display());
+    let action = state.step(&arena, &import, target, DEFAULT_PALETTE);
+
+    assert!(matches!(action, ReplAction::Loaded { .. }));
+
+    // `:reload` picks up changes to what loaded modules expose.
+    std::fs::write(
+        &module_path,
+        "module [double, triple]\n\ndouble = \\n -> n * 2\n\ntriple = \\n -> n * 3\n",
+    )
+    .unwrap();
+
+    let action = state.step(&arena, ":reload", target, DEFAULT_PALETTE);
+
+    assert!(matches!(action, ReplAction::Message(_)));
+    complete("triple 2", &mut state, "6 : Num *");
+
+    let load_app = format!(":load {}", app_path.display());
+    let action = state.step(&arena, &load_app, target, DEFAULT_PALETTE);
+
+    assert!(matches!(action, ReplAction::LoadProblem { .. }));
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
@@ -158,7 +360,7 @@ fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
 
     match action {
         ReplAction::Eval { opt_mono, problems } => {
-            let string = evaluate(opt_mono, problems, target);
+            let string = evaluate(opt_mono, problems, target, state.settings().answer_format());
             let escaped =
                 std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap())
                     .unwrap();
@@ -194,7 +396,7 @@ fn error(input: &str, state: &mut ReplState, expected_step_result: String) {
 
     match action {
         ReplAction::Eval { opt_mono, problems } => {
-            let string = evaluate(opt_mono, problems, target);
+            let string = evaluate(opt_mono, problems, target, state.settings().answer_format());
             let escaped =
                 std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap())
                     .unwrap();
@@ -626,19 +626,19 @@ fn list_of_3_field_records() {
 
 #[test]
 fn identity_lambda() {
-    expect_success("\\x -> x", "<function> : a -> a");
+    expect_success("\\x -> x", "<function : a -> a>");
 }
 
 #[cfg(not(feature = "wasm"))]
 #[test]
 fn sum_lambda() {
-    expect_success("\\x, y -> x + y", "<function> : Num a, Num a -> Num a");
+    expect_success("\\x, y -> x + y", "<function : Num a, Num a -> Num a>");
 }
 
 #[cfg(not(feature = "wasm"))]
 #[test]
 fn stdlib_function() {
-    expect_success("Num.abs", "<function> : Num a -> Num a");
+    expect_success("Num.abs", "<function : Num a -> Num a>");
 }
 
 #[cfg(not(feature = "wasm"))] // TODO: mismatch is due to terminal control codes!
@@ -1054,7 +1054,7 @@ fn large_nullable_wrapped_tag_union() {
 fn issue_2300() {
     expect_success(
         r#"\Email str -> str == """#,
-        r"<function> : [Email Str] -> Bool",
+        r"<function : [Email Str] -> Bool>",
     )
 }
 
@@ -1159,15 +1159,12 @@ fn issue_2343_complete_mono_with_shadowed_vars() {
             r"
                 ── DUPLICATE NAME ──────────────────────────────────────────────────────────────
 
-                The b name is first defined here:
+                The b name is defined twice:
 
                 4│      b = False
-                        ^
-
-                But then it's defined a second time here:
-
+                 │      - first defined here
                 5│      f = \b ->
-                             ^
+                             ^ defined again here
 
                 Since these variables have the same name, it's easy to use the wrong
                 one by accident. Give one of them a new name.
@@ -1289,6 +1286,37 @@ fn dec_in_repl() {
     )
 }
 
+#[test]
+fn negative_dec_in_repl() {
+    expect_success("-0.5dec", "-0.5 : Dec");
+    expect_success("-1.25dec", "-1.25 : Dec");
+    expect_success("{ a: -0.5dec }", "{ a: -0.5 } : { a : Dec }");
+}
+
+#[test]
+fn i128_and_u128_in_repl() {
+    expect_success(
+        "Num.maxI128",
+        "170141183460469231731687303715884105727 : I128",
+    );
+    expect_success(
+        "Num.minI128",
+        "-170141183460469231731687303715884105728 : I128",
+    );
+    expect_success(
+        "Num.maxU128",
+        "340282366920938463463374607431768211455 : U128",
+    );
+    expect_success(
+        "[Num.maxU128, 0]",
+        "[340282366920938463463374607431768211455, 0] : List U128",
+    );
+    expect_success(
+        "{ big: Num.minI128, small: 1u8 }",
+        "{ big: -170141183460469231731687303715884105728, small: 1 } : { big : I128, small : U8 }",
+    );
+}
+
 #[test]
 fn print_i8_issue_2710() {
     expect_success(
@@ -1334,7 +1362,7 @@ fn box_box_type_alias() {
 fn issue_2582_specialize_result_value() {
     expect_success(
         r#"\x, list -> if x > 0 then List.first list else Ok """#,
-        r"<function> : Num *, List Str -> Result Str [ListWasEmpty]",
+        r"<function : Num *, List Str -> Result Str [ListWasEmpty]>",
     )
 }
 
@@ -1349,7 +1377,7 @@ fn issue_2818() {
               x = []
               x"
         ),
-        r"<function> : {} -> List Str",
+        r"<function : {} -> List Str>",
     )
 }
 
@@ -1568,17 +1596,15 @@ fn interpolation_with_nested_interpolation() {
         ),
         indoc!(
             r#"
-                ── SYNTAX PROBLEM ──────────────────────────────────────────────────────────────
+                ── NESTED INTERPOLATION ────────────────────────────────────────────────────────
 
-                This string interpolation is invalid:
+                This string interpolation is inside of another one:
 
                 4│      "foo $(Str.joinWith ["a$(Num.toStr 5)", "b"] "c")"
-                               ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
-
-                String interpolations cannot contain newlines or other interpolations.
+                                               ^^^^^^^^^^^^^^
 
-                You can learn more about string interpolation at
-                <https://www.roc-lang.org/tutorial#string-interpolation>
+                Interpolations can't be nested. Try giving the inner string a name
+                with a = definition, and interpolating that instead.
 
 
                 Enter an expression to evaluate, or a definition (like x = 1) to use later.
@@ -0,0 +1,313 @@
+//! The REPL's commands, like `:help` and `:set width 100`. They start with a `:`, and the REPL
+//! handles them itself instead of evaluating them as Roc code.
+use roc_repl_eval::gen::AnswerFormat;
+
+use crate::colors::{CYAN, END_COL, GREEN};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommandInfo {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub args: &'static str,
+    pub help: &'static str,
+}
+
+pub const COMMANDS: &[CommandInfo] = &[
+    CommandInfo {
+        name: "help",
+        aliases: &[],
+        args: "[command]",
+        help: "shows this text again, or more about one command",
+    },
+    CommandInfo {
+        name: "quit",
+        aliases: &["q", "exit"],
+        args: "",
+        help: "quits",
+    },
+    CommandInfo {
+        name: "load",
+        aliases: &["import"],
+        args: "<path>",
+        help: "imports a module, and everything it exposes",
+    },
+    CommandInfo {
+        name: "reload",
+        aliases: &[],
+        args: "",
+        help: "loads the loaded modules again, to pick up changes to what they expose",
+    },
+    CommandInfo {
+        name: "reset",
+        aliases: &[],
+        args: "",
+        help: "forgets all definitions and imports",
+    },
+    CommandInfo {
+        name: "clear",
+        aliases: &[],
+        args: "",
+        help: "clears the screen",
+    },
+    CommandInfo {
+        name: "set",
+        aliases: &[],
+        args: "[<setting> <value>]",
+        help: "changes a setting, or shows them all",
+    },
+];
+
+/// Each setting's name and what it does.
+pub const SETTINGS: &[(&str, &str)] = &[
+    ("width", "the number of columns output wraps at"),
+    (
+        "depth",
+        "how many levels of nested lists, records, tuples, and tags to show, or off to show them all",
+    ),
+    (
+        "timing",
+        "on to show how long each input took to compile, and to evaluate (which includes generating code), or off. The web REPL doesn't show timings.",
+    ),
+];
+
+/// Narrower than this, reports would be mostly line breaks.
+const MIN_WIDTH: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command<'a> {
+    Help(Option<&'static CommandInfo>),
+    Quit,
+    /// The path of the module to load
+    Load(&'a str),
+    Reload,
+    Reset,
+    Clear,
+    /// `:set` on its own shows the current settings.
+    Set(Option<Setting>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Width(usize),
+    Depth(Option<usize>),
+    Timing(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplSettings {
+    pub width: usize,
+    pub depth: Option<usize>,
+    pub timing: bool,
+}
+
+impl Default for ReplSettings {
+    fn default() -> Self {
+        Self {
+            width: 70,
+            depth: None,
+            timing: false,
+        }
+    }
+}
+
+impl ReplSettings {
+    pub fn apply(&mut self, setting: Setting) {
+        match setting {
+            Setting::Width(width) => self.width = width,
+            Setting::Depth(depth) => self.depth = depth,
+            Setting::Timing(timing) => self.timing = timing,
+        }
+    }
+
+    pub fn answer_format(&self) -> AnswerFormat {
+        AnswerFormat {
+            width: self.width,
+            max_depth: self.depth,
+        }
+    }
+
+    /// Each setting's name and current value, one per line.
+    pub fn describe(&self) -> String {
+        let Self {
+            width,
+            depth,
+            timing,
+        } = self;
+        let depth = match depth {
+            Some(depth) => depth.to_string(),
+            None => "off".to_string(),
+        };
+        let timing = if *timing { "on" } else { "off" };
+
+        format!(
+            "{GREEN}width{END_COL} {width}\n{GREEN}depth{END_COL} {depth}\n{GREEN}timing{END_COL} {timing}"
+        )
+    }
+}
+
+/// Parses a line starting with `:`, or returns a message saying what's wrong with it.
+pub fn parse_command(line: &str) -> Result<Command<'_>, String> {
+    let line = line.trim().trim_start_matches(':');
+    let (name, args) = match line.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (line, ""),
+    };
+    let info = find_command(name).ok_or_else(|| unknown_command(name))?;
+
+    if info.args.is_empty() && !args.is_empty() {
+        return Err(format!("{} doesn't take any arguments.", colored(info)));
+    }
+
+    match info.name {
+        "help" if args.is_empty() => Ok(Command::Help(None)),
+        "help" => {
+            let topic = args.trim_start_matches(':');
+
+            match find_command(topic) {
+                Some(topic) => Ok(Command::Help(Some(topic))),
+                None => Err(unknown_command(topic)),
+            }
+        }
+        "quit" => Ok(Command::Quit),
+        "load" if args.is_empty() => Err(format!(
+            "{} needs the path of a module to load, like {GREEN}:load Foo.roc{END_COL}.",
+            colored(info)
+        )),
+        "load" => Ok(Command::Load(args)),
+        "reload" => Ok(Command::Reload),
+        "reset" => Ok(Command::Reset),
+        "clear" => Ok(Command::Clear),
+        "set" if args.is_empty() => Ok(Command::Set(None)),
+        "set" => parse_setting(args).map(|setting| Command::Set(Some(setting))),
+        other => unreachable!("The :{other} command isn't handled"),
+    }
+}
+
+fn parse_setting(args: &str) -> Result<Setting, String> {
+    let (name, value) = match args.split_once(char::is_whitespace) {
+        Some((name, value)) => (name, value.trim()),
+        None => (args, ""),
+    };
+
+    match name.to_lowercase().as_str() {
+        "width" => match value.parse::<usize>() {
+            Ok(width) if width >= MIN_WIDTH => Ok(Setting::Width(width)),
+            Ok(_) => Err(format!("The width has to be at least {MIN_WIDTH}.")),
+            Err(_) => Err(format!(
+                "{GREEN}:set width{END_COL} needs a number of columns, like {GREEN}:set width 100{END_COL}."
+            )),
+        },
+        "depth" => match value.to_lowercase().as_str() {
+            "off" => Ok(Setting::Depth(None)),
+            value => match value.parse::<usize>() {
+                Ok(depth) if depth > 0 => Ok(Setting::Depth(Some(depth))),
+                _ => Err(format!(
+                    "{GREEN}:set depth{END_COL} needs a number of levels, like {GREEN}:set depth 3{END_COL}, or {GREEN}off{END_COL}."
+                )),
+            },
+        },
+        "timing" => match value.to_lowercase().as_str() {
+            "on" => Ok(Setting::Timing(true)),
+            "off" => Ok(Setting::Timing(false)),
+            _ => Err(format!(
+                "{GREEN}:set timing{END_COL} needs to be {GREEN}on{END_COL} or {GREEN}off{END_COL}."
+            )),
+        },
+        _ => Err(format!(
+            "There's no setting called {GREEN}{name}{END_COL}. The settings are: {}.",
+            SETTINGS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+fn find_command(name: &str) -> Option<&'static CommandInfo> {
+    let name = name.to_lowercase();
+
+    COMMANDS
+        .iter()
+        .find(|info| info.name == name || info.aliases.contains(&name.as_str()))
+}
+
+fn unknown_command(name: &str) -> String {
+    format!("There's no command called {GREEN}:{name}{END_COL}. Enter {GREEN}:help{END_COL} to see the commands.")
+}
+
+fn colored(info: &CommandInfo) -> String {
+    format!("{GREEN}:{}{END_COL}", info.name)
+}
+
+/// One line for each command, in the same style as the tips.
+pub fn summary() -> String {
+    let mut buf = String::new();
+
+    for info in COMMANDS {
+        buf.push_str(&format!("{CYAN}  - {END_COL}{GREEN}:{}", info.name));
+
+        if !info.args.is_empty() {
+            buf.push(' ');
+            buf.push_str(info.args);
+        }
+
+        buf.push_str(END_COL);
+
+        for (index, alias) in info.aliases.iter().enumerate() {
+            let separator = if index == 0 { " (or " } else { ", " };
+
+            buf.push_str(&format!("{separator}{GREEN}:{alias}{END_COL}"));
+        }
+
+        if !info.aliases.is_empty() {
+            buf.push(')');
+        }
+
+        buf.push(' ');
+        buf.push_str(info.help);
+        buf.push('\n');
+    }
+
+    buf
+}
+
+/// More about one command, for `:help <command>`.
+pub fn details(info: &CommandInfo, settings: &ReplSettings) -> String {
+    let mut buf = format!("{GREEN}:{}", info.name);
+
+    if !info.args.is_empty() {
+        buf.push(' ');
+        buf.push_str(info.args);
+    }
+
+    buf.push_str(END_COL);
+    buf.push_str(&format!(" {}", info.help));
+
+    if !info.aliases.is_empty() {
+        let aliases: Vec<String> = info
+            .aliases
+            .iter()
+            .map(|alias| format!("{GREEN}:{alias}{END_COL}"))
+            .collect();
+
+        buf.push_str(&format!(
+            "\n\nIt can also be written as {}.",
+            aliases.join(" or ")
+        ));
+    }
+
+    if info.name == "set" {
+        buf.push_str("\n\nThe settings are:\n");
+
+        for (name, help) in SETTINGS {
+            buf.push_str(&format!(
+                "{CYAN}  - {END_COL}{GREEN}{name}{END_COL} {help}\n"
+            ));
+        }
+
+        buf.push_str(&format!("\nRight now:\n{}", settings.describe()));
+    }
+
+    buf
+}
@@ -1,14 +1,16 @@
 //! UI functionality, shared between CLI and web, for the Read-Evaluate-Print-Loop (REPL).
 // We don't do anything here related to the terminal (doesn't exist on the web) or LLVM (too big for the web).
 pub mod colors;
+pub mod commands;
 pub mod repl_state;
+pub mod session;
 
 use bumpalo::Bump;
 use colors::{CYAN, END_COL, GREEN};
 use const_format::concatcp;
 use repl_state::{parse_src, ParseOutcome};
 use roc_parse::ast::{Expr, ExtractSpaces, ValueDef};
-use roc_repl_eval::gen::{Problems, ReplOutput};
+use roc_repl_eval::gen::{Problems, ReplOutput, FUNCTION_ANSWER};
 use roc_reporting::report::StyleCodes;
 
 // TODO add link to repl tutorial (does not yet exist).
@@ -34,60 +36,120 @@ pub const TIPS: &str = concatcp!(
             "ctrl-j",
             END_COL,
             " makes a newline\n",
-            CYAN,
-            "  - ",
-            END_COL,
-            GREEN,
-            ":q",
-            END_COL,
-            " quits\n",
-            CYAN,
-            "  - ",
-            END_COL,
-            GREEN,
-            ":help",
-            END_COL,
-            " shows this text again\n",
         )
     }
 );
 
+/// The tips, followed by the commands.
+pub fn help_text() -> String {
+    format!("{TIPS}{}", commands::summary())
+}
+
 // For when nothing is entered in the repl
 // TODO add link to repl tutorial(does not yet exist).
 pub const SHORT_INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n\n";
 pub const PROMPT: &str = concatcp!(CYAN, "»", END_COL, " ");
 pub const CONT_PROMPT: &str = concatcp!(CYAN, "…", END_COL, " ");
 
+/// Whether the input so far needs more lines before it can be evaluated. While it does, the REPL
+/// keeps reading lines with a continuation prompt. Entering a blank line always ends the input,
+/// so there's a way out even when it's wrong about that.
 pub fn is_incomplete(input: &str) -> bool {
+    if input.ends_with('\n') {
+        return false;
+    }
+
     let arena = Bump::new();
 
     match parse_src(&arena, input) {
-        ParseOutcome::Incomplete => !input.ends_with('\n'),
+        ParseOutcome::Incomplete => true,
+        ParseOutcome::SyntaxErr => continues_on_next_line(input) || ends_in_indented_block(input),
         ParseOutcome::DefsAndExpr(defs, None) => {
             // Standalone annotations are default incomplete, because we can't know
             // whether they're about to annotate a body on the next line
             // (or if not, meaning they stay standalone) until you press Enter again!
-            //
-            // So it's Incomplete until you've pressed Enter again (causing the input to end in "\n")
-            if matches!(defs.last(), Some(Err(ValueDef::Annotation(_, _)))) {
-                !input.ends_with('\n')
-            } else {
-                false
-            }
+            matches!(defs.last(), Some(Err(ValueDef::Annotation(_, _))))
+                || ends_in_indented_block(input)
         }
         ParseOutcome::DefsAndExpr(_, Some(expr)) => {
-            if matches!(expr.extract_spaces().item, Expr::When(..)) {
-                // There might be lots of `when` branches, so don't assume the user is done entering
-                // them until they enter a blank line!
-                !input.ends_with('\n')
+            // There might be lots of `when` branches, so don't assume the user is done entering
+            // them until they enter a blank line!
+            matches!(expr.extract_spaces().item, Expr::When(..)) || ends_in_indented_block(input)
+        }
+        ParseOutcome::Empty | ParseOutcome::Command(_) | ParseOutcome::CommandErr(_) => false,
+    }
+}
+
+/// Whether the input has brackets or a multiline string which haven't been closed yet, or ends
+/// with an operator (or a comma, or a `\` starting a function) which needs something after it.
+fn continues_on_next_line(input: &str) -> bool {
+    let mut open_brackets = 0_usize;
+    let mut in_multiline_str = false;
+    let mut last_line_code = String::new();
+
+    for line in input.lines() {
+        let mut code = String::new();
+        let mut rest = line;
+
+        while let Some(ch) = rest.chars().next() {
+            if in_multiline_str {
+                match rest.find("\"\"\"") {
+                    Some(end) => {
+                        rest = &rest[end + 3..];
+                        in_multiline_str = false;
+                    }
+                    None => rest = "",
+                }
+            } else if let Some(after) = rest.strip_prefix("\"\"\"") {
+                code.push(ch);
+                rest = after;
+                in_multiline_str = true;
             } else {
-                false
+                match ch {
+                    '#' => break,
+                    '"' | '\'' => {
+                        // Skip to the closing quote, so brackets in strings don't count.
+                        let mut escaped = false;
+                        let end = rest[1..].find(|c: char| {
+                            let is_end = c == ch && !escaped;
+                            escaped = c == '\\' && !escaped;
+                            is_end
+                        });
+
+                        code.push(ch);
+                        rest = end.map_or("", |end| &rest[end + 2..]);
+
+                        continue;
+                    }
+                    '(' | '[' | '{' => open_brackets += 1,
+                    ')' | ']' | '}' => open_brackets = open_brackets.saturating_sub(1),
+                    _ => {}
+                }
+
+                code.push(ch);
+                rest = &rest[ch.len_utf8()..];
             }
         }
-        ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit | ParseOutcome::SyntaxErr => {
-            false
+
+        if !code.trim().is_empty() {
+            last_line_code = code;
         }
     }
+
+    open_brackets > 0
+        || in_multiline_str
+        || last_line_code.trim_end().ends_with([
+            '+', '-', '*', '/', '%', '^', '=', '<', '>', '&', '|', ':', ',', '\\',
+        ])
+}
+
+/// Whether the input is more than one line, and the last one is indented. Then it could be in the
+/// middle of a block (like a function body), so the user decides when it's done with a blank line.
+fn ends_in_indented_block(input: &str) -> bool {
+    match input.rsplit_once('\n') {
+        Some((_, last_line)) => last_line.starts_with([' ', '\t']) && !last_line.trim().is_empty(),
+        None => false,
+    }
 }
 
 pub fn format_output(
@@ -116,8 +178,17 @@ pub fn format_output(
         if !expr.is_empty() && problems.errors.is_empty() {
             const EXPR_TYPE_SEPARATOR: &str = " : "; // e.g. in "5 : Num *"
 
-            // Print the expr and its type
-            {
+            if expr == FUNCTION_ANSWER {
+                // There's nothing to show about a function except its type, so show that in its
+                // place, like `<function : Str -> U64>`.
+                buf.push_str("\n<function");
+                buf.push_str(style_codes.green);
+                buf.push_str(EXPR_TYPE_SEPARATOR);
+                buf.push_str(style_codes.reset);
+                buf.push_str(&expr_type);
+                buf.push('>');
+            } else {
+                // Print the expr and its type
                 buf.push('\n');
                 buf.push_str(&expr);
                 buf.push_str(style_codes.green); // Color for the type separator
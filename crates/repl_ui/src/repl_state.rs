@@ -1,11 +1,14 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 use bumpalo::Bump;
 use roc_collections::MutSet;
 use roc_load::MonomorphizedModule;
-use roc_parse::ast::{Defs, Expr, Pattern, StrLiteral, TypeDef, TypeHeader, ValueDef};
+use roc_parse::ast::{
+    Defs, Expr, Header, Pattern, SpacesBefore, StrLiteral, TypeDef, TypeHeader, ValueDef,
+};
 use roc_parse::expr::parse_repl_defs_and_optional_expr;
+use roc_parse::header::parse_header;
 use roc_parse::parser::EWhen;
 use roc_parse::parser::{EClosure, EExpr, EPattern};
 use roc_parse::state::State;
@@ -14,15 +17,30 @@ use roc_repl_eval::gen::{compile_to_mono, Problems};
 use roc_reporting::report::Palette;
 use roc_target::Target;
 
+use crate::commands::{self, parse_command, Command, ReplSettings};
+
 #[derive(Debug, Clone, PartialEq)]
 enum PastDef {
-    Def { ident: String, src: String },
+    Def {
+        ident: String,
+        src: String,
+    },
+    /// A standalone type annotation, for the next definition of the same name.
+    Annotation {
+        ident: String,
+        src: String,
+    },
     Import(String),
 }
 
 pub struct ReplState {
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
+    /// The directory imports are relative to. `:load` changes this to the loaded file's directory.
+    src_dir: PathBuf,
+    /// The files loaded with `:load`, in the order they were first loaded, for `:reload`.
+    loaded_files: Vec<PathBuf>,
+    settings: ReplSettings,
 }
 
 impl Default for ReplState {
@@ -44,6 +62,20 @@ pub enum ReplAction<'a> {
         filename: PathBuf,
         error: io::ErrorKind,
     },
+    /// A module was imported with `:load`, and typechecked.
+    Loaded {
+        filename: PathBuf,
+        exposed: Vec<String>,
+        problems: Problems,
+    },
+    LoadProblem {
+        filename: PathBuf,
+        message: String,
+    },
+    /// `:clear`, for the REPL to clear the screen if it can.
+    Clear,
+    /// Something to show in response to a command, like an error in its arguments.
+    Message(String),
     Nothing,
 }
 
@@ -52,9 +84,16 @@ impl ReplState {
         Self {
             past_defs: Default::default(),
             past_def_idents: Default::default(),
+            src_dir: PathBuf::from("."),
+            loaded_files: Vec::new(),
+            settings: ReplSettings::default(),
         }
     }
 
+    pub fn settings(&self) -> &ReplSettings {
+        &self.settings
+    }
+
     pub fn step<'a>(
         &mut self,
         arena: &'a Bump,
@@ -64,8 +103,11 @@ impl ReplState {
     ) -> ReplAction<'a> {
         let mut pending_past_def = None;
         let src: &str = match parse_src(arena, line) {
-            ParseOutcome::Empty | ParseOutcome::Help => return ReplAction::Help,
-            ParseOutcome::Exit => return ReplAction::Exit,
+            ParseOutcome::Empty => return ReplAction::Help,
+            ParseOutcome::Command(command) => {
+                return self.run_command(arena, command, target, palette)
+            }
+            ParseOutcome::CommandErr(message) => return ReplAction::Message(message),
             ParseOutcome::Incomplete | ParseOutcome::SyntaxErr => {
                 pending_past_def = None;
 
@@ -136,7 +178,7 @@ impl ReplState {
                                     _,
                                 ) => {
                                     // Record the standalone type annotation for future use.
-                                    self.add_past_def(
+                                    self.add_past_annotation(
                                         ident.trim_end().to_string(),
                                         line[vd.byte_range()].to_string(),
                                     );
@@ -212,6 +254,8 @@ impl ReplState {
 
                                         filename.set_extension("roc");
 
+                                        let filename = self.src_dir.join(filename);
+
                                         // Check we can read the file before we add it to past defs.
                                         // If we didn't do this, the bad import would remain in past_defs
                                         // and we'd report it on every subsequent evaluation.
@@ -230,8 +274,10 @@ impl ReplState {
                                     }
                                 },
                                 ValueDef::IngestedFileImport(file) => {
-                                    if let StrLiteral::PlainLine(path) = file.path.value {
-                                        let filename = PathBuf::from(path);
+                                    if let StrLiteral::PlainLine(path) | StrLiteral::Raw(path) =
+                                        file.path.value
+                                    {
+                                        let filename = self.src_dir.join(path);
                                         if let Err(err) = fs::metadata(&filename) {
                                             return ReplAction::FileProblem {
                                                 filename,
@@ -259,31 +305,254 @@ impl ReplState {
             }
         };
 
-        let (opt_mono, problems) = compile_to_mono(
-            arena,
-            self.past_defs.iter().map(|past_def| match past_def {
-                PastDef::Def { ident: _, src } => src.as_str(),
+        // A new definition of a name replaces the old one, so leave the old one out.
+        let redefined = pending_past_def.as_ref().map(|(ident, _)| ident.as_str());
+        let (opt_mono, problems) = self.compile(arena, src, redefined, target, palette);
+
+        if let Some((ident, src)) = pending_past_def {
+            self.add_past_def(ident, src);
+        }
+
+        ReplAction::Eval { opt_mono, problems }
+    }
+
+    fn compile<'a>(
+        &self,
+        arena: &'a Bump,
+        src: &str,
+        redefined: Option<&str>,
+        target: Target,
+        palette: Palette,
+    ) -> (Option<MonomorphizedModule<'a>>, Problems) {
+        let past_defs = self
+            .past_defs
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !redefined.is_some_and(|ident| self.is_replaced_by(*index, ident)))
+            .map(|(_, past_def)| match past_def {
+                PastDef::Def { ident: _, src } | PastDef::Annotation { ident: _, src } => {
+                    src.as_str()
+                }
                 PastDef::Import(src) => src.as_str(),
-            }),
+            });
+
+        compile_to_mono(
+            arena,
+            past_defs,
             src,
+            &self.src_dir,
             target,
             palette,
-        );
+            self.settings.width,
+        )
+    }
 
-        if let Some((ident, src)) = pending_past_def {
-            self.add_past_def(ident, src);
+    fn run_command<'a>(
+        &mut self,
+        arena: &'a Bump,
+        command: Command<'_>,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        match command {
+            Command::Help(None) => ReplAction::Help,
+            Command::Help(Some(info)) => {
+                ReplAction::Message(commands::details(info, &self.settings))
+            }
+            Command::Quit => ReplAction::Exit,
+            Command::Load(path) => self.load(arena, PathBuf::from(path), target, palette),
+            Command::Reload => self.reload(arena, target, palette),
+            Command::Reset => {
+                *self = Self {
+                    settings: self.settings,
+                    ..Self::new()
+                };
+
+                ReplAction::Message("Forgot all definitions and imports.".to_string())
+            }
+            Command::Clear => ReplAction::Clear,
+            Command::Set(None) => ReplAction::Message(self.settings.describe()),
+            Command::Set(Some(setting)) => {
+                self.settings.apply(setting);
+
+                ReplAction::Message(self.settings.describe())
+            }
         }
+    }
 
-        ReplAction::Eval { opt_mono, problems }
+    /// Handles `:load path/to/Module.roc` (or `:import path/to/Module.roc`) by importing the module,
+    /// exposing everything it exposes.
+    ///
+    /// The module gets typechecked right away, so problems in it are reported at once. Since every
+    /// evaluation recompiles the module, later edits to it are picked up automatically; running
+    /// `:load` again (or `:reload`) also picks up changes to what it exposes.
+    fn load<'a>(
+        &mut self,
+        arena: &'a Bump,
+        filename: PathBuf,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        let src = match fs::read_to_string(&filename) {
+            Ok(src) => src,
+            Err(err) => {
+                return ReplAction::FileProblem {
+                    filename,
+                    error: err.kind(),
+                }
+            }
+        };
+
+        let module_name = match filename.file_stem().and_then(|stem| stem.to_str()) {
+            Some(stem) => stem.to_string(),
+            None => {
+                return ReplAction::FileProblem {
+                    filename,
+                    error: io::ErrorKind::InvalidInput,
+                }
+            }
+        };
+
+        let header_arena = Bump::new();
+
+        let exposed: Vec<String> = match parse_header(&header_arena, State::new(src.as_bytes())) {
+            Ok((
+                SpacesBefore {
+                    item: Header::Module(header),
+                    ..
+                },
+                _,
+            )) => header
+                .exposes
+                .items
+                .iter()
+                .map(|name| name.value.item().as_str().to_string())
+                .collect(),
+            Ok(_) => {
+                return ReplAction::LoadProblem {
+                    filename,
+                    message: "Only modules (files which begin with `module`) can be loaded into the REPL. To use the functions in an app or a platform, move them into a module, and import that module from the app.".to_string(),
+                };
+            }
+            // Import it anyway, so the syntax error gets reported with all the usual context.
+            Err(_) => Vec::new(),
+        };
+
+        let src_dir = match filename.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let has_imports = self
+            .past_defs
+            .iter()
+            .any(|past_def| matches!(past_def, PastDef::Import(_)));
+
+        if has_imports && !same_dir(&src_dir, &self.src_dir) {
+            return ReplAction::LoadProblem {
+                filename,
+                message: format!(
+                    "The REPL can only import modules from one directory at a time, and the modules imported so far are in {}.",
+                    self.src_dir.display()
+                ),
+            };
+        }
+
+        self.src_dir = src_dir;
+
+        // Loading the same module again replaces its old import, in case what it exposes changed.
+        let import = format!("import {module_name}");
+        let import_with_exposing = format!("{import} exposing ");
+
+        self.past_defs.retain(|past_def| match past_def {
+            PastDef::Import(src) => *src != import && !src.starts_with(&import_with_exposing),
+            PastDef::Def { .. } | PastDef::Annotation { .. } => true,
+        });
+
+        if exposed.is_empty() {
+            self.past_defs.push(PastDef::Import(import));
+        } else {
+            self.past_defs.push(PastDef::Import(format!(
+                "{import_with_exposing}[{}]",
+                exposed.join(", ")
+            )));
+        }
+
+        if !self.loaded_files.contains(&filename) {
+            self.loaded_files.push(filename.clone());
+        }
+
+        let (_, problems) = self.compile(arena, "{}", None, target, palette);
+
+        ReplAction::Loaded {
+            filename,
+            exposed,
+            problems,
+        }
+    }
+
+    /// Handles `:reload` by loading every loaded file again. Stops at the first one which can't be
+    /// loaded, or has errors, so those get reported.
+    fn reload<'a>(&mut self, arena: &'a Bump, target: Target, palette: Palette) -> ReplAction<'a> {
+        if self.loaded_files.is_empty() {
+            return ReplAction::Message(
+                "Nothing has been loaded yet, so there's nothing to reload.".to_string(),
+            );
+        }
+
+        for filename in self.loaded_files.clone() {
+            match self.load(arena, filename, target, palette) {
+                ReplAction::Loaded { problems, .. } if problems.errors.is_empty() => {}
+                action => return action,
+            }
+        }
+
+        let filenames: Vec<String> = self
+            .loaded_files
+            .iter()
+            .map(|filename| filename.display().to_string())
+            .collect();
+
+        ReplAction::Message(format!("Reloaded {}.", filenames.join(", ")))
     }
 
     fn add_past_def(&mut self, ident: String, src: String) {
-        let existing_idents = &mut self.past_def_idents;
+        if self.past_def_idents.contains(&ident) {
+            let mut replaced = (0..self.past_defs.len())
+                .map(|index| self.is_replaced_by(index, &ident))
+                .collect::<Vec<_>>()
+                .into_iter();
 
-        existing_idents.insert(ident.clone());
+            self.past_defs.retain(|_| !replaced.next().unwrap());
+        }
 
+        self.past_def_idents.insert(ident.clone());
         self.past_defs.push(PastDef::Def { ident, src });
     }
+
+    fn add_past_annotation(&mut self, ident: String, src: String) {
+        self.past_defs.retain(|past_def| {
+            !matches!(past_def, PastDef::Annotation { ident: past_ident, .. } if *past_ident == ident)
+        });
+
+        self.past_def_idents.insert(ident.clone());
+        self.past_defs.push(PastDef::Annotation { ident, src });
+    }
+
+    /// Whether a new definition of `ident` replaces the past def at `index`. It replaces earlier
+    /// definitions and annotations of the same name, except for a standalone annotation entered
+    /// right before it, which is the new definition's annotation.
+    fn is_replaced_by(&self, index: usize, ident: &str) -> bool {
+        match &self.past_defs[index] {
+            PastDef::Def {
+                ident: past_ident, ..
+            } => past_ident == ident,
+            PastDef::Annotation {
+                ident: past_ident, ..
+            } => past_ident == ident && index + 1 < self.past_defs.len(),
+            PastDef::Import(_) => false,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -292,8 +561,9 @@ pub enum ParseOutcome<'a> {
     Incomplete,
     SyntaxErr,
     Empty,
-    Help,
-    Exit,
+    Command(Command<'a>),
+    /// A line starting with `:` which isn't a valid command, and what's wrong with it
+    CommandErr(String),
 }
 
 /// Special case some syntax errors to allow for multi-line inputs
@@ -311,12 +581,15 @@ fn parse_outcome_for_error(e: EExpr<'_>) -> ParseOutcome<'_> {
 pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
     match line.trim().to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
-        ":help" => ParseOutcome::Help,
         // These are all common things beginners try.
         // Let people exit the repl easily!
         // If you really need to evaluate `exit` for some reason,
         // you can do `foo = exit` and then evaluate `foo` instead.
-        ":exit" | ":quit" | ":q" | "exit" | "quit" | "exit()" | "quit()" => ParseOutcome::Exit,
+        "exit" | "quit" | "exit()" | "quit()" => ParseOutcome::Command(Command::Quit),
+        command if command.starts_with(':') => match parse_command(line) {
+            Ok(command) => ParseOutcome::Command(command),
+            Err(message) => ParseOutcome::CommandErr(message),
+        },
         _ => {
             let src_bytes = line.as_bytes();
 
@@ -333,3 +606,10 @@ pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
         }
     }
 }
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
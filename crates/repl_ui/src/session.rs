@@ -0,0 +1,144 @@
+//! Evaluating REPL inputs without a terminal, for embedding the REPL in other tools (like editors
+//! and tests). Each input goes through the same steps as in `roc repl`: parsing, loading into the
+//! REPL's module, typechecking, code generation, running, and printing the answer.
+//!
+//! Code generation and running are up to a [`ReplEvaluator`], so this works with any backend.
+use std::time::{Duration, Instant};
+
+use bumpalo::Bump;
+use roc_load::MonomorphizedModule;
+use roc_repl_eval::gen::{AnswerFormat, ReplOutput};
+use roc_reporting::report::{to_file_problem_report_string, ANSI_STYLE_CODES, DEFAULT_PALETTE};
+use roc_target::Target;
+
+use crate::repl_state::{ReplAction, ReplState};
+use crate::{format_output, help_text};
+
+/// Generates code for an input and runs it, e.g. with the LLVM JIT in `roc repl`.
+pub trait ReplEvaluator {
+    /// Returns `None` if the input couldn't be run.
+    fn eval(
+        &mut self,
+        mono: MonomorphizedModule<'_>,
+        target: Target,
+        answer_format: AnswerFormat,
+    ) -> Option<ReplOutput>;
+}
+
+/// What to do after an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplResponse {
+    /// Text to print, with ANSI colors. It's empty for inputs that don't print anything, like
+    /// type annotations.
+    Output(String),
+    /// `:clear` was entered.
+    Clear,
+    /// `:quit` was entered.
+    Exit,
+}
+
+/// A REPL session, which remembers definitions, imports, and settings between inputs.
+pub struct ReplSession<E: ReplEvaluator> {
+    state: ReplState,
+    target: Target,
+    arena: Bump,
+    evaluator: E,
+}
+
+impl<E: ReplEvaluator> ReplSession<E> {
+    /// A session which compiles for `target`, and runs inputs with `evaluator`.
+    pub fn new(evaluator: E, target: Target) -> Self {
+        Self {
+            state: ReplState::new(),
+            target,
+            arena: Bump::new(),
+            evaluator,
+        }
+    }
+
+    pub fn state(&self) -> &ReplState {
+        &self.state
+    }
+
+    /// Evaluates one input, which should be complete (see [`crate::is_incomplete`]).
+    pub fn eval(&mut self, input: &str) -> ReplResponse {
+        self.arena.reset();
+
+        let target = self.target;
+        let answer_format = self.state.settings().answer_format();
+        let timing = self.state.settings().timing;
+        let compile_start = Instant::now();
+        let mut output = String::new();
+
+        match self.state.step(&self.arena, input, target, DEFAULT_PALETTE) {
+            ReplAction::Eval { opt_mono, problems } => {
+                let compile_time = compile_start.elapsed();
+                let eval_start = Instant::now();
+                let opt_output =
+                    opt_mono.and_then(|mono| self.evaluator.eval(mono, target, answer_format));
+                let answer = format_output(ANSI_STYLE_CODES, opt_output, problems);
+
+                // If there was no output, don't print a blank line!
+                // (This happens for something like a type annotation.)
+                if !answer.is_empty() {
+                    output.push_str(&answer);
+                    output.push('\n');
+                }
+
+                if timing {
+                    output.push_str(&format_timing(compile_time, eval_start.elapsed()));
+                    output.push('\n');
+                }
+            }
+            ReplAction::Exit => return ReplResponse::Exit,
+            ReplAction::Clear => return ReplResponse::Clear,
+            ReplAction::FileProblem { filename, error } => {
+                output.push_str(&to_file_problem_report_string(filename, error, true));
+                output.push('\n');
+            }
+            ReplAction::Loaded {
+                filename,
+                exposed,
+                problems,
+            } => {
+                let loaded_without_errors = problems.errors.is_empty();
+                let problems = format_output(ANSI_STYLE_CODES, None, problems);
+
+                if !problems.is_empty() {
+                    output.push_str(&problems);
+                    output.push('\n');
+                }
+
+                if loaded_without_errors {
+                    output.push_str(&format!(
+                        "\nLoaded {}, which exposes: {}\n\n",
+                        filename.display(),
+                        exposed.join(", ")
+                    ));
+                }
+            }
+            ReplAction::LoadProblem { filename, message } => {
+                output.push_str(&format!(
+                    "\nI couldn't load {}. {message}\n\n",
+                    filename.display()
+                ));
+            }
+            ReplAction::Help => {
+                output.push_str(&help_text());
+                output.push('\n');
+            }
+            ReplAction::Message(message) => {
+                output.push_str(&format!("\n{message}\n\n"));
+            }
+            ReplAction::Nothing => {}
+        }
+
+        ReplResponse::Output(output)
+    }
+}
+
+/// How long an input took to compile (typecheck and specialize), and to evaluate. Evaluating
+/// includes generating code.
+fn format_timing(compile_time: Duration, eval_time: Duration) -> String {
+    format!("\n(compiled in {compile_time:.2?}, evaluated in {eval_time:.2?})\n")
+}
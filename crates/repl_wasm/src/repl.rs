@@ -8,13 +8,12 @@ use roc_load::MonomorphizedModule;
 use roc_parse::ast::Expr;
 use roc_repl_eval::{
     eval::jit_to_ast,
-    gen::{format_answer, ReplOutput},
+    gen::{format_answer, AnswerFormat, ReplOutput},
     ReplApp, ReplAppMemory,
 };
 use roc_repl_ui::{
-    format_output,
+    format_output, help_text,
     repl_state::{ReplAction, ReplState},
-    TIPS,
 };
 use roc_target::Target;
 use roc_types::pretty_print::{name_and_print_var, DebugPrint};
@@ -143,7 +142,7 @@ impl<'a> ReplApp<'a> for WasmReplApp<'a> {
         let result_bytes = &copied_bytes[app_result_addr..];
         let result: Return = unsafe {
             let ptr: *const Return = std::mem::transmute(result_bytes.as_ptr());
-            ptr.read()
+            ptr.read_unaligned()
         };
 
         let mem = self.arena.alloc(WasmMemory { copied_bytes });
@@ -192,24 +191,35 @@ pub async fn entrypoint_from_js(src: String) -> String {
     let target = Target::Wasm32;
 
     // Advance the REPL state machine
-    let action = REPL_STATE.with(|repl_state_cell| {
+    let (action, answer_format) = REPL_STATE.with(|repl_state_cell| {
         let mut repl_state = repl_state_cell.borrow_mut();
-        repl_state.step(arena, &src, target, DEFAULT_PALETTE_HTML)
+        let answer_format = repl_state.settings().answer_format();
+
+        (
+            repl_state.step(arena, &src, target, DEFAULT_PALETTE_HTML),
+            answer_format,
+        )
     });
 
     // Perform the action the state machine asked for, and return the appropriate output string
     match action {
-        ReplAction::Help => TIPS.to_string(),
+        ReplAction::Help => help_text(),
         ReplAction::Exit => {
             "To exit the web version of the REPL, just close the browser tab!".to_string()
         }
-        ReplAction::FileProblem { .. } => {
+        ReplAction::FileProblem { .. }
+        | ReplAction::Loaded { .. }
+        | ReplAction::LoadProblem { .. } => {
             "The web version of the REPL cannot import files... for now!".to_string()
         }
+        ReplAction::Clear => {
+            "The web version of the REPL can't clear its output... for now!".to_string()
+        }
+        ReplAction::Message(message) => message,
         ReplAction::Nothing => String::new(),
         ReplAction::Eval { opt_mono, problems } => {
             let opt_output = match opt_mono {
-                Some(mono) => eval_wasm(arena, target, mono).await,
+                Some(mono) => eval_wasm(arena, target, mono, answer_format).await,
                 None => None,
             };
 
@@ -222,6 +232,7 @@ async fn eval_wasm<'a>(
     arena: &'a Bump,
     target: Target,
     mono: MonomorphizedModule<'a>,
+    answer_format: AnswerFormat,
 ) -> Option<ReplOutput> {
     let MonomorphizedModule {
         module_id,
@@ -318,7 +329,7 @@ async fn eval_wasm<'a>(
     );
 
     // Transform the Expr to a string
-    let expr = format_answer(arena, res_answer).to_string();
+    let expr = format_answer(arena, res_answer, answer_format).to_string();
 
     Some(ReplOutput { expr, expr_type })
 }
@@ -1,29 +1,36 @@
 use std::path::PathBuf;
 
 use roc_collections::MutMap;
+use roc_error_macros::exit_code;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_problem::can::Problem;
-use roc_region::all::LineInfo;
+use roc_problem::Severity;
+use roc_region::all::{LineColumnRegion, LineInfo};
 use roc_solve_problem::TypeError;
 
-use crate::report::ANSI_STYLE_CODES;
+use crate::report::{PlatformHints, ANSI_STYLE_CODES};
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Problems {
     pub fatally_errored: bool,
     pub errors: usize,
+    /// How many of the `errors` were type errors.
+    pub type_errors: usize,
     pub warnings: usize,
 }
 
 impl Problems {
     pub fn exit_code(&self) -> i32 {
-        // 0 means no problems, 1 means errors, 2 means warnings
-        if self.errors > 0 {
-            1
+        // Type errors are often a consequence of earlier problems (e.g. a misspelled name),
+        // so those take precedence.
+        if self.errors > self.type_errors {
+            exit_code::PARSE_OR_CANONICALIZE_ERRORS
+        } else if self.errors > 0 {
+            exit_code::TYPE_ERRORS
         } else if self.warnings > 0 {
-            2
+            exit_code::WARNINGS
         } else {
-            0
+            exit_code::SUCCESS
         }
     }
 
@@ -60,16 +67,52 @@ impl Problems {
     }
 }
 
+/// A problem found while building, for tools which read problems as data rather than text
+/// (e.g. `roc build --message-format json`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: PathBuf,
+    pub title: String,
+    /// Where in the file the problem is, if it's about a particular part of the file.
+    /// Lines and columns start at 0.
+    pub region: Option<LineColumnRegion>,
+    /// The whole report as plain text, the way it's printed when colors are turned off.
+    pub rendered: String,
+}
+
 pub fn report_problems(
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+) -> Problems {
+    report_problems_to(
+        sources,
+        interns,
+        can_problems,
+        type_problems,
+        &PlatformHints::default(),
+        None,
+    )
+}
+
+/// Like [`report_problems`], except that if `on_diagnostic` is given, each problem is passed to it
+/// instead of being printed. Unlike when printing, warnings are passed along even if there are
+/// errors. Type errors involving anything the platform has a hint for show that hint.
+pub fn report_problems_to(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    platform_hints: &PlatformHints,
+    mut on_diagnostic: Option<&mut dyn FnMut(Diagnostic)>,
 ) -> Problems {
     use crate::report::{can_problem, type_problem, Report, RocDocAllocator, DEFAULT_PALETTE};
     use roc_problem::Severity::*;
 
     let palette = DEFAULT_PALETTE;
+    let print = on_diagnostic.is_none();
     let mut total_problems = 0;
 
     for problems in can_problems.values() {
@@ -85,6 +128,7 @@ pub fn report_problems(
     let mut warnings = Vec::with_capacity(total_problems);
     let mut errors = Vec::with_capacity(total_problems);
     let mut fatally_errored = false;
+    let mut type_errors = 0;
 
     for (home, (module_path, src)) in sources.iter() {
         let mut src_lines: Vec<&str> = Vec::new();
@@ -94,26 +138,30 @@ pub fn report_problems(
         let lines = LineInfo::new(&src_lines.join("\n"));
 
         // Report parsing and canonicalization problems
-        let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+        let alloc =
+            RocDocAllocator::new(&src_lines, *home, interns).with_platform_hints(platform_hints);
 
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
+            let region = problem.region();
+
             if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
                 let severity = report.severity;
-                let mut buf = String::new();
-
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                let region = region.map(|region| lines.convert_region(region));
+                let buf = render_report(report, region, &alloc, &palette, &mut on_diagnostic);
 
                 match severity {
                     Warning => {
                         warnings.push(buf);
                     }
                     RuntimeError => {
+                        type_errors += 1;
                         errors.push(buf);
                     }
                     Fatal => {
                         fatally_errored = true;
+                        type_errors += 1;
                         errors.push(buf);
                     }
                 }
@@ -137,11 +185,10 @@ pub fn report_problems(
         ordered.extend(shadowing_errs);
 
         for problem in ordered.into_iter() {
+            let region = problem.region().map(|region| lines.convert_region(region));
             let report = can_problem(&alloc, &lines, module_path.clone(), problem);
             let severity = report.severity;
-            let mut buf = String::new();
-
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            let buf = render_report(report, region, &alloc, &palette, &mut on_diagnostic);
 
             match severity {
                 Warning => {
@@ -163,8 +210,11 @@ pub fn report_problems(
 
     let problems_reported;
 
-    // Only print warnings if there are no errors
-    if errors.is_empty() {
+    if !print {
+        // They were all passed to on_diagnostic instead.
+        problems_reported = 0;
+    } else if errors.is_empty() {
+        // Only print warnings if there are no errors
         problems_reported = warnings.len();
 
         for warning in warnings.iter() {
@@ -191,6 +241,44 @@ pub fn report_problems(
     Problems {
         fatally_errored,
         errors: errors.len(),
+        type_errors,
         warnings: warnings.len(),
     }
 }
+
+/// Renders the report for printing, or if there's an `on_diagnostic`, passes the report to it
+/// and returns an empty string.
+fn render_report<'b>(
+    report: crate::report::Report<'b>,
+    region: Option<LineColumnRegion>,
+    alloc: &'b crate::report::RocDocAllocator<'b>,
+    palette: &'b crate::report::Palette,
+    on_diagnostic: &mut Option<&mut dyn FnMut(Diagnostic)>,
+) -> String {
+    let mut buf = String::new();
+
+    match on_diagnostic {
+        Some(on_diagnostic) => {
+            let severity = report.severity;
+            let path = report.filename.clone();
+            let title = report.title.clone();
+
+            report.render_ci(&mut buf, alloc);
+
+            on_diagnostic(Diagnostic {
+                severity,
+                path,
+                title,
+                region,
+                rendered: buf,
+            });
+
+            String::new()
+        }
+        None => {
+            report.render_color_terminal(&mut buf, alloc, palette);
+
+            buf
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use roc_collections::all::MutSet;
-use roc_module::called_via::Suffix;
+use roc_module::called_via::{BinOp, Suffix};
 use roc_module::ident::{Ident, Lowercase, ModuleName};
-use roc_module::symbol::DERIVABLE_ABILITIES;
+use roc_module::symbol::{Symbol, DERIVABLE_ABILITIES};
+use roc_parse::parser::ParseWarning;
 use roc_problem::can::PrecedenceProblem::BothNonAssociative;
 use roc_problem::can::{
     BadPattern, CycleEntry, ExtensionTypeKind, FloatErrorKind, IntErrorKind, Problem, RuntimeError,
@@ -28,6 +29,9 @@ const UNDECLARED_TYPE_VARIABLE: &str = "UNDECLARED TYPE VARIABLE";
 const WILDCARD_NOT_ALLOWED: &str = "WILDCARD NOT ALLOWED HERE";
 const UNDERSCORE_NOT_ALLOWED: &str = "UNDERSCORE NOT ALLOWED HERE";
 const UNUSED_ARG: &str = "UNUSED ARGUMENT";
+const DEPRECATED: &str = "DEPRECATED";
+const NOT_TAIL_RECURSIVE: &str = "NOT TAIL RECURSIVE";
+const DOCUMENTED_BUT_NOT_EXPOSED: &str = "DOCUMENTED BUT NOT EXPOSED";
 const MISSING_DEFINITION: &str = "MISSING DEFINITION";
 const DUPLICATE_FIELD_NAME: &str = "DUPLICATE FIELD NAME";
 const DUPLICATE_TAG_NAME: &str = "DUPLICATE TAG NAME";
@@ -266,6 +270,90 @@ pub fn can_problem<'b>(
             title = "BACKPASSING DEPRECATED".to_string();
         }
 
+        Problem::DeprecatedUse {
+            symbol,
+            region,
+            message,
+        } => {
+            let explanation = if message.is_empty() {
+                alloc.reflow("Its definition is marked as deprecated, so it may go away in a future version.")
+            } else {
+                alloc.string(message.into())
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.symbol_foreign_qualified(symbol),
+                    alloc.reflow(" is deprecated:"),
+                ]),
+                alloc.region(lines.convert_region(region), severity),
+                explanation,
+            ]);
+
+            title = DEPRECATED.to_string();
+        }
+
+        Problem::NotTailRecursive {
+            symbol,
+            region,
+            non_tail_calls,
+        } => {
+            doc = if non_tail_calls.is_empty() {
+                alloc.stack([
+                    alloc.concat([
+                        alloc.symbol_unqualified(symbol),
+                        alloc.reflow("'s doc comment says it's tail-recursive, but it never calls itself:"),
+                    ]),
+                    alloc.region(lines.convert_region(region), severity),
+                    alloc.concat([
+                        alloc.reflow("Only a function that calls itself can be tail-recursive. If it isn't meant to, remove the "),
+                        alloc.parser_suggestion("Tail-recursive"),
+                        alloc.reflow(" line from its doc comment."),
+                    ]),
+                ])
+            } else {
+                let calls = non_tail_calls
+                    .into_iter()
+                    .map(|call| alloc.region(lines.convert_region(call), severity));
+
+                alloc.stack(
+                    std::iter::once(alloc.concat([
+                        alloc.symbol_unqualified(symbol),
+                        alloc.reflow("'s doc comment says it's tail-recursive, but it calls itself where there's still work to do after the call returns:"),
+                    ]))
+                    .chain(calls)
+                    .chain([
+                        alloc.concat([
+                            alloc.reflow("Calls whose result is returned as-is get compiled into loops, but each of these uses more stack space, so "),
+                            alloc.symbol_unqualified(symbol),
+                            alloc.reflow(" can overflow the stack when it recurses deeply."),
+                        ]),
+                        alloc.tip().append(alloc.reflow(
+                            "Pass the work that's left along in an extra argument, like an accumulator, so the call's result can be returned directly.",
+                        )),
+                    ]),
+                )
+            };
+
+            title = NOT_TAIL_RECURSIVE.to_string();
+        }
+
+        Problem::ParseWarning(ParseWarning::OldPackageHeader(region)) => {
+            doc = alloc.stack([
+                alloc.reflow("This package header has a name, which is the old style:"),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.concat([
+                    alloc.reflow("Package headers don't have names anymore, so they look like "),
+                    alloc.parser_suggestion("package [Foo] {}"),
+                    alloc.reflow(" now. "),
+                    alloc.parser_suggestion("roc format"),
+                    alloc.reflow(" can update this one for you."),
+                ]),
+            ]);
+
+            title = "OLD PACKAGE HEADER".to_string();
+        }
+
         Problem::DefsOnlyUsedInRecursion(1, region) => {
             doc = alloc.stack([
                 alloc.reflow("This definition is only used in recursion with itself:"),
@@ -292,23 +380,89 @@ pub fn can_problem<'b>(
 
             title = "DEFINITIONS ONLY USED IN RECURSION".to_string();
         }
-        Problem::ExposedButNotDefined(symbol) => {
+        Problem::ExposedButNotDefined {
+            symbol,
+            region,
+            top_level_names,
+        } => {
+            let mut suggestions = suggest::sort(
+                symbol.as_str(alloc.interns),
+                top_level_names.iter().map(|name| name.as_ref()).collect(),
+            );
+            suggestions.truncate(4);
+
+            let fix = if suggestions.is_empty() {
+                alloc.concat([
+                    alloc.reflow("You can fix this by adding a definition for "),
+                    alloc.symbol_unqualified(symbol),
+                    alloc.reflow(", or by removing it from "),
+                    alloc.keyword("exposes"),
+                    alloc.reflow("."),
+                ])
+            } else {
+                alloc.stack([
+                    alloc.reflow("These top-level definitions have similar names:"),
+                    alloc
+                        .vcat(
+                            suggestions
+                                .into_iter()
+                                .map(|name| alloc.string(name.to_string())),
+                        )
+                        .indent(4),
+                    alloc.concat([
+                        alloc.reflow("If you meant one of them, put its name in "),
+                        alloc.keyword("exposes"),
+                        alloc.reflow(" instead. Otherwise, add a definition for "),
+                        alloc.symbol_unqualified(symbol),
+                        alloc.reflow(", or remove it from "),
+                        alloc.keyword("exposes"),
+                        alloc.reflow("."),
+                    ]),
+                ])
+            };
+
+            let listed = alloc
+                .symbol_unqualified(symbol)
+                .append(alloc.reflow(" is listed as exposed, but it isn't defined in this module"));
+
+            doc = if region.is_empty() {
+                alloc.stack([listed.append(alloc.reflow(".")), fix])
+            } else {
+                alloc.stack([
+                    listed.append(alloc.reflow(":")),
+                    alloc.region(lines.convert_region(region), severity),
+                    fix,
+                ])
+            };
+
+            title = MISSING_DEFINITION.to_string();
+        }
+        Problem::DocumentedButNotExposed(symbol, region) => {
             doc = alloc.stack([
-                alloc.symbol_unqualified(symbol).append(
-                    alloc.reflow(" is listed as exposed, but it isn't defined in this module."),
-                ),
-                alloc
-                    .reflow("You can fix this by adding a definition for ")
-                    .append(alloc.symbol_unqualified(symbol))
-                    .append(alloc.reflow(", or by removing it from "))
-                    .append(alloc.keyword("exposes"))
-                    .append(alloc.reflow(".")),
+                alloc.concat([
+                    alloc.symbol_unqualified(symbol),
+                    alloc.reflow(" has a doc comment, but this module doesn't expose it:"),
+                ]),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.concat([
+                    alloc.reflow(
+                        "Doc comments describe a module's API, but other modules can't use ",
+                    ),
+                    alloc.symbol_unqualified(symbol),
+                    alloc.reflow(". If it's meant to be public, add it to "),
+                    alloc.keyword("exposes"),
+                    alloc.reflow(". Otherwise, use a regular "),
+                    alloc.parser_suggestion("#"),
+                    alloc.reflow(" comment instead of "),
+                    alloc.parser_suggestion("##"),
+                    alloc.reflow("."),
+                ]),
             ]);
 
-            title = MISSING_DEFINITION.to_string();
+            title = DOCUMENTED_BUT_NOT_EXPOSED.to_string();
         }
         Problem::UnusedArgument(closure_symbol, is_anonymous, argument_symbol, region) => {
-            let line = "\". Adding an underscore at the start of a variable name is a way of saying that the variable is not used.";
+            let line = ". Adding an underscore at the start of a variable name is a way of saying that the variable is not used.";
 
             doc = alloc.stack([
                 alloc.concat([
@@ -333,8 +487,9 @@ pub fn can_problem<'b>(
                     } else {
                         alloc.symbol_unqualified(closure_symbol)
                     },
-                    alloc.reflow(", prefix it with an underscore, like this: \"_"),
-                    alloc.symbol_unqualified(argument_symbol),
+                    alloc.reflow(", prefix it with an underscore, like this: "),
+                    text!(alloc, "_{}", argument_symbol.as_str(alloc.interns))
+                        .annotate(Annotation::Symbol),
                     alloc.reflow(line),
                 ]),
             ]);
@@ -385,6 +540,7 @@ pub fn can_problem<'b>(
                     ])
                 },
                 alloc.region(lines.convert_region(region), severity),
+                same_precedence_note(alloc, left_bin_op.value, right_bin_op.value),
             ]);
 
             title = SYNTAX_PROBLEM.to_string();
@@ -781,7 +937,7 @@ pub fn can_problem<'b>(
             doc = alloc.stack([
                 alloc.reflow("This string interpolation is invalid:"),
                 alloc.region(lines.convert_region(region), severity),
-                alloc.reflow(r"String interpolations cannot contain newlines or other interpolations."),
+                alloc.reflow(r"String interpolations cannot contain newlines or comments."),
                 alloc.reflow(r"You can learn more about string interpolation at <https://www.roc-lang.org/tutorial#string-interpolation>"),
             ]);
 
@@ -1346,6 +1502,66 @@ pub fn can_problem<'b>(
             doc = report.doc;
             title = report.title;
         }
+        Problem::NonCamelCaseName {
+            symbol,
+            region,
+            suggestion,
+        } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("The name "),
+                    alloc.symbol_unqualified(symbol),
+                    alloc.reflow(" isn't camelCase:"),
+                ]),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.concat([
+                    alloc.reflow("Roc values are named in camelCase, so consider calling it "),
+                    alloc.string(suggestion).annotate(Annotation::Symbol),
+                    alloc.reflow(" instead."),
+                ]),
+            ]);
+            title = "NAMING CONVENTION".to_string();
+        }
+        Problem::RedundantIf { region, negated } => {
+            let (returns, suggestion) = if negated {
+                (
+                    " returns the opposite of its condition:",
+                    alloc.concat([
+                        alloc.reflow("It can be replaced with "),
+                        alloc.keyword("Bool.not"),
+                        alloc.reflow(" applied to its condition."),
+                    ]),
+                )
+            } else {
+                (
+                    " returns the same thing as its condition:",
+                    alloc.reflow("It can be replaced with just its condition."),
+                )
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("if"),
+                    alloc.reflow(returns),
+                ]),
+                alloc.region(lines.convert_region(region), severity),
+                suggestion,
+            ]);
+            title = "REDUNDANT IF".to_string();
+        }
+        Problem::SingleBranchWhen { region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("when"),
+                    alloc.reflow(" has only one branch, and its pattern matches everything:"),
+                ]),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.reflow("The branch always runs, so it can be replaced with a definition, or with the branch's body."),
+            ]);
+            title = "UNNECESSARY WHEN".to_string();
+        }
     };
 
     Report {
@@ -1365,6 +1581,51 @@ fn list_builtin_abilities<'a>(alloc: &'a RocDocAllocator<'a>) -> RocDocBuilder<'
     )
 }
 
+/// Explains why two non-associative operators with the same precedence need grouping.
+fn same_precedence_note<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    left: BinOp,
+    right: BinOp,
+) -> RocDocBuilder<'b> {
+    let peers: Vec<BinOp> = BinOp::iter()
+        .filter(|op| op.precedence() == left.precedence())
+        .collect();
+    let last = peers.len() - 1;
+    let mut parts = vec![alloc.note("")];
+
+    for (index, op) in peers.iter().enumerate() {
+        if index > 0 && last > 1 {
+            parts.push(alloc.reflow(", "));
+        } else if index > 0 {
+            parts.push(alloc.reflow(" "));
+        }
+
+        if index > 0 && index == last {
+            parts.push(alloc.reflow("and "));
+        }
+
+        parts.push(alloc.binop(*op));
+    }
+
+    parts.push(alloc.reflow(if last > 1 {
+        " all have the same precedence, "
+    } else {
+        " have the same precedence, "
+    }));
+    parts.push(alloc.reflow(concat!(
+        "and they don't group to the left or the right, so I can't tell which ",
+        "should happen first. Add parentheses to say, like ",
+    )));
+    parts.push(
+        alloc
+            .string(format!("(a {left} b) {right} c"))
+            .annotate(Annotation::ParserSuggestion),
+    );
+    parts.push(alloc.reflow("."));
+
+    alloc.concat(parts)
+}
+
 fn to_invalid_optional_value_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
@@ -1836,19 +2097,29 @@ fn report_shadowing<'b>(
     kind: ShadowKind,
     severity: Severity,
 ) -> (&'static str, RocDocBuilder<'b>) {
-    let (what, what_plural, is_builtin) = match kind {
-        ShadowKind::Variable => ("variable", "variables", false),
-        ShadowKind::Alias(sym) => ("alias", "aliases", sym.is_builtin()),
-        ShadowKind::Opaque(sym) => ("opaque type", "opaque types", sym.is_builtin()),
-        ShadowKind::Ability(sym) => ("ability", "abilities", sym.is_builtin()),
+    let (what, what_plural, original_symbol) = match kind {
+        ShadowKind::Builtin(builtin) => {
+            let doc =
+                report_shadowed_builtin(alloc, lines, original_region, shadow, builtin, severity);
+
+            return (DUPLICATE_NAME, doc);
+        }
+        ShadowKind::Variable => ("variable", "variables", None),
+        ShadowKind::Alias(sym) => ("alias", "aliases", Some(sym)),
+        ShadowKind::Opaque(sym) => ("opaque type", "opaque types", Some(sym)),
+        ShadowKind::Ability(sym) => ("ability", "abilities", Some(sym)),
     };
 
-    let doc = if is_builtin {
+    let shadowed_builtin = original_symbol.filter(|sym| sym.is_builtin());
+
+    let doc = if let Some(builtin) = shadowed_builtin {
         alloc.stack([
             alloc.concat([
                 alloc.reflow("This "),
                 alloc.reflow(what),
-                alloc.reflow(" has the same name as a builtin:"),
+                alloc.reflow(" has the same name as a builtin from the "),
+                alloc.module(builtin.module_id()),
+                alloc.reflow(" module:"),
             ]),
             alloc.region(lines.convert_region(shadow.region), severity),
             alloc.concat([
@@ -1860,14 +2131,37 @@ fn report_shadowing<'b>(
             ]),
         ])
     } else {
+        let original_region = lines.convert_region(original_region);
+        let shadow_region = lines.convert_region(shadow.region);
+        let name = alloc.ident(shadow.value);
+
+        let regions = match alloc.region_with_secondary(
+            shadow_region,
+            "defined again here",
+            original_region,
+            "first defined here",
+            severity,
+        ) {
+            Some(both) => alloc.stack([
+                alloc
+                    .text("The ")
+                    .append(name)
+                    .append(alloc.reflow(" name is defined twice:")),
+                both,
+            ]),
+            None => alloc.stack([
+                alloc
+                    .text("The ")
+                    .append(name)
+                    .append(alloc.reflow(" name is first defined here:")),
+                alloc.region(original_region, severity),
+                alloc.reflow("But then it's defined a second time here:"),
+                alloc.region(shadow_region, severity),
+            ]),
+        };
+
         alloc.stack([
-            alloc
-                .text("The ")
-                .append(alloc.ident(shadow.value))
-                .append(alloc.reflow(" name is first defined here:")),
-            alloc.region(lines.convert_region(original_region), severity),
-            alloc.reflow("But then it's defined a second time here:"),
-            alloc.region(lines.convert_region(shadow.region), severity),
+            regions,
             alloc.concat([
                 alloc.reflow("Since these "),
                 alloc.reflow(what_plural),
@@ -1879,6 +2173,37 @@ fn report_shadowing<'b>(
     (DUPLICATE_NAME, doc)
 }
 
+fn report_shadowed_builtin<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    lines: &LineInfo,
+    import_region: Region,
+    shadow: Loc<Ident>,
+    builtin: Symbol,
+    severity: Severity,
+) -> RocDocBuilder<'b> {
+    alloc.stack([
+        alloc.concat([
+            alloc.reflow("This definition has the same name as "),
+            alloc.symbol_qualified(builtin),
+            alloc.reflow(", a builtin from the "),
+            alloc.module(builtin.module_id()),
+            alloc.reflow(" module:"),
+        ]),
+        alloc.region(lines.convert_region(shadow.region), severity),
+        alloc.concat([alloc.reflow("The builtin is in scope because this import exposes it:")]),
+        alloc.region(lines.convert_region(import_region), severity),
+        alloc.concat([
+            alloc.reflow("Give this definition a different name, or remove "),
+            alloc.ident(shadow.value),
+            alloc.reflow(" from the import's "),
+            alloc.keyword("exposing"),
+            alloc.reflow(" list and refer to the builtin as "),
+            alloc.symbol_qualified(builtin),
+            alloc.reflow("."),
+        ]),
+    ])
+}
+
 fn pretty_runtime_error<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
@@ -2024,7 +2349,9 @@ fn pretty_runtime_error<'b>(
 
         RuntimeError::ModuleNotImported {
             module_name,
+            ident,
             imported_modules,
+            modules_exposing_ident,
             region,
             module_exists,
         } => {
@@ -2033,7 +2360,9 @@ fn pretty_runtime_error<'b>(
                 lines,
                 region,
                 &module_name,
+                &ident,
                 imported_modules,
+                modules_exposing_ident,
                 module_exists,
                 severity,
             );
@@ -2637,12 +2966,15 @@ fn not_found<'b>(
 /// Generate a message informing the user that a module was referenced, but not found
 ///
 /// See [`roc_problem::can::ModuleNotImported`]
+#[allow(clippy::too_many_arguments)]
 fn module_not_found<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
     region: roc_region::all::Region,
     name: &ModuleName,
+    ident: &Ident,
     options: MutSet<Box<str>>,
+    options_exposing_ident: MutSet<Box<str>>,
     module_exists: bool,
     severity: Severity,
 ) -> RocDocBuilder<'b> {
@@ -2654,9 +2986,24 @@ fn module_not_found<'b>(
         // If the module might not exist, suggest that it's a typo
         let mut suggestions =
             suggest::sort(name.as_str(), options.iter().map(|v| v.as_ref()).collect());
+
+        // Modules that actually expose the name being looked up are the most likely
+        // candidates, so for `Lst.map` we suggest `List.map` instead of just `List`.
+        let qualified_suggestions: Vec<_> = suggestions
+            .iter()
+            .filter(|module_name| options_exposing_ident.contains(**module_name))
+            .take(4)
+            .map(|module_name| alloc.string(format!("{module_name}.{ident}")))
+            .collect();
+
         suggestions.truncate(4);
 
-        if suggestions.is_empty() {
+        if !qualified_suggestions.is_empty() {
+            alloc.stack([
+                alloc.reflow("Is there an import missing? Perhaps there is a typo. Did you mean one of these?"),
+                alloc.vcat(qualified_suggestions).indent(4),
+            ])
+        } else if suggestions.is_empty() {
             // We don't have any recommended spelling corrections
             alloc.concat([
                 alloc.reflow("Is there an "),
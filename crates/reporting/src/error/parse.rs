@@ -1,9 +1,9 @@
-use roc_parse::parser::{ENumber, ESingleQuote, FileError, PList, SyntaxError};
+use roc_parse::parser::{ENumber, ESingleQuote, Expected, FileError, PList, SyntaxError};
 use roc_problem::Severity;
 use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Position, Region};
 use std::path::PathBuf;
 
-use crate::report::{Report, RocDocAllocator, RocDocBuilder};
+use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::DocAllocator;
 
 pub fn parse_problem<'a>(
@@ -52,6 +52,42 @@ fn list_patterns_look_like<'a>(alloc: &'a RocDocAllocator<'a>) -> RocDocBuilder<
     ])
 }
 
+/// Like "I was expecting a `,` or a `]` next."
+fn expected_next<'a>(alloc: &'a RocDocAllocator<'a>, expected: Expected) -> RocDocBuilder<'a> {
+    let things: Vec<_> = expected
+        .iter()
+        .map(|one| match one {
+            Expected::EXPR => alloc.reflow("an expression"),
+            Expected::RECORD_FIELD => alloc.reflow("a record field"),
+            Expected::COLON => alloc.concat([alloc.reflow("a "), alloc.keyword(":")]),
+            Expected::COMMA => alloc.concat([alloc.reflow("a "), alloc.keyword(",")]),
+            Expected::CLOSE_SQUARE => alloc.concat([alloc.reflow("a "), alloc.keyword("]")]),
+            Expected::CLOSE_CURLY => alloc.concat([alloc.reflow("a "), alloc.keyword("}")]),
+            _ => alloc.concat([alloc.reflow("a "), alloc.keyword(")")]),
+        })
+        .collect();
+    let last = things.len().saturating_sub(1);
+    let mut parts = vec![alloc.reflow("I was expecting ")];
+
+    for (index, thing) in things.into_iter().enumerate() {
+        if index > 0 && last > 1 {
+            parts.push(alloc.reflow(", "));
+        } else if index > 0 {
+            parts.push(alloc.reflow(" "));
+        }
+
+        if index > 0 && index == last {
+            parts.push(alloc.reflow("or "));
+        }
+
+        parts.push(thing);
+    }
+
+    parts.push(alloc.reflow(" next."));
+
+    alloc.concat(parts)
+}
+
 fn to_syntax_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
@@ -217,6 +253,28 @@ fn to_expr_report<'a>(
             }
         }
 
+        EExpr::KeywordNearMiss(keyword, region) => {
+            let surroundings = Region::new(start, region.end());
+            let region = lines.convert_region(*region);
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I am partway through parsing a definition, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([
+                    alloc.reflow("Did you mean to use the "),
+                    alloc.keyword(keyword),
+                    alloc.reflow(" keyword here? This looks like a misspelling of it."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "MISSPELLED KEYWORD".to_string(),
+                severity,
+            }
+        }
+
         EExpr::BadOperator(op, pos) => {
             let surroundings = Region::new(start, *pos);
             let region = Region::new(*pos, pos.bump_column(op.len() as u32));
@@ -686,25 +744,49 @@ fn to_record_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
     filename: PathBuf,
-    _parse_problem: &roc_parse::parser::ERecord<'a>,
+    parse_problem: &roc_parse::parser::ERecord<'a>,
     pos: Position,
     start: Position,
 ) -> Report<'a> {
-    let surroundings = Region::new(start, pos);
-    let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+    use roc_parse::parser::ERecord;
 
     let severity = Severity::RuntimeError;
-    let doc = alloc.stack([
-        alloc.reflow(r"I am partway through parsing a record, but I got stuck here:"),
-        alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
-        alloc.concat([alloc.reflow("TODO provide more context.")]),
-    ]);
 
-    Report {
-        filename,
-        doc,
-        title: "RECORD PARSE PROBLEM".to_string(),
-        severity,
+    match *parse_problem {
+        ERecord::End(expected, pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I am partway through parsing a record, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                expected_next(alloc, expected),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "UNFINISHED RECORD".to_string(),
+                severity,
+            }
+        }
+        _ => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I am partway through parsing a record, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([alloc.reflow("TODO provide more context.")]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "RECORD PARSE PROBLEM".to_string(),
+                severity,
+            }
+        }
     }
 }
 
@@ -962,9 +1044,8 @@ fn to_str_report<'a>(
             pos,
         ),
         EString::Space(error, pos) => to_space_report(alloc, lines, filename, &error, pos),
-        EString::UnknownEscape(pos) => {
-            let surroundings = Region::new(start, pos);
-            let region = Region::new(pos, pos.bump_column(2));
+        EString::UnknownEscape(region) => {
+            let surroundings = Region::new(start, region.end());
 
             let suggestion = |msg, sugg| {
                 alloc
@@ -1032,15 +1113,20 @@ fn to_str_report<'a>(
                 severity,
             }
         }
-        EString::FormatEnd(pos) => {
-            let surroundings = Region::new(start, pos);
-            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+        EString::UnterminatedInterpolation(region) => {
+            let surroundings = Region::new(start, region.end());
 
             let doc = alloc.stack([
-                alloc.reflow(r"I cannot find the end of this format expression:"),
-                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.reflow(r"I cannot find the end of this string interpolation:"),
+                alloc.region_with_subregion(
+                    lines.convert_region(surroundings),
+                    lines.convert_region(region),
+                    severity,
+                ),
                 alloc.concat([
-                    alloc.reflow(r"You could change it to something like "),
+                    alloc.reflow(r"I was expecting a "),
+                    alloc.parser_suggestion(")"),
+                    alloc.reflow(r" to end it, like in "),
                     alloc.parser_suggestion("\"The count is $(count)\""),
                     alloc.reflow("."),
                 ]),
@@ -1049,7 +1135,31 @@ fn to_str_report<'a>(
             Report {
                 filename,
                 doc,
-                title: "ENDLESS FORMAT".to_string(),
+                title: "ENDLESS INTERPOLATION".to_string(),
+                severity,
+            }
+        }
+        EString::NestedInterpolation(region) => {
+            let surroundings = Region::new(start, region.end());
+
+            let doc = alloc.stack([
+                alloc.reflow(r"This string interpolation is inside of another one:"),
+                alloc.region_with_subregion(
+                    lines.convert_region(surroundings),
+                    lines.convert_region(region),
+                    severity,
+                ),
+                alloc.concat([
+                    alloc.reflow(r"Interpolations can't be nested. Try giving the inner string a name with a "),
+                    alloc.keyword("="),
+                    alloc.reflow(r" definition, and interpolating that instead."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "NESTED INTERPOLATION".to_string(),
                 severity,
             }
         }
@@ -1212,6 +1322,34 @@ fn to_str_report<'a>(
                 severity,
             }
         }
+        EString::EndlessRaw(hashes, pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+            let hashes = "#".repeat(hashes as usize);
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I cannot find the end of this raw string:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([
+                    alloc.reflow(r"It starts with "),
+                    alloc
+                        .string(format!("r{hashes}\""))
+                        .annotate(Annotation::ParserSuggestion),
+                    alloc.reflow(r", so I was looking for a "),
+                    alloc
+                        .string(format!("\"{hashes}"))
+                        .annotate(Annotation::ParserSuggestion),
+                    alloc.reflow(r" to end it."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "ENDLESS STRING".to_string(),
+                severity,
+            }
+        }
         EString::MultilineInsufficientIndent(pos) => {
             let surroundings = Region::new(start, pos);
             let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
@@ -1275,21 +1413,16 @@ fn to_expr_in_parens_report<'a>(
                 severity,
             }
         }
-        EInParens::End(pos) => {
+        EInParens::End(expected, pos) => {
             let surroundings = Region::new(start, pos);
             let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
 
             let doc = alloc.stack([
-                alloc
-                    .reflow("I am partway through parsing a record pattern, but I got stuck here:"),
+                alloc.reflow(
+                    "I am partway through parsing a parenthesized expression or tuple, but I got stuck here:",
+                ),
                 alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
-                alloc.concat([
-                    alloc.reflow(
-                        r"I was expecting to see a closing parenthesis next, so try adding a ",
-                    ),
-                    alloc.parser_suggestion(")"),
-                    alloc.reflow(" and see if that helps?"),
-                ]),
+                expected_next(alloc, expected),
             ]);
 
             Report {
@@ -1349,7 +1482,7 @@ fn to_list_report<'a>(
             pos,
         ),
 
-        EList::Open(pos) | EList::End(pos) => {
+        EList::Open(pos) | EList::End(_, pos) => {
             match what_is_next(alloc.src_lines, lines.convert_pos(pos)) {
                 Next::Other(Some(',')) => {
                     let surroundings = Region::new(start, pos);
@@ -1381,6 +1514,10 @@ fn to_list_report<'a>(
                 _ => {
                     let surroundings = Region::new(start, pos);
                     let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+                    let expected = match *parse_problem {
+                        EList::End(expected, _) => expected,
+                        _ => Expected::EXPR.or(Expected::CLOSE_SQUARE),
+                    };
 
                     let doc = alloc.stack([
                         alloc.reflow(
@@ -1391,14 +1528,7 @@ fn to_list_report<'a>(
                             region,
                             severity,
                         ),
-                        alloc.concat([
-                            alloc.reflow(
-                                r"I was expecting to see a closing square bracket before this, ",
-                            ),
-                            alloc.reflow(r"so try adding a "),
-                            alloc.parser_suggestion("]"),
-                            alloc.reflow(r" and see if that helps?"),
-                        ]),
+                        expected_next(alloc, expected),
                         alloc.concat([
                             alloc.note("When "),
                             alloc.reflow(r"I get stuck like this, "),
@@ -3531,6 +3661,8 @@ fn to_header_report<'a>(
             to_packages_report(alloc, lines, filename, packages, *pos)
         }
 
+        EHeader::Hints(hints, pos) => to_hints_report(alloc, lines, filename, hints, *pos),
+
         EHeader::IndentStart(pos) => {
             let surroundings = Region::new(start, *pos);
             let region = LineColumnRegion::from_pos(lines.convert_pos(*pos));
@@ -4238,6 +4370,127 @@ fn to_packages_report<'a>(
     }
 }
 
+fn to_hints_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    parse_problem: &roc_parse::parser::EHints,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::parser::{EHintEntry, EHints};
+
+    let severity = Severity::RuntimeError;
+
+    match *parse_problem {
+        EHints::ListStart(pos) | EHints::IndentListStart(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I am partway through parsing a header, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([
+                    alloc.reflow("I am expecting the "),
+                    alloc.keyword("hints"),
+                    alloc.reflow(" keyword to be followed by a record of hints, like"),
+                ]),
+                alloc
+                    .parser_suggestion("hints { Task: \"Tasks run when main returns them.\" }")
+                    .indent(4),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "WEIRD HINTS".to_string(),
+                severity,
+            }
+        }
+        EHints::ListEnd(pos)
+        | EHints::HintEntry(
+            EHintEntry::Name(_)
+            | EHintEntry::Colon(_)
+            | EHintEntry::IndentHint(_)
+            | EHintEntry::Hint(..),
+            pos,
+        ) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc
+                    .reflow(r"I am partway through parsing a list of hints, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([
+                    alloc.reflow("I am expecting a type or function the platform exposes, "),
+                    alloc.reflow("followed by a colon and a string, like"),
+                ]),
+                alloc
+                    .parser_suggestion("hints { Task: \"…\", Task.await: \"…\" }")
+                    .indent(4),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "WEIRD HINTS LIST".to_string(),
+                severity,
+            }
+        }
+        EHints::HintEntry(EHintEntry::Escapes(pos), _) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"This hint has escapes or interpolation in it:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([
+                    alloc.reflow("Hints are shown exactly as they are written, so they can't "),
+                    alloc.reflow("use escapes or interpolation. A raw string, like "),
+                    alloc.parser_suggestion("r#\"C:\\Users\"#"),
+                    alloc.reflow(", keeps backslashes and quotes as they are."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "WEIRD HINT".to_string(),
+                severity,
+            }
+        }
+
+        EHints::Hints(pos) | EHints::IndentHints(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I am partway through parsing a header, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region, severity),
+                alloc.concat([
+                    alloc.reflow("I am expecting the "),
+                    alloc.keyword("hints"),
+                    alloc.reflow(" keyword next, like"),
+                ]),
+                alloc
+                    .parser_suggestion("hints { Task: \"Tasks run when main returns them.\" }")
+                    .indent(4),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "WEIRD HINTS".to_string(),
+                severity,
+            }
+        }
+
+        EHints::Space(error, pos) | EHints::HintEntry(EHintEntry::Space(error, pos), _) => {
+            to_space_report(alloc, lines, filename, &error, pos)
+        }
+    }
+}
+
 fn to_space_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
@@ -4259,6 +4512,15 @@ fn to_space_report<'a>(
                 alloc.reflow(
                     "Tab characters are not allowed in Roc code. Please use spaces instead!",
                 ),
+                alloc.concat([
+                    alloc.reflow("If you'd rather keep the tabs, run roc with "),
+                    alloc.keyword("--tab-width 4"),
+                    alloc.reflow(" (or set "),
+                    alloc.keyword("ROC_TAB_WIDTH=4"),
+                    alloc.reflow(
+                        ") and each one will count as up to that many columns of indentation.",
+                    ),
+                ]),
             ]);
 
             Report {
@@ -8,16 +8,17 @@ use roc_can::expected::{Expected, PExpected};
 use roc_collections::all::{HumanIndex, MutSet, SendMap};
 use roc_collections::VecMap;
 use roc_error_macros::internal_error;
-use roc_exhaustive::{CtorName, ListArity};
+use roc_exhaustive::{CoveredBy, CtorName, ListArity};
 use roc_module::called_via::{BinOp, CalledVia};
-use roc_module::ident::{IdentStr, Lowercase, TagName};
+use roc_module::ident::{IdentStr, Lowercase, ModuleName, TagName};
 use roc_module::symbol::Symbol;
 use roc_problem::Severity;
-use roc_region::all::{LineInfo, Region};
+use roc_region::all::{LineInfo, Loc, Region};
 use roc_solve_problem::{
     NotDerivableContext, NotDerivableEq, TypeError, UnderivableReason, Unfulfilled,
 };
 use roc_std::RocDec;
+use roc_types::num::IntLitWidth;
 use roc_types::pretty_print::{Parens, WILDCARD};
 use roc_types::types::{
     AbilitySet, AliasKind, Category, ErrorType, IndexOrField, PatternCategory, Polarity, Reason,
@@ -28,6 +29,58 @@ use ven_pretty::{text, DocAllocator};
 
 const ADD_ANNOTATIONS: &str = r#"Can more type annotations be added? Type annotations always help me give more specific messages, and I think they could help a lot in this case"#;
 
+/// The environment variable which makes reports print every type in full, instead of eliding
+/// the deeply nested parts of very large types.
+pub const VERBOSE_TYPES_VAR: &str = "ROC_VERBOSE_TYPES";
+
+/// Types made up of more than this many parts get their deeply nested structure elided.
+const ELIDE_TYPES_LARGER_THAN: usize = 60;
+
+/// How many records, tuples and tag unions deep a large type is printed before the rest is elided.
+const ELIDED_NESTING_DEPTH: usize = 3;
+
+/// The environment variable which makes reports print type aliases as the types they stand for.
+pub const EXPAND_ALIASES_VAR: &str = "ROC_EXPAND_ALIASES";
+
+/// The environment variable which makes reports qualify type names with their module.
+pub const QUALIFY_TYPES_VAR: &str = "ROC_QUALIFY_TYPES";
+
+/// The environment variable holding the width in columns up to which reports print records,
+/// tuples and tag unions on one line.
+pub const TYPE_WIDTH_VAR: &str = "ROC_TYPE_WIDTH";
+
+/// How types get printed in reports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TypePrintOptions {
+    /// Records, tuples and tag unions with several entries get printed on one line if they fit
+    /// in this many columns. Without a width, they're always printed over multiple lines.
+    pub max_width: Option<usize>,
+    /// Print aliases defined outside the builtins as the type they stand for, so a `Model`
+    /// alias is shown as its record. Opaque types are never expanded.
+    pub expand_aliases: bool,
+    /// Qualify the names of types with their module, even in the module being reported on.
+    /// Builtin types are never qualified, and neither are types defined in an app, since an
+    /// app's module has no name.
+    pub qualify_names: bool,
+}
+
+impl TypePrintOptions {
+    /// Reads the options from `ROC_TYPE_WIDTH`, `ROC_EXPAND_ALIASES` and `ROC_QUALIFY_TYPES`.
+    pub fn from_env() -> Self {
+        TypePrintOptions {
+            max_width: std::env::var(TYPE_WIDTH_VAR)
+                .ok()
+                .and_then(|width| width.trim().parse().ok()),
+            expand_aliases: env_flag(EXPAND_ALIASES_VAR),
+            qualify_names: env_flag(QUALIFY_TYPES_VAR),
+        }
+    }
+
+    fn expands(&self, symbol: Symbol, kind: AliasKind) -> bool {
+        self.expand_aliases && kind == AliasKind::Structural && !symbol.module_id().is_builtin()
+    }
+}
+
 const OPAQUE_NUM_SYMBOLS: &[Symbol] = &[
     Symbol::NUM_NUM,
     Symbol::NUM_INTEGER,
@@ -55,21 +108,39 @@ pub fn type_problem<'b>(
         };
 
     match problem {
-        BadExpr(region, category, found, expected) => Some(to_expr_report(
-            alloc, lines, filename, severity, region, category, found, expected,
-        )),
-        BadPattern(region, category, found, expected) => Some(to_pattern_report(
-            alloc, lines, filename, severity, region, category, found, expected,
-        )),
-        CircularType(region, symbol, overall_type) => Some(to_circular_report(
-            alloc,
-            lines,
-            filename,
-            severity,
-            region,
-            symbol,
-            overall_type,
-        )),
+        BadExpr(region, category, found, expected) => {
+            let mut symbols = Vec::new();
+            add_expr_symbols(&category, &found, &expected, &mut symbols);
+
+            let report = to_expr_report(
+                alloc, lines, filename, severity, region, category, found, expected,
+            );
+
+            Some(with_platform_hints(alloc, report, symbols))
+        }
+        BadPattern(region, category, found, expected) => {
+            let mut symbols = Vec::new();
+            add_type_symbols(&found, &mut symbols);
+            add_type_symbols(expected.get_type_ref(), &mut symbols);
+
+            let report = to_pattern_report(
+                alloc, lines, filename, severity, region, category, found, expected,
+            );
+
+            Some(with_platform_hints(alloc, report, symbols))
+        }
+        CircularType(region, symbol, overall_type, self_referential_arg) => {
+            Some(to_circular_report(
+                alloc,
+                lines,
+                filename,
+                severity,
+                region,
+                symbol,
+                overall_type,
+                self_referential_arg,
+            ))
+        }
         UnexposedLookup(_, symbol) => {
             let title = "UNRECOGNIZED NAME".to_string();
             let doc = alloc
@@ -314,6 +385,59 @@ pub fn type_problem<'b>(
                 severity,
             })
         }
+        DefaultedNumber {
+            region,
+            default,
+            is_error,
+        } => {
+            let (title, intro, explanation) = if is_error {
+                (
+                    "AMBIGUOUS NUMBER TYPE",
+                    alloc.reflow("Nothing decides this number's type:"),
+                    alloc.concat([
+                        alloc.reflow("It would have defaulted to "),
+                        alloc.symbol_unqualified(default.symbol()),
+                        alloc.reflow(", but defaulting number types is turned off."),
+                    ]),
+                )
+            } else {
+                (
+                    "DEFAULTED NUMBER",
+                    alloc.concat([
+                        alloc.reflow("Nothing decides this number's type, so it defaults to "),
+                        alloc.symbol_unqualified(default.symbol()),
+                        alloc.reflow(":"),
+                    ]),
+                    alloc.concat([
+                        alloc.reflow("Integers whose type isn't decided become an "),
+                        alloc.symbol_unqualified(Symbol::NUM_I64),
+                        alloc.reflow(" if they fit in one, and fractions become a "),
+                        alloc.symbol_unqualified(Symbol::NUM_DEC),
+                        alloc.reflow("."),
+                    ]),
+                )
+            };
+
+            let stack = [
+                intro,
+                alloc.region(lines.convert_region(region), severity),
+                explanation,
+                alloc.tip().append(alloc.concat([
+                    alloc.reflow(
+                        "To pick the type yourself, add a type annotation to a definition ",
+                    ),
+                    alloc.reflow("that uses this number, or give it a suffix like "),
+                    alloc.parser_suggestion("5u8"),
+                    alloc.reflow("."),
+                ])),
+            ];
+            Some(Report {
+                title: title.to_string(),
+                filename,
+                doc: alloc.stack(stack),
+                severity,
+            })
+        }
     }
 }
 
@@ -512,7 +636,7 @@ pub fn cyclic_alias<'b>(
     lines: &LineInfo,
     symbol: Symbol,
     region: roc_region::all::Region,
-    others: Vec<Symbol>,
+    others: Vec<Loc<Symbol>>,
     alias_kind: AliasKind,
     severity: Severity,
 ) -> (RocDocBuilder<'b>, String) {
@@ -521,46 +645,78 @@ pub fn cyclic_alias<'b>(
         .append(alloc.reflow(alias_kind.as_str()))
         .append(alloc.reflow("es is only allowed if recursion happens behind a tagged union, at least one variant of which is not recursive."));
 
-    let doc = if others.is_empty() {
-        alloc.stack([
-            alloc
-                .reflow("The ")
-                .append(alloc.symbol_unqualified(symbol))
-                .append(alloc.reflow(" "))
-                .append(alloc.reflow(alias_kind.as_str()))
-                .append(alloc.reflow(" is self-recursive in an invalid way:")),
-            alloc.region(lines.convert_region(region), severity),
-            when_is_recursion_legal,
-        ])
-    } else {
-        alloc.stack([
-            alloc
-                .reflow("The ")
-                .append(alloc.symbol_unqualified(symbol))
-                .append(alloc.reflow(" "))
-                .append(alloc.reflow(alias_kind.as_str()))
-                .append(alloc.reflow(" is recursive in an invalid way:")),
-            alloc.region(lines.convert_region(region), severity),
-            alloc
-                .reflow("The ")
-                .append(alloc.symbol_unqualified(symbol))
-                .append(alloc.reflow(" "))
-                .append(alloc.reflow(alias_kind.as_str()))
-                .append(
-                    alloc.reflow(" depends on itself through the following chain of definitions:"),
-                ),
-            crate::report::cycle(
+    // Suggest wrapping the first link of the chain in a tag union, since that's the
+    // recursion point closest to the definition we're reporting.
+    let recursion_point = others.first().map_or(symbol, |other| other.value);
+    let recursion_point_str = recursion_point.as_str(alloc.interns);
+    let introduce_tag_union = alloc.concat([
+        alloc.tip(),
+        alloc.reflow("To break the cycle, wrap the reference to "),
+        alloc.symbol_unqualified(recursion_point),
+        alloc.reflow(" in a tag union with a non-recursive variant, like "),
+        alloc.type_str(&format!("[Done, More {recursion_point_str}]")),
+        alloc.reflow(
+            ", or add a non-recursive variant to a tag union that is already in the cycle.",
+        ),
+    ]);
+
+    let doc =
+        if others.is_empty() {
+            alloc.stack([
+                alloc
+                    .reflow("The ")
+                    .append(alloc.symbol_unqualified(symbol))
+                    .append(alloc.reflow(" "))
+                    .append(alloc.reflow(alias_kind.as_str()))
+                    .append(alloc.reflow(" is self-recursive in an invalid way:")),
+                alloc.region(lines.convert_region(region), severity),
+                when_is_recursion_legal,
+                introduce_tag_union,
+            ])
+        } else {
+            let chain = crate::report::cycle(
                 alloc,
                 4,
                 alloc.symbol_unqualified(symbol),
                 others
-                    .into_iter()
-                    .map(|other| alloc.symbol_unqualified(other))
+                    .iter()
+                    .map(|other| alloc.symbol_unqualified(other.value))
                     .collect::<Vec<_>>(),
-            ),
-            when_is_recursion_legal,
-        ])
-    };
+            );
+
+            let links = others.into_iter().map(|other| {
+                alloc.stack([
+                    alloc
+                        .symbol_unqualified(other.value)
+                        .append(alloc.reflow(" is defined here:")),
+                    alloc.region(lines.convert_region(other.region), severity),
+                ])
+            });
+
+            alloc.stack(
+                [
+                    alloc
+                        .reflow("The ")
+                        .append(alloc.symbol_unqualified(symbol))
+                        .append(alloc.reflow(" "))
+                        .append(alloc.reflow(alias_kind.as_str()))
+                        .append(alloc.reflow(" is recursive in an invalid way:")),
+                    alloc.region(lines.convert_region(region), severity),
+                    alloc
+                        .reflow("The ")
+                        .append(alloc.symbol_unqualified(symbol))
+                        .append(alloc.reflow(" "))
+                        .append(alloc.reflow(alias_kind.as_str()))
+                        .append(alloc.reflow(
+                            " depends on itself through the following chain of definitions:",
+                        )),
+                    chain,
+                ]
+                .into_iter()
+                .chain(links)
+                .chain([when_is_recursion_legal, introduce_tag_union]),
+            )
+        };
 
     (doc, "CYCLIC ALIAS".to_string())
 }
@@ -675,6 +831,111 @@ fn lowercase_first(s: &str) -> String {
     }
 }
 
+/// Appends the hints the platform gave for any of these symbols to the report.
+fn with_platform_hints<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    mut report: Report<'b>,
+    symbols: Vec<Symbol>,
+) -> Report<'b> {
+    let platform_hints = match alloc.platform_hints {
+        Some(platform_hints) if !platform_hints.is_empty() => platform_hints,
+        _ => return report,
+    };
+
+    let mut shown: Vec<(&str, &str)> = Vec::new();
+
+    for symbol in symbols {
+        if let Some(entry) = platform_hints.get(alloc.interns, symbol) {
+            if !shown.contains(&entry) {
+                shown.push(entry);
+            }
+        }
+    }
+
+    if shown.is_empty() {
+        return report;
+    }
+
+    let hints = shown.into_iter().map(|(name, hint)| {
+        alloc.concat([
+            alloc.hint(""),
+            alloc.reflow("The platform says this about "),
+            alloc.text(name).annotate(Annotation::Symbol),
+            alloc.reflow(": "),
+            alloc.reflow(hint),
+        ])
+    });
+
+    report.doc = alloc.stack(std::iter::once(report.doc).chain(hints));
+
+    report
+}
+
+/// Adds the symbols involved in a mismatched expression: the names being looked up or
+/// called, and the named types on either side of the mismatch.
+fn add_expr_symbols(
+    category: &Category,
+    found: &ErrorType,
+    expected: &Expected<ErrorType>,
+    symbols: &mut Vec<Symbol>,
+) {
+    match category {
+        Category::Lookup(symbol) | Category::CallResult(Some(symbol), _) => symbols.push(*symbol),
+        _ => {}
+    }
+
+    if let Expected::ForReason(
+        Reason::FnArg {
+            name: Some(symbol), ..
+        }
+        | Reason::FnCall {
+            name: Some(symbol), ..
+        },
+        _,
+        _,
+    ) = expected
+    {
+        symbols.push(*symbol);
+    }
+
+    add_type_symbols(found, symbols);
+    add_type_symbols(expected.get_type_ref(), symbols);
+}
+
+/// Adds the names of the types and aliases that appear in this type. The types an alias
+/// stands for are left out, since those aren't what the user wrote or sees in the report.
+fn add_type_symbols(tipe: &ErrorType, symbols: &mut Vec<Symbol>) {
+    use ErrorType::*;
+
+    match tipe {
+        Type(symbol, args) | Alias(symbol, args, _, _) => {
+            symbols.push(*symbol);
+            args.iter().for_each(|arg| add_type_symbols(arg, symbols));
+        }
+        Record(fields, _) => fields
+            .iter()
+            .for_each(|(_, field)| add_type_symbols(field.as_inner(), symbols)),
+        Tuple(elems, _) => elems
+            .iter()
+            .for_each(|(_, elem)| add_type_symbols(elem, symbols)),
+        TagUnion(tags, _, _) | RecursiveTagUnion(_, tags, _, _) => tags
+            .iter()
+            .flat_map(|(_, payloads)| payloads)
+            .for_each(|payload| add_type_symbols(payload, symbols)),
+        Function(args, _, ret) => {
+            args.iter().for_each(|arg| add_type_symbols(arg, symbols));
+            add_type_symbols(ret, symbols);
+        }
+        Infinite
+        | FlexVar(_)
+        | RigidVar(_)
+        | FlexAbleVar(_, _)
+        | RigidAbleVar(_, _)
+        | Range(_)
+        | Error => {}
+    }
+}
+
 fn to_expr_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
@@ -1725,6 +1986,7 @@ fn type_comparison<'b>(
     instead_of: RocDocBuilder<'b>,
     context_hints: Option<RocDocBuilder<'b>>,
 ) -> RocDocBuilder<'b> {
+    let elided = elides_nested_types(&actual) || elides_nested_types(&expected);
     let comparison = to_comparison(alloc, actual, expected);
 
     let mut lines = vec![
@@ -1738,6 +2000,10 @@ fn type_comparison<'b>(
         lines.push(alloc.concat(context_hints));
     }
 
+    if elided {
+        lines.push(verbose_types_note(alloc));
+    }
+
     lines.extend(problems_to_tip(
         alloc,
         comparison.problems,
@@ -1755,10 +2021,15 @@ fn lone_type<'b>(
     i_am_seeing: RocDocBuilder<'b>,
     further_details: RocDocBuilder<'b>,
 ) -> RocDocBuilder<'b> {
+    let elided = elides_nested_types(&actual) || elides_nested_types(&expected);
     let comparison = to_comparison(alloc, actual, expected);
 
     let mut lines = vec![i_am_seeing, comparison.actual, further_details];
 
+    if elided {
+        lines.push(verbose_types_note(alloc));
+    }
+
     lines.extend(problems_to_tip(
         alloc,
         comparison.problems,
@@ -2250,6 +2521,7 @@ fn add_pattern_category<'b>(
     alloc.concat([i_am_trying_to_match, rest])
 }
 
+#[allow(clippy::too_many_arguments)]
 fn to_circular_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
@@ -2258,27 +2530,53 @@ fn to_circular_report<'b>(
     region: roc_region::all::Region,
     symbol: Symbol,
     overall_type: ErrorType,
+    self_referential_arg: Option<roc_region::all::Region>,
 ) -> Report<'b> {
+    let mut stack = vec![
+        alloc
+            .reflow("I'm inferring a weird self-referential type for ")
+            .append(alloc.symbol_unqualified(symbol))
+            .append(alloc.text(":")),
+        alloc.region(lines.convert_region(region), severity),
+        alloc.stack([
+            alloc.reflow(
+                "Here is my best effort at writing down the type. \
+                You will see ∞ for parts of the type that repeat \
+                something already printed out infinitely.",
+            ),
+            alloc.type_block(to_doc(alloc, Parens::Unnecessary, overall_type).0),
+        ]),
+    ];
+
+    if let Some(arg_region) = self_referential_arg {
+        stack.extend([
+            alloc.reflow(
+                "The type of this argument would have to contain itself, \
+                which is where the self-reference comes from:",
+            ),
+            alloc.region(lines.convert_region(arg_region), severity),
+        ]);
+    }
+
+    stack.push(alloc.tip().append(alloc.concat(
+        [
+            alloc.reflow("This usually happens when a value is applied to itself, like "),
+            alloc.keyword("g g"),
+            alloc.reflow(
+                "; when a function calls itself with its argument wrapped in something, like ",
+            ),
+            alloc.keyword("f [x]"),
+            alloc.reflow(
+                "; or when a call is missing an argument, so a function ends up being used \
+            where its result was expected.",
+            ),
+        ],
+    )));
+
     Report {
         title: "CIRCULAR TYPE".to_string(),
         filename,
-        doc: {
-            alloc.stack([
-                alloc
-                    .reflow("I'm inferring a weird self-referential type for ")
-                    .append(alloc.symbol_unqualified(symbol))
-                    .append(alloc.text(":")),
-                alloc.region(lines.convert_region(region), severity),
-                alloc.stack([
-                    alloc.reflow(
-                        "Here is my best effort at writing down the type. \
-                        You will see ∞ for parts of the type that repeat \
-                        something already printed out infinitely.",
-                    ),
-                    alloc.type_block(to_doc(alloc, Parens::Unnecessary, overall_type).0),
-                ]),
-            ])
-        },
+        doc: alloc.stack(stack),
         severity,
     }
 }
@@ -2286,6 +2584,9 @@ fn to_circular_report<'b>(
 #[derive(Debug, Clone)]
 pub enum Problem {
     IntFloat,
+    /// A number literal doesn't fit in the integer type it's used as. Holds that type, and the
+    /// smallest integer type the literal would fit in.
+    NumLiteralOutOfRange(IntLitWidth, Option<IntLitWidth>),
     ArityMismatch(usize, usize),
     FieldTypo(Lowercase, Vec<Lowercase>),
     FieldsMissing(Vec<Lowercase>),
@@ -2295,6 +2596,12 @@ pub enum Problem {
     OptionalRequiredMismatch(Lowercase),
     OpaqueComparedToNonOpaque,
     BoolVsBoolTag(TagName),
+    /// Two `Task`s that succeed with different types. Holds the actual and expected success types.
+    TaskSuccessMismatch(ErrorType, ErrorType),
+    /// A `Task` was used where the value it succeeds with was expected.
+    TaskUsedAsValue(ErrorType),
+    /// A value was used where a `Task` succeeding with it was expected.
+    ValueUsedAsTask(ErrorType),
 }
 
 fn problems_to_tip<'b>(
@@ -2489,6 +2796,11 @@ type AbleVariables = Vec<(Lowercase, AbilitySet)>;
 #[derive(Default)]
 struct Context {
     able_variables: AbleVariables,
+    /// How many records, tuples and tag unions we're currently nested inside of.
+    depth: usize,
+    /// The nesting depth at which records, tuples and tag unions get elided, if the type is
+    /// large enough for that.
+    elide_at_depth: Option<usize>,
 }
 
 pub fn to_doc<'b>(
@@ -2496,7 +2808,10 @@ pub fn to_doc<'b>(
     parens: Parens,
     tipe: ErrorType,
 ) -> (RocDocBuilder<'b>, AbleVariables) {
-    let mut ctx = Context::default();
+    let mut ctx = Context {
+        elide_at_depth: elision_depth(&tipe),
+        ..Context::default()
+    };
 
     let mut generated_name_usages = VecMap::default();
     count_generated_name_usages(&mut generated_name_usages, [&tipe]);
@@ -2513,6 +2828,118 @@ fn display_generated_name(name: &Lowercase) -> &str {
     &name.as_str()[1..]
 }
 
+fn env_flag(var: &str) -> bool {
+    std::env::var_os(var).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+fn verbose_types() -> bool {
+    env_flag(VERBOSE_TYPES_VAR)
+}
+
+/// Whether the type options ask for this type's name to be qualified with its module.
+fn qualifies(alloc: &RocDocAllocator<'_>, symbol: Symbol) -> bool {
+    alloc.type_options.qualify_names
+        && !symbol.module_id().is_builtin()
+        && symbol.module_string(alloc.interns).as_str() != ModuleName::APP
+}
+
+/// The name of a type, qualified with its module unless it's a builtin or (by default) it's
+/// defined in the module being reported on.
+fn type_name<'b>(alloc: &'b RocDocAllocator<'b>, symbol: Symbol) -> RocDocBuilder<'b> {
+    if qualifies(alloc, symbol) {
+        alloc.symbol_qualified(symbol)
+    } else {
+        alloc.symbol_foreign_qualified(symbol)
+    }
+}
+
+/// The nesting depth past which this type's records, tuples and tag unions are printed as `…`,
+/// or `None` if it's small enough to print in full.
+fn elision_depth(tipe: &ErrorType) -> Option<usize> {
+    if type_size(tipe) > ELIDE_TYPES_LARGER_THAN && !verbose_types() {
+        Some(ELIDED_NESTING_DEPTH)
+    } else {
+        None
+    }
+}
+
+/// Whether printing this type will elide some of its deeply nested structure.
+fn elides_nested_types(tipe: &ErrorType) -> bool {
+    elision_depth(tipe).is_some_and(|depth| nesting_depth(tipe) > depth)
+}
+
+fn verbose_types_note<'b>(alloc: &'b RocDocAllocator<'b>) -> RocDocBuilder<'b> {
+    alloc.note("").append(alloc.concat([
+        alloc.reflow("These types are large, so some of their deeply nested parts are shown as "),
+        alloc.ellipsis(),
+        alloc.reflow(" to keep them readable. Run roc with "),
+        alloc.keyword("--verbose-types"),
+        alloc.reflow(" (or set "),
+        alloc.keyword("ROC_VERBOSE_TYPES=1"),
+        alloc.reflow(") to see them in full."),
+    ]))
+}
+
+/// How many parts make up this type, counting every type, field and tag in it.
+fn type_size(tipe: &ErrorType) -> usize {
+    use ErrorType::*;
+
+    1 + match tipe {
+        Function(args, _, ret) => args.iter().map(type_size).sum::<usize>() + type_size(ret),
+        Type(_, args) | Alias(_, args, _, _) | Range(args) => args.iter().map(type_size).sum(),
+        Record(fields, _) => fields
+            .values()
+            .map(|field| 1 + type_size(field.as_inner()))
+            .sum(),
+        Tuple(elems, _) => elems.iter().map(|(_, elem)| type_size(elem)).sum(),
+        TagUnion(tags, _, _) | RecursiveTagUnion(_, tags, _, _) => tags
+            .values()
+            .map(|args| 1 + args.iter().map(type_size).sum::<usize>())
+            .sum(),
+        Infinite | Error | FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) => 0,
+    }
+}
+
+/// How many records, tuples and tag unions deep this type goes.
+fn nesting_depth(tipe: &ErrorType) -> usize {
+    use ErrorType::*;
+
+    match tipe {
+        Function(args, _, ret) => args
+            .iter()
+            .chain([&**ret])
+            .map(nesting_depth)
+            .max()
+            .unwrap_or(0),
+        Type(_, args) | Alias(_, args, _, _) | Range(args) => {
+            args.iter().map(nesting_depth).max().unwrap_or(0)
+        }
+        Record(fields, _) => {
+            1 + fields
+                .values()
+                .map(|field| nesting_depth(field.as_inner()))
+                .max()
+                .unwrap_or(0)
+        }
+        Tuple(elems, _) => {
+            1 + elems
+                .iter()
+                .map(|(_, elem)| nesting_depth(elem))
+                .max()
+                .unwrap_or(0)
+        }
+        TagUnion(tags, _, _) | RecursiveTagUnion(_, tags, _, _) => {
+            1 + tags
+                .values()
+                .flatten()
+                .map(nesting_depth)
+                .max()
+                .unwrap_or(0)
+        }
+        Infinite | Error | FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) => 0,
+    }
+}
+
 fn to_doc_help<'b>(
     ctx: &mut Context,
     gen_usages: &VecMap<Lowercase, usize>,
@@ -2522,6 +2949,57 @@ fn to_doc_help<'b>(
 ) -> RocDocBuilder<'b> {
     use ErrorType::*;
 
+    match tipe {
+        Record(..) | Tuple(..) | TagUnion(..) | RecursiveTagUnion(..) => {
+            if ctx.elide_at_depth.is_some_and(|depth| ctx.depth >= depth) {
+                return elided_to_doc(gen_usages, alloc, tipe);
+            }
+
+            ctx.depth += 1;
+            let doc = structure_to_doc(ctx, gen_usages, alloc, parens, tipe);
+            ctx.depth -= 1;
+
+            doc
+        }
+        _ => structure_to_doc(ctx, gen_usages, alloc, parens, tipe),
+    }
+}
+
+/// Prints a record, tuple or tag union with all of its contents replaced by `…`.
+fn elided_to_doc<'b>(
+    gen_usages: &VecMap<Lowercase, usize>,
+    alloc: &'b RocDocAllocator<'b>,
+    tipe: ErrorType,
+) -> RocDocBuilder<'b> {
+    use ErrorType::*;
+
+    match tipe {
+        Record(fields, ext) => {
+            report_text::record(alloc, vec![], record_ext_to_doc(alloc, ext), fields.len())
+        }
+        Tuple(elems, ext) => {
+            report_text::tuple(alloc, vec![], record_ext_to_doc(alloc, ext), elems.len())
+        }
+        TagUnion(tags, ext, pol) | RecursiveTagUnion(_, tags, ext, pol) => report_text::tag_union(
+            alloc,
+            vec![],
+            tag_ext_to_doc(alloc, pol, gen_usages, ext),
+            tags.len(),
+            None,
+        ),
+        _ => internal_error!("only records, tuples and tag unions are elided"),
+    }
+}
+
+fn structure_to_doc<'b>(
+    ctx: &mut Context,
+    gen_usages: &VecMap<Lowercase, usize>,
+    alloc: &'b RocDocAllocator<'b>,
+    parens: Parens,
+    tipe: ErrorType,
+) -> RocDocBuilder<'b> {
+    use ErrorType::*;
+
     match tipe {
         Function(args, _, ret) => report_text::function(
             alloc,
@@ -2531,7 +3009,7 @@ fn to_doc_help<'b>(
                 .collect(),
             to_doc_help(ctx, gen_usages, alloc, Parens::InFn, *ret),
         ),
-        Infinite => alloc.text("∞"),
+        Infinite => alloc.text("∞").annotate(Annotation::Error),
         Error => alloc.text("?"),
 
         FlexVar(lowercase) if is_generated_name(&lowercase) => {
@@ -2554,7 +3032,7 @@ fn to_doc_help<'b>(
         Type(symbol, args) => report_text::apply(
             alloc,
             parens,
-            alloc.symbol_foreign_qualified(symbol),
+            type_name(alloc, symbol),
             args.into_iter()
                 .map(|arg| to_doc_help(ctx, gen_usages, alloc, Parens::InTypeParam, arg))
                 .collect(),
@@ -2582,10 +3060,14 @@ fn to_doc_help<'b>(
             )
         }
 
+        Alias(symbol, _, real, kind) if alloc.type_options.expands(symbol, kind) => {
+            to_doc_help(ctx, gen_usages, alloc, parens, *real)
+        }
+
         Alias(symbol, args, _, _) => report_text::apply(
             alloc,
             parens,
-            alloc.symbol_foreign_qualified(symbol),
+            type_name(alloc, symbol),
             args.into_iter()
                 .map(|arg| to_doc_help(ctx, gen_usages, alloc, Parens::InTypeParam, arg))
                 .collect(),
@@ -2870,6 +3352,26 @@ pub fn error_type_to_doc<'b>(
     type_with_able_vars(alloc, typ, able_vars)
 }
 
+/// The name of a type in a diff, which is only qualified with its module if the options ask for
+/// that.
+fn diff_type_name<'b>(alloc: &'b RocDocAllocator<'b>, symbol: Symbol) -> RocDocBuilder<'b> {
+    if qualifies(alloc, symbol) {
+        alloc.symbol_qualified(symbol)
+    } else {
+        alloc.symbol_unqualified(symbol)
+    }
+}
+
+/// Replaces aliases with the types they stand for, as long as the options ask for that.
+fn expand_alias(alloc: &RocDocAllocator<'_>, typ: ErrorType) -> ErrorType {
+    match typ {
+        ErrorType::Alias(symbol, _, real, kind) if alloc.type_options.expands(symbol, kind) => {
+            expand_alias(alloc, *real)
+        }
+        typ => typ,
+    }
+}
+
 fn compact_builtin_aliases(typ: ErrorType) -> ErrorType {
     use ErrorType::*;
     match typ {
@@ -2900,8 +3402,8 @@ fn to_diff<'b>(
     use ErrorType::*;
 
     let (type1, type2) = (
-        compact_builtin_aliases(type1),
-        compact_builtin_aliases(type2),
+        compact_builtin_aliases(expand_alias(alloc, type1)),
+        compact_builtin_aliases(expand_alias(alloc, type2)),
     );
 
     // TODO remove clone
@@ -2983,13 +3485,13 @@ fn to_diff<'b>(
             let left = report_text::apply(
                 alloc,
                 parens,
-                alloc.symbol_unqualified(symbol1),
+                diff_type_name(alloc, symbol1),
                 args_diff.left,
             );
             let right = report_text::apply(
                 alloc,
                 parens,
-                alloc.symbol_unqualified(symbol2),
+                diff_type_name(alloc, symbol2),
                 args_diff.right,
             );
 
@@ -3003,17 +3505,34 @@ fn to_diff<'b>(
         }
 
         (Alias(symbol1, args1, _, _), Alias(symbol2, args2, _, _)) if symbol1 == symbol2 => {
-            let args_diff = diff_args(alloc, Parens::InTypeParam, args1, args2);
+            let task_problem = match (args1.first(), args2.first()) {
+                (Some(ok1), Some(ok2)) if is_task_alias(alloc, symbol1) => {
+                    match to_diff(alloc, Parens::Unnecessary, ok1.clone(), ok2.clone()).status {
+                        Status::Similar => None,
+                        Status::Different(_) => {
+                            Some(Problem::TaskSuccessMismatch(ok1.clone(), ok2.clone()))
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            let mut args_diff = diff_args(alloc, Parens::InTypeParam, args1, args2);
+
+            if let Some(problem) = task_problem {
+                args_diff.status.merge(Status::Different(vec![problem]));
+            }
+
             let left = report_text::apply(
                 alloc,
                 parens,
-                alloc.symbol_unqualified(symbol1),
+                diff_type_name(alloc, symbol1),
                 args_diff.left,
             );
             let right = report_text::apply(
                 alloc,
                 parens,
-                alloc.symbol_unqualified(symbol2),
+                diff_type_name(alloc, symbol2),
                 args_diff.right,
             );
 
@@ -3043,6 +3562,36 @@ fn to_diff<'b>(
             }
         }
 
+        (Alias(symbol, args, _, _), other)
+            if is_task_alias(alloc, symbol) && task_succeeds_with(alloc, &args, &other) =>
+        {
+            let (left, left_able) = to_doc(alloc, Parens::InFn, type1);
+            let (right, right_able) = to_doc(alloc, Parens::InFn, type2);
+
+            Diff {
+                left,
+                right,
+                status: Status::Different(vec![Problem::TaskUsedAsValue(other)]),
+                left_able,
+                right_able,
+            }
+        }
+
+        (other, Alias(symbol, args, _, _))
+            if is_task_alias(alloc, symbol) && task_succeeds_with(alloc, &args, &other) =>
+        {
+            let (left, left_able) = to_doc(alloc, Parens::InFn, type1);
+            let (right, right_able) = to_doc(alloc, Parens::InFn, type2);
+
+            Diff {
+                left,
+                right,
+                status: Status::Different(vec![Problem::ValueUsedAsTask(other)]),
+                left_able,
+                right_able,
+            }
+        }
+
         (Alias(sym, _, _, AliasKind::Opaque), _) | (_, Alias(sym, _, _, AliasKind::Opaque))
             // Skip the hint for numbers; it's not as useful as saying "this type is not a number"
             if !OPAQUE_NUM_SYMBOLS.contains(&sym)
@@ -3078,6 +3627,10 @@ fn to_diff<'b>(
             diff_record(alloc, fields1, ext1, fields2, ext2)
         }
 
+        (Tuple(elems1, ext1), Tuple(elems2, ext2)) if elems1.len() == elems2.len() => {
+            diff_tuple(alloc, elems1, ext1, elems2, ext2)
+        }
+
         (TagUnion(tags1, ext1, pol), TagUnion(tags2, ext2, _)) => {
             diff_tag_union(alloc, pol, tags1, ext1, None, tags2, ext2, None)
         }
@@ -3137,6 +3690,12 @@ fn to_diff<'b>(
                 (a, b) if (is_int(&a) && is_float(&b)) || (is_float(&a) && is_int(&b)) => {
                     vec![Problem::IntFloat]
                 }
+                (Range(range), other) | (other, Range(range)) => {
+                    match literal_out_of_range(&range, &other) {
+                        Some(problem) => vec![problem],
+                        None => vec![],
+                    }
+                }
                 _ => vec![],
             };
 
@@ -3151,6 +3710,58 @@ fn to_diff<'b>(
     }
 }
 
+/// Whether `symbol` is the builtin `Task`, or a platform's own alias or opaque type named `Task`.
+fn is_task_alias(alloc: &RocDocAllocator<'_>, symbol: Symbol) -> bool {
+    symbol == Symbol::TASK_TASK || symbol.as_str(alloc.interns) == "Task"
+}
+
+/// Whether a `Task` with the type arguments `task_args` succeeds with a value of type `other`.
+fn task_succeeds_with<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    task_args: &[ErrorType],
+    other: &ErrorType,
+) -> bool {
+    let is_task = matches!(other, ErrorType::Alias(symbol, ..) if is_task_alias(alloc, *symbol));
+
+    match task_args.first() {
+        Some(ok) if !is_task => matches!(
+            to_diff(alloc, Parens::Unnecessary, ok.clone(), other.clone()).status,
+            Status::Similar
+        ),
+        _ => false,
+    }
+}
+
+fn int_lit_width(tipe: &ErrorType) -> Option<IntLitWidth> {
+    match tipe {
+        ErrorType::Type(symbol, _) | ErrorType::Alias(symbol, _, _, _) => {
+            IntLitWidth::from_symbol(*symbol)
+        }
+        _ => None,
+    }
+}
+
+/// If a number literal, whose possible types are `range`, was used as an integer type it doesn't
+/// fit in, finds the smallest integer type it does fit in; preferring one of the same signedness.
+fn literal_out_of_range(range: &[ErrorType], expected: &ErrorType) -> Option<Problem> {
+    let expected = int_lit_width(expected).filter(IntLitWidth::is_int)?;
+    let signedness = expected.signedness_and_width().0;
+
+    let fitting_ints = range
+        .iter()
+        .filter_map(int_lit_width)
+        .filter(IntLitWidth::is_int)
+        .collect::<Vec<_>>();
+
+    let fits_in = fitting_ints
+        .iter()
+        .find(|width| width.signedness_and_width().0 == signedness)
+        .or_else(|| fitting_ints.first())
+        .copied();
+
+    Some(Problem::NumLiteralOutOfRange(expected, fits_in))
+}
+
 fn diff_args<'b, I>(
     alloc: &'b RocDocAllocator<'b>,
     parens: Parens,
@@ -3393,6 +4004,53 @@ fn diff_record<'b>(
     }
 }
 
+fn diff_tuple<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    elems1: Vec<(usize, ErrorType)>,
+    ext1: TypeExt,
+    elems2: Vec<(usize, ErrorType)>,
+    ext2: TypeExt,
+) -> Diff<RocDocBuilder<'b>> {
+    let mut elems_diff: Diff<Vec<RocDocBuilder<'b>>> = Diff {
+        left: Vec::with_capacity(elems1.len()),
+        right: Vec::with_capacity(elems2.len()),
+        status: Status::Similar,
+        left_able: vec![],
+        right_able: vec![],
+    };
+
+    // Elements are positional, so rather than omitting the ones with the same type
+    // entirely, we render an ellipsis in their slot to keep the positions lined up.
+    for ((index1, t1), (index2, t2)) in elems1.into_iter().zip(elems2) {
+        if index1 == index2 && !should_show_diff(&t1, &t2) {
+            elems_diff.left.push(alloc.ellipsis());
+            elems_diff.right.push(alloc.ellipsis());
+        } else {
+            let diff = to_diff(alloc, Parens::Unnecessary, t1, t2);
+
+            elems_diff.left.push(diff.left);
+            elems_diff.right.push(diff.right);
+            elems_diff.status.merge(diff.status);
+            elems_diff.left_able.extend(diff.left_able);
+            elems_diff.right_able.extend(diff.right_able);
+        }
+    }
+
+    let ext_diff = record_ext_to_diff(alloc, ext1, ext2);
+
+    elems_diff.status.merge(ext_diff.status);
+    elems_diff.left_able.extend(ext_diff.left_able);
+    elems_diff.right_able.extend(ext_diff.right_able);
+
+    Diff {
+        left: report_text::tuple(alloc, elems_diff.left, ext_diff.left, 0),
+        right: report_text::tuple(alloc, elems_diff.right, ext_diff.right, 0),
+        status: elems_diff.status,
+        left_able: elems_diff.left_able,
+        right_able: elems_diff.right_able,
+    }
+}
+
 /// This is a helper for should_show_field_diff - see its doc comment for details.
 fn should_show_diff(t1: &ErrorType, t2: &ErrorType) -> bool {
     use ErrorType::*;
@@ -4155,6 +4813,14 @@ mod report_text {
                 })
                 .append(alloc.text(" }"))
                 .append(ext_doc)
+        } else if let Some(line) = one_line(
+            alloc,
+            ("{ ", " }"),
+            entries.iter().cloned().map(entry_to_doc),
+            fields_omitted,
+            &ext_doc,
+        ) {
+            line
         } else {
             let ending = if fields_omitted == 0 {
                 alloc.reflow("}")
@@ -4183,6 +4849,33 @@ mod report_text {
         }
     }
 
+    /// Lays out the entries of a record, tuple or tag union on a single line, as long as the
+    /// type options give a width that line fits in.
+    fn one_line<'b>(
+        alloc: &'b RocDocAllocator<'b>,
+        (open, close): (&'static str, &'static str),
+        entries: impl Iterator<Item = RocDocBuilder<'b>>,
+        entries_omitted: usize,
+        ext_doc: &RocDocBuilder<'b>,
+    ) -> Option<RocDocBuilder<'b>> {
+        let max_width = alloc.type_options.max_width?;
+
+        let line = alloc
+            .text(open)
+            .append(alloc.intersperse(entries, alloc.text(", ")))
+            .append(if entries_omitted == 0 {
+                alloc.nil()
+            } else {
+                alloc.text(", ").append(alloc.ellipsis())
+            })
+            .append(alloc.text(close))
+            .append(ext_doc.clone());
+
+        let rendered = line.1.pretty(max_width).to_string();
+
+        (!rendered.contains('\n') && rendered.chars().count() <= max_width).then_some(line)
+    }
+
     pub fn tuple<'b>(
         alloc: &'b RocDocAllocator<'b>,
         entries: Vec<RocDocBuilder<'b>>,
@@ -4216,6 +4909,14 @@ mod report_text {
                 })
                 .append(alloc.text(")"))
                 .append(ext_doc)
+        } else if let Some(line) = one_line(
+            alloc,
+            ("(", ")"),
+            entries.iter().cloned(),
+            fields_omitted,
+            &ext_doc,
+        ) {
+            line
         } else {
             let ending = if fields_omitted == 0 {
                 alloc.reflow(")")
@@ -4357,6 +5058,14 @@ mod report_text {
                 })
                 .append(alloc.text("]"))
                 .append(ext_doc)
+        } else if let Some(line) = one_line(
+            alloc,
+            ("[", "]"),
+            entries.iter().cloned().map(entry_to_doc),
+            tags_omitted,
+            &ext_doc,
+        ) {
+            line
         } else {
             let ending = if tags_omitted == 0 {
                 alloc.reflow("]")
@@ -4752,6 +5461,36 @@ fn type_problem_to_pretty<'b>(
             }
         }
 
+        (NumLiteralOutOfRange(expected, fits_in), _) => {
+            // Unsigned fits are preferred, so a signed one here means the number is negative.
+            let is_negative = !expected.signedness_and_width().0.is_signed()
+                && fits_in.is_some_and(|width| width.signedness_and_width().0.is_signed());
+
+            let mut tip = vec![
+                alloc.reflow(if is_negative {
+                    "This number is negative, so it doesn't fit in "
+                } else {
+                    "This number doesn't fit in "
+                }),
+                alloc.symbol_unqualified(expected.symbol()),
+                alloc.reflow(", whose values range from "),
+                text!(alloc, "{}", expected.min_value()),
+                alloc.reflow(" to "),
+                text!(alloc, "{}", expected.max_value()),
+                alloc.reflow("."),
+            ];
+
+            if let Some(fits_in) = fits_in {
+                tip.extend([
+                    alloc.reflow(" The smallest type it fits in is "),
+                    alloc.symbol_unqualified(fits_in.symbol()),
+                    alloc.reflow("."),
+                ]);
+            }
+
+            alloc.tip().append(alloc.concat(tip))
+        }
+
         (IntFloat, _) => {
             alloc.tip().append(alloc.concat(
                 [
@@ -4863,6 +5602,41 @@ fn type_problem_to_pretty<'b>(
             alloc.tag_name(tag),
             alloc.reflow("?"),
         ])),
+        (TaskSuccessMismatch(actual, expected), _) => alloc.tip().append(alloc.concat([
+            alloc.reflow("This "),
+            alloc.symbol_unqualified(Symbol::TASK_TASK),
+            alloc.reflow(" succeeds with "),
+            to_doc(alloc, Parens::InFn, actual).0,
+            alloc.reflow(", but you are treating it as if it succeeds with "),
+            to_doc(alloc, Parens::InFn, expected).0,
+            alloc.reflow(". Did you forget a "),
+            alloc.symbol_qualified(Symbol::TASK_AWAIT),
+            alloc.reflow(" or "),
+            alloc.symbol_qualified(Symbol::TASK_MAP),
+            alloc.reflow("?"),
+        ])),
+        (TaskUsedAsValue(ok), _) => alloc.tip().append(alloc.concat([
+            alloc.reflow("This is a "),
+            alloc.symbol_unqualified(Symbol::TASK_TASK),
+            alloc.reflow(" that succeeds with "),
+            to_doc(alloc, Parens::InFn, ok).0,
+            alloc.reflow(", but it's used as if it were the "),
+            alloc.symbol_unqualified(Symbol::TASK_TASK),
+            alloc.reflow("'s result. Did you forget a "),
+            alloc.symbol_qualified(Symbol::TASK_AWAIT),
+            alloc.reflow(", or a "),
+            alloc.keyword("!"),
+            alloc.reflow(" after it?"),
+        ])),
+        (ValueUsedAsTask(ok), _) => alloc.tip().append(alloc.concat([
+            alloc.reflow("A "),
+            alloc.symbol_unqualified(Symbol::TASK_TASK),
+            alloc.reflow(" is expected here, but this is the "),
+            to_doc(alloc, Parens::InFn, ok).0,
+            alloc.reflow(" it would succeed with. Did you mean to wrap it with "),
+            alloc.symbol_qualified(Symbol::TASK_OK),
+            alloc.reflow("?"),
+        ])),
     }
 }
 
@@ -5043,23 +5817,72 @@ fn exhaustive_problem<'a>(
             overall_region,
             branch_region,
             index,
+            covered_by,
         } => {
-            let doc = alloc.stack([
-                alloc.concat([
-                    alloc.reflow("The "),
-                    alloc.string(index.ordinal()),
-                    alloc.reflow(" pattern is redundant:"),
-                ]),
+            let intro = alloc.concat([
+                alloc.reflow("The "),
+                alloc.string(index.ordinal()),
+                alloc.reflow(" pattern is redundant:"),
+            ]);
+            let snippet = || {
                 alloc.region_with_subregion(
                     lines.convert_region(overall_region),
                     lines.convert_region(branch_region),
                     severity,
-                ),
-                alloc.reflow(
-                    "Any value of this shape will be handled by \
-                a previous pattern, so this one should be removed.",
-                ),
-            ]);
+                )
+            };
+
+            let doc = match covered_by {
+                Some(CoveredBy {
+                    index: covering_index,
+                    region: covering_region,
+                    irrefutable: true,
+                }) => {
+                    // A branch after a catch-all can never be reached, so show both of them.
+                    let snippet = alloc
+                        .region_with_secondary(
+                            lines.convert_region(branch_region),
+                            "never reached",
+                            lines.convert_region(covering_region),
+                            "matches everything",
+                            severity,
+                        )
+                        .unwrap_or_else(snippet);
+
+                    alloc.stack([
+                        intro,
+                        snippet,
+                        alloc.concat([
+                            alloc.reflow("The "),
+                            alloc.string(covering_index.ordinal()),
+                            alloc.reflow(
+                                " pattern matches any value, so no branch after it can ever be \
+                                reached. Either move that pattern to the end, or remove this one.",
+                            ),
+                        ]),
+                    ])
+                }
+                Some(CoveredBy {
+                    index: covering_index,
+                    ..
+                }) => alloc.stack([
+                    intro,
+                    snippet(),
+                    alloc.concat([
+                        alloc.reflow("Any value of this shape will be handled by the "),
+                        alloc.string(covering_index.ordinal()),
+                        alloc.reflow(" pattern, so this one should be removed."),
+                    ]),
+                ]),
+                None => alloc.stack([
+                    intro,
+                    snippet(),
+                    alloc.reflow(
+                        "Any value of this shape will be handled by the patterns \
+                    before it, so this one should be removed.",
+                    ),
+                ]),
+            };
 
             Report {
                 filename,
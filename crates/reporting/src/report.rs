@@ -1,8 +1,10 @@
+use roc_collections::VecMap;
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
+use roc_parse::parser::{FileError, SourceError, SyntaxError};
 use roc_problem::Severity;
-use roc_region::all::LineColumnRegion;
+use roc_region::all::{LineColumnRegion, LineInfo};
 use std::path::{Path, PathBuf};
 use std::{fmt, io};
 use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder, Render, RenderAnnotated};
@@ -15,6 +17,7 @@ use roc_packaging::https::Problem;
 pub use crate::error::canonicalize::can_problem;
 pub use crate::error::parse::parse_problem;
 pub use crate::error::r#type::type_problem;
+use crate::error::r#type::TypePrintOptions;
 
 #[cfg(windows)]
 const CYCLE_ELEMENTS: [&str; 4] = ["+-----+", "|     ", "|     |", "+-<---+"];
@@ -29,6 +32,11 @@ const CYCLE_END: &str = CYCLE_ELEMENTS[3];
 
 const GUTTER_BAR: &str = "│";
 const ERROR_UNDERLINE: &str = "^";
+const SECONDARY_UNDERLINE: &str = "-";
+
+/// How many lines apart two regions can be and still get shown in one snippet
+/// by [`RocDocAllocator::region_with_secondary`].
+const MAX_SECONDARY_DISTANCE: u32 = 6;
 
 /// The number of monospace spaces the gutter bar takes up.
 /// (This is not necessarily the same as GUTTER_BAR.len()!)
@@ -155,12 +163,23 @@ impl<'b> Report<'b> {
         buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
         palette: &'b Palette,
+    ) {
+        self.render_color_terminal_with_width(70, buf, alloc, palette)
+    }
+
+    /// Like `render_color_terminal`, but wrapping lines at the given width.
+    pub fn render_color_terminal_with_width(
+        self,
+        width: usize,
+        buf: &mut String,
+        alloc: &'b RocDocAllocator<'b>,
+        palette: &'b Palette,
     ) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
             .1
-            .render_raw(70, &mut ColorWrite::new(palette, buf))
+            .render_raw(width, &mut ColorWrite::new(palette, buf))
             .expect(err_msg);
     }
 
@@ -348,12 +367,53 @@ pub fn strip_colors(str: &str) -> String {
         .replace(ANSI_STYLE_CODES.reset, "")
 }
 
+/// Hints a platform attaches to the types and functions it exposes, in the `hints`
+/// section of its header. Type error reports involving one of these show its hint.
+#[derive(Debug, Clone, Default)]
+pub struct PlatformHints {
+    /// Keyed by a type name like `Task`, or a qualified name like `Task.await`
+    hints: VecMap<Box<str>, Box<str>>,
+}
+
+impl PlatformHints {
+    pub fn insert(&mut self, name: &str, hint: &str) {
+        self.hints.insert(name.into(), hint.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    /// The name the platform gave the hint for this symbol, along with the hint itself
+    pub fn get(&self, interns: &Interns, symbol: Symbol) -> Option<(&str, &str)> {
+        if self.hints.is_empty() {
+            return None;
+        }
+
+        let module_name = interns.module_name(symbol.module_id()).as_str();
+        let ident = symbol.as_str(interns);
+
+        let is_type = ident.starts_with(|c: char| c.is_uppercase());
+
+        self.hints.iter().find_map(|(name, hint)| {
+            let matches = match name.split_once('.') {
+                Some((module, member)) => module == module_name && member == ident,
+                None => is_type && name.as_ref() == ident,
+            };
+
+            matches.then_some((name.as_ref(), hint.as_ref()))
+        })
+    }
+}
+
 // define custom allocator struct so we can `impl RocDocAllocator` custom helpers
 pub struct RocDocAllocator<'a> {
     upstream: BoxAllocator,
     pub src_lines: &'a [&'a str],
     pub home: ModuleId,
     pub interns: &'a Interns,
+    pub platform_hints: Option<&'a PlatformHints>,
+    pub type_options: TypePrintOptions,
 }
 
 pub type RocDocBuilder<'b> = DocBuilder<'b, RocDocAllocator<'b>, Annotation>;
@@ -390,9 +450,23 @@ impl<'a> RocDocAllocator<'a> {
             home,
             src_lines,
             interns,
+            platform_hints: None,
+            type_options: TypePrintOptions::from_env(),
         }
     }
 
+    pub fn with_platform_hints(mut self, platform_hints: &'a PlatformHints) -> Self {
+        self.platform_hints = Some(platform_hints);
+
+        self
+    }
+
+    pub fn with_type_options(mut self, type_options: TypePrintOptions) -> Self {
+        self.type_options = type_options;
+
+        self
+    }
+
     /// vertical concatenation. Adds a newline between elements
     pub fn vcat<A, I>(&'a self, docs: I) -> DocBuilder<'a, Self, A>
     where
@@ -553,7 +627,7 @@ impl<'a> RocDocAllocator<'a> {
         &'a self,
         content: roc_module::called_via::BinOp,
     ) -> DocBuilder<'a, Self, Annotation> {
-        self.text(content.to_string()).annotate(Annotation::BinOp)
+        self.text(content.as_str()).annotate(Annotation::BinOp)
     }
 
     pub fn unop(
@@ -837,6 +911,94 @@ impl<'a> RocDocAllocator<'a> {
         result
     }
 
+    /// Show the `primary` region along with an earlier `secondary` region that it relates to,
+    /// in one snippet where each is underlined and labeled. The primary region is underlined
+    /// like any other problem; the secondary one is underlined with dashes.
+    ///
+    /// This only works when both regions fit on their own single lines and are close enough
+    /// together to not make a huge snippet. Otherwise, this returns None and the caller should
+    /// show the regions separately.
+    pub fn region_with_secondary(
+        &'a self,
+        primary: LineColumnRegion,
+        primary_label: &'a str,
+        secondary: LineColumnRegion,
+        secondary_label: &'a str,
+        severity: Severity,
+    ) -> Option<DocBuilder<'a, Self, Annotation>> {
+        let first_line = secondary.start().line;
+        let last_line = primary.start().line;
+
+        if primary.start().line != primary.end().line
+            || secondary.start().line != secondary.end().line
+            || first_line >= last_line
+            || last_line - first_line > MAX_SECONDARY_DISTANCE
+        {
+            return None;
+        }
+
+        let annotation = match severity {
+            Severity::RuntimeError | Severity::Fatal => Annotation::Error,
+            Severity::Warning => Annotation::Warning,
+        };
+
+        let max_line_number_length = (last_line + 1).to_string().len();
+        let indent = 2;
+
+        let underline = |region: LineColumnRegion, underline: &str, label: &'a str| {
+            let width = (region.end().column - region.start().column).max(1) as usize;
+
+            self.text(" ".repeat(region.start().column as usize))
+                .indent(indent)
+                .append(self.text(underline.repeat(width)).append(" ").append(label))
+        };
+
+        let mut result = self.nil();
+
+        for i in first_line..=last_line {
+            let line_number = (i + 1).to_string();
+
+            // filter out any escape characters for the current line that could mess up the output.
+            let line: String = self
+                .src_lines
+                .get(i as usize)
+                .unwrap_or(&"")
+                .chars()
+                .filter(|&c| !c.is_ascii_control() || c == '\t')
+                .collect::<String>();
+
+            result = result
+                .append(self.text(" ".repeat(max_line_number_length - line_number.len())))
+                .append(self.text(line_number).annotate(Annotation::LineNumber))
+                .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar));
+
+            if !line.trim().is_empty() {
+                result = result.append(
+                    self.text(line)
+                        .annotate(Annotation::CodeBlock)
+                        .indent(indent),
+                );
+            }
+
+            result = result.append(self.line());
+
+            if i == first_line {
+                result = result
+                    .append(self.text(" ".repeat(max_line_number_length)))
+                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar))
+                    .append(underline(secondary, SECONDARY_UNDERLINE, secondary_label))
+                    .append(self.line());
+            }
+        }
+
+        // Omit the gutter bar, since no further line numbers get printed after this.
+        result = result
+            .append(self.text(" ".repeat(max_line_number_length + GUTTER_BAR_WIDTH)))
+            .append(underline(primary, ERROR_UNDERLINE, primary_label).annotate(annotation));
+
+        Some(result)
+    }
+
     pub fn region(
         &'a self,
         region: LineColumnRegion,
@@ -1713,6 +1875,58 @@ pub fn to_file_problem_report_string(
     buf
 }
 
+/// The warning `roc format` shows for a top-level def it left as it was, because the def has a
+/// syntax error.
+pub fn to_unformatted_def_report_string(
+    filename: PathBuf,
+    src: &str,
+    problem: SyntaxError,
+    has_color: bool,
+) -> String {
+    let src_lines: Vec<&str> = src.lines().collect();
+    let mut module_ids = ModuleIds::default();
+    let module_id = module_ids.get_or_insert(&"find module name somehow?".into());
+    let interns = Interns::default();
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+    let lines = LineInfo::new(src);
+
+    let mut buf = String::new();
+    let palette = if has_color {
+        DEFAULT_PALETTE
+    } else {
+        NO_COLOR_PALETTE
+    };
+
+    let syntax_report = parse_problem(
+        &alloc,
+        &lines,
+        filename.clone(),
+        0,
+        FileError {
+            problem: SourceError {
+                problem,
+                bytes: src.as_bytes(),
+            },
+            filename: filename.clone(),
+        },
+    );
+    let report = Report {
+        filename,
+        doc: alloc.stack([
+            alloc.reflow(
+                "I formatted the rest of this file, but I left a def as it was, because it has a syntax error:",
+            ),
+            syntax_report.doc,
+        ]),
+        title: "UNFORMATTED DEF".to_string(),
+        severity: Severity::Warning,
+    };
+
+    report.render_color_terminal(&mut buf, &alloc, &palette);
+
+    buf
+}
+
 pub fn to_file_problem_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     filename: PathBuf,
@@ -0,0 +1,242 @@
+use core::ffi::c_void;
+use std::alloc::{self, Layout};
+
+use crate::RocStr;
+
+/// The functions a host must provide for the Roc builtins to call, with reasonable defaults.
+///
+/// Rather than implementing this trait directly, most hosts should call
+/// [`declare_roc_externs!`](crate::declare_roc_externs), which exports each of these as the
+/// corresponding `roc_*` symbol. To customize some of them (e.g. to use an arena allocator, or
+/// to report panics differently), implement this trait on a type of your own and override just
+/// those methods:
+///
+/// ```ignore
+/// struct MyHost;
+///
+/// impl roc_std::RocHost for MyHost {
+///     fn panic(msg: &roc_std::RocStr, _tag_id: u32) {
+///         log::error!("Roc crashed: {msg}");
+///         std::process::exit(1);
+///     }
+/// }
+///
+/// roc_std::declare_roc_externs!(MyHost);
+/// ```
+///
+/// If you override any of `alloc`, `realloc`, or `dealloc`, override all three - the default
+/// implementations store bookkeeping in front of each allocation, and expect to be the ones
+/// who made it.
+pub trait RocHost {
+    /// # Safety
+    ///
+    /// `alignment` must be a power of two.
+    unsafe fn alloc(size: usize, alignment: u32) -> *mut c_void {
+        unsafe { rust_alloc(size, alignment) }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this host's `alloc` or `realloc`, and not freed since.
+    unsafe fn realloc(
+        ptr: *mut c_void,
+        new_size: usize,
+        _old_size: usize,
+        _alignment: u32,
+    ) -> *mut c_void {
+        unsafe { rust_realloc(ptr, new_size) }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this host's `alloc` or `realloc`, and not freed since.
+    unsafe fn dealloc(ptr: *mut c_void, _alignment: u32) {
+        unsafe { rust_dealloc(ptr) }
+    }
+
+    /// Called when Roc code crashes. A `tag_id` of 0 means the crash came from the
+    /// Roc standard library, and 1 means it came from the application (e.g. `crash`).
+    ///
+    /// Roc cannot continue after a crash, so this must not return.
+    fn panic(msg: &RocStr, tag_id: u32) {
+        match tag_id {
+            0 => eprintln!("Roc standard library hit a panic: {msg}"),
+            _ => eprintln!("Application hit a panic: {msg}"),
+        }
+
+        std::process::exit(1);
+    }
+
+    /// Called for each `dbg` in Roc code.
+    fn dbg(loc: &RocStr, msg: &RocStr, src: &RocStr) {
+        eprintln!("[{loc}] {src} = {msg}");
+    }
+
+    /// # Safety
+    ///
+    /// `dst` must be valid for writes of `n` bytes.
+    unsafe fn memset(dst: *mut c_void, c: i32, n: usize) -> *mut c_void {
+        unsafe { dst.cast::<u8>().write_bytes(c as u8, n) };
+
+        dst
+    }
+
+    /// # Safety
+    ///
+    /// `src` must be valid for reads of `n` bytes, `dst` must be valid for writes
+    /// of `n` bytes, and the two must not overlap.
+    unsafe fn memcpy(dst: *mut c_void, src: *const c_void, n: usize) -> *mut c_void {
+        unsafe { core::ptr::copy_nonoverlapping(src.cast::<u8>(), dst.cast::<u8>(), n) };
+
+        dst
+    }
+}
+
+/// A [`RocHost`] which uses all the defaults: Rust's global allocator, and printing
+/// panics and `dbg` output to stderr.
+pub struct DefaultRocHost;
+
+impl RocHost for DefaultRocHost {}
+
+/// `roc_dealloc` does not say how big the allocation was, and may be passed a different
+/// alignment than `roc_alloc` was, so each allocation gets a header recording both.
+/// The header is as big as the alignment, so the bytes we hand out stay aligned.
+const HEADER_ALIGN: usize = 2 * core::mem::size_of::<usize>();
+
+/// Returns the (total size, alignment) stored in the header in front of `ptr`.
+unsafe fn read_header(ptr: *mut c_void) -> (usize, usize) {
+    let header = ptr.cast::<usize>();
+
+    unsafe { (header.sub(1).read(), header.sub(2).read()) }
+}
+
+unsafe fn write_header(ptr: *mut u8, total_size: usize, align: usize) -> *mut c_void {
+    let header = ptr.cast::<usize>();
+
+    unsafe {
+        header.sub(1).write(total_size);
+        header.sub(2).write(align);
+    }
+
+    ptr.cast()
+}
+
+unsafe fn rust_alloc(size: usize, alignment: u32) -> *mut c_void {
+    let align = (alignment as usize).max(HEADER_ALIGN);
+    let total_size = align + size;
+
+    let layout = match Layout::from_size_align(total_size, align) {
+        Ok(layout) => layout,
+        Err(_) => return core::ptr::null_mut(),
+    };
+
+    let base = unsafe { alloc::alloc(layout) };
+
+    if base.is_null() {
+        return core::ptr::null_mut();
+    }
+
+    unsafe { write_header(base.add(align), total_size, align) }
+}
+
+unsafe fn rust_realloc(ptr: *mut c_void, new_size: usize) -> *mut c_void {
+    let (total_size, align) = unsafe { read_header(ptr) };
+    let base = unsafe { ptr.cast::<u8>().sub(align) };
+    let new_total_size = align + new_size;
+
+    let new_base = unsafe {
+        alloc::realloc(
+            base,
+            Layout::from_size_align_unchecked(total_size, align),
+            new_total_size,
+        )
+    };
+
+    if new_base.is_null() {
+        return core::ptr::null_mut();
+    }
+
+    unsafe { write_header(new_base.add(align), new_total_size, align) }
+}
+
+unsafe fn rust_dealloc(ptr: *mut c_void) {
+    let (total_size, align) = unsafe { read_header(ptr) };
+
+    unsafe {
+        alloc::dealloc(
+            ptr.cast::<u8>().sub(align),
+            Layout::from_size_align_unchecked(total_size, align),
+        )
+    }
+}
+
+/// Declares the `roc_alloc`, `roc_realloc`, `roc_dealloc`, `roc_panic`, `roc_dbg`,
+/// `roc_memset`, and `roc_memcpy` functions a Roc host needs to provide.
+///
+/// With no arguments, these use [`DefaultRocHost`](crate::DefaultRocHost). Pass a type
+/// implementing [`RocHost`](crate::RocHost) to customize them.
+///
+/// Platform-specific functions (e.g. `roc_getppid` and `roc_mmap` for `roc dev`) still need
+/// to be declared separately.
+#[macro_export]
+macro_rules! declare_roc_externs {
+    () => {
+        $crate::declare_roc_externs!($crate::DefaultRocHost);
+    };
+    ($host:ty) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_alloc(
+            size: usize,
+            alignment: u32,
+        ) -> *mut ::core::ffi::c_void {
+            <$host as $crate::RocHost>::alloc(size, alignment)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_realloc(
+            ptr: *mut ::core::ffi::c_void,
+            new_size: usize,
+            old_size: usize,
+            alignment: u32,
+        ) -> *mut ::core::ffi::c_void {
+            <$host as $crate::RocHost>::realloc(ptr, new_size, old_size, alignment)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_dealloc(ptr: *mut ::core::ffi::c_void, alignment: u32) {
+            <$host as $crate::RocHost>::dealloc(ptr, alignment)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_panic(msg: *mut $crate::RocStr, tag_id: u32) {
+            <$host as $crate::RocHost>::panic(&*msg, tag_id)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_dbg(
+            loc: *mut $crate::RocStr,
+            msg: *mut $crate::RocStr,
+            src: *mut $crate::RocStr,
+        ) {
+            <$host as $crate::RocHost>::dbg(&*loc, &*msg, &*src)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_memset(
+            dst: *mut ::core::ffi::c_void,
+            c: i32,
+            n: usize,
+        ) -> *mut ::core::ffi::c_void {
+            <$host as $crate::RocHost>::memset(dst, c, n)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn roc_memcpy(
+            dst: *mut ::core::ffi::c_void,
+            src: *const ::core::ffi::c_void,
+            n: usize,
+        ) -> *mut ::core::ffi::c_void {
+            <$host as $crate::RocHost>::memcpy(dst, src, n)
+        }
+    };
+}
@@ -1,4 +1,8 @@
 //! Provides Rust representations of Roc data structures.
+//!
+//! By default, refcounts are updated non-atomically, matching the code the Roc compiler emits.
+//! To share Roc values across threads, enable the `atomic-refcount` feature *and* build the
+//! app with `roc build --atomic-refcount`; mixing the two modes is a data race.
 // #![cfg_attr(not(feature = "std"), no_std)]
 #![crate_type = "lib"]
 
@@ -8,15 +12,17 @@ use core::ffi::c_void;
 use core::fmt::{self, Debug};
 use core::hash::{Hash, Hasher};
 use core::mem::{ManuallyDrop, MaybeUninit};
-use core::ops::Drop;
+use core::ops::{Add, Div, Drop, Mul, Neg, Sub};
 use core::str;
 use std::convert::Infallible;
 
+mod host;
 mod roc_box;
 mod roc_list;
 mod roc_str;
 mod storage;
 
+pub use host::{DefaultRocHost, RocHost};
 pub use roc_box::RocBox;
 pub use roc_list::{RocList, SendSafeRocList};
 pub use roc_str::{InteriorNulError, RocStr, SendSafeRocStr};
@@ -187,6 +193,26 @@ impl<T, E> RocResult<T, E> {
         matches!(self.tag, RocResultTag::RocErr)
     }
 
+    /// Borrow the payload as a Rust `Result`, without taking ownership.
+    pub fn as_ref(&self) -> Result<&T, &E> {
+        match self.as_result_of_refs() {
+            Ok(payload) => Ok(payload),
+            Err(payload) => Err(payload),
+        }
+    }
+
+    /// Mutably borrow the payload as a Rust `Result`, without taking ownership.
+    pub fn as_mut(&mut self) -> Result<&mut T, &mut E> {
+        use RocResultTag::*;
+
+        unsafe {
+            match self.tag {
+                RocOk => Ok(&mut self.payload.ok),
+                RocErr => Err(&mut self.payload.err),
+            }
+        }
+    }
+
     fn into_payload(self) -> RocResultPayload<T, E> {
         let mut value = MaybeUninit::uninit();
 
@@ -289,10 +315,26 @@ impl<T, E> Drop for RocResult<T, E> {
     }
 }
 
+/// Roc's `Dec` type: a fixed-point decimal with 18 digits after the decimal point,
+/// stored as an i128 which is 10^18 times the number it represents.
+///
+/// The arithmetic operators follow the semantics of the Roc builtins (`dec.zig`):
+/// results are truncated toward zero, and overflow or division by zero panics.
+/// Use the `checked_*` methods to handle those cases instead.
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(C, align(16))]
 pub struct RocDec([u8; 16]);
 
+/// The error returned when parsing a `RocDec` from a string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseRocDecError;
+
+impl fmt::Display for ParseRocDecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid Dec literal")
+    }
+}
+
 impl Debug for RocDec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("RocDec")
@@ -425,9 +467,15 @@ impl RocDec {
         // Self::DECIMAL_PLACES, this assert should remind you to change that format string as well.
         static_assertions::const_assert!(RocDec::DECIMAL_PLACES + 1 == 19);
 
+        // The sign goes first, so that the zero padding below goes after it. Otherwise numbers
+        // between -1 and 0 would be written like `-.5`.
+        if self.as_i128() < 0 {
+            string.push('-');
+        }
+
         // By using the :019 format, we're guaranteeing that numbers less than 1, say 0.01234
         // get their leading zeros placed in bytes for us. i.e. `string = b"0012340000000000000"`
-        write!(string, "{:019}", self.as_i128()).unwrap();
+        write!(string, "{:019}", self.as_i128().unsigned_abs()).unwrap();
 
         let decimal_location = string.len() - Self::DECIMAL_PLACES;
         // skip trailing zeros
@@ -463,6 +511,181 @@ impl RocDec {
     pub fn to_str(&self) -> RocStr {
         RocStr::from(self.to_str_helper(&mut ArrayString::new()))
     }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_add(other.as_i128()).map(Self::new)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.as_i128().checked_sub(other.as_i128()).map(Self::new)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.as_i128(), other.as_i128());
+
+        // Like the builtins, only allow i128::MIN when multiplying by 0 or 1.
+        if a == i128::MIN || b == i128::MIN {
+            return match (a, b) {
+                (0, _) | (_, 0) => Some(Self::new(0)),
+                (_, Self::ONE_POINT_ZERO) => Some(self),
+                (Self::ONE_POINT_ZERO, _) => Some(other),
+                _ => None,
+            };
+        }
+
+        let product = mul_u128(a.unsigned_abs(), b.unsigned_abs());
+        let answer = div_u256_by_u128(product, Self::ONE_POINT_ZERO as u128)?;
+
+        Self::with_sign(answer, (a < 0) != (b < 0))
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        let (numerator, denominator) = (self.as_i128(), other.as_i128());
+
+        if denominator == 0 {
+            return None;
+        }
+
+        if numerator == 0 {
+            return Some(Self::new(0));
+        }
+
+        // Like the builtins, only allow i128::MIN when dividing by 1 or dividing 1 by it.
+        if numerator == i128::MIN {
+            return (denominator == Self::ONE_POINT_ZERO).then_some(self);
+        }
+
+        if denominator == i128::MIN {
+            return (numerator == Self::ONE_POINT_ZERO).then_some(other);
+        }
+
+        let scaled = mul_u128(numerator.unsigned_abs(), Self::ONE_POINT_ZERO as u128);
+        let answer = div_u256_by_u128(scaled, denominator.unsigned_abs())?;
+
+        Self::with_sign(answer, (numerator < 0) != (denominator < 0))
+    }
+
+    fn with_sign(magnitude: u128, is_negative: bool) -> Option<Self> {
+        let magnitude = i128::try_from(magnitude).ok()?;
+
+        Some(Self::new(if is_negative { -magnitude } else { magnitude }))
+    }
+}
+
+/// Multiply two u128s into a u256, returned as (high bits, low bits).
+fn mul_u128(a: u128, b: u128) -> (u128, u128) {
+    const LOWER_MASK: u128 = u64::MAX as u128;
+
+    let (a_hi, a_lo) = (a >> 64, a & LOWER_MASK);
+    let (b_hi, b_lo) = (b >> 64, b & LOWER_MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let middle = (lo_lo >> 64) + (hi_lo & LOWER_MASK) + (lo_hi & LOWER_MASK);
+
+    let lo = (lo_lo & LOWER_MASK) | (middle << 64);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (middle >> 64);
+
+    (hi, lo)
+}
+
+/// Divide a u256 (given as (high bits, low bits)) by a u128, truncating.
+/// Returns `None` if the quotient does not fit in a u128.
+fn div_u256_by_u128((hi, lo): (u128, u128), divisor: u128) -> Option<u128> {
+    if hi >= divisor {
+        return None;
+    }
+
+    // Shift-and-subtract long division. Since `hi < divisor`, the quotient fits in 128 bits.
+    let mut remainder = hi;
+    let mut quotient = 0;
+
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+
+    Some(quotient)
+}
+
+impl Add for RocDec {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other)
+            .expect("Decimal addition overflowed!")
+    }
+}
+
+impl Sub for RocDec {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other)
+            .expect("Decimal subtraction overflowed!")
+    }
+}
+
+impl Mul for RocDec {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other)
+            .expect("Decimal multiplication overflowed!")
+    }
+}
+
+impl Div for RocDec {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        if other.as_i128() == 0 {
+            panic!("Decimal division by 0!");
+        }
+
+        self.checked_div(other).expect("Decimal division overflow!")
+    }
+}
+
+impl Neg for RocDec {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(
+            self.as_i128()
+                .checked_neg()
+                .expect("Decimal negation overflowed!"),
+        )
+    }
+}
+
+impl PartialOrd for RocDec {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RocDec {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_i128().cmp(&other.as_i128())
+    }
+}
+
+impl str::FromStr for RocDec {
+    type Err = ParseRocDecError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        RocDec::from_str(value).ok_or(ParseRocDecError)
+    }
 }
 
 impl From<i32> for RocDec {
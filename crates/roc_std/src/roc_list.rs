@@ -14,7 +14,11 @@ use core::{
 };
 use std::{cmp::max, ops::Range};
 
-use crate::{roc_alloc, roc_dealloc, roc_realloc, storage::Storage, RocRefcounted};
+use crate::{
+    roc_alloc, roc_dealloc, roc_realloc,
+    storage::{decrement_refcount, increment_refcount, Storage},
+    RocRefcounted,
+};
 
 #[cfg(feature = "serde")]
 use core::marker::PhantomData;
@@ -268,8 +272,12 @@ where
         self.push(value)
     }
 
+    /// Appends an element to the end of the list.
+    ///
+    /// If this list is not unique, or does not have enough capacity, this will
+    /// reallocate; the other references to the original list will be unaffected.
     pub fn push(&mut self, value: T) {
-        if self.capacity() <= self.len() {
+        if self.capacity() <= self.len() || !self.is_unique() || self.is_seamless_slice() {
             // reserve space for (at least!) one more element
             self.reserve(1);
         }
@@ -321,11 +329,7 @@ where
 
         let new_len = self.len() + slice.len();
         let non_null_elements = if let Some((elements, storage)) = self.elements_and_storage() {
-            // Decrement the list's refence count.
-            let mut copy = storage.get();
-            let is_unique = copy.decrease();
-
-            if is_unique {
+            if storage.get().is_unique() {
                 // If we have enough capacity, we can add to the existing elements in-place.
                 if self.capacity() >= new_len {
                     elements
@@ -348,19 +352,18 @@ where
                     }))
                 }
             } else {
-                if !copy.is_readonly() {
-                    // Write the decremented reference count back.
-                    storage.set(copy);
-                }
-
                 // Allocate new memory.
-                self.capacity_or_ref_ptr = slice.len();
-                let new_elements = Self::elems_with_capacity(slice.len());
+                let new_elements = Self::elems_with_capacity(new_len);
 
                 // Copy the old elements to the new allocation.
                 unsafe {
                     copy_nonoverlapping(elements.as_ptr(), new_elements.as_ptr(), self.len());
                 }
+
+                unsafe { self.release_copied_allocation(new_elements) };
+
+                self.capacity_or_ref_ptr = new_len;
+
                 // Clear the seamless slice bit since we now have clear ownership.
                 self.length = self.len();
 
@@ -453,6 +456,8 @@ where
 
                         if new_alloc == old_alloc {
                             // We successfully reallocated in-place; we're done!
+                            self.capacity_or_ref_ptr = new_len;
+
                             return;
                         } else {
                             // We got back a different allocation; copy the existing elements
@@ -479,27 +484,7 @@ where
                         copy_nonoverlapping(old_elements_ptr, new_elems.as_ptr(), self.len());
                     }
 
-                    // Decrease the current allocation's reference count.
-                    let mut new_storage = storage.get();
-
-                    if !new_storage.is_readonly() {
-                        let needs_dealloc = new_storage.decrease();
-
-                        if needs_dealloc {
-                            // Unlike in Drop, do *not* decrement the refcounts of all the elements!
-                            // The new allocation is referencing them, so instead of incrementing them all
-                            // all just to decrement them again here, we neither increment nor decrement them.
-                            unsafe {
-                                roc_dealloc(
-                                    self.ptr_to_allocation(),
-                                    Self::alloc_alignment() as u32,
-                                );
-                            }
-                        } else {
-                            // Write the storage back.
-                            storage.set(new_storage);
-                        }
-                    }
+                    unsafe { self.release_copied_allocation(new_elems) };
                 }
             }
             None => {
@@ -517,6 +502,144 @@ where
         });
     }
 
+    /// Give up this list's reference to its current allocation, after its elements
+    /// have been copied into `new_elems`.
+    ///
+    /// # Safety
+    ///
+    /// `new_elems` must hold a bitwise copy of this list's elements.
+    unsafe fn release_copied_allocation(&self, new_elems: NonNull<ManuallyDrop<T>>) {
+        if unsafe { decrement_refcount(self.ptr_to_refcount().cast()) } {
+            // Unlike in Drop, do *not* decrement the refcounts of all the elements!
+            // The new allocation is referencing them, so instead of incrementing them all
+            // all just to decrement them again here, we neither increment nor decrement them.
+            unsafe { roc_dealloc(self.ptr_to_allocation(), Self::alloc_alignment() as u32) };
+        } else if T::is_refcounted() {
+            // The old allocation is still referencing the elements, so the copies
+            // in the new allocation need references of their own.
+            for index in 0..self.len() {
+                unsafe { (*new_elems.as_ptr().add(index)).inc() };
+            }
+        }
+    }
+
+    /// Make sure this list is the only reference to its allocation (copying
+    /// it if necessary), so its elements can be mutated in place.
+    fn make_unique(&mut self) {
+        if self.elements.is_some() && (!self.is_unique() || self.is_seamless_slice()) {
+            self.reserve(0);
+        }
+    }
+
+    /// Retains only the elements for which the given function returns `true`,
+    /// like `Vec::retain`.
+    ///
+    /// If this list is not unique, this will copy it first.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        if self.is_empty() {
+            return;
+        }
+
+        self.make_unique();
+
+        /// Keeps the list valid if `keep` panics, like the guard in `Vec::retain`: it moves the
+        /// elements that haven't been looked at yet down over the removed ones, and sets the
+        /// length to match, so no element gets dropped twice.
+        struct BackshiftOnDrop<'a, T: RocRefcounted> {
+            list: &'a mut RocList<T>,
+            processed: usize,
+            removed: usize,
+            original_len: usize,
+        }
+
+        impl<T: RocRefcounted> Drop for BackshiftOnDrop<'_, T> {
+            fn drop(&mut self) {
+                let elements = self.list.elements.unwrap().as_ptr();
+
+                if self.removed > 0 {
+                    unsafe {
+                        ptr::copy(
+                            elements.add(self.processed),
+                            elements.add(self.processed - self.removed),
+                            self.original_len - self.processed,
+                        );
+                    }
+                }
+
+                self.list.length = self.original_len - self.removed;
+            }
+        }
+
+        let elements = self.elements.unwrap().as_ptr();
+        let original_len = self.len();
+        let mut guard = BackshiftOnDrop {
+            list: self,
+            processed: 0,
+            removed: 0,
+            original_len,
+        };
+
+        while guard.processed < original_len {
+            unsafe {
+                let elem = elements.add(guard.processed);
+
+                if keep(&**elem) {
+                    if guard.removed > 0 {
+                        ptr::copy_nonoverlapping(elem, elem.sub(guard.removed), 1);
+                    }
+
+                    guard.processed += 1;
+                } else {
+                    // Count it as removed before dec'ing it, in case that panics.
+                    guard.processed += 1;
+                    guard.removed += 1;
+
+                    (*elem).dec();
+                }
+            }
+        }
+    }
+
+    /// Removes the elements in the given range and returns them, like `Vec::drain`.
+    /// Unlike `Vec::drain`, the elements are removed right away, rather than when
+    /// the returned iterator is dropped.
+    ///
+    /// If this list is not unique, this will copy it first.
+    #[track_caller]
+    pub fn drain(&mut self, range: Range<usize>) -> std::vec::IntoIter<T> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "drain range out of bounds"
+        );
+
+        if range.is_empty() {
+            return Vec::new().into_iter();
+        }
+
+        self.make_unique();
+
+        let elements = self.elements.unwrap().as_ptr();
+        let len = self.len();
+        let mut drained = Vec::with_capacity(range.len());
+
+        unsafe {
+            for index in range.clone() {
+                drained.push(ManuallyDrop::into_inner(ptr::read(elements.add(index))));
+            }
+
+            // Shift the remaining elements down to fill the gap.
+            ptr::copy(
+                elements.add(range.end),
+                elements.add(range.start),
+                len - range.end,
+            );
+        }
+
+        self.length = len - range.len();
+
+        drained.into_iter()
+    }
+
     /// Replace self with a new version, without letting `drop` run in between.
     fn update_to(&mut self, mut updated: Self) {
         // We want to replace `self` with `updated` in a way that makes sure
@@ -646,13 +769,8 @@ where
 {
     fn clone(&self) -> Self {
         // Increment the reference count
-        if let Some((_, storage)) = self.elements_and_storage() {
-            let mut new_storage = storage.get();
-
-            if !new_storage.is_readonly() {
-                new_storage.increment_reference_count();
-                storage.set(new_storage);
-            }
+        if self.elements.is_some() {
+            unsafe { increment_refcount(self.ptr_to_refcount().cast()) }
         }
 
         Self {
@@ -673,40 +791,32 @@ where
             return;
         }
 
-        let ptr = self.ptr_to_refcount();
-        unsafe {
-            let value = std::ptr::read(ptr);
-            std::ptr::write(ptr, Ord::max(0, ((value as isize) + 1) as usize));
-        }
+        unsafe { increment_refcount(self.ptr_to_refcount().cast()) }
     }
 
     fn dec(&mut self) {
-        if let Some((_, storage)) = self.elements_and_storage() {
-            // Decrease the list's reference count.
-            let mut new_storage = storage.get();
-
-            if !new_storage.is_readonly() {
-                let needs_dealloc = new_storage.decrease();
+        if self.elements.is_none() {
+            // Empty, non-allocated list, no refcounting to do.
+            return;
+        }
 
-                if needs_dealloc {
-                    let alloc_ptr = self.ptr_to_allocation();
-                    unsafe {
-                        // Dec the stored elements in the underlying allocation.
-                        if T::is_refcounted() {
-                            let elements_ptr = Self::elem_ptr_from_alloc_ptr(alloc_ptr) as *mut T;
-                            let len = self.allocation_element_count();
-                            for index in 0..len {
-                                (*elements_ptr.add(index)).dec()
-                            }
-                        }
+        // Decrease the list's reference count.
+        let needs_dealloc = unsafe { decrement_refcount(self.ptr_to_refcount().cast()) };
 
-                        // Release the memory.
-                        roc_dealloc(alloc_ptr, Self::alloc_alignment() as u32);
+        if needs_dealloc {
+            let alloc_ptr = self.ptr_to_allocation();
+            unsafe {
+                // Dec the stored elements in the underlying allocation.
+                if T::is_refcounted() {
+                    let elements_ptr = Self::elem_ptr_from_alloc_ptr(alloc_ptr) as *mut T;
+                    let len = self.allocation_element_count();
+                    for index in 0..len {
+                        (*elements_ptr.add(index)).dec()
                     }
-                } else {
-                    // Write the storage back.
-                    storage.set(new_storage);
                 }
+
+                // Release the memory.
+                roc_dealloc(alloc_ptr, Self::alloc_alignment() as u32);
             }
         }
     }
@@ -820,6 +930,37 @@ where
     }
 }
 
+/// Copies the platform's encoding of the OsStr, which (unlike converting to a RocStr)
+/// cannot fail. On UNIX these are the raw bytes; on Windows they are WTF-8.
+#[cfg(feature = "std")]
+impl From<&std::ffi::OsStr> for RocList<u8> {
+    fn from(os_str: &std::ffi::OsStr) -> Self {
+        Self::from_slice(os_str.as_encoded_bytes())
+    }
+}
+
+/// Copies the platform's encoding of the Path, which (unlike converting to a RocStr)
+/// cannot fail. On UNIX these are the raw bytes; on Windows they are WTF-8.
+#[cfg(feature = "std")]
+impl From<&std::path::Path> for RocList<u8> {
+    fn from(path: &std::path::Path) -> Self {
+        Self::from(path.as_os_str())
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl RocList<u8> {
+    /// Interpret these bytes as an OsStr, e.g. for bytes that came from `RocList::from(&OsStr)`.
+    pub fn as_os_str(&self) -> &std::ffi::OsStr {
+        std::os::unix::ffi::OsStrExt::from_bytes(self.as_slice())
+    }
+
+    /// Interpret these bytes as a Path, e.g. for bytes that came from `RocList::from(&Path)`.
+    pub fn as_path(&self) -> &std::path::Path {
+        std::path::Path::new(self.as_os_str())
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<T: Serialize> Serialize for RocList<T>
 where
@@ -18,10 +18,17 @@ use core::{
 };
 
 #[cfg(feature = "std")]
-use std::ffi::{CStr, CString};
+use std::{
+    ffi::{CStr, CString, OsStr, OsString},
+    path::{Path, PathBuf},
+};
 use std::{ops::Range, ptr::NonNull};
 
-use crate::{roc_realloc, RocList, RocRefcounted};
+use crate::{
+    roc_realloc,
+    storage::{decrement_refcount, increment_refcount},
+    RocList, RocRefcounted,
+};
 
 #[repr(transparent)]
 pub struct RocStr(RocStrInner);
@@ -293,6 +300,99 @@ impl RocStr {
         SplitWhitespace(self.as_str().char_indices().peekable(), self)
     }
 
+    /// Like `str::split`, except each piece is a `RocStr` that shares this string's allocation
+    /// (as a seamless slice) instead of borrowing from it.
+    pub fn split<'a>(&'a self, delimiter: &'a str) -> Split<'a> {
+        Split(self.as_str().split(delimiter), self)
+    }
+
+    /// Append the given `&str` to the end of this RocStr.
+    ///
+    /// If this RocStr is not unique, or does not have enough capacity, this will
+    /// reallocate; the other references to the original string will be unaffected.
+    pub fn push_str(&mut self, string: &str) {
+        if string.is_empty() {
+            return;
+        }
+
+        // After this, we are either a small string with enough room, or a unique
+        // (non-slice) heap-allocated string with enough capacity.
+        self.reserve(string.len());
+
+        if self.is_small_str() {
+            let small_string = unsafe { &mut self.0.small_string };
+            let len = small_string.len();
+            let new_len = len + string.len();
+
+            small_string.bytes[len..new_len].copy_from_slice(string.as_bytes());
+            small_string.len = new_len as u8 | RocStr::MASK;
+        } else {
+            let big_string = unsafe { &mut self.0.heap_allocated };
+
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    string.as_ptr(),
+                    big_string.ptr_to_first_elem().add(big_string.len()),
+                    string.len(),
+                );
+            }
+
+            big_string.length += string.len();
+        }
+    }
+
+    /// Returns the bytes of this RocStr for in-place mutation, or `None` if the
+    /// underlying allocation is shared with other references.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the bytes are valid UTF-8 when the borrow ends.
+    pub unsafe fn as_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        if self.is_small_str() {
+            let small_string = unsafe { &mut self.0.small_string };
+            let len = small_string.len();
+
+            Some(&mut small_string.bytes[..len])
+        } else if self.is_unique() {
+            let big_string = unsafe { &self.0.heap_allocated };
+            let bytes = unsafe {
+                core::slice::from_raw_parts_mut(big_string.ptr_to_first_elem(), big_string.len())
+            };
+
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+
+    /// Returns this RocStr as a `&mut str` if it is unique (e.g. a small string, or a
+    /// heap-allocated string with a refcount of 1), or `None` if its allocation is shared.
+    ///
+    /// Unlike `as_mut_str`, this never clones a shared string to make it unique, so it
+    /// never allocates.
+    pub fn try_as_mut_str(&mut self) -> Option<&mut str> {
+        // Safety: a `&mut str` cannot be used to write invalid UTF-8 without unsafe code.
+        let bytes = unsafe { self.as_bytes_mut()? };
+
+        Some(unsafe { core::str::from_utf8_unchecked_mut(bytes) })
+    }
+
+    /// Returns this RocStr as a `&mut str`, for in-place edits like `make_ascii_uppercase`.
+    ///
+    /// If the underlying allocation is shared (or readonly), this first copies the string into
+    /// a fresh allocation; the other references to the original string will be unaffected.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        if !self.is_unique() {
+            // Reserving on a shared string always copies it into a new, unique allocation.
+            self.reserve(0);
+        }
+
+        match self.try_as_mut_str() {
+            Some(string) => string,
+            None => unreachable!("a RocStr was not unique right after being copied"),
+        }
+    }
+
     /// Returns the index of the first interior \0 byte in the string, or None if there are none.
     fn first_nul_byte(&self) -> Option<usize> {
         self.as_bytes().iter().position(|byte| *byte == 0)
@@ -653,6 +753,26 @@ impl Iterator for SplitWhitespace<'_> {
     }
 }
 
+pub struct Split<'a>(std::str::Split<'a, &'a str>, &'a RocStr);
+
+impl Iterator for Split<'_> {
+    type Item = RocStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let piece = self.0.next()?;
+
+        if piece.is_empty() {
+            // Empty seamless slices have no capacity, so they would never release
+            // the reference they hold on the original allocation.
+            return Some(RocStr::empty());
+        }
+
+        let start = piece.as_ptr() as usize - self.1.as_ptr() as usize;
+
+        self.1.try_slice_range(start..start + piece.len())
+    }
+}
+
 impl Deref for RocStr {
     type Target = str;
 
@@ -684,6 +804,68 @@ impl TryFrom<CString> for RocStr {
     }
 }
 
+/// This can fail because an OsStr may contain invalid UTF-8 characters.
+///
+/// To pass along an OsStr losslessly, convert it to a `RocList<u8>` instead.
+#[cfg(feature = "std")]
+impl TryFrom<&OsStr> for RocStr {
+    type Error = core::str::Utf8Error;
+
+    fn try_from(os_str: &OsStr) -> Result<Self, Self::Error> {
+        core::str::from_utf8(os_str.as_encoded_bytes()).map(RocStr::from)
+    }
+}
+
+/// This can fail because an OsString may contain invalid UTF-8 characters.
+///
+/// To pass along an OsString losslessly, convert it to a `RocList<u8>` instead.
+#[cfg(feature = "std")]
+impl TryFrom<OsString> for RocStr {
+    type Error = core::str::Utf8Error;
+
+    fn try_from(os_string: OsString) -> Result<Self, Self::Error> {
+        RocStr::try_from(os_string.as_os_str())
+    }
+}
+
+/// This can fail because a Path may contain invalid UTF-8 characters.
+///
+/// To pass along a Path losslessly, convert it to a `RocList<u8>` instead.
+#[cfg(feature = "std")]
+impl TryFrom<&Path> for RocStr {
+    type Error = core::str::Utf8Error;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        RocStr::try_from(path.as_os_str())
+    }
+}
+
+/// This can fail because a PathBuf may contain invalid UTF-8 characters.
+///
+/// To pass along a PathBuf losslessly, convert it to a `RocList<u8>` instead.
+#[cfg(feature = "std")]
+impl TryFrom<PathBuf> for RocStr {
+    type Error = core::str::Utf8Error;
+
+    fn try_from(path_buf: PathBuf) -> Result<Self, Self::Error> {
+        RocStr::try_from(path_buf.as_os_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsRef<OsStr> for RocStr {
+    fn as_ref(&self) -> &OsStr {
+        OsStr::new(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsRef<Path> for RocStr {
+    fn as_ref(&self) -> &Path {
+        Path::new(self.as_str())
+    }
+}
+
 #[cfg(not(feature = "no_std"))]
 /// Like https://doc.rust-lang.org/std/ffi/struct.NulError.html but
 /// only for interior nuls, not for missing nul terminators.
@@ -705,6 +887,12 @@ impl From<&str> for RocStr {
     }
 }
 
+impl From<char> for RocStr {
+    fn from(c: char) -> Self {
+        Self::from(&*c.encode_utf8(&mut [0; 4]))
+    }
+}
+
 impl PartialEq for RocStr {
     fn eq(&self, other: &Self) -> bool {
         self.deref() == other.deref()
@@ -891,11 +1079,7 @@ impl BigString {
     }
 
     fn inc(&mut self) {
-        let ptr = self.ptr_to_refcount();
-        unsafe {
-            let value = std::ptr::read(ptr);
-            std::ptr::write(ptr, Ord::max(0, ((value as isize) + 1) as usize));
-        }
+        unsafe { increment_refcount(self.ptr_to_refcount().cast()) }
     }
 
     fn dec(&mut self) {
@@ -904,21 +1088,9 @@ impl BigString {
             return;
         }
 
-        let ptr = self.ptr_to_refcount();
-        unsafe {
-            let value = std::ptr::read(ptr) as isize;
-            match value {
-                0 => {
-                    // static lifetime, do nothing
-                }
-                isize::MIN => {
-                    // refcount becomes zero; free allocation
-                    crate::roc_dealloc(self.ptr_to_allocation().cast(), 1);
-                }
-                _ => {
-                    std::ptr::write(ptr, (value - 1) as usize);
-                }
-            }
+        if unsafe { decrement_refcount(self.ptr_to_refcount().cast()) } {
+            // refcount becomes zero; free allocation
+            unsafe { crate::roc_dealloc(self.ptr_to_allocation().cast(), 1) };
         }
     }
 
@@ -971,6 +1143,9 @@ impl BigString {
             let elements = unsafe { NonNull::new_unchecked(ptr.cast::<u8>().add(align)) };
 
             unsafe {
+                // Initialize the reference count of the new allocation.
+                std::ptr::write(ptr.cast::<usize>(), isize::MIN as usize);
+
                 // Copy the old elements to the new allocation.
                 std::ptr::copy_nonoverlapping(self.ptr_to_first_elem(), ptr.add(align), self.len());
             }
@@ -1,4 +1,6 @@
 use core::num::NonZeroIsize;
+#[cfg(feature = "atomic-refcount")]
+use core::sync::atomic::{fence, AtomicIsize, Ordering};
 
 /// # Safety
 ///
@@ -64,3 +66,73 @@ impl Storage {
         matches!(self, Self::ReferenceCounted(REFCOUNT_1))
     }
 }
+
+/// Increment the refcount at the given address, unless it is readonly.
+///
+/// With the `atomic-refcount` feature, this uses an atomic increment. That feature
+/// must match whether the app was built with `roc build --atomic-refcount`.
+///
+/// # Safety
+///
+/// `ptr` must point to the (correctly aligned) refcount of a live allocation.
+pub(crate) unsafe fn increment_refcount(ptr: *mut isize) {
+    #[cfg(not(feature = "atomic-refcount"))]
+    unsafe {
+        let rc = ptr.read();
+
+        if rc != 0 {
+            // Saturates to readonly (0) on overflow.
+            ptr.write(rc.wrapping_add(1));
+        }
+    }
+
+    #[cfg(feature = "atomic-refcount")]
+    {
+        let rc = unsafe { AtomicIsize::from_ptr(ptr) };
+
+        if rc.load(Ordering::Relaxed) != 0 {
+            rc.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Decrement the refcount at the given address, unless it is readonly.
+///
+/// Returns `true` if this was the last reference, in which case the caller
+/// is responsible for freeing the allocation.
+///
+/// # Safety
+///
+/// `ptr` must point to the (correctly aligned) refcount of a live allocation.
+pub(crate) unsafe fn decrement_refcount(ptr: *mut isize) -> bool {
+    #[cfg(not(feature = "atomic-refcount"))]
+    unsafe {
+        match ptr.read() {
+            0 => false,
+            isize::MIN => true,
+            rc => {
+                ptr.write(rc - 1);
+
+                false
+            }
+        }
+    }
+
+    #[cfg(feature = "atomic-refcount")]
+    {
+        let rc = unsafe { AtomicIsize::from_ptr(ptr) };
+
+        if rc.load(Ordering::Relaxed) == 0 {
+            return false;
+        }
+
+        if rc.fetch_sub(1, Ordering::Release) == isize::MIN {
+            // Make sure all other threads are done with the allocation before it gets freed.
+            fence(Ordering::Acquire);
+
+            true
+        } else {
+            false
+        }
+    }
+}
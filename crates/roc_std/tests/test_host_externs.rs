@@ -0,0 +1,78 @@
+//! Uses `declare_roc_externs!` instead of hand-written externs. This has to be its own
+//! test binary, since the externs are `#[no_mangle]`.
+
+use core::ffi::c_void;
+use core::sync::atomic::{AtomicIsize, Ordering};
+use roc_std::{DefaultRocHost, RocDec, RocHost, RocList, RocStr};
+
+/// Counts live allocations, and otherwise uses the defaults.
+struct CountingHost;
+
+static LIVE_ALLOCATIONS: AtomicIsize = AtomicIsize::new(0);
+
+impl RocHost for CountingHost {
+    unsafe fn alloc(size: usize, alignment: u32) -> *mut c_void {
+        LIVE_ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+
+        DefaultRocHost::alloc(size, alignment)
+    }
+
+    unsafe fn realloc(
+        ptr: *mut c_void,
+        new_size: usize,
+        old_size: usize,
+        alignment: u32,
+    ) -> *mut c_void {
+        DefaultRocHost::realloc(ptr, new_size, old_size, alignment)
+    }
+
+    unsafe fn dealloc(ptr: *mut c_void, alignment: u32) {
+        LIVE_ALLOCATIONS.fetch_sub(1, Ordering::SeqCst);
+
+        DefaultRocHost::dealloc(ptr, alignment)
+    }
+}
+
+roc_std::declare_roc_externs!(CountingHost);
+
+// The allocation counter is global, so everything that allocates runs in this one test.
+#[test]
+fn allocations_are_freed() {
+    {
+        let mut list = RocList::from_slice(&[1u8, 2, 3]);
+
+        for i in 0..1000 {
+            list.push(i as u8);
+        }
+
+        assert_eq!(list.len(), 1003);
+        assert_eq!(&list[..3], &[1, 2, 3]);
+
+        // RocDec has an alignment of 16
+        let decs = RocList::from_slice(&[RocDec::from(1), RocDec::from(2)]);
+        assert_eq!(decs.as_ptr() as usize % 16, 0);
+
+        let mut string = RocStr::from("This is a long string that will not fit in a small string");
+        string.push_str(", and now it is even longer");
+
+        let clone = string.clone();
+        assert_eq!(clone, string);
+
+        assert!(LIVE_ALLOCATIONS.load(Ordering::SeqCst) > 0);
+    }
+
+    assert_eq!(LIVE_ALLOCATIONS.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn memset_and_memcpy() {
+    let mut src = [0u8; 8];
+    let mut dst = [0u8; 8];
+
+    unsafe {
+        roc_memset(src.as_mut_ptr().cast(), 7, 4);
+        roc_memcpy(dst.as_mut_ptr().cast(), src.as_ptr().cast(), 8);
+    }
+
+    assert_eq!(dst, [7, 7, 7, 7, 0, 0, 0, 0]);
+}
@@ -267,6 +267,42 @@ mod test_roc_std {
         assert!(roc_result.is_err());
     }
 
+    #[test]
+    fn rust_result_to_roc_result() {
+        let ok: RocResult<u64, RocStr> = Ok(42).into();
+        assert_eq!(ok.as_ref(), Ok(&42));
+
+        let err: RocResult<u64, RocStr> = Err(RocStr::from("oops")).into();
+        assert_eq!(err.as_ref(), Err(&RocStr::from("oops")));
+    }
+
+    #[test]
+    fn roc_result_as_mut() {
+        let mut roc_result: RocResult<u64, ()> = RocResult::ok(1);
+
+        if let Ok(payload) = roc_result.as_mut() {
+            *payload += 1;
+        }
+
+        assert_eq!(Result::from(roc_result), Ok(2));
+    }
+
+    #[test]
+    fn roc_result_layout() {
+        // Roc lays out `Result ok err` as the payload followed by the tag id,
+        // with tags sorted alphabetically: `Err` is 0 and `Ok` is 1.
+        fn tag_byte<T, E>(roc_result: &RocResult<T, E>) -> u8 {
+            let ptr = roc_result as *const RocResult<T, E> as *const u8;
+            unsafe { *ptr.add(core::mem::size_of::<T>().max(core::mem::size_of::<E>())) }
+        }
+
+        assert_eq!(core::mem::size_of::<RocResult<u64, u32>>(), 16);
+        assert_eq!(core::mem::align_of::<RocResult<u64, u32>>(), 8);
+
+        assert_eq!(tag_byte(&RocResult::<u64, u32>::err(7)), 0);
+        assert_eq!(tag_byte(&RocResult::<u64, u32>::ok(7)), 1);
+    }
+
     #[test]
     fn create_roc_box() {
         let contents = 42i32;
@@ -307,6 +343,111 @@ mod test_roc_std {
         assert_eq!(format!("{example}"), "3.141592653589793238");
     }
 
+    fn dec(s: &str) -> RocDec {
+        RocDec::from_str(s).unwrap()
+    }
+
+    // These mirror the test vectors for the builtin implementation in dec.zig
+
+    #[test]
+    fn roc_dec_add_sub() {
+        assert_eq!(RocDec::new(0) + RocDec::new(0), RocDec::new(0));
+        assert_eq!(RocDec::new(0) + RocDec::new(1), RocDec::new(1));
+        assert_eq!(RocDec::new(1) - RocDec::new(0), RocDec::new(1));
+        assert_eq!(RocDec::new(1) - RocDec::new(1), RocDec::new(0));
+        assert_eq!(dec("1.5") + dec("-0.25"), dec("1.25"));
+
+        assert_eq!(RocDec::MAX.checked_add(RocDec::new(1)), None);
+        assert_eq!(RocDec::MIN.checked_sub(RocDec::new(1)), None);
+    }
+
+    #[test]
+    fn roc_dec_mul() {
+        assert_eq!(RocDec::from(15) * RocDec::from(1), RocDec::from(15));
+        assert_eq!(RocDec::from(15) * RocDec::from(2), RocDec::from(30));
+        assert_eq!(RocDec::from(0) * RocDec::from(0), RocDec::from(0));
+        assert_eq!(dec("-1.5") * dec("2.5"), dec("-3.75"));
+        assert_eq!(RocDec::MIN * RocDec::from(1), RocDec::MIN);
+
+        // truncates toward zero, like the builtins
+        assert_eq!(
+            RocDec::new(1) * dec("0.5"),
+            RocDec::new(0),
+            "the smallest positive Dec halved should truncate to 0"
+        );
+
+        assert_eq!(RocDec::MAX.checked_mul(RocDec::from(2)), None);
+        assert_eq!(RocDec::MIN.checked_mul(RocDec::from(-1)), None);
+    }
+
+    #[test]
+    fn roc_dec_div() {
+        assert_eq!(RocDec::from(0) / RocDec::from(2), RocDec::from(0));
+        assert_eq!(RocDec::from(2) / RocDec::from(2), RocDec::from(1));
+        assert_eq!(RocDec::from(20) / RocDec::from(2), RocDec::from(10));
+        assert_eq!(RocDec::from(8) / RocDec::from(5), dec("1.6"));
+        assert_eq!(
+            RocDec::from(10) / RocDec::from(3),
+            dec("3.333333333333333333")
+        );
+        assert_eq!(RocDec::from(341) / RocDec::from(341), RocDec::from(1));
+        assert_eq!(
+            RocDec::from(342) / RocDec::from(343),
+            dec("0.997084548104956268")
+        );
+        assert_eq!(RocDec::from(680) / RocDec::from(340), RocDec::from(2));
+        assert_eq!(RocDec::from(500) / RocDec::from(1000), dec("0.5"));
+        assert_eq!(RocDec::from(-10) / RocDec::from(4), dec("-2.5"));
+
+        assert_eq!(RocDec::from(1).checked_div(RocDec::from(0)), None);
+        assert_eq!(RocDec::MAX.checked_div(dec("0.5")), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Decimal multiplication overflowed!")]
+    fn roc_dec_mul_overflow() {
+        let _ = RocDec::MAX * RocDec::from(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Decimal division by 0!")]
+    fn roc_dec_div_by_zero() {
+        let _ = RocDec::from(1) / RocDec::from(0);
+    }
+
+    #[test]
+    fn roc_dec_ord() {
+        assert!(RocDec::MIN < RocDec::from(0));
+        assert!(dec("-0.5") < dec("0.25"));
+        assert!(RocDec::MAX > dec("1000000"));
+        assert_eq!(-dec("1.5"), dec("-1.5"));
+    }
+
+    #[test]
+    fn roc_dec_parse_and_to_str() {
+        assert_eq!("1.25".parse::<RocDec>(), Ok(dec("1.25")));
+        assert!("not a number".parse::<RocDec>().is_err());
+
+        assert_eq!(
+            RocDec::MAX.to_str().as_str(),
+            "170141183460469231731.687303715884105727"
+        );
+        assert_eq!(
+            RocDec::MIN.to_str().as_str(),
+            "-170141183460469231731.687303715884105728"
+        );
+        assert_eq!(
+            RocDec::new(12345678912345678912111111111111111111)
+                .to_str()
+                .as_str(),
+            "12345678912345678912.111111111111111111"
+        );
+        assert_eq!(dec("-0.5").to_str().as_str(), "-0.5");
+        assert_eq!(dec("-1.25").to_str().as_str(), "-1.25");
+        assert_eq!(RocDec::new(-1).to_str().as_str(), "-0.000000000000000001");
+        assert_eq!(dec("-3").to_str().as_str(), "-3");
+    }
+
     #[test]
     fn safe_send_no_copy() {
         let x = RocStr::from("This is a long string but still unique. Yay!!!");
@@ -420,6 +561,133 @@ mod test_roc_std {
         assert_eq!(sliced.as_slice(), &[1, 2, 3, 5]);
     }
 
+    #[test]
+    fn roc_list_push_shared() {
+        let mut original = RocList::with_capacity(10);
+        original.extend_from_slice(&[1, 2, 3]);
+
+        // There is room to push in place, but the allocation is shared.
+        let mut copy = original.clone();
+        copy.push(4);
+
+        assert_eq!(copy.as_slice(), &[1, 2, 3, 4]);
+        assert_eq!(original.as_slice(), &[1, 2, 3]);
+        assert!(copy.is_unique());
+        assert!(original.is_unique());
+    }
+
+    #[test]
+    fn roc_list_reserve_in_place() {
+        let mut list = RocList::from_slice(&[1u64, 2, 3]);
+
+        list.reserve(100);
+        assert!(list.capacity() >= 103);
+
+        for i in 4..=103 {
+            list.push(i);
+        }
+
+        assert_eq!(list.len(), 103);
+        assert_eq!(list[102], 103);
+    }
+
+    #[test]
+    fn roc_list_copy_on_write_refcounted_elements() {
+        let long_str = RocStr::from("This is a long string, so it will be heap-allocated");
+        let original = RocList::from_slice(&[long_str.clone()]);
+        let mut copy = original.clone();
+
+        copy.push(RocStr::from("x"));
+
+        // both lists now reference the string, and each one is responsible for its own reference
+        drop(original);
+        assert!(!long_str.is_unique());
+
+        drop(copy);
+        assert!(long_str.is_unique());
+    }
+
+    #[test]
+    fn roc_list_retain() {
+        let mut list = RocList::from_slice(&[1, 2, 3, 4, 5, 6]);
+
+        list.retain(|n| n % 2 == 0);
+
+        assert_eq!(list.as_slice(), &[2, 4, 6]);
+    }
+
+    #[test]
+    fn roc_list_retain_shared() {
+        let long_str = RocStr::from("This is a long string, so it will be heap-allocated");
+        let original = RocList::from_slice(&[long_str.clone(), RocStr::from("keep")]);
+        let mut copy = original.clone();
+
+        copy.retain(|s| s.as_str() == "keep");
+
+        assert_eq!(copy.as_slice(), &[RocStr::from("keep")]);
+        assert_eq!(original.len(), 2);
+
+        drop(original);
+        assert!(long_str.is_unique());
+    }
+
+    #[test]
+    fn roc_list_retain_panic() {
+        let strs: Vec<RocStr> = (0..4)
+            .map(|i| {
+                RocStr::from(format!("This is long string {i}, so it's heap-allocated").as_str())
+            })
+            .collect();
+        let mut list = RocList::from_slice(&strs);
+        let mut calls = 0;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.retain(|_| {
+                calls += 1;
+
+                if calls == 3 {
+                    panic!("the predicate panicked");
+                }
+
+                calls != 1
+            })
+        }));
+
+        assert!(result.is_err());
+
+        // The first string was removed and the second kept. The predicate panicked on the
+        // third, so it and the fourth are still in the list.
+        assert_eq!(list.as_slice(), &strs[1..]);
+
+        drop(list);
+        assert!(strs.iter().all(RocStr::is_unique));
+    }
+
+    #[test]
+    fn roc_list_drain() {
+        let mut list = RocList::from_slice(&[1, 2, 3, 4, 5, 6]);
+
+        let drained: Vec<_> = list.drain(1..4).collect();
+
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(list.as_slice(), &[1, 5, 6]);
+
+        list.push(7);
+        assert_eq!(list.as_slice(), &[1, 5, 6, 7]);
+    }
+
+    #[test]
+    fn roc_list_drain_shared() {
+        let original = RocList::from_slice(&[1, 2, 3, 4]);
+        let mut copy = original.clone();
+
+        let drained: Vec<_> = copy.drain(0..2).collect();
+
+        assert_eq!(drained, vec![1, 2]);
+        assert_eq!(copy.as_slice(), &[3, 4]);
+        assert_eq!(original.as_slice(), &[1, 2, 3, 4]);
+    }
+
     #[test]
     fn split_whitespace() {
         let example = RocStr::from("chaos is a ladder");
@@ -436,6 +704,152 @@ mod test_roc_std {
             ]
         );
     }
+
+    #[test]
+    fn split() {
+        let example = RocStr::from("chaos,is,,a ladder, and then some more");
+
+        let split: Vec<_> = example.split(",").collect();
+
+        assert_eq!(
+            split,
+            vec![
+                RocStr::from("chaos"),
+                RocStr::from("is"),
+                RocStr::empty(),
+                RocStr::from("a ladder"),
+                RocStr::from(" and then some more"),
+            ]
+        );
+    }
+
+    #[test]
+    fn contains_and_starts_with() {
+        let example = RocStr::from("chaos is a ladder");
+
+        assert!(example.contains("is a"));
+        assert!(!example.contains("ladders"));
+        assert!(example.starts_with("chaos"));
+        assert!(!example.starts_with("ladder"));
+    }
+
+    #[test]
+    fn push_str_small() {
+        let mut roc_str = RocStr::from("chaos");
+
+        roc_str.push_str(" is");
+
+        assert_eq!(roc_str.as_str(), "chaos is");
+    }
+
+    #[test]
+    fn push_str_small_to_big() {
+        let mut roc_str = RocStr::from("chaos");
+
+        roc_str.push_str(" is a ladder, and has been for a long time");
+
+        assert_eq!(
+            roc_str.as_str(),
+            "chaos is a ladder, and has been for a long time"
+        );
+        assert!(roc_str.is_unique());
+    }
+
+    #[test]
+    fn push_str_shared() {
+        let original = RocStr::from("This is a long string that will not fit in a small string");
+        let mut copy = original.clone();
+
+        copy.push_str("!!!");
+
+        assert_eq!(
+            copy.as_str(),
+            "This is a long string that will not fit in a small string!!!"
+        );
+        assert_eq!(
+            original.as_str(),
+            "This is a long string that will not fit in a small string"
+        );
+        assert!(copy.is_unique());
+        assert!(original.is_unique());
+    }
+
+    #[test]
+    fn as_bytes_mut() {
+        let mut unique = RocStr::from("This is a long string that will not fit in a small string");
+
+        unsafe { unique.as_bytes_mut() }
+            .unwrap()
+            .make_ascii_uppercase();
+
+        assert_eq!(
+            unique.as_str(),
+            "THIS IS A LONG STRING THAT WILL NOT FIT IN A SMALL STRING"
+        );
+
+        let mut shared = unique.clone();
+        assert!(unsafe { shared.as_bytes_mut() }.is_none());
+
+        let mut small = RocStr::from("small");
+        unsafe { small.as_bytes_mut() }
+            .unwrap()
+            .make_ascii_uppercase();
+        assert_eq!(small.as_str(), "SMALL");
+    }
+
+    #[test]
+    fn try_as_mut_str() {
+        let mut unique = RocStr::from("This is a long string that will not fit in a small string");
+
+        unique.try_as_mut_str().unwrap().make_ascii_uppercase();
+
+        assert_eq!(
+            unique.as_str(),
+            "THIS IS A LONG STRING THAT WILL NOT FIT IN A SMALL STRING"
+        );
+
+        let mut shared = unique.clone();
+        assert!(shared.try_as_mut_str().is_none());
+    }
+
+    #[test]
+    fn as_mut_str_shared() {
+        let original = RocStr::from("This is a long string that will not fit in a small string");
+        let mut copy = original.clone();
+
+        copy.as_mut_str().make_ascii_uppercase();
+
+        assert_eq!(
+            copy.as_str(),
+            "THIS IS A LONG STRING THAT WILL NOT FIT IN A SMALL STRING"
+        );
+        assert_eq!(
+            original.as_str(),
+            "This is a long string that will not fit in a small string"
+        );
+        assert!(copy.is_unique());
+        assert!(original.is_unique());
+    }
+
+    #[test]
+    fn as_mut_str_seamless_slice() {
+        let original = RocStr::from("This is a long string that will not fit in a small string");
+        let mut slice = original.slice_range(0..30);
+
+        slice.as_mut_str().make_ascii_uppercase();
+
+        assert_eq!(slice.as_str(), "THIS IS A LONG STRING THAT WIL");
+        assert_eq!(
+            original.as_str(),
+            "This is a long string that will not fit in a small string"
+        );
+    }
+
+    #[test]
+    fn from_char() {
+        assert_eq!(RocStr::from('a').as_str(), "a");
+        assert_eq!(RocStr::from('🦅').as_str(), "🦅");
+    }
 }
 
 #[cfg(test)]
@@ -547,3 +961,87 @@ mod with_terminator {
         // verify_temp_c(&string_for_len(65), 64);
     }
 }
+
+#[cfg(all(test, feature = "atomic-refcount"))]
+mod atomic_refcount {
+    use roc_std::{RocList, RocStr};
+
+    /// Sharing a refcounted Roc value between threads is only sound with atomic refcounting.
+    struct Shared<T>(T);
+
+    unsafe impl<T> Send for Shared<T> {}
+
+    #[test]
+    fn clone_and_drop_across_threads() {
+        let list = RocList::from_slice(&[RocStr::from(
+            "This is a long string, so it will be heap-allocated and refcounted",
+        )]);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let shared = Shared(list.clone());
+
+                std::thread::spawn(move || {
+                    let shared = shared;
+
+                    for _ in 0..10_000 {
+                        drop(shared.0.clone());
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(list.is_unique());
+        assert!(list[0].is_unique());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod os_str {
+    use roc_std::{RocList, RocStr};
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn path_roundtrip() {
+        let path = Path::new("/home/roc/examples/hello-world.roc");
+        let roc_str = RocStr::try_from(path).unwrap();
+
+        assert_eq!(roc_str.as_str(), "/home/roc/examples/hello-world.roc");
+        assert_eq!(AsRef::<Path>::as_ref(&roc_str), path);
+        assert_eq!(
+            RocStr::try_from(PathBuf::from(path)).unwrap().as_str(),
+            roc_str.as_str()
+        );
+    }
+
+    #[test]
+    fn os_str_roundtrip() {
+        let os_str = OsStr::new("héllo");
+        let roc_str = RocStr::try_from(os_str).unwrap();
+
+        assert_eq!(roc_str.as_str(), "héllo");
+        assert_eq!(AsRef::<OsStr>::as_ref(&roc_str), os_str);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn invalid_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let os_str = OsStr::from_bytes(b"not \xFF utf-8");
+
+        assert!(RocStr::try_from(os_str).is_err());
+        assert!(RocStr::try_from(Path::new(os_str)).is_err());
+
+        // Going through RocList<u8> is lossless
+        let bytes = RocList::from(os_str);
+        assert_eq!(bytes.as_slice(), b"not \xFF utf-8");
+        assert_eq!(bytes.as_os_str(), os_str);
+        assert_eq!(bytes.as_path(), Path::new(os_str));
+    }
+}